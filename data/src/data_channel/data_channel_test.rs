@@ -35,6 +35,7 @@ async fn create_new_association_pair(
             max_receive_buffer_size: 0,
             max_message_size: 0,
             name: "client".to_owned(),
+            stream_scheduler: sctp::association::StreamSchedulerType::default(),
         })
         .await;
 
@@ -51,6 +52,7 @@ async fn create_new_association_pair(
             max_receive_buffer_size: 0,
             max_message_size: 0,
             name: "server".to_owned(),
+            stream_scheduler: sctp::association::StreamSchedulerType::default(),
         })
         .await;
 
@@ -50,6 +50,8 @@ pub struct DataChannel {
 
 impl DataChannel {
     pub fn new(stream: Arc<Stream>, config: Config) -> Self {
+        stream.set_priority(config.priority);
+
         Self {
             config,
             stream,
@@ -0,0 +1,53 @@
+use std::io::{BufReader, BufWriter};
+
+use super::*;
+
+#[test]
+fn test_ack() -> Result<()> {
+    let raw_ack = vec![
+        0x00, 0x20, // length
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, // epoch
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, // sequence_number
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, // epoch
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, // sequence_number
+    ];
+    let parsed_ack = Ack {
+        record_numbers: vec![
+            RecordNumber {
+                epoch: 2,
+                sequence_number: 1,
+            },
+            RecordNumber {
+                epoch: 2,
+                sequence_number: 2,
+            },
+        ],
+    };
+
+    let mut reader = BufReader::new(raw_ack.as_slice());
+    let c = Ack::unmarshal(&mut reader)?;
+    assert_eq!(
+        c, parsed_ack,
+        "ack unmarshal: got {c:?}, want {parsed_ack:?}"
+    );
+    assert_eq!(c.size(), raw_ack.len());
+
+    let mut raw = vec![];
+    {
+        let mut writer = BufWriter::<&mut Vec<u8>>::new(raw.as_mut());
+        c.marshal(&mut writer)?;
+    }
+    assert_eq!(raw, raw_ack, "ack marshal: got {raw:?}, want {raw_ack:?}");
+
+    Ok(())
+}
+
+#[test]
+fn test_ack_invalid_length() {
+    let raw_ack = vec![0x00, 0x03, 0x00, 0x00, 0x00];
+    let mut reader = BufReader::new(raw_ack.as_slice());
+    assert_eq!(
+        Ack::unmarshal(&mut reader).err(),
+        Some(Error::ErrLengthMismatch)
+    );
+}
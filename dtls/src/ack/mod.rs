@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod ack_test;
+
+use std::io::{Read, Write};
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+use super::content::*;
+use super::error::*;
+
+const RECORD_NUMBER_SIZE: usize = 16;
+
+// RecordNumber identifies a record by its full epoch and sequence number.
+// https://www.rfc-editor.org/rfc/rfc9147#section-4
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct RecordNumber {
+    pub epoch: u64,
+    pub sequence_number: u64,
+}
+
+// The ACK message is used by an endpoint to indicate which handshake
+// records it has received and processed from the other side. ACK is not
+// a handshake message but is rather a separate content type.
+// https://www.rfc-editor.org/rfc/rfc9147#section-7
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Ack {
+    pub record_numbers: Vec<RecordNumber>,
+}
+
+impl Ack {
+    pub fn content_type(&self) -> ContentType {
+        ContentType::Ack
+    }
+
+    pub fn size(&self) -> usize {
+        2 + self.record_numbers.len() * RECORD_NUMBER_SIZE
+    }
+
+    pub fn marshal<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_u16::<BigEndian>((self.record_numbers.len() * RECORD_NUMBER_SIZE) as u16)?;
+        for r in &self.record_numbers {
+            writer.write_u64::<BigEndian>(r.epoch)?;
+            writer.write_u64::<BigEndian>(r.sequence_number)?;
+        }
+
+        Ok(writer.flush()?)
+    }
+
+    pub fn unmarshal<R: Read>(reader: &mut R) -> Result<Self> {
+        let record_numbers_len = reader.read_u16::<BigEndian>()? as usize;
+        if record_numbers_len % RECORD_NUMBER_SIZE != 0 {
+            return Err(Error::ErrLengthMismatch);
+        }
+
+        let mut record_numbers = vec![];
+        for _ in 0..record_numbers_len / RECORD_NUMBER_SIZE {
+            let epoch = reader.read_u64::<BigEndian>()?;
+            let sequence_number = reader.read_u64::<BigEndian>()?;
+            record_numbers.push(RecordNumber {
+                epoch,
+                sequence_number,
+            });
+        }
+
+        Ok(Ack { record_numbers })
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::*;
+use crate::content::*;
+use crate::crypto::crypto_gcm_dtls13::*;
+use crate::prf::key_schedule::*;
+use crate::record_layer::unified_header::*;
+
+// Number of epochs whose keys are kept per direction, so that records
+// reordered around a KeyUpdate can still be processed.
+const MAX_EPOCHS: usize = 3;
+
+struct EpochKeys {
+    traffic_secret: Vec<u8>,
+    crypto: CryptoGcmDtls13,
+    // Highest received sequence number plus one, the reference to reconstruct
+    // full sequence numbers from the bits carried in the header.
+    next_sequence_number: AtomicU64,
+}
+
+impl EpochKeys {
+    fn new(traffic_secret: &[u8]) -> Result<Self> {
+        let keys = traffic_keys(
+            traffic_secret,
+            CRYPTO_GCM_DTLS13_KEY_LENGTH,
+            CRYPTO_GCM_DTLS13_IV_LENGTH,
+        )?;
+
+        Ok(EpochKeys {
+            traffic_secret: traffic_secret.to_vec(),
+            crypto: CryptoGcmDtls13::new(&keys.key, &keys.iv, &keys.sn_key),
+            next_sequence_number: AtomicU64::new(0),
+        })
+    }
+}
+
+// TLS_AES_128_GCM_SHA256 is the DTLS 1.3 cipher suite. Unlike the DTLS 1.2
+// suites it does not derive its keys from a master secret, every epoch gets
+// its own traffic secret from the handshake or from a KeyUpdate.
+// https://www.rfc-editor.org/rfc/rfc8446#appendix-B.4
+#[derive(Default)]
+pub struct CipherSuiteTlsAes128GcmSha256 {
+    local: BTreeMap<u16, EpochKeys>,
+    remote: BTreeMap<u16, EpochKeys>,
+}
+
+impl CipherSuiteTlsAes128GcmSha256 {
+    fn keys_mut(&mut self, is_local: bool) -> &mut BTreeMap<u16, EpochKeys> {
+        if is_local {
+            &mut self.local
+        } else {
+            &mut self.remote
+        }
+    }
+
+    fn insert_keys(&mut self, epoch: u16, keys: EpochKeys, is_local: bool) {
+        let epochs = self.keys_mut(is_local);
+        epochs.insert(epoch, keys);
+        while epochs.len() > MAX_EPOCHS {
+            if let Some(&oldest) = epochs.keys().next() {
+                epochs.remove(&oldest);
+            }
+        }
+    }
+}
+
+// reconstruct_sequence_number picks the sequence number closest to the
+// expected one whose low bits match the received ones.
+// https://www.rfc-editor.org/rfc/rfc9147#section-4.2.2
+fn reconstruct_sequence_number(expected: u64, bits: u64, bits_len: usize) -> u64 {
+    let window = 1u64 << bits_len;
+    let candidate = (expected & !(window - 1)) | bits;
+
+    if candidate + window / 2 < expected && candidate + window <= MAX_SEQUENCE_NUMBER {
+        candidate + window
+    } else if candidate > expected + window / 2 && candidate >= window {
+        candidate - window
+    } else {
+        candidate
+    }
+}
+
+impl CipherSuite for CipherSuiteTlsAes128GcmSha256 {
+    fn to_string(&self) -> String {
+        "TLS_AES_128_GCM_SHA256".to_owned()
+    }
+
+    fn id(&self) -> CipherSuiteId {
+        CipherSuiteId::Tls_Aes_128_Gcm_Sha256
+    }
+
+    fn certificate_type(&self) -> ClientCertificateType {
+        ClientCertificateType::EcdsaSign
+    }
+
+    fn hash_func(&self) -> CipherSuiteHash {
+        CipherSuiteHash::Sha256
+    }
+
+    fn is_psk(&self) -> bool {
+        false
+    }
+
+    fn is_initialized(&self) -> bool {
+        !self.local.is_empty() && !self.remote.is_empty()
+    }
+
+    fn is_dtls13(&self) -> bool {
+        true
+    }
+
+    // The traffic secrets can not be recovered from the exporter secret kept
+    // in the serialized state, so DTLS 1.3 connections can not be resumed.
+    fn init(
+        &mut self,
+        _master_secret: &[u8],
+        _client_random: &[u8],
+        _server_random: &[u8],
+        _is_client: bool,
+    ) -> Result<()> {
+        Err(Error::ErrInvalidCipherSuite)
+    }
+
+    fn set_traffic_secret(
+        &mut self,
+        epoch: u16,
+        traffic_secret: &[u8],
+        is_local: bool,
+    ) -> Result<()> {
+        let keys = EpochKeys::new(traffic_secret)?;
+        self.insert_keys(epoch, keys, is_local);
+        Ok(())
+    }
+
+    fn update_traffic_secret(&mut self, is_local: bool) -> Result<u16> {
+        let (epoch, traffic_secret) = match self.keys_mut(is_local).iter().next_back() {
+            Some((epoch, keys)) => (*epoch, keys.traffic_secret.clone()),
+            None => return Err(Error::ErrEpochKeysNotReady),
+        };
+        if epoch == u16::MAX {
+            return Err(Error::ErrSequenceNumberOverflow);
+        }
+
+        let keys = EpochKeys::new(&next_traffic_secret(&traffic_secret)?)?;
+        self.insert_keys(epoch + 1, keys, is_local);
+        Ok(epoch + 1)
+    }
+
+    fn encrypt(&self, pkt_rlh: &RecordLayerHeader, raw: &[u8]) -> Result<Vec<u8>> {
+        let keys = match self.local.get(&pkt_rlh.epoch) {
+            Some(keys) => keys,
+            None => return Err(Error::ErrEpochKeysNotReady),
+        };
+
+        let mut header = UnifiedHeader {
+            connection_id: vec![],
+            epoch_bits: (pkt_rlh.epoch & 0x3) as u8,
+            sequence_number_bits: pkt_rlh.sequence_number as u16,
+            sequence_number_16_bit: true,
+            length: None,
+        };
+
+        keys.crypto.encrypt(
+            &mut header,
+            pkt_rlh.sequence_number,
+            pkt_rlh.content_type,
+            &raw[RECORD_LAYER_HEADER_SIZE..],
+        )
+    }
+
+    // DTLS 1.3 protects records with the unified header only
+    fn decrypt(&self, _h: &RecordLayerHeader, _input: &[u8]) -> Result<Vec<u8>> {
+        Err(Error::ErrInvalidContentType)
+    }
+
+    // decrypt_dtls13 takes a DTLSCiphertext record and returns it as DTLSPlaintext
+    // record, so it can be processed like records of DTLS 1.2.
+    fn decrypt_dtls13(&self, input: &[u8]) -> Result<Vec<u8>> {
+        let mut reader = input;
+        let h = UnifiedHeader::unmarshal(&mut reader, 0)?;
+        let header_len = h.size();
+        let ciphertext = match h.length {
+            Some(length) => input
+                .get(header_len..header_len + length as usize)
+                .ok_or(Error::ErrInvalidPacketLength)?,
+            None => &input[header_len..],
+        };
+
+        let (epoch, keys) = match self
+            .remote
+            .iter()
+            .rev()
+            .find(|(epoch, _)| (**epoch & 0x3) as u8 == h.epoch_bits)
+        {
+            Some((epoch, keys)) => (*epoch, keys),
+            None => return Err(Error::ErrEpochKeysNotReady),
+        };
+
+        let mask = keys.crypto.sequence_number_mask(ciphertext)?;
+        let mut raw_header = input[..header_len].to_vec();
+        let offset = h.sequence_number_offset();
+        let size = h.sequence_number_size();
+        for i in 0..size {
+            raw_header[offset + i] ^= mask[i];
+        }
+        let bits = raw_header[offset..offset + size]
+            .iter()
+            .fold(0u64, |acc, b| (acc << 8) | *b as u64);
+        let sequence_number = reconstruct_sequence_number(
+            keys.next_sequence_number.load(Ordering::SeqCst),
+            bits,
+            size * 8,
+        );
+
+        let (content_type, content) =
+            keys.crypto
+                .decrypt(&raw_header, sequence_number, ciphertext)?;
+        if content_type == ContentType::Invalid {
+            return Err(Error::ErrInvalidContentType);
+        }
+        keys.next_sequence_number
+            .fetch_max(sequence_number + 1, Ordering::SeqCst);
+
+        let record_layer_header = RecordLayerHeader {
+            content_type,
+            protocol_version: PROTOCOL_VERSION1_2,
+            epoch,
+            sequence_number,
+            connection_id: vec![],
+            content_len: content.len() as u16,
+        };
+        let mut out = Vec::with_capacity(RECORD_LAYER_HEADER_SIZE + content.len());
+        record_layer_header.marshal(&mut out)?;
+        out.extend_from_slice(&content);
+
+        Ok(out)
+    }
+}
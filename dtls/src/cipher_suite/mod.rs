@@ -1,6 +1,7 @@
 pub mod cipher_suite_aes_128_ccm;
 pub mod cipher_suite_aes_128_gcm_sha256;
 pub mod cipher_suite_aes_256_cbc_sha;
+pub mod cipher_suite_tls_aes_128_gcm_sha256;
 pub mod cipher_suite_tls_ecdhe_ecdsa_with_aes_128_ccm;
 pub mod cipher_suite_tls_ecdhe_ecdsa_with_aes_128_ccm8;
 pub mod cipher_suite_tls_psk_with_aes_128_ccm;
@@ -12,6 +13,7 @@ use std::marker::{Send, Sync};
 
 use cipher_suite_aes_128_gcm_sha256::*;
 use cipher_suite_aes_256_cbc_sha::*;
+use cipher_suite_tls_aes_128_gcm_sha256::*;
 use cipher_suite_tls_ecdhe_ecdsa_with_aes_128_ccm::*;
 use cipher_suite_tls_ecdhe_ecdsa_with_aes_128_ccm8::*;
 use cipher_suite_tls_psk_with_aes_128_ccm::*;
@@ -43,6 +45,9 @@ pub enum CipherSuiteId {
     Tls_Psk_With_Aes_128_Ccm_8 = 0xc0a8,
     Tls_Psk_With_Aes_128_Gcm_Sha256 = 0x00a8,
 
+    // DTLS 1.3
+    Tls_Aes_128_Gcm_Sha256 = 0x1301,
+
     Unsupported,
 }
 
@@ -72,6 +77,7 @@ impl fmt::Display for CipherSuiteId {
             CipherSuiteId::Tls_Psk_With_Aes_128_Gcm_Sha256 => {
                 write!(f, "TLS_PSK_WITH_AES_128_GCM_SHA256")
             }
+            CipherSuiteId::Tls_Aes_128_Gcm_Sha256 => write!(f, "TLS_AES_128_GCM_SHA256"),
             _ => write!(f, "Unsupported CipherSuiteID"),
         }
     }
@@ -96,6 +102,8 @@ impl From<u16> for CipherSuiteId {
             0xc0a8 => CipherSuiteId::Tls_Psk_With_Aes_128_Ccm_8,
             0x00a8 => CipherSuiteId::Tls_Psk_With_Aes_128_Gcm_Sha256,
 
+            0x1301 => CipherSuiteId::Tls_Aes_128_Gcm_Sha256,
+
             _ => CipherSuiteId::Unsupported,
         }
     }
@@ -132,7 +140,35 @@ pub trait CipherSuite {
     ) -> Result<()>;
 
     fn encrypt(&self, pkt_rlh: &RecordLayerHeader, raw: &[u8]) -> Result<Vec<u8>>;
-    fn decrypt(&self, input: &[u8]) -> Result<Vec<u8>>;
+    // Decrypt a DTLSCiphertext record, h is its header as parsed by the connection
+    fn decrypt(&self, h: &RecordLayerHeader, input: &[u8]) -> Result<Vec<u8>>;
+
+    // is_dtls13 reports whether records are protected as DTLS 1.3 ciphertexts
+    fn is_dtls13(&self) -> bool {
+        false
+    }
+
+    // Install the keys derived from a DTLS 1.3 traffic secret for an epoch
+    fn set_traffic_secret(
+        &mut self,
+        _epoch: u16,
+        _traffic_secret: &[u8],
+        _is_local: bool,
+    ) -> Result<()> {
+        Err(Error::ErrInvalidCipherSuite)
+    }
+
+    // Decrypt a DTLS 1.3 record with a unified header into a DTLSPlaintext
+    // record carrying the full epoch and sequence number
+    fn decrypt_dtls13(&self, _input: &[u8]) -> Result<Vec<u8>> {
+        Err(Error::ErrInvalidCipherSuite)
+    }
+
+    // Derive the keys of the next epoch after a DTLS 1.3 KeyUpdate,
+    // returns the new epoch
+    fn update_traffic_secret(&mut self, _is_local: bool) -> Result<u16> {
+        Err(Error::ErrInvalidCipherSuite)
+    }
 }
 
 // Taken from https://www.iana.org/assignments/tls-parameters/tls-parameters.xml
@@ -167,10 +203,26 @@ pub fn cipher_suite_for_id(id: CipherSuiteId) -> Result<Box<dyn CipherSuite + Se
         CipherSuiteId::Tls_Psk_With_Aes_128_Gcm_Sha256 => {
             Ok(Box::<CipherSuiteTlsPskWithAes128GcmSha256>::default())
         }
+        CipherSuiteId::Tls_Aes_128_Gcm_Sha256 => {
+            Ok(Box::<CipherSuiteTlsAes128GcmSha256>::default())
+        }
         _ => Err(Error::ErrInvalidCipherSuite),
     }
 }
 
+// supports_connection_id reports whether records of the cipher suite can
+// carry a connection ID. Only the AEAD cipher suites of DTLS 1.2 do.
+// https://www.rfc-editor.org/rfc/rfc9146#section-5
+pub(crate) fn supports_connection_id(id: CipherSuiteId) -> bool {
+    !matches!(
+        id,
+        CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_256_Cbc_Sha
+            | CipherSuiteId::Tls_Ecdhe_Rsa_With_Aes_256_Cbc_Sha
+            | CipherSuiteId::Tls_Aes_128_Gcm_Sha256
+            | CipherSuiteId::Unsupported
+    )
+}
+
 // CipherSuites we support in order of preference
 pub(crate) fn default_cipher_suites() -> Vec<Box<dyn CipherSuite + Send + Sync>> {
     vec![
@@ -214,9 +266,11 @@ pub(crate) fn parse_cipher_suites(
         default_cipher_suites()
     };
 
+    // The DTLS 1.3 cipher suite is offered depending on Config::max_version
     let filtered_cipher_suites: Vec<Box<dyn CipherSuite + Send + Sync>> = cipher_suites
         .into_iter()
         .filter(|c| !((exclude_psk && c.is_psk()) || (exclude_non_psk && !c.is_psk())))
+        .filter(|c| !c.is_dtls13())
         .collect();
 
     if filtered_cipher_suites.is_empty() {
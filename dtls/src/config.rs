@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use rand::Rng;
 use tokio::time::Duration;
 
 use crate::cipher_suite::*;
@@ -100,6 +101,24 @@ pub struct Config {
     /// Packet with sequence number older than this value compared to the latest
     /// accepted packet will be discarded. (default is 64)
     pub replay_protection_window: usize,
+
+    /// max_version is the highest DTLS version the connection may negotiate
+    /// (default is DTLS 1.2). When DTLS 1.3 is allowed, DTLS 1.2 is still
+    /// negotiated with peers that do not support DTLS 1.3.
+    /// DTLS 1.3 is not offered nor accepted when psk is set.
+    pub max_version: DtlsVersion,
+
+    /// connection_id_generator generates the connection ID this side asks its
+    /// peer to put in the records it sends, see [RFC 9146](https://www.rfc-editor.org/rfc/rfc9146).
+    /// Records are then matched to the connection by their connection ID
+    /// instead of the remote address, so the connection survives a change of
+    /// the peer address such as a NAT rebinding.
+    /// Connection IDs are not negotiated if connection_id_generator is None,
+    /// and only with DTLS 1.2 AEAD cipher suites.
+    /// A generator returning an empty connection ID sends connection IDs to
+    /// peers asking for them without asking for any itself.
+    /// All connection IDs generated for a listener must have the same length.
+    pub connection_id_generator: Option<ConnectionIdGenerator>,
 }
 
 impl Default for Config {
@@ -123,6 +142,8 @@ impl Default for Config {
             server_name: String::default(),
             mtu: 0,
             replay_protection_window: 0,
+            max_version: DtlsVersion::default(),
+            connection_id_generator: None,
         }
     }
 }
@@ -133,6 +154,25 @@ pub(crate) const DEFAULT_MTU: usize = 1200; // bytes
 // If the remote provided none it will be nil
 pub(crate) type PskCallback = Arc<dyn (Fn(&[u8]) -> Result<Vec<u8>>) + Send + Sync>;
 
+// ConnectionIdGenerator returns the connection ID of a new connection.
+pub type ConnectionIdGenerator = Arc<dyn (Fn() -> Vec<u8>) + Send + Sync>;
+
+/// random_connection_id_generator generates random connection IDs of the given size.
+pub fn random_connection_id_generator(size: usize) -> ConnectionIdGenerator {
+    Arc::new(move || {
+        let mut connection_id = vec![0u8; size];
+        rand::thread_rng().fill(connection_id.as_mut_slice());
+        connection_id
+    })
+}
+
+/// only_send_connection_id_generator generates empty connection IDs, the
+/// connection sends the connection ID asked for by its peer but does not ask
+/// for one itself.
+pub fn only_send_connection_id_generator() -> ConnectionIdGenerator {
+    Arc::new(Vec::new)
+}
+
 // ClientAuthType declares the policy the server will follow for
 // TLS Client Authentication.
 #[derive(Default, Copy, Clone, PartialEq, Eq)]
@@ -155,6 +195,14 @@ pub enum ExtendedMasterSecretType {
     Disable = 2,
 }
 
+// DtlsVersion declares a version of the DTLS protocol
+#[derive(Default, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug)]
+pub enum DtlsVersion {
+    #[default]
+    Dtls12,
+    Dtls13,
+}
+
 pub(crate) fn validate_config(is_client: bool, config: &Config) -> Result<()> {
     if is_client && config.psk.is_some() && config.psk_identity_hint.is_none() {
         return Err(Error::ErrPskAndIdentityMustBeSetForClient);
@@ -180,6 +228,12 @@ pub(crate) fn validate_config(is_client: bool, config: &Config) -> Result<()> {
         }
     }
 
+    if let Some(connection_id_generator) = &config.connection_id_generator {
+        if connection_id_generator().len() > u8::MAX as usize {
+            return Err(Error::ErrInvalidConnectionId);
+        }
+    }
+
     parse_cipher_suites(
         &config.cipher_suites,
         config.psk.is_none(),
@@ -2423,7 +2423,7 @@ async fn test_renegotiation_info() -> Result<()> {
         )
         .await?;
         let n = ca.recv(&mut resp).await?;
-        let messages = unpack_datagram(&resp[..n])?;
+        let messages = unpack_datagram(&resp[..n], 0)?;
 
         let mut reader = BufReader::new(&messages[0][..]);
         let record = RecordLayer::unmarshal(&mut reader)?;
@@ -2455,3 +2455,335 @@ async fn test_renegotiation_info() -> Result<()> {
 
     Ok(())
 }
+
+async fn pipe_conn_with_versions(
+    client_max_version: DtlsVersion,
+    server_max_version: DtlsVersion,
+    client_auth: ClientAuthType,
+) -> Result<(DTLSConn, DTLSConn)> {
+    let (ua, ub) = pipe();
+    let (c_tx, mut c_rx) = mpsc::channel(1);
+
+    tokio::spawn(async move {
+        let client = create_test_client(
+            Arc::new(ua),
+            Config {
+                max_version: client_max_version,
+                srtp_protection_profiles: vec![SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_80],
+                ..Default::default()
+            },
+            true,
+        )
+        .await;
+
+        let _ = c_tx.send(client).await;
+    });
+
+    let server = create_test_server(
+        Arc::new(ub),
+        Config {
+            max_version: server_max_version,
+            client_auth,
+            srtp_protection_profiles: vec![SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_80],
+            ..Default::default()
+        },
+        true,
+    )
+    .await?;
+
+    let client = c_rx.recv().await.unwrap()?;
+
+    Ok((client, server))
+}
+
+async fn cipher_suite_id(conn: &DTLSConn) -> CipherSuiteId {
+    conn.cipher_suite_id().await.unwrap()
+}
+
+async fn assert_echo(ca: &DTLSConn, cb: &DTLSConn) -> Result<()> {
+    let buf_a = vec![0xFA; 100];
+    let n_a = ca.write(&buf_a, Some(Duration::from_secs(5))).await?;
+    assert_eq!(n_a, 100);
+
+    let mut buf_b = vec![0; 1024];
+    let n_b = cb.read(&mut buf_b, Some(Duration::from_secs(5))).await?;
+    assert_eq!(&buf_a[..], &buf_b[0..n_b]);
+
+    let n_b = cb
+        .write(&buf_b[..n_b], Some(Duration::from_secs(5)))
+        .await?;
+    let mut buf_a = vec![0; 1024];
+    let n_a = ca.read(&mut buf_a, Some(Duration::from_secs(5))).await?;
+    assert_eq!(&buf_a[..n_a], &buf_b[..n_b]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dtls13_handshake() -> Result<()> {
+    let (ca, cb) = pipe_conn_with_versions(
+        DtlsVersion::Dtls13,
+        DtlsVersion::Dtls13,
+        ClientAuthType::NoClientCert,
+    )
+    .await?;
+
+    assert_eq!(
+        cipher_suite_id(&ca).await,
+        CipherSuiteId::Tls_Aes_128_Gcm_Sha256
+    );
+    assert_eq!(
+        cipher_suite_id(&cb).await,
+        CipherSuiteId::Tls_Aes_128_Gcm_Sha256
+    );
+    assert_eq!(
+        ca.selected_srtpprotection_profile(),
+        SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_80
+    );
+    assert_eq!(
+        cb.selected_srtpprotection_profile(),
+        SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_80
+    );
+    assert_eq!(ca.protocol_version().await, Some(PROTOCOL_VERSION1_3));
+    assert_eq!(cb.protocol_version().await, Some(PROTOCOL_VERSION1_3));
+    assert!(!ca.connection_state().await.peer_certificates.is_empty());
+
+    assert_echo(&ca, &cb).await?;
+
+    let export_label = "EXTRACTOR-dtls_srtp";
+    let client_keying_material = ca
+        .connection_state()
+        .await
+        .export_keying_material(export_label, &[], 30)
+        .await?;
+    let server_keying_material = cb
+        .connection_state()
+        .await
+        .export_keying_material(export_label, &[], 30)
+        .await?;
+    assert_eq!(client_keying_material, server_keying_material);
+
+    ca.close().await?;
+    cb.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dtls13_client_auth() -> Result<()> {
+    let (ca, cb) = pipe_conn_with_versions(
+        DtlsVersion::Dtls13,
+        DtlsVersion::Dtls13,
+        ClientAuthType::RequireAnyClientCert,
+    )
+    .await?;
+
+    assert!(!cb.connection_state().await.peer_certificates.is_empty());
+    assert_echo(&ca, &cb).await?;
+
+    ca.close().await?;
+    cb.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dtls13_fallback_to_dtls12() -> Result<()> {
+    for (name, client_max_version, server_max_version) in [
+        ("DTLS 1.2 client", DtlsVersion::Dtls12, DtlsVersion::Dtls13),
+        ("DTLS 1.2 server", DtlsVersion::Dtls13, DtlsVersion::Dtls12),
+    ] {
+        let (ca, cb) = pipe_conn_with_versions(
+            client_max_version,
+            server_max_version,
+            ClientAuthType::NoClientCert,
+        )
+        .await?;
+
+        assert!(
+            !cipher_suite_id(&ca)
+                .await
+                .to_string()
+                .starts_with("TLS_AES"),
+            "{name}: expected DTLS 1.2 cipher suite"
+        );
+        assert_eq!(
+            ca.protocol_version().await,
+            Some(PROTOCOL_VERSION1_2),
+            "{name}"
+        );
+        assert_echo(&ca, &cb).await?;
+
+        ca.close().await?;
+        cb.close().await?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dtls13_key_update() -> Result<()> {
+    let (ca, cb) = pipe_conn_with_versions(
+        DtlsVersion::Dtls13,
+        DtlsVersion::Dtls13,
+        ClientAuthType::NoClientCert,
+    )
+    .await?;
+
+    let client_epoch = ca.get_local_epoch();
+    let server_epoch = cb.get_local_epoch();
+
+    ca.update_keys(false).await?;
+    assert_eq!(ca.get_local_epoch(), client_epoch + 1);
+    assert_echo(&ca, &cb).await?;
+
+    ca.update_keys(true).await?;
+    assert_eq!(ca.get_local_epoch(), client_epoch + 2);
+    assert_echo(&ca, &cb).await?;
+    assert_eq!(cb.get_local_epoch(), server_epoch + 1);
+
+    ca.close().await?;
+    cb.close().await?;
+
+    let (ca, cb) = pipe_conn_with_versions(
+        DtlsVersion::Dtls12,
+        DtlsVersion::Dtls12,
+        ClientAuthType::NoClientCert,
+    )
+    .await?;
+    assert_eq!(ca.update_keys(false).await, Err(Error::ErrDtls13Only));
+
+    ca.close().await?;
+    cb.close().await?;
+
+    Ok(())
+}
+
+async fn pipe_conn_with_connection_id(
+    client_generator: Option<ConnectionIdGenerator>,
+    server_generator: Option<ConnectionIdGenerator>,
+    cipher_suites: Vec<CipherSuiteId>,
+) -> Result<(DTLSConn, DTLSConn)> {
+    let (ua, ub) = pipe();
+    let (c_tx, mut c_rx) = mpsc::channel(1);
+
+    let client_cipher_suites = cipher_suites.clone();
+    tokio::spawn(async move {
+        let client = create_test_client(
+            Arc::new(ua),
+            Config {
+                cipher_suites: client_cipher_suites,
+                connection_id_generator: client_generator,
+                ..Default::default()
+            },
+            true,
+        )
+        .await;
+
+        let _ = c_tx.send(client).await;
+    });
+
+    let server = create_test_server(
+        Arc::new(ub),
+        Config {
+            cipher_suites,
+            connection_id_generator: server_generator,
+            ..Default::default()
+        },
+        true,
+    )
+    .await?;
+
+    let client = c_rx.recv().await.unwrap()?;
+
+    Ok((client, server))
+}
+
+async fn connection_ids(conn: &DTLSConn) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+    let connection_id_state = conn.state.connection_id_state.lock().await;
+    (
+        connection_id_state.local_connection_id.clone(),
+        connection_id_state.remote_connection_id.clone(),
+    )
+}
+
+#[tokio::test]
+async fn test_connection_id() -> Result<()> {
+    let (ca, cb) = pipe_conn_with_connection_id(
+        Some(random_connection_id_generator(8)),
+        Some(random_connection_id_generator(4)),
+        vec![],
+    )
+    .await?;
+
+    let (client_local, client_remote) = connection_ids(&ca).await;
+    let (server_local, server_remote) = connection_ids(&cb).await;
+    assert_eq!(client_local.as_ref().map(|cid| cid.len()), Some(8));
+    assert_eq!(server_local.as_ref().map(|cid| cid.len()), Some(4));
+    assert_eq!(client_local, server_remote);
+    assert_eq!(server_local, client_remote);
+    assert_echo(&ca, &cb).await?;
+
+    ca.close().await?;
+    cb.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_connection_id_only_send() -> Result<()> {
+    let (ca, cb) = pipe_conn_with_connection_id(
+        Some(only_send_connection_id_generator()),
+        Some(random_connection_id_generator(8)),
+        vec![],
+    )
+    .await?;
+
+    let (client_local, client_remote) = connection_ids(&ca).await;
+    assert_eq!(client_local, Some(vec![]));
+    assert_eq!(client_remote.map(|cid| cid.len()), Some(8));
+    assert_echo(&ca, &cb).await?;
+
+    ca.close().await?;
+    cb.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_connection_id_not_negotiated() -> Result<()> {
+    let tests = vec![
+        (
+            "server without generator",
+            Some(random_connection_id_generator(8)),
+            None,
+            vec![],
+        ),
+        (
+            "client without generator",
+            None,
+            Some(random_connection_id_generator(8)),
+            vec![],
+        ),
+        (
+            "CBC cipher suite",
+            Some(random_connection_id_generator(8)),
+            Some(random_connection_id_generator(8)),
+            vec![CipherSuiteId::Tls_Ecdhe_Ecdsa_With_Aes_256_Cbc_Sha],
+        ),
+    ];
+
+    for (name, client_generator, server_generator, cipher_suites) in tests {
+        let (ca, cb) =
+            pipe_conn_with_connection_id(client_generator, server_generator, cipher_suites).await?;
+
+        assert_eq!(connection_ids(&ca).await, (None, None), "{name}: client");
+        assert_eq!(connection_ids(&cb).await, (None, None), "{name}: server");
+        assert_echo(&ca, &cb).await?;
+
+        ca.close().await?;
+        cb.close().await?;
+    }
+
+    Ok(())
+}
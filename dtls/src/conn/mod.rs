@@ -9,11 +9,12 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use log::*;
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{mpsc, Mutex, Notify};
 use tokio::time::Duration;
 use util::replay_detector::*;
 use util::Conn;
 
+use crate::ack::*;
 use crate::alert::*;
 use crate::application_data::*;
 use crate::cipher_suite::*;
@@ -22,6 +23,7 @@ use crate::content::*;
 use crate::curve::named_curve::NamedCurve;
 use crate::error::*;
 use crate::extension::extension_use_srtp::*;
+use crate::flight::dtls13::*;
 use crate::flight::flight0::*;
 use crate::flight::flight1::*;
 use crate::flight::flight5::*;
@@ -30,9 +32,12 @@ use crate::flight::*;
 use crate::fragment_buffer::*;
 use crate::handshake::handshake_cache::*;
 use crate::handshake::handshake_header::HandshakeHeader;
+use crate::handshake::handshake_message_key_update::*;
 use crate::handshake::*;
 use crate::handshaker::*;
+use crate::record_layer::inner_plaintext::*;
 use crate::record_layer::record_layer_header::*;
+use crate::record_layer::unified_header::*;
 use crate::record_layer::*;
 use crate::signature_hash_algorithm::parse_signature_schemes;
 use crate::state::*;
@@ -43,6 +48,8 @@ pub(crate) const DEFAULT_NAMED_CURVE: NamedCurve = NamedCurve::X25519;
 pub(crate) const INBOUND_BUFFER_SIZE: usize = 8192;
 // Default replay protection window is specified by RFC 6347 Section 4.1.2.6
 pub(crate) const DEFAULT_REPLAY_PROTECTION_WINDOW: usize = 64;
+// Number of times a DTLS 1.3 KeyUpdate is retransmitted before giving up
+pub(crate) const KEY_UPDATE_MAX_RETRANSMITS: usize = 5;
 
 pub static INVALID_KEYING_LABELS: &[&str] = &[
     "client finished",
@@ -53,6 +60,51 @@ pub static INVALID_KEYING_LABELS: &[&str] = &[
 
 type PacketSendRequest = (Vec<Packet>, Option<mpsc::Sender<Result<()>>>);
 
+// AckState keeps track of the DTLS 1.3 handshake records to acknowledge
+// and of the acknowledgements received from the peer.
+// https://www.rfc-editor.org/rfc/rfc9147#section-7
+#[derive(Default)]
+pub(crate) struct AckState {
+    // Handshake records received from the peer and not acknowledged yet
+    pub(crate) received: Vec<RecordNumber>,
+    // Records the peer has acknowledged
+    pub(crate) acked: Vec<RecordNumber>,
+    // KeyUpdate sent with the keys of its record epoch, until it is acknowledged
+    pub(crate) key_update: Option<Packet>,
+    // Message sequence of the next post-handshake message
+    pub(crate) next_message_sequence: u16,
+    pub(crate) key_update_notify: Arc<Notify>,
+}
+
+// ConnectionIdState holds the connection IDs negotiated with the
+// connection_id extension, they are only used by DTLS 1.2 connections.
+// https://www.rfc-editor.org/rfc/rfc9146
+#[derive(Default, Debug)]
+pub(crate) struct ConnectionIdState {
+    // Connection ID the peer puts in its protected records, Some once offered
+    pub(crate) local_connection_id: Option<Vec<u8>>,
+    // Connection ID we put in our protected records, Some once negotiated
+    pub(crate) remote_connection_id: Option<Vec<u8>>,
+}
+
+impl AckState {
+    // key_update_packet builds a KeyUpdate sent with the keys of epoch.
+    pub(crate) fn key_update_packet(&mut self, epoch: u16, update_requested: bool) -> Packet {
+        let mut handshake =
+            Handshake::new(HandshakeMessage::KeyUpdate(HandshakeMessageKeyUpdate {
+                update_requested,
+            }));
+        handshake.handshake_header.message_sequence = self.next_message_sequence;
+        self.next_message_sequence = self.next_message_sequence.wrapping_add(1);
+
+        Packet {
+            record: RecordLayer::new(PROTOCOL_VERSION1_2, epoch, Content::Handshake(handshake)),
+            should_encrypt: true,
+            reset_local_sequence_number: false,
+        }
+    }
+}
+
 struct ConnReaderContext {
     is_client: bool,
     replay_protection_window: usize,
@@ -66,6 +118,16 @@ struct ConnReaderContext {
     handshake_tx: mpsc::Sender<mpsc::Sender<()>>,
     handshake_done_rx: mpsc::Receiver<()>,
     packet_tx: Arc<mpsc::Sender<PacketSendRequest>>,
+    ack_state: Arc<Mutex<AckState>>,
+    // The peer asked for a KeyUpdate of ours
+    key_update_requested: bool,
+    // The peer updated its keys, records queued for the next epoch can be handled
+    remote_key_updated: bool,
+    connection_id_state: Arc<Mutex<ConnectionIdState>>,
+    // Epoch and sequence number of the newest record carrying our connection ID
+    latest_connection_id_record: Option<(u16, u64)>,
+    // The last handled record carried our connection ID and was the newest one
+    is_latest_connection_id_record: bool,
 }
 
 // Conn represents a DTLS connection
@@ -200,6 +262,13 @@ impl DTLSConn {
             }
         }
 
+        // DTLS 1.3 is only negotiated with certificates
+        let max_version = if config.psk.is_some() {
+            DtlsVersion::Dtls12
+        } else {
+            config.max_version
+        };
+
         let cfg = HandshakeConfig {
             local_psk_callback: config.psk.take(),
             local_psk_identity_hint: config.psk_identity_hint.take(),
@@ -229,6 +298,8 @@ impl DTLSConn {
             retransmit_interval,
             //log: logger,
             initial_epoch: 0,
+            max_version,
+            connection_id_generator: config.connection_id_generator.take(),
             ..Default::default()
         };
 
@@ -296,6 +367,7 @@ impl DTLSConn {
 
         let cipher_suite1 = Arc::clone(&c.state.cipher_suite);
         let sequence_number = Arc::clone(&c.state.local_sequence_number);
+        let connection_id_state1 = Arc::clone(&c.state.connection_id_state);
 
         tokio::spawn(async move {
             loop {
@@ -303,6 +375,13 @@ impl DTLSConn {
                 if let Some(r) = rx {
                     let (pkt, result_tx) = r;
 
+                    let remote_connection_id = {
+                        let connection_id_state = connection_id_state1.lock().await;
+                        connection_id_state
+                            .remote_connection_id
+                            .clone()
+                            .unwrap_or_default()
+                    };
                     let result = DTLSConn::handle_outgoing_packets(
                         &next_conn_tx,
                         pkt,
@@ -311,6 +390,7 @@ impl DTLSConn {
                         &sequence_number,
                         &cipher_suite1,
                         maximum_transmission_unit,
+                        &remote_connection_id,
                     )
                     .await;
 
@@ -327,6 +407,8 @@ impl DTLSConn {
         let local_epoch = Arc::clone(&c.state.local_epoch);
         let remote_epoch = Arc::clone(&c.state.remote_epoch);
         let cipher_suite2 = Arc::clone(&c.state.cipher_suite);
+        let ack_state = Arc::clone(&c.state.ack_state);
+        let connection_id_state2 = Arc::clone(&c.state.connection_id_state);
 
         tokio::spawn(async move {
             let mut buf = vec![0u8; INBOUND_BUFFER_SIZE];
@@ -343,6 +425,12 @@ impl DTLSConn {
                 handshake_tx,
                 handshake_done_rx,
                 packet_tx: packet_tx2,
+                ack_state,
+                key_update_requested: false,
+                remote_key_updated: false,
+                connection_id_state: connection_id_state2,
+                latest_connection_id_record: None,
+                is_latest_connection_id_record: false,
             };
 
             //trace!("before enter read_and_buffer: {}] ", srv_cli_str(is_client));
@@ -467,6 +555,53 @@ impl DTLSConn {
         Ok(p.len())
     }
 
+    /// update_keys updates the keys used to send data on a DTLS 1.3 connection
+    /// with a KeyUpdate message. If request_peer_update is set, the peer is asked
+    /// to update its keys as well. It returns once the peer acknowledged the
+    /// KeyUpdate.
+    pub async fn update_keys(&self, request_peer_update: bool) -> Result<()> {
+        if !self.is_handshake_completed_successfully() {
+            return Err(Error::ErrHandshakeInProgress);
+        }
+
+        {
+            let cipher_suite = self.state.cipher_suite.lock().await;
+            if !cipher_suite.as_ref().map_or(false, |cs| cs.is_dtls13()) {
+                return Err(Error::ErrDtls13Only);
+            }
+        }
+
+        let (pkt, key_update_notify) = {
+            let mut ack_state = self.state.ack_state.lock().await;
+            if ack_state.key_update.is_some() {
+                return Err(Error::ErrInvalidKeyUpdate);
+            }
+            let pkt = ack_state.key_update_packet(self.get_local_epoch(), request_peer_update);
+            ack_state.key_update = Some(pkt.clone());
+            (pkt, Arc::clone(&ack_state.key_update_notify))
+        };
+
+        for _ in 0..=KEY_UPDATE_MAX_RETRANSMITS {
+            self.write_packets(vec![pkt.clone()]).await?;
+
+            let _ =
+                tokio::time::timeout(self.cfg.retransmit_interval, key_update_notify.notified())
+                    .await;
+
+            let mut ack_state = self.state.ack_state.lock().await;
+            if ack_state.key_update.is_none() {
+                return Ok(());
+            }
+            if self.is_connection_closed() {
+                ack_state.key_update = None;
+                return Err(Error::ErrConnClosed);
+            }
+        }
+
+        self.state.ack_state.lock().await.key_update = None;
+        Err(Error::ErrKeyUpdateNotAcknowledged)
+    }
+
     // Close closes the connection.
     pub async fn close(&self) -> Result<()> {
         if !self.closed.load(Ordering::SeqCst) {
@@ -498,6 +633,26 @@ impl DTLSConn {
         self.state.srtp_protection_profile
     }
 
+    /// cipher_suite_id returns the ID of the negotiated cipher suite, or None if
+    /// a cipher suite hasn't been chosen yet.
+    pub async fn cipher_suite_id(&self) -> Option<CipherSuiteId> {
+        let cipher_suite = self.state.cipher_suite.lock().await;
+        cipher_suite.as_ref().map(|cs| cs.id())
+    }
+
+    /// protocol_version returns the negotiated DTLS version, or None if a cipher
+    /// suite hasn't been chosen yet.
+    pub async fn protocol_version(&self) -> Option<ProtocolVersion> {
+        let cipher_suite = self.state.cipher_suite.lock().await;
+        cipher_suite.as_ref().map(|cs| {
+            if cs.is_dtls13() {
+                PROTOCOL_VERSION1_3
+            } else {
+                PROTOCOL_VERSION1_2
+            }
+        })
+    }
+
     pub(crate) async fn notify(&self, level: AlertLevel, desc: AlertDescription) -> Result<()> {
         self.write_packets(vec![Packet {
             record: RecordLayer::new(
@@ -526,6 +681,7 @@ impl DTLSConn {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn handle_outgoing_packets(
         next_conn: &Arc<dyn util::Conn + Send + Sync>,
         mut pkts: Vec<Packet>,
@@ -534,6 +690,7 @@ impl DTLSConn {
         local_sequence_number: &Arc<Mutex<Vec<u64>>>,
         cipher_suite: &Arc<Mutex<Option<Box<dyn CipherSuite + Send + Sync>>>>,
         maximum_transmission_unit: usize,
+        remote_connection_id: &[u8],
     ) -> Result<()> {
         let mut raw_packets = vec![];
         for p in &mut pkts {
@@ -564,6 +721,7 @@ impl DTLSConn {
                     local_sequence_number,
                     cipher_suite,
                     maximum_transmission_unit,
+                    remote_connection_id,
                     p,
                     h,
                 )
@@ -576,8 +734,13 @@ impl DTLSConn {
                     }
                 }*/
 
-                let raw_packet =
-                    DTLSConn::process_packet(local_sequence_number, cipher_suite, p).await?;
+                let raw_packet = DTLSConn::process_packet(
+                    local_sequence_number,
+                    cipher_suite,
+                    remote_connection_id,
+                    p,
+                )
+                .await?;
                 raw_packets.push(raw_packet);
             }
         }
@@ -597,6 +760,7 @@ impl DTLSConn {
     async fn process_packet(
         local_sequence_number: &Arc<Mutex<Vec<u64>>>,
         cipher_suite: &Arc<Mutex<Option<Box<dyn CipherSuite + Send + Sync>>>>,
+        remote_connection_id: &[u8],
         p: &mut Packet,
     ) -> Result<Vec<u8>> {
         let epoch = p.record.record_layer_header.epoch as usize;
@@ -625,10 +789,21 @@ impl DTLSConn {
             p.record.marshal(&mut writer)?;
         }
 
-        if p.should_encrypt {
-            let cipher_suite = cipher_suite.lock().await;
-            if let Some(cipher_suite) = &*cipher_suite {
-                raw_packet = cipher_suite.encrypt(&p.record.record_layer_header, &raw_packet)?;
+        let cipher_suite = cipher_suite.lock().await;
+        if let Some(cipher_suite) = &*cipher_suite {
+            // DTLS 1.3 protects every record after the initial epoch.
+            if p.should_encrypt || (epoch != 0 && cipher_suite.is_dtls13()) {
+                if epoch != 0 && !remote_connection_id.is_empty() && !cipher_suite.is_dtls13() {
+                    let (record_layer_header, raw) = wrap_connection_id(
+                        &p.record.record_layer_header,
+                        &raw_packet,
+                        remote_connection_id,
+                    )?;
+                    raw_packet = cipher_suite.encrypt(&record_layer_header, &raw)?;
+                } else {
+                    raw_packet =
+                        cipher_suite.encrypt(&p.record.record_layer_header, &raw_packet)?;
+                }
             }
         }
 
@@ -639,6 +814,7 @@ impl DTLSConn {
         local_sequence_number: &Arc<Mutex<Vec<u64>>>,
         cipher_suite: &Arc<Mutex<Option<Box<dyn CipherSuite + Send + Sync>>>>,
         maximum_transmission_unit: usize,
+        remote_connection_id: &[u8],
         p: &Packet,
         h: &Handshake,
     ) -> Result<Vec<Vec<u8>>> {
@@ -669,6 +845,7 @@ impl DTLSConn {
                 content_len: handshake_fragment.len() as u16,
                 epoch: p.record.record_layer_header.epoch,
                 sequence_number: seq,
+                connection_id: vec![],
             };
 
             let mut record_layer_header_bytes = vec![];
@@ -682,10 +859,19 @@ impl DTLSConn {
             let mut raw_packet = vec![];
             raw_packet.extend_from_slice(&record_layer_header_bytes);
             raw_packet.extend_from_slice(handshake_fragment);
-            if p.should_encrypt {
-                let cipher_suite = cipher_suite.lock().await;
-                if let Some(cipher_suite) = &*cipher_suite {
-                    raw_packet = cipher_suite.encrypt(&record_layer_header, &raw_packet)?;
+            let cipher_suite = cipher_suite.lock().await;
+            if let Some(cipher_suite) = &*cipher_suite {
+                if p.should_encrypt || (epoch != 0 && cipher_suite.is_dtls13()) {
+                    if epoch != 0 && !remote_connection_id.is_empty() && !cipher_suite.is_dtls13() {
+                        let (record_layer_header, raw) = wrap_connection_id(
+                            &record_layer_header,
+                            &raw_packet,
+                            remote_connection_id,
+                        )?;
+                        raw_packet = cipher_suite.encrypt(&record_layer_header, &raw)?;
+                    } else {
+                        raw_packet = cipher_suite.encrypt(&record_layer_header, &raw_packet)?;
+                    }
                 }
             }
 
@@ -757,11 +943,47 @@ impl DTLSConn {
         local_epoch: &Arc<AtomicU16>,
         handshake_completed_successfully: &Arc<AtomicBool>,
     ) -> Result<()> {
-        let n = next_conn.recv(buf).await?;
-        let pkts = unpack_datagram(&buf[..n])?;
+        let local_connection_id = {
+            let connection_id_state = ctx.connection_id_state.lock().await;
+            connection_id_state
+                .local_connection_id
+                .clone()
+                .unwrap_or_default()
+        };
+        // The source address of records carrying our connection ID may change
+        let (n, raddr) = if local_connection_id.is_empty() {
+            (next_conn.recv(buf).await?, None)
+        } else {
+            let (n, raddr) = next_conn.recv_from(buf).await?;
+            (n, Some(raddr))
+        };
+        let pkts = unpack_datagram(&buf[..n], local_connection_id.len())?;
         let mut has_handshake = false;
         for pkt in pkts {
             let (hs, alert, mut err) = DTLSConn::handle_incoming_packet(ctx, pkt, true).await;
+
+            // Only an authenticated record newer than all the others moves the
+            // connection to a new peer address.
+            // https://www.rfc-editor.org/rfc/rfc9146#section-6
+            if std::mem::take(&mut ctx.is_latest_connection_id_record) {
+                if let Some(raddr) = raddr {
+                    if next_conn.remote_addr().map_or(false, |addr| addr != raddr) {
+                        debug!(
+                            "{}: peer address changed to {}",
+                            srv_cli_str(ctx.is_client),
+                            raddr
+                        );
+                        if let Err(err) = next_conn.connect(raddr).await {
+                            debug!(
+                                "{}: failed to change peer address: {}",
+                                srv_cli_str(ctx.is_client),
+                                err
+                            );
+                        }
+                    }
+                }
+            }
+
             if let Some(alert) = alert {
                 let alert_err = ctx
                     .packet_tx
@@ -804,6 +1026,21 @@ impl DTLSConn {
             }
         }
 
+        let is_dtls13 = {
+            let cipher_suite = ctx.cipher_suite.lock().await;
+            cipher_suite.as_ref().map_or(false, |cs| cs.is_dtls13())
+        };
+        if is_dtls13 && handshake_completed_successfully.load(Ordering::SeqCst) {
+            // Post-handshake messages of DTLS 1.3 are handled here, the
+            // handshaker does not run anymore.
+            return DTLSConn::handle_post_handshake_dtls13(
+                ctx,
+                local_epoch,
+                handshake_completed_successfully,
+            )
+            .await;
+        }
+
         if has_handshake {
             let (done_tx, mut done_rx) = mpsc::channel(1);
 
@@ -835,6 +1072,78 @@ impl DTLSConn {
         Ok(())
     }
 
+    // handle_post_handshake_dtls13 acknowledges the handshake records received
+    // after the handshake, and answers KeyUpdates of the peer.
+    // https://www.rfc-editor.org/rfc/rfc9147#section-8
+    async fn handle_post_handshake_dtls13(
+        ctx: &mut ConnReaderContext,
+        local_epoch: &Arc<AtomicU16>,
+        handshake_completed_successfully: &Arc<AtomicBool>,
+    ) -> Result<()> {
+        let mut pkts = vec![];
+        {
+            let mut ack_state = ctx.ack_state.lock().await;
+
+            // Our KeyUpdate was acknowledged, switch to the keys of the next epoch.
+            let key_update_epoch = ack_state
+                .key_update
+                .as_ref()
+                .map(|p| p.record.record_layer_header.epoch as u64);
+            if let Some(key_update_epoch) = key_update_epoch {
+                if ack_state.acked.iter().any(|r| r.epoch == key_update_epoch) {
+                    let mut cipher_suite = ctx.cipher_suite.lock().await;
+                    if let Some(cipher_suite) = &mut *cipher_suite {
+                        let epoch = cipher_suite.update_traffic_secret(true)?;
+                        local_epoch.store(epoch, Ordering::SeqCst);
+                    }
+                    ack_state.key_update = None;
+                    ack_state.key_update_notify.notify_one();
+                }
+            }
+            ack_state.acked.clear();
+
+            let epoch = local_epoch.load(Ordering::SeqCst);
+            if !ack_state.received.is_empty() {
+                pkts.push(Packet {
+                    record: RecordLayer::new(
+                        PROTOCOL_VERSION1_2,
+                        epoch,
+                        Content::Ack(Ack {
+                            record_numbers: std::mem::take(&mut ack_state.received),
+                        }),
+                    ),
+                    should_encrypt: true,
+                    reset_local_sequence_number: false,
+                });
+            }
+
+            if ctx.key_update_requested && ack_state.key_update.is_none() {
+                ctx.key_update_requested = false;
+                let pkt = ack_state.key_update_packet(epoch, false);
+                ack_state.key_update = Some(pkt.clone());
+                pkts.push(pkt);
+            }
+        }
+
+        if !pkts.is_empty() {
+            ctx.packet_tx.send((pkts, None)).await?;
+        }
+
+        if ctx.remote_key_updated {
+            ctx.remote_key_updated = false;
+            let pkts = ctx.encrypted_packets.drain(..).collect();
+            DTLSConn::handle_queued_packets(
+                ctx,
+                local_epoch,
+                handshake_completed_successfully,
+                pkts,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
     async fn handle_queued_packets(
         ctx: &mut ConnReaderContext,
         local_epoch: &Arc<AtomicU16>,
@@ -888,8 +1197,58 @@ impl DTLSConn {
         mut pkt: Vec<u8>,
         enqueue: bool,
     ) -> (bool, Option<Alert>, Option<Error>) {
+        // DTLS 1.3 records with a unified header only carry the low bits of
+        // their epoch and sequence number, they are decrypted first.
+        let is_dtls13_record = pkt
+            .first()
+            .map_or(false, |b| UnifiedHeader::is_unified_header(*b));
+        if is_dtls13_record {
+            let cipher_suite = ctx.cipher_suite.lock().await;
+            let result = match &*cipher_suite {
+                Some(cipher_suite) if cipher_suite.is_dtls13() => cipher_suite.decrypt_dtls13(&pkt),
+                Some(_) => {
+                    debug!(
+                        "{}: discarded DTLS 1.3 record on DTLS 1.2 connection",
+                        srv_cli_str(ctx.is_client)
+                    );
+                    return (false, None, None);
+                }
+                None => Err(Error::ErrEpochKeysNotReady),
+            };
+            drop(cipher_suite);
+
+            pkt = match result {
+                Ok(pkt) => pkt,
+                Err(Error::ErrEpochKeysNotReady) => {
+                    if enqueue {
+                        debug!(
+                            "{}: keys of the epoch not ready, queuing packet",
+                            srv_cli_str(ctx.is_client)
+                        );
+                        ctx.encrypted_packets.push(pkt);
+                    }
+                    return (false, None, None);
+                }
+                Err(err) => {
+                    debug!("{}: decrypt failed: {}", srv_cli_str(ctx.is_client), err);
+                    return (false, None, None);
+                }
+            };
+        }
+
+        let local_connection_id = {
+            let connection_id_state = ctx.connection_id_state.lock().await;
+            connection_id_state
+                .local_connection_id
+                .clone()
+                .unwrap_or_default()
+        };
+
         let mut reader = BufReader::new(pkt.as_slice());
-        let h = match RecordLayerHeader::unmarshal(&mut reader) {
+        let h = match RecordLayerHeader::unmarshal_with_connection_id(
+            &mut reader,
+            local_connection_id.len(),
+        ) {
             Ok(h) => h,
             Err(err) => {
                 // Decode error must be silently discarded
@@ -905,7 +1264,7 @@ impl DTLSConn {
 
         // Validate epoch
         let epoch = ctx.remote_epoch.load(Ordering::SeqCst);
-        if h.epoch > epoch {
+        if h.epoch > epoch && !is_dtls13_record {
             if h.epoch > epoch + 1 {
                 debug!(
                     "{}: discarded future packet (epoch: {}, seq: {})",
@@ -946,7 +1305,7 @@ impl DTLSConn {
         }
 
         // Decrypt
-        if h.epoch != 0 {
+        if h.epoch != 0 && !is_dtls13_record {
             let invalid_cipher_suite = {
                 let cipher_suite = ctx.cipher_suite.lock().await;
                 if cipher_suite.is_none() {
@@ -968,9 +1327,25 @@ impl DTLSConn {
                 return (false, None, None);
             }
 
+            // Once connection IDs are negotiated, every protected record of
+            // the peer carries ours.
+            // https://www.rfc-editor.org/rfc/rfc9146#section-6
+            let has_connection_id = h.content_type == ContentType::ConnectionId;
+            if has_connection_id == local_connection_id.is_empty()
+                || (has_connection_id && h.connection_id != local_connection_id)
+            {
+                debug!(
+                    "{}: discarded packet with unexpected connection ID (epoch: {}, seq: {})",
+                    srv_cli_str(ctx.is_client),
+                    h.epoch,
+                    h.sequence_number,
+                );
+                return (false, None, None);
+            }
+
             let cipher_suite = ctx.cipher_suite.lock().await;
             if let Some(cipher_suite) = &*cipher_suite {
-                pkt = match cipher_suite.decrypt(&pkt) {
+                pkt = match cipher_suite.decrypt(&h, &pkt) {
                     Ok(pkt) => pkt,
                     Err(err) => {
                         debug!("{}: decrypt failed: {}", srv_cli_str(ctx.is_client), err);
@@ -991,6 +1366,27 @@ impl DTLSConn {
                     }
                 };
             }
+            drop(cipher_suite);
+
+            if has_connection_id {
+                pkt = match unwrap_connection_id(&h, &pkt) {
+                    Ok(pkt) => pkt,
+                    Err(err) => {
+                        debug!(
+                            "{}: discarded broken packet: {}",
+                            srv_cli_str(ctx.is_client),
+                            err
+                        );
+                        return (false, None, None);
+                    }
+                };
+
+                let record = (h.epoch, h.sequence_number);
+                if ctx.latest_connection_id_record < Some(record) {
+                    ctx.latest_connection_id_record = Some(record);
+                    ctx.is_latest_connection_id_record = true;
+                }
+            }
         }
 
         let is_handshake = match ctx.fragment_buffer.push(&pkt) {
@@ -1004,10 +1400,23 @@ impl DTLSConn {
         };
         if is_handshake {
             ctx.replay_detector[h.epoch as usize].accept();
+            if is_dtls13_record {
+                let mut ack_state = ctx.ack_state.lock().await;
+                ack_state.received.push(RecordNumber {
+                    epoch: h.epoch as u64,
+                    sequence_number: h.sequence_number,
+                });
+            }
             while let Ok((out, epoch)) = ctx.fragment_buffer.pop() {
                 //log::debug!("Extension Debug: out.len()={}", out.len());
                 let mut reader = BufReader::new(out.as_slice());
-                let raw_handshake = match Handshake::unmarshal(&mut reader) {
+                // Messages protected with DTLS 1.3 keys have their own encoding
+                let result = if epoch >= DTLS13_HANDSHAKE_EPOCH {
+                    Handshake::unmarshal_dtls13(&mut reader)
+                } else {
+                    Handshake::unmarshal(&mut reader)
+                };
+                let raw_handshake = match result {
                     Ok(rh) => {
                         trace!(
                             "Recv [handshake:{}] -> {} (epoch: {}, seq: {})",
@@ -1028,7 +1437,8 @@ impl DTLSConn {
                     }
                 };
 
-                ctx.cache
+                let is_new = ctx
+                    .cache
                     .push(
                         out,
                         epoch,
@@ -1037,6 +1447,35 @@ impl DTLSConn {
                         !ctx.is_client,
                     )
                     .await;
+
+                if let HandshakeMessage::KeyUpdate(key_update) = &raw_handshake.handshake_message {
+                    if !is_new {
+                        continue;
+                    }
+
+                    let mut cipher_suite = ctx.cipher_suite.lock().await;
+                    if let Some(cipher_suite) = &mut *cipher_suite {
+                        match cipher_suite.update_traffic_secret(false) {
+                            Ok(remote_epoch) => {
+                                ctx.remote_epoch.store(remote_epoch, Ordering::SeqCst);
+                            }
+                            Err(err) => {
+                                return (
+                                    false,
+                                    Some(Alert {
+                                        alert_level: AlertLevel::Fatal,
+                                        alert_description: AlertDescription::InternalError,
+                                    }),
+                                    Some(err),
+                                );
+                            }
+                        }
+                    }
+                    ctx.remote_key_updated = true;
+                    if key_update.update_requested {
+                        ctx.key_update_requested = true;
+                    }
+                }
             }
 
             return (true, None, None);
@@ -1109,6 +1548,13 @@ impl DTLSConn {
                     ctx.replay_detector[h.epoch as usize].accept();
                 }
             }
+            Content::Ack(a) if is_dtls13_record => {
+                ctx.replay_detector[h.epoch as usize].accept();
+
+                let mut ack_state = ctx.ack_state.lock().await;
+                ack_state.acked.extend_from_slice(&a.record_numbers);
+                return (true, None, None);
+            }
             Content::ApplicationData(a) => {
                 if h.epoch == 0 {
                     return (
@@ -1158,6 +1604,51 @@ impl DTLSConn {
     }
 }
 
+// wrap_connection_id moves the content of a record into a DTLSInnerPlaintext
+// behind a tls12_cid header carrying the connection ID of the peer.
+// https://www.rfc-editor.org/rfc/rfc9146#section-4
+fn wrap_connection_id(
+    h: &RecordLayerHeader,
+    raw: &[u8],
+    connection_id: &[u8],
+) -> Result<(RecordLayerHeader, Vec<u8>)> {
+    let inner_plaintext = InnerPlaintext {
+        content: raw[RECORD_LAYER_HEADER_SIZE..].to_vec(),
+        real_type: h.content_type,
+        zeros: 0,
+    };
+    let record_layer_header = RecordLayerHeader {
+        content_type: ContentType::ConnectionId,
+        connection_id: connection_id.to_vec(),
+        content_len: inner_plaintext.size() as u16,
+        ..h.clone()
+    };
+
+    let mut out = Vec::with_capacity(record_layer_header.size() + inner_plaintext.size());
+    record_layer_header.marshal(&mut out)?;
+    inner_plaintext.marshal(&mut out)?;
+
+    Ok((record_layer_header, out))
+}
+
+// unwrap_connection_id turns a decrypted tls12_cid record back into a record
+// with its real content type.
+fn unwrap_connection_id(h: &RecordLayerHeader, raw: &[u8]) -> Result<Vec<u8>> {
+    let inner_plaintext = InnerPlaintext::unmarshal(&raw[h.size()..])?;
+    let record_layer_header = RecordLayerHeader {
+        content_type: inner_plaintext.real_type,
+        connection_id: vec![],
+        content_len: inner_plaintext.content.len() as u16,
+        ..h.clone()
+    };
+
+    let mut out = Vec::with_capacity(RECORD_LAYER_HEADER_SIZE + inner_plaintext.content.len());
+    record_layer_header.marshal(&mut out)?;
+    out.extend_from_slice(&inner_plaintext.content);
+
+    Ok(out)
+}
+
 fn compact_raw_packets(raw_packets: &[Vec<u8>], maximum_transmission_unit: usize) -> Vec<Vec<u8>> {
     let mut combined_raw_packets = vec![];
     let mut current_combined_raw_packet = vec![];
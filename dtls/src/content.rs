@@ -1,5 +1,6 @@
 use std::io::{Read, Write};
 
+use super::ack::*;
 use super::alert::*;
 use super::application_data::*;
 use super::change_cipher_spec::*;
@@ -13,6 +14,10 @@ pub enum ContentType {
     Alert = 21,
     Handshake = 22,
     ApplicationData = 23,
+    // tls12_cid, the outer type of records carrying a connection ID
+    // https://www.rfc-editor.org/rfc/rfc9146#section-4
+    ConnectionId = 25,
+    Ack = 26,
     #[default]
     Invalid,
 }
@@ -24,6 +29,8 @@ impl From<u8> for ContentType {
             21 => ContentType::Alert,
             22 => ContentType::Handshake,
             23 => ContentType::ApplicationData,
+            25 => ContentType::ConnectionId,
+            26 => ContentType::Ack,
             _ => ContentType::Invalid,
         }
     }
@@ -35,6 +42,7 @@ pub enum Content {
     Alert(Alert),
     Handshake(Handshake),
     ApplicationData(ApplicationData),
+    Ack(Ack),
 }
 
 impl Content {
@@ -44,6 +52,7 @@ impl Content {
             Content::Alert(c) => c.content_type(),
             Content::Handshake(c) => c.content_type(),
             Content::ApplicationData(c) => c.content_type(),
+            Content::Ack(c) => c.content_type(),
         }
     }
 
@@ -53,6 +62,7 @@ impl Content {
             Content::Alert(c) => c.size(),
             Content::Handshake(c) => c.size(),
             Content::ApplicationData(c) => c.size(),
+            Content::Ack(c) => c.size(),
         }
     }
 
@@ -62,6 +72,7 @@ impl Content {
             Content::Alert(c) => c.marshal(writer),
             Content::Handshake(c) => c.marshal(writer),
             Content::ApplicationData(c) => c.marshal(writer),
+            Content::Ack(c) => c.marshal(writer),
         }
     }
 
@@ -75,6 +86,7 @@ impl Content {
             ContentType::ApplicationData => Ok(Content::ApplicationData(
                 ApplicationData::unmarshal(reader)?,
             )),
+            ContentType::Ack => Ok(Content::Ack(Ack::unmarshal(reader)?)),
             _ => Err(Error::ErrInvalidContentType),
         }
     }
@@ -0,0 +1,119 @@
+// AES-GCM record protection of DTLS 1.3, including the encryption of record
+// sequence numbers.
+// https://www.rfc-editor.org/rfc/rfc9147#section-4.2.3
+
+use aes::cipher::BlockEncrypt;
+use aes::Aes128;
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::aead::AeadInPlace;
+use aes_gcm::{Aes128Gcm, KeyInit};
+
+use crate::content::*;
+use crate::error::*;
+use crate::record_layer::unified_header::*;
+
+pub(crate) const CRYPTO_GCM_DTLS13_KEY_LENGTH: usize = 16;
+pub(crate) const CRYPTO_GCM_DTLS13_IV_LENGTH: usize = 12;
+pub(crate) const CRYPTO_GCM_DTLS13_TAG_LENGTH: usize = 16;
+
+// Keys protecting the records of a single epoch in a single direction
+#[derive(Clone)]
+pub(crate) struct CryptoGcmDtls13 {
+    gcm: Aes128Gcm,
+    iv: Vec<u8>,
+    sn_cipher: Aes128,
+}
+
+impl CryptoGcmDtls13 {
+    pub(crate) fn new(key: &[u8], iv: &[u8], sn_key: &[u8]) -> Self {
+        CryptoGcmDtls13 {
+            gcm: Aes128Gcm::new(GenericArray::from_slice(key)),
+            iv: iv.to_vec(),
+            sn_cipher: Aes128::new(GenericArray::from_slice(sn_key)),
+        }
+    }
+
+    // The per-record nonce is the IV XORed with the sequence number, the epoch
+    // is not part of it.
+    fn nonce(&self, sequence_number: u64) -> Vec<u8> {
+        let mut nonce = self.iv.clone();
+        let seq = sequence_number.to_be_bytes();
+        let offset = nonce.len() - seq.len();
+        for (n, s) in nonce[offset..].iter_mut().zip(seq.iter()) {
+            *n ^= s;
+        }
+        nonce
+    }
+
+    // sequence_number_mask encrypts the first block of the ciphertext with the
+    // sn_key, the sequence number bits of the header are XORed with it.
+    pub(crate) fn sequence_number_mask(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        if ciphertext.len() < 16 {
+            return Err(Error::ErrNotEnoughRoomForNonce);
+        }
+
+        let mut block = GenericArray::clone_from_slice(&ciphertext[..16]);
+        self.sn_cipher.encrypt_block(&mut block);
+        Ok(block.to_vec())
+    }
+
+    // encrypt builds a complete DTLSCiphertext record. header.length is set to
+    // the length of the protected payload.
+    pub(crate) fn encrypt(
+        &self,
+        header: &mut UnifiedHeader,
+        sequence_number: u64,
+        content_type: ContentType,
+        content: &[u8],
+    ) -> Result<Vec<u8>> {
+        // DTLSInnerPlaintext without padding
+        let mut buffer = Vec::with_capacity(content.len() + 1 + CRYPTO_GCM_DTLS13_TAG_LENGTH);
+        buffer.extend_from_slice(content);
+        buffer.push(content_type as u8);
+
+        header.length = Some((buffer.len() + CRYPTO_GCM_DTLS13_TAG_LENGTH) as u16);
+        let mut raw_header = vec![];
+        header.marshal(&mut raw_header)?;
+
+        let nonce = self.nonce(sequence_number);
+        self.gcm
+            .encrypt_in_place(GenericArray::from_slice(&nonce), &raw_header, &mut buffer)
+            .map_err(|e| Error::Other(e.to_string()))?;
+
+        let mask = self.sequence_number_mask(&buffer)?;
+        let offset = header.sequence_number_offset();
+        for (i, m) in mask.iter().take(header.sequence_number_size()).enumerate() {
+            raw_header[offset + i] ^= m;
+        }
+
+        let mut r = raw_header;
+        r.extend_from_slice(&buffer);
+        Ok(r)
+    }
+
+    // decrypt takes the header with the sequence number bits already
+    // decrypted and returns the content type and content of the record.
+    pub(crate) fn decrypt(
+        &self,
+        raw_header: &[u8],
+        sequence_number: u64,
+        ciphertext: &[u8],
+    ) -> Result<(ContentType, Vec<u8>)> {
+        let mut buffer = ciphertext.to_vec();
+        let nonce = self.nonce(sequence_number);
+        self.gcm
+            .decrypt_in_place(GenericArray::from_slice(&nonce), raw_header, &mut buffer)
+            .map_err(|e| Error::Other(e.to_string()))?;
+
+        // Strip the padding, the content type is the last non-zero byte
+        while let Some(0) = buffer.last() {
+            buffer.pop();
+        }
+        let content_type = match buffer.pop() {
+            Some(content_type) => content_type.into(),
+            None => return Err(Error::ErrInvalidContentType),
+        };
+
+        Ok((content_type, buffer))
+    }
+}
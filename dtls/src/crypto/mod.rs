@@ -4,6 +4,7 @@ mod crypto_test;
 pub mod crypto_cbc;
 pub mod crypto_ccm;
 pub mod crypto_gcm;
+pub mod crypto_gcm_dtls13;
 
 use std::convert::TryFrom;
 use std::sync::Arc;
@@ -14,6 +15,7 @@ use rcgen::KeyPair;
 use ring::rand::SystemRandom;
 use ring::signature::{EcdsaKeyPair, Ed25519KeyPair};
 
+use crate::content::ContentType;
 use crate::curve::named_curve::*;
 use crate::error::*;
 use crate::record_layer::record_layer_header::*;
@@ -489,6 +491,10 @@ pub(crate) fn verify_server_cert(
 }
 
 pub(crate) fn generate_aead_additional_data(h: &RecordLayerHeader, payload_len: usize) -> Vec<u8> {
+    if h.content_type == ContentType::ConnectionId {
+        return generate_aead_additional_data_connection_id(h, payload_len);
+    }
+
     let mut additional_data = vec![0u8; 13];
     // SequenceNumber MUST be set first
     // we only want uint48, clobbering an extra 2 (using uint64, rust doesn't have uint48)
@@ -502,6 +508,29 @@ pub(crate) fn generate_aead_additional_data(h: &RecordLayerHeader, payload_len:
     additional_data
 }
 
+// Records carrying a connection ID authenticate it, the sequence number is
+// replaced by a placeholder which makes the additional data distinct from
+// the one of records without connection ID.
+// https://www.rfc-editor.org/rfc/rfc9146#section-5
+fn generate_aead_additional_data_connection_id(
+    h: &RecordLayerHeader,
+    payload_len: usize,
+) -> Vec<u8> {
+    let mut additional_data = Vec::with_capacity(23 + h.connection_id.len());
+    additional_data.extend_from_slice(&[0xff; 8]); // seq_num_placeholder
+    additional_data.push(ContentType::ConnectionId as u8);
+    additional_data.push(h.connection_id.len() as u8);
+    additional_data.push(ContentType::ConnectionId as u8);
+    additional_data.push(h.protocol_version.major);
+    additional_data.push(h.protocol_version.minor);
+    additional_data.extend_from_slice(&h.epoch.to_be_bytes());
+    additional_data.extend_from_slice(&h.sequence_number.to_be_bytes()[2..]); // uint48 in spec
+    additional_data.extend_from_slice(&h.connection_id);
+    additional_data.extend_from_slice(&(payload_len as u16).to_be_bytes());
+
+    additional_data
+}
+
 #[cfg(test)]
 mod test {
     #[cfg(feature = "pem")]
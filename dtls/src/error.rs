@@ -143,6 +143,24 @@ pub enum Error {
     ErrEmptyFragment,
     #[error("Alert is Fatal or Close Notify")]
     ErrAlertFatalOrClose,
+    #[error("invalid KeyUpdate request")]
+    ErrInvalidKeyUpdate,
+    #[error("keys for the epoch of the record are not available yet")]
+    ErrEpochKeysNotReady,
+    #[error("peer did not offer a key share for a supported group")]
+    ErrNoSupportedKeyShare,
+    #[error("invalid HelloRetryRequest")]
+    ErrInvalidHelloRetryRequest,
+    #[error("DTLS 1.2 was negotiated although both sides support DTLS 1.3")]
+    ErrProtocolVersionDowngrade,
+    #[error("KeyUpdate was not acknowledged")]
+    ErrKeyUpdateNotAcknowledged,
+    #[error("operation is only supported on DTLS 1.3 connections")]
+    ErrDtls13Only,
+    #[error("connection ID must not be longer than 255 bytes")]
+    ErrInvalidConnectionId,
+    #[error("peer sent a connection ID that was not offered")]
+    ErrUnexpectedConnectionId,
 
     #[error(
         "Fragment buffer overflow. New size {new_size} is greater than specified max {max_size}"
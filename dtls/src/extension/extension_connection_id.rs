@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod extension_connection_id_test;
+
+use super::*;
+
+// The connection_id extension carries the connection ID its sender wants to
+// receive in the records of its peer. A zero-length connection ID means the
+// sender is willing to send connection IDs but does not want to receive any.
+// https://www.rfc-editor.org/rfc/rfc9146#section-3
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtensionConnectionId {
+    pub connection_id: Vec<u8>,
+}
+
+impl ExtensionConnectionId {
+    pub fn extension_value(&self) -> ExtensionValue {
+        ExtensionValue::ConnectionId
+    }
+
+    pub fn size(&self) -> usize {
+        2 + 1 + self.connection_id.len()
+    }
+
+    pub fn marshal<W: Write>(&self, writer: &mut W) -> Result<()> {
+        if self.connection_id.len() > u8::MAX as usize {
+            return Err(Error::ErrInvalidConnectionId);
+        }
+
+        writer.write_u16::<BigEndian>(1 + self.connection_id.len() as u16)?;
+        writer.write_u8(self.connection_id.len() as u8)?;
+        writer.write_all(&self.connection_id)?;
+
+        Ok(writer.flush()?)
+    }
+
+    pub fn unmarshal<R: Read>(reader: &mut R) -> Result<Self> {
+        let _ = reader.read_u16::<BigEndian>()?;
+
+        let connection_id_len = reader.read_u8()? as usize;
+        let mut connection_id = vec![0u8; connection_id_len];
+        reader.read_exact(&mut connection_id)?;
+
+        Ok(ExtensionConnectionId { connection_id })
+    }
+}
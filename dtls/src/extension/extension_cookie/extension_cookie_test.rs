@@ -0,0 +1,30 @@
+use std::io::{BufReader, BufWriter};
+
+use super::*;
+
+#[test]
+fn test_extension_cookie() -> Result<()> {
+    let raw_extension_cookie = vec![0x00, 0x05, 0x00, 0x03, 0x01, 0x02, 0x03];
+    let parsed_extension_cookie = ExtensionCookie {
+        cookie: vec![0x01, 0x02, 0x03],
+    };
+
+    let mut raw = vec![];
+    {
+        let mut writer = BufWriter::<&mut Vec<u8>>::new(raw.as_mut());
+        parsed_extension_cookie.marshal(&mut writer)?;
+    }
+    assert_eq!(
+        raw, raw_extension_cookie,
+        "extensionCookie marshal: got {raw:?}, want {raw_extension_cookie:?}"
+    );
+
+    let mut reader = BufReader::new(raw.as_slice());
+    let new_extension_cookie = ExtensionCookie::unmarshal(&mut reader)?;
+    assert_eq!(
+        new_extension_cookie, parsed_extension_cookie,
+        "extensionCookie unmarshal: got {new_extension_cookie:?}, want {parsed_extension_cookie:?}"
+    );
+
+    Ok(())
+}
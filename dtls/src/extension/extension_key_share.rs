@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod extension_key_share_test;
+
+use super::*;
+use crate::curve::named_curve::*;
+
+// https://www.rfc-editor.org/rfc/rfc8446#section-4.2.8
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyShareEntry {
+    pub group: NamedCurve,
+    pub key_exchange: Vec<u8>,
+}
+
+impl KeyShareEntry {
+    fn size(&self) -> usize {
+        2 + 2 + self.key_exchange.len()
+    }
+
+    fn marshal<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_u16::<BigEndian>(self.group as u16)?;
+        writer.write_u16::<BigEndian>(self.key_exchange.len() as u16)?;
+        writer.write_all(&self.key_exchange)?;
+        Ok(())
+    }
+
+    fn unmarshal<R: Read>(reader: &mut R) -> Result<Self> {
+        let group = reader.read_u16::<BigEndian>()?.into();
+        let key_exchange_len = reader.read_u16::<BigEndian>()? as usize;
+        let mut key_exchange = vec![0u8; key_exchange_len];
+        reader.read_exact(&mut key_exchange)?;
+        Ok(KeyShareEntry {
+            group,
+            key_exchange,
+        })
+    }
+}
+
+// The key_share extension contains the endpoint's cryptographic parameters.
+// The ClientHello carries a list of shares, the ServerHello the single share
+// it selected and the HelloRetryRequest only the group the client has to use
+// in its second ClientHello.
+// https://www.rfc-editor.org/rfc/rfc8446#section-4.2.8
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExtensionKeyShare {
+    ClientShares(Vec<KeyShareEntry>),
+    ServerShare(KeyShareEntry),
+    SelectedGroup(NamedCurve),
+}
+
+impl ExtensionKeyShare {
+    pub fn extension_value(&self) -> ExtensionValue {
+        ExtensionValue::KeyShare
+    }
+
+    pub fn size(&self) -> usize {
+        2 + match self {
+            ExtensionKeyShare::ClientShares(entries) => {
+                2 + entries.iter().map(|e| e.size()).sum::<usize>()
+            }
+            ExtensionKeyShare::ServerShare(entry) => entry.size(),
+            ExtensionKeyShare::SelectedGroup(_) => 2,
+        }
+    }
+
+    pub fn marshal<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_u16::<BigEndian>((self.size() - 2) as u16)?;
+        match self {
+            ExtensionKeyShare::ClientShares(entries) => {
+                writer.write_u16::<BigEndian>((self.size() - 4) as u16)?;
+                for entry in entries {
+                    entry.marshal(writer)?;
+                }
+            }
+            ExtensionKeyShare::ServerShare(entry) => entry.marshal(writer)?,
+            ExtensionKeyShare::SelectedGroup(group) => {
+                writer.write_u16::<BigEndian>(*group as u16)?
+            }
+        }
+
+        Ok(writer.flush()?)
+    }
+
+    // The wire format does not say which of the three variants is encoded, it is
+    // derived from the lengths: a HelloRetryRequest only carries a group and the
+    // client_shares vector is the only one whose length prefix covers the rest
+    // of the extension.
+    pub fn unmarshal<R: Read>(reader: &mut R) -> Result<Self> {
+        let extension_len = reader.read_u16::<BigEndian>()? as usize;
+        let mut data = vec![0u8; extension_len];
+        reader.read_exact(&mut data)?;
+
+        if extension_len == 2 {
+            let group = u16::from_be_bytes([data[0], data[1]]).into();
+            return Ok(ExtensionKeyShare::SelectedGroup(group));
+        }
+        if extension_len < 2 {
+            return Err(Error::ErrLengthMismatch);
+        }
+
+        let first = u16::from_be_bytes([data[0], data[1]]) as usize;
+        if first + 2 == extension_len {
+            let mut entries = vec![];
+            let mut reader = &data[2..];
+            while !reader.is_empty() {
+                entries.push(KeyShareEntry::unmarshal(&mut reader)?);
+            }
+            Ok(ExtensionKeyShare::ClientShares(entries))
+        } else {
+            let mut reader = data.as_slice();
+            let entry = KeyShareEntry::unmarshal(&mut reader)?;
+            if !reader.is_empty() {
+                return Err(Error::ErrLengthMismatch);
+            }
+            Ok(ExtensionKeyShare::ServerShare(entry))
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::io::{BufReader, BufWriter};
+
+use super::*;
+
+#[test]
+fn test_extension_key_share() -> Result<()> {
+    let tests = vec![
+        (
+            vec![
+                0x00, 0x0e, 0x00, 0x0c, 0x00, 0x1d, 0x00, 0x02, 0x01, 0x02, 0x00, 0x17, 0x00, 0x02,
+                0x03, 0x04,
+            ],
+            ExtensionKeyShare::ClientShares(vec![
+                KeyShareEntry {
+                    group: NamedCurve::X25519,
+                    key_exchange: vec![0x01, 0x02],
+                },
+                KeyShareEntry {
+                    group: NamedCurve::P256,
+                    key_exchange: vec![0x03, 0x04],
+                },
+            ]),
+        ),
+        (
+            vec![0x00, 0x06, 0x00, 0x1d, 0x00, 0x02, 0x01, 0x02],
+            ExtensionKeyShare::ServerShare(KeyShareEntry {
+                group: NamedCurve::X25519,
+                key_exchange: vec![0x01, 0x02],
+            }),
+        ),
+        (
+            vec![0x00, 0x02, 0x00, 0x17],
+            ExtensionKeyShare::SelectedGroup(NamedCurve::P256),
+        ),
+    ];
+
+    for (raw_extension, parsed_extension) in tests {
+        let mut raw = vec![];
+        {
+            let mut writer = BufWriter::<&mut Vec<u8>>::new(raw.as_mut());
+            parsed_extension.marshal(&mut writer)?;
+        }
+        assert_eq!(
+            raw, raw_extension,
+            "extensionKeyShare marshal: got {raw:?}, want {raw_extension:?}"
+        );
+        assert_eq!(parsed_extension.size(), raw_extension.len());
+
+        let mut reader = BufReader::new(raw.as_slice());
+        let new_extension = ExtensionKeyShare::unmarshal(&mut reader)?;
+        assert_eq!(
+            new_extension, parsed_extension,
+            "extensionKeyShare unmarshal: got {new_extension:?}, want {parsed_extension:?}"
+        );
+    }
+
+    Ok(())
+}
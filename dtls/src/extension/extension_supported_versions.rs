@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod extension_supported_versions_test;
+
+use super::*;
+use crate::record_layer::record_layer_header::ProtocolVersion;
+
+// The supported_versions extension is used by the client to indicate which
+// versions it supports and by the server to indicate which version it is
+// using. The ClientHello carries a list, the ServerHello and the
+// HelloRetryRequest carry a single selected version.
+// https://www.rfc-editor.org/rfc/rfc8446#section-4.2.1
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExtensionSupportedVersions {
+    Offered(Vec<ProtocolVersion>),
+    Selected(ProtocolVersion),
+}
+
+impl ExtensionSupportedVersions {
+    pub fn extension_value(&self) -> ExtensionValue {
+        ExtensionValue::SupportedVersions
+    }
+
+    pub fn size(&self) -> usize {
+        match self {
+            ExtensionSupportedVersions::Offered(versions) => 2 + 1 + versions.len() * 2,
+            ExtensionSupportedVersions::Selected(_) => 2 + 2,
+        }
+    }
+
+    pub fn marshal<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_u16::<BigEndian>((self.size() - 2) as u16)?;
+        match self {
+            ExtensionSupportedVersions::Offered(versions) => {
+                writer.write_u8((versions.len() * 2) as u8)?;
+                for v in versions {
+                    writer.write_u8(v.major)?;
+                    writer.write_u8(v.minor)?;
+                }
+            }
+            ExtensionSupportedVersions::Selected(v) => {
+                writer.write_u8(v.major)?;
+                writer.write_u8(v.minor)?;
+            }
+        }
+
+        Ok(writer.flush()?)
+    }
+
+    pub fn unmarshal<R: Read>(reader: &mut R) -> Result<Self> {
+        let extension_len = reader.read_u16::<BigEndian>()? as usize;
+        if extension_len == 2 {
+            let major = reader.read_u8()?;
+            let minor = reader.read_u8()?;
+            return Ok(ExtensionSupportedVersions::Selected(ProtocolVersion {
+                major,
+                minor,
+            }));
+        }
+
+        let versions_len = reader.read_u8()? as usize;
+        if versions_len + 1 != extension_len || versions_len % 2 != 0 {
+            return Err(Error::ErrLengthMismatch);
+        }
+        let mut versions = vec![];
+        for _ in 0..versions_len / 2 {
+            let major = reader.read_u8()?;
+            let minor = reader.read_u8()?;
+            versions.push(ProtocolVersion { major, minor });
+        }
+
+        Ok(ExtensionSupportedVersions::Offered(versions))
+    }
+}
@@ -0,0 +1,40 @@
+use std::io::{BufReader, BufWriter};
+
+use super::*;
+use crate::record_layer::record_layer_header::*;
+
+#[test]
+fn test_extension_supported_versions() -> Result<()> {
+    let tests = vec![
+        (
+            vec![0x00, 0x05, 0x04, 0xfe, 0xfc, 0xfe, 0xfd],
+            ExtensionSupportedVersions::Offered(vec![PROTOCOL_VERSION1_3, PROTOCOL_VERSION1_2]),
+        ),
+        (
+            vec![0x00, 0x02, 0xfe, 0xfc],
+            ExtensionSupportedVersions::Selected(PROTOCOL_VERSION1_3),
+        ),
+    ];
+
+    for (raw_extension, parsed_extension) in tests {
+        let mut raw = vec![];
+        {
+            let mut writer = BufWriter::<&mut Vec<u8>>::new(raw.as_mut());
+            parsed_extension.marshal(&mut writer)?;
+        }
+        assert_eq!(
+            raw, raw_extension,
+            "extensionSupportedVersions marshal: got {raw:?}, want {raw_extension:?}"
+        );
+        assert_eq!(parsed_extension.size(), raw_extension.len());
+
+        let mut reader = BufReader::new(raw.as_slice());
+        let new_extension = ExtensionSupportedVersions::unmarshal(&mut reader)?;
+        assert_eq!(
+            new_extension, parsed_extension,
+            "extensionSupportedVersions unmarshal: got {new_extension:?}, want {parsed_extension:?}"
+        );
+    }
+
+    Ok(())
+}
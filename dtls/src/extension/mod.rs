@@ -1,7 +1,11 @@
+pub mod extension_connection_id;
+pub mod extension_cookie;
+pub mod extension_key_share;
 pub mod extension_server_name;
 pub mod extension_supported_elliptic_curves;
 pub mod extension_supported_point_formats;
 pub mod extension_supported_signature_algorithms;
+pub mod extension_supported_versions;
 pub mod extension_use_extended_master_secret;
 pub mod extension_use_srtp;
 pub mod renegotiation_info;
@@ -9,10 +13,14 @@ pub mod renegotiation_info;
 use std::io::{Read, Write};
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use extension_connection_id::*;
+use extension_cookie::*;
+use extension_key_share::*;
 use extension_server_name::*;
 use extension_supported_elliptic_curves::*;
 use extension_supported_point_formats::*;
 use extension_supported_signature_algorithms::*;
+use extension_supported_versions::*;
 use extension_use_extended_master_secret::*;
 use extension_use_srtp::*;
 
@@ -28,6 +36,10 @@ pub enum ExtensionValue {
     SupportedSignatureAlgorithms = 13,
     UseSrtp = 14,
     UseExtendedMasterSecret = 23,
+    SupportedVersions = 43,
+    Cookie = 44,
+    KeyShare = 51,
+    ConnectionId = 54,
     RenegotiationInfo = 65281,
     Unsupported,
 }
@@ -41,6 +53,10 @@ impl From<u16> for ExtensionValue {
             13 => ExtensionValue::SupportedSignatureAlgorithms,
             14 => ExtensionValue::UseSrtp,
             23 => ExtensionValue::UseExtendedMasterSecret,
+            43 => ExtensionValue::SupportedVersions,
+            44 => ExtensionValue::Cookie,
+            51 => ExtensionValue::KeyShare,
+            54 => ExtensionValue::ConnectionId,
             65281 => ExtensionValue::RenegotiationInfo,
             _ => ExtensionValue::Unsupported,
         }
@@ -55,6 +71,10 @@ pub enum Extension {
     SupportedSignatureAlgorithms(ExtensionSupportedSignatureAlgorithms),
     UseSrtp(ExtensionUseSrtp),
     UseExtendedMasterSecret(ExtensionUseExtendedMasterSecret),
+    SupportedVersions(ExtensionSupportedVersions),
+    Cookie(ExtensionCookie),
+    KeyShare(ExtensionKeyShare),
+    ConnectionId(ExtensionConnectionId),
     RenegotiationInfo(ExtensionRenegotiationInfo),
 }
 
@@ -67,6 +87,10 @@ impl Extension {
             Extension::SupportedSignatureAlgorithms(ext) => ext.extension_value(),
             Extension::UseSrtp(ext) => ext.extension_value(),
             Extension::UseExtendedMasterSecret(ext) => ext.extension_value(),
+            Extension::SupportedVersions(ext) => ext.extension_value(),
+            Extension::Cookie(ext) => ext.extension_value(),
+            Extension::KeyShare(ext) => ext.extension_value(),
+            Extension::ConnectionId(ext) => ext.extension_value(),
             Extension::RenegotiationInfo(ext) => ext.extension_value(),
         }
     }
@@ -81,6 +105,10 @@ impl Extension {
             Extension::SupportedSignatureAlgorithms(ext) => ext.size(),
             Extension::UseSrtp(ext) => ext.size(),
             Extension::UseExtendedMasterSecret(ext) => ext.size(),
+            Extension::SupportedVersions(ext) => ext.size(),
+            Extension::Cookie(ext) => ext.size(),
+            Extension::KeyShare(ext) => ext.size(),
+            Extension::ConnectionId(ext) => ext.size(),
             Extension::RenegotiationInfo(ext) => ext.size(),
         };
 
@@ -96,6 +124,10 @@ impl Extension {
             Extension::SupportedSignatureAlgorithms(ext) => ext.marshal(writer),
             Extension::UseSrtp(ext) => ext.marshal(writer),
             Extension::UseExtendedMasterSecret(ext) => ext.marshal(writer),
+            Extension::SupportedVersions(ext) => ext.marshal(writer),
+            Extension::Cookie(ext) => ext.marshal(writer),
+            Extension::KeyShare(ext) => ext.marshal(writer),
+            Extension::ConnectionId(ext) => ext.marshal(writer),
             Extension::RenegotiationInfo(ext) => ext.marshal(writer),
         }
     }
@@ -121,6 +153,16 @@ impl Extension {
             ExtensionValue::UseExtendedMasterSecret => Ok(Extension::UseExtendedMasterSecret(
                 ExtensionUseExtendedMasterSecret::unmarshal(reader)?,
             )),
+            ExtensionValue::SupportedVersions => Ok(Extension::SupportedVersions(
+                ExtensionSupportedVersions::unmarshal(reader)?,
+            )),
+            ExtensionValue::Cookie => Ok(Extension::Cookie(ExtensionCookie::unmarshal(reader)?)),
+            ExtensionValue::KeyShare => {
+                Ok(Extension::KeyShare(ExtensionKeyShare::unmarshal(reader)?))
+            }
+            ExtensionValue::ConnectionId => Ok(Extension::ConnectionId(
+                ExtensionConnectionId::unmarshal(reader)?,
+            )),
             ExtensionValue::RenegotiationInfo => Ok(Extension::RenegotiationInfo(
                 ExtensionRenegotiationInfo::unmarshal(reader)?,
             )),
@@ -128,3 +170,44 @@ impl Extension {
         }
     }
 }
+
+// unmarshal_extensions parses an extensions block without its length prefix.
+// Extensions this implementation does not know are skipped.
+pub(crate) fn unmarshal_extensions(buf: &[u8]) -> Result<Vec<Extension>> {
+    let mut extensions = vec![];
+
+    let mut offset = 0;
+    while offset < buf.len() {
+        if buf.len() - offset < 4 {
+            return Err(Error::ErrLengthMismatch);
+        }
+        let extension_len = u16::from_be_bytes([buf[offset + 2], buf[offset + 3]]) as usize;
+        if buf.len() - offset - 4 < extension_len {
+            return Err(Error::ErrLengthMismatch);
+        }
+
+        let mut extension_reader = &buf[offset..offset + 4 + extension_len];
+        if let Ok(extension) = Extension::unmarshal(&mut extension_reader) {
+            extensions.push(extension);
+        } else {
+            log::warn!(
+                "Unsupported Extension Type {} {}",
+                buf[offset],
+                buf[offset + 1]
+            );
+        }
+        offset += 4 + extension_len;
+    }
+
+    Ok(extensions)
+}
+
+// marshal_extensions writes an extensions block including its length prefix.
+pub(crate) fn marshal_extensions<W: Write>(extensions: &[Extension], writer: &mut W) -> Result<()> {
+    let extensions_len: usize = extensions.iter().map(|e| e.size()).sum();
+    writer.write_u16::<BigEndian>(extensions_len as u16)?;
+    for extension in extensions {
+        extension.marshal(writer)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,384 @@
+use std::io::BufWriter;
+use std::sync::atomic::Ordering;
+
+use super::*;
+use crate::cipher_suite::cipher_suite_tls_aes_128_gcm_sha256::*;
+use crate::cipher_suite::*;
+use crate::curve::named_curve::*;
+use crate::extension::extension_key_share::*;
+use crate::extension::extension_supported_versions::*;
+use crate::extension::*;
+use crate::handshake::handshake_message_client_hello::*;
+use crate::handshake::*;
+use crate::prf::key_schedule::*;
+use crate::prf::*;
+use crate::record_layer::record_layer_header::*;
+use crate::signature_hash_algorithm::*;
+
+/*
+  DTLS 1.3 replaces flights 2 to 6 of DTLS 1.2. The server always answers
+  the first ClientHello with a HelloRetryRequest carrying a cookie, so the
+  state is not committed before the client proved its address.
+  https://www.rfc-editor.org/rfc/rfc9147#section-5.8
+  Client                                          Server
+  ------                                          ------
+  ClientHello             -------->                           Flight 1
+
+                          <-------    HelloRetryRequest       Flight 2
+
+  ClientHello              -------->                          Flight 3
+
+                                             ServerHello    \
+                                   {EncryptedExtensions}     \
+                                   {CertificateRequest*}      \
+                                          {Certificate}        Flight 4
+                                    {CertificateVerify}       /
+                          <--------            {Finished}    /
+
+  {Certificate*}                                            \
+  {CertificateVerify*}                                       Flight 5
+  {Finished}              -------->                         /
+
+                          <--------                 [ACK]     Flight 6
+
+  {} messages are protected with the handshake traffic keys (epoch 2),
+  [] messages with the application traffic keys (epoch 3).
+*/
+
+pub(crate) const DTLS13_HANDSHAKE_EPOCH: u16 = 2;
+pub(crate) const DTLS13_APPLICATION_EPOCH: u16 = 3;
+
+// Key exchange groups usable with DTLS 1.3, in order of preference
+pub(crate) const DTLS13_NAMED_CURVES: [NamedCurve; 3] =
+    [NamedCurve::X25519, NamedCurve::P256, NamedCurve::P384];
+
+// Indexes into dtls13_transcript_rules
+pub(crate) const TRANSCRIPT_CLIENT_HELLO: usize = 0;
+pub(crate) const TRANSCRIPT_SERVER_HELLO: usize = 1;
+pub(crate) const TRANSCRIPT_SERVER_CERTIFICATE: usize = 4;
+pub(crate) const TRANSCRIPT_SERVER_CERTIFICATE_VERIFY: usize = 5;
+pub(crate) const TRANSCRIPT_SERVER_FINISHED: usize = 6;
+pub(crate) const TRANSCRIPT_CLIENT_CERTIFICATE: usize = 7;
+pub(crate) const TRANSCRIPT_CLIENT_CERTIFICATE_VERIFY: usize = 8;
+
+// dtls13_transcript_rules lists the handshake messages of a DTLS 1.3
+// handshake in transcript order.
+pub(crate) fn dtls13_transcript_rules(initial_epoch: u16) -> Vec<HandshakeCachePullRule> {
+    let epoch = initial_epoch + DTLS13_HANDSHAKE_EPOCH;
+    [
+        (HandshakeType::ClientHello, initial_epoch, true),
+        (HandshakeType::ServerHello, initial_epoch, false),
+        (HandshakeType::EncryptedExtensions, epoch, false),
+        (HandshakeType::CertificateRequest, epoch, false),
+        (HandshakeType::Certificate, epoch, false),
+        (HandshakeType::CertificateVerify, epoch, false),
+        (HandshakeType::Finished, epoch, false),
+        (HandshakeType::Certificate, epoch, true),
+        (HandshakeType::CertificateVerify, epoch, true),
+        (HandshakeType::Finished, epoch, true),
+    ]
+    .iter()
+    .map(|(typ, epoch, is_client)| HandshakeCachePullRule {
+        typ: *typ,
+        epoch: *epoch,
+        is_client: *is_client,
+        optional: true,
+    })
+    .collect()
+}
+
+// client_offers_dtls13 checks that the ClientHello allows to negotiate DTLS 1.3
+pub(crate) fn client_offers_dtls13(client_hello: &HandshakeMessageClientHello) -> bool {
+    let offers_version = client_hello.extensions.iter().any(|e| {
+        matches!(e, Extension::SupportedVersions(ExtensionSupportedVersions::Offered(versions))
+            if versions.contains(&PROTOCOL_VERSION1_3))
+    });
+
+    offers_version
+        && client_hello
+            .cipher_suites
+            .contains(&CipherSuiteId::Tls_Aes_128_Gcm_Sha256)
+}
+
+// selected_dtls13 checks that a ServerHello selected DTLS 1.3
+pub(crate) fn selected_dtls13(extensions: &[Extension]) -> bool {
+    extensions.iter().any(|e| {
+        matches!(
+            e,
+            Extension::SupportedVersions(ExtensionSupportedVersions::Selected(version))
+                if *version == PROTOCOL_VERSION1_3
+        )
+    })
+}
+
+// dtls13_client_hello_extensions returns the extensions a client adds to its
+// ClientHello to offer DTLS 1.3 along with DTLS 1.2.
+pub(crate) fn dtls13_client_hello_extensions(state: &State) -> Vec<Extension> {
+    let mut extensions = vec![Extension::SupportedVersions(
+        ExtensionSupportedVersions::Offered(vec![PROTOCOL_VERSION1_3, PROTOCOL_VERSION1_2]),
+    )];
+
+    if let Some(local_keypair) = &state.local_keypair {
+        extensions.push(Extension::KeyShare(ExtensionKeyShare::ClientShares(vec![
+            KeyShareEntry {
+                group: local_keypair.curve,
+                key_exchange: local_keypair.public_key.clone(),
+            },
+        ])));
+    }
+
+    extensions
+}
+
+// dtls13_client_hello_cipher_suites puts the DTLS 1.3 cipher suite in front
+// of the configured DTLS 1.2 ones.
+pub(crate) fn dtls13_client_hello_cipher_suites(cfg: &HandshakeConfig) -> Vec<CipherSuiteId> {
+    let mut cipher_suites = vec![CipherSuiteId::Tls_Aes_128_Gcm_Sha256];
+    cipher_suites.extend_from_slice(&cfg.local_cipher_suites);
+    cipher_suites
+}
+
+// handshake_transcript_form marshals a handshake message in the form it has in
+// the DTLS 1.3 transcript.
+pub(crate) fn handshake_transcript_form(
+    h: &Handshake,
+) -> Result<Vec<u8>, (Option<Alert>, Option<Error>)> {
+    let mut raw = vec![];
+    {
+        let mut writer = BufWriter::<&mut Vec<u8>>::new(raw.as_mut());
+        if let Err(err) = h.marshal(&mut writer) {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::InternalError,
+                }),
+                Some(err),
+            ));
+        }
+    }
+
+    Ok(dtls13_handshake_form(&raw))
+}
+
+// certificate_verify_content returns the content covered by the signature of
+// a DTLS 1.3 CertificateVerify.
+// https://www.rfc-editor.org/rfc/rfc8446#section-4.4.3
+pub(crate) fn certificate_verify_content(is_server: bool, transcript: &[u8]) -> Vec<u8> {
+    let context: &[u8] = if is_server {
+        b"TLS 1.3, server CertificateVerify"
+    } else {
+        b"TLS 1.3, client CertificateVerify"
+    };
+
+    let mut content = vec![0x20u8; 64];
+    content.extend_from_slice(context);
+    content.push(0);
+    content.extend_from_slice(&transcript_hash(transcript));
+    content
+}
+
+// key_share_secret runs the (EC)DHE exchange with the key share of the peer.
+pub(crate) fn key_share_secret(
+    state: &State,
+    peer_key_exchange: &[u8],
+) -> Result<Vec<u8>, (Option<Alert>, Option<Error>)> {
+    let local_keypair = match &state.local_keypair {
+        Some(local_keypair) => local_keypair,
+        None => {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::InternalError,
+                }),
+                Some(Error::ErrNoSupportedKeyShare),
+            ))
+        }
+    };
+
+    match prf_pre_master_secret(
+        peer_key_exchange,
+        &local_keypair.private_key,
+        local_keypair.curve,
+    ) {
+        Ok(secret) => Ok(secret),
+        Err(err) => Err((
+            Some(Alert {
+                alert_level: AlertLevel::Fatal,
+                alert_description: AlertDescription::IllegalParameter,
+            }),
+            Some(err),
+        )),
+    }
+}
+
+// derive_handshake_secrets computes the handshake traffic secrets from the
+// shared secret in pre_master_secret and the transcript up to the ServerHello.
+pub(crate) fn derive_handshake_secrets(
+    state: &mut State,
+    transcript: &[u8],
+) -> Result<(), (Option<Alert>, Option<Error>)> {
+    let result = (|| -> crate::error::Result<()> {
+        let hash = transcript_hash(transcript);
+        state.handshake_secret = handshake_secret(&state.pre_master_secret)?;
+        state.client_handshake_traffic_secret = derive_secret(
+            &state.handshake_secret,
+            CLIENT_HANDSHAKE_TRAFFIC_LABEL,
+            &hash,
+        )?;
+        state.server_handshake_traffic_secret = derive_secret(
+            &state.handshake_secret,
+            SERVER_HANDSHAKE_TRAFFIC_LABEL,
+            &hash,
+        )?;
+        Ok(())
+    })();
+
+    result.map_err(|err| {
+        (
+            Some(Alert {
+                alert_level: AlertLevel::Fatal,
+                alert_description: AlertDescription::InternalError,
+            }),
+            Some(err),
+        )
+    })
+}
+
+// derive_application_secrets computes the application traffic secrets and the
+// exporter master secret from the transcript up to the server Finished.
+pub(crate) fn derive_application_secrets(
+    state: &mut State,
+    transcript: &[u8],
+) -> Result<(), (Option<Alert>, Option<Error>)> {
+    let result = (|| -> crate::error::Result<()> {
+        let hash = transcript_hash(transcript);
+        let master_secret = master_secret(&state.handshake_secret)?;
+        state.client_application_traffic_secret =
+            derive_secret(&master_secret, CLIENT_APPLICATION_TRAFFIC_LABEL, &hash)?;
+        state.server_application_traffic_secret =
+            derive_secret(&master_secret, SERVER_APPLICATION_TRAFFIC_LABEL, &hash)?;
+        state.master_secret = derive_secret(&master_secret, EXPORTER_MASTER_LABEL, &hash)?;
+        Ok(())
+    })();
+
+    result.map_err(|err| {
+        (
+            Some(Alert {
+                alert_level: AlertLevel::Fatal,
+                alert_description: AlertDescription::InternalError,
+            }),
+            Some(err),
+        )
+    })
+}
+
+// finished_verify_data_for computes the verify_data of a Finished message
+// sent with the given handshake traffic secret.
+pub(crate) fn finished_verify_data_for(
+    traffic_secret: &[u8],
+    transcript: &[u8],
+) -> Result<Vec<u8>, (Option<Alert>, Option<Error>)> {
+    match finished_verify_data(traffic_secret, &transcript_hash(transcript)) {
+        Ok(verify_data) => Ok(verify_data),
+        Err(err) => Err((
+            Some(Alert {
+                alert_level: AlertLevel::Fatal,
+                alert_description: AlertDescription::InternalError,
+            }),
+            Some(err),
+        )),
+    }
+}
+
+// set_traffic_secret installs the keys of an epoch, selecting the DTLS 1.3
+// cipher suite if it is not in use yet.
+pub(crate) async fn set_traffic_secret(
+    state: &mut State,
+    epoch: u16,
+    traffic_secret: &[u8],
+    is_local: bool,
+) -> Result<(), (Option<Alert>, Option<Error>)> {
+    let mut cipher_suite = state.cipher_suite.lock().await;
+    if !cipher_suite.as_ref().map_or(false, |cs| cs.is_dtls13()) {
+        *cipher_suite = Some(Box::<CipherSuiteTlsAes128GcmSha256>::default());
+    }
+
+    if let Some(cipher_suite) = &mut *cipher_suite {
+        if let Err(err) = cipher_suite.set_traffic_secret(epoch, traffic_secret, is_local) {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::InternalError,
+                }),
+                Some(err),
+            ));
+        }
+    }
+
+    if !is_local {
+        state.remote_epoch.store(epoch, Ordering::SeqCst);
+    }
+
+    Ok(())
+}
+
+// server_select_dtls13 decides whether DTLS 1.3 is negotiated for the
+// ClientHello. A server that could have negotiated DTLS 1.3 with the client
+// but falls back to DTLS 1.2 marks its random, so the client can detect a
+// downgrade.
+// https://www.rfc-editor.org/rfc/rfc8446#section-4.1.3
+pub(crate) fn server_select_dtls13(
+    state: &mut State,
+    cfg: &HandshakeConfig,
+    client_hello: &HandshakeMessageClientHello,
+) -> bool {
+    if !cfg.dtls13_enabled() {
+        return false;
+    }
+
+    let certificate_usable = match cfg.get_certificate(&cfg.server_name) {
+        Ok(cert) => {
+            select_signature_scheme_dtls13(&cfg.local_signature_schemes, &cert.private_key).is_ok()
+        }
+        Err(_) => false,
+    };
+    if !certificate_usable {
+        return false;
+    }
+
+    if !client_offers_dtls13(client_hello) {
+        state.local_random.set_downgrade_sentinel();
+        return false;
+    }
+
+    true
+}
+
+// select_dtls13_named_curve picks the key exchange group, preferring one the
+// client already sent a key share for.
+pub(crate) fn select_dtls13_named_curve(
+    client_hello: &HandshakeMessageClientHello,
+) -> Option<NamedCurve> {
+    for extension in &client_hello.extensions {
+        if let Extension::KeyShare(ExtensionKeyShare::ClientShares(shares)) = extension {
+            if let Some(share) = shares
+                .iter()
+                .find(|share| DTLS13_NAMED_CURVES.contains(&share.group))
+            {
+                return Some(share.group);
+            }
+        }
+    }
+
+    for extension in &client_hello.extensions {
+        if let Extension::SupportedEllipticCurves(e) = extension {
+            return e
+                .elliptic_curves
+                .iter()
+                .find(|curve| DTLS13_NAMED_CURVES.contains(curve))
+                .copied();
+        }
+    }
+
+    None
+}
@@ -4,8 +4,12 @@ use std::sync::atomic::Ordering;
 use async_trait::async_trait;
 use rand::Rng;
 
+use super::dtls13::*;
 use super::flight2::*;
+use super::flight2dtls13::*;
 use super::*;
+use crate::cipher_suite::cipher_suite_tls_aes_128_gcm_sha256::*;
+use crate::cipher_suite::supports_connection_id;
 use crate::config::*;
 use crate::conn::*;
 use crate::error::Error;
@@ -77,7 +81,16 @@ impl Flight for Flight0 {
 
             state.remote_random = client_hello.random.clone();
 
-            if let Ok(id) =
+            let dtls13 = server_select_dtls13(state, cfg, client_hello);
+            if dtls13 {
+                log::debug!(
+                    "[handshake:{}] use DTLS 1.3 with cipher suite: {}",
+                    srv_cli_str(state.is_client),
+                    CipherSuiteId::Tls_Aes_128_Gcm_Sha256
+                );
+                let mut cs = state.cipher_suite.lock().await;
+                *cs = Some(Box::<CipherSuiteTlsAes128GcmSha256>::default());
+            } else if let Ok(id) =
                 find_matching_cipher_suite(&client_hello.cipher_suites, &cfg.local_cipher_suites)
             {
                 if let Ok(cipher_suite) = cipher_suite_for_id(id) {
@@ -99,8 +112,12 @@ impl Flight for Flight0 {
                 ));
             }
 
+            let mut remote_connection_id = None;
             for extension in &client_hello.extensions {
                 match extension {
+                    Extension::ConnectionId(e) => {
+                        remote_connection_id = Some(e.connection_id.clone());
+                    }
                     Extension::SupportedEllipticCurves(e) => {
                         if e.elliptic_curves.is_empty() {
                             return Err((
@@ -141,6 +158,43 @@ impl Flight for Flight0 {
                 }
             }
 
+            if dtls13 {
+                state.named_curve = match select_dtls13_named_curve(client_hello) {
+                    Some(named_curve) => named_curve,
+                    None => {
+                        return Err((
+                            Some(Alert {
+                                alert_level: AlertLevel::Fatal,
+                                alert_description: AlertDescription::HandshakeFailure,
+                            }),
+                            Some(Error::ErrNoSupportedKeyShare),
+                        ))
+                    }
+                };
+
+                return Ok(Box::new(Flight2Dtls13 {}));
+            }
+
+            // An offered connection ID is answered with the one we want to
+            // receive if the cipher suite can carry it.
+            if let (Some(connection_id), Some(generator)) =
+                (remote_connection_id, &cfg.connection_id_generator)
+            {
+                let supported = state
+                    .cipher_suite
+                    .lock()
+                    .await
+                    .as_ref()
+                    .map_or(false, |cs| supports_connection_id(cs.id()));
+                if supported {
+                    let mut connection_id_state = state.connection_id_state.lock().await;
+                    connection_id_state
+                        .local_connection_id
+                        .get_or_insert_with(|| generator());
+                    connection_id_state.remote_connection_id = Some(connection_id);
+                }
+            }
+
             if cfg.extended_master_secret == ExtendedMasterSecretType::Require
                 && !state.extended_master_secret
             {
@@ -3,14 +3,19 @@ use std::sync::atomic::Ordering;
 
 use async_trait::async_trait;
 
+use super::dtls13::*;
 use super::flight3::*;
+use super::flight3dtls13::*;
 use super::*;
+use crate::cipher_suite::*;
 use crate::compression_methods::*;
 use crate::config::*;
 use crate::conn::*;
 use crate::content::*;
 use crate::curve::named_curve::*;
 use crate::error::Error;
+use crate::extension::extension_connection_id::*;
+use crate::extension::extension_key_share::*;
 use crate::extension::extension_server_name::*;
 use crate::extension::extension_supported_elliptic_curves::*;
 use crate::extension::extension_supported_point_formats::*;
@@ -20,6 +25,7 @@ use crate::extension::extension_use_srtp::*;
 use crate::extension::renegotiation_info::ExtensionRenegotiationInfo;
 use crate::extension::*;
 use crate::handshake::handshake_message_client_hello::*;
+use crate::handshake::handshake_message_server_hello::*;
 use crate::handshake::*;
 use crate::record_layer::record_layer_header::*;
 use crate::record_layer::*;
@@ -69,6 +75,18 @@ impl Flight for Flight1 {
             Err(_) => return Err((None, None)),
         };
 
+        if let Some(HandshakeMessage::ServerHello(h)) = msgs.get(&HandshakeType::ServerHello) {
+            if cfg.dtls13_enabled() && h.random.is_hello_retry_request() {
+                handle_hello_retry_request(state, h)?;
+                state.handshake_recv_sequence = seq;
+                return Ok(Box::new(Flight3Dtls13 {}));
+            }
+            if cfg.dtls13_enabled() && selected_dtls13(&h.extensions) {
+                // The server did not ask for a cookie.
+                return Flight3Dtls13 {}.parse(tx, state, cache, cfg).await;
+            }
+        }
+
         if msgs.contains_key(&HandshakeType::ServerHello) {
             // Flight1 and flight2 were skipped.
             // Parse as flight3.
@@ -130,44 +148,26 @@ impl Flight for Flight1 {
         state.cookie = vec![];
         state.local_random.populate();
 
-        let mut extensions = vec![
-            Extension::SupportedSignatureAlgorithms(ExtensionSupportedSignatureAlgorithms {
-                signature_hash_algorithms: cfg.local_signature_schemes.clone(),
-            }),
-            Extension::RenegotiationInfo(ExtensionRenegotiationInfo {
-                renegotiated_connection: 0,
-            }),
-        ];
-
-        if cfg.local_psk_callback.is_none() {
-            extensions.extend_from_slice(&[
-                Extension::SupportedEllipticCurves(ExtensionSupportedEllipticCurves {
-                    elliptic_curves: vec![NamedCurve::P256, NamedCurve::X25519, NamedCurve::P384],
-                }),
-                Extension::SupportedPointFormats(ExtensionSupportedPointFormats {
-                    point_formats: vec![ELLIPTIC_CURVE_POINT_FORMAT_UNCOMPRESSED],
-                }),
-            ]);
-        }
-
-        if !cfg.local_srtp_protection_profiles.is_empty() {
-            extensions.push(Extension::UseSrtp(ExtensionUseSrtp {
-                protection_profiles: cfg.local_srtp_protection_profiles.clone(),
-            }));
-        }
-
-        if cfg.extended_master_secret == ExtendedMasterSecretType::Request
-            || cfg.extended_master_secret == ExtendedMasterSecretType::Require
-        {
-            extensions.push(Extension::UseExtendedMasterSecret(
-                ExtensionUseExtendedMasterSecret { supported: true },
-            ));
-        }
+        state.connection_id_state.lock().await.local_connection_id = None;
 
-        if !cfg.server_name.is_empty() {
-            extensions.push(Extension::ServerName(ExtensionServerName {
-                server_name: cfg.server_name.clone(),
-            }));
+        let mut extensions = client_hello_extensions(cfg);
+        extensions.extend(client_hello_connection_id_extension(state, cfg).await);
+        let mut cipher_suites = cfg.local_cipher_suites.clone();
+        if cfg.dtls13_enabled() {
+            state.local_keypair = match DEFAULT_NAMED_CURVE.generate_keypair() {
+                Ok(local_keypair) => Some(local_keypair),
+                Err(err) => {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::InternalError,
+                        }),
+                        Some(err),
+                    ))
+                }
+            };
+            extensions.extend(dtls13_client_hello_extensions(state));
+            cipher_suites = dtls13_client_hello_cipher_suites(cfg);
         }
 
         Ok(vec![Packet {
@@ -180,7 +180,7 @@ impl Flight for Flight1 {
                         random: state.local_random.clone(),
                         cookie: state.cookie.clone(),
 
-                        cipher_suites: cfg.local_cipher_suites.clone(),
+                        cipher_suites,
                         compression_methods: default_compression_methods(),
                         extensions,
                     },
@@ -191,3 +191,128 @@ impl Flight for Flight1 {
         }])
     }
 }
+
+// client_hello_extensions returns the extensions of a ClientHello offering DTLS 1.2.
+pub(crate) fn client_hello_extensions(cfg: &HandshakeConfig) -> Vec<Extension> {
+    let mut extensions = vec![
+        Extension::SupportedSignatureAlgorithms(ExtensionSupportedSignatureAlgorithms {
+            signature_hash_algorithms: cfg.local_signature_schemes.clone(),
+        }),
+        Extension::RenegotiationInfo(ExtensionRenegotiationInfo {
+            renegotiated_connection: 0,
+        }),
+    ];
+
+    if cfg.local_psk_callback.is_none() {
+        extensions.extend_from_slice(&[
+            Extension::SupportedEllipticCurves(ExtensionSupportedEllipticCurves {
+                elliptic_curves: vec![NamedCurve::P256, NamedCurve::X25519, NamedCurve::P384],
+            }),
+            Extension::SupportedPointFormats(ExtensionSupportedPointFormats {
+                point_formats: vec![ELLIPTIC_CURVE_POINT_FORMAT_UNCOMPRESSED],
+            }),
+        ]);
+    }
+
+    if !cfg.local_srtp_protection_profiles.is_empty() {
+        extensions.push(Extension::UseSrtp(ExtensionUseSrtp {
+            protection_profiles: cfg.local_srtp_protection_profiles.clone(),
+        }));
+    }
+
+    if cfg.extended_master_secret == ExtendedMasterSecretType::Request
+        || cfg.extended_master_secret == ExtendedMasterSecretType::Require
+    {
+        extensions.push(Extension::UseExtendedMasterSecret(
+            ExtensionUseExtendedMasterSecret { supported: true },
+        ));
+    }
+
+    if !cfg.server_name.is_empty() {
+        extensions.push(Extension::ServerName(ExtensionServerName {
+            server_name: cfg.server_name.clone(),
+        }));
+    }
+
+    extensions
+}
+
+// client_hello_connection_id_extension returns the connection_id extension
+// offering the connection ID we want to receive, it is generated once for the
+// first ClientHello.
+pub(crate) async fn client_hello_connection_id_extension(
+    state: &State,
+    cfg: &HandshakeConfig,
+) -> Option<Extension> {
+    let generator = cfg.connection_id_generator.as_ref()?;
+    let mut connection_id_state = state.connection_id_state.lock().await;
+    let connection_id = connection_id_state
+        .local_connection_id
+        .get_or_insert_with(|| generator())
+        .clone();
+    Some(Extension::ConnectionId(ExtensionConnectionId {
+        connection_id,
+    }))
+}
+
+// handle_hello_retry_request validates a HelloRetryRequest and prepares the
+// second ClientHello with its cookie and, if asked for, a key share of
+// another group.
+// https://www.rfc-editor.org/rfc/rfc8446#section-4.1.4
+fn handle_hello_retry_request(
+    state: &mut State,
+    h: &HandshakeMessageServerHello,
+) -> Result<(), (Option<Alert>, Option<Error>)> {
+    let mut cookie = None;
+    let mut selected_group = None;
+    for extension in &h.extensions {
+        match extension {
+            Extension::Cookie(e) => cookie = Some(e.cookie.clone()),
+            Extension::KeyShare(ExtensionKeyShare::SelectedGroup(group)) => {
+                selected_group = Some(*group)
+            }
+            _ => {}
+        }
+    }
+
+    let local_curve = state.local_keypair.as_ref().map(|k| k.curve);
+    let valid_group = selected_group.map_or(true, |group| {
+        DTLS13_NAMED_CURVES.contains(&group) && Some(group) != local_curve
+    });
+    let cookie = match cookie {
+        Some(cookie)
+            if selected_dtls13(&h.extensions)
+                && h.cipher_suite == CipherSuiteId::Tls_Aes_128_Gcm_Sha256
+                && valid_group =>
+        {
+            cookie
+        }
+        _ => {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::IllegalParameter,
+                }),
+                Some(Error::ErrInvalidHelloRetryRequest),
+            ))
+        }
+    };
+
+    if let Some(group) = selected_group {
+        state.local_keypair = match group.generate_keypair() {
+            Ok(local_keypair) => Some(local_keypair),
+            Err(err) => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::InternalError,
+                    }),
+                    Some(err),
+                ))
+            }
+        };
+    }
+    state.cookie = cookie;
+
+    Ok(())
+}
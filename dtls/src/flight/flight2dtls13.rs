@@ -0,0 +1,220 @@
+use std::fmt;
+
+use async_trait::async_trait;
+
+use super::dtls13::*;
+use super::flight0::*;
+use super::flight4dtls13::*;
+use super::*;
+use crate::cipher_suite::*;
+use crate::compression_methods::*;
+use crate::content::*;
+use crate::error::Error;
+use crate::extension::extension_cookie::*;
+use crate::extension::extension_key_share::*;
+use crate::extension::extension_supported_versions::*;
+use crate::extension::*;
+use crate::handshake::handshake_message_server_hello::*;
+use crate::handshake::handshake_random::*;
+use crate::handshake::*;
+use crate::record_layer::record_layer_header::*;
+
+// Flight2Dtls13 sends a HelloRetryRequest with a cookie, asking for a key
+// share of the selected group if the client did not send one.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Flight2Dtls13;
+
+impl fmt::Display for Flight2Dtls13 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Flight 2 (DTLS 1.3)")
+    }
+}
+
+#[async_trait]
+impl Flight for Flight2Dtls13 {
+    fn has_retransmit(&self) -> bool {
+        false
+    }
+
+    async fn parse(
+        &self,
+        tx: &mut mpsc::Sender<mpsc::Sender<()>>,
+        state: &mut State,
+        cache: &HandshakeCache,
+        cfg: &HandshakeConfig,
+    ) -> Result<Box<dyn Flight + Send + Sync>, (Option<Alert>, Option<Error>)> {
+        let (seq, msgs) = match cache
+            .full_pull_map(
+                state.handshake_recv_sequence,
+                &[HandshakeCachePullRule {
+                    typ: HandshakeType::ClientHello,
+                    epoch: cfg.initial_epoch,
+                    is_client: true,
+                    optional: false,
+                }],
+            )
+            .await
+        {
+            Ok((seq, msgs)) => (seq, msgs),
+
+            // Client may retransmit the first ClientHello when HelloRetryRequest is dropped.
+            // Parse as flight 0 in this case.
+            Err(_) => return Flight0 {}.parse(tx, state, cache, cfg).await,
+        };
+
+        let client_hello = match msgs.get(&HandshakeType::ClientHello) {
+            Some(HandshakeMessage::ClientHello(client_hello)) => client_hello,
+            _ => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::InternalError,
+                    }),
+                    None,
+                ))
+            }
+        };
+
+        let cookie = client_hello.extensions.iter().find_map(|e| match e {
+            Extension::Cookie(e) => Some(&e.cookie),
+            _ => None,
+        });
+        match cookie {
+            None => return Err((None, None)),
+            Some(cookie) if *cookie != state.cookie => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::AccessDenied,
+                    }),
+                    Some(Error::ErrCookieMismatch),
+                ))
+            }
+            _ => {}
+        }
+
+        // The legacy cookie of DTLS 1.2 must be empty in a DTLS 1.3 ClientHello.
+        // https://www.rfc-editor.org/rfc/rfc9147#section-5.3
+        if !client_offers_dtls13(client_hello) || !client_hello.cookie.is_empty() {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::IllegalParameter,
+                }),
+                Some(Error::ErrInvalidHelloRetryRequest),
+            ));
+        }
+
+        let key_share = client_hello.extensions.iter().find_map(|e| match e {
+            Extension::KeyShare(ExtensionKeyShare::ClientShares(shares)) => shares
+                .iter()
+                .find(|share| share.group == state.named_curve)
+                .cloned(),
+            _ => None,
+        });
+        let key_share = match key_share {
+            Some(key_share) => key_share,
+            None => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::IllegalParameter,
+                    }),
+                    Some(Error::ErrNoSupportedKeyShare),
+                ))
+            }
+        };
+
+        state.handshake_recv_sequence = seq;
+        state.remote_random = client_hello.random.clone();
+
+        state.local_keypair = match state.named_curve.generate_keypair() {
+            Ok(local_keypair) => Some(local_keypair),
+            Err(err) => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::IllegalParameter,
+                    }),
+                    Some(err),
+                ))
+            }
+        };
+        state.pre_master_secret = key_share_secret(state, &key_share.key_exchange)?;
+
+        Ok(Box::new(Flight4Dtls13 {}))
+    }
+
+    async fn generate(
+        &self,
+        state: &mut State,
+        cache: &HandshakeCache,
+        cfg: &HandshakeConfig,
+    ) -> Result<Vec<Packet>, (Option<Alert>, Option<Error>)> {
+        state.handshake_send_sequence = 0;
+
+        let (_, msgs) = match cache
+            .full_pull_map(
+                0,
+                &[HandshakeCachePullRule {
+                    typ: HandshakeType::ClientHello,
+                    epoch: cfg.initial_epoch,
+                    is_client: true,
+                    optional: false,
+                }],
+            )
+            .await
+        {
+            Ok((seq, msgs)) => (seq, msgs),
+            Err(err) => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::InternalError,
+                    }),
+                    Some(err),
+                ))
+            }
+        };
+        let has_key_share = match msgs.get(&HandshakeType::ClientHello) {
+            Some(HandshakeMessage::ClientHello(client_hello)) => {
+                client_hello.extensions.iter().any(|e| {
+                    matches!(e, Extension::KeyShare(ExtensionKeyShare::ClientShares(shares))
+                        if shares.iter().any(|share| share.group == state.named_curve))
+                })
+            }
+            _ => false,
+        };
+
+        let mut extensions = vec![
+            Extension::SupportedVersions(ExtensionSupportedVersions::Selected(PROTOCOL_VERSION1_3)),
+            Extension::Cookie(ExtensionCookie {
+                cookie: state.cookie.clone(),
+            }),
+        ];
+
+        if !has_key_share {
+            extensions.push(Extension::KeyShare(ExtensionKeyShare::SelectedGroup(
+                state.named_curve,
+            )));
+        }
+
+        Ok(vec![Packet {
+            record: RecordLayer::new(
+                PROTOCOL_VERSION1_2,
+                0,
+                Content::Handshake(Handshake::new(HandshakeMessage::ServerHello(
+                    HandshakeMessageServerHello {
+                        version: PROTOCOL_VERSION1_2,
+                        random: HandshakeRandom::hello_retry_request(),
+                        cipher_suite: CipherSuiteId::Tls_Aes_128_Gcm_Sha256,
+                        compression_method: default_compression_methods().ids[0],
+                        extensions,
+                    },
+                ))),
+            ),
+            should_encrypt: false,
+            reset_local_sequence_number: false,
+        }])
+    }
+}
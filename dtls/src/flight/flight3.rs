@@ -3,21 +3,16 @@ use std::fmt;
 use async_trait::async_trait;
 use log::*;
 
+use super::dtls13::*;
+use super::flight1::*;
 use super::flight5::*;
 use super::*;
-use crate::cipher_suite::cipher_suite_for_id;
+use crate::cipher_suite::{cipher_suite_for_id, supports_connection_id};
 use crate::compression_methods::*;
 use crate::config::*;
 use crate::content::*;
-use crate::curve::named_curve::*;
 use crate::error::Error;
-use crate::extension::extension_server_name::*;
-use crate::extension::extension_supported_elliptic_curves::*;
-use crate::extension::extension_supported_point_formats::*;
-use crate::extension::extension_supported_signature_algorithms::*;
-use crate::extension::extension_use_extended_master_secret::*;
 use crate::extension::extension_use_srtp::*;
-use crate::extension::renegotiation_info::ExtensionRenegotiationInfo;
 use crate::extension::*;
 use crate::handshake::handshake_message_client_hello::*;
 use crate::handshake::handshake_message_server_key_exchange::*;
@@ -191,8 +186,25 @@ impl Flight for Flight3 {
                 ));
             }
 
+            // A client offering DTLS 1.3 must abort when a server able to
+            // negotiate it answers with DTLS 1.2.
+            // https://www.rfc-editor.org/rfc/rfc8446#section-4.1.3
+            if cfg.dtls13_enabled() && h.random.has_downgrade_sentinel() {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::IllegalParameter,
+                    }),
+                    Some(Error::ErrProtocolVersionDowngrade),
+                ));
+            }
+
+            let mut remote_connection_id = None;
             for extension in &h.extensions {
                 match extension {
+                    Extension::ConnectionId(e) => {
+                        remote_connection_id = Some(e.connection_id.clone())
+                    }
                     Extension::UseSrtp(e) => {
                         let profile = match find_matching_srtp_profile(
                             &e.protection_profiles,
@@ -286,6 +298,30 @@ impl Flight for Flight3 {
                 let mut cs = state.cipher_suite.lock().await;
                 *cs = Some(cipher_suite);
             }
+
+            // The server may only send a connection ID if we offered one, and
+            // we stop expecting ours if it did not answer.
+            {
+                let mut connection_id_state = state.connection_id_state.lock().await;
+                match remote_connection_id {
+                    Some(connection_id)
+                        if connection_id_state.local_connection_id.is_some()
+                            && supports_connection_id(h.cipher_suite) =>
+                    {
+                        connection_id_state.remote_connection_id = Some(connection_id);
+                    }
+                    Some(_) => {
+                        return Err((
+                            Some(Alert {
+                                alert_level: AlertLevel::Fatal,
+                                alert_description: AlertDescription::UnsupportedExtension,
+                            }),
+                            Some(Error::ErrUnexpectedConnectionId),
+                        ))
+                    }
+                    None => connection_id_state.local_connection_id = None,
+                }
+            }
             state.remote_random = h.random.clone();
         }
 
@@ -349,44 +385,12 @@ impl Flight for Flight3 {
         _cache: &HandshakeCache,
         cfg: &HandshakeConfig,
     ) -> Result<Vec<Packet>, (Option<Alert>, Option<Error>)> {
-        let mut extensions = vec![
-            Extension::SupportedSignatureAlgorithms(ExtensionSupportedSignatureAlgorithms {
-                signature_hash_algorithms: cfg.local_signature_schemes.clone(),
-            }),
-            Extension::RenegotiationInfo(ExtensionRenegotiationInfo {
-                renegotiated_connection: 0,
-            }),
-        ];
-
-        if cfg.local_psk_callback.is_none() {
-            extensions.extend_from_slice(&[
-                Extension::SupportedEllipticCurves(ExtensionSupportedEllipticCurves {
-                    elliptic_curves: vec![NamedCurve::P256, NamedCurve::X25519, NamedCurve::P384],
-                }),
-                Extension::SupportedPointFormats(ExtensionSupportedPointFormats {
-                    point_formats: vec![ELLIPTIC_CURVE_POINT_FORMAT_UNCOMPRESSED],
-                }),
-            ]);
-        }
-
-        if !cfg.local_srtp_protection_profiles.is_empty() {
-            extensions.push(Extension::UseSrtp(ExtensionUseSrtp {
-                protection_profiles: cfg.local_srtp_protection_profiles.clone(),
-            }));
-        }
-
-        if cfg.extended_master_secret == ExtendedMasterSecretType::Request
-            || cfg.extended_master_secret == ExtendedMasterSecretType::Require
-        {
-            extensions.push(Extension::UseExtendedMasterSecret(
-                ExtensionUseExtendedMasterSecret { supported: true },
-            ));
-        }
-
-        if !cfg.server_name.is_empty() {
-            extensions.push(Extension::ServerName(ExtensionServerName {
-                server_name: cfg.server_name.clone(),
-            }));
+        let mut extensions = client_hello_extensions(cfg);
+        extensions.extend(client_hello_connection_id_extension(state, cfg).await);
+        let mut cipher_suites = cfg.local_cipher_suites.clone();
+        if cfg.dtls13_enabled() {
+            extensions.extend(dtls13_client_hello_extensions(state));
+            cipher_suites = dtls13_client_hello_cipher_suites(cfg);
         }
 
         Ok(vec![Packet {
@@ -399,7 +403,7 @@ impl Flight for Flight3 {
                         random: state.local_random.clone(),
                         cookie: state.cookie.clone(),
 
-                        cipher_suites: cfg.local_cipher_suites.clone(),
+                        cipher_suites,
                         compression_methods: default_compression_methods(),
                         extensions,
                     },
@@ -0,0 +1,463 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use log::*;
+
+use super::dtls13::*;
+use super::flight1::*;
+use super::flight5dtls13::*;
+use super::*;
+use crate::cipher_suite::*;
+use crate::compression_methods::*;
+use crate::content::*;
+use crate::crypto::*;
+use crate::error::Error;
+use crate::extension::extension_cookie::*;
+use crate::extension::extension_key_share::*;
+use crate::extension::extension_use_srtp::*;
+use crate::extension::*;
+use crate::find_matching_srtp_profile;
+use crate::handshake::handshake_message_client_hello::*;
+use crate::handshake::*;
+use crate::record_layer::record_layer_header::*;
+use crate::record_layer::*;
+
+// Flight3Dtls13 answers a HelloRetryRequest with a second ClientHello and
+// processes the ServerHello and the encrypted messages of the server.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Flight3Dtls13;
+
+impl fmt::Display for Flight3Dtls13 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Flight 3 (DTLS 1.3)")
+    }
+}
+
+#[async_trait]
+impl Flight for Flight3Dtls13 {
+    async fn parse(
+        &self,
+        tx: &mut mpsc::Sender<mpsc::Sender<()>>,
+        state: &mut State,
+        cache: &HandshakeCache,
+        cfg: &HandshakeConfig,
+    ) -> Result<Box<dyn Flight + Send + Sync>, (Option<Alert>, Option<Error>)> {
+        let (seq, msgs) = match cache
+            .full_pull_map(
+                state.handshake_recv_sequence,
+                &[HandshakeCachePullRule {
+                    typ: HandshakeType::ServerHello,
+                    epoch: cfg.initial_epoch,
+                    is_client: false,
+                    optional: false,
+                }],
+            )
+            .await
+        {
+            Ok((seq, msgs)) => (seq, msgs),
+            // No valid message received. Keep reading
+            Err(_) => return Err((None, None)),
+        };
+
+        let h = match msgs.get(&HandshakeType::ServerHello) {
+            Some(HandshakeMessage::ServerHello(h)) => h,
+            _ => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::InternalError,
+                    }),
+                    None,
+                ))
+            }
+        };
+
+        // A second HelloRetryRequest must abort the handshake.
+        // https://www.rfc-editor.org/rfc/rfc8446#section-4.1.4
+        if h.random.is_hello_retry_request() {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::UnexpectedMessage,
+                }),
+                Some(Error::ErrInvalidHelloRetryRequest),
+            ));
+        }
+
+        if !selected_dtls13(&h.extensions) {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::ProtocolVersion,
+                }),
+                Some(Error::ErrUnsupportedProtocolVersion),
+            ));
+        }
+
+        // Connection IDs are not negotiated for DTLS 1.3.
+        state.connection_id_state.lock().await.local_connection_id = None;
+
+        if h.cipher_suite != CipherSuiteId::Tls_Aes_128_Gcm_Sha256 {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::InsufficientSecurity,
+                }),
+                Some(Error::ErrCipherSuiteNoIntersection),
+            ));
+        }
+
+        let local_curve = state.local_keypair.as_ref().map(|k| k.curve);
+        let key_share = h.extensions.iter().find_map(|e| match e {
+            Extension::KeyShare(ExtensionKeyShare::ServerShare(share))
+                if Some(share.group) == local_curve =>
+            {
+                Some(share.key_exchange.clone())
+            }
+            _ => None,
+        });
+        let key_share = match key_share {
+            Some(key_share) => key_share,
+            None => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::IllegalParameter,
+                    }),
+                    Some(Error::ErrNoSupportedKeyShare),
+                ))
+            }
+        };
+
+        state.remote_random = h.random.clone();
+
+        let handshake_epoch = cfg.initial_epoch + DTLS13_HANDSHAKE_EPOCH;
+        let rules = dtls13_transcript_rules(cfg.initial_epoch);
+
+        if state.client_handshake_traffic_secret.is_empty() {
+            trace!(
+                "[handshake:{}] use cipher suite: {}",
+                srv_cli_str(state.is_client),
+                h.cipher_suite
+            );
+
+            state.pre_master_secret = key_share_secret(state, &key_share)?;
+            let transcript = cache
+                .dtls13_transcript(&rules[..=TRANSCRIPT_SERVER_HELLO])
+                .await;
+            derive_handshake_secrets(state, &transcript)?;
+
+            let client_handshake_traffic_secret = state.client_handshake_traffic_secret.clone();
+            let server_handshake_traffic_secret = state.server_handshake_traffic_secret.clone();
+            set_traffic_secret(
+                state,
+                handshake_epoch,
+                &client_handshake_traffic_secret,
+                true,
+            )
+            .await?;
+            set_traffic_secret(
+                state,
+                handshake_epoch,
+                &server_handshake_traffic_secret,
+                false,
+            )
+            .await?;
+        }
+
+        // Now, encrypted packets can be handled
+        let (done_tx, mut done_rx) = mpsc::channel(1);
+        if let Err(err) = tx.send(done_tx).await {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::InternalError,
+                }),
+                Some(Error::Other(err.to_string())),
+            ));
+        }
+
+        done_rx.recv().await;
+
+        let (seq, msgs) = match cache
+            .full_pull_map_dtls13(
+                seq,
+                &[
+                    HandshakeCachePullRule {
+                        typ: HandshakeType::EncryptedExtensions,
+                        epoch: handshake_epoch,
+                        is_client: false,
+                        optional: false,
+                    },
+                    HandshakeCachePullRule {
+                        typ: HandshakeType::CertificateRequest,
+                        epoch: handshake_epoch,
+                        is_client: false,
+                        optional: true,
+                    },
+                    HandshakeCachePullRule {
+                        typ: HandshakeType::Certificate,
+                        epoch: handshake_epoch,
+                        is_client: false,
+                        optional: false,
+                    },
+                    HandshakeCachePullRule {
+                        typ: HandshakeType::CertificateVerify,
+                        epoch: handshake_epoch,
+                        is_client: false,
+                        optional: false,
+                    },
+                    HandshakeCachePullRule {
+                        typ: HandshakeType::Finished,
+                        epoch: handshake_epoch,
+                        is_client: false,
+                        optional: false,
+                    },
+                ],
+            )
+            .await
+        {
+            Ok((seq, msgs)) => (seq, msgs),
+            // No valid message received. Keep reading
+            Err(_) => return Err((None, None)),
+        };
+
+        if let Some(HandshakeMessage::EncryptedExtensions(h)) =
+            msgs.get(&HandshakeType::EncryptedExtensions)
+        {
+            for extension in &h.extensions {
+                if let Extension::UseSrtp(e) = extension {
+                    let profile = match find_matching_srtp_profile(
+                        &e.protection_profiles,
+                        &cfg.local_srtp_protection_profiles,
+                    ) {
+                        Ok(profile) => profile,
+                        Err(_) => {
+                            return Err((
+                                Some(Alert {
+                                    alert_level: AlertLevel::Fatal,
+                                    alert_description: AlertDescription::IllegalParameter,
+                                }),
+                                Some(Error::ErrClientNoMatchingSrtpProfile),
+                            ))
+                        }
+                    };
+                    state.srtp_protection_profile = profile;
+                }
+            }
+        }
+        if !cfg.local_srtp_protection_profiles.is_empty()
+            && state.srtp_protection_profile == SrtpProtectionProfile::Unsupported
+        {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::InsufficientSecurity,
+                }),
+                Some(Error::ErrRequestedButNoSrtpExtension),
+            ));
+        }
+
+        if let Some(message) = msgs.get(&HandshakeType::CertificateRequest) {
+            match message {
+                HandshakeMessage::CertificateRequestDtls13(_) => {}
+                _ => {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::InternalError,
+                        }),
+                        None,
+                    ))
+                }
+            };
+            state.remote_requested_certificate = true;
+        }
+
+        match msgs.get(&HandshakeType::Certificate) {
+            Some(HandshakeMessage::CertificateDtls13(h)) => {
+                state.peer_certificates = h.certificate.clone();
+            }
+            _ => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::InternalError,
+                    }),
+                    None,
+                ))
+            }
+        }
+
+        let certificate_verify = match msgs.get(&HandshakeType::CertificateVerify) {
+            Some(HandshakeMessage::CertificateVerify(h)) => h,
+            _ => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::InternalError,
+                    }),
+                    None,
+                ))
+            }
+        };
+
+        // Verify that the pair of hash algorithm and signature is listed.
+        let algorithm = &certificate_verify.algorithm;
+        let valid_signature_scheme = algorithm.is_dtls13()
+            && cfg
+                .local_signature_schemes
+                .iter()
+                .any(|ss| ss.hash == algorithm.hash && ss.signature == algorithm.signature);
+        if !valid_signature_scheme {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::InsufficientSecurity,
+                }),
+                Some(Error::ErrNoAvailableSignatureSchemes),
+            ));
+        }
+
+        let transcript = cache
+            .dtls13_transcript(&rules[..=TRANSCRIPT_SERVER_CERTIFICATE])
+            .await;
+        if let Err(err) = verify_certificate_verify(
+            &certificate_verify_content(true, &transcript),
+            algorithm,
+            &certificate_verify.signature,
+            &state.peer_certificates,
+            cfg.insecure_verification,
+        ) {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::BadCertificate,
+                }),
+                Some(err),
+            ));
+        }
+
+        let mut chains = vec![];
+        if !cfg.insecure_skip_verify {
+            chains = match verify_server_cert(
+                &state.peer_certificates,
+                &cfg.server_cert_verifier,
+                &cfg.server_name,
+            ) {
+                Ok(chains) => chains,
+                Err(err) => {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::BadCertificate,
+                        }),
+                        Some(err),
+                    ))
+                }
+            }
+        }
+        if let Some(verify_peer_certificate) = &cfg.verify_peer_certificate {
+            if let Err(err) = verify_peer_certificate(&state.peer_certificates, &chains) {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::BadCertificate,
+                    }),
+                    Some(err),
+                ));
+            }
+        }
+
+        let finished = match msgs.get(&HandshakeType::Finished) {
+            Some(HandshakeMessage::Finished(h)) => h,
+            _ => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::InternalError,
+                    }),
+                    None,
+                ))
+            }
+        };
+
+        let transcript = cache
+            .dtls13_transcript(&rules[..=TRANSCRIPT_SERVER_CERTIFICATE_VERIFY])
+            .await;
+        let expected_verify_data =
+            finished_verify_data_for(&state.server_handshake_traffic_secret, &transcript)?;
+        if expected_verify_data != finished.verify_data {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::HandshakeFailure,
+                }),
+                Some(Error::ErrVerifyDataMismatch),
+            ));
+        }
+
+        let transcript = cache
+            .dtls13_transcript(&rules[..=TRANSCRIPT_SERVER_FINISHED])
+            .await;
+        derive_application_secrets(state, &transcript)?;
+
+        let application_epoch = cfg.initial_epoch + DTLS13_APPLICATION_EPOCH;
+        let server_application_traffic_secret = state.server_application_traffic_secret.clone();
+        let client_application_traffic_secret = state.client_application_traffic_secret.clone();
+        set_traffic_secret(
+            state,
+            application_epoch,
+            &server_application_traffic_secret,
+            false,
+        )
+        .await?;
+        set_traffic_secret(
+            state,
+            application_epoch,
+            &client_application_traffic_secret,
+            true,
+        )
+        .await?;
+
+        state.handshake_recv_sequence = seq;
+
+        Ok(Box::new(Flight5Dtls13 {}) as Box<dyn Flight + Send + Sync>)
+    }
+
+    async fn generate(
+        &self,
+        state: &mut State,
+        _cache: &HandshakeCache,
+        cfg: &HandshakeConfig,
+    ) -> Result<Vec<Packet>, (Option<Alert>, Option<Error>)> {
+        let mut extensions = client_hello_extensions(cfg);
+        extensions.extend(client_hello_connection_id_extension(state, cfg).await);
+        extensions.extend(dtls13_client_hello_extensions(state));
+        extensions.push(Extension::Cookie(ExtensionCookie {
+            cookie: state.cookie.clone(),
+        }));
+
+        Ok(vec![Packet {
+            record: RecordLayer::new(
+                PROTOCOL_VERSION1_2,
+                0,
+                Content::Handshake(Handshake::new(HandshakeMessage::ClientHello(
+                    HandshakeMessageClientHello {
+                        version: PROTOCOL_VERSION1_2,
+                        random: state.local_random.clone(),
+                        // The cookie of a HelloRetryRequest is echoed in the cookie
+                        // extension, the legacy field stays empty.
+                        cookie: vec![],
+
+                        cipher_suites: dtls13_client_hello_cipher_suites(cfg),
+                        compression_methods: default_compression_methods(),
+                        extensions,
+                    },
+                ))),
+            ),
+            should_encrypt: false,
+            reset_local_sequence_number: false,
+        }])
+    }
+}
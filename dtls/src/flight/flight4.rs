@@ -15,6 +15,7 @@ use crate::crypto::*;
 use crate::curve::named_curve::*;
 use crate::curve::*;
 use crate::error::Error;
+use crate::extension::extension_connection_id::*;
 use crate::extension::extension_supported_elliptic_curves::*;
 use crate::extension::extension_supported_point_formats::*;
 use crate::extension::extension_use_extended_master_secret::*;
@@ -519,6 +520,12 @@ impl Flight for Flight4 {
             }));
         }
 
+        if let Some(connection_id) = &state.connection_id_state.lock().await.local_connection_id {
+            extensions.push(Extension::ConnectionId(ExtensionConnectionId {
+                connection_id: connection_id.clone(),
+            }));
+        }
+
         if cfg.local_psk_callback.is_none() {
             extensions.extend_from_slice(&[
                 Extension::SupportedEllipticCurves(ExtensionSupportedEllipticCurves {
@@ -769,7 +776,7 @@ mod tests {
         fn encrypt(&self, _pkt_rlh: &RecordLayerHeader, _raw: &[u8]) -> Result<Vec<u8>> {
             unimplemented!();
         }
-        fn decrypt(&self, _input: &[u8]) -> Result<Vec<u8>> {
+        fn decrypt(&self, _h: &RecordLayerHeader, _input: &[u8]) -> Result<Vec<u8>> {
             unimplemented!();
         }
     }
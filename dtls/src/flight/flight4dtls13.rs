@@ -0,0 +1,502 @@
+use std::fmt;
+
+use async_trait::async_trait;
+use log::*;
+
+use super::dtls13::*;
+use super::flight6dtls13::*;
+use super::*;
+use crate::cipher_suite::*;
+use crate::compression_methods::*;
+use crate::config::*;
+use crate::content::*;
+use crate::crypto::*;
+use crate::error::Error;
+use crate::extension::extension_key_share::*;
+use crate::extension::extension_supported_signature_algorithms::*;
+use crate::extension::extension_supported_versions::*;
+use crate::extension::extension_use_srtp::*;
+use crate::extension::*;
+use crate::handshake::handshake_message_certificate_dtls13::*;
+use crate::handshake::handshake_message_certificate_request_dtls13::*;
+use crate::handshake::handshake_message_certificate_verify::*;
+use crate::handshake::handshake_message_encrypted_extensions::*;
+use crate::handshake::handshake_message_finished::*;
+use crate::handshake::handshake_message_server_hello::*;
+use crate::handshake::*;
+use crate::record_layer::record_layer_header::*;
+use crate::record_layer::*;
+use crate::signature_hash_algorithm::*;
+
+// Flight4Dtls13 sends the ServerHello followed by the messages protected
+// with the handshake traffic keys, and waits for the client Finished.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Flight4Dtls13;
+
+impl fmt::Display for Flight4Dtls13 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Flight 4 (DTLS 1.3)")
+    }
+}
+
+#[async_trait]
+impl Flight for Flight4Dtls13 {
+    async fn parse(
+        &self,
+        _tx: &mut mpsc::Sender<mpsc::Sender<()>>,
+        state: &mut State,
+        cache: &HandshakeCache,
+        cfg: &HandshakeConfig,
+    ) -> Result<Box<dyn Flight + Send + Sync>, (Option<Alert>, Option<Error>)> {
+        let epoch = cfg.initial_epoch + DTLS13_HANDSHAKE_EPOCH;
+        let (seq, msgs) = match cache
+            .full_pull_map_dtls13(
+                state.handshake_recv_sequence,
+                &[
+                    HandshakeCachePullRule {
+                        typ: HandshakeType::Certificate,
+                        epoch,
+                        is_client: true,
+                        optional: true,
+                    },
+                    HandshakeCachePullRule {
+                        typ: HandshakeType::CertificateVerify,
+                        epoch,
+                        is_client: true,
+                        optional: true,
+                    },
+                    HandshakeCachePullRule {
+                        typ: HandshakeType::Finished,
+                        epoch,
+                        is_client: true,
+                        optional: false,
+                    },
+                ],
+            )
+            .await
+        {
+            Ok((seq, msgs)) => (seq, msgs),
+            // No valid message received. Keep reading
+            Err(_) => return Err((None, None)),
+        };
+
+        let finished = match msgs.get(&HandshakeType::Finished) {
+            Some(HandshakeMessage::Finished(h)) => h,
+            _ => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::InternalError,
+                    }),
+                    None,
+                ))
+            }
+        };
+
+        if let Some(message) = msgs.get(&HandshakeType::Certificate) {
+            let h = match message {
+                HandshakeMessage::CertificateDtls13(h) => h,
+                _ => {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::InternalError,
+                        }),
+                        None,
+                    ))
+                }
+            };
+
+            state.peer_certificates = h.certificate.clone();
+            trace!(
+                "[handshake] PeerCertificates4 {}",
+                state.peer_certificates.len()
+            );
+        }
+
+        let rules = dtls13_transcript_rules(cfg.initial_epoch);
+
+        if let Some(message) = msgs.get(&HandshakeType::CertificateVerify) {
+            let h = match message {
+                HandshakeMessage::CertificateVerify(h) => h,
+                _ => {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::InternalError,
+                        }),
+                        None,
+                    ))
+                }
+            };
+
+            if state.peer_certificates.is_empty() {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::NoCertificate,
+                    }),
+                    Some(Error::ErrCertificateVerifyNoCertificate),
+                ));
+            }
+
+            // Verify that the pair of hash algorithm and signature is listed.
+            let valid_signature_scheme = h.algorithm.is_dtls13()
+                && cfg
+                    .local_signature_schemes
+                    .iter()
+                    .any(|ss| ss.hash == h.algorithm.hash && ss.signature == h.algorithm.signature);
+            if !valid_signature_scheme {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::InsufficientSecurity,
+                    }),
+                    Some(Error::ErrNoAvailableSignatureSchemes),
+                ));
+            }
+
+            let transcript = cache
+                .dtls13_transcript(&rules[..=TRANSCRIPT_CLIENT_CERTIFICATE])
+                .await;
+            if let Err(err) = verify_certificate_verify(
+                &certificate_verify_content(false, &transcript),
+                &h.algorithm,
+                &h.signature,
+                &state.peer_certificates,
+                cfg.insecure_verification,
+            ) {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::BadCertificate,
+                    }),
+                    Some(err),
+                ));
+            }
+
+            let mut chains = vec![];
+            let mut verified = false;
+            if cfg.client_auth as u8 >= ClientAuthType::VerifyClientCertIfGiven as u8 {
+                if let Some(client_cert_verifier) = &cfg.client_cert_verifier {
+                    chains =
+                        match verify_client_cert(&state.peer_certificates, client_cert_verifier) {
+                            Ok(chains) => chains,
+                            Err(err) => {
+                                return Err((
+                                    Some(Alert {
+                                        alert_level: AlertLevel::Fatal,
+                                        alert_description: AlertDescription::BadCertificate,
+                                    }),
+                                    Some(err),
+                                ))
+                            }
+                        };
+                } else {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::BadCertificate,
+                        }),
+                        Some(Error::ErrInvalidCertificate),
+                    ));
+                }
+
+                verified = true
+            }
+            if let Some(verify_peer_certificate) = &cfg.verify_peer_certificate {
+                if let Err(err) = verify_peer_certificate(&state.peer_certificates, &chains) {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::BadCertificate,
+                        }),
+                        Some(err),
+                    ));
+                }
+            }
+            state.peer_certificates_verified = verified
+        } else if !state.peer_certificates.is_empty() {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::DecryptError,
+                }),
+                Some(Error::ErrCertificateVerifyNoCertificate),
+            ));
+        }
+
+        let transcript = cache
+            .dtls13_transcript(&rules[..=TRANSCRIPT_CLIENT_CERTIFICATE_VERIFY])
+            .await;
+        let expected_verify_data =
+            finished_verify_data_for(&state.client_handshake_traffic_secret, &transcript)?;
+        if expected_verify_data != finished.verify_data {
+            return Err((
+                Some(Alert {
+                    alert_level: AlertLevel::Fatal,
+                    alert_description: AlertDescription::HandshakeFailure,
+                }),
+                Some(Error::ErrVerifyDataMismatch),
+            ));
+        }
+
+        match cfg.client_auth {
+            ClientAuthType::RequireAnyClientCert => {
+                if state.peer_certificates.is_empty() {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::NoCertificate,
+                        }),
+                        Some(Error::ErrClientCertificateRequired),
+                    ));
+                }
+            }
+            ClientAuthType::VerifyClientCertIfGiven => {
+                if !state.peer_certificates.is_empty() && !state.peer_certificates_verified {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::BadCertificate,
+                        }),
+                        Some(Error::ErrClientCertificateNotVerified),
+                    ));
+                }
+            }
+            ClientAuthType::RequireAndVerifyClientCert => {
+                if state.peer_certificates.is_empty() {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::NoCertificate,
+                        }),
+                        Some(Error::ErrClientCertificateRequired),
+                    ));
+                }
+                if !state.peer_certificates_verified {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::BadCertificate,
+                        }),
+                        Some(Error::ErrClientCertificateNotVerified),
+                    ));
+                }
+            }
+            ClientAuthType::NoClientCert | ClientAuthType::RequestClientCert => {}
+        }
+
+        let client_application_traffic_secret = state.client_application_traffic_secret.clone();
+        set_traffic_secret(
+            state,
+            cfg.initial_epoch + DTLS13_APPLICATION_EPOCH,
+            &client_application_traffic_secret,
+            false,
+        )
+        .await?;
+
+        state.handshake_recv_sequence = seq;
+
+        Ok(Box::new(Flight6Dtls13 {}) as Box<dyn Flight + Send + Sync>)
+    }
+
+    async fn generate(
+        &self,
+        state: &mut State,
+        cache: &HandshakeCache,
+        cfg: &HandshakeConfig,
+    ) -> Result<Vec<Packet>, (Option<Alert>, Option<Error>)> {
+        let certificate = match cfg.get_certificate(&cfg.server_name) {
+            Ok(cert) => cert,
+            Err(err) => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::HandshakeFailure,
+                    }),
+                    Some(err),
+                ))
+            }
+        };
+
+        // Find compatible signature scheme
+        let signature_hash_algo = match select_signature_scheme_dtls13(
+            &cfg.local_signature_schemes,
+            &certificate.private_key,
+        ) {
+            Ok(s) => s,
+            Err(err) => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::InsufficientSecurity,
+                    }),
+                    Some(err),
+                ))
+            }
+        };
+
+        let public_key = match &state.local_keypair {
+            Some(local_keypair) => local_keypair.public_key.clone(),
+            None => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::InternalError,
+                    }),
+                    Some(Error::ErrNoSupportedKeyShare),
+                ))
+            }
+        };
+
+        let server_hello =
+            Handshake::new(HandshakeMessage::ServerHello(HandshakeMessageServerHello {
+                version: PROTOCOL_VERSION1_2,
+                random: state.local_random.clone(),
+                cipher_suite: CipherSuiteId::Tls_Aes_128_Gcm_Sha256,
+                compression_method: default_compression_methods().ids[0],
+                extensions: vec![
+                    Extension::SupportedVersions(ExtensionSupportedVersions::Selected(
+                        PROTOCOL_VERSION1_3,
+                    )),
+                    Extension::KeyShare(ExtensionKeyShare::ServerShare(KeyShareEntry {
+                        group: state.named_curve,
+                        key_exchange: public_key,
+                    })),
+                ],
+            }));
+
+        let rules = dtls13_transcript_rules(cfg.initial_epoch);
+        let mut transcript = cache
+            .dtls13_transcript(&rules[..=TRANSCRIPT_CLIENT_HELLO])
+            .await;
+        transcript.extend_from_slice(&handshake_transcript_form(&server_hello)?);
+        derive_handshake_secrets(state, &transcript)?;
+
+        let mut encrypted_extensions = vec![];
+        if state.srtp_protection_profile != SrtpProtectionProfile::Unsupported {
+            encrypted_extensions.push(Extension::UseSrtp(ExtensionUseSrtp {
+                protection_profiles: vec![state.srtp_protection_profile],
+            }));
+        }
+
+        let mut messages = vec![Handshake::new(HandshakeMessage::EncryptedExtensions(
+            HandshakeMessageEncryptedExtensions {
+                extensions: encrypted_extensions,
+            },
+        ))];
+
+        if cfg.client_auth as u8 > ClientAuthType::NoClientCert as u8 {
+            messages.push(Handshake::new(HandshakeMessage::CertificateRequestDtls13(
+                HandshakeMessageCertificateRequestDtls13 {
+                    certificate_request_context: vec![],
+                    extensions: vec![Extension::SupportedSignatureAlgorithms(
+                        ExtensionSupportedSignatureAlgorithms {
+                            signature_hash_algorithms: cfg
+                                .local_signature_schemes
+                                .iter()
+                                .filter(|ss| ss.is_dtls13())
+                                .cloned()
+                                .collect(),
+                        },
+                    )],
+                },
+            )));
+        }
+
+        messages.push(Handshake::new(HandshakeMessage::CertificateDtls13(
+            HandshakeMessageCertificateDtls13 {
+                certificate_request_context: vec![],
+                certificate: certificate
+                    .certificate
+                    .iter()
+                    .map(|x| x.0.clone())
+                    .collect(),
+            },
+        )));
+
+        for message in &messages {
+            transcript.extend_from_slice(&handshake_transcript_form(message)?);
+        }
+
+        let signature = match generate_certificate_verify(
+            &certificate_verify_content(true, &transcript),
+            &certificate.private_key,
+        ) {
+            Ok(signature) => signature,
+            Err(err) => {
+                return Err((
+                    Some(Alert {
+                        alert_level: AlertLevel::Fatal,
+                        alert_description: AlertDescription::InternalError,
+                    }),
+                    Some(err),
+                ))
+            }
+        };
+        let certificate_verify = Handshake::new(HandshakeMessage::CertificateVerify(
+            HandshakeMessageCertificateVerify {
+                algorithm: signature_hash_algo,
+                signature,
+            },
+        ));
+        transcript.extend_from_slice(&handshake_transcript_form(&certificate_verify)?);
+        messages.push(certificate_verify);
+
+        let verify_data =
+            finished_verify_data_for(&state.server_handshake_traffic_secret, &transcript)?;
+        let finished = Handshake::new(HandshakeMessage::Finished(HandshakeMessageFinished {
+            verify_data,
+        }));
+        transcript.extend_from_slice(&handshake_transcript_form(&finished)?);
+        messages.push(finished);
+
+        derive_application_secrets(state, &transcript)?;
+
+        let handshake_epoch = cfg.initial_epoch + DTLS13_HANDSHAKE_EPOCH;
+        let server_handshake_traffic_secret = state.server_handshake_traffic_secret.clone();
+        let client_handshake_traffic_secret = state.client_handshake_traffic_secret.clone();
+        let server_application_traffic_secret = state.server_application_traffic_secret.clone();
+        set_traffic_secret(
+            state,
+            handshake_epoch,
+            &server_handshake_traffic_secret,
+            true,
+        )
+        .await?;
+        set_traffic_secret(
+            state,
+            handshake_epoch,
+            &client_handshake_traffic_secret,
+            false,
+        )
+        .await?;
+        set_traffic_secret(
+            state,
+            cfg.initial_epoch + DTLS13_APPLICATION_EPOCH,
+            &server_application_traffic_secret,
+            true,
+        )
+        .await?;
+
+        let mut pkts = vec![Packet {
+            record: RecordLayer::new(PROTOCOL_VERSION1_2, 0, Content::Handshake(server_hello)),
+            should_encrypt: false,
+            reset_local_sequence_number: false,
+        }];
+        for message in messages {
+            pkts.push(Packet {
+                record: RecordLayer::new(
+                    PROTOCOL_VERSION1_2,
+                    DTLS13_HANDSHAKE_EPOCH,
+                    Content::Handshake(message),
+                ),
+                should_encrypt: true,
+                reset_local_sequence_number: false,
+            });
+        }
+
+        Ok(pkts)
+    }
+}
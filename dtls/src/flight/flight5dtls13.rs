@@ -0,0 +1,174 @@
+use std::fmt;
+use std::sync::atomic::Ordering;
+
+use async_trait::async_trait;
+
+use super::dtls13::*;
+use super::*;
+use crate::content::*;
+use crate::crypto::*;
+use crate::error::Error;
+use crate::handshake::handshake_message_certificate_dtls13::*;
+use crate::handshake::handshake_message_certificate_verify::*;
+use crate::handshake::handshake_message_finished::*;
+use crate::handshake::*;
+use crate::record_layer::record_layer_header::*;
+use crate::record_layer::*;
+use crate::signature_hash_algorithm::*;
+
+// Flight5Dtls13 sends the final flight of the client, protected with the
+// handshake traffic keys, and waits until the server acknowledges it.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Flight5Dtls13;
+
+impl fmt::Display for Flight5Dtls13 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Flight 5 (DTLS 1.3)")
+    }
+}
+
+#[async_trait]
+impl Flight for Flight5Dtls13 {
+    fn is_last_recv_flight(&self) -> bool {
+        true
+    }
+
+    async fn parse(
+        &self,
+        _tx: &mut mpsc::Sender<mpsc::Sender<()>>,
+        state: &mut State,
+        _cache: &HandshakeCache,
+        cfg: &HandshakeConfig,
+    ) -> Result<Box<dyn Flight + Send + Sync>, (Option<Alert>, Option<Error>)> {
+        let handshake_epoch = (cfg.initial_epoch + DTLS13_HANDSHAKE_EPOCH) as u64;
+        {
+            let mut ack_state = state.ack_state.lock().await;
+            if !ack_state
+                .acked
+                .iter()
+                .any(|record_number| record_number.epoch == handshake_epoch)
+            {
+                // No ACK of the final flight received. Keep reading
+                return Err((None, None));
+            }
+            ack_state.acked.clear();
+            ack_state.received.clear();
+        }
+
+        state.local_epoch.store(
+            cfg.initial_epoch + DTLS13_APPLICATION_EPOCH,
+            Ordering::SeqCst,
+        );
+
+        Ok(Box::new(Flight5Dtls13 {}))
+    }
+
+    async fn generate(
+        &self,
+        state: &mut State,
+        cache: &HandshakeCache,
+        cfg: &HandshakeConfig,
+    ) -> Result<Vec<Packet>, (Option<Alert>, Option<Error>)> {
+        let certificate = if !cfg.local_certificates.is_empty() {
+            let cert = match cfg.get_certificate(&cfg.server_name) {
+                Ok(cert) => cert,
+                Err(err) => {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::HandshakeFailure,
+                        }),
+                        Some(err),
+                    ))
+                }
+            };
+            Some(cert)
+        } else {
+            None
+        };
+
+        let rules = dtls13_transcript_rules(cfg.initial_epoch);
+        let mut transcript = cache
+            .dtls13_transcript(&rules[..=TRANSCRIPT_SERVER_FINISHED])
+            .await;
+
+        let mut messages = vec![];
+        if state.remote_requested_certificate {
+            let certificate_message = Handshake::new(HandshakeMessage::CertificateDtls13(
+                HandshakeMessageCertificateDtls13 {
+                    certificate_request_context: vec![],
+                    certificate: if let Some(cert) = &certificate {
+                        cert.certificate.iter().map(|x| x.0.clone()).collect()
+                    } else {
+                        vec![]
+                    },
+                },
+            ));
+            transcript.extend_from_slice(&handshake_transcript_form(&certificate_message)?);
+            messages.push(certificate_message);
+
+            // If the client has sent a certificate, a CertificateVerify message
+            // proves the possession of its private key.
+            if let Some(cert) = &certificate {
+                let signature_hash_algo = match select_signature_scheme_dtls13(
+                    &cfg.local_signature_schemes,
+                    &cert.private_key,
+                ) {
+                    Ok(s) => s,
+                    Err(err) => {
+                        return Err((
+                            Some(Alert {
+                                alert_level: AlertLevel::Fatal,
+                                alert_description: AlertDescription::InsufficientSecurity,
+                            }),
+                            Some(err),
+                        ))
+                    }
+                };
+
+                let signature = match generate_certificate_verify(
+                    &certificate_verify_content(false, &transcript),
+                    &cert.private_key,
+                ) {
+                    Ok(signature) => signature,
+                    Err(err) => {
+                        return Err((
+                            Some(Alert {
+                                alert_level: AlertLevel::Fatal,
+                                alert_description: AlertDescription::InternalError,
+                            }),
+                            Some(err),
+                        ))
+                    }
+                };
+                let certificate_verify = Handshake::new(HandshakeMessage::CertificateVerify(
+                    HandshakeMessageCertificateVerify {
+                        algorithm: signature_hash_algo,
+                        signature,
+                    },
+                ));
+                transcript.extend_from_slice(&handshake_transcript_form(&certificate_verify)?);
+                messages.push(certificate_verify);
+            }
+        }
+
+        let verify_data =
+            finished_verify_data_for(&state.client_handshake_traffic_secret, &transcript)?;
+        messages.push(Handshake::new(HandshakeMessage::Finished(
+            HandshakeMessageFinished { verify_data },
+        )));
+
+        Ok(messages
+            .into_iter()
+            .map(|message| Packet {
+                record: RecordLayer::new(
+                    PROTOCOL_VERSION1_2,
+                    DTLS13_HANDSHAKE_EPOCH,
+                    Content::Handshake(message),
+                ),
+                should_encrypt: true,
+                reset_local_sequence_number: false,
+            })
+            .collect())
+    }
+}
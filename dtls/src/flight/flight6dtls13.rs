@@ -0,0 +1,93 @@
+use std::fmt;
+
+use async_trait::async_trait;
+
+use super::dtls13::*;
+use super::*;
+use crate::ack::*;
+use crate::content::*;
+use crate::handshake::*;
+use crate::record_layer::record_layer_header::*;
+
+// Flight6Dtls13 acknowledges the final flight of the client with the
+// application traffic keys.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Flight6Dtls13;
+
+impl fmt::Display for Flight6Dtls13 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Flight 6 (DTLS 1.3)")
+    }
+}
+
+#[async_trait]
+impl Flight for Flight6Dtls13 {
+    fn is_last_send_flight(&self) -> bool {
+        true
+    }
+
+    async fn parse(
+        &self,
+        _tx: &mut mpsc::Sender<mpsc::Sender<()>>,
+        state: &mut State,
+        cache: &HandshakeCache,
+        cfg: &HandshakeConfig,
+    ) -> Result<Box<dyn Flight + Send + Sync>, (Option<Alert>, Option<Error>)> {
+        let (_, msgs) = match cache
+            .full_pull_map_dtls13(
+                state.handshake_recv_sequence - 1,
+                &[HandshakeCachePullRule {
+                    typ: HandshakeType::Finished,
+                    epoch: cfg.initial_epoch + DTLS13_HANDSHAKE_EPOCH,
+                    is_client: true,
+                    optional: false,
+                }],
+            )
+            .await
+        {
+            Ok((seq, msgs)) => (seq, msgs),
+            // No valid message received. Keep reading
+            Err(_) => return Err((None, None)),
+        };
+
+        if let Some(message) = msgs.get(&HandshakeType::Finished) {
+            match message {
+                HandshakeMessage::Finished(_) => {}
+                _ => {
+                    return Err((
+                        Some(Alert {
+                            alert_level: AlertLevel::Fatal,
+                            alert_description: AlertDescription::InternalError,
+                        }),
+                        None,
+                    ))
+                }
+            };
+        }
+
+        // Other party retransmitted the last flight.
+        Ok(Box::new(Flight6Dtls13 {}))
+    }
+
+    async fn generate(
+        &self,
+        state: &mut State,
+        _cache: &HandshakeCache,
+        _cfg: &HandshakeConfig,
+    ) -> Result<Vec<Packet>, (Option<Alert>, Option<Error>)> {
+        let record_numbers = {
+            let mut ack_state = state.ack_state.lock().await;
+            std::mem::take(&mut ack_state.received)
+        };
+
+        Ok(vec![Packet {
+            record: RecordLayer::new(
+                PROTOCOL_VERSION1_2,
+                DTLS13_APPLICATION_EPOCH,
+                Content::Ack(Ack { record_numbers }),
+            ),
+            should_encrypt: true,
+            reset_local_sequence_number: false,
+        }])
+    }
+}
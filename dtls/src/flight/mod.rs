@@ -1,10 +1,16 @@
+pub(crate) mod dtls13;
 pub(crate) mod flight0;
 pub(crate) mod flight1;
 pub(crate) mod flight2;
+pub(crate) mod flight2dtls13;
 pub(crate) mod flight3;
+pub(crate) mod flight3dtls13;
 pub(crate) mod flight4;
+pub(crate) mod flight4dtls13;
 pub(crate) mod flight5;
+pub(crate) mod flight5dtls13;
 pub(crate) mod flight6;
+pub(crate) mod flight6dtls13;
 
 use std::fmt;
 
@@ -9,6 +9,7 @@ use sha2::{Digest, Sha256};
 use tokio::sync::Mutex;
 
 use crate::cipher_suite::*;
+use crate::handshake::handshake_header::HANDSHAKE_HEADER_LENGTH;
 use crate::handshake::*;
 
 #[derive(Clone, Debug)]
@@ -101,6 +102,26 @@ impl HandshakeCache {
         &self,
         start_seq: isize,
         rules: &[HandshakeCachePullRule],
+    ) -> Result<(isize, HashMap<HandshakeType, HandshakeMessage>)> {
+        self.full_pull_map_with(start_seq, rules, |r| Handshake::unmarshal(r))
+            .await
+    }
+
+    // full_pull_map_dtls13 is full_pull_map for handshakes protected with DTLS 1.3 keys.
+    pub(crate) async fn full_pull_map_dtls13(
+        &self,
+        start_seq: isize,
+        rules: &[HandshakeCachePullRule],
+    ) -> Result<(isize, HashMap<HandshakeType, HandshakeMessage>)> {
+        self.full_pull_map_with(start_seq, rules, |r| Handshake::unmarshal_dtls13(r))
+            .await
+    }
+
+    async fn full_pull_map_with(
+        &self,
+        start_seq: isize,
+        rules: &[HandshakeCachePullRule],
+        unmarshal: fn(&mut BufReader<&[u8]>) -> Result<Handshake>,
     ) -> Result<(isize, HashMap<HandshakeType, HandshakeMessage>)> {
         let cache = self.cache.lock().await;
 
@@ -134,7 +155,7 @@ impl HandshakeCache {
             let t = r.typ;
             if let Some(i) = ci.get(&t) {
                 let mut reader = BufReader::new(i.data.as_slice());
-                let raw_handshake = Handshake::unmarshal(&mut reader)?;
+                let raw_handshake = unmarshal(&mut reader)?;
                 if seq as u16 != raw_handshake.handshake_header.message_sequence {
                     // There is a gap. Some messages are not arrived.
                     return Err(Error::Other(
@@ -160,6 +181,62 @@ impl HandshakeCache {
         merged
     }
 
+    // dtls13_transcript pulls handshakes like pull_and_merge, but merges them in
+    // the form used by the DTLS 1.3 transcript, without message_seq,
+    // fragment_offset and fragment_length. If a HelloRetryRequest was
+    // exchanged, the transcript starts with the hash of the first ClientHello
+    // followed by the HelloRetryRequest.
+    // https://www.rfc-editor.org/rfc/rfc9147#section-5.2
+    // https://www.rfc-editor.org/rfc/rfc8446#section-4.4.1
+    pub(crate) async fn dtls13_transcript(&self, rules: &[HandshakeCachePullRule]) -> Vec<u8> {
+        let items = self.pull(rules).await;
+        let cache = self.cache.lock().await;
+
+        let hello_retry_request = cache.iter().find(|c| {
+            if c.typ != HandshakeType::ServerHello || c.is_client {
+                return false;
+            }
+            let mut reader = BufReader::new(c.data.as_slice());
+            matches!(
+                Handshake::unmarshal(&mut reader),
+                Ok(Handshake {
+                    handshake_message: HandshakeMessage::ServerHello(h),
+                    ..
+                }) if h.random.is_hello_retry_request()
+            )
+        });
+        let first_client_hello = cache
+            .iter()
+            .filter(|c| c.typ == HandshakeType::ClientHello && c.is_client)
+            .min_by_key(|c| c.message_sequence);
+
+        let mut merged = vec![];
+        if let (Some(hrr), Some(ch)) = (hello_retry_request, first_client_hello) {
+            let client_hello_hash = Sha256::digest(dtls13_handshake_form(&ch.data));
+            merged.extend_from_slice(&[
+                HandshakeType::MessageHash as u8,
+                0,
+                0,
+                client_hello_hash.len() as u8,
+            ]);
+            merged.extend_from_slice(&client_hello_hash);
+            merged.extend_from_slice(&dtls13_handshake_form(&hrr.data));
+        }
+
+        for item in &items {
+            let is_hello_retry_request = hello_retry_request.map_or(false, |hrr| {
+                hrr.typ == item.typ
+                    && hrr.is_client == item.is_client
+                    && hrr.message_sequence == item.message_sequence
+            });
+            if !is_hello_retry_request {
+                merged.extend_from_slice(&dtls13_handshake_form(&item.data));
+            }
+        }
+
+        merged
+    }
+
     // session_hash returns the session hash for Extended Master Secret support
     // https://tools.ietf.org/html/draft-ietf-tls-session-hash-06#section-4
     pub(crate) async fn session_hash(
@@ -239,3 +316,16 @@ impl HandshakeCache {
         Ok(result.as_slice().to_vec())
     }
 }
+
+// dtls13_handshake_form strips message_seq, fragment_offset and fragment_length
+// from a marshaled DTLS handshake, leaving the TLS handshake header.
+pub(crate) fn dtls13_handshake_form(data: &[u8]) -> Vec<u8> {
+    if data.len() < HANDSHAKE_HEADER_LENGTH {
+        return data.to_vec();
+    }
+
+    let mut out = Vec::with_capacity(data.len() - HANDSHAKE_HEADER_LENGTH + 4);
+    out.extend_from_slice(&data[..4]);
+    out.extend_from_slice(&data[HANDSHAKE_HEADER_LENGTH..]);
+    out
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod handshake_message_certificate_dtls13_test;
+
+use std::io::{Read, Write};
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+use super::*;
+
+/*
+In DTLS 1.3 the Certificate message carries a request context and
+per-certificate extensions in addition to the certificate chain.
+The request context is empty in the main handshake; this implementation
+neither sends nor interprets certificate extensions.
+https://www.rfc-editor.org/rfc/rfc8446#section-4.4.2
+*/
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct HandshakeMessageCertificateDtls13 {
+    pub(crate) certificate_request_context: Vec<u8>,
+    pub(crate) certificate: Vec<Vec<u8>>,
+}
+
+impl HandshakeMessageCertificateDtls13 {
+    pub fn handshake_type(&self) -> HandshakeType {
+        HandshakeType::Certificate
+    }
+
+    fn certificate_list_size(&self) -> usize {
+        self.certificate.iter().map(|c| 3 + c.len() + 2).sum()
+    }
+
+    pub fn size(&self) -> usize {
+        1 + self.certificate_request_context.len() + 3 + self.certificate_list_size()
+    }
+
+    pub fn marshal<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_u8(self.certificate_request_context.len() as u8)?;
+        writer.write_all(&self.certificate_request_context)?;
+
+        writer.write_u24::<BigEndian>(self.certificate_list_size() as u32)?;
+        for c in &self.certificate {
+            writer.write_u24::<BigEndian>(c.len() as u32)?;
+            writer.write_all(c)?;
+            // Empty CertificateEntry extensions
+            writer.write_u16::<BigEndian>(0)?;
+        }
+
+        Ok(writer.flush()?)
+    }
+
+    pub fn unmarshal<R: Read>(reader: &mut R) -> Result<Self> {
+        let context_len = reader.read_u8()? as usize;
+        let mut certificate_request_context = vec![0u8; context_len];
+        reader.read_exact(&mut certificate_request_context)?;
+
+        let mut certificate = vec![];
+        let certificate_list_len = reader.read_u24::<BigEndian>()? as usize;
+        let mut offset = 0;
+        while offset < certificate_list_len {
+            let certificate_len = reader.read_u24::<BigEndian>()? as usize;
+            let mut buf = vec![0u8; certificate_len];
+            reader.read_exact(&mut buf)?;
+
+            let extensions_len = reader.read_u16::<BigEndian>()? as usize;
+            let mut extensions = vec![0u8; extensions_len];
+            reader.read_exact(&mut extensions)?;
+
+            offset += 3 + certificate_len + 2 + extensions_len;
+            certificate.push(buf);
+        }
+        if offset != certificate_list_len {
+            return Err(Error::ErrLengthMismatch);
+        }
+
+        Ok(HandshakeMessageCertificateDtls13 {
+            certificate_request_context,
+            certificate,
+        })
+    }
+}
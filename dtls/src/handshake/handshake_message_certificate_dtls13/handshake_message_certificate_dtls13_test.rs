@@ -0,0 +1,37 @@
+use std::io::{BufReader, BufWriter};
+
+use super::*;
+
+#[test]
+fn test_handshake_message_certificate_dtls13() -> Result<()> {
+    let raw_certificate = vec![
+        0x00, // certificate_request_context
+        0x00, 0x00, 0x0d, // certificate_list length
+        0x00, 0x00, 0x03, 0x01, 0x02, 0x03, 0x00, 0x00, // first entry
+        0x00, 0x00, 0x00, 0x00, 0x00, // second, empty entry
+    ];
+    let parsed_certificate = HandshakeMessageCertificateDtls13 {
+        certificate_request_context: vec![],
+        certificate: vec![vec![0x01, 0x02, 0x03], vec![]],
+    };
+
+    let mut reader = BufReader::new(raw_certificate.as_slice());
+    let c = HandshakeMessageCertificateDtls13::unmarshal(&mut reader)?;
+    assert_eq!(
+        c, parsed_certificate,
+        "handshakeMessageCertificateDtls13 unmarshal: got {c:?}, want {parsed_certificate:?}"
+    );
+
+    let mut raw = vec![];
+    {
+        let mut writer = BufWriter::<&mut Vec<u8>>::new(raw.as_mut());
+        c.marshal(&mut writer)?;
+    }
+    assert_eq!(
+        raw, raw_certificate,
+        "handshakeMessageCertificateDtls13 marshal: got {raw:?}, want {raw_certificate:?}"
+    );
+    assert_eq!(c.size(), raw_certificate.len());
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod handshake_message_certificate_request_dtls13_test;
+
+use std::io::{Read, Write};
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+use super::*;
+use crate::extension::*;
+
+/*
+A server which is authenticating with a certificate MAY optionally
+request a certificate from the client.  In DTLS 1.3 the acceptable
+signature algorithms are carried in the signature_algorithms
+extension instead of dedicated fields.
+https://www.rfc-editor.org/rfc/rfc8446#section-4.3.2
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandshakeMessageCertificateRequestDtls13 {
+    pub(crate) certificate_request_context: Vec<u8>,
+    pub(crate) extensions: Vec<Extension>,
+}
+
+impl HandshakeMessageCertificateRequestDtls13 {
+    pub fn handshake_type(&self) -> HandshakeType {
+        HandshakeType::CertificateRequest
+    }
+
+    pub fn size(&self) -> usize {
+        1 + self.certificate_request_context.len()
+            + 2
+            + self.extensions.iter().map(|e| e.size()).sum::<usize>()
+    }
+
+    pub fn marshal<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_u8(self.certificate_request_context.len() as u8)?;
+        writer.write_all(&self.certificate_request_context)?;
+        marshal_extensions(&self.extensions, writer)?;
+
+        Ok(writer.flush()?)
+    }
+
+    pub fn unmarshal<R: Read>(reader: &mut R) -> Result<Self> {
+        let context_len = reader.read_u8()? as usize;
+        let mut certificate_request_context = vec![0u8; context_len];
+        reader.read_exact(&mut certificate_request_context)?;
+
+        let extensions_len = reader.read_u16::<BigEndian>()? as usize;
+        let mut extension_buffer = vec![0u8; extensions_len];
+        reader.read_exact(&mut extension_buffer)?;
+
+        Ok(HandshakeMessageCertificateRequestDtls13 {
+            certificate_request_context,
+            extensions: unmarshal_extensions(&extension_buffer)?,
+        })
+    }
+}
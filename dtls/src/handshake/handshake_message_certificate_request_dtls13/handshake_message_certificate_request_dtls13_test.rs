@@ -0,0 +1,51 @@
+use std::io::{BufReader, BufWriter};
+
+use super::*;
+use crate::extension::extension_supported_signature_algorithms::*;
+use crate::signature_hash_algorithm::*;
+
+#[test]
+fn test_handshake_message_certificate_request_dtls13() -> Result<()> {
+    let raw_certificate_request = vec![
+        0x00, // certificate_request_context
+        0x00, 0x0a, // extensions length
+        0x00, 0x0d, 0x00, 0x06, 0x00, 0x04, 0x04, 0x03, 0x08, 0x07,
+    ];
+    let parsed_certificate_request = HandshakeMessageCertificateRequestDtls13 {
+        certificate_request_context: vec![],
+        extensions: vec![Extension::SupportedSignatureAlgorithms(
+            ExtensionSupportedSignatureAlgorithms {
+                signature_hash_algorithms: vec![
+                    SignatureHashAlgorithm {
+                        hash: HashAlgorithm::Sha256,
+                        signature: SignatureAlgorithm::Ecdsa,
+                    },
+                    SignatureHashAlgorithm {
+                        hash: HashAlgorithm::Ed25519,
+                        signature: SignatureAlgorithm::Ed25519,
+                    },
+                ],
+            },
+        )],
+    };
+
+    let mut reader = BufReader::new(raw_certificate_request.as_slice());
+    let c = HandshakeMessageCertificateRequestDtls13::unmarshal(&mut reader)?;
+    assert_eq!(
+        c, parsed_certificate_request,
+        "handshakeMessageCertificateRequestDtls13 unmarshal: got {c:?}, want {parsed_certificate_request:?}"
+    );
+
+    let mut raw = vec![];
+    {
+        let mut writer = BufWriter::<&mut Vec<u8>>::new(raw.as_mut());
+        c.marshal(&mut writer)?;
+    }
+    assert_eq!(
+        raw, raw_certificate_request,
+        "handshakeMessageCertificateRequestDtls13 marshal: got {raw:?}, want {raw_certificate_request:?}"
+    );
+    assert_eq!(c.size(), raw_certificate_request.len());
+
+    Ok(())
+}
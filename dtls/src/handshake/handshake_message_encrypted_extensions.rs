@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod handshake_message_encrypted_extensions_test;
+
+use std::io::{Read, Write};
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use super::*;
+use crate::extension::*;
+
+/*
+In all handshakes, the server MUST send the EncryptedExtensions
+message immediately after the ServerHello message.  This is the
+first message that is encrypted under keys derived from the
+server_handshake_traffic_secret.
+https://www.rfc-editor.org/rfc/rfc8446#section-4.3.1
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandshakeMessageEncryptedExtensions {
+    pub(crate) extensions: Vec<Extension>,
+}
+
+impl HandshakeMessageEncryptedExtensions {
+    pub fn handshake_type(&self) -> HandshakeType {
+        HandshakeType::EncryptedExtensions
+    }
+
+    pub fn size(&self) -> usize {
+        2 + self.extensions.iter().map(|e| e.size()).sum::<usize>()
+    }
+
+    pub fn marshal<W: Write>(&self, writer: &mut W) -> Result<()> {
+        marshal_extensions(&self.extensions, writer)?;
+
+        Ok(writer.flush()?)
+    }
+
+    pub fn unmarshal<R: Read>(reader: &mut R) -> Result<Self> {
+        let extensions_len = reader.read_u16::<BigEndian>()? as usize;
+        let mut extension_buffer = vec![0u8; extensions_len];
+        reader.read_exact(&mut extension_buffer)?;
+
+        Ok(HandshakeMessageEncryptedExtensions {
+            extensions: unmarshal_extensions(&extension_buffer)?,
+        })
+    }
+}
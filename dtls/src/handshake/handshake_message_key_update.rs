@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod handshake_message_key_update_test;
+
+use std::io::{Read, Write};
+
+use byteorder::{ReadBytesExt, WriteBytesExt};
+
+use super::*;
+
+/*
+The KeyUpdate handshake message is used to indicate that the sender
+is updating its sending cryptographic keys.  If update_requested is
+set, the receiver MUST send a KeyUpdate of its own before sending
+its next application data.
+https://www.rfc-editor.org/rfc/rfc8446#section-4.6.3
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandshakeMessageKeyUpdate {
+    pub(crate) update_requested: bool,
+}
+
+impl HandshakeMessageKeyUpdate {
+    pub fn handshake_type(&self) -> HandshakeType {
+        HandshakeType::KeyUpdate
+    }
+
+    pub fn size(&self) -> usize {
+        1
+    }
+
+    pub fn marshal<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_u8(self.update_requested as u8)?;
+
+        Ok(writer.flush()?)
+    }
+
+    pub fn unmarshal<R: Read>(reader: &mut R) -> Result<Self> {
+        let update_requested = match reader.read_u8()? {
+            0 => false,
+            1 => true,
+            _ => return Err(Error::ErrInvalidKeyUpdate),
+        };
+
+        Ok(HandshakeMessageKeyUpdate { update_requested })
+    }
+}
@@ -0,0 +1,46 @@
+use std::io::{BufReader, BufWriter};
+
+use super::*;
+
+#[test]
+fn test_handshake_message_key_update() -> Result<()> {
+    for (raw_key_update, parsed_key_update) in [
+        (
+            vec![0x00],
+            HandshakeMessageKeyUpdate {
+                update_requested: false,
+            },
+        ),
+        (
+            vec![0x01],
+            HandshakeMessageKeyUpdate {
+                update_requested: true,
+            },
+        ),
+    ] {
+        let mut reader = BufReader::new(raw_key_update.as_slice());
+        let c = HandshakeMessageKeyUpdate::unmarshal(&mut reader)?;
+        assert_eq!(
+            c, parsed_key_update,
+            "handshakeMessageKeyUpdate unmarshal: got {c:?}, want {parsed_key_update:?}"
+        );
+
+        let mut raw = vec![];
+        {
+            let mut writer = BufWriter::<&mut Vec<u8>>::new(raw.as_mut());
+            c.marshal(&mut writer)?;
+        }
+        assert_eq!(
+            raw, raw_key_update,
+            "handshakeMessageKeyUpdate marshal: got {raw:?}, want {raw_key_update:?}"
+        );
+    }
+
+    let mut reader = BufReader::new([0x02].as_slice());
+    assert_eq!(
+        HandshakeMessageKeyUpdate::unmarshal(&mut reader).err(),
+        Some(Error::ErrInvalidKeyUpdate)
+    );
+
+    Ok(())
+}
@@ -7,6 +7,18 @@ use rand::Rng;
 pub const RANDOM_BYTES_LENGTH: usize = 28;
 pub const HANDSHAKE_RANDOM_LENGTH: usize = RANDOM_BYTES_LENGTH + 4;
 
+// The random of a HelloRetryRequest is the SHA-256 of "HelloRetryRequest".
+// https://www.rfc-editor.org/rfc/rfc8446#section-4.1.3
+const HELLO_RETRY_REQUEST_RANDOM: [u8; HANDSHAKE_RANDOM_LENGTH] = [
+    0xcf, 0x21, 0xad, 0x74, 0xe5, 0x9a, 0x61, 0x11, 0xbe, 0x1d, 0x8c, 0x02, 0x1e, 0x65, 0xb8, 0x91,
+    0xc2, 0xa2, 0x11, 0x16, 0x7a, 0xbb, 0x8c, 0x5e, 0x07, 0x9e, 0x09, 0xe2, 0xc8, 0xa8, 0x33, 0x9c,
+];
+
+// A server able to negotiate DTLS 1.3 puts this value in the last bytes of
+// its random when it negotiates DTLS 1.2.
+// https://www.rfc-editor.org/rfc/rfc8446#section-4.1.3
+const DOWNGRADE_SENTINEL_DTLS12: [u8; 8] = *b"DOWNGRD\x01";
+
 // https://tools.ietf.org/html/rfc4346#section-7.4.1.2
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct HandshakeRandom {
@@ -64,4 +76,24 @@ impl HandshakeRandom {
         self.gmt_unix_time = SystemTime::now();
         rand::thread_rng().fill(&mut self.random_bytes);
     }
+
+    pub(crate) fn hello_retry_request() -> Self {
+        let mut reader = &HELLO_RETRY_REQUEST_RANDOM[..];
+        // reading from a slice of the right length can not fail
+        HandshakeRandom::unmarshal(&mut reader).unwrap_or_default()
+    }
+
+    pub(crate) fn is_hello_retry_request(&self) -> bool {
+        *self == HandshakeRandom::hello_retry_request()
+    }
+
+    pub(crate) fn set_downgrade_sentinel(&mut self) {
+        self.random_bytes[RANDOM_BYTES_LENGTH - DOWNGRADE_SENTINEL_DTLS12.len()..]
+            .copy_from_slice(&DOWNGRADE_SENTINEL_DTLS12);
+    }
+
+    pub(crate) fn has_downgrade_sentinel(&self) -> bool {
+        self.random_bytes[RANDOM_BYTES_LENGTH - DOWNGRADE_SENTINEL_DTLS12.len()..]
+            == DOWNGRADE_SENTINEL_DTLS12
+    }
 }
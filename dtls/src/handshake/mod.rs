@@ -1,12 +1,16 @@
 pub mod handshake_cache;
 pub mod handshake_header;
 pub mod handshake_message_certificate;
+pub mod handshake_message_certificate_dtls13;
 pub mod handshake_message_certificate_request;
+pub mod handshake_message_certificate_request_dtls13;
 pub mod handshake_message_certificate_verify;
 pub mod handshake_message_client_hello;
 pub mod handshake_message_client_key_exchange;
+pub mod handshake_message_encrypted_extensions;
 pub mod handshake_message_finished;
 pub mod handshake_message_hello_verify_request;
+pub mod handshake_message_key_update;
 pub mod handshake_message_server_hello;
 pub mod handshake_message_server_hello_done;
 pub mod handshake_message_server_key_exchange;
@@ -20,12 +24,16 @@ use std::io::{Read, Write};
 
 use handshake_header::*;
 use handshake_message_certificate::*;
+use handshake_message_certificate_dtls13::*;
 use handshake_message_certificate_request::*;
+use handshake_message_certificate_request_dtls13::*;
 use handshake_message_certificate_verify::*;
 use handshake_message_client_hello::*;
 use handshake_message_client_key_exchange::*;
+use handshake_message_encrypted_extensions::*;
 use handshake_message_finished::*;
 use handshake_message_hello_verify_request::*;
+use handshake_message_key_update::*;
 use handshake_message_server_hello::*;
 use handshake_message_server_hello_done::*;
 use handshake_message_server_key_exchange::*;
@@ -40,6 +48,8 @@ pub enum HandshakeType {
     ClientHello = 1,
     ServerHello = 2,
     HelloVerifyRequest = 3,
+    NewSessionTicket = 4,
+    EncryptedExtensions = 8,
     Certificate = 11,
     ServerKeyExchange = 12,
     CertificateRequest = 13,
@@ -47,6 +57,8 @@ pub enum HandshakeType {
     CertificateVerify = 15,
     ClientKeyExchange = 16,
     Finished = 20,
+    KeyUpdate = 24,
+    MessageHash = 254,
     #[default]
     Invalid,
 }
@@ -58,6 +70,8 @@ impl fmt::Display for HandshakeType {
             HandshakeType::ClientHello => write!(f, "ClientHello"),
             HandshakeType::ServerHello => write!(f, "ServerHello"),
             HandshakeType::HelloVerifyRequest => write!(f, "HelloVerifyRequest"),
+            HandshakeType::NewSessionTicket => write!(f, "NewSessionTicket"),
+            HandshakeType::EncryptedExtensions => write!(f, "EncryptedExtensions"),
             HandshakeType::Certificate => write!(f, "Certificate"),
             HandshakeType::ServerKeyExchange => write!(f, "ServerKeyExchange"),
             HandshakeType::CertificateRequest => write!(f, "CertificateRequest"),
@@ -65,6 +79,8 @@ impl fmt::Display for HandshakeType {
             HandshakeType::CertificateVerify => write!(f, "CertificateVerify"),
             HandshakeType::ClientKeyExchange => write!(f, "ClientKeyExchange"),
             HandshakeType::Finished => write!(f, "Finished"),
+            HandshakeType::KeyUpdate => write!(f, "KeyUpdate"),
+            HandshakeType::MessageHash => write!(f, "MessageHash"),
             HandshakeType::Invalid => write!(f, "Invalid"),
         }
     }
@@ -77,6 +93,8 @@ impl From<u8> for HandshakeType {
             1 => HandshakeType::ClientHello,
             2 => HandshakeType::ServerHello,
             3 => HandshakeType::HelloVerifyRequest,
+            4 => HandshakeType::NewSessionTicket,
+            8 => HandshakeType::EncryptedExtensions,
             11 => HandshakeType::Certificate,
             12 => HandshakeType::ServerKeyExchange,
             13 => HandshakeType::CertificateRequest,
@@ -84,6 +102,8 @@ impl From<u8> for HandshakeType {
             15 => HandshakeType::CertificateVerify,
             16 => HandshakeType::ClientKeyExchange,
             20 => HandshakeType::Finished,
+            24 => HandshakeType::KeyUpdate,
+            254 => HandshakeType::MessageHash,
             _ => HandshakeType::Invalid,
         }
     }
@@ -102,6 +122,10 @@ pub enum HandshakeMessage {
     CertificateVerify(HandshakeMessageCertificateVerify),
     ClientKeyExchange(HandshakeMessageClientKeyExchange),
     Finished(HandshakeMessageFinished),
+    EncryptedExtensions(HandshakeMessageEncryptedExtensions),
+    CertificateDtls13(HandshakeMessageCertificateDtls13),
+    CertificateRequestDtls13(HandshakeMessageCertificateRequestDtls13),
+    KeyUpdate(HandshakeMessageKeyUpdate),
 }
 
 impl HandshakeMessage {
@@ -117,6 +141,10 @@ impl HandshakeMessage {
             HandshakeMessage::CertificateVerify(msg) => msg.handshake_type(),
             HandshakeMessage::ClientKeyExchange(msg) => msg.handshake_type(),
             HandshakeMessage::Finished(msg) => msg.handshake_type(),
+            HandshakeMessage::EncryptedExtensions(msg) => msg.handshake_type(),
+            HandshakeMessage::CertificateDtls13(msg) => msg.handshake_type(),
+            HandshakeMessage::CertificateRequestDtls13(msg) => msg.handshake_type(),
+            HandshakeMessage::KeyUpdate(msg) => msg.handshake_type(),
         }
     }
 
@@ -132,6 +160,10 @@ impl HandshakeMessage {
             HandshakeMessage::CertificateVerify(msg) => msg.size(),
             HandshakeMessage::ClientKeyExchange(msg) => msg.size(),
             HandshakeMessage::Finished(msg) => msg.size(),
+            HandshakeMessage::EncryptedExtensions(msg) => msg.size(),
+            HandshakeMessage::CertificateDtls13(msg) => msg.size(),
+            HandshakeMessage::CertificateRequestDtls13(msg) => msg.size(),
+            HandshakeMessage::KeyUpdate(msg) => msg.size(),
         }
     }
 
@@ -147,6 +179,10 @@ impl HandshakeMessage {
             HandshakeMessage::CertificateVerify(msg) => msg.marshal(writer)?,
             HandshakeMessage::ClientKeyExchange(msg) => msg.marshal(writer)?,
             HandshakeMessage::Finished(msg) => msg.marshal(writer)?,
+            HandshakeMessage::EncryptedExtensions(msg) => msg.marshal(writer)?,
+            HandshakeMessage::CertificateDtls13(msg) => msg.marshal(writer)?,
+            HandshakeMessage::CertificateRequestDtls13(msg) => msg.marshal(writer)?,
+            HandshakeMessage::KeyUpdate(msg) => msg.marshal(writer)?,
         }
 
         Ok(())
@@ -235,4 +271,38 @@ impl Handshake {
             handshake_message,
         })
     }
+
+    // unmarshal_dtls13 parses a handshake message protected with DTLS 1.3
+    // keys. Certificate and CertificateRequest have a different layout than
+    // in DTLS 1.2 and messages that only exist in DTLS 1.2 are rejected.
+    pub fn unmarshal_dtls13<R: Read>(reader: &mut R) -> Result<Self> {
+        let handshake_header = HandshakeHeader::unmarshal(reader)?;
+
+        let handshake_message = match handshake_header.handshake_type {
+            HandshakeType::EncryptedExtensions => HandshakeMessage::EncryptedExtensions(
+                HandshakeMessageEncryptedExtensions::unmarshal(reader)?,
+            ),
+            HandshakeType::Certificate => HandshakeMessage::CertificateDtls13(
+                HandshakeMessageCertificateDtls13::unmarshal(reader)?,
+            ),
+            HandshakeType::CertificateRequest => HandshakeMessage::CertificateRequestDtls13(
+                HandshakeMessageCertificateRequestDtls13::unmarshal(reader)?,
+            ),
+            HandshakeType::CertificateVerify => HandshakeMessage::CertificateVerify(
+                HandshakeMessageCertificateVerify::unmarshal(reader)?,
+            ),
+            HandshakeType::Finished => {
+                HandshakeMessage::Finished(HandshakeMessageFinished::unmarshal(reader)?)
+            }
+            HandshakeType::KeyUpdate => {
+                HandshakeMessage::KeyUpdate(HandshakeMessageKeyUpdate::unmarshal(reader)?)
+            }
+            _ => return Err(Error::ErrNotImplemented),
+        };
+
+        Ok(Handshake {
+            handshake_header,
+            handshake_message,
+        })
+    }
 }
@@ -92,6 +92,8 @@ pub(crate) struct HandshakeConfig {
     pub(crate) client_cert_verifier: Option<Arc<dyn rustls::server::ClientCertVerifier>>,
     pub(crate) retransmit_interval: tokio::time::Duration,
     pub(crate) initial_epoch: u16,
+    pub(crate) max_version: DtlsVersion,
+    pub(crate) connection_id_generator: Option<ConnectionIdGenerator>,
     //log           logging.LeveledLogger
     //mu sync.Mutex
 }
@@ -119,11 +121,17 @@ impl Default for HandshakeConfig {
             client_cert_verifier: None,
             retransmit_interval: tokio::time::Duration::from_secs(0),
             initial_epoch: 0,
+            max_version: DtlsVersion::Dtls12,
+            connection_id_generator: None,
         }
     }
 }
 
 impl HandshakeConfig {
+    pub(crate) fn dtls13_enabled(&self) -> bool {
+        self.max_version >= DtlsVersion::Dtls13
+    }
+
     pub(crate) fn get_certificate(&self, server_name: &str) -> Result<Certificate> {
         //TODO
         /*if self.name_to_certificate.is_empty() {
@@ -209,6 +217,9 @@ impl DTLSConn {
             );
 
             if state == HandshakeState::Finished && !self.is_handshake_completed_successfully() {
+                // Post-handshake messages continue the message sequence of the handshake.
+                self.state.ack_state.lock().await.next_message_sequence =
+                    self.state.handshake_send_sequence as u16;
                 self.set_handshake_completed_successfully();
                 self.handshake_done_tx.take(); // drop it by take
                 return Ok(());
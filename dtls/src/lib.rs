@@ -1,6 +1,7 @@
 #![warn(rust_2018_idioms)]
 #![allow(dead_code)]
 
+pub mod ack;
 pub mod alert;
 pub mod application_data;
 pub mod change_cipher_spec;
@@ -1,3 +1,6 @@
+#[cfg(test)]
+mod listener_test;
+
 use std::future::Future;
 use std::io::BufReader;
 use std::net::SocketAddr;
@@ -13,6 +16,10 @@ use crate::config::*;
 use crate::conn::DTLSConn;
 use crate::content::ContentType;
 use crate::error::Result;
+use crate::extension::Extension;
+use crate::handshake::handshake_header::HandshakeHeader;
+use crate::handshake::handshake_message_server_hello::HandshakeMessageServerHello;
+use crate::handshake::HandshakeType;
 use crate::record_layer::record_layer_header::RecordLayerHeader;
 use crate::record_layer::unpack_datagram;
 
@@ -23,7 +30,7 @@ pub async fn listen<A: 'static + ToSocketAddrs>(laddr: A, config: Config) -> Res
     let mut lc = ListenConfig {
         accept_filter: Some(Box::new(
             |packet: &[u8]| -> Pin<Box<dyn Future<Output = bool> + Send + 'static>> {
-                let pkts = match unpack_datagram(packet) {
+                let pkts = match unpack_datagram(packet, 0) {
                     Ok(pkts) => {
                         if pkts.is_empty() {
                             return Box::pin(async { false });
@@ -46,10 +53,67 @@ pub async fn listen<A: 'static + ToSocketAddrs>(laddr: A, config: Config) -> Res
         ..Default::default()
     };
 
+    // Records carrying a connection ID are routed to their connection even
+    // if the remote address changed.
+    if let Some(connection_id_generator) = &config.connection_id_generator {
+        let connection_id_len = connection_id_generator().len();
+        if connection_id_len > 0 {
+            lc.datagram_router = Some(Box::new(move |packet: &[u8]| {
+                connection_id_datagram_router(packet, connection_id_len)
+            }));
+            lc.connection_identifier = Some(Arc::new(connection_id_identifier));
+        }
+    }
+
     let parent = Arc::new(lc.listen(laddr).await?);
     Ok(DTLSListener { parent, config })
 }
 
+// connection_id_datagram_router returns the connection ID of the first
+// record of the datagram carrying one.
+fn connection_id_datagram_router(packet: &[u8], connection_id_len: usize) -> Option<String> {
+    let pkts = unpack_datagram(packet, connection_id_len).ok()?;
+    pkts.iter()
+        .filter(|pkt| pkt.first() == Some(&(ContentType::ConnectionId as u8)))
+        .find_map(|pkt| {
+            let mut reader = BufReader::new(pkt.as_slice());
+            RecordLayerHeader::unmarshal_with_connection_id(&mut reader, connection_id_len)
+                .ok()
+                .map(|h| connection_id_key(&h.connection_id))
+        })
+}
+
+// connection_id_identifier returns the connection ID the ServerHello of the
+// datagram assigns to the connection.
+fn connection_id_identifier(packet: &[u8]) -> Option<String> {
+    let pkts = unpack_datagram(packet, 0).ok()?;
+    pkts.iter().find_map(|pkt| {
+        let mut reader = BufReader::new(pkt.as_slice());
+        let h = RecordLayerHeader::unmarshal(&mut reader).ok()?;
+        if h.content_type != ContentType::Handshake || h.epoch != 0 {
+            return None;
+        }
+        let handshake_header = HandshakeHeader::unmarshal(&mut reader).ok()?;
+        if handshake_header.handshake_type != HandshakeType::ServerHello {
+            return None;
+        }
+        let server_hello = HandshakeMessageServerHello::unmarshal(&mut reader).ok()?;
+        server_hello
+            .extensions
+            .iter()
+            .find_map(|extension| match extension {
+                Extension::ConnectionId(e) if !e.connection_id.is_empty() => {
+                    Some(connection_id_key(&e.connection_id))
+                }
+                _ => None,
+            })
+    })
+}
+
+fn connection_id_key(connection_id: &[u8]) -> String {
+    connection_id.iter().map(|b| format!("{b:02x}")).collect()
+}
+
 /// DTLSListener represents a DTLS listener
 pub struct DTLSListener {
     parent: Arc<dyn Listener + Send + Sync>,
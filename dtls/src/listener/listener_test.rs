@@ -0,0 +1,112 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tokio::net::UdpSocket;
+
+use super::*;
+use crate::crypto::Certificate;
+
+// A UDP proxy between the client and the listener whose address towards the
+// listener changes when rebind is set, like a NAT rebinding.
+struct RebindingProxy {
+    addr: SocketAddr,
+    rebind: Arc<AtomicBool>,
+}
+
+impl RebindingProxy {
+    async fn new(client_addr: SocketAddr, server_addr: SocketAddr) -> Result<Self> {
+        let downstream = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+        let upstreams = [
+            Arc::new(UdpSocket::bind("127.0.0.1:0").await?),
+            Arc::new(UdpSocket::bind("127.0.0.1:0").await?),
+        ];
+        let rebind = Arc::new(AtomicBool::new(false));
+
+        for upstream in &upstreams {
+            let (upstream, downstream) = (Arc::clone(upstream), Arc::clone(&downstream));
+            tokio::spawn(async move {
+                let mut buf = vec![0u8; 8192];
+                while let Ok(n) = upstream.recv(&mut buf).await {
+                    let _ = downstream.send_to(&buf[..n], client_addr).await;
+                }
+            });
+        }
+
+        let addr = downstream.local_addr()?;
+        let rebind2 = Arc::clone(&rebind);
+        tokio::spawn(async move {
+            let mut buf = vec![0u8; 8192];
+            while let Ok(n) = downstream.recv(&mut buf).await {
+                let upstream = &upstreams[rebind2.load(Ordering::SeqCst) as usize];
+                let _ = upstream.send_to(&buf[..n], server_addr).await;
+            }
+        });
+
+        Ok(RebindingProxy { addr, rebind })
+    }
+}
+
+async fn echo(client: &DTLSConn, server: &Arc<dyn Conn + Send + Sync>, msg: &[u8]) -> Result<()> {
+    let timeout = Some(Duration::from_secs(5));
+    let mut buf = vec![0u8; 1024];
+
+    client.write(msg, timeout).await?;
+    let n = server.recv(&mut buf).await?;
+    assert_eq!(&buf[..n], msg);
+
+    server.send(msg).await?;
+    let n = client.read(&mut buf, timeout).await?;
+    assert_eq!(&buf[..n], msg);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_listener_connection_id_rebinding() -> Result<()> {
+    let listener = listen(
+        "127.0.0.1:0",
+        Config {
+            certificates: vec![Certificate::generate_self_signed(vec![
+                "localhost".to_owned()
+            ])?],
+            connection_id_generator: Some(random_connection_id_generator(8)),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let client_socket = UdpSocket::bind("127.0.0.1:0").await?;
+    let proxy = RebindingProxy::new(client_socket.local_addr()?, listener.addr().await?).await?;
+    client_socket.connect(proxy.addr).await?;
+
+    let client = tokio::spawn(async move {
+        DTLSConn::new(
+            Arc::new(client_socket),
+            Config {
+                insecure_skip_verify: true,
+                connection_id_generator: Some(only_send_connection_id_generator()),
+                ..Default::default()
+            },
+            true,
+            None,
+        )
+        .await
+    });
+    let (server, server_raddr) = listener.accept().await?;
+    let client = client.await.unwrap()?;
+
+    echo(&client, &server, b"before rebinding").await?;
+
+    proxy.rebind.store(true, Ordering::SeqCst);
+    echo(&client, &server, b"after rebinding").await?;
+    assert_ne!(server.remote_addr(), Some(server_raddr));
+
+    let accepted = tokio::time::timeout(Duration::from_millis(50), listener.accept()).await;
+    assert!(accepted.is_err(), "unexpected connection accepted");
+
+    client.close().await?;
+    server.close().await?;
+    listener.close().await?;
+
+    Ok(())
+}
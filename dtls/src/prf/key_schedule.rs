@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod key_schedule_test;
+
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::error::*;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// DTLS 1.3 uses the TLS 1.3 key schedule with its own label prefix.
+// https://www.rfc-editor.org/rfc/rfc9147#section-5.9
+const DTLS13_LABEL_PREFIX: &[u8] = b"dtls13";
+
+pub(crate) const DERIVED_LABEL: &str = "derived";
+pub(crate) const CLIENT_HANDSHAKE_TRAFFIC_LABEL: &str = "c hs traffic";
+pub(crate) const SERVER_HANDSHAKE_TRAFFIC_LABEL: &str = "s hs traffic";
+pub(crate) const CLIENT_APPLICATION_TRAFFIC_LABEL: &str = "c ap traffic";
+pub(crate) const SERVER_APPLICATION_TRAFFIC_LABEL: &str = "s ap traffic";
+pub(crate) const EXPORTER_MASTER_LABEL: &str = "exp master";
+pub(crate) const FINISHED_LABEL: &str = "finished";
+pub(crate) const TRAFFIC_UPDATE_LABEL: &str = "traffic upd";
+pub(crate) const EXPORTER_LABEL: &str = "exporter";
+pub(crate) const KEY_LABEL: &str = "key";
+pub(crate) const IV_LABEL: &str = "iv";
+pub(crate) const SN_LABEL: &str = "sn";
+
+pub(crate) const HASH_LENGTH: usize = 32;
+
+// Traffic keys derived from a traffic secret: the AEAD key and IV, and the
+// key protecting the record sequence numbers.
+// https://www.rfc-editor.org/rfc/rfc9147#section-4.2.3
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub(crate) struct TrafficKeys {
+    pub(crate) key: Vec<u8>,
+    pub(crate) iv: Vec<u8>,
+    pub(crate) sn_key: Vec<u8>,
+}
+
+pub(crate) fn transcript_hash(transcript: &[u8]) -> Vec<u8> {
+    Sha256::digest(transcript).to_vec()
+}
+
+pub(crate) fn hkdf_extract(salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+    let (prk, _) = Hkdf::<Sha256>::extract(Some(salt), ikm);
+    prk.to_vec()
+}
+
+// https://www.rfc-editor.org/rfc/rfc8446#section-7.1
+fn expand_label(
+    secret: &[u8],
+    prefix: &[u8],
+    label: &str,
+    context: &[u8],
+    length: usize,
+) -> Result<Vec<u8>> {
+    let hk = Hkdf::<Sha256>::from_prk(secret).map_err(|e| Error::Other(e.to_string()))?;
+
+    let mut info = vec![];
+    info.extend_from_slice(&(length as u16).to_be_bytes());
+    info.push((prefix.len() + label.len()) as u8);
+    info.extend_from_slice(prefix);
+    info.extend_from_slice(label.as_bytes());
+    info.push(context.len() as u8);
+    info.extend_from_slice(context);
+
+    let mut okm = vec![0u8; length];
+    hk.expand(&info, &mut okm)
+        .map_err(|e| Error::Other(e.to_string()))?;
+    Ok(okm)
+}
+
+pub(crate) fn hkdf_expand_label(
+    secret: &[u8],
+    label: &str,
+    context: &[u8],
+    length: usize,
+) -> Result<Vec<u8>> {
+    expand_label(secret, DTLS13_LABEL_PREFIX, label, context, length)
+}
+
+// derive_secret takes the hash of the transcript, not the messages themselves.
+pub(crate) fn derive_secret(secret: &[u8], label: &str, transcript_hash: &[u8]) -> Result<Vec<u8>> {
+    hkdf_expand_label(secret, label, transcript_hash, HASH_LENGTH)
+}
+
+// handshake_secret runs the key schedule without PSK up to the
+// Handshake Secret for the given (EC)DHE shared secret.
+pub(crate) fn handshake_secret(shared_secret: &[u8]) -> Result<Vec<u8>> {
+    let early_secret = hkdf_extract(&[0u8; HASH_LENGTH], &[0u8; HASH_LENGTH]);
+    let derived = derive_secret(&early_secret, DERIVED_LABEL, &transcript_hash(&[]))?;
+    Ok(hkdf_extract(&derived, shared_secret))
+}
+
+pub(crate) fn master_secret(handshake_secret: &[u8]) -> Result<Vec<u8>> {
+    let derived = derive_secret(handshake_secret, DERIVED_LABEL, &transcript_hash(&[]))?;
+    Ok(hkdf_extract(&derived, &[0u8; HASH_LENGTH]))
+}
+
+pub(crate) fn traffic_keys(
+    traffic_secret: &[u8],
+    key_len: usize,
+    iv_len: usize,
+) -> Result<TrafficKeys> {
+    Ok(TrafficKeys {
+        key: hkdf_expand_label(traffic_secret, KEY_LABEL, &[], key_len)?,
+        iv: hkdf_expand_label(traffic_secret, IV_LABEL, &[], iv_len)?,
+        sn_key: hkdf_expand_label(traffic_secret, SN_LABEL, &[], key_len)?,
+    })
+}
+
+// https://www.rfc-editor.org/rfc/rfc8446#section-4.4.4
+pub(crate) fn finished_verify_data(
+    traffic_secret: &[u8],
+    transcript_hash: &[u8],
+) -> Result<Vec<u8>> {
+    let finished_key = hkdf_expand_label(traffic_secret, FINISHED_LABEL, &[], HASH_LENGTH)?;
+
+    let mut mac =
+        HmacSha256::new_from_slice(&finished_key).map_err(|e| Error::Other(e.to_string()))?;
+    mac.update(transcript_hash);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+// https://www.rfc-editor.org/rfc/rfc8446#section-7.2
+pub(crate) fn next_traffic_secret(traffic_secret: &[u8]) -> Result<Vec<u8>> {
+    hkdf_expand_label(traffic_secret, TRAFFIC_UPDATE_LABEL, &[], HASH_LENGTH)
+}
+
+// https://www.rfc-editor.org/rfc/rfc8446#section-7.5
+pub(crate) fn export_keying_material(
+    exporter_master_secret: &[u8],
+    label: &str,
+    context: &[u8],
+    length: usize,
+) -> Result<Vec<u8>> {
+    let secret = derive_secret(exporter_master_secret, label, &transcript_hash(&[]))?;
+    hkdf_expand_label(&secret, EXPORTER_LABEL, &transcript_hash(context), length)
+}
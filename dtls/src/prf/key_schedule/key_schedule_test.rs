@@ -0,0 +1,100 @@
+use super::*;
+
+// Test vectors from the simple 1-RTT handshake of RFC 8448, which uses the
+// "tls13 " label prefix instead of the DTLS 1.3 one.
+// https://www.rfc-editor.org/rfc/rfc8448#section-3
+const TLS13_LABEL_PREFIX: &[u8] = b"tls13 ";
+
+fn tls13_derive_secret(secret: &[u8], label: &str, messages: &[u8]) -> Result<Vec<u8>> {
+    expand_label(
+        secret,
+        TLS13_LABEL_PREFIX,
+        label,
+        &transcript_hash(messages),
+        HASH_LENGTH,
+    )
+}
+
+#[test]
+fn test_tls13_key_schedule() -> Result<()> {
+    let early_secret = hkdf_extract(&[0u8; HASH_LENGTH], &[0u8; HASH_LENGTH]);
+    assert_eq!(
+        early_secret,
+        vec![
+            0x33, 0xad, 0x0a, 0x1c, 0x60, 0x7e, 0xc0, 0x3b, 0x09, 0xe6, 0xcd, 0x98, 0x93, 0x68,
+            0x0c, 0xe2, 0x10, 0xad, 0xf3, 0x00, 0xaa, 0x1f, 0x26, 0x60, 0xe1, 0xb2, 0x2e, 0x10,
+            0xf1, 0x70, 0xf9, 0x2a,
+        ]
+    );
+
+    let derived = tls13_derive_secret(&early_secret, DERIVED_LABEL, &[])?;
+    assert_eq!(
+        derived,
+        vec![
+            0x6f, 0x26, 0x15, 0xa1, 0x08, 0xc7, 0x02, 0xc5, 0x67, 0x8f, 0x54, 0xfc, 0x9d, 0xba,
+            0xb6, 0x97, 0x16, 0xc0, 0x76, 0x18, 0x9c, 0x48, 0x25, 0x0c, 0xeb, 0xea, 0xc3, 0x57,
+            0x6c, 0x36, 0x11, 0xba,
+        ]
+    );
+
+    let shared_secret = [
+        0x8b, 0xd4, 0x05, 0x4f, 0xb5, 0x5b, 0x9d, 0x63, 0xfd, 0xfb, 0xac, 0xf9, 0xf0, 0x4b, 0x9f,
+        0x0d, 0x35, 0xe6, 0xd6, 0x3f, 0x53, 0x75, 0x63, 0xef, 0xd4, 0x62, 0x72, 0x90, 0x0f, 0x89,
+        0x49, 0x2d,
+    ];
+    let handshake_secret = hkdf_extract(&derived, &shared_secret);
+    assert_eq!(
+        handshake_secret,
+        vec![
+            0x1d, 0xc8, 0x26, 0xe9, 0x36, 0x06, 0xaa, 0x6f, 0xdc, 0x0a, 0xad, 0xc1, 0x2f, 0x74,
+            0x1b, 0x01, 0x04, 0x6a, 0xa6, 0xb9, 0x9f, 0x69, 0x1e, 0xd2, 0x21, 0xa9, 0xf0, 0xca,
+            0x04, 0x3f, 0xbe, 0xac,
+        ]
+    );
+
+    let derived = tls13_derive_secret(&handshake_secret, DERIVED_LABEL, &[])?;
+    let master_secret = hkdf_extract(&derived, &[0u8; HASH_LENGTH]);
+    assert_eq!(
+        master_secret,
+        vec![
+            0x18, 0xdf, 0x06, 0x84, 0x3d, 0x13, 0xa0, 0x8b, 0xf2, 0xa4, 0x49, 0x84, 0x4c, 0x5f,
+            0x8a, 0x47, 0x80, 0x01, 0xbc, 0x4d, 0x4c, 0x62, 0x79, 0x84, 0xd5, 0xa4, 0x1d, 0xa8,
+            0xd0, 0x40, 0x29, 0x19,
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_dtls13_key_schedule() -> Result<()> {
+    let shared_secret = [0x42u8; 32];
+    let hs = handshake_secret(&shared_secret)?;
+    let ms = master_secret(&hs)?;
+    assert_ne!(hs, ms);
+
+    // The DTLS 1.3 labels must not produce the TLS 1.3 secrets
+    let early_secret = hkdf_extract(&[0u8; HASH_LENGTH], &[0u8; HASH_LENGTH]);
+    let tls13_derived = tls13_derive_secret(&early_secret, DERIVED_LABEL, &[])?;
+    assert_ne!(hs, hkdf_extract(&tls13_derived, &shared_secret));
+
+    let keys = traffic_keys(&ms, 16, 12)?;
+    assert_eq!(keys.key.len(), 16);
+    assert_eq!(keys.iv.len(), 12);
+    assert_eq!(keys.sn_key.len(), 16);
+    assert_ne!(keys.key, keys.sn_key);
+
+    let next = next_traffic_secret(&ms)?;
+    assert_eq!(next.len(), HASH_LENGTH);
+    assert_ne!(next, ms);
+
+    let verify_data = finished_verify_data(&ms, &transcript_hash(b"transcript"))?;
+    assert_eq!(verify_data.len(), HASH_LENGTH);
+
+    let a = export_keying_material(&ms, "EXTRACTOR-dtls_srtp", &[], 60)?;
+    let b = export_keying_material(&ms, "EXTRACTOR-dtls_srtp", &[], 60)?;
+    assert_eq!(a.len(), 60);
+    assert_eq!(a, b);
+
+    Ok(())
+}
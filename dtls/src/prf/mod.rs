@@ -1,3 +1,5 @@
+pub(crate) mod key_schedule;
+
 #[cfg(test)]
 mod prf_test;
 
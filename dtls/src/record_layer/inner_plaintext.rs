@@ -0,0 +1,52 @@
+use std::io::Write;
+
+use crate::content::*;
+use crate::error::*;
+
+/*
+ The plaintext of a record carrying a connection ID. The real content type
+ is sent encrypted after the content, optionally followed by zero padding
+ which hides the length of the content.
+
+ struct {
+     opaque content[length];
+     ContentType real_type;
+     uint8 zeros[length_of_padding];
+ } DTLSInnerPlaintext;
+ https://www.rfc-editor.org/rfc/rfc9146#section-4
+*/
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct InnerPlaintext {
+    pub content: Vec<u8>,
+    pub real_type: ContentType,
+    pub zeros: usize,
+}
+
+impl InnerPlaintext {
+    pub fn size(&self) -> usize {
+        self.content.len() + 1 + self.zeros
+    }
+
+    pub fn marshal<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(&self.content)?;
+        writer.write_all(&[self.real_type as u8])?;
+        writer.write_all(&vec![0u8; self.zeros])?;
+
+        Ok(writer.flush()?)
+    }
+
+    pub fn unmarshal(data: &[u8]) -> Result<Self> {
+        // The real content type is the last non-zero byte
+        let zeros = data.iter().rev().take_while(|b| **b == 0).count();
+        if zeros == data.len() {
+            return Err(Error::ErrInvalidContentType);
+        }
+        let real_type_index = data.len() - zeros - 1;
+
+        Ok(InnerPlaintext {
+            content: data[..real_type_index].to_vec(),
+            real_type: data[real_type_index].into(),
+            zeros,
+        })
+    }
+}
@@ -1,4 +1,6 @@
+pub mod inner_plaintext;
 pub mod record_layer_header;
+pub mod unified_header;
 
 #[cfg(test)]
 mod record_layer_test;
@@ -6,9 +8,11 @@ mod record_layer_test;
 use std::io::{Read, Write};
 
 use record_layer_header::*;
+use unified_header::*;
 
 use super::content::*;
 use super::error::*;
+use crate::ack::Ack;
 use crate::alert::Alert;
 use crate::application_data::ApplicationData;
 use crate::change_cipher_spec::ChangeCipherSpec;
@@ -44,6 +48,7 @@ impl RecordLayer {
                 protocol_version,
                 epoch,
                 sequence_number: 0,
+                connection_id: vec![],
                 content_len: content.size() as u16,
             },
             content,
@@ -67,6 +72,7 @@ impl RecordLayer {
                 Content::ChangeCipherSpec(ChangeCipherSpec::unmarshal(reader)?)
             }
             ContentType::Handshake => Content::Handshake(Handshake::unmarshal(reader)?),
+            ContentType::Ack => Content::Ack(Ack::unmarshal(reader)?),
             _ => return Err(Error::Other("Invalid Content Type".to_owned())),
         };
 
@@ -83,18 +89,46 @@ impl RecordLayer {
 // two DTLS messages into the same datagram: in the same record or in
 // separate records.
 // https://tools.ietf.org/html/rfc6347#section-4.2.3
-pub(crate) fn unpack_datagram(buf: &[u8]) -> Result<Vec<Vec<u8>>> {
+//
+// DTLS 1.3 records using the unified header may omit their length, in which
+// case they extend to the end of the datagram. connection_id_len is the
+// length of the connection ID carried by such records and by tls12_cid records.
+// https://www.rfc-editor.org/rfc/rfc9147#section-4.2
+// https://www.rfc-editor.org/rfc/rfc9146#section-4
+pub(crate) fn unpack_datagram(buf: &[u8], connection_id_len: usize) -> Result<Vec<Vec<u8>>> {
     let mut out = vec![];
 
     let mut offset = 0;
     while buf.len() != offset {
-        if buf.len() - offset <= RECORD_LAYER_HEADER_SIZE {
+        if UnifiedHeader::is_unified_header(buf[offset]) {
+            let mut reader = &buf[offset..];
+            let h = UnifiedHeader::unmarshal(&mut reader, connection_id_len)
+                .map_err(|_| Error::ErrInvalidPacketLength)?;
+            let pkt_len = match h.length {
+                Some(length) => h.size() + length as usize,
+                None => buf.len() - offset,
+            };
+            if offset + pkt_len > buf.len() {
+                return Err(Error::ErrInvalidPacketLength);
+            }
+
+            out.push(buf[offset..offset + pkt_len].to_vec());
+            offset += pkt_len;
+            continue;
+        }
+
+        let header_size = if buf[offset] == ContentType::ConnectionId as u8 {
+            RECORD_LAYER_HEADER_SIZE + connection_id_len
+        } else {
+            RECORD_LAYER_HEADER_SIZE
+        };
+        if buf.len() - offset <= header_size {
             return Err(Error::ErrInvalidPacketLength);
         }
 
-        let pkt_len = RECORD_LAYER_HEADER_SIZE
-            + (((buf[offset + RECORD_LAYER_HEADER_SIZE - 2] as usize) << 8)
-                | buf[offset + RECORD_LAYER_HEADER_SIZE - 1] as usize);
+        let pkt_len = header_size
+            + (((buf[offset + header_size - 2] as usize) << 8)
+                | buf[offset + header_size - 1] as usize);
         if offset + pkt_len > buf.len() {
             return Err(Error::ErrInvalidPacketLength);
         }
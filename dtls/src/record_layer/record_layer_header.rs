@@ -11,12 +11,16 @@ pub const MAX_SEQUENCE_NUMBER: u64 = 0x0000FFFFFFFFFFFF;
 pub const DTLS1_2MAJOR: u8 = 0xfe;
 pub const DTLS1_2MINOR: u8 = 0xfd;
 
+pub const DTLS1_3MAJOR: u8 = 0xfe;
+pub const DTLS1_3MINOR: u8 = 0xfc;
+
 pub const DTLS1_0MAJOR: u8 = 0xfe;
 pub const DTLS1_0MINOR: u8 = 0xff;
 
 // VERSION_DTLS12 is the DTLS version in the same style as
 // VersionTLSXX from crypto/tls
 pub const VERSION_DTLS12: u16 = 0xfefd;
+pub const VERSION_DTLS13: u16 = 0xfefc;
 
 pub const PROTOCOL_VERSION1_0: ProtocolVersion = ProtocolVersion {
     major: DTLS1_0MAJOR,
@@ -26,6 +30,12 @@ pub const PROTOCOL_VERSION1_2: ProtocolVersion = ProtocolVersion {
     major: DTLS1_2MAJOR,
     minor: DTLS1_2MINOR,
 };
+// DTLS 1.3 is only ever negotiated with the supported_versions extension,
+// the record layer keeps using PROTOCOL_VERSION1_2 as legacy version.
+pub const PROTOCOL_VERSION1_3: ProtocolVersion = ProtocolVersion {
+    major: DTLS1_3MAJOR,
+    minor: DTLS1_3MINOR,
+};
 
 // https://tools.ietf.org/html/rfc4346#section-6.2.1
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
@@ -34,16 +44,28 @@ pub struct ProtocolVersion {
     pub minor: u8,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+// Records with the tls12_cid content type carry a connection ID between the
+// sequence number and the length.
+// https://www.rfc-editor.org/rfc/rfc9146#section-4
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub struct RecordLayerHeader {
     pub content_type: ContentType,
     pub protocol_version: ProtocolVersion,
     pub epoch: u16,
     pub sequence_number: u64, // uint48 in spec
+    pub connection_id: Vec<u8>,
     pub content_len: u16,
 }
 
 impl RecordLayerHeader {
+    pub fn size(&self) -> usize {
+        if self.content_type == ContentType::ConnectionId {
+            RECORD_LAYER_HEADER_SIZE + self.connection_id.len()
+        } else {
+            RECORD_LAYER_HEADER_SIZE
+        }
+    }
+
     pub fn marshal<W: Write>(&self, writer: &mut W) -> Result<()> {
         if self.sequence_number > MAX_SEQUENCE_NUMBER {
             return Err(Error::ErrSequenceNumberOverflow);
@@ -57,12 +79,25 @@ impl RecordLayerHeader {
         let be: [u8; 8] = self.sequence_number.to_be_bytes();
         writer.write_all(&be[2..])?; // uint48 in spec
 
+        if self.content_type == ContentType::ConnectionId {
+            writer.write_all(&self.connection_id)?;
+        }
+
         writer.write_u16::<BigEndian>(self.content_len)?;
 
         Ok(writer.flush()?)
     }
 
     pub fn unmarshal<R: Read>(reader: &mut R) -> Result<Self> {
+        RecordLayerHeader::unmarshal_with_connection_id(reader, 0)
+    }
+
+    // The length of the connection ID is not part of the header, it is the
+    // length the receiver asked its peer to use.
+    pub fn unmarshal_with_connection_id<R: Read>(
+        reader: &mut R,
+        connection_id_len: usize,
+    ) -> Result<Self> {
         let content_type = reader.read_u8()?.into();
         let major = reader.read_u8()?;
         let minor = reader.read_u8()?;
@@ -77,6 +112,12 @@ impl RecordLayerHeader {
         if protocol_version != PROTOCOL_VERSION1_0 && protocol_version != PROTOCOL_VERSION1_2 {
             return Err(Error::ErrUnsupportedProtocolVersion);
         }
+
+        let mut connection_id = vec![];
+        if content_type == ContentType::ConnectionId {
+            connection_id = vec![0u8; connection_id_len];
+            reader.read_exact(&mut connection_id)?;
+        }
         let content_len = reader.read_u16::<BigEndian>()?;
 
         Ok(RecordLayerHeader {
@@ -84,6 +125,7 @@ impl RecordLayerHeader {
             protocol_version,
             epoch,
             sequence_number,
+            connection_id,
             content_len,
         })
     }
@@ -1,5 +1,6 @@
 use std::io::{BufReader, BufWriter};
 
+use super::inner_plaintext::*;
 use super::record_layer_header::*;
 use super::*;
 use crate::change_cipher_spec::ChangeCipherSpec;
@@ -35,6 +36,42 @@ fn test_udp_decode() -> Result<()> {
             ],
             None,
         ),
+        (
+            "Unified header with length followed by unified header without length",
+            vec![
+                0x2e, 0x00, 0x01, 0x00, 0x02, 0xaa, 0xbb, 0x22, 0x00, 0xcc, 0xdd, 0xee,
+            ],
+            vec![
+                vec![0x2e, 0x00, 0x01, 0x00, 0x02, 0xaa, 0xbb],
+                vec![0x22, 0x00, 0xcc, 0xdd, 0xee],
+            ],
+            None,
+        ),
+        (
+            "Unified header declared invalid length",
+            vec![0x2e, 0x00, 0x01, 0x00, 0x09, 0xaa, 0xbb],
+            vec![],
+            Some(Error::ErrInvalidPacketLength),
+        ),
+        (
+            "Connection ID record followed by Change Cipher Spec",
+            vec![
+                0x19, 0xfe, 0xfd, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0xaa, 0xbb, 0x00,
+                0x02, 0xcc, 0xdd, 0x14, 0xfe, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x12,
+                0x00, 0x01, 0x01,
+            ],
+            vec![
+                vec![
+                    0x19, 0xfe, 0xfd, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0xaa, 0xbb,
+                    0x00, 0x02, 0xcc, 0xdd,
+                ],
+                vec![
+                    0x14, 0xfe, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x12, 0x00, 0x01,
+                    0x01,
+                ],
+            ],
+            None,
+        ),
         (
             "Invalid packet length",
             vec![0x14, 0xfe],
@@ -52,7 +89,7 @@ fn test_udp_decode() -> Result<()> {
     ];
 
     for (name, data, wanted, wanted_err) in tests {
-        let dtls_pkts = unpack_datagram(&data);
+        let dtls_pkts = unpack_datagram(&data, 2);
         if let Some(err) = wanted_err {
             if let Err(dtls) = dtls_pkts {
                 assert_eq!(err.to_string(), dtls.to_string());
@@ -88,6 +125,7 @@ fn test_record_layer_round_trip() -> Result<()> {
                 },
                 epoch: 0,
                 sequence_number: 18,
+                connection_id: vec![],
                 content_len: 1,
             },
             content: Content::ChangeCipherSpec(ChangeCipherSpec {}),
@@ -116,3 +154,137 @@ fn test_record_layer_round_trip() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_record_layer_header_connection_id_round_trip() -> Result<()> {
+    let data = vec![
+        0x19, 0xfe, 0xfd, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x01, 0x02, 0x03, 0x00,
+        0x20,
+    ];
+    let want = RecordLayerHeader {
+        content_type: ContentType::ConnectionId,
+        protocol_version: PROTOCOL_VERSION1_2,
+        epoch: 1,
+        sequence_number: 5,
+        connection_id: vec![0x01, 0x02, 0x03],
+        content_len: 0x20,
+    };
+
+    let mut reader = BufReader::new(data.as_slice());
+    let h = RecordLayerHeader::unmarshal_with_connection_id(&mut reader, 3)?;
+    assert_eq!(
+        want, h,
+        "recordLayerHeader.unmarshal: got {h:?}, want {want:?}"
+    );
+    assert_eq!(h.size(), data.len(), "recordLayerHeader.size");
+
+    let mut data2 = vec![];
+    {
+        let mut writer = BufWriter::<&mut Vec<u8>>::new(data2.as_mut());
+        h.marshal(&mut writer)?;
+    }
+    assert_eq!(
+        data, data2,
+        "recordLayerHeader.marshal: got {data2:?}, want {data:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_inner_plaintext_round_trip() -> Result<()> {
+    let want = InnerPlaintext {
+        content: vec![0x01, 0x02, 0x00],
+        real_type: ContentType::ApplicationData,
+        zeros: 2,
+    };
+    let data = vec![0x01, 0x02, 0x00, 0x17, 0x00, 0x00];
+
+    let mut data2 = vec![];
+    {
+        let mut writer = BufWriter::<&mut Vec<u8>>::new(data2.as_mut());
+        want.marshal(&mut writer)?;
+    }
+    assert_eq!(data, data2, "innerPlaintext.marshal");
+    assert_eq!(want.size(), data.len(), "innerPlaintext.size");
+
+    let p = InnerPlaintext::unmarshal(&data)?;
+    assert_eq!(p.content, want.content, "innerPlaintext.unmarshal content");
+    assert_eq!(p.real_type, want.real_type, "innerPlaintext.unmarshal type");
+
+    assert_eq!(
+        InnerPlaintext::unmarshal(&[0x00, 0x00]).err(),
+        Some(Error::ErrInvalidContentType)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_unified_header_round_trip() -> Result<()> {
+    let tests = vec![
+        (
+            "16 bit sequence number and length",
+            vec![0x2e, 0x12, 0x34, 0x00, 0x20],
+            0,
+            UnifiedHeader {
+                connection_id: vec![],
+                epoch_bits: 2,
+                sequence_number_bits: 0x1234,
+                sequence_number_16_bit: true,
+                length: Some(0x20),
+            },
+        ),
+        (
+            "8 bit sequence number without length",
+            vec![0x23, 0x56],
+            0,
+            UnifiedHeader {
+                connection_id: vec![],
+                epoch_bits: 3,
+                sequence_number_bits: 0x56,
+                sequence_number_16_bit: false,
+                length: None,
+            },
+        ),
+        (
+            "Connection ID",
+            vec![0x3d, 0x01, 0x02, 0x03, 0x04, 0x00, 0x01, 0x00, 0x10],
+            4,
+            UnifiedHeader {
+                connection_id: vec![0x01, 0x02, 0x03, 0x04],
+                epoch_bits: 1,
+                sequence_number_bits: 0x0001,
+                sequence_number_16_bit: true,
+                length: Some(0x10),
+            },
+        ),
+    ];
+
+    for (name, data, connection_id_len, want) in tests {
+        let mut reader = BufReader::new(data.as_slice());
+        let h = UnifiedHeader::unmarshal(&mut reader, connection_id_len)?;
+        assert_eq!(
+            want, h,
+            "{name} unifiedHeader.unmarshal: got {h:?}, want {want:?}"
+        );
+        assert_eq!(h.size(), data.len(), "{name} unifiedHeader.size");
+
+        let mut data2 = vec![];
+        {
+            let mut writer = BufWriter::<&mut Vec<u8>>::new(data2.as_mut());
+            h.marshal(&mut writer)?;
+        }
+        assert_eq!(
+            data, data2,
+            "{name} unifiedHeader.marshal: got {data2:?}, want {data:?}"
+        );
+    }
+
+    assert!(!UnifiedHeader::is_unified_header(
+        ContentType::Handshake as u8
+    ));
+    assert!(!UnifiedHeader::is_unified_header(ContentType::Ack as u8));
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use std::io::{Read, Write};
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+use crate::error::*;
+
+const UNIFIED_HEADER_FIXED_BITS: u8 = 0b0010_0000;
+const UNIFIED_HEADER_FIXED_MASK: u8 = 0b1110_0000;
+const UNIFIED_HEADER_CONNECTION_ID_BIT: u8 = 0b0001_0000;
+const UNIFIED_HEADER_SEQUENCE_NUMBER_16_BIT: u8 = 0b0000_1000;
+const UNIFIED_HEADER_LENGTH_BIT: u8 = 0b0000_0100;
+const UNIFIED_HEADER_EPOCH_MASK: u8 = 0b0000_0011;
+
+/*
+ DTLS 1.3 protects records with the unified header. Only the low bits of
+ the epoch and of the sequence number are sent, the sequence number bits
+ are additionally encrypted.
+
+  0 1 2 3 4 5 6 7
+ +-+-+-+-+-+-+-+-+
+ |0|0|1|C|S|L|E E|
+ +-+-+-+-+-+-+-+-+
+ | Connection ID |   Legend:
+ | (if any,      |
+ /  length as    /   C   - Connection ID (CID) present
+ |  negotiated)  |   S   - Sequence number length
+ +-+-+-+-+-+-+-+-+   L   - Length present
+ |  8 or 16 bit  |   E   - Epoch
+ |Sequence Number|
+ +-+-+-+-+-+-+-+-+
+ | 16 bit Length |
+ | (if present)  |
+ +-+-+-+-+-+-+-+-+
+ https://www.rfc-editor.org/rfc/rfc9147#section-4
+*/
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct UnifiedHeader {
+    pub connection_id: Vec<u8>,
+    pub epoch_bits: u8,
+    pub sequence_number_bits: u16,
+    pub sequence_number_16_bit: bool,
+    pub length: Option<u16>,
+}
+
+impl UnifiedHeader {
+    // is_unified_header tells a DTLS 1.3 ciphertext record from a
+    // DTLSPlaintext record by its first byte.
+    pub fn is_unified_header(first_byte: u8) -> bool {
+        first_byte & UNIFIED_HEADER_FIXED_MASK == UNIFIED_HEADER_FIXED_BITS
+    }
+
+    pub fn size(&self) -> usize {
+        1 + self.connection_id.len()
+            + self.sequence_number_size()
+            + if self.length.is_some() { 2 } else { 0 }
+    }
+
+    // sequence_number_offset is the position of the sequence number bits
+    // which are protected by record number encryption.
+    pub fn sequence_number_offset(&self) -> usize {
+        1 + self.connection_id.len()
+    }
+
+    pub fn sequence_number_size(&self) -> usize {
+        if self.sequence_number_16_bit {
+            2
+        } else {
+            1
+        }
+    }
+
+    pub fn marshal<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let mut flags = UNIFIED_HEADER_FIXED_BITS | (self.epoch_bits & UNIFIED_HEADER_EPOCH_MASK);
+        if !self.connection_id.is_empty() {
+            flags |= UNIFIED_HEADER_CONNECTION_ID_BIT;
+        }
+        if self.sequence_number_16_bit {
+            flags |= UNIFIED_HEADER_SEQUENCE_NUMBER_16_BIT;
+        }
+        if self.length.is_some() {
+            flags |= UNIFIED_HEADER_LENGTH_BIT;
+        }
+
+        writer.write_u8(flags)?;
+        writer.write_all(&self.connection_id)?;
+        if self.sequence_number_16_bit {
+            writer.write_u16::<BigEndian>(self.sequence_number_bits)?;
+        } else {
+            writer.write_u8(self.sequence_number_bits as u8)?;
+        }
+        if let Some(length) = self.length {
+            writer.write_u16::<BigEndian>(length)?;
+        }
+
+        Ok(writer.flush()?)
+    }
+
+    // The length of the connection ID is not part of the header, it is the
+    // length the receiver asked its peer to use.
+    pub fn unmarshal<R: Read>(reader: &mut R, connection_id_len: usize) -> Result<Self> {
+        let flags = reader.read_u8()?;
+        if !UnifiedHeader::is_unified_header(flags) {
+            return Err(Error::ErrInvalidContentType);
+        }
+
+        let mut connection_id = vec![];
+        if flags & UNIFIED_HEADER_CONNECTION_ID_BIT != 0 {
+            connection_id = vec![0u8; connection_id_len];
+            reader.read_exact(&mut connection_id)?;
+        }
+
+        let sequence_number_16_bit = flags & UNIFIED_HEADER_SEQUENCE_NUMBER_16_BIT != 0;
+        let sequence_number_bits = if sequence_number_16_bit {
+            reader.read_u16::<BigEndian>()?
+        } else {
+            reader.read_u8()? as u16
+        };
+
+        let length = if flags & UNIFIED_HEADER_LENGTH_BIT != 0 {
+            Some(reader.read_u16::<BigEndian>()?)
+        } else {
+            None
+        };
+
+        Ok(UnifiedHeader {
+            connection_id,
+            epoch_bits: flags & UNIFIED_HEADER_EPOCH_MASK,
+            sequence_number_bits,
+            sequence_number_16_bit,
+            length,
+        })
+    }
+}
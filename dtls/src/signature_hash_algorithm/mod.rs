@@ -102,6 +102,17 @@ impl SignatureHashAlgorithm {
             CryptoPrivateKeyKind::Rsa256(_) => self.signature == SignatureAlgorithm::Rsa,
         }
     }
+
+    // is_dtls13 checks that the scheme can be used in DTLS 1.3, which drops the
+    // RSASSA-PKCS1-v1_5 signatures and ties the ECDSA hash to the curve.
+    // https://www.rfc-editor.org/rfc/rfc8446#section-4.2.3
+    pub(crate) fn is_dtls13(&self) -> bool {
+        matches!(
+            (self.hash, self.signature),
+            (HashAlgorithm::Sha256, SignatureAlgorithm::Ecdsa)
+                | (HashAlgorithm::Ed25519, SignatureAlgorithm::Ed25519)
+        )
+    }
 }
 
 pub(crate) fn default_signature_schemes() -> Vec<SignatureHashAlgorithm> {
@@ -151,6 +162,21 @@ pub(crate) fn select_signature_scheme(
     Err(Error::ErrNoAvailableSignatureSchemes)
 }
 
+// select_signature_scheme_dtls13 is select_signature_scheme restricted to the
+// schemes allowed in DTLS 1.3.
+pub(crate) fn select_signature_scheme_dtls13(
+    sigs: &[SignatureHashAlgorithm],
+    private_key: &CryptoPrivateKey,
+) -> Result<SignatureHashAlgorithm> {
+    for ss in sigs {
+        if ss.is_dtls13() && ss.is_compatible(private_key) {
+            return Ok(*ss);
+        }
+    }
+
+    Err(Error::ErrNoAvailableSignatureSchemes)
+}
+
 // SignatureScheme identifies a signature algorithm supported by TLS. See
 // RFC 8446, Section 4.2.3.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
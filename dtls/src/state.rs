@@ -47,6 +47,16 @@ pub struct State {
     pub(crate) local_key_signature: Vec<u8>,       // cached keySignature
     pub(crate) peer_certificates_verified: bool,
     //pub(crate) replay_detector: Vec<Box<dyn ReplayDetector + Send + Sync>>,
+
+    // DTLS 1.3 secrets, master_secret holds the exporter master secret
+    pub(crate) handshake_secret: Vec<u8>,
+    pub(crate) client_handshake_traffic_secret: Vec<u8>,
+    pub(crate) server_handshake_traffic_secret: Vec<u8>,
+    pub(crate) client_application_traffic_secret: Vec<u8>,
+    pub(crate) server_application_traffic_secret: Vec<u8>,
+    pub(crate) ack_state: Arc<Mutex<AckState>>,
+
+    pub(crate) connection_id_state: Arc<Mutex<ConnectionIdState>>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -96,6 +106,13 @@ impl Default for State {
             local_key_signature: vec![],         // cached keySignature
             peer_certificates_verified: false,
             //replay_detector: vec![],
+            handshake_secret: vec![],
+            client_handshake_traffic_secret: vec![],
+            server_handshake_traffic_secret: vec![],
+            client_application_traffic_secret: vec![],
+            server_application_traffic_secret: vec![],
+            ack_state: Arc::new(Mutex::new(AckState::default())),
+            connection_id_state: Arc::new(Mutex::new(ConnectionIdState::default())),
         }
     }
 }
@@ -132,8 +149,9 @@ impl State {
         let local_epoch = self.local_epoch.load(Ordering::SeqCst);
         let remote_epoch = self.remote_epoch.load(Ordering::SeqCst);
         let sequence_number = {
+            // The local epoch of DTLS 1.3 may advance before any record is sent with it
             let lsn = self.local_sequence_number.lock().await;
-            lsn[local_epoch as usize]
+            lsn.get(local_epoch as usize).copied().unwrap_or(0)
         };
         let cipher_suite_id = {
             let cipher_suite = self.cipher_suite.lock().await;
@@ -271,6 +289,23 @@ impl KeyingMaterialExporter for State {
             return Err(ReservedExportKeyingMaterial);
         }
 
+        {
+            let cipher_suite = self.cipher_suite.lock().await;
+            if let Some(cipher_suite) = &*cipher_suite {
+                if cipher_suite.is_dtls13() {
+                    return match key_schedule::export_keying_material(
+                        &self.master_secret,
+                        label,
+                        context,
+                        length,
+                    ) {
+                        Ok(v) => Ok(v),
+                        Err(err) => Err(Hash(err.to_string())),
+                    };
+                }
+            }
+        }
+
         let mut local_random = vec![];
         {
             let mut writer = BufWriter::<&mut Vec<u8>>::new(local_random.as_mut());
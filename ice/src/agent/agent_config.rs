@@ -7,6 +7,7 @@ use super::*;
 use crate::error::*;
 use crate::mdns::*;
 use crate::network_type::*;
+use crate::tcp_mux::TCPMux;
 use crate::udp_network::UDPNetwork;
 use crate::url::*;
 
@@ -64,6 +65,11 @@ pub struct AgentConfig {
     /// See [`UDPNetwork`]
     pub udp_network: UDPNetwork,
 
+    /// Enables gathering of passive ICE-TCP candidates. Connections accepted by the mux are routed
+    /// to this agent by its local ufrag. Requires `NetworkType::Tcp4` or `NetworkType::Tcp6` to be
+    /// enabled in `network_types` as well.
+    pub tcp_mux: Option<Arc<dyn TCPMux + Send + Sync>>,
+
     /// It is used to perform connectivity checks. The values MUST be unguessable, with at least
     /// 128 bits of random number generator output used to generate the password, and at least 24
     /// bits of output to generate the username fragment.
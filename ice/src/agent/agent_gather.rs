@@ -1,4 +1,4 @@
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -14,14 +14,19 @@ use crate::candidate::candidate_server_reflexive::CandidateServerReflexiveConfig
 use crate::candidate::*;
 use crate::error::*;
 use crate::network_type::*;
+use crate::tcp_mux::TCPMux;
 use crate::udp_network::UDPNetwork;
 use crate::url::{ProtoType, SchemeType, Url};
 use crate::util::*;
 
 const STUN_GATHER_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// The port advertised by active ICE-TCP candidates, see RFC 6544 section 4.5.
+const TCP_ACTIVE_CANDIDATE_PORT: u16 = 9;
+
 pub(crate) struct GatherCandidatesInternalParams {
     pub(crate) udp_network: UDPNetwork,
+    pub(crate) tcp_mux: Option<Arc<dyn TCPMux + Send + Sync>>,
     pub(crate) candidate_types: Vec<CandidateType>,
     pub(crate) urls: Vec<Url>,
     pub(crate) network_types: Vec<NetworkType>,
@@ -38,6 +43,7 @@ pub(crate) struct GatherCandidatesInternalParams {
 
 struct GatherCandidatesLocalParams {
     udp_network: UDPNetwork,
+    tcp_mux: Option<Arc<dyn TCPMux + Send + Sync>>,
     network_types: Vec<NetworkType>,
     mdns_mode: MulticastDnsMode,
     mdns_name: String,
@@ -58,6 +64,18 @@ struct GatherCandidatesLocalUDPMuxParams {
     udp_mux: Arc<dyn UDPMux + Send + Sync>,
 }
 
+struct GatherCandidatesLocalTCPParams {
+    network_types: Vec<NetworkType>,
+    mdns_mode: MulticastDnsMode,
+    mdns_name: String,
+    interface_filter: Arc<Option<InterfaceFilterFn>>,
+    ip_filter: Arc<Option<IpFilterFn>>,
+    ext_ip_mapper: Arc<Option<ExternalIpMapper>>,
+    net: Arc<Net>,
+    agent_internal: Arc<AgentInternal>,
+    tcp_mux: Option<Arc<dyn TCPMux + Send + Sync>>,
+}
+
 struct GatherCandidatesSrflxMappedParasm {
     network_types: Vec<NetworkType>,
     port_max: u16,
@@ -92,6 +110,7 @@ impl Agent {
                 CandidateType::Host => {
                     let local_params = GatherCandidatesLocalParams {
                         udp_network: params.udp_network.clone(),
+                        tcp_mux: params.tcp_mux.clone(),
                         network_types: params.network_types.clone(),
                         mdns_mode: params.mdns_mode,
                         mdns_name: params.mdns_name.clone(),
@@ -195,6 +214,7 @@ impl Agent {
     async fn gather_candidates_local(params: GatherCandidatesLocalParams) {
         let GatherCandidatesLocalParams {
             udp_network,
+            tcp_mux,
             network_types,
             mdns_mode,
             mdns_name,
@@ -205,8 +225,29 @@ impl Agent {
             agent_internal,
         } = params;
 
+        let (tcp_network_types, network_types): (Vec<_>, Vec<_>) =
+            network_types.into_iter().partition(|n| n.is_tcp());
+
+        if !tcp_network_types.is_empty() {
+            Self::gather_candidates_local_tcp(GatherCandidatesLocalTCPParams {
+                network_types: tcp_network_types,
+                mdns_mode,
+                mdns_name: mdns_name.clone(),
+                interface_filter: Arc::clone(&interface_filter),
+                ip_filter: Arc::clone(&ip_filter),
+                ext_ip_mapper: Arc::clone(&ext_ip_mapper),
+                net: Arc::clone(&net),
+                agent_internal: Arc::clone(&agent_internal),
+                tcp_mux,
+            })
+            .await;
+        }
+
+        if network_types.is_empty() {
+            return;
+        }
+
         // If we wanna use UDP mux, do so
-        // FIXME: We still need to support TCP in combination with this option
         if let UDPNetwork::Muxed(udp_mux) = udp_network {
             let result = Self::gather_candidates_local_udp_mux(GatherCandidatesLocalUDPMuxParams {
                 network_types,
@@ -228,23 +269,7 @@ impl Agent {
 
         let ips = local_interfaces(&net, &interface_filter, &ip_filter, &network_types).await;
         for ip in ips {
-            let mut mapped_ip = ip;
-
-            if mdns_mode != MulticastDnsMode::QueryAndGather && ext_ip_mapper.is_some() {
-                if let Some(ext_ip_mapper2) = ext_ip_mapper.as_ref() {
-                    if ext_ip_mapper2.candidate_type == CandidateType::Host {
-                        if let Ok(mi) = ext_ip_mapper2.find_external_ip(&ip.to_string()) {
-                            mapped_ip = mi;
-                        } else {
-                            log::warn!(
-                                "[{}]: 1:1 NAT mapping is enabled but no external IP is found for {}",
-                                agent_internal.get_name(),
-                                ip
-                            );
-                        }
-                    }
-                }
-            }
+            let mapped_ip = Self::map_host_ip(&agent_internal, &ext_ip_mapper, mdns_mode, ip);
 
             let address = if mdns_mode == MulticastDnsMode::QueryAndGather {
                 mdns_name.clone()
@@ -252,27 +277,8 @@ impl Agent {
                 mapped_ip.to_string()
             };
 
-            //TODO: for network in networks
             let network = UDP.to_owned();
             if let UDPNetwork::Ephemeral(ephemeral_config) = &udp_network {
-                /*TODO:switch network {
-                case tcp:
-                    // Handle ICE TCP passive mode
-
-                    a.log.Debugf("GetConn by ufrag: %s\n", a.localUfrag)
-                    conn, err = a.tcpMux.GetConnByUfrag(a.localUfrag)
-                    if err != nil {
-                        if !errors.Is(err, ErrTCPMuxNotInitialized) {
-                            a.log.Warnf("error getting tcp conn by ufrag: %s %s %s\n", network, ip, a.localUfrag)
-                        }
-                        continue
-                    }
-                    port = conn.LocalAddr().(*net.TCPAddr).Port
-                    tcpType = TCPTypePassive
-                    // is there a way to verify that the listen address is even
-                    // accessible from the current interface.
-                case udp:*/
-
                 let conn: Arc<dyn Conn + Send + Sync> = match listen_udp_in_port_range(
                     &net,
                     ephemeral_config.port_max(),
@@ -453,6 +459,191 @@ impl Agent {
         Ok(())
     }
 
+    /// Maps a local interface IP to the IP advertised by host candidates when 1:1 NAT mapping is
+    /// enabled for them.
+    fn map_host_ip(
+        agent_internal: &Arc<AgentInternal>,
+        ext_ip_mapper: &Arc<Option<ExternalIpMapper>>,
+        mdns_mode: MulticastDnsMode,
+        ip: IpAddr,
+    ) -> IpAddr {
+        if mdns_mode == MulticastDnsMode::QueryAndGather {
+            return ip;
+        }
+
+        if let Some(ext_ip_mapper) = ext_ip_mapper.as_ref() {
+            if ext_ip_mapper.candidate_type == CandidateType::Host {
+                if let Ok(mapped_ip) = ext_ip_mapper.find_external_ip(&ip.to_string()) {
+                    return mapped_ip;
+                } else {
+                    log::warn!(
+                        "[{}]: 1:1 NAT mapping is enabled but no external IP is found for {}",
+                        agent_internal.get_name(),
+                        ip
+                    );
+                }
+            }
+        }
+
+        ip
+    }
+
+    /// Gathers ICE-TCP host candidates (RFC 6544): a passive candidate per interface when a
+    /// [`TCPMux`] is configured, and an active candidate per interface that dials out to remote
+    /// passive candidates once they are known.
+    async fn gather_candidates_local_tcp(params: GatherCandidatesLocalTCPParams) {
+        let GatherCandidatesLocalTCPParams {
+            network_types,
+            mdns_mode,
+            mdns_name,
+            interface_filter,
+            ip_filter,
+            ext_ip_mapper,
+            net,
+            agent_internal,
+            tcp_mux,
+        } = params;
+
+        let passive_conn = if let Some(tcp_mux) = tcp_mux {
+            let ufrag = {
+                let ufrag_pwd = agent_internal.ufrag_pwd.lock().await;
+
+                ufrag_pwd.local_ufrag.clone()
+            };
+
+            log::debug!(
+                "[{}]: get tcp conn by ufrag: {}",
+                agent_internal.get_name(),
+                ufrag
+            );
+            match tcp_mux.get_conn_by_ufrag(&ufrag).await {
+                Ok(conn) => match conn.local_addr() {
+                    Ok(local_addr) => Some((conn, local_addr)),
+                    Err(err) => {
+                        log::warn!(
+                            "[{}]: could not get local addr of tcp conn: {}",
+                            agent_internal.get_name(),
+                            err
+                        );
+                        None
+                    }
+                },
+                Err(err) => {
+                    log::warn!(
+                        "[{}]: error getting tcp conn by ufrag {}: {}",
+                        agent_internal.get_name(),
+                        ufrag,
+                        err
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let ips = local_interfaces(&net, &interface_filter, &ip_filter, &network_types).await;
+        for ip in ips {
+            let mapped_ip = Self::map_host_ip(&agent_internal, &ext_ip_mapper, mdns_mode, ip);
+
+            let address = if mdns_mode == MulticastDnsMode::QueryAndGather {
+                mdns_name.clone()
+            } else {
+                mapped_ip.to_string()
+            };
+
+            if let Some((conn, local_addr)) = &passive_conn {
+                // A mux bound to a specific address can only accept connections on that address.
+                if local_addr.ip().is_unspecified() || local_addr.ip() == ip {
+                    let host_config = CandidateHostConfig {
+                        base_config: CandidateBaseConfig {
+                            network: TCP.to_owned(),
+                            address: address.clone(),
+                            port: local_addr.port(),
+                            component: COMPONENT_RTP,
+                            conn: Some(Arc::clone(conn)),
+                            ..CandidateBaseConfig::default()
+                        },
+                        tcp_type: TcpType::Passive,
+                    };
+                    Self::add_tcp_host_candidate(&agent_internal, host_config, mdns_mode, ip).await;
+                }
+            }
+
+            // Active candidates don't own a connection until they are paired with a remote
+            // passive candidate, see `AgentInternal::dial_active_tcp`.
+            let host_config = CandidateHostConfig {
+                base_config: CandidateBaseConfig {
+                    network: TCP.to_owned(),
+                    address,
+                    port: TCP_ACTIVE_CANDIDATE_PORT,
+                    component: COMPONENT_RTP,
+                    ..CandidateBaseConfig::default()
+                },
+                tcp_type: TcpType::Active,
+            };
+            Self::add_tcp_host_candidate(&agent_internal, host_config, mdns_mode, ip).await;
+        }
+    }
+
+    async fn add_tcp_host_candidate(
+        agent_internal: &Arc<AgentInternal>,
+        host_config: CandidateHostConfig,
+        mdns_mode: MulticastDnsMode,
+        ip: IpAddr,
+    ) {
+        let (address, port, tcp_type) = (
+            host_config.base_config.address.clone(),
+            host_config.base_config.port,
+            host_config.tcp_type,
+        );
+
+        let candidate = match host_config.new_candidate_host() {
+            Ok(candidate) => candidate,
+            Err(err) => {
+                log::warn!(
+                    "[{}]: Failed to create {} tcp host candidate: {} {}: {}",
+                    agent_internal.get_name(),
+                    tcp_type,
+                    address,
+                    port,
+                    err
+                );
+                return;
+            }
+        };
+
+        if mdns_mode == MulticastDnsMode::QueryAndGather {
+            if let Err(err) = candidate.set_ip(&ip) {
+                log::warn!(
+                    "[{}]: Failed to create {} tcp host candidate: {} {}: {}",
+                    agent_internal.get_name(),
+                    tcp_type,
+                    address,
+                    port,
+                    err
+                );
+                return;
+            }
+        }
+
+        let candidate: Arc<dyn Candidate + Send + Sync> = Arc::new(candidate);
+        if let Err(err) = agent_internal.add_candidate(&candidate).await {
+            if let Err(close_err) = candidate.close().await {
+                log::warn!(
+                    "[{}]: Failed to close candidate: {}",
+                    agent_internal.get_name(),
+                    close_err
+                );
+            }
+            log::warn!(
+                "[{}]: Failed to append to localCandidates and run onCandidateHdlr: {}",
+                agent_internal.get_name(),
+                err
+            );
+        }
+    }
+
     async fn gather_candidates_srflx_mapped(params: GatherCandidatesSrflxMappedParasm) {
         let GatherCandidatesSrflxMappedParasm {
             network_types,
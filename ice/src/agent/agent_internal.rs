@@ -1,14 +1,21 @@
+use std::collections::HashSet;
+use std::net::{Ipv4Addr, Ipv6Addr};
 use std::sync::atomic::{AtomicBool, AtomicU64};
 
 use arc_swap::ArcSwapOption;
 use util::sync::Mutex as SyncMutex;
+use util::Conn;
 
 use super::agent_transport::*;
 use super::*;
 use crate::candidate::candidate_base::CandidateBaseConfig;
+use crate::candidate::candidate_host::CandidateHostConfig;
 use crate::candidate::candidate_peer_reflexive::CandidatePeerReflexiveConfig;
 use crate::util::*;
 
+/// How long an active ICE-TCP candidate waits for its connection to be established.
+const TCP_ACTIVE_DIAL_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub type ChanCandidateTx =
     Arc<Mutex<Option<mpsc::Sender<Option<Arc<dyn Candidate + Send + Sync>>>>>>;
 
@@ -63,6 +70,11 @@ pub struct AgentInternal {
 
     pub(crate) agent_conn: Arc<AgentConn>,
 
+    pub(crate) net: Arc<Net>,
+    // Pairs of local active and remote passive ICE-TCP candidates a connection was dialed for,
+    // by candidate id
+    pub(crate) active_tcp_dials: SyncMutex<HashSet<(String, String)>>,
+
     // the following variables won't be changed after init_with_defaults()
     pub(crate) insecure_skip_verify: bool,
     pub(crate) max_binding_requests: u16,
@@ -84,7 +96,7 @@ pub struct AgentInternal {
 }
 
 impl AgentInternal {
-    pub(super) fn new(config: &AgentConfig) -> (Self, ChanReceivers) {
+    pub(super) fn new(config: &AgentConfig, net: Arc<Net>) -> (Self, ChanReceivers) {
         let (chan_state_tx, chan_state_rx) = mpsc::channel(1);
         let (chan_candidate_tx, chan_candidate_rx) = mpsc::channel(1);
         let (chan_candidate_pair_tx, chan_candidate_pair_rx) = mpsc::channel(1);
@@ -157,6 +169,9 @@ impl AgentInternal {
 
             // AgentConn
             agent_conn: Arc::new(AgentConn::new()),
+
+            net,
+            active_tcp_dials: SyncMutex::new(HashSet::new()),
         };
 
         let chan_receivers = ChanReceivers {
@@ -516,8 +531,168 @@ impl AgentInternal {
         let _ = self.force_candidate_contact_tx.try_send(true);
     }
 
+    /// Pairs a local candidate with a remote one. Active ICE-TCP candidates are not paired
+    /// directly, instead a connection is dialed to the remote passive candidate and the pair is
+    /// formed with the resulting candidate once it is established.
+    pub(crate) async fn pair_candidates(
+        self: &Arc<Self>,
+        local: Arc<dyn Candidate + Send + Sync>,
+        remote: Arc<dyn Candidate + Send + Sync>,
+    ) {
+        match (local.tcp_type(), remote.tcp_type()) {
+            (TcpType::Active, TcpType::Passive) if local.get_conn().is_none() => {
+                self.dial_active_tcp(local, remote);
+            }
+            // Candidates dialed by us are only paired with the remote candidate they were dialed
+            // to, and passive candidates only ever accept connections from active ones.
+            (TcpType::Active, _) | (TcpType::Passive, TcpType::Passive) => {}
+            _ => self.add_pair(local, remote).await,
+        }
+    }
+
+    /// Connects an active ICE-TCP candidate to a remote passive candidate in the background. The
+    /// connection is owned by a new local candidate, which is paired with the remote candidate
+    /// once the connection is established. A pair is only dialed once, until the candidates are
+    /// deleted on restart or close, which also closes the candidates of dials finishing later.
+    fn dial_active_tcp(
+        self: &Arc<Self>,
+        local: Arc<dyn Candidate + Send + Sync>,
+        remote: Arc<dyn Candidate + Send + Sync>,
+    ) {
+        let dial = (local.id(), remote.id());
+        if !self.active_tcp_dials.lock().insert(dial.clone()) {
+            return;
+        }
+
+        let ai = Arc::clone(self);
+        tokio::spawn(async move {
+            // Addresses mapped by a 1:1 NAT aren't local, they're dialed from any address.
+            let mut local_ip = local.addr().ip();
+            let interfaces = ai.net.get_interfaces().await;
+            if !interfaces
+                .iter()
+                .any(|ifc| ifc.addrs().iter().any(|addr| addr.addr() == local_ip))
+            {
+                local_ip = if local_ip.is_ipv4() {
+                    Ipv4Addr::UNSPECIFIED.into()
+                } else {
+                    Ipv6Addr::UNSPECIFIED.into()
+                };
+            }
+
+            let remote_addr = remote.addr();
+            let stream = match tokio::time::timeout(
+                TCP_ACTIVE_DIAL_TIMEOUT,
+                ai.net.dial_tcp(local_ip, remote_addr),
+            )
+            .await
+            {
+                Ok(Ok(stream)) => stream,
+                Ok(Err(err)) => {
+                    log::warn!(
+                        "[{}]: Failed to dial tcp address {}: {}",
+                        ai.get_name(),
+                        remote_addr,
+                        err
+                    );
+                    return;
+                }
+                Err(_) => {
+                    log::warn!(
+                        "[{}]: Timed out dialing tcp address {}",
+                        ai.get_name(),
+                        remote_addr
+                    );
+                    return;
+                }
+            };
+
+            let local_addr = match stream.local_addr() {
+                Ok(local_addr) => local_addr,
+                Err(err) => {
+                    log::warn!("[{}]: could not get local addr: {}", ai.get_name(), err);
+                    return;
+                }
+            };
+
+            let conn = Arc::new(TCPPacketConn::new(local_addr));
+            if let Err(err) = conn.add_conn(stream, None) {
+                log::warn!(
+                    "[{}]: Failed to add tcp conn to {}: {}",
+                    ai.get_name(),
+                    remote_addr,
+                    err
+                );
+                let _ = conn.close().await;
+                return;
+            }
+
+            let host_config = CandidateHostConfig {
+                base_config: CandidateBaseConfig {
+                    network: TCP.to_owned(),
+                    address: local_addr.ip().to_string(),
+                    port: local_addr.port(),
+                    component: local.component(),
+                    conn: Some(Arc::clone(&conn) as Arc<dyn Conn + Send + Sync>),
+                    ..CandidateBaseConfig::default()
+                },
+                tcp_type: TcpType::Active,
+            };
+            let candidate: Arc<dyn Candidate + Send + Sync> = match host_config.new_candidate_host()
+            {
+                Ok(candidate) => Arc::new(candidate),
+                Err(err) => {
+                    log::warn!(
+                        "[{}]: Failed to create active tcp candidate for {}: {}",
+                        ai.get_name(),
+                        remote_addr,
+                        err
+                    );
+                    let _ = conn.close().await;
+                    return;
+                }
+            };
+
+            // The dial is forgotten once the candidates are deleted, on restart or close.
+            let added = {
+                let mut local_candidates = ai.local_candidates.lock().await;
+                let added = ai.active_tcp_dials.lock().contains(&dial);
+                if added {
+                    local_candidates
+                        .entry(candidate.network_type())
+                        .or_default()
+                        .push(Arc::clone(&candidate));
+                }
+                added
+            };
+            if !added {
+                if let Err(err) = candidate.close().await {
+                    log::warn!(
+                        "[{}]: Failed to close candidate {}: {}",
+                        ai.get_name(),
+                        candidate,
+                        err
+                    );
+                }
+                return;
+            }
+
+            let initialized_ch = {
+                let started_ch_tx = ai.started_ch_tx.lock().await;
+                (*started_ch_tx).as_ref().map(|tx| tx.subscribe())
+            };
+            ai.start_candidate(&candidate, initialized_ch).await;
+
+            ai.add_pair(candidate, remote).await;
+            ai.request_connectivity_check();
+        });
+    }
+
     /// Assumes you are holding the lock (must be execute using a.run).
-    pub(crate) async fn add_remote_candidate(&self, c: &Arc<dyn Candidate + Send + Sync>) {
+    pub(crate) async fn add_remote_candidate(
+        self: &Arc<Self>,
+        c: &Arc<dyn Candidate + Send + Sync>,
+    ) {
         let network_type = c.network_type();
 
         {
@@ -546,7 +721,7 @@ impl AgentInternal {
         }
 
         for cand in local_cands {
-            self.add_pair(cand, c.clone()).await;
+            self.pair_candidates(cand, c.clone()).await;
         }
 
         self.request_connectivity_check();
@@ -598,7 +773,7 @@ impl AgentInternal {
         }
 
         for cand in remote_cands {
-            self.add_pair(c.clone(), cand).await;
+            self.pair_candidates(c.clone(), cand).await;
         }
 
         self.request_connectivity_check();
@@ -653,6 +828,9 @@ impl AgentInternal {
     ///
     /// This is used for restarts, failures and on close.
     pub(crate) async fn delete_all_candidates(&self) {
+        // Candidates of dials still in progress are closed once they're established.
+        self.active_tcp_dials.lock().clear();
+
         {
             let mut local_candidates = self.local_candidates.lock().await;
             for cs in local_candidates.values_mut() {
@@ -823,7 +1001,7 @@ impl AgentInternal {
 
     /// Processes STUN traffic from a remote candidate.
     pub(crate) async fn handle_inbound(
-        &self,
+        self: &Arc<Self>,
         m: &mut Message,
         local: &Arc<dyn Candidate + Send + Sync>,
         remote: SocketAddr,
@@ -922,11 +1100,11 @@ impl AgentInternal {
             }
 
             if remote_candidate.is_none() {
-                let (ip, port, network_type) = (remote.ip(), remote.port(), NetworkType::Udp4);
+                let (ip, port, network_type) = (remote.ip(), remote.port(), local.network_type());
 
                 let prflx_candidate_config = CandidatePeerReflexiveConfig {
                     base_config: CandidateBaseConfig {
-                        network: network_type.to_string(),
+                        network: network_type.network_short(),
                         address: ip.to_string(),
                         port,
                         component: local.component(),
@@ -1046,7 +1224,8 @@ impl AgentInternal {
                     .recv_loop(cand, closed_ch_rx, initialized_ch, conn, addr)
                     .await;
             });
-        } else {
+        } else if candidate.tcp_type() != TcpType::Active {
+            // Active ICE-TCP candidates only get a connection once they're dialed
             log::error!("[{}]: Can't start due to conn is_none", self.get_name(),);
         }
     }
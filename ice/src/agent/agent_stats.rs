@@ -179,6 +179,16 @@ pub struct CandidateStats {
     /// The `priority` field of the ICECandidate.
     pub priority: u32,
 
+    /// The `foundation` field of the ICECandidate.
+    pub foundation: String,
+
+    /// The address the candidate is derived from: the base of a server reflexive or peer
+    /// reflexive candidate, or the mapped address of a relay candidate. None for host candidates.
+    pub related_address: Option<String>,
+
+    /// The port of `related_address`.
+    pub related_port: Option<u16>,
+
     /// The url of the TURN or STUN server indicated in the that translated this IP address.
     /// It is the url address surfaced in an PeerConnectionICEEvent.
     pub url: String,
@@ -205,6 +215,9 @@ impl Default for CandidateStats {
             port: 0,
             candidate_type: CandidateType::default(),
             priority: 0,
+            foundation: String::new(),
+            related_address: None,
+            related_port: None,
             url: String::new(),
             relay_protocol: String::new(),
             deleted: false,
@@ -237,6 +250,7 @@ impl AgentInternal {
         let mut res = Vec::with_capacity(local_candidates.len());
         for (network_type, local_candidates) in &*local_candidates {
             for c in local_candidates {
+                let related_address = c.related_address();
                 let stat = CandidateStats {
                     timestamp: Instant::now(),
                     id: c.id(),
@@ -245,8 +259,16 @@ impl AgentInternal {
                     port: c.port(),
                     candidate_type: c.candidate_type(),
                     priority: c.priority(),
+                    foundation: c.foundation(),
+                    related_address: related_address.as_ref().map(|r| r.address.clone()),
+                    related_port: related_address.map(|r| r.port),
                     // URL string
-                    relay_protocol: "udp".to_owned(),
+                    // Only relay candidates over UDP TURN allocations are gathered.
+                    relay_protocol: if c.candidate_type() == CandidateType::Relay {
+                        "udp".to_owned()
+                    } else {
+                        String::new()
+                    },
                     // Deleted bool
                     ..CandidateStats::default()
                 };
@@ -262,6 +284,7 @@ impl AgentInternal {
         let mut res = Vec::with_capacity(remote_candidates.len());
         for (network_type, remote_candidates) in &*remote_candidates {
             for c in remote_candidates {
+                let related_address = c.related_address();
                 let stat = CandidateStats {
                     timestamp: Instant::now(),
                     id: c.id(),
@@ -270,8 +293,10 @@ impl AgentInternal {
                     port: c.port(),
                     candidate_type: c.candidate_type(),
                     priority: c.priority(),
+                    foundation: c.foundation(),
+                    related_address: related_address.as_ref().map(|r| r.address.clone()),
+                    related_port: related_address.map(|r| r.port),
                     // URL string
-                    relay_protocol: "udp".to_owned(),
                     // Deleted bool
                     ..CandidateStats::default()
                 };
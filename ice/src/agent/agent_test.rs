@@ -19,6 +19,7 @@ use crate::candidate::candidate_relay::*;
 use crate::candidate::candidate_server_reflexive::*;
 use crate::control::AttrControlling;
 use crate::priority::PriorityAttr;
+use crate::tcp_mux::{TCPMuxDefault, TCPMuxParams};
 use crate::use_candidate::UseCandidateAttr;
 
 #[tokio::test]
@@ -1292,6 +1293,17 @@ async fn test_local_candidate_stats() -> Result<()> {
         srflx_local.id(),
         "missing srflx local stat"
     );
+    assert_eq!(host_local_stat.related_address, None);
+    assert_eq!(host_local_stat.relay_protocol, "");
+    assert_eq!(
+        (
+            srflx_local_stat.related_address.as_deref(),
+            srflx_local_stat.related_port
+        ),
+        (Some("4.3.2.1"), Some(43212)),
+        "invalid srflx local stat related address"
+    );
+    assert_eq!(srflx_local_stat.foundation, srflx_local.foundation());
 
     a.close().await?;
 
@@ -1439,6 +1451,18 @@ async fn test_remote_candidate_stats() -> Result<()> {
         host_remote.id(),
         "missing host remote stat"
     );
+    assert_eq!(
+        (
+            relay_remote_stat.related_address.as_deref(),
+            relay_remote_stat.related_port
+        ),
+        (Some("4.3.2.1"), Some(43210)),
+        "invalid relay remote stat related address"
+    );
+    assert_eq!(
+        relay_remote_stat.relay_protocol, "",
+        "relay protocol is only known for local candidates"
+    );
 
     a.close().await?;
 
@@ -2197,3 +2221,126 @@ async fn test_lite_lifecycle() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_connectivity_ice_tcp() -> Result<()> {
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:0").await?;
+    let tcp_mux = TCPMuxDefault::new(TCPMuxParams::new(listener))?;
+
+    let (a_notifier, mut a_connected) = on_connected();
+    let (b_notifier, mut b_connected) = on_connected();
+
+    // Agent A only offers passive candidates through the mux, agent B dials them with its active
+    // candidates.
+    let a_agent = Arc::new(
+        Agent::new(AgentConfig {
+            network_types: vec![NetworkType::Tcp4],
+            multicast_dns_mode: MulticastDnsMode::Disabled,
+            tcp_mux: Some(Arc::clone(&tcp_mux) as Arc<dyn TCPMux + Send + Sync>),
+            ..Default::default()
+        })
+        .await?,
+    );
+    a_agent.on_connection_state_change(a_notifier);
+
+    let b_agent = Arc::new(
+        Agent::new(AgentConfig {
+            network_types: vec![NetworkType::Tcp4],
+            multicast_dns_mode: MulticastDnsMode::Disabled,
+            ..Default::default()
+        })
+        .await?,
+    );
+    b_agent.on_connection_state_change(b_notifier);
+
+    let (a_conn, b_conn) = connect_with_vnet(&a_agent, &b_agent).await?;
+
+    let _ = a_connected.recv().await;
+    let _ = b_connected.recv().await;
+
+    for agent in [&a_agent, &b_agent] {
+        let pair = agent
+            .get_selected_candidate_pair()
+            .expect("a pair must be selected");
+        assert_eq!(pair.local.network_type(), NetworkType::Tcp4);
+        assert_eq!(pair.remote.network_type(), NetworkType::Tcp4);
+    }
+
+    let msg = b"hello over ice-tcp";
+    b_conn.send(msg).await?;
+    let mut buf = vec![0u8; msg.len()];
+    let n = a_conn.recv(&mut buf).await?;
+    assert_eq!(&buf[..n], msg);
+
+    a_agent.close().await?;
+    b_agent.close().await?;
+    tcp_mux.close().await?;
+
+    Ok(())
+}
+
+// Assert that a pair of an active and a passive ICE-TCP candidate is only dialed once, and that
+// the dialed connection is closed with the agent
+#[tokio::test]
+async fn test_active_tcp_dial_once() -> Result<()> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+
+    let a = Agent::new(AgentConfig {
+        network_types: vec![NetworkType::Tcp4],
+        multicast_dns_mode: MulticastDnsMode::Disabled,
+        ..Default::default()
+    })
+    .await?;
+
+    let local: Arc<dyn Candidate + Send + Sync> = Arc::new(
+        CandidateHostConfig {
+            base_config: CandidateBaseConfig {
+                network: TCP.to_owned(),
+                address: "127.0.0.1".to_owned(),
+                port: 9,
+                component: COMPONENT_RTP,
+                ..Default::default()
+            },
+            tcp_type: TcpType::Active,
+        }
+        .new_candidate_host()?,
+    );
+    let remote: Arc<dyn Candidate + Send + Sync> = Arc::new(
+        CandidateHostConfig {
+            base_config: CandidateBaseConfig {
+                network: TCP.to_owned(),
+                address: "127.0.0.1".to_owned(),
+                port: listener.local_addr()?.port(),
+                component: COMPONENT_RTP,
+                ..Default::default()
+            },
+            tcp_type: TcpType::Passive,
+        }
+        .new_candidate_host()?,
+    );
+
+    for _ in 0..2 {
+        a.internal
+            .pair_candidates(Arc::clone(&local), Arc::clone(&remote))
+            .await;
+    }
+
+    let (mut stream, _) = tokio::time::timeout(Duration::from_secs(5), listener.accept())
+        .await
+        .expect("the pair should be dialed")?;
+    let result = tokio::time::timeout(Duration::from_millis(100), listener.accept()).await;
+    assert!(result.is_err(), "the pair should only be dialed once");
+
+    a.close().await?;
+
+    let mut buf = [0u8; 1];
+    let n = tokio::time::timeout(
+        Duration::from_secs(5),
+        tokio::io::AsyncReadExt::read(&mut stream, &mut buf),
+    )
+    .await
+    .expect("the connection should be closed")?;
+    assert_eq!(n, 0, "the connection should be closed");
+
+    Ok(())
+}
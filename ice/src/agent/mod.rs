@@ -45,6 +45,7 @@ use crate::mdns::*;
 use crate::network_type::*;
 use crate::rand::*;
 use crate::state::*;
+use crate::tcp_mux::{TCPMux, TCPPacketConn};
 use crate::tcp_type::TcpType;
 use crate::udp_mux::UDPMux;
 use crate::udp_network::UDPNetwork;
@@ -102,6 +103,7 @@ pub struct Agent {
     pub(crate) internal: Arc<AgentInternal>,
 
     pub(crate) udp_network: UDPNetwork,
+    pub(crate) tcp_mux: Option<Arc<dyn TCPMux + Send + Sync>>,
     pub(crate) interface_filter: Arc<Option<InterfaceFilterFn>>,
     pub(crate) ip_filter: Arc<Option<IpFilterFn>>,
     pub(crate) mdns_mode: MulticastDnsMode,
@@ -144,7 +146,20 @@ impl Agent {
                 }
             };
 
-        let (mut ai, chan_receivers) = AgentInternal::new(&config);
+        let net = if let Some(net) = &config.net {
+            if net.is_virtual() {
+                log::warn!("vnet is enabled");
+                if mdns_mode != MulticastDnsMode::Disabled {
+                    log::warn!("vnet does not support mDNS yet");
+                }
+            }
+
+            Arc::clone(net)
+        } else {
+            Arc::new(Net::new(None))
+        };
+
+        let (mut ai, chan_receivers) = AgentInternal::new(&config, Arc::clone(&net));
         let (chan_state_rx, chan_candidate_rx, chan_candidate_pair_rx) = (
             chan_receivers.chan_state_rx,
             chan_receivers.chan_candidate_rx,
@@ -182,21 +197,9 @@ impl Agent {
             }
         };
 
-        let net = if let Some(net) = config.net {
-            if net.is_virtual() {
-                log::warn!("vnet is enabled");
-                if mdns_mode != MulticastDnsMode::Disabled {
-                    log::warn!("vnet does not support mDNS yet");
-                }
-            }
-
-            net
-        } else {
-            Arc::new(Net::new(None))
-        };
-
         let agent = Self {
             udp_network: config.udp_network,
+            tcp_mux: config.tcp_mux,
             internal: Arc::new(ai),
             interface_filter: Arc::clone(&config.interface_filter),
             ip_filter: Arc::clone(&config.ip_filter),
@@ -346,6 +349,11 @@ impl Agent {
             udp_mux.remove_conn_by_ufrag(&ufrag).await;
         }
 
+        if let Some(tcp_mux) = &self.tcp_mux {
+            let (ufrag, _) = self.get_local_user_credentials().await;
+            tcp_mux.remove_conn_by_ufrag(&ufrag).await;
+        }
+
         //FIXME: deadlock here
         self.internal.close().await
     }
@@ -452,6 +460,7 @@ impl Agent {
 
         let params = GatherCandidatesInternalParams {
             udp_network: self.udp_network.clone(),
+            tcp_mux: self.tcp_mux.clone(),
             candidate_types: self.candidate_types.clone(),
             urls: self.urls.clone(),
             network_types: self.network_types.clone(),
@@ -60,6 +60,7 @@ async fn test_relay_only_connection() -> Result<(), Error> {
                 net: Arc::new(util::vnet::net::Net::new(None)),
             }),
         }],
+        listener_configs: vec![],
         channel_bind_timeout: Duration::from_secs(0),
         alloc_close_notify: None,
     })
@@ -41,6 +41,7 @@ async fn test_server_reflexive_only_connection() -> Result<()> {
                 net: Arc::new(util::vnet::net::Net::new(None)),
             }),
         }],
+        listener_configs: vec![],
         channel_bind_timeout: Duration::from_secs(0),
         alloc_close_notify: None,
     })
@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{watch, Mutex};
+use tokio::time::Duration;
+use util::{Conn, Error};
+
+mod tcp_packet_conn;
+use tcp_packet_conn::read_streaming_packet;
+pub use tcp_packet_conn::TCPPacketConn;
+
+#[cfg(test)]
+mod tcp_mux_test;
+
+use stun::attributes::ATTR_USERNAME;
+use stun::message::{is_message as is_stun_message, Message as STUNMessage, BINDING_REQUEST};
+
+use crate::candidate::RECEIVE_MTU;
+
+/// How long an accepted TCP connection has to send its first STUN binding request before it is
+/// dropped.
+const FIRST_PACKET_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Muxes ICE-TCP (RFC 6544) connections accepted on a single listener onto per-agent connections.
+#[async_trait]
+pub trait TCPMux {
+    /// Close the muxing.
+    async fn close(&self) -> Result<(), Error>;
+
+    /// Get the underlying connection for a given ufrag.
+    async fn get_conn_by_ufrag(
+        self: Arc<Self>,
+        ufrag: &str,
+    ) -> Result<Arc<dyn Conn + Send + Sync>, Error>;
+
+    /// Remove the underlying connection for a given ufrag.
+    async fn remove_conn_by_ufrag(&self, ufrag: &str);
+}
+
+pub struct TCPMuxParams {
+    listener: TcpListener,
+}
+
+impl TCPMuxParams {
+    pub fn new(listener: TcpListener) -> Self {
+        Self { listener }
+    }
+}
+
+/// The default [`TCPMux`] implementation.
+///
+/// Incoming connections are routed by the ufrag in the USERNAME attribute of the first STUN
+/// binding request received on them, the same way [`crate::udp_mux::UDPMuxDefault`] routes
+/// packets from unknown source addresses.
+pub struct TCPMuxDefault {
+    /// Address the listener is bound to.
+    local_addr: SocketAddr,
+
+    /// Maps from ufrag to the underlying connection.
+    conns: Mutex<HashMap<String, Arc<TCPPacketConn>>>,
+
+    // Close sender
+    closed_watch_tx: Mutex<Option<watch::Sender<()>>>,
+}
+
+impl TCPMuxDefault {
+    pub fn new(params: TCPMuxParams) -> Result<Arc<Self>, Error> {
+        let local_addr = params.listener.local_addr()?;
+        let (closed_watch_tx, closed_watch_rx) = watch::channel(());
+
+        let mux = Arc::new(Self {
+            local_addr,
+            conns: Mutex::default(),
+            closed_watch_tx: Mutex::new(Some(closed_watch_tx)),
+        });
+
+        let cloned_mux = Arc::clone(&mux);
+        cloned_mux.start_accept_worker(params.listener, closed_watch_rx);
+
+        Ok(mux)
+    }
+
+    pub async fn is_closed(&self) -> bool {
+        self.closed_watch_tx.lock().await.is_none()
+    }
+
+    /// Returns the address the underlying listener is bound to.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Returns the connection for a given ufrag, creating it if it doesn't exist yet.
+    async fn get_or_create_conn(self: &Arc<Self>, ufrag: &str) -> Arc<TCPPacketConn> {
+        let mut conns = self.conns.lock().await;
+        if let Some(conn) = conns.get(ufrag) {
+            return Arc::clone(conn);
+        }
+
+        let conn = Arc::new(TCPPacketConn::new(self.local_addr));
+        let mut close_rx = conn.close_rx();
+        let cloned_self = Arc::clone(self);
+        let cloned_conn = Arc::clone(&conn);
+        let cloned_ufrag = ufrag.to_string();
+        tokio::spawn(async move {
+            let _ = close_rx.changed().await;
+
+            let mut conns = cloned_self.conns.lock().await;
+            // Only remove the entry if it hasn't been replaced in the meantime.
+            if conns
+                .get(&cloned_ufrag)
+                .map_or(false, |c| Arc::ptr_eq(c, &cloned_conn))
+            {
+                conns.remove(&cloned_ufrag);
+            }
+        });
+
+        conns.insert(ufrag.to_owned(), Arc::clone(&conn));
+
+        conn
+    }
+
+    fn start_accept_worker(
+        self: Arc<Self>,
+        listener: TcpListener,
+        mut closed_watch_rx: watch::Receiver<()>,
+    ) {
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    res = listener.accept() => {
+                        match res {
+                            Ok((stream, addr)) => {
+                                let mux = Arc::clone(&self);
+                                tokio::spawn(async move {
+                                    mux.handle_conn(stream, addr).await;
+                                });
+                            }
+                            Err(err) => {
+                                log::error!("Could not accept tcp connection: {}", err);
+                                break;
+                            }
+                        }
+                    }
+                    _ = closed_watch_rx.changed() => {
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
+    async fn handle_conn(self: Arc<Self>, mut stream: TcpStream, addr: SocketAddr) {
+        let mut buffer = vec![0u8; RECEIVE_MTU];
+
+        let n = match tokio::time::timeout(
+            FIRST_PACKET_READ_TIMEOUT,
+            read_streaming_packet(&mut stream, &mut buffer),
+        )
+        .await
+        {
+            Ok(Ok(n)) => n,
+            Ok(Err(err)) => {
+                log::warn!("Failed to read first packet from {}: {}", addr, err);
+                return;
+            }
+            Err(_) => {
+                log::warn!("Timed out waiting for first packet from {}", addr);
+                return;
+            }
+        };
+        let buffer = &buffer[..n];
+
+        if !is_stun_message(buffer) {
+            log::warn!("Not a STUN message from {}, dropping connection", addr);
+            return;
+        }
+
+        let mut message = STUNMessage::new();
+        if let Err(err) = message.unmarshal_binary(buffer) {
+            log::warn!("Failed to handle decode ICE from {}: {}", addr, err);
+            return;
+        }
+
+        if message.typ != BINDING_REQUEST {
+            log::warn!(
+                "Not a binding request from {}: {}, dropping connection",
+                addr,
+                message.typ
+            );
+            return;
+        }
+
+        let (attr, found) = message.attributes.get(ATTR_USERNAME);
+        if !found {
+            log::warn!("No username attribute in STUN message from {}", addr);
+            return;
+        }
+
+        let ufrag = match String::from_utf8(attr.value) {
+            Ok(username) => match username.split(':').next() {
+                Some(ufrag) => ufrag.to_owned(),
+                None => return,
+            },
+            Err(err) => {
+                log::warn!(
+                    "Failed to decode USERNAME from STUN message as UTF-8: {}",
+                    err
+                );
+                return;
+            }
+        };
+
+        if self.is_closed().await {
+            return;
+        }
+
+        let conn = self.get_or_create_conn(&ufrag).await;
+        if let Err(err) = conn.add_conn(stream, Some(buffer.to_vec())) {
+            log::error!(
+                "Failed to add tcp conn from {} for {}: {}",
+                addr,
+                ufrag,
+                err
+            );
+        }
+    }
+}
+
+#[async_trait]
+impl TCPMux for TCPMuxDefault {
+    async fn close(&self) -> Result<(), Error> {
+        let mut closed_tx = self.closed_watch_tx.lock().await;
+
+        if let Some(tx) = closed_tx.take() {
+            let _ = tx.send(());
+            drop(closed_tx);
+
+            let old_conns = {
+                let mut conns = self.conns.lock().await;
+
+                std::mem::take(&mut (*conns))
+            };
+
+            for (_, conn) in old_conns {
+                let _ = conn.close().await;
+            }
+
+            Ok(())
+        } else {
+            Err(Error::ErrAlreadyClosed)
+        }
+    }
+
+    async fn get_conn_by_ufrag(
+        self: Arc<Self>,
+        ufrag: &str,
+    ) -> Result<Arc<dyn Conn + Send + Sync>, Error> {
+        if self.is_closed().await {
+            return Err(Error::ErrUseClosedNetworkConn);
+        }
+
+        Ok(self.get_or_create_conn(ufrag).await as Arc<dyn Conn + Send + Sync>)
+    }
+
+    async fn remove_conn_by_ufrag(&self, ufrag: &str) {
+        let removed_conn = {
+            let mut conns = self.conns.lock().await;
+            conns.remove(ufrag)
+        };
+
+        if let Some(conn) = removed_conn {
+            let _ = conn.close().await;
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::time::Duration;
+
+use stun::message::{Message, BINDING_REQUEST};
+use tokio::io::AsyncReadExt;
+use tokio::time::timeout;
+
+use super::tcp_packet_conn::write_streaming_packet;
+use super::*;
+use crate::error::Result;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn binding_request(username: &str) -> Vec<u8> {
+    let mut m = Message {
+        typ: BINDING_REQUEST,
+        ..Message::default()
+    };
+    m.add(ATTR_USERNAME, username.as_bytes());
+    m.write_header();
+
+    m.marshal_binary().unwrap()
+}
+
+async fn listen() -> Result<(Arc<TCPMuxDefault>, SocketAddr)> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let tcp_mux = TCPMuxDefault::new(TCPMuxParams::new(listener))?;
+    let addr = tcp_mux.local_addr();
+
+    Ok((tcp_mux, addr))
+}
+
+#[tokio::test]
+async fn test_streaming_packet_framing() -> Result<()> {
+    let (mut a, mut b) = tokio::io::duplex(64);
+
+    write_streaming_packet(&mut a, b"hello").await?;
+    write_streaming_packet(&mut a, b"").await?;
+    write_streaming_packet(&mut a, b"world!").await?;
+
+    let mut buf = [0u8; 16];
+    let n = read_streaming_packet(&mut b, &mut buf).await?;
+    assert_eq!(&buf[..n], b"hello");
+    let n = read_streaming_packet(&mut b, &mut buf).await?;
+    assert_eq!(n, 0);
+    let n = read_streaming_packet(&mut b, &mut buf).await?;
+    assert_eq!(&buf[..n], b"world!");
+
+    // A packet that doesn't fit the buffer is rejected rather than truncated
+    write_streaming_packet(&mut a, &[0u8; 32]).await?;
+    assert!(read_streaming_packet(&mut b, &mut buf).await.is_err());
+
+    assert!(
+        write_streaming_packet(&mut a, &vec![0u8; u16::MAX as usize + 1])
+            .await
+            .is_err(),
+        "packets longer than the 16 bit length field must not be framed"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tcp_mux_routes_by_ufrag() -> Result<()> {
+    let (tcp_mux, addr) = listen().await?;
+
+    let conn1 = Arc::clone(&tcp_mux).get_conn_by_ufrag("ufrag1").await?;
+    let conn2 = Arc::clone(&tcp_mux).get_conn_by_ufrag("ufrag2").await?;
+    assert_eq!(conn1.local_addr()?, addr);
+
+    let mut buffer = vec![0u8; RECEIVE_MTU];
+    for (ufrag, conn) in [("ufrag1", &conn1), ("ufrag2", &conn2)] {
+        let mut remote = TcpStream::connect(addr).await?;
+        let remote_addr = remote.local_addr()?;
+
+        let stun_msg = binding_request(&format!("{ufrag}:otherufrag"));
+        write_streaming_packet(&mut remote, &stun_msg).await?;
+        write_streaming_packet(&mut remote, b"after binding").await?;
+
+        let (n, from) = timeout(TIMEOUT, conn.recv_from(&mut buffer))
+            .await
+            .expect("timed out")?;
+        assert_eq!(&buffer[..n], &stun_msg[..]);
+        assert_eq!(from, remote_addr);
+
+        let (n, from) = timeout(TIMEOUT, conn.recv_from(&mut buffer))
+            .await
+            .expect("timed out")?;
+        assert_eq!(&buffer[..n], b"after binding");
+        assert_eq!(from, remote_addr);
+
+        conn.send_to(b"response", remote_addr).await?;
+        let n = timeout(TIMEOUT, read_streaming_packet(&mut remote, &mut buffer))
+            .await
+            .expect("timed out")?;
+        assert_eq!(&buffer[..n], b"response");
+    }
+
+    tcp_mux.close().await?;
+    assert!(
+        Arc::clone(&tcp_mux)
+            .get_conn_by_ufrag("ufrag1")
+            .await
+            .is_err(),
+        "Getting connections after TCPMuxDefault is closed should fail"
+    );
+    assert!(conn1.recv_from(&mut buffer).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tcp_mux_drops_non_binding_request() -> Result<()> {
+    let (tcp_mux, addr) = listen().await?;
+
+    let mut remote = TcpStream::connect(addr).await?;
+    write_streaming_packet(&mut remote, b"not a stun message").await?;
+
+    // The mux hangs up on connections that don't start with a binding request
+    let mut buf = [0u8; 1];
+    let n = timeout(TIMEOUT, remote.read(&mut buf))
+        .await
+        .expect("timed out")?;
+    assert_eq!(n, 0);
+
+    tcp_mux.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tcp_mux_remove_conn_by_ufrag() -> Result<()> {
+    let (tcp_mux, addr) = listen().await?;
+
+    let conn = Arc::clone(&tcp_mux).get_conn_by_ufrag("ufrag").await?;
+
+    let mut remote = TcpStream::connect(addr).await?;
+    write_streaming_packet(&mut remote, &binding_request("ufrag:otherufrag")).await?;
+
+    let mut buffer = vec![0u8; RECEIVE_MTU];
+    timeout(TIMEOUT, conn.recv_from(&mut buffer))
+        .await
+        .expect("timed out")?;
+
+    tcp_mux.remove_conn_by_ufrag("ufrag").await;
+
+    // Removing the conn closes it together with the streams routed to it
+    assert!(conn.recv_from(&mut buffer).await.is_err());
+    let n = timeout(TIMEOUT, remote.read(&mut buffer))
+        .await
+        .expect("timed out")?;
+    assert_eq!(n, 0);
+
+    let new_conn = Arc::clone(&tcp_mux).get_conn_by_ufrag("ufrag").await?;
+    assert!(!Arc::ptr_eq(&conn, &new_conn));
+
+    tcp_mux.close().await?;
+
+    Ok(())
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, watch, Mutex};
+use util::sync::Mutex as SyncMutex;
+use util::{Conn, Error};
+
+use crate::candidate::RECEIVE_MTU;
+
+/// The maximum number of packets buffered by a [`TCPPacketConn`] before the readers of the
+/// underlying streams are back-pressured.
+const TCP_PACKET_CONN_QUEUE_SIZE: usize = 128;
+
+type ConnResult<T> = Result<T, util::Error>;
+
+/// Reads a single RFC 4571 framed packet from `reader` into `buf`.
+///
+/// Returns the length of the packet. Packets that don't fit into `buf` are an error, since
+/// silently truncating them would desynchronize the stream.
+pub(crate) async fn read_streaming_packet<R>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize>
+where
+    R: AsyncRead + Unpin,
+{
+    // RFC 4571, section 2: every packet is prefixed with a 16 bit length field.
+    let len = reader.read_u16().await? as usize;
+    if len > buf.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "framed packet of {} bytes exceeds buffer of {}",
+                len,
+                buf.len()
+            ),
+        ));
+    }
+
+    reader.read_exact(&mut buf[..len]).await?;
+
+    Ok(len)
+}
+
+/// Writes `buf` as a single RFC 4571 framed packet to `writer`.
+///
+/// Returns the number of payload bytes written, excluding the length prefix.
+pub(crate) async fn write_streaming_packet<W>(writer: &mut W, buf: &[u8]) -> io::Result<usize>
+where
+    W: AsyncWrite + Unpin,
+{
+    if buf.len() > u16::MAX as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("packet of {} bytes is too large to be framed", buf.len()),
+        ));
+    }
+
+    // Assemble the frame up front so the length prefix and payload go out in one write.
+    let mut frame = Vec::with_capacity(2 + buf.len());
+    frame.extend_from_slice(&(buf.len() as u16).to_be_bytes());
+    frame.extend_from_slice(buf);
+    writer.write_all(&frame).await?;
+
+    Ok(buf.len())
+}
+
+/// A packet oriented [`Conn`] on top of one or more TCP streams.
+///
+/// Each stream carries RFC 4571 framed packets. Packets received on any stream are returned from
+/// [`Conn::recv_from`] together with the remote address of the stream, and [`Conn::send_to`]
+/// writes to the stream connected to the target address.
+pub struct TCPPacketConn {
+    local_addr: SocketAddr,
+
+    /// Write halves of the streams, keyed by their remote address.
+    writers: Arc<SyncMutex<HashMap<SocketAddr, Arc<Mutex<OwnedWriteHalf>>>>>,
+
+    packets_tx: mpsc::Sender<(Vec<u8>, SocketAddr)>,
+    packets_rx: Mutex<mpsc::Receiver<(Vec<u8>, SocketAddr)>>,
+
+    /// Close Sender. We'll send a value on this channel when we close
+    closed_watch_tx: SyncMutex<Option<watch::Sender<bool>>>,
+    closed_watch_rx: watch::Receiver<bool>,
+}
+
+impl TCPPacketConn {
+    /// Creates a new [`TCPPacketConn`] without any streams attached.
+    pub fn new(local_addr: SocketAddr) -> Self {
+        let (packets_tx, packets_rx) = mpsc::channel(TCP_PACKET_CONN_QUEUE_SIZE);
+        let (closed_watch_tx, closed_watch_rx) = watch::channel(false);
+
+        Self {
+            local_addr,
+            writers: Arc::new(SyncMutex::new(HashMap::new())),
+            packets_tx,
+            packets_rx: Mutex::new(packets_rx),
+            closed_watch_tx: SyncMutex::new(Some(closed_watch_tx)),
+            closed_watch_rx,
+        }
+    }
+
+    /// Attaches a TCP stream to this connection.
+    ///
+    /// `first_packet` is a packet that has already been read off the stream, e.g. by a mux that
+    /// needed it to route the stream, and is delivered before anything else read from it.
+    pub fn add_conn(&self, stream: TcpStream, first_packet: Option<Vec<u8>>) -> ConnResult<()> {
+        if self.is_closed() {
+            return Err(Error::ErrUseClosedNetworkConn);
+        }
+
+        let remote_addr = stream.peer_addr()?;
+        let (mut reader, writer) = stream.into_split();
+
+        {
+            let mut writers = self.writers.lock();
+            if writers.contains_key(&remote_addr) {
+                return Err(Error::from_std(crate::Error::ErrTcpRemoteAddrAlreadyExists));
+            }
+            writers.insert(remote_addr, Arc::new(Mutex::new(writer)));
+        }
+
+        let writers = Arc::clone(&self.writers);
+        let packets_tx = self.packets_tx.clone();
+        let mut closed_watch_rx = self.closed_watch_rx.clone();
+        tokio::spawn(async move {
+            if let Some(packet) = first_packet {
+                if packets_tx.send((packet, remote_addr)).await.is_err() {
+                    return;
+                }
+            }
+
+            let mut buf = vec![0u8; RECEIVE_MTU];
+            loop {
+                tokio::select! {
+                    result = read_streaming_packet(&mut reader, &mut buf) => {
+                        match result {
+                            Ok(n) => {
+                                if packets_tx.send((buf[..n].to_vec(), remote_addr)).await.is_err() {
+                                    return;
+                                }
+                            }
+                            Err(err) => {
+                                log::debug!("Closing TCP stream from {}: {}", remote_addr, err);
+                                writers.lock().remove(&remote_addr);
+                                return;
+                            }
+                        }
+                    }
+                    _ = closed_watch_rx.changed() => return,
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Returns the remote addresses of all streams attached to this connection.
+    pub fn remote_addrs(&self) -> Vec<SocketAddr> {
+        self.writers.lock().keys().copied().collect()
+    }
+
+    /// Returns true if this connection is closed.
+    pub fn is_closed(&self) -> bool {
+        self.closed_watch_tx.lock().is_none()
+    }
+
+    /// Gets a copy of the close [`tokio::sync::watch::Receiver`] that fires when this
+    /// connection is closed.
+    pub fn close_rx(&self) -> watch::Receiver<bool> {
+        self.closed_watch_rx.clone()
+    }
+
+    fn remove_conn(&self, remote_addr: &SocketAddr) {
+        self.writers.lock().remove(remote_addr);
+    }
+}
+
+#[async_trait]
+impl Conn for TCPPacketConn {
+    async fn connect(&self, _addr: SocketAddr) -> ConnResult<()> {
+        Err(io::Error::new(io::ErrorKind::Other, "Not applicable").into())
+    }
+
+    async fn recv(&self, _buf: &mut [u8]) -> ConnResult<usize> {
+        Err(io::Error::new(io::ErrorKind::Other, "Not applicable").into())
+    }
+
+    async fn recv_from(&self, buf: &mut [u8]) -> ConnResult<(usize, SocketAddr)> {
+        let mut closed_watch_rx = self.closed_watch_rx.clone();
+        if *closed_watch_rx.borrow() {
+            return Err(Error::ErrUseClosedNetworkConn);
+        }
+
+        let mut packets_rx = self.packets_rx.lock().await;
+        tokio::select! {
+            packet = packets_rx.recv() => {
+                match packet {
+                    Some((packet, remote_addr)) => {
+                        if packet.len() > buf.len() {
+                            return Err(Error::ErrBufferShort);
+                        }
+                        buf[..packet.len()].copy_from_slice(&packet);
+                        Ok((packet.len(), remote_addr))
+                    }
+                    None => Err(Error::ErrUseClosedNetworkConn),
+                }
+            }
+            _ = closed_watch_rx.changed() => Err(Error::ErrUseClosedNetworkConn),
+        }
+    }
+
+    async fn send(&self, _buf: &[u8]) -> ConnResult<usize> {
+        Err(io::Error::new(io::ErrorKind::Other, "Not applicable").into())
+    }
+
+    async fn send_to(&self, buf: &[u8], target: SocketAddr) -> ConnResult<usize> {
+        let writer = self.writers.lock().get(&target).cloned();
+        let writer = match writer {
+            Some(writer) => writer,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotConnected,
+                    format!("no TCP stream to {target}"),
+                )
+                .into())
+            }
+        };
+
+        let mut writer = writer.lock().await;
+        match write_streaming_packet(&mut *writer, buf).await {
+            Ok(n) => Ok(n),
+            Err(err) => {
+                self.remove_conn(&target);
+                Err(err.into())
+            }
+        }
+    }
+
+    fn local_addr(&self) -> ConnResult<SocketAddr> {
+        Ok(self.local_addr)
+    }
+
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    async fn close(&self) -> ConnResult<()> {
+        let closed_watch_tx = self.closed_watch_tx.lock().take();
+        if let Some(tx) = closed_watch_tx {
+            let _ = tx.send(true);
+
+            // Dropping the write halves, together with the read halves owned by the reader tasks
+            // that just got notified, closes the underlying streams.
+            let writers = std::mem::take(&mut *self.writers.lock());
+            for (_, writer) in writers {
+                let _ = writer.lock().await.shutdown().await;
+            }
+
+            Ok(())
+        } else {
+            Err(Error::ErrAlreadyClosed)
+        }
+    }
+}
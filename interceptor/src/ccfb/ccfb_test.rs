@@ -0,0 +1,115 @@
+use super::*;
+
+fn report(pkts: &[Box<dyn rtcp::packet::Packet + Send + Sync>]) -> &CongestionControlFeedback {
+    assert_eq!(pkts.len(), 1);
+    pkts[0]
+        .as_any()
+        .downcast_ref::<CongestionControlFeedback>()
+        .unwrap()
+}
+
+fn received(ecn: Ecn, arrival_time_offset: u16) -> CcfbMetricBlock {
+    CcfbMetricBlock {
+        received: true,
+        ecn,
+        arrival_time_offset,
+    }
+}
+
+#[test]
+fn test_recorder_empty() {
+    let mut r = Recorder::new(5000);
+    assert!(r.build_feedback_packet(0, 0).is_empty());
+}
+
+#[test]
+fn test_recorder_feedback() {
+    let mut r = Recorder::new(5000);
+    r.record(1, 10, Ecn::NotEct, 0);
+    r.record(1, 13, Ecn::Ce, 500_000);
+    r.record(1, 11, Ecn::Ect0, 1_000_000);
+    r.record(2, 0, Ecn::Ect1, 1_000_000);
+
+    let pkts = r.build_feedback_packet(1_000_000, 0x1234);
+    let fb = report(&pkts);
+    assert_eq!(fb.sender_ssrc, 5000);
+    assert_eq!(fb.report_timestamp, 0x1234);
+    assert_eq!(
+        fb.report_blocks,
+        vec![
+            CcfbReportBlock {
+                media_ssrc: 1,
+                begin_sequence: 10,
+                metric_blocks: vec![
+                    received(Ecn::NotEct, 1024),
+                    received(Ecn::Ect0, 0),
+                    CcfbMetricBlock::default(),
+                    received(Ecn::Ce, 512),
+                ],
+            },
+            CcfbReportBlock {
+                media_ssrc: 2,
+                begin_sequence: 0,
+                metric_blocks: vec![received(Ecn::Ect1, 0)],
+            },
+        ]
+    );
+
+    // Packets are only reported once and late packets already reported lost
+    // are ignored.
+    assert!(r.build_feedback_packet(2_000_000, 0).is_empty());
+    r.record(1, 12, Ecn::NotEct, 2_000_000);
+    assert!(r.build_feedback_packet(2_000_000, 0).is_empty());
+
+    r.record(1, 15, Ecn::NotEct, 2_000_000);
+    let pkts = r.build_feedback_packet(12_000_000, 0);
+    assert_eq!(
+        report(&pkts).report_blocks,
+        vec![CcfbReportBlock {
+            media_ssrc: 1,
+            begin_sequence: 14,
+            metric_blocks: vec![
+                CcfbMetricBlock::default(),
+                received(Ecn::NotEct, CCFB_ARRIVAL_TIME_OFFSET_OVERRANGE),
+            ],
+        }]
+    );
+}
+
+#[test]
+fn test_recorder_sequence_number_wrap() {
+    let mut r = Recorder::new(0);
+    r.record(1, 65534, Ecn::NotEct, 0);
+    r.record(1, 1, Ecn::NotEct, 0);
+    r.record(1, 65535, Ecn::NotEct, 0);
+
+    let pkts = r.build_feedback_packet(0, 0);
+    let block = &report(&pkts).report_blocks[0];
+    assert_eq!(block.begin_sequence, 65534);
+    assert_eq!(
+        block.metric_blocks,
+        vec![
+            received(Ecn::NotEct, 0),
+            received(Ecn::NotEct, 0),
+            CcfbMetricBlock::default(),
+            received(Ecn::NotEct, 0),
+        ]
+    );
+}
+
+#[test]
+fn test_recorder_max_metric_blocks() {
+    let mut r = Recorder::new(0);
+    r.record(1, 0, Ecn::NotEct, 0);
+    r.record(1, 20000, Ecn::NotEct, 0);
+
+    let pkts = r.build_feedback_packet(0, 0);
+    let block = &report(&pkts).report_blocks[0];
+    assert_eq!(
+        block.begin_sequence as usize,
+        20001 - CCFB_MAX_METRIC_BLOCKS
+    );
+    assert_eq!(block.metric_blocks.len(), CCFB_MAX_METRIC_BLOCKS);
+    assert_eq!(block.metric_blocks[0], CcfbMetricBlock::default());
+    assert!(block.metric_blocks[CCFB_MAX_METRIC_BLOCKS - 1].received);
+}
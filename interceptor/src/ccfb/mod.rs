@@ -0,0 +1,158 @@
+#[cfg(test)]
+mod ccfb_test;
+
+pub mod receiver;
+
+use std::collections::{BTreeMap, HashMap};
+
+use rtcp::transport_feedbacks::ccfb::{
+    CcfbMetricBlock, CcfbReportBlock, CongestionControlFeedback, Ecn,
+    CCFB_ARRIVAL_TIME_OFFSET_OVERRANGE, CCFB_MAX_ARRIVAL_TIME_OFFSET, CCFB_MAX_METRIC_BLOCKS,
+};
+
+/// The parameter of the `ack` RTCP feedback type that negotiates congestion control
+/// feedback, `a=rtcp-fb:* ack ccfb`.
+pub const RTCP_FB_ACK_CCFB: &str = "ccfb";
+
+#[derive(Default, Debug, PartialEq, Clone)]
+struct StreamLog {
+    /// Extended sequence number of the first packet not reported yet.
+    begin: i64,
+    /// Extended sequence number of the highest received packet.
+    last: i64,
+    /// Arrival time in microseconds and ECN codepoint of received packets.
+    packets: BTreeMap<i64, (i64, Ecn)>,
+}
+
+impl StreamLog {
+    fn new(sequence_number: u16) -> Self {
+        StreamLog {
+            begin: sequence_number as i64,
+            last: sequence_number as i64,
+            packets: BTreeMap::new(),
+        }
+    }
+
+    fn unwrap(&self, sequence_number: u16) -> i64 {
+        let delta = sequence_number.wrapping_sub(self.last as u16) as i16;
+        self.last + delta as i64
+    }
+}
+
+/// Recorder records incoming RTP packets, their arrival times and ECN codepoints
+/// and creates congestion control feedback reports as specified in
+/// https://datatracker.ietf.org/doc/html/rfc8888
+///
+/// Every packet is reported once. Packets arriving after a report already
+/// counted them as lost are ignored.
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct Recorder {
+    streams: HashMap<u32, StreamLog>,
+    sender_ssrc: u32,
+}
+
+impl Recorder {
+    /// new creates a new Recorder which uses the given sender_ssrc in the created
+    /// feedback packets.
+    pub fn new(sender_ssrc: u32) -> Self {
+        Recorder {
+            sender_ssrc,
+            ..Default::default()
+        }
+    }
+
+    /// record marks the packet of media_ssrc with the RTP sequence number
+    /// sequence_number as received at arrival_time, in microseconds, with the
+    /// ECN codepoint ecn.
+    pub fn record(&mut self, media_ssrc: u32, sequence_number: u16, ecn: Ecn, arrival_time: i64) {
+        let stream = self
+            .streams
+            .entry(media_ssrc)
+            .or_insert_with(|| StreamLog::new(sequence_number));
+
+        let sequence_number = stream.unwrap(sequence_number);
+        if sequence_number < stream.begin {
+            return;
+        }
+        stream.last = stream.last.max(sequence_number);
+        stream
+            .packets
+            .entry(sequence_number)
+            .or_insert((arrival_time, ecn));
+
+        // A report block covers at most CCFB_MAX_METRIC_BLOCKS packets, older
+        // packets are dropped without being reported.
+        let max_begin = stream.last - CCFB_MAX_METRIC_BLOCKS as i64 + 1;
+        if stream.begin < max_begin {
+            stream.begin = max_begin;
+            stream.packets = stream.packets.split_off(&max_begin);
+        }
+    }
+
+    /// build_feedback_packet creates a new RTCP packet containing a congestion
+    /// control feedback report for all packets received since the last report.
+    /// now is the current time in microseconds, on the same clock as the arrival
+    /// times, and report_timestamp the middle 32 bits of the current NTP time.
+    pub fn build_feedback_packet(
+        &mut self,
+        now: i64,
+        report_timestamp: u32,
+    ) -> Vec<Box<dyn rtcp::packet::Packet + Send + Sync>> {
+        let mut ssrcs: Vec<u32> = self
+            .streams
+            .iter()
+            .filter(|(_, stream)| !stream.packets.is_empty())
+            .map(|(ssrc, _)| *ssrc)
+            .collect();
+        if ssrcs.is_empty() {
+            return vec![];
+        }
+        ssrcs.sort_unstable();
+
+        let mut report_blocks = Vec::with_capacity(ssrcs.len());
+        for media_ssrc in ssrcs {
+            let stream = match self.streams.get_mut(&media_ssrc) {
+                Some(stream) => stream,
+                None => continue,
+            };
+
+            let metric_blocks = (stream.begin..=stream.last)
+                .map(
+                    |sequence_number| match stream.packets.get(&sequence_number) {
+                        Some((arrival_time, ecn)) => CcfbMetricBlock {
+                            received: true,
+                            ecn: *ecn,
+                            arrival_time_offset: arrival_time_offset(now - arrival_time),
+                        },
+                        None => CcfbMetricBlock::default(),
+                    },
+                )
+                .collect();
+
+            report_blocks.push(CcfbReportBlock {
+                media_ssrc,
+                begin_sequence: stream.begin as u16,
+                metric_blocks,
+            });
+
+            stream.begin = stream.last + 1;
+            stream.packets.clear();
+        }
+
+        vec![Box::new(CongestionControlFeedback {
+            sender_ssrc: self.sender_ssrc,
+            report_blocks,
+            report_timestamp,
+        })]
+    }
+}
+
+/// arrival_time_offset converts a delay in microseconds to 1/1024 seconds.
+fn arrival_time_offset(delay: i64) -> u16 {
+    let offset = delay.max(0) * 1024 / 1_000_000;
+    if offset > CCFB_MAX_ARRIVAL_TIME_OFFSET as i64 {
+        CCFB_ARRIVAL_TIME_OFFSET_OVERRANGE
+    } else {
+        offset as u16
+    }
+}
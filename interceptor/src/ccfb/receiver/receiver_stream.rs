@@ -0,0 +1,53 @@
+use super::*;
+
+pub(super) struct ReceiverStream {
+    parent_rtp_reader: Arc<dyn RTPReader + Send + Sync>,
+    ssrc: u32,
+    packet_chan_tx: mpsc::Sender<Packet>,
+    // we use tokio's Instant because it makes testing easier via `tokio::time::advance`.
+    start_time: tokio::time::Instant,
+}
+
+impl ReceiverStream {
+    pub(super) fn new(
+        parent_rtp_reader: Arc<dyn RTPReader + Send + Sync>,
+        ssrc: u32,
+        packet_chan_tx: mpsc::Sender<Packet>,
+        start_time: tokio::time::Instant,
+    ) -> Self {
+        ReceiverStream {
+            parent_rtp_reader,
+            ssrc,
+            packet_chan_tx,
+            start_time,
+        }
+    }
+}
+
+#[async_trait]
+impl RTPReader for ReceiverStream {
+    /// read a rtp packet
+    async fn read(
+        &self,
+        buf: &mut [u8],
+        attributes: &Attributes,
+    ) -> Result<(rtp::packet::Packet, Attributes)> {
+        let (pkt, attr) = self.parent_rtp_reader.read(buf, attributes).await?;
+
+        let ecn = attr
+            .get(&ATTR_KEY_ECN)
+            .map(|ecn| Ecn::from(*ecn as u8))
+            .unwrap_or_default();
+        let _ = self
+            .packet_chan_tx
+            .send(Packet {
+                sequence_number: pkt.header.sequence_number,
+                ecn,
+                arrival_time: (tokio::time::Instant::now() - self.start_time).as_micros() as i64,
+                ssrc: self.ssrc,
+            })
+            .await;
+
+        Ok((pkt, attr))
+    }
+}
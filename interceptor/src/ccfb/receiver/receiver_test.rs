@@ -0,0 +1,123 @@
+use rtcp::transport_feedbacks::ccfb::{CcfbMetricBlock, CongestionControlFeedback};
+
+use super::*;
+use crate::mock::mock_stream::MockStream;
+use crate::stream_info::RTCPFeedback;
+
+fn ccfb_stream_info() -> StreamInfo {
+    StreamInfo {
+        ssrc: 1,
+        rtcp_feedback: vec![RTCPFeedback {
+            typ: "ack".to_owned(),
+            parameter: RTCP_FB_ACK_CCFB.to_owned(),
+        }],
+        ..Default::default()
+    }
+}
+
+async fn receive(stream: &MockStream, sequence_number: u16) {
+    stream
+        .receive_rtp(rtp::packet::Packet {
+            header: rtp::header::Header {
+                sequence_number,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn test_ccfb_receiver_interceptor_before_any_packets() -> Result<()> {
+    let icpr = Receiver::builder().build("")?;
+    let stream = MockStream::new(&ccfb_stream_info(), icpr).await;
+
+    tokio::select! {
+        pkts = stream.written_rtcp() => {
+            assert!(pkts.map(|p| p.is_empty()).unwrap_or(true), "Should not have sent an RTCP packet before receiving the first RTP packets")
+        }
+        _ = tokio::time::sleep(Duration::from_millis(300)) => {
+            // All good
+        }
+    }
+
+    stream.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ccfb_receiver_interceptor_not_negotiated() -> Result<()> {
+    let icpr = Receiver::builder()
+        .with_interval(Duration::from_millis(50))
+        .build("")?;
+    let stream = MockStream::new(
+        &StreamInfo {
+            ssrc: 1,
+            ..Default::default()
+        },
+        icpr,
+    )
+    .await;
+
+    for i in 0..10 {
+        receive(&stream, i).await;
+    }
+
+    tokio::select! {
+        pkts = stream.written_rtcp() => {
+            assert!(pkts.map(|p| p.is_empty()).unwrap_or(true), "Should not report on streams without ack ccfb")
+        }
+        _ = tokio::time::sleep(Duration::from_millis(300)) => {
+            // All good
+        }
+    }
+
+    stream.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_ccfb_receiver_interceptor_after_rtp_packets() -> Result<()> {
+    let icpr = Receiver::builder()
+        .with_interval(Duration::from_millis(500))
+        .build("")?;
+    let stream = MockStream::new(&ccfb_stream_info(), icpr).await;
+
+    for i in [0, 1, 3] {
+        receive(&stream, 100 + i).await;
+
+        // Yield so this packet can be processed
+        tokio::task::yield_now().await;
+        tokio::time::advance(Duration::from_millis(125)).await;
+    }
+
+    // Force a packet to be generated
+    tokio::time::advance(Duration::from_millis(500)).await;
+    tokio::task::yield_now().await;
+
+    let pkts = stream.written_rtcp().await.unwrap();
+    assert_eq!(pkts.len(), 1);
+    let fb = pkts[0]
+        .as_any()
+        .downcast_ref::<CongestionControlFeedback>()
+        .unwrap();
+    assert_eq!(fb.report_blocks.len(), 1);
+    let block = &fb.report_blocks[0];
+    assert_eq!(block.media_ssrc, 1);
+    assert_eq!(block.begin_sequence, 100);
+    assert_eq!(block.metric_blocks.len(), 4);
+    assert!(block.metric_blocks[0].received);
+    assert!(block.metric_blocks[1].received);
+    assert_eq!(block.metric_blocks[2], CcfbMetricBlock::default());
+    assert!(block.metric_blocks[3].received);
+    assert!(
+        block.metric_blocks[0].arrival_time_offset > block.metric_blocks[3].arrival_time_offset,
+        "earlier packets have larger arrival time offsets"
+    );
+
+    stream.close().await?;
+
+    Ok(())
+}
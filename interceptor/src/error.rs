@@ -21,6 +21,8 @@ pub enum Error {
     ErrShortBuffer,
     #[error("Invalid buffer size")]
     ErrInvalidSize,
+    #[error("Invalid number of media or FEC packets")]
+    ErrInvalidFecPacketCount,
 
     #[error("{0}")]
     Srtp(#[from] srtp::Error),
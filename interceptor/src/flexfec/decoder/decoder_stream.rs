@@ -0,0 +1,231 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use tokio::sync::{mpsc, Mutex};
+use util::marshal::Marshal;
+
+use crate::error::{Error, Result};
+use crate::flexfec::flexfec_03::FecPacket;
+use crate::flexfec::UINT16SIZE_HALF;
+use crate::{Attributes, RTPReader};
+
+/// Maximum number of FEC packets waiting for their protected packets.
+const MAX_FEC_PACKETS: usize = 64;
+
+struct DecoderStreamInternal {
+    ssrc: u32,
+    packets: Vec<Option<(u16, Bytes)>>,
+    size: u16,
+    last_added: u16,
+    started: bool,
+    fec_packets: VecDeque<FecPacket>,
+}
+
+impl DecoderStreamInternal {
+    fn new(log2_size: u8, ssrc: u32) -> Self {
+        DecoderStreamInternal {
+            ssrc,
+            packets: vec![None; 1 << log2_size],
+            size: 1 << log2_size,
+            last_added: 0,
+            started: false,
+            fec_packets: VecDeque::new(),
+        }
+    }
+
+    /// add stores a media packet, returning false if it was already received or recovered.
+    fn add(&mut self, seq: u16, raw: Bytes) -> bool {
+        if self.get(seq).is_some() {
+            return false;
+        }
+
+        if !self.started || seq.wrapping_sub(self.last_added) < UINT16SIZE_HALF {
+            self.last_added = seq;
+            self.started = true;
+        }
+        self.packets[(seq % self.size) as usize] = Some((seq, raw));
+        true
+    }
+
+    fn get(&self, seq: u16) -> Option<&Bytes> {
+        match &self.packets[(seq % self.size) as usize] {
+            Some((s, raw)) if *s == seq => Some(raw),
+            _ => None,
+        }
+    }
+
+    /// is_stale reports if a packet is too old to still be kept.
+    fn is_stale(&self, seq: u16) -> bool {
+        let diff = self.last_added.wrapping_sub(seq);
+        diff < UINT16SIZE_HALF && diff >= self.size
+    }
+
+    fn add_fec(&mut self, fec: FecPacket) {
+        if fec.header.ssrc != self.ssrc {
+            return;
+        }
+        if self.fec_packets.len() == MAX_FEC_PACKETS {
+            self.fec_packets.pop_front();
+        }
+        self.fec_packets.push_back(fec);
+    }
+
+    /// recover restores the packets that are the only missing packet protected
+    /// by a FEC packet. A recovered packet can make other packets recoverable.
+    fn recover(&mut self) -> Vec<rtp::packet::Packet> {
+        let mut recovered = vec![];
+        let mut i = 0;
+        while i < self.fec_packets.len() {
+            let header = &self.fec_packets[i].header;
+            let mut missing = vec![];
+            let mut stale = false;
+            for seq in header.protected_sequence_numbers() {
+                if self.is_stale(seq) {
+                    stale = true;
+                    break;
+                }
+                if self.get(seq).is_none() {
+                    missing.push(seq);
+                }
+            }
+
+            if stale || missing.is_empty() {
+                self.fec_packets.remove(i);
+                continue;
+            }
+            if missing.len() > 1 {
+                i += 1;
+                continue;
+            }
+
+            let seq = missing[0];
+            if let Some(fec) = self.fec_packets.remove(i) {
+                let received = fec
+                    .header
+                    .protected_sequence_numbers()
+                    .filter(|s| *s != seq)
+                    .filter_map(|s| self.get(s));
+                if let Some(pkt) = fec.recover(seq, received) {
+                    if let Ok(raw) = pkt.marshal() {
+                        self.add(seq, raw);
+                    }
+                    recovered.push(pkt);
+                    i = 0;
+                }
+            }
+        }
+
+        recovered
+    }
+}
+
+/// DecoderStream reads a protected stream, adding the packets recovered from its FEC stream.
+pub(super) struct DecoderStream {
+    internal: util::sync::Mutex<DecoderStreamInternal>,
+    recovered_tx: mpsc::Sender<rtp::packet::Packet>,
+    recovered_rx: Mutex<mpsc::Receiver<rtp::packet::Packet>>,
+    parent_rtp_reader: Arc<dyn RTPReader + Send + Sync>,
+}
+
+impl DecoderStream {
+    pub(super) fn new(log2_size: u8, ssrc: u32, reader: Arc<dyn RTPReader + Send + Sync>) -> Self {
+        let (recovered_tx, recovered_rx) = mpsc::channel(MAX_FEC_PACKETS);
+        DecoderStream {
+            internal: util::sync::Mutex::new(DecoderStreamInternal::new(log2_size, ssrc)),
+            recovered_tx,
+            recovered_rx: Mutex::new(recovered_rx),
+            parent_rtp_reader: reader,
+        }
+    }
+
+    /// add_media returns false for packets that were already received or recovered.
+    fn add_media(&self, pkt: &rtp::packet::Packet) -> bool {
+        let raw = match pkt.marshal() {
+            Ok(raw) => raw,
+            Err(_) => return true,
+        };
+
+        let mut internal = self.internal.lock();
+        if !internal.add(pkt.header.sequence_number, raw) {
+            return false;
+        }
+        for pkt in internal.recover() {
+            let _ = self.recovered_tx.try_send(pkt);
+        }
+        true
+    }
+
+    fn add_fec(&self, pkt: &rtp::packet::Packet) {
+        if let Some(fec) = FecPacket::unmarshal(pkt) {
+            let mut internal = self.internal.lock();
+            internal.add_fec(fec);
+            for pkt in internal.recover() {
+                // Recovered packets are dropped if the stream isn't read
+                let _ = self.recovered_tx.try_send(pkt);
+            }
+        }
+    }
+}
+
+/// RTPReader is used by Interceptor.bind_remote_stream.
+#[async_trait]
+impl RTPReader for DecoderStream {
+    /// read a rtp packet
+    async fn read(
+        &self,
+        buf: &mut [u8],
+        a: &Attributes,
+    ) -> Result<(rtp::packet::Packet, Attributes)> {
+        let mut recovered_rx = self.recovered_rx.lock().await;
+        loop {
+            tokio::select! {
+                biased;
+
+                pkt = recovered_rx.recv() => {
+                    return pkt.map(|pkt| (pkt, Attributes::new())).ok_or(Error::ErrIoEOF);
+                }
+                result = self.parent_rtp_reader.read(buf, a) => {
+                    let (pkt, attr) = result?;
+                    if self.add_media(&pkt) {
+                        return Ok((pkt, attr));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// FecStreamReader reads a FEC stream, passing its packets to the stream they protect.
+pub(super) struct FecStreamReader {
+    stream: Arc<DecoderStream>,
+    parent_rtp_reader: Arc<dyn RTPReader + Send + Sync>,
+}
+
+impl FecStreamReader {
+    pub(super) fn new(
+        stream: Arc<DecoderStream>,
+        reader: Arc<dyn RTPReader + Send + Sync>,
+    ) -> Self {
+        FecStreamReader {
+            stream,
+            parent_rtp_reader: reader,
+        }
+    }
+}
+
+/// RTPReader is used by Interceptor.bind_remote_stream.
+#[async_trait]
+impl RTPReader for FecStreamReader {
+    /// read a rtp packet
+    async fn read(
+        &self,
+        buf: &mut [u8],
+        a: &Attributes,
+    ) -> Result<(rtp::packet::Packet, Attributes)> {
+        let (pkt, attr) = self.parent_rtp_reader.read(buf, a).await?;
+        self.stream.add_fec(&pkt);
+        Ok((pkt, attr))
+    }
+}
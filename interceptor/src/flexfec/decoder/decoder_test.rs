@@ -0,0 +1,149 @@
+use bytes::Bytes;
+use tokio::time::Duration;
+
+use super::*;
+use crate::flexfec::flexfec_03::FlexFecEncoder03;
+use crate::mock::mock_stream::MockStream;
+use crate::test::timeout_or_fail;
+
+fn media_packet(seq: u16) -> rtp::packet::Packet {
+    rtp::packet::Packet {
+        header: rtp::header::Header {
+            version: 2,
+            payload_type: 96,
+            sequence_number: seq,
+            timestamp: 90 * u32::from(seq),
+            ssrc: 1,
+            ..Default::default()
+        },
+        payload: Bytes::from(vec![seq as u8; 1 + seq as usize % 7]),
+    }
+}
+
+#[tokio::test]
+async fn test_flexfec_decoder_interceptor() -> Result<()> {
+    let icpr: Arc<dyn Interceptor + Send + Sync> = FlexFecDecoder::builder().build("")?;
+
+    let media_stream = MockStream::new(
+        &StreamInfo {
+            ssrc: 1,
+            ssrc_forward_error_correction: 2,
+            ..Default::default()
+        },
+        Arc::clone(&icpr),
+    )
+    .await;
+    let fec_stream = MockStream::new(
+        &StreamInfo {
+            ssrc: 2,
+            ..Default::default()
+        },
+        icpr,
+    )
+    .await;
+
+    let media_packets: Vec<_> = (10..16).map(media_packet).collect();
+    let fec_packets = FlexFecEncoder03::new(2, 49).encode_fec(&media_packets, 2);
+
+    // 12 and 13 are lost, they are protected by different FEC packets
+    for pkt in &media_packets {
+        if [12, 13].contains(&pkt.header.sequence_number) {
+            continue;
+        }
+        media_stream.receive_rtp(pkt.clone()).await;
+        let p = timeout_or_fail(Duration::from_millis(10), media_stream.read_rtp())
+            .await
+            .expect("A packet")
+            .expect("Not an error");
+        assert_eq!(&p, pkt);
+    }
+
+    for pkt in &fec_packets {
+        fec_stream.receive_rtp(pkt.clone()).await;
+        let p = timeout_or_fail(Duration::from_millis(10), fec_stream.read_rtp())
+            .await
+            .expect("A packet")
+            .expect("Not an error");
+        assert_eq!(&p, pkt);
+    }
+
+    let mut recovered = vec![];
+    for _ in 0..2 {
+        let p = timeout_or_fail(Duration::from_millis(10), media_stream.read_rtp())
+            .await
+            .expect("A recovered packet")
+            .expect("Not an error");
+        recovered.push(p);
+    }
+    recovered.sort_by_key(|p| p.header.sequence_number);
+    assert_eq!(recovered, media_packets[2..4]);
+
+    // a late copy of a recovered packet is dropped
+    media_stream.receive_rtp(media_packets[2].clone()).await;
+    media_stream.receive_rtp(media_packet(16)).await;
+    let p = timeout_or_fail(Duration::from_millis(10), media_stream.read_rtp())
+        .await
+        .expect("A packet")
+        .expect("Not an error");
+    assert_eq!(p.header.sequence_number, 16);
+
+    media_stream.close().await?;
+    fec_stream.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_flexfec_decoder_unrecoverable() -> Result<()> {
+    let icpr: Arc<dyn Interceptor + Send + Sync> = FlexFecDecoder::builder().build("")?;
+
+    let media_stream = MockStream::new(
+        &StreamInfo {
+            ssrc: 1,
+            ssrc_forward_error_correction: 2,
+            ..Default::default()
+        },
+        Arc::clone(&icpr),
+    )
+    .await;
+    let fec_stream = MockStream::new(
+        &StreamInfo {
+            ssrc: 2,
+            ..Default::default()
+        },
+        icpr,
+    )
+    .await;
+
+    let media_packets: Vec<_> = (10..14).map(media_packet).collect();
+    let fec_packets = FlexFecEncoder03::new(2, 49).encode_fec(&media_packets, 1);
+
+    // two packets protected by the same FEC packet can't be recovered
+    for pkt in &media_packets[..2] {
+        media_stream.receive_rtp(pkt.clone()).await;
+        let _ = media_stream.read_rtp().await;
+    }
+    fec_stream.receive_rtp(fec_packets[0].clone()).await;
+    let _ = fec_stream.read_rtp().await;
+
+    let result = tokio::time::timeout(Duration::from_millis(10), media_stream.read_rtp()).await;
+    assert!(result.is_err(), "unexpected recovered packet");
+
+    // until one of them is received
+    media_stream.receive_rtp(media_packets[3].clone()).await;
+    let mut packets = vec![];
+    for _ in 0..2 {
+        let p = timeout_or_fail(Duration::from_millis(10), media_stream.read_rtp())
+            .await
+            .expect("A packet")
+            .expect("Not an error");
+        packets.push(p);
+    }
+    packets.sort_by_key(|p| p.header.sequence_number);
+    assert_eq!(packets, media_packets[2..4]);
+
+    media_stream.close().await?;
+    fec_stream.close().await?;
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+mod decoder_stream;
+#[cfg(test)]
+mod decoder_test;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use decoder_stream::{DecoderStream, FecStreamReader};
+use tokio::sync::Mutex;
+
+use crate::error::Result;
+use crate::stream_info::StreamInfo;
+use crate::{Interceptor, InterceptorBuilder, RTCPReader, RTCPWriter, RTPReader, RTPWriter};
+
+/// FlexFecDecoderBuilder can be used to configure FlexFecDecoder Interceptor
+#[derive(Default)]
+pub struct FlexFecDecoderBuilder {
+    log2_size: Option<u8>,
+}
+
+impl FlexFecDecoderBuilder {
+    /// with_log2_size sets the number of received media packets kept for recovering lost packets.
+    /// Size must be one of: 1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024, 2048, 4096, 8192, 16384, 32768
+    pub fn with_log2_size(mut self, log2_size: u8) -> FlexFecDecoderBuilder {
+        self.log2_size = Some(log2_size);
+        self
+    }
+}
+
+impl InterceptorBuilder for FlexFecDecoderBuilder {
+    fn build(&self, _id: &str) -> Result<Arc<dyn Interceptor + Send + Sync>> {
+        Ok(Arc::new(FlexFecDecoder {
+            log2_size: self.log2_size.unwrap_or(9), // 512 = 1 << 9
+            streams: Mutex::new(HashMap::new()),
+        }))
+    }
+}
+
+/// FlexFecDecoder recovers lost packets of the incoming streams from their FlexFEC streams.
+/// Recovered packets are returned by the reads of the stream they belong to.
+pub struct FlexFecDecoder {
+    log2_size: u8,
+    /// Protected streams by the SSRC of their FEC stream.
+    streams: Mutex<HashMap<u32, Arc<DecoderStream>>>,
+}
+
+impl FlexFecDecoder {
+    /// builder returns a new FlexFecDecoderBuilder.
+    pub fn builder() -> FlexFecDecoderBuilder {
+        FlexFecDecoderBuilder::default()
+    }
+}
+
+#[async_trait]
+impl Interceptor for FlexFecDecoder {
+    /// bind_rtcp_reader lets you modify any incoming RTCP packets. It is called once per sender/receiver, however this might
+    /// change in the future. The returned method will be called once per packet batch.
+    async fn bind_rtcp_reader(
+        &self,
+        reader: Arc<dyn RTCPReader + Send + Sync>,
+    ) -> Arc<dyn RTCPReader + Send + Sync> {
+        reader
+    }
+
+    /// bind_rtcp_writer lets you modify any outgoing RTCP packets. It is called once per PeerConnection. The returned method
+    /// will be called once per packet batch.
+    async fn bind_rtcp_writer(
+        &self,
+        writer: Arc<dyn RTCPWriter + Send + Sync>,
+    ) -> Arc<dyn RTCPWriter + Send + Sync> {
+        writer
+    }
+
+    /// bind_local_stream lets you modify any outgoing RTP packets. It is called once for per LocalStream. The returned method
+    /// will be called once per rtp packet.
+    async fn bind_local_stream(
+        &self,
+        _info: &StreamInfo,
+        writer: Arc<dyn RTPWriter + Send + Sync>,
+    ) -> Arc<dyn RTPWriter + Send + Sync> {
+        writer
+    }
+
+    /// unbind_local_stream is called when the Stream is removed. It can be used to clean up any data related to that track.
+    async fn unbind_local_stream(&self, _info: &StreamInfo) {}
+
+    /// bind_remote_stream lets you modify any incoming RTP packets. It is called once for per RemoteStream. The returned method
+    /// will be called once per rtp packet.
+    /// The protected stream must be bound before its FEC stream.
+    async fn bind_remote_stream(
+        &self,
+        info: &StreamInfo,
+        reader: Arc<dyn RTPReader + Send + Sync>,
+    ) -> Arc<dyn RTPReader + Send + Sync> {
+        let mut streams = self.streams.lock().await;
+        if info.ssrc_forward_error_correction != 0 {
+            let stream = Arc::new(DecoderStream::new(self.log2_size, info.ssrc, reader));
+            streams.insert(info.ssrc_forward_error_correction, Arc::clone(&stream));
+            return stream;
+        }
+
+        if let Some(stream) = streams.get(&info.ssrc) {
+            return Arc::new(FecStreamReader::new(Arc::clone(stream), reader));
+        }
+
+        reader
+    }
+
+    /// unbind_remote_stream is called when the Stream is removed. It can be used to clean up any data related to that track.
+    async fn unbind_remote_stream(&self, info: &StreamInfo) {
+        if info.ssrc_forward_error_correction != 0 {
+            let mut streams = self.streams.lock().await;
+            streams.remove(&info.ssrc_forward_error_correction);
+        }
+    }
+
+    /// close closes the Interceptor, cleaning up any data if necessary.
+    async fn close(&self) -> Result<()> {
+        Ok(())
+    }
+}
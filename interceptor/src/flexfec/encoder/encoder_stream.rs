@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use util::sync::Mutex;
+
+use crate::error::Result;
+use crate::flexfec::flexfec_03::FlexFecEncoder03;
+use crate::stream_info::StreamInfo;
+use crate::{Attributes, RTPWriter};
+
+struct EncoderStreamInternal {
+    encoder: FlexFecEncoder03,
+    media_packets: Vec<rtp::packet::Packet>,
+}
+
+pub(super) struct EncoderStream {
+    ssrc: u32,
+    num_media_packets: usize,
+    num_fec_packets: usize,
+    internal: Mutex<EncoderStreamInternal>,
+    next_rtp_writer: Arc<dyn RTPWriter + Send + Sync>,
+}
+
+impl EncoderStream {
+    pub(super) fn new(
+        info: &StreamInfo,
+        num_media_packets: usize,
+        num_fec_packets: usize,
+        writer: Arc<dyn RTPWriter + Send + Sync>,
+    ) -> Self {
+        EncoderStream {
+            ssrc: info.ssrc,
+            num_media_packets,
+            num_fec_packets,
+            internal: Mutex::new(EncoderStreamInternal {
+                encoder: FlexFecEncoder03::new(
+                    info.ssrc_forward_error_correction,
+                    info.payload_type_forward_error_correction,
+                ),
+                media_packets: Vec::with_capacity(num_media_packets),
+            }),
+            next_rtp_writer: writer,
+        }
+    }
+
+    /// add buffers a media packet and returns the FEC packets once a group of
+    /// media packets is complete.
+    fn add(&self, pkt: &rtp::packet::Packet) -> Vec<rtp::packet::Packet> {
+        let mut internal = self.internal.lock();
+        internal.media_packets.push(pkt.clone());
+        if internal.media_packets.len() < self.num_media_packets {
+            return vec![];
+        }
+
+        let media_packets = std::mem::take(&mut internal.media_packets);
+        internal
+            .encoder
+            .encode_fec(&media_packets, self.num_fec_packets)
+    }
+}
+
+/// RTPWriter is used by Interceptor.bind_local_stream.
+#[async_trait]
+impl RTPWriter for EncoderStream {
+    /// write a rtp packet
+    async fn write(&self, pkt: &rtp::packet::Packet, a: &Attributes) -> Result<usize> {
+        let n = self.next_rtp_writer.write(pkt, a).await?;
+
+        // Packets of other streams, like retransmissions, are not protected
+        if pkt.header.ssrc != self.ssrc {
+            return Ok(n);
+        }
+
+        for fec_packet in self.add(pkt) {
+            if let Err(err) = self.next_rtp_writer.write(&fec_packet, a).await {
+                log::warn!("failed sending FlexFEC packet: {}", err);
+            }
+        }
+
+        Ok(n)
+    }
+}
@@ -0,0 +1,187 @@
+use bytes::Bytes;
+use tokio::time::Duration;
+use util::marshal::Marshal;
+
+use super::*;
+use crate::flexfec::flexfec_03::FecPacket;
+use crate::mock::mock_stream::MockStream;
+use crate::test::timeout_or_fail;
+
+fn media_packet(seq: u16, payload: &'static [u8]) -> rtp::packet::Packet {
+    rtp::packet::Packet {
+        header: rtp::header::Header {
+            version: 2,
+            marker: seq % 2 == 0,
+            payload_type: 96,
+            sequence_number: seq,
+            timestamp: 3000 * u32::from(seq),
+            ssrc: 1,
+            ..Default::default()
+        },
+        payload: Bytes::from_static(payload),
+    }
+}
+
+#[tokio::test]
+async fn test_flexfec_encoder_interceptor() -> Result<()> {
+    let icpr: Arc<dyn Interceptor + Send + Sync> = FlexFecEncoder::builder()
+        .with_num_media_packets(4)
+        .with_num_fec_packets(2)
+        .build("")?;
+
+    let stream = MockStream::new(
+        &StreamInfo {
+            ssrc: 1,
+            ssrc_forward_error_correction: 2,
+            payload_type_forward_error_correction: 49,
+            ..Default::default()
+        },
+        icpr,
+    )
+    .await;
+
+    let payloads: [&'static [u8]; 4] = [b"\x01\x02\x03", b"\x04", b"\x05\x06\x07\x08\x09", b""];
+    let media_packets: Vec<_> = (0..4)
+        .map(|i| media_packet(65534u16.wrapping_add(i), payloads[i as usize]))
+        .collect();
+
+    // retransmissions on another SSRC aren't protected
+    let mut rtx = media_packet(7, b"\x00\x07");
+    rtx.header.ssrc = 3;
+    stream.write_rtp(&rtx).await?;
+    let p = timeout_or_fail(Duration::from_millis(10), stream.written_rtp())
+        .await
+        .expect("A packet");
+    assert_eq!(p.header.ssrc, 3);
+
+    for pkt in &media_packets {
+        stream.write_rtp(pkt).await?;
+        let p = timeout_or_fail(Duration::from_millis(10), stream.written_rtp())
+            .await
+            .expect("A packet");
+        assert_eq!(&p, pkt);
+    }
+
+    let mut fec_packets = vec![];
+    for _ in 0..2 {
+        let p = timeout_or_fail(Duration::from_millis(10), stream.written_rtp())
+            .await
+            .expect("A FEC packet");
+        assert_eq!(p.header.ssrc, 2);
+        assert_eq!(p.header.payload_type, 49);
+        assert_eq!(p.header.timestamp, media_packets[3].header.timestamp);
+        fec_packets.push(p);
+    }
+    assert_eq!(
+        fec_packets[1].header.sequence_number,
+        fec_packets[0].header.sequence_number.wrapping_add(1)
+    );
+
+    // media packets are interleaved over the FEC packets
+    for (i, fec_packet) in fec_packets.iter().enumerate() {
+        let fec = FecPacket::unmarshal(fec_packet).expect("A valid FEC packet");
+        assert_eq!(fec.header.ssrc, 1);
+        assert_eq!(fec.header.sequence_number_base, 65534);
+        let protected: Vec<u16> = fec.header.protected_sequence_numbers().collect();
+        let expected: Vec<u16> = media_packets
+            .iter()
+            .skip(i)
+            .step_by(2)
+            .map(|p| p.header.sequence_number)
+            .collect();
+        assert_eq!(protected, expected);
+
+        // each protected packet can be recovered from the others
+        for lost in &protected {
+            let received: Vec<Bytes> = media_packets
+                .iter()
+                .filter(|p| protected.contains(&p.header.sequence_number))
+                .filter(|p| p.header.sequence_number != *lost)
+                .map(|p| p.marshal().unwrap())
+                .collect();
+            let recovered = fec.recover(*lost, received.iter()).expect("A packet");
+            let original = media_packets
+                .iter()
+                .find(|p| p.header.sequence_number == *lost)
+                .unwrap();
+            assert_eq!(&recovered, original);
+        }
+    }
+
+    // no more FEC packets until the next group is complete
+    stream.write_rtp(&media_packet(2, b"\x0a")).await?;
+    let _ = stream.written_rtp().await;
+    let result = tokio::time::timeout(Duration::from_millis(10), stream.written_rtp()).await;
+    assert!(result.is_err(), "unexpected FEC packet");
+
+    stream.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_flexfec_encoder_long_mask() -> Result<()> {
+    let icpr: Arc<dyn Interceptor + Send + Sync> = FlexFecEncoder::builder()
+        .with_num_media_packets(MAX_PROTECTED_PACKETS)
+        .with_num_fec_packets(1)
+        .build("")?;
+
+    let stream = MockStream::new(
+        &StreamInfo {
+            ssrc: 1,
+            ssrc_forward_error_correction: 2,
+            payload_type_forward_error_correction: 49,
+            ..Default::default()
+        },
+        icpr,
+    )
+    .await;
+
+    let mut media_packets = vec![];
+    for seq in 0..MAX_PROTECTED_PACKETS {
+        let pkt = media_packet(seq, b"\x01\x02");
+        stream.write_rtp(&pkt).await?;
+        let _ = stream.written_rtp().await;
+        media_packets.push(pkt);
+    }
+
+    let p = timeout_or_fail(Duration::from_millis(10), stream.written_rtp())
+        .await
+        .expect("A FEC packet");
+    let fec = FecPacket::unmarshal(&p).expect("A valid FEC packet");
+    let protected: Vec<u16> = fec.header.protected_sequence_numbers().collect();
+    assert_eq!(protected, (0..MAX_PROTECTED_PACKETS).collect::<Vec<u16>>());
+
+    let received: Vec<Bytes> = media_packets[..108]
+        .iter()
+        .map(|p| p.marshal().unwrap())
+        .collect();
+    let recovered = fec.recover(108, received.iter()).expect("A packet");
+    assert_eq!(recovered, media_packets[108]);
+
+    stream.close().await?;
+
+    Ok(())
+}
+
+#[test]
+fn test_flexfec_encoder_builder() {
+    for (num_media_packets, num_fec_packets, valid) in [
+        (5, 2, true),
+        (1, 1, true),
+        (0, 1, false),
+        (2, 0, false),
+        (2, 3, false),
+        (MAX_PROTECTED_PACKETS + 1, 1, false),
+    ] {
+        let result = FlexFecEncoder::builder()
+            .with_num_media_packets(num_media_packets)
+            .with_num_fec_packets(num_fec_packets)
+            .build("");
+        assert_eq!(
+            result.is_ok(),
+            valid,
+            "{num_media_packets} {num_fec_packets}"
+        );
+    }
+}
@@ -0,0 +1,131 @@
+mod encoder_stream;
+#[cfg(test)]
+mod encoder_test;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use encoder_stream::EncoderStream;
+
+use super::flexfec_03::MAX_PROTECTED_PACKETS;
+use crate::error::{Error, Result};
+use crate::stream_info::StreamInfo;
+use crate::{Interceptor, InterceptorBuilder, RTCPReader, RTCPWriter, RTPReader, RTPWriter};
+
+/// FlexFecEncoderBuilder can be used to configure FlexFecEncoder Interceptor
+#[derive(Default)]
+pub struct FlexFecEncoderBuilder {
+    num_media_packets: Option<u16>,
+    num_fec_packets: Option<u16>,
+}
+
+impl FlexFecEncoderBuilder {
+    /// with_num_media_packets sets the number of media packets protected together,
+    /// at most 109. Defaults to 5.
+    pub fn with_num_media_packets(mut self, num_media_packets: u16) -> FlexFecEncoderBuilder {
+        self.num_media_packets = Some(num_media_packets);
+        self
+    }
+
+    /// with_num_fec_packets sets the number of FEC packets generated for each group of media
+    /// packets, at most the number of media packets. Defaults to 2.
+    /// The protection ratio is num_fec_packets / num_media_packets, a burst of up to
+    /// num_fec_packets lost packets in a group can be recovered.
+    pub fn with_num_fec_packets(mut self, num_fec_packets: u16) -> FlexFecEncoderBuilder {
+        self.num_fec_packets = Some(num_fec_packets);
+        self
+    }
+}
+
+impl InterceptorBuilder for FlexFecEncoderBuilder {
+    fn build(&self, _id: &str) -> Result<Arc<dyn Interceptor + Send + Sync>> {
+        let num_media_packets = self.num_media_packets.unwrap_or(5);
+        let num_fec_packets = self.num_fec_packets.unwrap_or(2);
+        if num_media_packets == 0
+            || num_media_packets > MAX_PROTECTED_PACKETS
+            || num_fec_packets == 0
+            || num_fec_packets > num_media_packets
+        {
+            return Err(Error::ErrInvalidFecPacketCount);
+        }
+
+        Ok(Arc::new(FlexFecEncoder {
+            num_media_packets: num_media_packets as usize,
+            num_fec_packets: num_fec_packets as usize,
+        }))
+    }
+}
+
+/// FlexFecEncoder sends FlexFEC protection packets for the outgoing streams.
+pub struct FlexFecEncoder {
+    num_media_packets: usize,
+    num_fec_packets: usize,
+}
+
+impl FlexFecEncoder {
+    /// builder returns a new FlexFecEncoderBuilder.
+    pub fn builder() -> FlexFecEncoderBuilder {
+        FlexFecEncoderBuilder::default()
+    }
+}
+
+#[async_trait]
+impl Interceptor for FlexFecEncoder {
+    /// bind_rtcp_reader lets you modify any incoming RTCP packets. It is called once per sender/receiver, however this might
+    /// change in the future. The returned method will be called once per packet batch.
+    async fn bind_rtcp_reader(
+        &self,
+        reader: Arc<dyn RTCPReader + Send + Sync>,
+    ) -> Arc<dyn RTCPReader + Send + Sync> {
+        reader
+    }
+
+    /// bind_rtcp_writer lets you modify any outgoing RTCP packets. It is called once per PeerConnection. The returned method
+    /// will be called once per packet batch.
+    async fn bind_rtcp_writer(
+        &self,
+        writer: Arc<dyn RTCPWriter + Send + Sync>,
+    ) -> Arc<dyn RTCPWriter + Send + Sync> {
+        writer
+    }
+
+    /// bind_local_stream lets you modify any outgoing RTP packets. It is called once for per LocalStream. The returned method
+    /// will be called once per rtp packet.
+    async fn bind_local_stream(
+        &self,
+        info: &StreamInfo,
+        writer: Arc<dyn RTPWriter + Send + Sync>,
+    ) -> Arc<dyn RTPWriter + Send + Sync> {
+        if info.ssrc_forward_error_correction == 0 {
+            return writer;
+        }
+
+        Arc::new(EncoderStream::new(
+            info,
+            self.num_media_packets,
+            self.num_fec_packets,
+            writer,
+        ))
+    }
+
+    /// unbind_local_stream is called when the Stream is removed. It can be used to clean up any data related to that track.
+    async fn unbind_local_stream(&self, _info: &StreamInfo) {}
+
+    /// bind_remote_stream lets you modify any incoming RTP packets. It is called once for per RemoteStream. The returned method
+    /// will be called once per rtp packet.
+    async fn bind_remote_stream(
+        &self,
+        _info: &StreamInfo,
+        reader: Arc<dyn RTPReader + Send + Sync>,
+    ) -> Arc<dyn RTPReader + Send + Sync> {
+        reader
+    }
+
+    /// unbind_remote_stream is called when the Stream is removed. It can be used to clean up any data related to that track.
+    async fn unbind_remote_stream(&self, _info: &StreamInfo) {}
+
+    /// close closes the Interceptor, cleaning up any data if necessary.
+    async fn close(&self) -> Result<()> {
+        Ok(())
+    }
+}
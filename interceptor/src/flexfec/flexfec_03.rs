@@ -0,0 +1,270 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use rtp::header::Header;
+use rtp::packet::Packet;
+use util::marshal::{Marshal, Unmarshal};
+
+/// Size of the fixed RTP header, which is not covered by the repair payload.
+const RTP_HEADER_SIZE: usize = 12;
+/// Size of the FEC header up to and including the SSRC of the protected stream.
+const FEC_HEADER_SIZE: usize = 16;
+/// Maximum number of media packets one FEC packet can protect, the size of the
+/// three mask chunks of 15, 31 and 63 bits.
+pub(crate) const MAX_PROTECTED_PACKETS: u16 = 109;
+
+const MASK_CHUNK_BITS: [u16; 3] = [15, 31, 63];
+
+/// Recovery fields of the FEC header and the repair payload, the XOR of the
+/// protected packets.
+#[derive(Default, Clone)]
+struct Recovery {
+    /// P, X, CC, M, PT, length and timestamp recovery fields.
+    header: [u8; 8],
+    payload: Vec<u8>,
+}
+
+impl Recovery {
+    fn xor(&mut self, raw: &[u8]) {
+        let length = (raw.len() - RTP_HEADER_SIZE) as u16;
+        self.header[0] ^= raw[0];
+        self.header[1] ^= raw[1];
+        self.header[2] ^= (length >> 8) as u8;
+        self.header[3] ^= length as u8;
+        for (r, b) in self.header[4..8].iter_mut().zip(&raw[4..8]) {
+            *r ^= b;
+        }
+
+        let body = &raw[RTP_HEADER_SIZE..];
+        if self.payload.len() < body.len() {
+            self.payload.resize(body.len(), 0);
+        }
+        for (r, b) in self.payload.iter_mut().zip(body) {
+            *r ^= b;
+        }
+    }
+}
+
+/// FlexFecHeader is the FEC header of a FlexFEC packet protecting a single stream
+/// with a flexible mask.
+/// <https://datatracker.ietf.org/doc/html/draft-ietf-payload-flexible-fec-scheme-03#section-4.2>
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FlexFecHeader {
+    pub(crate) ssrc: u32,
+    pub(crate) sequence_number_base: u16,
+    /// Bit n is set if the packet with sequence number base + n is protected.
+    pub(crate) mask: u128,
+}
+
+impl FlexFecHeader {
+    /// protected_sequence_numbers returns the sequence numbers of the protected packets.
+    pub(crate) fn protected_sequence_numbers(&self) -> impl Iterator<Item = u16> + '_ {
+        (0..MAX_PROTECTED_PACKETS)
+            .filter(|n| self.mask & (1 << n) != 0)
+            .map(|n| self.sequence_number_base.wrapping_add(n))
+    }
+
+    fn marshal_mask(&self, buf: &mut BytesMut) {
+        let mut offset = 0;
+        for (i, bits) in MASK_CHUNK_BITS.iter().enumerate() {
+            let last = i == MASK_CHUNK_BITS.len() - 1 || self.mask >> (offset + bits) == 0;
+            let chunk = ((self.mask >> offset) & ((1 << bits) - 1)).reverse_bits() >> (128 - bits);
+            let k = u128::from(last) << bits;
+            match bits {
+                15 => buf.put_u16((k | chunk) as u16),
+                31 => buf.put_u32((k | chunk) as u32),
+                _ => buf.put_u64((k | chunk) as u64),
+            }
+            if last {
+                return;
+            }
+            offset += bits;
+        }
+    }
+
+    /// unmarshal parses the FEC header, returning it with the remaining repair payload.
+    /// Only FEC packets protecting a single stream with a flexible mask are supported.
+    fn unmarshal(payload: &Bytes) -> Option<(Self, [u8; 8], Bytes)> {
+        if payload.len() < FEC_HEADER_SIZE + 2 {
+            return None;
+        }
+        // R: retransmission, F: fixed mask
+        if payload[0] & 0xc0 != 0 || payload[8] != 1 {
+            return None;
+        }
+
+        let mut recovery = [0u8; 8];
+        recovery.copy_from_slice(&payload[..8]);
+        let ssrc = u32::from_be_bytes([payload[12], payload[13], payload[14], payload[15]]);
+        let sequence_number_base = u16::from_be_bytes([payload[16], payload[17]]);
+
+        let mut mask = 0u128;
+        let mut offset = 0;
+        let mut pos = FEC_HEADER_SIZE + 2;
+        for bits in MASK_CHUNK_BITS {
+            let size = (bits as usize + 1) / 8;
+            if payload.len() < pos + size {
+                return None;
+            }
+            let chunk = payload[pos..pos + size]
+                .iter()
+                .fold(0u128, |acc, b| (acc << 8) | u128::from(*b));
+            pos += size;
+
+            let k = chunk >> bits != 0;
+            let chunk = (chunk & ((1 << bits) - 1)).reverse_bits() >> (128 - bits);
+            mask |= chunk << offset;
+            offset += bits;
+            if k {
+                return Some((
+                    FlexFecHeader {
+                        ssrc,
+                        sequence_number_base,
+                        mask,
+                    },
+                    recovery,
+                    payload.slice(pos..),
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+/// FlexFecEncoder03 generates FlexFEC-03 protection packets for a group of media packets.
+pub(crate) struct FlexFecEncoder03 {
+    fec_ssrc: u32,
+    payload_type: u8,
+    sequence_number: u16,
+}
+
+impl FlexFecEncoder03 {
+    pub(crate) fn new(fec_ssrc: u32, payload_type: u8) -> Self {
+        FlexFecEncoder03 {
+            fec_ssrc,
+            payload_type,
+            sequence_number: rand::random::<u16>(),
+        }
+    }
+
+    /// encode_fec returns num_fec_packets FEC packets protecting the media packets, which must
+    /// belong to the same stream. The packets are interleaved over the FEC packets, so a burst
+    /// of up to num_fec_packets lost packets can be recovered.
+    pub(crate) fn encode_fec(
+        &mut self,
+        media_packets: &[Packet],
+        num_fec_packets: usize,
+    ) -> Vec<Packet> {
+        let first = match media_packets.first() {
+            Some(first) => first,
+            None => return vec![],
+        };
+        let ssrc = first.header.ssrc;
+        let sequence_number_base = first.header.sequence_number;
+        let timestamp = media_packets
+            .last()
+            .map_or(first.header.timestamp, |p| p.header.timestamp);
+
+        let mut fec_packets = Vec::with_capacity(num_fec_packets);
+        for i in 0..num_fec_packets.min(media_packets.len()) {
+            let mut recovery = Recovery::default();
+            let mut mask = 0u128;
+            for p in media_packets.iter().skip(i).step_by(num_fec_packets) {
+                let offset = p.header.sequence_number.wrapping_sub(sequence_number_base);
+                if p.header.ssrc != ssrc || offset >= MAX_PROTECTED_PACKETS {
+                    continue;
+                }
+                let raw = match p.marshal() {
+                    Ok(raw) => raw,
+                    Err(_) => continue,
+                };
+                recovery.xor(&raw);
+                mask |= 1 << offset;
+            }
+            if mask == 0 {
+                continue;
+            }
+
+            let header = FlexFecHeader {
+                ssrc,
+                sequence_number_base,
+                mask,
+            };
+            let mut payload =
+                BytesMut::with_capacity(FEC_HEADER_SIZE + 16 + recovery.payload.len());
+            // R and F are 0, the version bits are not recovered
+            payload.put_u8(recovery.header[0] & 0x3f);
+            payload.put_slice(&recovery.header[1..8]);
+            payload.put_u8(1); // SSRCCount
+            payload.put_slice(&[0, 0, 0]); // reserved
+            payload.put_u32(ssrc);
+            payload.put_u16(sequence_number_base);
+            header.marshal_mask(&mut payload);
+            payload.put_slice(&recovery.payload);
+
+            fec_packets.push(Packet {
+                header: Header {
+                    version: 2,
+                    payload_type: self.payload_type,
+                    sequence_number: self.sequence_number,
+                    timestamp,
+                    ssrc: self.fec_ssrc,
+                    ..Default::default()
+                },
+                payload: payload.freeze(),
+            });
+            self.sequence_number = self.sequence_number.wrapping_add(1);
+        }
+
+        fec_packets
+    }
+}
+
+/// FecPacket is a received FlexFEC-03 packet.
+#[derive(Clone)]
+pub(crate) struct FecPacket {
+    pub(crate) header: FlexFecHeader,
+    recovery: [u8; 8],
+    repair_payload: Bytes,
+}
+
+impl FecPacket {
+    pub(crate) fn unmarshal(pkt: &Packet) -> Option<Self> {
+        let (header, recovery, repair_payload) = FlexFecHeader::unmarshal(&pkt.payload)?;
+        Some(FecPacket {
+            header,
+            recovery,
+            repair_payload,
+        })
+    }
+
+    /// recover restores the protected packet with the given sequence number from
+    /// the marshaled other protected packets.
+    pub(crate) fn recover<'a>(
+        &self,
+        sequence_number: u16,
+        received: impl Iterator<Item = &'a Bytes>,
+    ) -> Option<Packet> {
+        let mut recovery = Recovery {
+            header: self.recovery,
+            payload: self.repair_payload.to_vec(),
+        };
+        for raw in received {
+            recovery.xor(raw);
+        }
+
+        let length = u16::from_be_bytes([recovery.header[2], recovery.header[3]]) as usize;
+        if length > recovery.payload.len() {
+            return None;
+        }
+
+        let mut raw = BytesMut::with_capacity(RTP_HEADER_SIZE + length);
+        raw.put_u8(0x80 | (recovery.header[0] & 0x3f));
+        raw.put_u8(recovery.header[1]);
+        raw.put_u16(sequence_number);
+        raw.put_slice(&recovery.header[4..8]);
+        raw.put_u32(self.header.ssrc);
+        raw.put_slice(&recovery.payload[..length]);
+
+        Packet::unmarshal(&mut raw.freeze()).ok()
+    }
+}
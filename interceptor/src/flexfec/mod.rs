@@ -0,0 +1,18 @@
+//! Forward error correction with FlexFEC as described in
+//! [draft-ietf-payload-flexible-fec-scheme-03](https://datatracker.ietf.org/doc/html/draft-ietf-payload-flexible-fec-scheme-03),
+//! the version of RFC 8627 implemented by browsers.
+//!
+//! The [`FlexFecEncoder`] sends protection packets of a local stream on a separate SSRC, and
+//! the [`FlexFecDecoder`] uses them to recover lost packets of a remote stream. Both are only
+//! enabled for streams with a `ssrc_forward_error_correction` in their [`StreamInfo`].
+//!
+//! [`StreamInfo`]: crate::stream_info::StreamInfo
+
+pub mod decoder;
+pub mod encoder;
+mod flexfec_03;
+
+pub use decoder::{FlexFecDecoder, FlexFecDecoderBuilder};
+pub use encoder::{FlexFecEncoder, FlexFecEncoderBuilder};
+
+const UINT16SIZE_HALF: u16 = 1 << 15;
@@ -0,0 +1,230 @@
+use super::{
+    data_rate::{DataRate, DataSize},
+    network_types::PacketResult,
+    time::Timestamp,
+};
+
+const INITIAL_RATE_WINDOW_MS: i64 = 500;
+const RATE_WINDOW_MS: i64 = 150;
+
+const UNCERTAINTY_SCALE: f64 = 10.0;
+const UNCERTAINTY_SCALE_IN_ALR: f64 = UNCERTAINTY_SCALE;
+const SMALL_SAMPLE_UNCERTAINTY_SCALE: f64 = UNCERTAINTY_SCALE;
+
+/// Computes a bayesian estimate of the throughput given acks containing the arrival time and
+/// payload size. Samples which are far from the current estimate or are based on few packets
+/// are given a smaller weight, as they are considered to be more likely to have been caused by,
+/// e.g., delay spikes unrelated to congestion.
+pub struct BitrateEstimator {
+    sum: i64,
+    current_window_ms: i64,
+    prev_time_ms: i64,
+    bitrate_estimate_kbps: f64,
+    bitrate_estimate_var: f64,
+    small_sample_threshold: DataSize,
+    uncertainty_symmetry_cap: DataRate,
+    estimate_floor: DataRate,
+}
+
+impl BitrateEstimator {
+    pub fn new() -> Self {
+        Self {
+            sum: 0,
+            current_window_ms: 0,
+            prev_time_ms: -1,
+            bitrate_estimate_kbps: -1.0,
+            bitrate_estimate_var: 50.0,
+            small_sample_threshold: DataSize::zero(),
+            uncertainty_symmetry_cap: DataRate::zero(),
+            estimate_floor: DataRate::zero(),
+        }
+    }
+
+    pub fn update(&mut self, at_time: Timestamp, amount: DataSize, in_alr: bool) {
+        let rate_window_ms = if self.bitrate_estimate_kbps < 0.0 {
+            // To get an initial estimate we use a longer window. Then the window is
+            // decreased to be able to follow changes faster.
+            INITIAL_RATE_WINDOW_MS
+        } else {
+            RATE_WINDOW_MS
+        };
+        let (bitrate_sample_kbps, is_small_sample) =
+            self.update_window(at_time.ms(), amount.bytes(), rate_window_ms);
+        if bitrate_sample_kbps < 0.0 {
+            return;
+        }
+        if self.bitrate_estimate_kbps < 0.0 {
+            // This is the very first sample we get. Use it to initialize the estimate.
+            self.bitrate_estimate_kbps = bitrate_sample_kbps;
+            return;
+        }
+        // Optionally use higher uncertainty for very small samples to avoid dropping estimate and
+        // for samples obtained in ALR.
+        let mut scale = if in_alr {
+            UNCERTAINTY_SCALE_IN_ALR
+        } else {
+            UNCERTAINTY_SCALE
+        };
+        if is_small_sample && bitrate_sample_kbps < self.bitrate_estimate_kbps {
+            scale = SMALL_SAMPLE_UNCERTAINTY_SCALE;
+        }
+        // Define the sample uncertainty as a function of how far away it is from the
+        // current estimate. With low values of uncertainty_symmetry_cap we add more
+        // uncertainty to increases than to decreases. For higher values we approach
+        // symmetry.
+        let sample_uncertainty = scale * (self.bitrate_estimate_kbps - bitrate_sample_kbps).abs()
+            / (self.bitrate_estimate_kbps
+                + f64::min(
+                    bitrate_sample_kbps,
+                    self.uncertainty_symmetry_cap.kbps() as f64,
+                ));
+
+        let sample_var = sample_uncertainty * sample_uncertainty;
+        // Update a bayesian estimate of the rate, weighting it lower if the sample
+        // uncertainty is large.
+        // The bitrate estimate uncertainty is increased with each update to model
+        // that the bitrate changes over time.
+        let pred_bitrate_estimate_var = self.bitrate_estimate_var + 5.0;
+        self.bitrate_estimate_kbps = (sample_var * self.bitrate_estimate_kbps
+            + pred_bitrate_estimate_var * bitrate_sample_kbps)
+            / (sample_var + pred_bitrate_estimate_var);
+        self.bitrate_estimate_kbps = f64::max(
+            self.bitrate_estimate_kbps,
+            self.estimate_floor.kbps() as f64,
+        );
+        self.bitrate_estimate_var =
+            sample_var * pred_bitrate_estimate_var / (sample_var + pred_bitrate_estimate_var);
+    }
+
+    pub fn bitrate(&self) -> Option<DataRate> {
+        if self.bitrate_estimate_kbps < 0.0 {
+            None
+        } else {
+            Some(DataRate::from_bits_per_sec(
+                (self.bitrate_estimate_kbps * 1000.0) as i64,
+            ))
+        }
+    }
+
+    pub fn expect_fast_rate_change(&mut self) {
+        // By setting the bitrate-estimate variance to a higher value we allow the
+        // bitrate to change fast for the next few samples.
+        self.bitrate_estimate_var += 200.0;
+    }
+
+    fn update_window(&mut self, now_ms: i64, bytes: i64, rate_window_ms: i64) -> (f64, bool) {
+        // Reset if time moves backwards.
+        if now_ms < self.prev_time_ms {
+            self.prev_time_ms = -1;
+            self.sum = 0;
+            self.current_window_ms = 0;
+        }
+        if self.prev_time_ms >= 0 {
+            self.current_window_ms += now_ms - self.prev_time_ms;
+            // Reset if nothing has been received for more than a full window.
+            if now_ms - self.prev_time_ms > rate_window_ms {
+                self.sum = 0;
+                self.current_window_ms %= rate_window_ms;
+            }
+        }
+        self.prev_time_ms = now_ms;
+        let mut bitrate_sample = -1.0;
+        let mut is_small_sample = false;
+        if self.current_window_ms >= rate_window_ms {
+            is_small_sample = self.sum < self.small_sample_threshold.bytes();
+            bitrate_sample = 8.0 * self.sum as f64 / rate_window_ms as f64;
+            self.current_window_ms -= rate_window_ms;
+            self.sum = 0;
+        }
+        self.sum += bytes;
+        (bitrate_sample, is_small_sample)
+    }
+}
+
+pub struct AcknowledgedBitrateEstimator {
+    alr_ended_time: Option<Timestamp>,
+    in_alr: bool,
+    bitrate_estimator: BitrateEstimator,
+}
+
+impl AcknowledgedBitrateEstimator {
+    pub fn new() -> Self {
+        Self {
+            alr_ended_time: None,
+            in_alr: false,
+            bitrate_estimator: BitrateEstimator::new(),
+        }
+    }
+
+    /// Expects `packet_feedback_vector` to be sorted by receive time.
+    pub fn incoming_packet_feedback_vector(&mut self, packet_feedback_vector: &[PacketResult]) {
+        for packet in packet_feedback_vector {
+            if let Some(alr_ended_time) = self.alr_ended_time {
+                if packet.sent_packet.send_time > alr_ended_time {
+                    self.bitrate_estimator.expect_fast_rate_change();
+                    self.alr_ended_time = None;
+                }
+            }
+            let acknowledged_estimate = DataSize::from_bytes(
+                packet.sent_packet.size.bytes() + packet.sent_packet.prior_unacked_data.bytes(),
+            );
+            self.bitrate_estimator
+                .update(packet.receive_time, acknowledged_estimate, self.in_alr);
+        }
+    }
+
+    pub fn bitrate(&self) -> Option<DataRate> {
+        self.bitrate_estimator.bitrate()
+    }
+
+    pub fn set_alr_ended_time(&mut self, alr_ended_time: Timestamp) {
+        self.alr_ended_time = Some(alr_ended_time);
+    }
+
+    pub fn set_alr(&mut self, in_alr: bool) {
+        self.in_alr = in_alr;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gcc::network_types::SentPacket;
+
+    fn received(send_ms: i64, receive_ms: i64, bytes: i64) -> PacketResult {
+        PacketResult {
+            sent_packet: SentPacket {
+                send_time: Timestamp::from_millis(send_ms),
+                size: DataSize::from_bytes(bytes),
+                ..Default::default()
+            },
+            receive_time: Timestamp::from_millis(receive_ms),
+        }
+    }
+
+    #[test]
+    fn no_estimate_before_initial_window() {
+        let mut estimator = AcknowledgedBitrateEstimator::new();
+        let packets: Vec<_> = (0..40)
+            .map(|i| received(i * 10, i * 10 + 20, 1000))
+            .collect();
+        estimator.incoming_packet_feedback_vector(&packets);
+        assert_eq!(estimator.bitrate(), None);
+    }
+
+    #[test]
+    fn converges_to_throughput() {
+        let mut estimator = AcknowledgedBitrateEstimator::new();
+        // 1000 bytes every 10ms is 800kbps.
+        let packets: Vec<_> = (0..500)
+            .map(|i| received(i * 10, i * 10 + 20, 1000))
+            .collect();
+        estimator.incoming_packet_feedback_vector(&packets);
+
+        let bitrate = estimator.bitrate().expect("should have an estimate").kbps();
+        assert!(
+            (750..=850).contains(&bitrate),
+            "unexpected estimate {bitrate}kbps"
+        );
+    }
+}
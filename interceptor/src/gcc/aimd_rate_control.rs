@@ -96,7 +96,7 @@ impl AimdRateControl {
 
     pub fn set_min_bitrate(&mut self, min_bitrate: DataRate) {
         self.min_configured_bitrate = min_bitrate;
-        self.current_bitrate = Ord::min(min_bitrate, self.current_bitrate);
+        self.current_bitrate = Ord::max(min_bitrate, self.current_bitrate);
     }
 
     pub fn valid_estimate(&self) -> bool {
@@ -361,7 +361,7 @@ impl AimdRateControl {
             let time_since_last_update = at_time - last_time;
             alpha = f64::powf(
                 alpha,
-                f64::min(time_since_last_update.seconds() as f64, 1.0),
+                f64::min(time_since_last_update.us() as f64 / 1_000_000.0, 1.0),
             );
         }
         std::cmp::max(
@@ -371,7 +371,7 @@ impl AimdRateControl {
     }
 
     pub fn additive_rate_increase(&self, at_time: Timestamp, last_time: Timestamp) -> DataRate {
-        let time_period_seconds = (at_time - last_time).seconds() as f64;
+        let time_period_seconds = (at_time - last_time).us() as f64 / 1_000_000.0;
         let data_rate_increase_bps =
             self.get_near_max_increase_rate_bps_per_second() * time_period_seconds;
         DataRate::from_bits_per_sec(data_rate_increase_bps as i64)
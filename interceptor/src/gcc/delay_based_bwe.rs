@@ -1,35 +1,277 @@
 use super::{
-    aimd_rate_control::{AimdRateControl, BandwidthUsage},
+    aimd_rate_control::{AimdRateControl, AimdRateControlConfig, BandwidthUsage, RateControlInput},
     data_rate::DataRate,
-    inter_arrival::InterArrival,
     inter_arrival_delta::InterArrivalDelta,
-    network_state_predictor::NetworkStatePredictor,
+    network_types::{NetworkStateEstimate, PacketResult, TransportPacketsFeedback},
     time::{TimeDelta, Timestamp},
-    trendline_estimator::TrendlineEstimator,
+    trendline_estimator::{TrendlineEstimator, TrendlineEstimatorSettings},
 };
 
+const STREAM_TIME_OUT: TimeDelta = TimeDelta::from_seconds(2);
+const SEND_TIME_GROUP_LENGTH: TimeDelta = TimeDelta::from_millis(5);
+
 pub struct DelayBasedBwe {
     separate_audio_: BweSeparateAudioPacketsSettings,
     audio_packets_since_last_video_: i64,
     last_video_packet_recv_time_: Timestamp,
-    network_state_predictor_: Box<dyn NetworkStatePredictor>,
-    video_inter_arrival_: InterArrival,
     video_inter_arrival_delta_: InterArrivalDelta,
     video_delay_detector_: TrendlineEstimator,
-    audio_inter_arrival_: InterArrival,
     audio_inter_arrival_delta_: InterArrivalDelta,
     audio_delay_detector_: TrendlineEstimator,
-    active_delay_detector_: TrendlineEstimator,
+    // Instead of pointing at one of the detectors like upstream does, remember which one is
+    // active.
+    audio_delay_detector_active_: bool,
     last_seen_packet_: Timestamp,
-    uma_recorded_: bool,
     rate_control_: AimdRateControl,
     prev_bitrate_: DataRate,
     prev_state_: BandwidthUsage,
 }
 
+impl DelayBasedBwe {
+    pub fn new() -> Self {
+        Self {
+            separate_audio_: BweSeparateAudioPacketsSettings::new(),
+            audio_packets_since_last_video_: 0,
+            last_video_packet_recv_time_: Timestamp::minus_infinity(),
+            video_inter_arrival_delta_: InterArrivalDelta::new(SEND_TIME_GROUP_LENGTH),
+            video_delay_detector_: TrendlineEstimator::new(
+                TrendlineEstimatorSettings::default(),
+                None,
+            ),
+            audio_inter_arrival_delta_: InterArrivalDelta::new(SEND_TIME_GROUP_LENGTH),
+            audio_delay_detector_: TrendlineEstimator::new(
+                TrendlineEstimatorSettings::default(),
+                None,
+            ),
+            audio_delay_detector_active_: false,
+            last_seen_packet_: Timestamp::minus_infinity(),
+            rate_control_: AimdRateControl::new(AimdRateControlConfig::default(), true),
+            prev_bitrate_: DataRate::zero(),
+            prev_state_: BandwidthUsage::Normal,
+        }
+    }
+
+    pub fn incoming_packet_feedback_vector(
+        &mut self,
+        msg: &TransportPacketsFeedback,
+        acked_bitrate: Option<DataRate>,
+        probe_bitrate: Option<DataRate>,
+        network_estimate: Option<&NetworkStateEstimate>,
+        in_alr: bool,
+    ) -> DelayBasedBweResult {
+        let packet_feedback_vector = msg.sorted_by_receive_time();
+        if packet_feedback_vector.is_empty() {
+            return DelayBasedBweResult::default();
+        }
+
+        let mut recovered_from_overuse = false;
+        let mut prev_detector_state = self.active_delay_detector().state();
+        for packet_feedback in &packet_feedback_vector {
+            self.incoming_packet_feedback(packet_feedback, msg.feedback_time);
+            if prev_detector_state == BandwidthUsage::Underusing
+                && self.active_delay_detector().state() == BandwidthUsage::Normal
+            {
+                recovered_from_overuse = true;
+            }
+            prev_detector_state = self.active_delay_detector().state();
+        }
+
+        self.rate_control_.set_in_application_limited_region(in_alr);
+        self.rate_control_
+            .set_network_state_estimate(network_estimate);
+        self.maybe_update_estimate(
+            acked_bitrate,
+            probe_bitrate,
+            recovered_from_overuse,
+            msg.feedback_time,
+        )
+    }
+
+    pub fn on_rtt_update(&mut self, avg_rtt: TimeDelta) {
+        self.rate_control_.set_rtt(avg_rtt);
+    }
+
+    pub fn set_start_bitrate(&mut self, start_bitrate: DataRate) {
+        self.rate_control_.set_start_bitrate(start_bitrate);
+    }
+
+    pub fn set_min_bitrate(&mut self, min_bitrate: DataRate) {
+        self.rate_control_.set_min_bitrate(min_bitrate);
+    }
+
+    pub fn get_expected_bwe_period(&self) -> TimeDelta {
+        self.rate_control_.get_expected_bandwidth_period()
+    }
+
+    pub fn last_estimate(&self) -> DataRate {
+        self.prev_bitrate_
+    }
+
+    pub fn last_state(&self) -> BandwidthUsage {
+        self.prev_state_
+    }
+
+    fn active_delay_detector(&self) -> &TrendlineEstimator {
+        if self.audio_delay_detector_active_ {
+            &self.audio_delay_detector_
+        } else {
+            &self.video_delay_detector_
+        }
+    }
+
+    fn incoming_packet_feedback(&mut self, packet_feedback: &PacketResult, at_time: Timestamp) {
+        // Reset if the stream has timed out.
+        if self.last_seen_packet_.is_infinite()
+            || at_time - self.last_seen_packet_ > STREAM_TIME_OUT
+        {
+            self.video_inter_arrival_delta_ = InterArrivalDelta::new(SEND_TIME_GROUP_LENGTH);
+            self.audio_inter_arrival_delta_ = InterArrivalDelta::new(SEND_TIME_GROUP_LENGTH);
+
+            self.video_delay_detector_ =
+                TrendlineEstimator::new(TrendlineEstimatorSettings::default(), None);
+            self.audio_delay_detector_ =
+                TrendlineEstimator::new(TrendlineEstimatorSettings::default(), None);
+            self.audio_delay_detector_active_ = false;
+        }
+        self.last_seen_packet_ = at_time;
+
+        // As an alternative to ignoring small packets, we can separate audio and
+        // video packets for overuse detection.
+        let mut use_audio_detector = false;
+        if self.separate_audio_.enabled {
+            if packet_feedback.sent_packet.audio {
+                use_audio_detector = true;
+                self.audio_packets_since_last_video_ += 1;
+                if self.audio_packets_since_last_video_ > self.separate_audio_.packet_threshold
+                    && packet_feedback.receive_time - self.last_video_packet_recv_time_
+                        > self.separate_audio_.time_threshold
+                {
+                    self.audio_delay_detector_active_ = true;
+                }
+            } else {
+                self.audio_packets_since_last_video_ = 0;
+                self.last_video_packet_recv_time_ = Ord::max(
+                    self.last_video_packet_recv_time_,
+                    packet_feedback.receive_time,
+                );
+                self.audio_delay_detector_active_ = false;
+            }
+        }
+        let packet_size = packet_feedback.sent_packet.size;
+
+        let mut send_delta = TimeDelta::zero();
+        let mut recv_delta = TimeDelta::zero();
+        let mut size_delta = 0;
+
+        let (inter_arrival_for_packet, delay_detector_for_packet) = if use_audio_detector {
+            (
+                &mut self.audio_inter_arrival_delta_,
+                &mut self.audio_delay_detector_,
+            )
+        } else {
+            (
+                &mut self.video_inter_arrival_delta_,
+                &mut self.video_delay_detector_,
+            )
+        };
+        let calculated_deltas = inter_arrival_for_packet.compute_deltas(
+            packet_feedback.sent_packet.send_time,
+            packet_feedback.receive_time,
+            at_time,
+            packet_size.bytes() as usize,
+            &mut send_delta,
+            &mut recv_delta,
+            &mut size_delta,
+        );
+
+        delay_detector_for_packet.update(
+            recv_delta.us() as f64 / 1000.0,
+            send_delta.us() as f64 / 1000.0,
+            packet_feedback.sent_packet.send_time.ms(),
+            packet_feedback.receive_time.ms(),
+            packet_size.bytes() as usize,
+            calculated_deltas,
+        );
+    }
+
+    fn maybe_update_estimate(
+        &mut self,
+        acked_bitrate: Option<DataRate>,
+        probe_bitrate: Option<DataRate>,
+        recovered_from_overuse: bool,
+        at_time: Timestamp,
+    ) -> DelayBasedBweResult {
+        let mut result = DelayBasedBweResult::default();
+
+        // Currently overusing the bandwidth.
+        if self.active_delay_detector().state() == BandwidthUsage::Overusing {
+            match acked_bitrate {
+                Some(acked_bitrate)
+                    if self
+                        .rate_control_
+                        .time_to_reduce_further(at_time, acked_bitrate) =>
+                {
+                    result.updated = self.update_estimate(
+                        at_time,
+                        Some(acked_bitrate),
+                        &mut result.target_bitrate,
+                    );
+                }
+                None if self.rate_control_.valid_estimate()
+                    && self.rate_control_.initial_time_to_reduce_further(at_time) =>
+                {
+                    // Overusing before we have a measured acknowledged bitrate. Reduce send
+                    // rate by 50% every 200 ms.
+                    self.rate_control_
+                        .set_estimate(self.rate_control_.latest_estimate() / 2, at_time);
+                    result.updated = true;
+                    result.probe = false;
+                    result.target_bitrate = self.rate_control_.latest_estimate();
+                }
+                _ => {}
+            }
+        } else if let Some(probe_bitrate) = probe_bitrate {
+            result.probe = true;
+            result.updated = true;
+            self.rate_control_.set_estimate(probe_bitrate, at_time);
+            result.target_bitrate = self.rate_control_.latest_estimate();
+        } else {
+            result.updated =
+                self.update_estimate(at_time, acked_bitrate, &mut result.target_bitrate);
+            result.recovered_from_overuse = recovered_from_overuse;
+        }
+
+        let detector_state = self.active_delay_detector().state();
+        if (result.updated && self.prev_bitrate_ != result.target_bitrate)
+            || detector_state != self.prev_state_
+        {
+            let bitrate = if result.updated {
+                result.target_bitrate
+            } else {
+                self.prev_bitrate_
+            };
+            self.prev_bitrate_ = bitrate;
+            self.prev_state_ = detector_state;
+        }
+        result.delay_detector_state = detector_state;
+        result
+    }
+
+    fn update_estimate(
+        &mut self,
+        at_time: Timestamp,
+        acked_bitrate: Option<DataRate>,
+        target_rate: &mut DataRate,
+    ) -> bool {
+        let input = RateControlInput::new(self.active_delay_detector().state(), acked_bitrate);
+        *target_rate = self.rate_control_.update(&input, at_time);
+        self.rate_control_.valid_estimate()
+    }
+}
+
 pub struct BweSeparateAudioPacketsSettings {
     enabled: bool,
-    packet_threshold: i32,
+    packet_threshold: i64,
     time_threshold: TimeDelta,
 }
 
@@ -44,11 +286,11 @@ impl BweSeparateAudioPacketsSettings {
 }
 
 pub struct DelayBasedBweResult {
-    updated: bool,
-    probe: bool,
-    target_bitrate: DataRate,
-    recovered_from_overuse: bool,
-    delay_detector_state: BandwidthUsage,
+    pub updated: bool,
+    pub probe: bool,
+    pub target_bitrate: DataRate,
+    pub recovered_from_overuse: bool,
+    pub delay_detector_state: BandwidthUsage,
 }
 
 impl Default for DelayBasedBweResult {
@@ -62,3 +304,103 @@ impl Default for DelayBasedBweResult {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gcc::data_rate::DataSize;
+    use crate::gcc::network_types::SentPacket;
+
+    const PACKET_SIZE: DataSize = DataSize::from_bytes(1200);
+
+    /// Feeds the estimator with one feedback report per 100ms of packets, sent every
+    /// `send_interval` and received with the queuing delay growing by `delay_growth` per packet.
+    fn run(
+        bwe: &mut DelayBasedBwe,
+        start: Timestamp,
+        reports: usize,
+        send_interval: TimeDelta,
+        delay_growth: TimeDelta,
+    ) -> DelayBasedBweResult {
+        let mut send_time = start;
+        let mut delay = TimeDelta::from_millis(50);
+        let mut sequence_number = 0;
+        let mut result = DelayBasedBweResult::default();
+        for _ in 0..reports {
+            let mut feedback = TransportPacketsFeedback::default();
+            let report_end = send_time + TimeDelta::from_millis(100);
+            while send_time < report_end {
+                feedback.packet_feedbacks.push(PacketResult {
+                    sent_packet: SentPacket {
+                        send_time,
+                        size: PACKET_SIZE,
+                        sequence_number,
+                        ..Default::default()
+                    },
+                    receive_time: send_time + delay,
+                });
+                sequence_number += 1;
+                send_time += send_interval;
+                delay = delay + delay_growth;
+            }
+            feedback.feedback_time = send_time + delay;
+            let acked_bitrate = PACKET_SIZE / send_interval;
+            result = bwe.incoming_packet_feedback_vector(
+                &feedback,
+                Some(acked_bitrate),
+                None,
+                None,
+                false,
+            );
+        }
+        result
+    }
+
+    #[test]
+    fn empty_feedback_is_ignored() {
+        let mut bwe = DelayBasedBwe::new();
+        let result = bwe.incoming_packet_feedback_vector(
+            &TransportPacketsFeedback::default(),
+            None,
+            None,
+            None,
+            false,
+        );
+        assert!(!result.updated);
+    }
+
+    #[test]
+    fn increases_without_congestion() {
+        let mut bwe = DelayBasedBwe::new();
+        bwe.set_start_bitrate(DataRate::from_kilobits_per_sec(300));
+
+        // 1200 bytes every 10ms is 960kbps of throughput with a constant delay.
+        let result = run(
+            &mut bwe,
+            Timestamp::from_seconds(1),
+            50,
+            TimeDelta::from_millis(10),
+            TimeDelta::zero(),
+        );
+        assert!(result.updated);
+        assert_eq!(result.delay_detector_state, BandwidthUsage::Normal);
+        assert!(result.target_bitrate > DataRate::from_kilobits_per_sec(300));
+    }
+
+    #[test]
+    fn decreases_on_growing_delay() {
+        let mut bwe = DelayBasedBwe::new();
+        bwe.set_start_bitrate(DataRate::from_kilobits_per_sec(1000));
+
+        // Every packet is queued 1ms longer than the previous one.
+        run(
+            &mut bwe,
+            Timestamp::from_seconds(1),
+            20,
+            TimeDelta::from_millis(10),
+            TimeDelta::from_millis(1),
+        );
+        assert_eq!(bwe.last_state(), BandwidthUsage::Overusing);
+        assert!(bwe.last_estimate() < DataRate::from_kilobits_per_sec(1000));
+    }
+}
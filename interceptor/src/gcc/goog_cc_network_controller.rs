@@ -0,0 +1,254 @@
+use std::collections::VecDeque;
+
+use super::{
+    acknowledged_bitrate_estimator::AcknowledgedBitrateEstimator,
+    aimd_rate_control::BandwidthUsage,
+    data_rate::DataRate,
+    delay_based_bwe::DelayBasedBwe,
+    network_types::TransportPacketsFeedback,
+    send_side_bandwidth_estimation::SendSideBandwidthEstimation,
+    time::{TimeDelta, Timestamp},
+};
+
+const LOSS_UPDATE_INTERVAL: TimeDelta = TimeDelta::from_millis(1000);
+const MAX_FEEDBACK_RTTS: usize = 32;
+
+/// Combines the delay based and the loss based estimators into a single target bitrate, driven
+/// only by transport wide congestion control feedback.
+pub struct GoogCcNetworkController {
+    acknowledged_bitrate_estimator: AcknowledgedBitrateEstimator,
+    delay_based_bwe: DelayBasedBwe,
+    bandwidth_estimation: SendSideBandwidthEstimation,
+
+    feedback_max_rtts: VecDeque<i64>,
+    expected_packets_since_last_loss_update: i64,
+    lost_packets_since_last_loss_update: i64,
+    next_loss_update: Timestamp,
+}
+
+impl GoogCcNetworkController {
+    pub fn new(
+        start_bitrate: DataRate,
+        min_bitrate: DataRate,
+        max_bitrate: DataRate,
+        at_time: Timestamp,
+    ) -> Self {
+        let mut delay_based_bwe = DelayBasedBwe::new();
+        delay_based_bwe.set_start_bitrate(start_bitrate);
+        delay_based_bwe.set_min_bitrate(min_bitrate);
+
+        let mut bandwidth_estimation = SendSideBandwidthEstimation::new();
+        bandwidth_estimation.set_bitrates(Some(start_bitrate), min_bitrate, max_bitrate, at_time);
+
+        Self {
+            acknowledged_bitrate_estimator: AcknowledgedBitrateEstimator::new(),
+            delay_based_bwe,
+            bandwidth_estimation,
+            feedback_max_rtts: VecDeque::with_capacity(MAX_FEEDBACK_RTTS + 1),
+            expected_packets_since_last_loss_update: 0,
+            lost_packets_since_last_loss_update: 0,
+            next_loss_update: Timestamp::minus_infinity(),
+        }
+    }
+
+    /// Updates the estimators with a feedback report and returns the resulting target bitrate.
+    pub fn on_transport_packets_feedback(&mut self, report: &TransportPacketsFeedback) -> DataRate {
+        let feedbacks = report.received_with_send_info();
+        if !feedbacks.is_empty() {
+            self.update_rtt(report);
+        }
+
+        let packets_with_feedback = report.packets_with_feedback();
+        self.expected_packets_since_last_loss_update += packets_with_feedback.len() as i64;
+        self.lost_packets_since_last_loss_update += packets_with_feedback
+            .iter()
+            .filter(|packet| !packet.is_received())
+            .count() as i64;
+        if report.feedback_time > self.next_loss_update {
+            self.next_loss_update = report.feedback_time + LOSS_UPDATE_INTERVAL;
+            self.bandwidth_estimation.update_packets_lost(
+                self.lost_packets_since_last_loss_update,
+                self.expected_packets_since_last_loss_update,
+                report.feedback_time,
+            );
+            self.expected_packets_since_last_loss_update = 0;
+            self.lost_packets_since_last_loss_update = 0;
+        }
+
+        self.acknowledged_bitrate_estimator
+            .incoming_packet_feedback_vector(&report.sorted_by_receive_time());
+        let acknowledged_bitrate = self.acknowledged_bitrate_estimator.bitrate();
+
+        let result = self.delay_based_bwe.incoming_packet_feedback_vector(
+            report,
+            acknowledged_bitrate,
+            None,
+            None,
+            false,
+        );
+        if result.updated {
+            if result.probe {
+                self.bandwidth_estimation
+                    .set_send_bitrate(result.target_bitrate, report.feedback_time);
+            }
+            // Since SetSendBitrate now resets the delay-based estimate, we have to
+            // call UpdateDelayBasedEstimate after SetSendBitrate.
+            self.bandwidth_estimation
+                .update_delay_based_estimate(report.feedback_time, result.target_bitrate);
+        }
+        self.bandwidth_estimation
+            .update_estimate(report.feedback_time);
+
+        self.bandwidth_estimation.target_rate()
+    }
+
+    pub fn target_rate(&self) -> DataRate {
+        self.bandwidth_estimation.target_rate()
+    }
+
+    pub fn acknowledged_bitrate(&self) -> Option<DataRate> {
+        self.acknowledged_bitrate_estimator.bitrate()
+    }
+
+    pub fn delay_based_limit(&self) -> DataRate {
+        self.bandwidth_estimation.delay_based_limit()
+    }
+
+    pub fn delay_state(&self) -> BandwidthUsage {
+        self.delay_based_bwe.last_state()
+    }
+
+    pub fn fraction_loss(&self) -> f64 {
+        self.bandwidth_estimation.fraction_loss()
+    }
+
+    pub fn round_trip_time(&self) -> TimeDelta {
+        self.bandwidth_estimation.round_trip_time()
+    }
+
+    fn update_rtt(&mut self, report: &TransportPacketsFeedback) {
+        let feedbacks = report.received_with_send_info();
+
+        let max_recv_time = feedbacks
+            .iter()
+            .map(|feedback| feedback.receive_time)
+            .max()
+            .unwrap_or_else(Timestamp::minus_infinity);
+
+        let mut max_feedback_rtt = TimeDelta::minus_infinity();
+        let mut feedback_min_rtt = TimeDelta::plus_infinity();
+        for feedback in &feedbacks {
+            let feedback_rtt = report.feedback_time - feedback.sent_packet.send_time;
+            max_feedback_rtt = Ord::max(max_feedback_rtt, feedback_rtt);
+
+            // The time the receiver held on to the packet before sending the feedback isn't
+            // part of the round trip.
+            let pending_time = max_recv_time - feedback.receive_time;
+            feedback_min_rtt = Ord::min(feedback_min_rtt, feedback_rtt - pending_time);
+        }
+
+        if max_feedback_rtt.is_finite() {
+            self.feedback_max_rtts.push_back(max_feedback_rtt.ms());
+            if self.feedback_max_rtts.len() > MAX_FEEDBACK_RTTS {
+                self.feedback_max_rtts.pop_front();
+            }
+            let sum_rtt_ms: i64 = self.feedback_max_rtts.iter().sum();
+            let mean_rtt_ms = sum_rtt_ms / self.feedback_max_rtts.len() as i64;
+            self.delay_based_bwe
+                .on_rtt_update(TimeDelta::from_millis(mean_rtt_ms));
+        }
+
+        if feedback_min_rtt.is_finite() {
+            self.bandwidth_estimation.update_rtt(feedback_min_rtt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gcc::data_rate::DataSize;
+    use crate::gcc::network_types::{PacketResult, SentPacket};
+
+    const PACKET_SIZE: DataSize = DataSize::from_bytes(1200);
+
+    fn controller() -> GoogCcNetworkController {
+        GoogCcNetworkController::new(
+            DataRate::from_kilobits_per_sec(300),
+            DataRate::from_kilobits_per_sec(30),
+            DataRate::from_kilobits_per_sec(3000),
+            Timestamp::from_millis(0),
+        )
+    }
+
+    /// Sends a packet every 5ms, reporting them every 100ms starting with report `first_report`.
+    /// Every `loss_every`th packet is lost.
+    fn run(
+        controller: &mut GoogCcNetworkController,
+        first_report: i64,
+        reports: i64,
+        loss_every: i64,
+    ) -> DataRate {
+        let mut target = controller.target_rate();
+        let mut sequence_number = (first_report - 1) * 20;
+        for report in first_report..first_report + reports {
+            let mut feedback = TransportPacketsFeedback::default();
+            for _ in 0..20 {
+                let send_time = Timestamp::from_millis(sequence_number * 5);
+                let lost = loss_every > 0 && sequence_number % loss_every == 0;
+                feedback.packet_feedbacks.push(PacketResult {
+                    sent_packet: SentPacket {
+                        send_time,
+                        size: PACKET_SIZE,
+                        sequence_number,
+                        ..Default::default()
+                    },
+                    receive_time: if lost {
+                        Timestamp::plus_infinity()
+                    } else {
+                        send_time + TimeDelta::from_millis(20)
+                    },
+                });
+                sequence_number += 1;
+            }
+            feedback.feedback_time = Timestamp::from_millis(report * 100 + 40);
+            target = controller.on_transport_packets_feedback(&feedback);
+        }
+        target
+    }
+
+    #[test]
+    fn estimates_round_trip_time() {
+        let mut controller = controller();
+        run(&mut controller, 1, 10, 0);
+
+        // The last packet of each report is sent 45ms before the feedback arrives.
+        assert_eq!(controller.round_trip_time(), TimeDelta::from_millis(45));
+    }
+
+    #[test]
+    fn ramps_up_without_loss() {
+        let mut controller = controller();
+        let target = run(&mut controller, 1, 100, 0);
+
+        assert_eq!(controller.fraction_loss(), 0.0);
+        assert_eq!(controller.delay_state(), BandwidthUsage::Normal);
+        assert!(target > DataRate::from_kilobits_per_sec(300));
+        assert!(target <= DataRate::from_kilobits_per_sec(3000));
+
+        // 1200 bytes every 5ms is 1920kbps
+        let acknowledged = controller.acknowledged_bitrate().unwrap().kbps();
+        assert!((1800..=2000).contains(&acknowledged), "{acknowledged}");
+    }
+
+    #[test]
+    fn backs_off_on_heavy_loss() {
+        let mut controller = controller();
+        let target = run(&mut controller, 1, 100, 0);
+
+        // Every 4th packet lost
+        let lossy_target = run(&mut controller, 101, 30, 4);
+        assert!(controller.fraction_loss() > 0.2);
+        assert!(lossy_target < target);
+    }
+}
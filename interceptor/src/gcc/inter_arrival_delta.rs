@@ -92,8 +92,8 @@ impl InterArrivalDelta {
                 } else {
                     self.num_consecutive_reordered_packets = 0;
                 }
-                *packet_size_delta =
-                    (self.current_timestamp_group.size - self.prev_timestamp_group.size) as i32;
+                *packet_size_delta = self.current_timestamp_group.size as i32
+                    - self.prev_timestamp_group.size as i32;
                 calculated_deltas = true;
             }
             self.prev_timestamp_group = self.current_timestamp_group.clone();
@@ -1,12 +1,27 @@
 //! Direct port of Chromium's WebRTC commit 0c4165e667751972c7d39c81d8993e8617cb7e13
+//!
+//! The estimators are driven by transport wide congestion control feedback through the
+//! [`SendSideBwe`] interceptor, which publishes the resulting target bitrate through a
+//! [`BandwidthEstimator`].
 
+#[cfg(test)]
+mod send_side_bwe_test;
+
+mod acknowledged_bitrate_estimator;
 mod aimd_rate_control;
 mod data_rate;
 mod delay_based_bwe;
+mod goog_cc_network_controller;
 mod inter_arrival;
 mod inter_arrival_delta;
 mod link_capacity_estimator;
 mod network_state_predictor;
 mod network_types;
+mod send_side_bandwidth_estimation;
+mod send_side_bwe;
 mod time;
+mod transport_feedback_adapter;
 mod trendline_estimator;
+
+pub use aimd_rate_control::BandwidthUsage;
+pub use send_side_bwe::{BandwidthEstimator, SendSideBwe, SendSideBweBuilder, Stats};
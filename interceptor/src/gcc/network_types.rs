@@ -7,11 +7,11 @@ use super::{
 
 #[derive(Debug, Clone)]
 pub struct PacedPacketInfo {
-    send_bitrate: DataRate,
-    probe_cluster_id: i32,
-    probe_cluster_min_probes: i32,
-    probe_cluster_min_bytes: i32,
-    probe_cluster_bytes_sent: i32,
+    pub send_bitrate: DataRate,
+    pub probe_cluster_id: i32,
+    pub probe_cluster_min_probes: i32,
+    pub probe_cluster_min_bytes: i32,
+    pub probe_cluster_bytes_sent: i32,
 }
 
 impl PacedPacketInfo {
@@ -32,22 +32,22 @@ impl Default for PacedPacketInfo {
 
 #[derive(Debug, Clone)]
 pub struct SentPacket {
-    send_time: Timestamp,
+    pub send_time: Timestamp,
     // Size of packet with overhead up to IP layer.
-    size: DataSize,
+    pub size: DataSize,
     // Size of preceeding packets that are not part of feedback.
-    prior_unacked_data: DataSize,
+    pub prior_unacked_data: DataSize,
     // Probe cluster id and parameters including bitrate, number of packets and
     // number of bytes.
-    pacing_info: PacedPacketInfo,
+    pub pacing_info: PacedPacketInfo,
     // True if the packet is an audio packet, false for video, padding, RTX etc.
-    audio: bool,
+    pub audio: bool,
     // Transport independent sequence number, any tracked packet should have a
     // sequence number that is unique over the whole call and increasing by 1 for
     // each packet.
-    sequence_number: i64,
+    pub sequence_number: i64,
     // Tracked data in flight when the packet was sent, excluding unacked data.
-    data_in_flight: DataSize,
+    pub data_in_flight: DataSize,
 }
 
 impl Default for SentPacket {
@@ -66,8 +66,8 @@ impl Default for SentPacket {
 
 #[derive(Debug, Clone)]
 pub struct PacketResult {
-    sent_packet: SentPacket,
-    receive_time: Timestamp,
+    pub sent_packet: SentPacket,
+    pub receive_time: Timestamp,
 }
 
 impl Default for PacketResult {
@@ -86,13 +86,13 @@ impl PacketResult {
 }
 
 pub struct TransportPacketsFeedback {
-    feedback_time: Timestamp,
-    first_unacked_send_time: Timestamp,
-    data_in_flight: DataSize,
-    prior_in_flight: DataSize,
-    packet_feedbacks: Vec<PacketResult>,
+    pub feedback_time: Timestamp,
+    pub first_unacked_send_time: Timestamp,
+    pub data_in_flight: DataSize,
+    pub prior_in_flight: DataSize,
+    pub packet_feedbacks: Vec<PacketResult>,
     // Arrival times for messages without send time information.
-    sendless_arrival_times: Vec<Timestamp>,
+    pub sendless_arrival_times: Vec<Timestamp>,
 }
 
 impl Default for TransportPacketsFeedback {
@@ -0,0 +1,370 @@
+use std::collections::VecDeque;
+
+use super::{
+    data_rate::DataRate,
+    time::{TimeDelta, Timestamp},
+};
+
+const BWE_INCREASE_INTERVAL: TimeDelta = TimeDelta::from_millis(1000);
+const BWE_DECREASE_INTERVAL: TimeDelta = TimeDelta::from_millis(300);
+const MAX_RTCP_FEEDBACK_INTERVAL: TimeDelta = TimeDelta::from_millis(5000);
+const LIMIT_NUM_PACKETS: i64 = 20;
+const DEFAULT_MAX_BITRATE: DataRate = DataRate::from_bits_per_sec(1_000_000_000);
+const CONGESTION_CONTROLLER_MIN_BITRATE: DataRate = DataRate::from_bits_per_sec(5_000);
+
+const DEFAULT_LOW_LOSS_THRESHOLD: f64 = 0.02;
+const DEFAULT_HIGH_LOSS_THRESHOLD: f64 = 0.1;
+const DEFAULT_BITRATE_THRESHOLD: DataRate = DataRate::zero();
+
+/// The loss based part of the send side bandwidth estimation. The estimate is increased by 8%
+/// per second while less than 2% of the packets are lost, is held while the loss stays below
+/// 10%, and is decreased proportionally to the loss above that. The result never exceeds the
+/// delay based estimate.
+pub struct SendSideBandwidthEstimation {
+    min_bitrate_history: VecDeque<(Timestamp, DataRate)>,
+
+    // incoming filters
+    lost_packets_since_last_loss_update: i64,
+    expected_packets_since_last_loss_update: i64,
+
+    current_target: DataRate,
+    min_bitrate_configured: DataRate,
+    max_bitrate_configured: DataRate,
+    last_low_bitrate_log: Timestamp,
+
+    has_decreased_since_last_fraction_loss: bool,
+    last_loss_feedback: Timestamp,
+    last_loss_packet_report: Timestamp,
+    last_fraction_loss: u8,
+    last_round_trip_time: TimeDelta,
+
+    time_last_decrease: Timestamp,
+    delay_based_limit: DataRate,
+    low_loss_threshold: f64,
+    high_loss_threshold: f64,
+    bitrate_threshold: DataRate,
+}
+
+impl SendSideBandwidthEstimation {
+    pub fn new() -> Self {
+        Self {
+            min_bitrate_history: VecDeque::new(),
+            lost_packets_since_last_loss_update: 0,
+            expected_packets_since_last_loss_update: 0,
+            current_target: DataRate::zero(),
+            min_bitrate_configured: CONGESTION_CONTROLLER_MIN_BITRATE,
+            max_bitrate_configured: DEFAULT_MAX_BITRATE,
+            last_low_bitrate_log: Timestamp::minus_infinity(),
+            has_decreased_since_last_fraction_loss: false,
+            last_loss_feedback: Timestamp::minus_infinity(),
+            last_loss_packet_report: Timestamp::minus_infinity(),
+            last_fraction_loss: 0,
+            last_round_trip_time: TimeDelta::zero(),
+            time_last_decrease: Timestamp::minus_infinity(),
+            delay_based_limit: DataRate::plus_infinity(),
+            low_loss_threshold: DEFAULT_LOW_LOSS_THRESHOLD,
+            high_loss_threshold: DEFAULT_HIGH_LOSS_THRESHOLD,
+            bitrate_threshold: DEFAULT_BITRATE_THRESHOLD,
+        }
+    }
+
+    pub fn target_rate(&self) -> DataRate {
+        self.current_target
+    }
+
+    /// Returns the fraction of packets lost in the last loss report, scaled to `[0, 1]`.
+    pub fn fraction_loss(&self) -> f64 {
+        self.last_fraction_loss as f64 / 256.0
+    }
+
+    pub fn round_trip_time(&self) -> TimeDelta {
+        self.last_round_trip_time
+    }
+
+    pub fn delay_based_limit(&self) -> DataRate {
+        self.delay_based_limit
+    }
+
+    /// Call when we receive a RTCP message with a ReceiveBlock.
+    pub fn update_packets_lost(
+        &mut self,
+        packets_lost: i64,
+        number_of_packets: i64,
+        at_time: Timestamp,
+    ) {
+        self.last_loss_feedback = at_time;
+
+        // Check sequence number diff and weight loss report
+        if number_of_packets > 0 {
+            let expected = self.expected_packets_since_last_loss_update + number_of_packets;
+
+            // Don't generate a loss rate until it can be based on enough packets.
+            if expected < LIMIT_NUM_PACKETS {
+                // Accumulate reports.
+                self.expected_packets_since_last_loss_update = expected;
+                self.lost_packets_since_last_loss_update += packets_lost;
+                return;
+            }
+
+            self.has_decreased_since_last_fraction_loss = false;
+            let lost_q8 = (self.lost_packets_since_last_loss_update + packets_lost).max(0) << 8;
+            self.last_fraction_loss = std::cmp::min(lost_q8 / expected, 255) as u8;
+
+            // Reset accumulators.
+            self.lost_packets_since_last_loss_update = 0;
+            self.expected_packets_since_last_loss_update = 0;
+            self.last_loss_packet_report = at_time;
+            self.update_estimate(at_time);
+        }
+    }
+
+    pub fn update_rtt(&mut self, rtt: TimeDelta) {
+        // Update RTT if we were able to compute an RTT based on this RTCP.
+        if rtt > TimeDelta::zero() {
+            self.last_round_trip_time = rtt;
+        }
+    }
+
+    pub fn update_delay_based_estimate(&mut self, at_time: Timestamp, bitrate: DataRate) {
+        // TODO(srte): Ensure caller passes PlusInfinity, not zero, to represent no
+        // limitation.
+        self.delay_based_limit = if bitrate.is_zero() {
+            DataRate::plus_infinity()
+        } else {
+            bitrate
+        };
+        self.apply_target_limits(at_time);
+    }
+
+    pub fn set_bitrates(
+        &mut self,
+        send_bitrate: Option<DataRate>,
+        min_bitrate: DataRate,
+        max_bitrate: DataRate,
+        at_time: Timestamp,
+    ) {
+        self.set_min_max_bitrate(min_bitrate, max_bitrate);
+        if let Some(send_bitrate) = send_bitrate {
+            self.set_send_bitrate(send_bitrate, at_time);
+        }
+    }
+
+    pub fn set_send_bitrate(&mut self, bitrate: DataRate, at_time: Timestamp) {
+        debug_assert!(bitrate > DataRate::zero());
+        // Reset to avoid being capped by the estimate.
+        self.delay_based_limit = DataRate::plus_infinity();
+        self.update_target_bitrate(bitrate, at_time);
+        // Clear last sent bitrate history so the new value can be used directly
+        // and not capped.
+        self.min_bitrate_history.clear();
+    }
+
+    pub fn set_min_max_bitrate(&mut self, min_bitrate: DataRate, max_bitrate: DataRate) {
+        self.min_bitrate_configured = std::cmp::max(min_bitrate, CONGESTION_CONTROLLER_MIN_BITRATE);
+        if max_bitrate > DataRate::zero() && max_bitrate.is_finite() {
+            self.max_bitrate_configured = std::cmp::max(self.min_bitrate_configured, max_bitrate);
+        } else {
+            self.max_bitrate_configured = DEFAULT_MAX_BITRATE;
+        }
+    }
+
+    pub fn update_estimate(&mut self, at_time: Timestamp) {
+        self.update_min_history(at_time);
+        if self.last_loss_packet_report.is_infinite() {
+            // No feedback received.
+            self.apply_target_limits(at_time);
+            return;
+        }
+
+        let time_since_loss_packet_report = at_time - self.last_loss_packet_report;
+        if time_since_loss_packet_report < MAX_RTCP_FEEDBACK_INTERVAL * 1.2 {
+            // We only care about loss above a given bitrate threshold.
+            let loss = self.fraction_loss();
+            // We only make decisions based on loss when the bitrate is above a
+            // threshold. This is a crude way of handling loss which is uncorrelated
+            // to congestion.
+            if self.current_target < self.bitrate_threshold || loss <= self.low_loss_threshold {
+                // Loss < 2%: Increase rate by 8% of the min bitrate in the last
+                // BWE_INCREASE_INTERVAL.
+                // Note that by remembering the bitrate over the last second one can
+                // rampup up one second faster than if only allowed to start ramping
+                // at 8% per second rate now. E.g.:
+                //   If sending a constant 100kbps it can rampup immediately to 108kbps
+                //   whenever a receiver report is received with lower packet loss.
+                //   If instead one would do: current_bitrate_ *= 1.08^(delta time),
+                //   it would take over one second since the lower packet loss to achieve
+                //   108kbps.
+                let min_bitrate = self
+                    .min_bitrate_history
+                    .front()
+                    .map(|(_, bitrate)| *bitrate)
+                    .unwrap_or(self.current_target);
+                let new_bitrate =
+                    DataRate::from_bits_per_sec((min_bitrate.bps() as f64 * 1.08 + 0.5) as i64)
+                        + DataRate::from_bits_per_sec(1000);
+                self.update_target_bitrate(new_bitrate, at_time);
+                return;
+            } else if self.current_target > self.bitrate_threshold {
+                if loss <= self.high_loss_threshold {
+                    // Loss between 2% - 10%: Do nothing.
+                } else {
+                    // Loss > 10%: Limit the rate decreases to once a BWE_DECREASE_INTERVAL
+                    // + rtt.
+                    if !self.has_decreased_since_last_fraction_loss
+                        && (at_time - self.time_last_decrease)
+                            >= (BWE_DECREASE_INTERVAL + self.last_round_trip_time)
+                    {
+                        self.time_last_decrease = at_time;
+
+                        // Reduce rate:
+                        //   newRate = rate * (1 - 0.5*lossRate);
+                        //   where packetLoss = 256*lossRate;
+                        let new_bitrate = DataRate::from_bits_per_sec(
+                            (self.current_target.bps() as f64
+                                * (512 - self.last_fraction_loss as i64) as f64
+                                / 512.0) as i64,
+                        );
+                        self.has_decreased_since_last_fraction_loss = true;
+                        self.update_target_bitrate(new_bitrate, at_time);
+                        return;
+                    }
+                }
+            }
+        }
+        self.apply_target_limits(at_time);
+    }
+
+    fn get_upper_limit(&self) -> DataRate {
+        std::cmp::min(self.delay_based_limit, self.max_bitrate_configured)
+    }
+
+    fn maybe_log_low_bitrate_warning(&mut self, bitrate: DataRate, at_time: Timestamp) {
+        const LOW_BITRATE_LOG_PERIOD: TimeDelta = TimeDelta::from_millis(10000);
+        if at_time - self.last_low_bitrate_log > LOW_BITRATE_LOG_PERIOD {
+            log::warn!(
+                "Estimated available bandwidth {} kbps is below configured min bitrate {} kbps.",
+                bitrate.kbps(),
+                self.min_bitrate_configured.kbps()
+            );
+            self.last_low_bitrate_log = at_time;
+        }
+    }
+
+    fn update_min_history(&mut self, at_time: Timestamp) {
+        // Remove old data points from history.
+        // Since history precision is in ms, add one so it is able to increase
+        // bitrate if it is off by as little as 0.5ms.
+        while let Some((time, _)) = self.min_bitrate_history.front() {
+            if at_time - *time + TimeDelta::from_millis(1) > BWE_INCREASE_INTERVAL {
+                self.min_bitrate_history.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        // Typical minimum sliding-window algorithm: Pop values higher than current
+        // bitrate before pushing it.
+        while let Some((_, bitrate)) = self.min_bitrate_history.back() {
+            if self.current_target <= *bitrate {
+                self.min_bitrate_history.pop_back();
+            } else {
+                break;
+            }
+        }
+
+        self.min_bitrate_history
+            .push_back((at_time, self.current_target));
+    }
+
+    fn update_target_bitrate(&mut self, mut new_bitrate: DataRate, at_time: Timestamp) {
+        new_bitrate = std::cmp::min(new_bitrate, self.get_upper_limit());
+        if new_bitrate < self.min_bitrate_configured {
+            self.maybe_log_low_bitrate_warning(new_bitrate, at_time);
+            new_bitrate = self.min_bitrate_configured;
+        }
+        self.current_target = new_bitrate;
+    }
+
+    fn apply_target_limits(&mut self, at_time: Timestamp) {
+        self.update_target_bitrate(self.current_target, at_time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimation(start_bitrate: DataRate) -> SendSideBandwidthEstimation {
+        let mut bwe = SendSideBandwidthEstimation::new();
+        bwe.set_bitrates(
+            Some(start_bitrate),
+            DataRate::from_kilobits_per_sec(30),
+            DataRate::from_kilobits_per_sec(2500),
+            Timestamp::from_millis(0),
+        );
+        bwe
+    }
+
+    #[test]
+    fn increases_on_low_loss() {
+        let mut bwe = estimation(DataRate::from_kilobits_per_sec(300));
+
+        let mut now = Timestamp::from_seconds(1);
+        for _ in 0..10 {
+            bwe.update_packets_lost(0, 100, now);
+            now += TimeDelta::from_millis(1000);
+        }
+        assert!(bwe.target_rate() > DataRate::from_kilobits_per_sec(500));
+        assert!(bwe.target_rate() <= DataRate::from_kilobits_per_sec(2500));
+    }
+
+    #[test]
+    fn holds_on_moderate_loss() {
+        let mut bwe = estimation(DataRate::from_kilobits_per_sec(300));
+
+        // 5% loss
+        bwe.update_packets_lost(5, 100, Timestamp::from_seconds(1));
+        assert_eq!(bwe.target_rate(), DataRate::from_kilobits_per_sec(300));
+    }
+
+    #[test]
+    fn decreases_on_high_loss() {
+        let mut bwe = estimation(DataRate::from_kilobits_per_sec(1000));
+
+        // 50% loss
+        bwe.update_packets_lost(50, 100, Timestamp::from_seconds(1));
+        let decreased = bwe.target_rate();
+        assert!(decreased < DataRate::from_kilobits_per_sec(1000));
+        assert!(decreased >= DataRate::from_kilobits_per_sec(700));
+
+        // A second report within the decrease interval doesn't decrease any further.
+        bwe.update_packets_lost(50, 100, Timestamp::from_millis(1100));
+        assert_eq!(bwe.target_rate(), decreased);
+    }
+
+    #[test]
+    fn waits_for_enough_packets() {
+        let mut bwe = estimation(DataRate::from_kilobits_per_sec(1000));
+
+        bwe.update_packets_lost(10, 10, Timestamp::from_seconds(1));
+        assert_eq!(bwe.target_rate(), DataRate::from_kilobits_per_sec(1000));
+        assert_eq!(bwe.fraction_loss(), 0.0);
+
+        bwe.update_packets_lost(0, 10, Timestamp::from_seconds(1));
+        assert!(bwe.fraction_loss() > 0.4);
+    }
+
+    #[test]
+    fn limited_by_delay_based_estimate() {
+        let mut bwe = estimation(DataRate::from_kilobits_per_sec(1000));
+
+        bwe.update_delay_based_estimate(
+            Timestamp::from_seconds(1),
+            DataRate::from_kilobits_per_sec(400),
+        );
+        assert_eq!(bwe.target_rate(), DataRate::from_kilobits_per_sec(400));
+
+        bwe.update_packets_lost(0, 100, Timestamp::from_seconds(2));
+        assert_eq!(bwe.target_rate(), DataRate::from_kilobits_per_sec(400));
+    }
+}
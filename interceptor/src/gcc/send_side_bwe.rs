@@ -0,0 +1,352 @@
+use std::sync::{Arc, Mutex as SyncMutex};
+use std::time::Instant;
+
+use async_trait::async_trait;
+use rtcp::transport_feedbacks::transport_layer_cc::TransportLayerCc;
+use rtp::extension::transport_cc_extension::TransportCcExtension;
+use tokio::sync::watch;
+use util::{MarshalSize, Unmarshal};
+
+use super::aimd_rate_control::BandwidthUsage;
+use super::data_rate::{DataRate, DataSize};
+use super::goog_cc_network_controller::GoogCcNetworkController;
+use super::time::Timestamp;
+use super::transport_feedback_adapter::TransportFeedbackAdapter;
+use crate::error::Result;
+use crate::stream_info::StreamInfo;
+use crate::twcc::sender::TRANSPORT_CC_URI;
+use crate::{
+    Attributes, Interceptor, InterceptorBuilder, RTCPReader, RTCPWriter, RTPReader, RTPWriter,
+};
+
+const DEFAULT_INITIAL_BITRATE: u64 = 300_000;
+const DEFAULT_MIN_BITRATE: u64 = 30_000;
+const DEFAULT_MAX_BITRATE: u64 = 10_000_000;
+
+type OnNewEstimatorFn = Box<dyn Fn(&str, BandwidthEstimator) + Send + Sync>;
+
+/// SendSideBweBuilder is a InterceptorBuilder for a SendSideBwe Interceptor
+pub struct SendSideBweBuilder {
+    initial_bitrate: u64,
+    min_bitrate: u64,
+    max_bitrate: u64,
+    on_new_estimator: Option<OnNewEstimatorFn>,
+}
+
+impl Default for SendSideBweBuilder {
+    fn default() -> Self {
+        SendSideBweBuilder {
+            initial_bitrate: DEFAULT_INITIAL_BITRATE,
+            min_bitrate: DEFAULT_MIN_BITRATE,
+            max_bitrate: DEFAULT_MAX_BITRATE,
+            on_new_estimator: None,
+        }
+    }
+}
+
+impl SendSideBweBuilder {
+    /// with_initial_bitrate sets the bitrate in bits per second the estimation starts from.
+    pub fn with_initial_bitrate(mut self, initial_bitrate: u64) -> SendSideBweBuilder {
+        self.initial_bitrate = initial_bitrate;
+        self
+    }
+
+    /// with_min_bitrate sets the lowest target bitrate in bits per second.
+    pub fn with_min_bitrate(mut self, min_bitrate: u64) -> SendSideBweBuilder {
+        self.min_bitrate = min_bitrate;
+        self
+    }
+
+    /// with_max_bitrate sets the highest target bitrate in bits per second.
+    pub fn with_max_bitrate(mut self, max_bitrate: u64) -> SendSideBweBuilder {
+        self.max_bitrate = max_bitrate;
+        self
+    }
+
+    /// with_on_new_estimator sets a callback that is invoked with the id of the PeerConnection
+    /// and its BandwidthEstimator every time a new interceptor is built.
+    pub fn with_on_new_estimator(
+        mut self,
+        f: impl Fn(&str, BandwidthEstimator) + Send + Sync + 'static,
+    ) -> SendSideBweBuilder {
+        self.on_new_estimator = Some(Box::new(f));
+        self
+    }
+}
+
+impl InterceptorBuilder for SendSideBweBuilder {
+    /// build constructs a new SendSideBwe
+    fn build(&self, id: &str) -> Result<Arc<dyn Interceptor + Send + Sync>> {
+        let min_bitrate = self.min_bitrate.min(self.max_bitrate);
+        let initial_bitrate = self.initial_bitrate.clamp(min_bitrate, self.max_bitrate);
+
+        let controller = GoogCcNetworkController::new(
+            DataRate::from_bits_per_sec(initial_bitrate as i64),
+            DataRate::from_bits_per_sec(min_bitrate as i64),
+            DataRate::from_bits_per_sec(self.max_bitrate as i64),
+            Timestamp::zero(),
+        );
+        let (target_bitrate_tx, _) = watch::channel(initial_bitrate);
+
+        let estimator = BandwidthEstimator {
+            internal: Arc::new(EstimatorInternal {
+                start: Instant::now(),
+                state: SyncMutex::new(EstimatorState {
+                    adapter: TransportFeedbackAdapter::new(),
+                    controller,
+                }),
+                target_bitrate_tx,
+            }),
+        };
+
+        if let Some(f) = &self.on_new_estimator {
+            f(id, estimator.clone());
+        }
+
+        Ok(Arc::new(SendSideBwe { estimator }))
+    }
+}
+
+/// Stats are the internal values of a BandwidthEstimator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    /// The bitrate in bits per second the encoders should target.
+    pub target_bitrate: u64,
+    /// The estimate of the delay based controller in bits per second.
+    pub delay_based_bitrate: Option<u64>,
+    /// The throughput acknowledged by the remote peer in bits per second.
+    pub acknowledged_bitrate: Option<u64>,
+    /// The state of the delay based overuse detector.
+    pub delay_state: BandwidthUsage,
+    /// The fraction of packets lost as reported by the last loss update.
+    pub loss_ratio: f64,
+    /// The round trip time in milliseconds, derived from the feedback.
+    pub round_trip_time_ms: i64,
+}
+
+struct EstimatorState {
+    adapter: TransportFeedbackAdapter,
+    controller: GoogCcNetworkController,
+}
+
+struct EstimatorInternal {
+    start: Instant,
+    state: SyncMutex<EstimatorState>,
+    target_bitrate_tx: watch::Sender<u64>,
+}
+
+impl EstimatorInternal {
+    fn now(&self) -> Timestamp {
+        Timestamp::from_micros(self.start.elapsed().as_micros() as i64)
+    }
+
+    fn on_packet_sent(&self, sequence_number: u16, size: usize, audio: bool) {
+        let now = self.now();
+        let mut state = self.state.lock().unwrap();
+        state.adapter.add_packet(
+            sequence_number,
+            DataSize::from_bytes(size as i64),
+            audio,
+            now,
+        );
+    }
+
+    fn on_feedback(&self, feedback: &TransportLayerCc) {
+        let now = self.now();
+        let target_bitrate = {
+            let mut state = self.state.lock().unwrap();
+            let report = match state.adapter.process_transport_feedback(feedback, now) {
+                Some(report) => report,
+                None => return,
+            };
+            state.controller.on_transport_packets_feedback(&report)
+        };
+
+        let target_bitrate = target_bitrate.bps().max(0) as u64;
+        self.target_bitrate_tx.send_if_modified(|current| {
+            if *current != target_bitrate {
+                *current = target_bitrate;
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
+
+/// BandwidthEstimator is a handle to the estimate of a SendSideBwe interceptor.
+#[derive(Clone)]
+pub struct BandwidthEstimator {
+    internal: Arc<EstimatorInternal>,
+}
+
+impl BandwidthEstimator {
+    /// target_bitrate returns the current target bitrate in bits per second.
+    pub fn target_bitrate(&self) -> u64 {
+        *self.internal.target_bitrate_tx.borrow()
+    }
+
+    /// subscribe returns a receiver which is notified every time the target bitrate changes.
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.internal.target_bitrate_tx.subscribe()
+    }
+
+    /// stats returns the internal values of the estimator.
+    pub fn stats(&self) -> Stats {
+        let state = self.internal.state.lock().unwrap();
+        let controller = &state.controller;
+        let delay_based_bitrate = controller.delay_based_limit();
+
+        Stats {
+            target_bitrate: self.target_bitrate(),
+            delay_based_bitrate: delay_based_bitrate
+                .is_finite()
+                .then(|| delay_based_bitrate.bps() as u64),
+            acknowledged_bitrate: controller
+                .acknowledged_bitrate()
+                .map(|bitrate| bitrate.bps() as u64),
+            delay_state: controller.delay_state(),
+            loss_ratio: controller.fraction_loss(),
+            round_trip_time_ms: controller.round_trip_time().ms(),
+        }
+    }
+}
+
+/// SendSideBwe estimates the available send bandwidth with Google Congestion Control, using the
+/// transport wide congestion control feedback of the remote peer. It must be registered before
+/// the twcc::sender::Sender so it sees the transport wide sequence numbers of outgoing packets.
+pub struct SendSideBwe {
+    estimator: BandwidthEstimator,
+}
+
+impl SendSideBwe {
+    /// builder returns a new SendSideBweBuilder.
+    pub fn builder() -> SendSideBweBuilder {
+        SendSideBweBuilder::default()
+    }
+
+    /// estimator returns the handle to the bandwidth estimate of this interceptor.
+    pub fn estimator(&self) -> BandwidthEstimator {
+        self.estimator.clone()
+    }
+}
+
+#[async_trait]
+impl Interceptor for SendSideBwe {
+    /// bind_rtcp_reader returns a reader that feeds incoming transport wide congestion control
+    /// feedback to the estimator.
+    async fn bind_rtcp_reader(
+        &self,
+        reader: Arc<dyn RTCPReader + Send + Sync>,
+    ) -> Arc<dyn RTCPReader + Send + Sync> {
+        Arc::new(FeedbackReader {
+            internal: Arc::clone(&self.estimator.internal),
+            next_rtcp_reader: reader,
+        })
+    }
+
+    /// bind_rtcp_writer lets you modify any outgoing RTCP packets. It is called once per PeerConnection. The returned method
+    /// will be called once per packet batch.
+    async fn bind_rtcp_writer(
+        &self,
+        writer: Arc<dyn RTCPWriter + Send + Sync>,
+    ) -> Arc<dyn RTCPWriter + Send + Sync> {
+        writer
+    }
+
+    /// bind_local_stream returns a writer that records the transport wide sequence number,
+    /// size and send time of each outgoing packet.
+    async fn bind_local_stream(
+        &self,
+        info: &StreamInfo,
+        writer: Arc<dyn RTPWriter + Send + Sync>,
+    ) -> Arc<dyn RTPWriter + Send + Sync> {
+        let hdr_ext_id = info
+            .rtp_header_extensions
+            .iter()
+            .find(|e| e.uri == TRANSPORT_CC_URI)
+            .map_or(0, |e| e.id as u8);
+        if hdr_ext_id == 0 {
+            // Without transport wide sequence numbers there won't be any feedback
+            return writer;
+        }
+
+        Arc::new(SentPacketRecorder {
+            internal: Arc::clone(&self.estimator.internal),
+            next_rtp_writer: writer,
+            hdr_ext_id,
+            audio: info.mime_type.to_lowercase().starts_with("audio/"),
+        })
+    }
+
+    /// unbind_local_stream is called when the Stream is removed. It can be used to clean up any data related to that track.
+    async fn unbind_local_stream(&self, _info: &StreamInfo) {}
+
+    /// bind_remote_stream lets you modify any incoming RTP packets. It is called once for per RemoteStream. The returned method
+    /// will be called once per rtp packet.
+    async fn bind_remote_stream(
+        &self,
+        _info: &StreamInfo,
+        reader: Arc<dyn RTPReader + Send + Sync>,
+    ) -> Arc<dyn RTPReader + Send + Sync> {
+        reader
+    }
+
+    /// unbind_remote_stream is called when the Stream is removed. It can be used to clean up any data related to that track.
+    async fn unbind_remote_stream(&self, _info: &StreamInfo) {}
+
+    /// close closes the Interceptor, cleaning up any data if necessary.
+    async fn close(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+struct SentPacketRecorder {
+    internal: Arc<EstimatorInternal>,
+    next_rtp_writer: Arc<dyn RTPWriter + Send + Sync>,
+    hdr_ext_id: u8,
+    audio: bool,
+}
+
+#[async_trait]
+impl RTPWriter for SentPacketRecorder {
+    /// write a rtp packet
+    async fn write(&self, pkt: &rtp::packet::Packet, a: &Attributes) -> Result<usize> {
+        if let Some(mut payload) = pkt.header.get_extension(self.hdr_ext_id) {
+            if let Ok(tcc_ext) = TransportCcExtension::unmarshal(&mut payload) {
+                self.internal.on_packet_sent(
+                    tcc_ext.transport_sequence,
+                    pkt.marshal_size(),
+                    self.audio,
+                );
+            }
+        }
+
+        self.next_rtp_writer.write(pkt, a).await
+    }
+}
+
+struct FeedbackReader {
+    internal: Arc<EstimatorInternal>,
+    next_rtcp_reader: Arc<dyn RTCPReader + Send + Sync>,
+}
+
+#[async_trait]
+impl RTCPReader for FeedbackReader {
+    /// read a batch of rtcp packets
+    async fn read(
+        &self,
+        buf: &mut [u8],
+        a: &Attributes,
+    ) -> Result<(Vec<Box<dyn rtcp::packet::Packet + Send + Sync>>, Attributes)> {
+        let (pkts, attr) = self.next_rtcp_reader.read(buf, a).await?;
+
+        for p in &pkts {
+            if let Some(feedback) = p.as_any().downcast_ref::<TransportLayerCc>() {
+                self.internal.on_feedback(feedback);
+            }
+        }
+
+        Ok((pkts, attr))
+    }
+}
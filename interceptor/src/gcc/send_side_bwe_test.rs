@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use rtcp::transport_feedbacks::transport_layer_cc::{
+    PacketStatusChunk, RunLengthChunk, StatusChunkTypeTcc, SymbolTypeTcc, TransportLayerCc,
+};
+use rtp::extension::transport_cc_extension::TransportCcExtension;
+use tokio::sync::Mutex;
+use tokio::time::Duration;
+use util::Marshal;
+
+use super::*;
+use crate::error::Result;
+use crate::mock::mock_stream::MockStream;
+use crate::stream_info::{RTPHeaderExtension, StreamInfo};
+use crate::test::timeout_or_fail;
+use crate::twcc::sender::TRANSPORT_CC_URI;
+use crate::InterceptorBuilder;
+
+async fn new_stream(builder: SendSideBweBuilder) -> Result<(Arc<MockStream>, BandwidthEstimator)> {
+    let estimator = Arc::new(Mutex::new(None));
+    let estimator2 = Arc::clone(&estimator);
+    let icpr = builder
+        .with_on_new_estimator(move |id, e| {
+            assert_eq!(id, "pc");
+            estimator2.try_lock().unwrap().replace(e);
+        })
+        .build("pc")?;
+
+    let stream = MockStream::new(
+        &StreamInfo {
+            ssrc: 1,
+            mime_type: "video/VP8".to_owned(),
+            rtp_header_extensions: vec![RTPHeaderExtension {
+                uri: TRANSPORT_CC_URI.to_owned(),
+                id: 1,
+            }],
+            ..Default::default()
+        },
+        icpr,
+    )
+    .await;
+
+    let estimator = estimator.lock().await.take().expect("estimator callback");
+    Ok((stream, estimator))
+}
+
+async fn send_packets(stream: &MockStream, count: u16) -> Result<()> {
+    for seq_num in 0..count {
+        let mut pkt = rtp::packet::Packet {
+            header: rtp::header::Header {
+                ssrc: 1,
+                sequence_number: seq_num,
+                ..Default::default()
+            },
+            payload: vec![0u8; 1000].into(),
+        };
+        let tcc_ext = TransportCcExtension {
+            transport_sequence: seq_num,
+        };
+        pkt.header.set_extension(1, tcc_ext.marshal()?)?;
+        stream.write_rtp(&pkt).await?;
+
+        let p = timeout_or_fail(Duration::from_millis(10), stream.written_rtp())
+            .await
+            .expect("A packet");
+        assert_eq!(p.header.sequence_number, seq_num);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_send_side_bwe_backs_off_on_loss() -> Result<()> {
+    let (stream, estimator) = new_stream(SendSideBwe::builder()).await?;
+    assert_eq!(estimator.target_bitrate(), 300_000);
+    let mut target_bitrate_rx = estimator.subscribe();
+
+    send_packets(&stream, 20).await?;
+
+    // The remote peer didn't receive any of the packets
+    stream
+        .receive_rtcp(vec![Box::new(TransportLayerCc {
+            media_ssrc: 1,
+            base_sequence_number: 0,
+            packet_status_count: 20,
+            packet_chunks: vec![PacketStatusChunk::RunLengthChunk(RunLengthChunk {
+                type_tcc: StatusChunkTypeTcc::RunLengthChunk,
+                packet_status_symbol: SymbolTypeTcc::PacketNotReceived,
+                run_length: 20,
+            })],
+            ..Default::default()
+        })])
+        .await;
+    let pkts = timeout_or_fail(Duration::from_millis(10), stream.read_rtcp())
+        .await
+        .expect("A feedback")?;
+    assert_eq!(pkts.len(), 1);
+
+    timeout_or_fail(Duration::from_millis(10), target_bitrate_rx.changed())
+        .await
+        .expect("target bitrate changed");
+    let target_bitrate = *target_bitrate_rx.borrow();
+    assert!(target_bitrate < 300_000, "{target_bitrate}");
+    assert!(target_bitrate >= 30_000, "{target_bitrate}");
+    assert_eq!(estimator.target_bitrate(), target_bitrate);
+
+    let stats = estimator.stats();
+    assert_eq!(stats.target_bitrate, target_bitrate);
+    assert!(stats.loss_ratio > 0.9, "{}", stats.loss_ratio);
+
+    stream.close().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_send_side_bwe_respects_bitrate_limits() -> Result<()> {
+    let builder = SendSideBwe::builder()
+        .with_initial_bitrate(1_000_000)
+        .with_min_bitrate(200_000)
+        .with_max_bitrate(500_000);
+    let (stream, estimator) = new_stream(builder).await?;
+    assert_eq!(estimator.target_bitrate(), 500_000);
+
+    stream.close().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_send_side_bwe_without_transport_cc() -> Result<()> {
+    let icpr = SendSideBwe::builder().build("")?;
+    let stream = MockStream::new(
+        &StreamInfo {
+            ssrc: 1,
+            ..Default::default()
+        },
+        icpr,
+    )
+    .await;
+
+    // Packets without the header extension are passed through untouched
+    stream
+        .write_rtp(&rtp::packet::Packet {
+            header: rtp::header::Header {
+                ssrc: 1,
+                sequence_number: 5,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await?;
+    let p = timeout_or_fail(Duration::from_millis(10), stream.written_rtp())
+        .await
+        .expect("A packet");
+    assert_eq!(p.header.sequence_number, 5);
+
+    stream.close().await?;
+    Ok(())
+}
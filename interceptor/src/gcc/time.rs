@@ -132,8 +132,12 @@ impl std::ops::Sub<Timestamp> for Timestamp {
     type Output = TimeDelta;
 
     fn sub(self, rhs: Timestamp) -> Self::Output {
-        Self::Output {
-            value: self.value - rhs.value,
+        if self.is_plus_infinity() || rhs.is_minus_infinity() {
+            Self::Output::plus_infinity()
+        } else if self.is_minus_infinity() || rhs.is_plus_infinity() {
+            Self::Output::minus_infinity()
+        } else {
+            Self::Output::from_micros(self.us() - rhs.us())
         }
     }
 }
@@ -142,8 +146,12 @@ impl std::ops::Add for TimeDelta {
     type Output = Self;
 
     fn add(self, rhs: TimeDelta) -> Self::Output {
-        Self {
-            value: self.value + rhs.value,
+        if self.is_plus_infinity() || rhs.is_plus_infinity() {
+            Self::plus_infinity()
+        } else if self.is_minus_infinity() || rhs.is_minus_infinity() {
+            Self::minus_infinity()
+        } else {
+            Self::from_micros(self.us() + rhs.us())
         }
     }
 }
@@ -152,8 +160,12 @@ impl std::ops::Sub for TimeDelta {
     type Output = Self;
 
     fn sub(self, rhs: TimeDelta) -> Self::Output {
-        Self {
-            value: self.value - rhs.value,
+        if self.is_plus_infinity() || rhs.is_minus_infinity() {
+            Self::plus_infinity()
+        } else if self.is_minus_infinity() || rhs.is_plus_infinity() {
+            Self::minus_infinity()
+        } else {
+            Self::from_micros(self.us() - rhs.us())
         }
     }
 }
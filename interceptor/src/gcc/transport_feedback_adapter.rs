@@ -0,0 +1,308 @@
+use std::collections::BTreeMap;
+
+use rtcp::transport_feedbacks::transport_layer_cc::{
+    PacketStatusChunk, SymbolTypeTcc, TransportLayerCc,
+};
+
+use super::{
+    data_rate::DataSize,
+    network_types::{PacketResult, SentPacket, TransportPacketsFeedback},
+    time::{TimeDelta, Timestamp},
+};
+
+const SEND_TIME_HISTORY_WINDOW: TimeDelta = TimeDelta::from_seconds(60);
+
+/// The reference time of a feedback is expressed in multiples of 64ms.
+const BASE_TIME_TICK: TimeDelta = TimeDelta::from_millis(64);
+/// The reference time is a 24 bit field and wraps around after this long.
+const BASE_TIME_WRAP_PERIOD: TimeDelta = TimeDelta::from_micros(64_000 * (1 << 24));
+
+/// Unwraps 16 bit transport wide sequence numbers into monotonic 64 bit ones.
+#[derive(Default)]
+struct SequenceNumberUnwrapper {
+    last_unwrapped: Option<i64>,
+}
+
+impl SequenceNumberUnwrapper {
+    fn unwrap(&mut self, value: u16) -> i64 {
+        let unwrapped = match self.last_unwrapped {
+            Some(last) => last + value.wrapping_sub(last as u16) as i16 as i64,
+            None => value as i64,
+        };
+        self.last_unwrapped = Some(unwrapped);
+        unwrapped
+    }
+}
+
+/// Matches transport wide congestion control feedback with the history of sent packets.
+pub struct TransportFeedbackAdapter {
+    seq_num_unwrapper: SequenceNumberUnwrapper,
+    history: BTreeMap<i64, SentPacket>,
+
+    last_timestamp: Option<TimeDelta>,
+    current_offset: Timestamp,
+}
+
+impl TransportFeedbackAdapter {
+    pub fn new() -> Self {
+        Self {
+            seq_num_unwrapper: SequenceNumberUnwrapper::default(),
+            history: BTreeMap::new(),
+            last_timestamp: None,
+            current_offset: Timestamp::minus_infinity(),
+        }
+    }
+
+    /// Records a packet carrying the transport wide sequence number `sequence_number`.
+    pub fn add_packet(
+        &mut self,
+        sequence_number: u16,
+        size: DataSize,
+        audio: bool,
+        send_time: Timestamp,
+    ) {
+        let sequence_number = self.seq_num_unwrapper.unwrap(sequence_number);
+
+        while let Some((&oldest, packet)) = self.history.iter().next() {
+            if send_time - packet.send_time > SEND_TIME_HISTORY_WINDOW {
+                self.history.remove(&oldest);
+            } else {
+                break;
+            }
+        }
+
+        self.history.insert(
+            sequence_number,
+            SentPacket {
+                send_time,
+                size,
+                audio,
+                sequence_number,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Builds the feedback for the packets reported by `feedback`, which was received at
+    /// `feedback_receive_time`. Returns `None` if none of them were sent by us.
+    pub fn process_transport_feedback(
+        &mut self,
+        feedback: &TransportLayerCc,
+        feedback_receive_time: Timestamp,
+    ) -> Option<TransportPacketsFeedback> {
+        let base_time = BASE_TIME_TICK * (feedback.reference_time & 0xff_ffff) as f64;
+        match self.last_timestamp {
+            None => self.current_offset = feedback_receive_time,
+            Some(last_timestamp) => {
+                let mut delta = base_time - last_timestamp;
+                // Pick the delta closest to zero to handle wrap arounds of the reference time.
+                if delta.us() > BASE_TIME_WRAP_PERIOD.us() / 2 {
+                    delta = delta - BASE_TIME_WRAP_PERIOD;
+                } else if delta.us() < -BASE_TIME_WRAP_PERIOD.us() / 2 {
+                    delta = delta + BASE_TIME_WRAP_PERIOD;
+                }
+
+                if self.current_offset + delta < Timestamp::zero() {
+                    log::warn!("Unexpected feedback timestamp received.");
+                    self.current_offset = feedback_receive_time;
+                } else {
+                    self.current_offset += delta;
+                }
+            }
+        }
+        self.last_timestamp = Some(base_time);
+
+        let mut packet_feedbacks = vec![];
+        let mut recv_deltas = feedback.recv_deltas.iter();
+        let mut offset_us = 0i64;
+        let mut failed_lookups = 0;
+        for (i, symbol) in packet_symbols(feedback).enumerate() {
+            let receive_time = match symbol {
+                SymbolTypeTcc::PacketReceivedSmallDelta
+                | SymbolTypeTcc::PacketReceivedLargeDelta => match recv_deltas.next() {
+                    Some(recv_delta) => {
+                        offset_us += recv_delta.delta;
+                        Some(self.current_offset + TimeDelta::from_micros(offset_us))
+                    }
+                    None => break,
+                },
+                SymbolTypeTcc::PacketNotReceived => None,
+                // Without a delta the packet can't be used for the estimation.
+                SymbolTypeTcc::PacketReceivedWithoutDelta => continue,
+            };
+
+            let sequence_number = self
+                .seq_num_unwrapper
+                .unwrap(feedback.base_sequence_number.wrapping_add(i as u16));
+            let sent_packet = match receive_time {
+                // Lost packets are not removed from history because they might be reported as
+                // received by a later feedback.
+                Some(_) => self.history.remove(&sequence_number),
+                None => self.history.get(&sequence_number).cloned(),
+            };
+            let sent_packet = match sent_packet {
+                Some(sent_packet) => sent_packet,
+                None => {
+                    failed_lookups += 1;
+                    continue;
+                }
+            };
+
+            packet_feedbacks.push(PacketResult {
+                sent_packet,
+                receive_time: receive_time.unwrap_or_else(Timestamp::plus_infinity),
+            });
+        }
+
+        if failed_lookups > 0 {
+            log::debug!(
+                "Failed to lookup send time for {} packet(s). Send time history too small?",
+                failed_lookups
+            );
+        }
+
+        if packet_feedbacks.is_empty() {
+            return None;
+        }
+
+        Some(TransportPacketsFeedback {
+            feedback_time: feedback_receive_time,
+            packet_feedbacks,
+            ..Default::default()
+        })
+    }
+}
+
+/// Iterates over the status symbols of the first `packet_status_count` packets of `feedback`.
+fn packet_symbols(feedback: &TransportLayerCc) -> impl Iterator<Item = SymbolTypeTcc> + '_ {
+    feedback
+        .packet_chunks
+        .iter()
+        .flat_map(|chunk| -> Box<dyn Iterator<Item = SymbolTypeTcc> + '_> {
+            match chunk {
+                PacketStatusChunk::RunLengthChunk(chunk) => {
+                    Box::new((0..chunk.run_length).map(move |_| chunk.packet_status_symbol))
+                }
+                PacketStatusChunk::StatusVectorChunk(chunk) => {
+                    Box::new(chunk.symbol_list.iter().copied())
+                }
+            }
+        })
+        .take(feedback.packet_status_count as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use rtcp::transport_feedbacks::transport_layer_cc::{
+        RecvDelta, RunLengthChunk, StatusChunkTypeTcc, StatusVectorChunk, SymbolSizeTypeTcc,
+    };
+
+    use super::*;
+
+    fn feedback(base_sequence_number: u16, reference_time: u32) -> TransportLayerCc {
+        // Packets base..base+3 received 1ms apart, base+3 lost, base+4 received.
+        TransportLayerCc {
+            base_sequence_number,
+            packet_status_count: 5,
+            reference_time,
+            packet_chunks: vec![
+                PacketStatusChunk::RunLengthChunk(RunLengthChunk {
+                    type_tcc: StatusChunkTypeTcc::RunLengthChunk,
+                    packet_status_symbol: SymbolTypeTcc::PacketReceivedSmallDelta,
+                    run_length: 3,
+                }),
+                PacketStatusChunk::StatusVectorChunk(StatusVectorChunk {
+                    type_tcc: StatusChunkTypeTcc::StatusVectorChunk,
+                    symbol_size: SymbolSizeTypeTcc::OneBit,
+                    symbol_list: vec![
+                        SymbolTypeTcc::PacketNotReceived,
+                        SymbolTypeTcc::PacketReceivedSmallDelta,
+                        // Padding beyond packet_status_count
+                        SymbolTypeTcc::PacketNotReceived,
+                        SymbolTypeTcc::PacketNotReceived,
+                    ],
+                }),
+            ],
+            recv_deltas: (0..4)
+                .map(|_| RecvDelta {
+                    type_tcc_packet: SymbolTypeTcc::PacketReceivedSmallDelta,
+                    delta: 1000,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unwraps_sequence_numbers() {
+        let mut unwrapper = SequenceNumberUnwrapper::default();
+        assert_eq!(unwrapper.unwrap(65534), 65534);
+        assert_eq!(unwrapper.unwrap(1), 65537);
+        assert_eq!(unwrapper.unwrap(65535), 65535);
+        assert_eq!(unwrapper.unwrap(3), 65539);
+    }
+
+    #[test]
+    fn matches_feedback_with_sent_packets() {
+        let mut adapter = TransportFeedbackAdapter::new();
+        for i in 0..5u16 {
+            adapter.add_packet(
+                65534u16.wrapping_add(i),
+                DataSize::from_bytes(1000 + i as i64),
+                false,
+                Timestamp::from_millis(10 * i as i64),
+            );
+        }
+
+        let feedback_time = Timestamp::from_millis(200);
+        let report = adapter
+            .process_transport_feedback(&feedback(65534, 100), feedback_time)
+            .expect("should match sent packets");
+        assert_eq!(report.feedback_time, feedback_time);
+        assert_eq!(report.packet_feedbacks.len(), 5);
+
+        let received = report.received_with_send_info();
+        assert_eq!(received.len(), 4);
+        for (i, packet) in received.iter().enumerate().take(3) {
+            assert_eq!(packet.sent_packet.size.bytes(), 1000 + i as i64);
+            assert_eq!(
+                packet.receive_time,
+                feedback_time + TimeDelta::from_millis(1 + i as i64)
+            );
+        }
+        assert_eq!(received[3].sent_packet.size.bytes(), 1004);
+        assert_eq!(
+            received[3].receive_time,
+            feedback_time + TimeDelta::from_millis(4)
+        );
+
+        let lost = report.lost_with_send_info();
+        assert_eq!(lost.len(), 1);
+        assert_eq!(lost[0].sent_packet.size.bytes(), 1003);
+
+        // The following feedback is offset by the change of its reference time.
+        adapter.add_packet(
+            4,
+            DataSize::from_bytes(1200),
+            false,
+            Timestamp::from_millis(300),
+        );
+        let report = adapter
+            .process_transport_feedback(&feedback(4, 102), Timestamp::from_millis(400))
+            .expect("should match sent packets");
+        let received = report.received_with_send_info();
+        assert_eq!(received.len(), 1);
+        assert_eq!(
+            received[0].receive_time,
+            feedback_time + TimeDelta::from_millis(2 * 64 + 1)
+        );
+    }
+
+    #[test]
+    fn ignores_unknown_packets() {
+        let mut adapter = TransportFeedbackAdapter::new();
+        assert!(adapter
+            .process_transport_feedback(&feedback(10, 0), Timestamp::from_millis(100))
+            .is_none());
+    }
+}
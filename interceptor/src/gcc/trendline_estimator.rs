@@ -39,7 +39,7 @@ pub struct TrendlineEstimator {
     overuse_counter: i32,
     hypothesis: BandwidthUsage,
     hypothesis_predicted: BandwidthUsage,
-    network_state_predictor: Option<Box<dyn NetworkStatePredictor>>,
+    network_state_predictor: Option<Box<dyn NetworkStatePredictor + Send>>,
 }
 
 fn linear_fit_slope(packets: &VecDeque<PacketTiming>) -> Option<f64> {
@@ -112,7 +112,7 @@ fn compute_slope_cap(
 impl TrendlineEstimator {
     pub fn new(
         settings: TrendlineEstimatorSettings,
-        network_state_predictor: Option<Box<dyn NetworkStatePredictor>>,
+        network_state_predictor: Option<Box<dyn NetworkStatePredictor + Send>>,
     ) -> Self {
         // ```
         // if self.delay_hist.len() > self.window_size {
@@ -10,8 +10,10 @@ use async_trait::async_trait;
 use error::Result;
 use stream_info::StreamInfo;
 
+pub mod ccfb;
 pub mod chain;
 mod error;
+pub mod flexfec;
 pub mod gcc;
 pub mod mock;
 pub mod nack;
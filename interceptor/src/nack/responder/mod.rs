@@ -77,6 +77,7 @@ impl ResponderInternal {
                     let stream3 = Arc::clone(&stream2);
                     Box::pin(async move {
                         if let Some(p) = stream3.get(seq).await {
+                            let p = stream3.retransmission(p);
                             let a = Attributes::new();
                             if let Err(err) = stream3.next_rtp_writer.write(&p, &a).await {
                                 log::warn!("failed resending nacked packet: {}", err);
@@ -168,7 +169,7 @@ impl Interceptor for Responder {
             return writer;
         }
 
-        let stream = Arc::new(ResponderStream::new(self.internal.log2_size, writer));
+        let stream = Arc::new(ResponderStream::new(self.internal.log2_size, writer, info));
         {
             let mut streams = self.internal.streams.lock().await;
             streams.insert(info.ssrc, Arc::clone(&stream));
@@ -1,12 +1,20 @@
+use std::sync::atomic::{AtomicU16, Ordering};
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use bytes::{BufMut, BytesMut};
 use tokio::sync::Mutex;
 
 use crate::error::Result;
 use crate::nack::UINT16SIZE_HALF;
+use crate::stream_info::StreamInfo;
 use crate::{Attributes, RTPWriter};
 
+const SDES_MID_URI: &str = "urn:ietf:params:rtp-hdrext:sdes:mid";
+const SDES_RTP_STREAM_ID_URI: &str = "urn:ietf:params:rtp-hdrext:sdes:rtp-stream-id";
+const SDES_REPAIRED_RTP_STREAM_ID_URI: &str =
+    "urn:ietf:params:rtp-hdrext:sdes:repaired-rtp-stream-id";
+
 struct ResponderStreamInternal {
     packets: Vec<Option<rtp::packet::Packet>>,
     size: u16,
@@ -65,16 +73,78 @@ impl ResponderStreamInternal {
 pub(super) struct ResponderStream {
     internal: Mutex<ResponderStreamInternal>,
     pub(super) next_rtp_writer: Arc<dyn RTPWriter + Send + Sync>,
+
+    rtx_ssrc: u32,
+    rtx_payload_type: u8,
+    rtx_sequence_number: AtomicU16,
+
+    /// Header extension ids of the mid, of the rid of the stream and of the rid it
+    /// repairs, 0 if not negotiated
+    mid_id: u8,
+    rid_id: u8,
+    repaired_rid_id: u8,
 }
 
 impl ResponderStream {
-    pub(super) fn new(log2_size: u8, writer: Arc<dyn RTPWriter + Send + Sync>) -> Self {
+    pub(super) fn new(
+        log2_size: u8,
+        writer: Arc<dyn RTPWriter + Send + Sync>,
+        info: &StreamInfo,
+    ) -> Self {
         ResponderStream {
             internal: Mutex::new(ResponderStreamInternal::new(log2_size)),
             next_rtp_writer: writer,
+
+            rtx_ssrc: info.ssrc_retransmission,
+            rtx_payload_type: info.payload_type_retransmission,
+            rtx_sequence_number: AtomicU16::new(rand::random::<u16>()),
+
+            mid_id: header_extension_id(info, SDES_MID_URI),
+            rid_id: header_extension_id(info, SDES_RTP_STREAM_ID_URI),
+            repaired_rid_id: header_extension_id(info, SDES_REPAIRED_RTP_STREAM_ID_URI),
         }
     }
 
+    /// retransmission returns the packet to resend for a nacked packet. If an RTX stream
+    /// is negotiated it is sent on the RTX stream with its original sequence number
+    /// prepended to the payload, as described in RFC 4588 section 4. The rid of a simulcast
+    /// stream is sent as the repaired rid of the RTX stream, as described in RFC 8852.
+    ///
+    /// Only the mid and repaired rid header extensions are kept, the others, like the
+    /// transport-wide sequence number, describe the original packet.
+    pub(super) fn retransmission(&self, mut pkt: rtp::packet::Packet) -> rtp::packet::Packet {
+        if self.rtx_ssrc == 0 {
+            return pkt;
+        }
+
+        let rid = if self.rid_id != 0 {
+            pkt.header.get_extension(self.rid_id)
+        } else {
+            None
+        };
+        for id in pkt.header.get_extension_ids() {
+            if self.mid_id == 0 || id != self.mid_id {
+                let _ = pkt.header.del_extension(id);
+            }
+        }
+        if let Some(rid) = rid {
+            if self.repaired_rid_id != 0 {
+                let _ = pkt.header.set_extension(self.repaired_rid_id, rid);
+            }
+        }
+
+        let mut payload = BytesMut::with_capacity(2 + pkt.payload.len());
+        payload.put_u16(pkt.header.sequence_number);
+        payload.put_slice(&pkt.payload);
+
+        pkt.header.ssrc = self.rtx_ssrc;
+        pkt.header.payload_type = self.rtx_payload_type;
+        pkt.header.sequence_number = self.rtx_sequence_number.fetch_add(1, Ordering::SeqCst);
+        pkt.header.padding = false;
+        pkt.payload = payload.freeze();
+        pkt
+    }
+
     async fn add(&self, pkt: &rtp::packet::Packet) {
         let mut internal = self.internal.lock().await;
         internal.add(pkt);
@@ -86,6 +156,13 @@ impl ResponderStream {
     }
 }
 
+fn header_extension_id(info: &StreamInfo, uri: &str) -> u8 {
+    info.rtp_header_extensions
+        .iter()
+        .find(|ext| ext.uri == uri)
+        .map_or(0, |ext| ext.id as u8)
+}
+
 /// RTPWriter is used by Interceptor.bind_local_stream.
 #[async_trait]
 impl RTPWriter for ResponderStream {
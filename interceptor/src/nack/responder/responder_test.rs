@@ -1,9 +1,10 @@
+use bytes::Bytes;
 use rtcp::transport_feedbacks::transport_layer_nack::{NackPair, TransportLayerNack};
 use tokio::time::Duration;
 
 use super::*;
 use crate::mock::mock_stream::MockStream;
-use crate::stream_info::RTCPFeedback;
+use crate::stream_info::{RTCPFeedback, RTPHeaderExtension};
 use crate::test::timeout_or_fail;
 
 #[tokio::test]
@@ -74,3 +75,170 @@ async fn test_responder_interceptor() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_responder_interceptor_rtx() -> Result<()> {
+    let icpr: Arc<dyn Interceptor + Send + Sync> =
+        Responder::builder().with_log2_size(3).build("")?;
+
+    let stream = MockStream::new(
+        &StreamInfo {
+            ssrc: 1,
+            payload_type: 96,
+            ssrc_retransmission: 5,
+            payload_type_retransmission: 97,
+            rtcp_feedback: vec![RTCPFeedback {
+                typ: "nack".to_owned(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        },
+        icpr,
+    )
+    .await;
+
+    for seq_num in [10, 11, 12] {
+        stream
+            .write_rtp(&rtp::packet::Packet {
+                header: rtp::header::Header {
+                    ssrc: 1,
+                    payload_type: 96,
+                    sequence_number: seq_num,
+                    ..Default::default()
+                },
+                payload: vec![0xAA, 0xBB].into(),
+            })
+            .await?;
+
+        let p = timeout_or_fail(Duration::from_millis(10), stream.written_rtp())
+            .await
+            .expect("A packet");
+        assert_eq!(p.header.sequence_number, seq_num);
+    }
+
+    stream
+        .receive_rtcp(vec![Box::new(TransportLayerNack {
+            media_ssrc: 1,
+            sender_ssrc: 2,
+            nacks: vec![NackPair {
+                packet_id: 10,
+                lost_packets: 0b10,
+            }], // sequence numbers: 10, 12
+        })])
+        .await;
+
+    let mut rtx_seq_num = None;
+    for seq_num in [10u16, 12] {
+        let p = timeout_or_fail(Duration::from_millis(50), stream.written_rtp())
+            .await
+            .expect("A retransmission");
+        assert_eq!(p.header.ssrc, 5, "retransmission must use the RTX SSRC");
+        assert_eq!(p.header.payload_type, 97);
+        let osn = seq_num.to_be_bytes();
+        assert_eq!(&p.payload[..], &[osn[0], osn[1], 0xAA, 0xBB]);
+
+        // RTX packets have their own sequence numbers
+        if let Some(prev) = rtx_seq_num {
+            assert_eq!(p.header.sequence_number, u16::wrapping_add(prev, 1));
+        }
+        rtx_seq_num = Some(p.header.sequence_number);
+    }
+
+    stream.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_responder_interceptor_rtx_repaired_rid() -> Result<()> {
+    let icpr: Arc<dyn Interceptor + Send + Sync> =
+        Responder::builder().with_log2_size(3).build("")?;
+
+    let stream = MockStream::new(
+        &StreamInfo {
+            ssrc: 1,
+            payload_type: 96,
+            ssrc_retransmission: 5,
+            payload_type_retransmission: 97,
+            rtp_header_extensions: vec![
+                RTPHeaderExtension {
+                    uri: "urn:ietf:params:rtp-hdrext:sdes:mid".to_owned(),
+                    id: 1,
+                },
+                RTPHeaderExtension {
+                    uri: "urn:ietf:params:rtp-hdrext:sdes:rtp-stream-id".to_owned(),
+                    id: 2,
+                },
+                RTPHeaderExtension {
+                    uri: "urn:ietf:params:rtp-hdrext:sdes:repaired-rtp-stream-id".to_owned(),
+                    id: 3,
+                },
+                RTPHeaderExtension {
+                    uri:
+                        "http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01"
+                            .to_owned(),
+                    id: 4,
+                },
+            ],
+            rtcp_feedback: vec![RTCPFeedback {
+                typ: "nack".to_owned(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        },
+        icpr,
+    )
+    .await;
+
+    let mut header = rtp::header::Header {
+        ssrc: 1,
+        payload_type: 96,
+        sequence_number: 10,
+        ..Default::default()
+    };
+    header.set_extension(1, Bytes::from_static(b"0"))?;
+    header.set_extension(2, Bytes::from_static(b"h"))?;
+    header.set_extension(4, Bytes::from_static(&[0x00, 0x01]))?;
+    stream
+        .write_rtp(&rtp::packet::Packet {
+            header,
+            payload: vec![0xAA].into(),
+        })
+        .await?;
+    let p = timeout_or_fail(Duration::from_millis(10), stream.written_rtp())
+        .await
+        .expect("A packet");
+    assert_eq!(p.header.get_extension(2), Some(Bytes::from_static(b"h")));
+
+    stream
+        .receive_rtcp(vec![Box::new(TransportLayerNack {
+            media_ssrc: 1,
+            sender_ssrc: 2,
+            nacks: vec![NackPair {
+                packet_id: 10,
+                lost_packets: 0,
+            }],
+        })])
+        .await;
+
+    let p = timeout_or_fail(Duration::from_millis(50), stream.written_rtp())
+        .await
+        .expect("A retransmission");
+    assert_eq!(p.header.ssrc, 5);
+    assert_eq!(
+        p.header.get_extension(2),
+        None,
+        "rid must not be sent on RTX"
+    );
+    assert_eq!(p.header.get_extension(3), Some(Bytes::from_static(b"h")));
+    assert_eq!(p.header.get_extension(1), Some(Bytes::from_static(b"0")));
+    assert_eq!(
+        p.header.get_extension(4),
+        None,
+        "transport-cc must not be sent on RTX"
+    );
+
+    stream.close().await?;
+
+    Ok(())
+}
@@ -44,6 +44,8 @@ enum StatsUpdate {
         packets: u64,
         header_bytes: u64,
         payload_bytes: u64,
+        frames: u64,
+        sequence_number: u16,
         last_packet_timestamp: SystemTime,
     },
     /// Stats collected on the sending end(outbound) of an RTP stream.
@@ -51,6 +53,7 @@ enum StatsUpdate {
         packets: u64,
         header_bytes: u64,
         payload_bytes: u64,
+        frames: u64,
         last_packet_timestamp: SystemTime,
     },
     /// Stats collected from received RTCP packets.
@@ -218,25 +221,37 @@ fn handle_stats_update(ssrc_stats: &mut StatsContainer, ssrc: u32, update: Stats
             packets,
             header_bytes,
             payload_bytes,
+            frames,
+            sequence_number,
             last_packet_timestamp,
         } => {
             let stats = ssrc_stats.get_or_create_inbound_stream_stats(ssrc);
 
-            stats
-                .rtp_stats
-                .update(header_bytes, payload_bytes, packets, last_packet_timestamp);
+            stats.rtp_stats.update(
+                header_bytes,
+                payload_bytes,
+                packets,
+                frames,
+                last_packet_timestamp,
+            );
+            stats.record_sequence_number(sequence_number);
             stats.mark_updated();
         }
         StatsUpdate::OutboundRTP {
             packets,
             header_bytes,
             payload_bytes,
+            frames,
             last_packet_timestamp,
         } => {
             let stats = ssrc_stats.get_or_create_outbound_stream_stats(ssrc);
-            stats
-                .rtp_stats
-                .update(header_bytes, payload_bytes, packets, last_packet_timestamp);
+            stats.rtp_stats.update(
+                header_bytes,
+                payload_bytes,
+                packets,
+                frames,
+                last_packet_timestamp,
+            );
             stats.mark_updated();
         }
         StatsUpdate::InboundRTCP {
@@ -737,6 +752,8 @@ impl RTPReader for RTPReadRecorder {
                     packets: 1,
                     header_bytes: pkt.header.marshal_size() as u64,
                     payload_bytes: pkt.payload.len() as u64,
+                    frames: pkt.header.marker as u64,
+                    sequence_number: pkt.header.sequence_number,
                     last_packet_timestamp: SystemTime::now(),
                 },
             })
@@ -777,6 +794,7 @@ impl RTPWriter for RTPWriteRecorder {
                     packets: 1,
                     header_bytes: pkt.header.marshal_size() as u64,
                     payload_bytes: pkt.payload.len() as u64,
+                    frames: pkt.header.marker as u64,
                     last_packet_timestamp: SystemTime::now(),
                 },
             })
@@ -904,6 +922,7 @@ mod test {
             .write_rtp(&rtp::packet::Packet {
                 header: rtp::header::Header {
                     ssrc: 234567,
+                    marker: true,
                     ..Default::default()
                 },
                 payload: Bytes::from_static(&[0x13, 0x37]),
@@ -925,6 +944,51 @@ mod test {
         assert_eq!(send_snapshot.packets_sent(), 2);
         assert_eq!(send_snapshot.header_bytes_sent(), 24);
         assert_eq!(send_snapshot.payload_bytes_sent(), 10);
+        assert_eq!(send_snapshot.frames_sent(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stats_interceptor_rtp_packets_lost() -> Result<()> {
+        let icpr: Arc<_> = Arc::new(StatsInterceptor::new("Hello".to_owned()));
+
+        let recv_stream = MockStream::new(
+            &StreamInfo {
+                ssrc: 123456,
+                ..Default::default()
+            },
+            icpr.clone(),
+        )
+        .await;
+
+        // Wraps around, 0 and 1 are lost and 1 is received late, after 2.
+        for (sequence_number, marker) in [(65534, false), (65535, true), (2, true), (1, false)] {
+            recv_stream
+                .receive_rtp(rtp::packet::Packet {
+                    header: rtp::header::Header {
+                        ssrc: 123456,
+                        sequence_number,
+                        marker,
+                        ..Default::default()
+                    },
+                    payload: Bytes::from_static(b"\xde\xad\xbe\xef"),
+                })
+                .await;
+
+            let _ = recv_stream
+                .read_rtp()
+                .await
+                .expect("After calling receive_rtp read_rtp should return Some")?;
+        }
+
+        let snapshots = icpr.fetch_inbound_stats(vec![123456]).await;
+        let recv_snapshot = snapshots[0]
+            .as_ref()
+            .expect("Stats should exist for ssrc: 123456");
+        assert_eq!(recv_snapshot.packets_received(), 4);
+        assert_eq!(recv_snapshot.packets_lost(), 1);
+        assert_eq!(recv_snapshot.frames_received(), 2);
 
         Ok(())
     }
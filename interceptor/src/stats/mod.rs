@@ -33,6 +33,13 @@ mod inbound {
         /// The last time any stats where update, used for garbage collection to remove obsolete stats.
         last_update: Instant,
 
+        /// The extended sequence number of the first packet received. [`None`] before the first
+        /// packet is received.
+        base_ext_seq_num: Option<u64>,
+
+        /// The highest extended sequence number received.
+        highest_ext_seq_num: u64,
+
         /// The number of packets sent as reported in the latest SR from the remote.
         remote_packets_sent: u32,
 
@@ -59,6 +66,8 @@ mod inbound {
                 rtp_stats: RTPStats::default(),
                 rtcp_stats: RTCPStats::default(),
                 last_update: Instant::now(),
+                base_ext_seq_num: None,
+                highest_ext_seq_num: 0,
                 remote_packets_sent: 0,
                 remote_bytes_sent: 0,
                 remote_reports_sent: 0,
@@ -82,6 +91,35 @@ mod inbound {
             self.last_update.elapsed()
         }
 
+        /// Records the sequence number of a received packet, extending it with the number of
+        /// sequence number cycles as described in [RFC3550 A.1](https://www.rfc-editor.org/rfc/rfc3550#appendix-A.1).
+        pub(super) fn record_sequence_number(&mut self, seq_num: u16) {
+            let Some(base) = self.base_ext_seq_num else {
+                // Start far enough from zero that reordered packets before the first one
+                // still have a valid extended sequence number.
+                let ext_seq_num = (1 << 16) + seq_num as u64;
+                self.base_ext_seq_num = Some(ext_seq_num);
+                self.highest_ext_seq_num = ext_seq_num;
+                return;
+            };
+
+            let delta = seq_num.wrapping_sub(self.highest_ext_seq_num as u16) as i16;
+            let ext_seq_num = (self.highest_ext_seq_num as i64 + delta as i64) as u64;
+            if ext_seq_num > self.highest_ext_seq_num {
+                self.highest_ext_seq_num = ext_seq_num;
+            } else if ext_seq_num < base {
+                self.base_ext_seq_num = Some(ext_seq_num);
+            }
+        }
+
+        /// The number of packets expected from the sequence numbers received, as described in
+        /// [RFC3550 A.3](https://www.rfc-editor.org/rfc/rfc3550#appendix-A.3).
+        fn packets_expected(&self) -> u64 {
+            self.base_ext_seq_num
+                .map(|base| self.highest_ext_seq_num - base + 1)
+                .unwrap_or(0)
+        }
+
         pub(super) fn record_sender_report(&mut self, packets_sent: u32, bytes_sent: u32) {
             self.remote_reports_sent += 1;
             self.remote_packets_sent = packets_sent;
@@ -110,6 +148,9 @@ mod inbound {
         /// Common RTCP stats derived from inbound and outbound RTCP packets.
         rtcp_stats: RTCPStats,
 
+        /// The number of packets lost, may be negative if packets are duplicated.
+        packets_lost: i64,
+
         /// The number of packets sent as reported in the latest SR from the remote.
         remote_packets_sent: u32,
 
@@ -147,6 +188,17 @@ mod inbound {
             self.rtp_stats.last_packet_timestamp
         }
 
+        /// Packets received with the marker bit set, i.e. complete frames for video.
+        pub fn frames_received(&self) -> u64 {
+            self.rtp_stats.frames
+        }
+
+        /// Packets lost as defined in [RFC3550](https://www.rfc-editor.org/rfc/rfc3550) section 6.4.1,
+        /// negative if more packets than expected were received.
+        pub fn packets_lost(&self) -> i64 {
+            self.packets_lost
+        }
+
         pub fn nacks_sent(&self) -> u64 {
             self.rtcp_stats.nack_count
         }
@@ -188,6 +240,8 @@ mod inbound {
             Self {
                 rtp_stats: stream_stats.rtp_stats.clone(),
                 rtcp_stats: stream_stats.rtcp_stats.clone(),
+                packets_lost: stream_stats.packets_expected() as i64
+                    - stream_stats.rtp_stats.packets as i64,
                 remote_packets_sent: stream_stats.remote_packets_sent,
                 remote_bytes_sent: stream_stats.remote_bytes_sent,
                 remote_reports_sent: stream_stats.remote_reports_sent,
@@ -383,6 +437,11 @@ mod outbound {
             self.rtp_stats.last_packet_timestamp
         }
 
+        /// Packets sent with the marker bit set, i.e. complete frames for video.
+        pub fn frames_sent(&self) -> u64 {
+            self.rtp_stats.frames
+        }
+
         pub fn nacks_received(&self) -> u64 {
             self.rtcp_stats.nack_count
         }
@@ -496,16 +555,28 @@ pub struct RTPStats {
     /// Header bytes sent or received
     header_bytes: u64,
 
+    /// Packets sent or received with the marker bit set. For video this is the number of
+    /// frames, as the marker bit is set on the last packet of each frame.
+    frames: u64,
+
     /// A wall clock timestamp for when the last packet was sent or received encoded as milliseconds since
     /// [`SystemTime::UNIX_EPOCH`].
     last_packet_timestamp: Option<SystemTime>,
 }
 
 impl RTPStats {
-    fn update(&mut self, header_bytes: u64, payload_bytes: u64, packets: u64, now: SystemTime) {
+    fn update(
+        &mut self,
+        header_bytes: u64,
+        payload_bytes: u64,
+        packets: u64,
+        frames: u64,
+        now: SystemTime,
+    ) {
         self.header_bytes += header_bytes;
         self.payload_bytes += payload_bytes;
         self.packets += packets;
+        self.frames += frames;
         self.last_packet_timestamp = Some(now);
     }
 
@@ -521,6 +592,10 @@ impl RTPStats {
         self.packets
     }
 
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
     pub fn last_packet_timestamp(&self) -> Option<SystemTime> {
         self.last_packet_timestamp
     }
@@ -579,11 +654,17 @@ mod test {
             (0, 0, 0),
         );
 
-        stats.update(24, 960, 1, SystemTime::now());
+        stats.update(24, 960, 1, 0, SystemTime::now());
+        stats.update(24, 960, 1, 1, SystemTime::now());
 
         assert_eq!(
-            (stats.header_bytes(), stats.payload_bytes(), stats.packets()),
-            (24, 960, 1),
+            (
+                stats.header_bytes(),
+                stats.payload_bytes(),
+                stats.packets(),
+                stats.frames()
+            ),
+            (48, 1920, 2, 1),
         );
     }
 
@@ -14,6 +14,15 @@ pub struct StreamInfo {
     pub attributes: Attributes,
     pub ssrc: u32,
     pub payload_type: u8,
+    /// SSRC of the RTX (RFC 4588) retransmission stream, 0 if retransmissions
+    /// are sent on the stream itself.
+    pub ssrc_retransmission: u32,
+    /// Payload type of the RTX retransmission stream.
+    pub payload_type_retransmission: u8,
+    /// SSRC of the FlexFEC stream protecting the stream, 0 if it isn't protected.
+    pub ssrc_forward_error_correction: u32,
+    /// Payload type of the FlexFEC stream.
+    pub payload_type_forward_error_correction: u8,
     pub rtp_header_extensions: Vec<RTPHeaderExtension>,
     pub mime_type: String,
     pub clock_rate: u32,
@@ -0,0 +1,210 @@
+use rtp::header::Header;
+
+use super::*;
+
+/// Depacketizer of frames ending with a packet with the marker bit.
+struct FakeDepacketizer;
+
+impl Depacketizer for FakeDepacketizer {
+    fn depacketize(&mut self, b: &Bytes) -> std::result::Result<Bytes, rtp::Error> {
+        Ok(b.clone())
+    }
+
+    fn is_partition_head(&self, _payload: &Bytes) -> bool {
+        true
+    }
+
+    fn is_partition_tail(&self, marker: bool, _payload: &Bytes) -> bool {
+        marker
+    }
+}
+
+const CLOCK_RATE: u32 = 48000;
+/// 20ms frames
+const FRAME_DURATION: u32 = 960;
+
+fn packet(sequence_number: u16, timestamp: u32, marker: bool, payload: &'static [u8]) -> Packet {
+    Packet {
+        header: Header {
+            sequence_number,
+            timestamp,
+            marker,
+            ..Default::default()
+        },
+        payload: Bytes::from_static(payload),
+    }
+}
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+#[test]
+fn test_jitter_buffer_playout_delay() {
+    let mut jb =
+        JitterBuffer::new(FakeDepacketizer, CLOCK_RATE).with_min_delay(Duration::from_millis(40));
+    let start = Instant::now();
+
+    assert!(jb.pop(start).is_none());
+    assert!(jb.next_playout_time().is_none());
+
+    jb.push(packet(10, 1000, true, &[0x01]), start);
+    jb.push(
+        packet(11, 1000 + FRAME_DURATION, true, &[0x02]),
+        start + ms(20),
+    );
+    assert_eq!(jb.next_playout_time(), Some(start + ms(40)));
+    assert!(jb.pop(start + ms(39)).is_none());
+
+    let sample = jb
+        .pop(start + ms(40))
+        .expect("first frame should be emitted");
+    assert_eq!(sample.data, Bytes::from_static(&[0x01]));
+    assert_eq!(sample.packet_timestamp, 1000);
+    assert_eq!(sample.duration, ms(20));
+    assert!(jb.pop(start + ms(40)).is_none());
+
+    let sample = jb
+        .pop(start + ms(60))
+        .expect("second frame should be emitted");
+    assert_eq!(sample.data, Bytes::from_static(&[0x02]));
+    assert_eq!(sample.duration, ms(20));
+
+    let stats = jb.stats();
+    assert_eq!(stats.jitter, Duration::ZERO);
+    assert_eq!(stats.jitter_buffer_emitted_count, 2);
+    assert_eq!(stats.jitter_buffer_delay, ms(80));
+    assert_eq!(stats.jitter_buffer_target_delay, ms(80));
+    assert_eq!(stats.jitter_buffer_minimum_delay, Duration::ZERO);
+    assert_eq!(stats.packets_discarded, 0);
+    assert_eq!(stats.concealment_events, 0);
+}
+
+#[test]
+fn test_jitter_buffer_reorder() {
+    let mut jb =
+        JitterBuffer::new(FakeDepacketizer, CLOCK_RATE).with_min_delay(Duration::from_millis(40));
+    let start = Instant::now();
+
+    // A frame of two packets whose first packet arrives last
+    jb.push(packet(2, FRAME_DURATION, false, &[0x02]), start + ms(20));
+    jb.push(packet(0, 0, true, &[0x00]), start + ms(20));
+    jb.push(packet(3, FRAME_DURATION, true, &[0x03]), start + ms(21));
+    jb.push(packet(1, 0, true, &[0x01]), start + ms(22));
+
+    let sample = jb
+        .pop(start + ms(100))
+        .expect("first frame should be emitted");
+    assert_eq!(sample.data, Bytes::from_static(&[0x00, 0x01]));
+    let sample = jb
+        .pop(start + ms(100))
+        .expect("second frame should be emitted");
+    assert_eq!(sample.data, Bytes::from_static(&[0x02, 0x03]));
+    assert!(jb.pop(start + ms(100)).is_none());
+}
+
+#[test]
+fn test_jitter_buffer_conceal_late_frame() {
+    let mut jb =
+        JitterBuffer::new(FakeDepacketizer, CLOCK_RATE).with_min_delay(Duration::from_millis(40));
+    let start = Instant::now();
+
+    jb.push(packet(0, 0, true, &[0x00]), start);
+    jb.push(packet(1, FRAME_DURATION, true, &[0x01]), start + ms(20));
+    // The frame with sequence number 2 is late and the one with 3 and 4 is incomplete
+    jb.push(
+        packet(3, 3 * FRAME_DURATION, false, &[0x03]),
+        start + ms(60),
+    );
+    jb.push(packet(5, 4 * FRAME_DURATION, true, &[0x05]), start + ms(80));
+
+    let sample = jb
+        .pop(start + ms(60))
+        .expect("first frame should be emitted");
+    assert_eq!(sample.data, Bytes::from_static(&[0x00]));
+    let sample = jb
+        .pop(start + ms(60))
+        .expect("second frame should be emitted");
+    assert_eq!(sample.data, Bytes::from_static(&[0x01]));
+    assert_eq!(sample.duration, ms(20));
+
+    let sample = jb
+        .pop(start + ms(120))
+        .expect("last frame should be emitted");
+    assert_eq!(sample.data, Bytes::from_static(&[0x05]));
+    assert_eq!(sample.prev_dropped_packets, 1);
+    assert_eq!(sample.duration, ms(20));
+
+    // Packets of frames which were played out are discarded
+    jb.push(
+        packet(2, 2 * FRAME_DURATION, true, &[0x02]),
+        start + ms(120),
+    );
+    jb.push(
+        packet(4, 3 * FRAME_DURATION, true, &[0x04]),
+        start + ms(120),
+    );
+    assert!(jb.next_playout_time().is_none());
+
+    let stats = jb.stats();
+    assert_eq!(stats.jitter_buffer_emitted_count, 3);
+    assert_eq!(stats.packets_discarded, 3);
+    assert_eq!(stats.concealment_events, 1);
+    assert_eq!(stats.concealed_samples, 2 * FRAME_DURATION as u64);
+}
+
+#[test]
+fn test_jitter_buffer_adaptive_delay() {
+    let mut jb = JitterBuffer::new(FakeDepacketizer, CLOCK_RATE)
+        .with_min_delay(Duration::from_millis(10))
+        .with_max_delay(Duration::from_millis(100));
+    let start = Instant::now();
+    assert_eq!(jb.target_delay(), ms(10));
+
+    // Frames arrive 10ms early or late
+    let mut emitted = 0;
+    for i in 0..100u16 {
+        let arrival = start + ms(20 * i as u64 + if i % 2 == 0 { 10 } else { 0 });
+        jb.push(
+            packet(i, u32::from(i) * FRAME_DURATION, true, &[0x00]),
+            arrival,
+        );
+        while jb.pop(arrival).is_some() {
+            emitted += 1;
+        }
+    }
+
+    // The jitter converges to 10ms
+    let stats = jb.stats();
+    assert!(
+        stats.jitter > ms(9) && stats.jitter <= ms(10),
+        "unexpected jitter {:?}",
+        stats.jitter
+    );
+    assert!(jb.target_delay() > ms(27) && jb.target_delay() <= ms(30));
+    assert_eq!(stats.packets_discarded, 0);
+    assert_eq!(stats.jitter_buffer_emitted_count, emitted);
+
+    let jb = jb.with_max_delay(Duration::from_millis(20));
+    assert_eq!(jb.target_delay(), ms(20));
+}
+
+#[test]
+fn test_jitter_buffer_timestamp_wraparound() {
+    let mut jb = JitterBuffer::new(FakeDepacketizer, CLOCK_RATE);
+    let start = Instant::now();
+
+    jb.push(packet(u16::MAX, u32::MAX - 479, true, &[0x00]), start);
+    jb.push(packet(0, 480, true, &[0x01]), start + ms(20));
+
+    let sample = jb
+        .pop(start + ms(20))
+        .expect("first frame should be emitted");
+    assert_eq!(sample.data, Bytes::from_static(&[0x00]));
+    assert_eq!(sample.duration, ms(20));
+    let sample = jb
+        .pop(start + ms(20))
+        .expect("second frame should be emitted");
+    assert_eq!(sample.data, Bytes::from_static(&[0x01]));
+    assert_eq!(sample.packet_timestamp, 480);
+}
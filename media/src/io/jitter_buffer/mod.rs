@@ -0,0 +1,337 @@
+#[cfg(test)]
+mod jitter_buffer_test;
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant, SystemTime};
+
+use bytes::{Bytes, BytesMut};
+use rtp::packet::Packet;
+use rtp::packetizer::Depacketizer;
+
+use crate::Sample;
+
+/// Default upper bound of the playout delay.
+const DEFAULT_MAX_DELAY: Duration = Duration::from_millis(500);
+/// The target delay covers this many times the estimated jitter.
+const JITTER_MULTIPLIER: f64 = 3.0;
+/// Number of buffered frames after which the oldest frames are played out regardless
+/// of their playout time.
+const MAX_FRAMES: usize = 512;
+
+/// JitterBufferStats are the statistics of a [`JitterBuffer`], as defined for
+/// RTCInboundRtpStreamStats.
+/// <https://www.w3.org/TR/webrtc-stats/#dom-rtcinboundrtpstreamstats>
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct JitterBufferStats {
+    /// jitter is the estimated interarrival jitter of the frames.
+    pub jitter: Duration,
+    /// jitter_buffer_delay is the sum of the times the emitted frames spent in the buffer,
+    /// from the arrival of their first packet to their emission.
+    pub jitter_buffer_delay: Duration,
+    /// jitter_buffer_target_delay is the sum of the target delays at the emission of
+    /// each frame.
+    pub jitter_buffer_target_delay: Duration,
+    /// jitter_buffer_minimum_delay is the sum of the target delays at the emission of
+    /// each frame, without the lower bound set by the application.
+    pub jitter_buffer_minimum_delay: Duration,
+    /// jitter_buffer_emitted_count is the number of emitted frames.
+    pub jitter_buffer_emitted_count: u64,
+    /// packets_discarded is the number of packets which arrived after the playout time
+    /// of their frame, or belonged to incomplete frames at their playout time.
+    pub packets_discarded: u64,
+    /// concealment_events is the number of gaps in the emitted frames.
+    pub concealment_events: u64,
+    /// concealed_samples is the duration of the gaps in the emitted frames in RTP
+    /// timestamp units, which are samples for audio.
+    pub concealed_samples: u64,
+}
+
+struct Frame {
+    first_arrival: Instant,
+    /// Packets of the frame keyed by unwrapped sequence number.
+    packets: BTreeMap<i64, Packet>,
+}
+
+/// JitterBuffer reorders the RTP packets of a stream into frames and emits them at their
+/// playout time. The playout delay adapts to the jitter estimated from the arrival times
+/// and RTP timestamps of the frames, frames which are incomplete at their playout time
+/// are concealed by skipping them.
+pub struct JitterBuffer<T: Depacketizer> {
+    depacketizer: T,
+    clock_rate: u32,
+    min_delay: Duration,
+    max_delay: Duration,
+
+    /// Frames keyed by unwrapped RTP timestamp.
+    frames: BTreeMap<i64, Frame>,
+    last_timestamp: Option<(u32, i64)>,
+    last_sequence_number: Option<(u16, i64)>,
+
+    /// Arrival time and timestamp of the frame with the smallest transit time, which
+    /// anchors the playout times.
+    base: Option<(Instant, i64)>,
+    /// Arrival time and timestamp of the first packet of the last new frame.
+    last_arrival: Option<(Instant, i64)>,
+    jitter: f64,
+
+    /// Timestamp of the last frame whose playout time passed.
+    played_out: Option<i64>,
+    /// Timestamp and last sequence number of the last emitted frame.
+    last_emitted: Option<(i64, i64)>,
+    /// Timestamp difference of the last consecutive frames.
+    frame_duration: Option<i64>,
+    dropped_packets: u16,
+
+    stats: JitterBufferStats,
+}
+
+impl<T: Depacketizer> JitterBuffer<T> {
+    /// Constructs a new JitterBuffer with the depacketizer of the codec of the stream and
+    /// its clock rate.
+    pub fn new(depacketizer: T, clock_rate: u32) -> Self {
+        JitterBuffer {
+            depacketizer,
+            clock_rate,
+            min_delay: Duration::ZERO,
+            max_delay: DEFAULT_MAX_DELAY,
+            frames: BTreeMap::new(),
+            last_timestamp: None,
+            last_sequence_number: None,
+            base: None,
+            last_arrival: None,
+            jitter: 0.0,
+            played_out: None,
+            last_emitted: None,
+            frame_duration: None,
+            dropped_packets: 0,
+            stats: JitterBufferStats::default(),
+        }
+    }
+
+    /// with_min_delay sets the lower bound of the playout delay, 0 by default.
+    pub fn with_min_delay(mut self, min_delay: Duration) -> Self {
+        self.min_delay = min_delay;
+        self
+    }
+
+    /// with_max_delay sets the upper bound of the playout delay, 500ms by default.
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// stats returns the statistics of the buffer.
+    pub fn stats(&self) -> JitterBufferStats {
+        JitterBufferStats {
+            jitter: Duration::from_secs_f64(self.jitter),
+            ..self.stats
+        }
+    }
+
+    /// target_delay returns the current playout delay.
+    pub fn target_delay(&self) -> Duration {
+        self.minimum_delay().clamp(self.min_delay, self.max_delay)
+    }
+
+    fn minimum_delay(&self) -> Duration {
+        Duration::from_secs_f64(JITTER_MULTIPLIER * self.jitter)
+    }
+
+    /// Adds a packet which arrived at the given time.
+    pub fn push(&mut self, packet: Packet, arrival: Instant) {
+        let timestamp = unwrap_timestamp(&mut self.last_timestamp, packet.header.timestamp);
+        let sequence_number = unwrap_sequence_number(
+            &mut self.last_sequence_number,
+            packet.header.sequence_number,
+        );
+
+        if self
+            .played_out
+            .map_or(false, |played_out| timestamp <= played_out)
+        {
+            // The playout time of the frame already passed
+            self.discard(1);
+            return;
+        }
+
+        if !self.frames.contains_key(&timestamp) {
+            self.update_jitter(timestamp, arrival);
+        }
+        let frame = self.frames.entry(timestamp).or_insert_with(|| Frame {
+            first_arrival: arrival,
+            packets: BTreeMap::new(),
+        });
+        frame.packets.insert(sequence_number, packet);
+    }
+
+    fn update_jitter(&mut self, timestamp: i64, arrival: Instant) {
+        // Estimation of the interarrival jitter of RFC 3550 Section 6.4.1
+        if let Some((last_arrival, last_timestamp)) = self.last_arrival {
+            let d = signed_secs(arrival, last_arrival)
+                - (timestamp - last_timestamp) as f64 / self.clock_rate as f64;
+            self.jitter += (d.abs() - self.jitter) / 16.0;
+        }
+        self.last_arrival = Some((arrival, timestamp));
+
+        // The frame with the smallest transit time was delayed the least by the network
+        if self.base.map_or(true, |_| {
+            self.expected_arrival(timestamp)
+                .map_or(false, |e| arrival < e)
+        }) {
+            self.base = Some((arrival, timestamp));
+        }
+    }
+
+    /// Time at which the frame would have arrived with the transit time of the base frame.
+    fn expected_arrival(&self, timestamp: i64) -> Option<Instant> {
+        let (base_arrival, base_timestamp) = self.base?;
+        let offset = timestamp - base_timestamp;
+        let duration =
+            Duration::from_secs_f64(offset.unsigned_abs() as f64 / self.clock_rate as f64);
+        if offset >= 0 {
+            base_arrival.checked_add(duration)
+        } else {
+            base_arrival.checked_sub(duration)
+        }
+    }
+
+    fn playout_time(&self, timestamp: i64) -> Option<Instant> {
+        self.expected_arrival(timestamp)?
+            .checked_add(self.target_delay())
+    }
+
+    /// next_playout_time returns the playout time of the next frame, if any.
+    pub fn next_playout_time(&self) -> Option<Instant> {
+        let (timestamp, _) = self.frames.iter().next()?;
+        self.playout_time(*timestamp)
+    }
+
+    /// Returns the next frame if its playout time passed. Incomplete frames are
+    /// skipped at their playout time.
+    pub fn pop(&mut self, now: Instant) -> Option<Sample> {
+        loop {
+            let (&timestamp, _) = self.frames.iter().next()?;
+            if self.frames.len() <= MAX_FRAMES
+                && self.playout_time(timestamp).map_or(false, |t| t > now)
+            {
+                return None;
+            }
+
+            let frame = self.frames.remove(&timestamp)?;
+            self.played_out = Some(timestamp);
+            let count = frame.packets.len() as u64;
+            match self.build_sample(timestamp, &frame) {
+                Some(sample) => {
+                    let target_delay = self.target_delay();
+                    self.stats.jitter_buffer_delay +=
+                        now.saturating_duration_since(frame.first_arrival);
+                    self.stats.jitter_buffer_target_delay += target_delay;
+                    self.stats.jitter_buffer_minimum_delay +=
+                        self.minimum_delay().min(self.max_delay);
+                    self.stats.jitter_buffer_emitted_count += 1;
+                    return Some(sample);
+                }
+                None => self.discard(count),
+            }
+        }
+    }
+
+    fn discard(&mut self, count: u64) {
+        self.stats.packets_discarded += count;
+        self.dropped_packets = self.dropped_packets.saturating_add(count as u16);
+    }
+
+    fn build_sample(&mut self, timestamp: i64, frame: &Frame) -> Option<Sample> {
+        let (first_sequence_number, first) = frame.packets.iter().next()?;
+        let (last_sequence_number, last) = frame.packets.iter().next_back()?;
+        if last_sequence_number - first_sequence_number + 1 != frame.packets.len() as i64
+            || !self.depacketizer.is_partition_head(&first.payload)
+            || !self
+                .depacketizer
+                .is_partition_tail(last.header.marker, &last.payload)
+        {
+            return None;
+        }
+
+        let mut data = BytesMut::new();
+        for packet in frame.packets.values() {
+            data.extend_from_slice(&self.depacketizer.depacketize(&packet.payload).ok()?);
+        }
+
+        // Frames following the previous one without missing packets give the frame duration
+        let follows = |last_sequence_number: i64, frame: &Frame| {
+            frame
+                .packets
+                .iter()
+                .next()
+                .map_or(false, |(s, _)| *s == last_sequence_number + 1)
+        };
+        if let Some((last, last_sequence_number)) = self.last_emitted {
+            if *first_sequence_number == last_sequence_number + 1 {
+                self.frame_duration = Some(timestamp - last);
+            } else {
+                let gap = timestamp - last - self.frame_duration.unwrap_or(0);
+                self.stats.concealment_events += 1;
+                self.stats.concealed_samples += gap.max(0) as u64;
+            }
+        }
+        self.last_emitted = Some((timestamp, *last_sequence_number));
+
+        let duration = match self.frames.iter().next() {
+            Some((next, next_frame)) if follows(*last_sequence_number, next_frame) => {
+                next - timestamp
+            }
+            next => self
+                .frame_duration
+                .or_else(|| next.map(|(next, _)| next - timestamp))
+                .unwrap_or(0),
+        };
+
+        let prev_dropped_packets = self.dropped_packets;
+        self.dropped_packets = 0;
+
+        Some(Sample {
+            data: Bytes::from(data),
+            timestamp: SystemTime::now(),
+            duration: Duration::from_secs_f64(duration as f64 / self.clock_rate as f64),
+            packet_timestamp: first.header.timestamp,
+            prev_dropped_packets,
+            prev_padding_packets: 0,
+        })
+    }
+}
+
+fn unwrap_timestamp(last: &mut Option<(u32, i64)>, timestamp: u32) -> i64 {
+    let unwrapped = match *last {
+        Some((last_timestamp, last_unwrapped)) => {
+            last_unwrapped + i64::from(timestamp.wrapping_sub(last_timestamp) as i32)
+        }
+        None => i64::from(timestamp),
+    };
+    if last.map_or(true, |(_, last_unwrapped)| unwrapped > last_unwrapped) {
+        *last = Some((timestamp, unwrapped));
+    }
+    unwrapped
+}
+
+fn unwrap_sequence_number(last: &mut Option<(u16, i64)>, sequence_number: u16) -> i64 {
+    let unwrapped = match *last {
+        Some((last_sequence_number, last_unwrapped)) => {
+            last_unwrapped + i64::from(sequence_number.wrapping_sub(last_sequence_number) as i16)
+        }
+        None => i64::from(sequence_number),
+    };
+    if last.map_or(true, |(_, last_unwrapped)| unwrapped > last_unwrapped) {
+        *last = Some((sequence_number, unwrapped));
+    }
+    unwrapped
+}
+
+/// Returns a - b in seconds.
+fn signed_secs(a: Instant, b: Instant) -> f64 {
+    if a >= b {
+        (a - b).as_secs_f64()
+    } else {
+        -(b - a).as_secs_f64()
+    }
+}
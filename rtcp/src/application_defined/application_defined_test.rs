@@ -0,0 +1,108 @@
+use bytes::Bytes;
+
+use super::*;
+
+#[test]
+fn test_application_defined_round_trip() -> Result<()> {
+    let tests = vec![
+        (
+            "aligned",
+            ApplicationDefined {
+                sub_type: 1,
+                ssrc: 0x902f9e2e,
+                name: *b"NAME",
+                data: Bytes::from_static(&[1, 2, 3, 4]),
+            },
+            vec![
+                0x81, 0xcc, 0x00, 0x03, // v=2, p=0, subtype=1, PT=204, len=3
+                0x90, 0x2f, 0x9e, 0x2e, // ssrc=0x902f9e2e
+                b'N', b'A', b'M', b'E', // name
+                0x01, 0x02, 0x03, 0x04, // data
+            ],
+        ),
+        (
+            "padded",
+            ApplicationDefined {
+                sub_type: 31,
+                ssrc: 0x902f9e2e,
+                name: *b"test",
+                data: Bytes::from_static(&[1, 2, 3, 4, 5]),
+            },
+            vec![
+                0xbf, 0xcc, 0x00, 0x04, // v=2, p=1, subtype=31, PT=204, len=4
+                0x90, 0x2f, 0x9e, 0x2e, // ssrc=0x902f9e2e
+                b't', b'e', b's', b't', // name
+                0x01, 0x02, 0x03, 0x04, // data
+                0x05, 0x00, 0x00, 0x03, // data, padding
+            ],
+        ),
+        (
+            "no data",
+            ApplicationDefined {
+                sub_type: 0,
+                ssrc: 1,
+                name: *b"none",
+                data: Bytes::new(),
+            },
+            vec![
+                0x80, 0xcc, 0x00, 0x02, // v=2, p=0, subtype=0, PT=204, len=2
+                0x00, 0x00, 0x00, 0x01, // ssrc=1
+                b'n', b'o', b'n', b'e', // name
+            ],
+        ),
+    ];
+
+    for (name, packet, raw) in tests {
+        assert_eq!(packet.marshal()?, Bytes::from(raw.clone()), "{name}");
+
+        let mut data = Bytes::from(raw);
+        let packets = crate::packet::unmarshal(&mut data)?;
+        assert_eq!(
+            packets[0].as_any().downcast_ref::<ApplicationDefined>(),
+            Some(&packet),
+            "{name}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_application_defined_errors() {
+    let too_long_sub_type = ApplicationDefined {
+        sub_type: 32,
+        ..Default::default()
+    };
+    assert_eq!(
+        Error::InvalidHeader,
+        too_long_sub_type.marshal().unwrap_err()
+    );
+
+    let tests: Vec<(&str, Vec<u8>, Error)> = vec![
+        (
+            "too short",
+            vec![0x80, 0xcc, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01],
+            Error::PacketTooShort,
+        ),
+        (
+            "wrong type",
+            vec![
+                0x80, 0xcb, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, b'n', b'o', b'n', b'e',
+            ],
+            Error::WrongType,
+        ),
+        (
+            "invalid padding",
+            vec![
+                0xa0, 0xcc, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, b'n', b'o', b'n', 0x00,
+            ],
+            Error::WrongPadding,
+        ),
+    ];
+
+    for (name, data, want) in tests {
+        let mut raw = Bytes::from(data);
+        let got = ApplicationDefined::unmarshal(&mut raw);
+        assert_eq!(want, got.unwrap_err(), "{name}");
+    }
+}
@@ -0,0 +1,169 @@
+#[cfg(test)]
+mod application_defined_test;
+
+use std::any::Any;
+use std::fmt;
+
+use bytes::{Buf, BufMut, Bytes};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+use crate::header::*;
+use crate::packet::*;
+use crate::util::*;
+
+type Result<T> = std::result::Result<T, util::Error>;
+
+const APP_NAME_LENGTH: usize = 4;
+
+/// ApplicationDefined (APP) packets carry data of applications, identified by
+/// their name and subtype, as specified in https://www.rfc-editor.org/rfc/rfc3550#section-6.7
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct ApplicationDefined {
+    /// Subtype of the packet within the application, 5 bits
+    pub sub_type: u8,
+    /// SSRC or CSRC of the sender
+    pub ssrc: u32,
+    /// Name of the application, four ASCII characters
+    pub name: [u8; APP_NAME_LENGTH],
+    /// Application dependent data. It is padded to 32 bits when marshaled.
+    pub data: Bytes,
+}
+
+impl fmt::Display for ApplicationDefined {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ApplicationDefined {:x} {}/{}: {} bytes",
+            self.ssrc,
+            String::from_utf8_lossy(&self.name),
+            self.sub_type,
+            self.data.len()
+        )
+    }
+}
+
+impl Packet for ApplicationDefined {
+    /// Header returns the Header associated with this packet.
+    fn header(&self) -> Header {
+        Header {
+            padding: get_padding_size(self.raw_size()) != 0,
+            count: self.sub_type,
+            packet_type: PacketType::ApplicationDefined,
+            length: ((self.marshal_size() / 4) - 1) as u16,
+        }
+    }
+
+    /// Destination SSRC returns an array of SSRC values that this packet refers to.
+    fn destination_ssrc(&self) -> Vec<u32> {
+        vec![self.ssrc]
+    }
+
+    fn raw_size(&self) -> usize {
+        HEADER_LENGTH + SSRC_LENGTH + APP_NAME_LENGTH + self.data.len()
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+
+    fn equal(&self, other: &(dyn Packet + Send + Sync)) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<ApplicationDefined>()
+            .map_or(false, |a| self == a)
+    }
+
+    fn cloned(&self) -> Box<dyn Packet + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+impl MarshalSize for ApplicationDefined {
+    fn marshal_size(&self) -> usize {
+        let l = self.raw_size();
+        // align to 32-bit boundary
+        l + get_padding_size(l)
+    }
+}
+
+impl Marshal for ApplicationDefined {
+    /// Marshal encodes the ApplicationDefined packet in binary
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize> {
+        /*
+         *  0                   1                   2                   3
+         *  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+         * +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+         * |V=2|P| subtype |   PT=APP=204  |             length            |
+         * +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+         * |                           SSRC/CSRC                           |
+         * +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+         * |                          name (ASCII)                         |
+         * +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+         * |                   application-dependent data                ...
+         * +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+         */
+        if buf.remaining_mut() < self.marshal_size() {
+            return Err(Error::BufferTooShort.into());
+        }
+
+        let h = self.header();
+        let n = h.marshal_to(buf)?;
+        buf = &mut buf[n..];
+
+        buf.put_u32(self.ssrc);
+        buf.put_slice(&self.name);
+        buf.put(self.data.clone());
+
+        if h.padding {
+            put_padding(buf, self.raw_size());
+        }
+
+        Ok(self.marshal_size())
+    }
+}
+
+impl Unmarshal for ApplicationDefined {
+    /// Unmarshal decodes the ApplicationDefined packet from binary
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        if raw_packet.remaining() < HEADER_LENGTH + SSRC_LENGTH + APP_NAME_LENGTH {
+            return Err(Error::PacketTooShort.into());
+        }
+
+        let h = Header::unmarshal(raw_packet)?;
+        if h.packet_type != PacketType::ApplicationDefined {
+            return Err(Error::WrongType.into());
+        }
+
+        let length = h.length as usize * 4;
+        if raw_packet.remaining() < length {
+            return Err(Error::PacketTooShort.into());
+        }
+        let mut payload = raw_packet.copy_to_bytes(length);
+        if h.padding {
+            let padding = payload.last().copied().unwrap_or_default() as usize;
+            if padding == 0 || padding > payload.len() {
+                return Err(Error::WrongPadding.into());
+            }
+            payload.truncate(payload.len() - padding);
+        }
+        if payload.len() < SSRC_LENGTH + APP_NAME_LENGTH {
+            return Err(Error::PacketTooShort.into());
+        }
+
+        let ssrc = payload.get_u32();
+        let mut name = [0u8; APP_NAME_LENGTH];
+        payload.copy_to_slice(&mut name);
+
+        Ok(ApplicationDefined {
+            sub_type: h.count,
+            ssrc,
+            name,
+            data: payload,
+        })
+    }
+}
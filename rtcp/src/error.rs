@@ -90,6 +90,8 @@ pub enum Error {
     PacketStatusChunkLength,
     #[error("Invalid bitrate")]
     InvalidBitrate,
+    #[error("Invalid measured overhead")]
+    InvalidOverhead,
     #[error("Wrong chunk type")]
     WrongChunkType,
     #[error("Struct contains unexpected member type")]
@@ -41,6 +41,7 @@
 //!     // ...
 //!```
 
+pub mod application_defined;
 pub mod compound_packet;
 mod error;
 pub mod extended_report;
@@ -4,6 +4,7 @@ use std::fmt;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use util::marshal::{Marshal, Unmarshal};
 
+use crate::application_defined::ApplicationDefined;
 use crate::error::{Error, Result};
 use crate::extended_report::ExtendedReport;
 use crate::goodbye::*;
@@ -16,7 +17,10 @@ use crate::raw_packet::*;
 use crate::receiver_report::*;
 use crate::sender_report::*;
 use crate::source_description::*;
+use crate::transport_feedbacks::ccfb::*;
 use crate::transport_feedbacks::rapid_resynchronization_request::*;
+use crate::transport_feedbacks::temporary_maximum_media_stream_bitrate_notification::*;
+use crate::transport_feedbacks::temporary_maximum_media_stream_bitrate_request::*;
 use crate::transport_feedbacks::transport_layer_cc::*;
 use crate::transport_feedbacks::transport_layer_nack::*;
 
@@ -99,11 +103,19 @@ where
         PacketType::ReceiverReport => Box::new(ReceiverReport::unmarshal(&mut in_packet)?),
         PacketType::SourceDescription => Box::new(SourceDescription::unmarshal(&mut in_packet)?),
         PacketType::Goodbye => Box::new(Goodbye::unmarshal(&mut in_packet)?),
+        PacketType::ApplicationDefined => Box::new(ApplicationDefined::unmarshal(&mut in_packet)?),
 
         PacketType::TransportSpecificFeedback => match h.count {
             FORMAT_TLN => Box::new(TransportLayerNack::unmarshal(&mut in_packet)?),
             FORMAT_RRR => Box::new(RapidResynchronizationRequest::unmarshal(&mut in_packet)?),
+            FORMAT_TMMBR => Box::new(TemporaryMaximumMediaStreamBitrateRequest::unmarshal(
+                &mut in_packet,
+            )?),
+            FORMAT_TMMBN => Box::new(TemporaryMaximumMediaStreamBitrateNotification::unmarshal(
+                &mut in_packet,
+            )?),
             FORMAT_TCC => Box::new(TransportLayerCc::unmarshal(&mut in_packet)?),
+            FORMAT_CCFB => Box::new(CongestionControlFeedback::unmarshal(&mut in_packet)?),
             _ => Box::new(RawPacket::unmarshal(&mut in_packet)?),
         },
         PacketType::PayloadSpecificFeedback => match h.count {
@@ -0,0 +1,192 @@
+use bytes::Bytes;
+
+use super::*;
+
+fn example() -> CongestionControlFeedback {
+    CongestionControlFeedback {
+        sender_ssrc: 0x902f9e2e,
+        report_blocks: vec![
+            CcfbReportBlock {
+                media_ssrc: 0x4bc4fcb4,
+                begin_sequence: 0xfffe,
+                metric_blocks: vec![
+                    CcfbMetricBlock {
+                        received: true,
+                        ecn: Ecn::Ect0,
+                        arrival_time_offset: 0x10,
+                    },
+                    CcfbMetricBlock {
+                        received: false,
+                        ..Default::default()
+                    },
+                    CcfbMetricBlock {
+                        received: true,
+                        ecn: Ecn::Ce,
+                        arrival_time_offset: CCFB_ARRIVAL_TIME_OFFSET_UNAVAILABLE,
+                    },
+                ],
+            },
+            CcfbReportBlock {
+                media_ssrc: 0x12345678,
+                begin_sequence: 1,
+                metric_blocks: vec![
+                    CcfbMetricBlock {
+                        received: true,
+                        ecn: Ecn::NotEct,
+                        arrival_time_offset: 1,
+                    },
+                    CcfbMetricBlock {
+                        received: true,
+                        ecn: Ecn::Ect1,
+                        arrival_time_offset: 0,
+                    },
+                ],
+            },
+        ],
+        report_timestamp: 0xdeadbeef,
+    }
+}
+
+const EXAMPLE: &[u8] = &[
+    0x8b, 0xcd, 0x00, 0x09, // v=2, p=0, FMT=11, PT=205, len=9
+    0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+    0x4b, 0xc4, 0xfc, 0xb4, // media=0x4bc4fcb4
+    0xff, 0xfe, 0x00, 0x03, // begin_seq=0xfffe, num_reports=3
+    0xc0, 0x10, // R=1, ECN=ECT(0), ATO=0x10
+    0x00, 0x00, // R=0
+    0xff, 0xff, // R=1, ECN=CE, ATO=unavailable
+    0x00, 0x00, // padding
+    0x12, 0x34, 0x56, 0x78, // media=0x12345678
+    0x00, 0x01, 0x00, 0x02, // begin_seq=1, num_reports=2
+    0x80, 0x01, // R=1, ECN=Not-ECT, ATO=1
+    0xa0, 0x00, // R=1, ECN=ECT(1), ATO=0
+    0xde, 0xad, 0xbe, 0xef, // report timestamp
+];
+
+#[test]
+fn test_ccfb_marshal() -> Result<()> {
+    let packet = example();
+    assert_eq!(packet.marshal()?, Bytes::from_static(EXAMPLE));
+    assert_eq!(packet.destination_ssrc(), vec![0x4bc4fcb4, 0x12345678]);
+
+    // ECN and arrival time offset of lost packets are not sent.
+    let lost = CongestionControlFeedback {
+        report_blocks: vec![CcfbReportBlock {
+            metric_blocks: vec![CcfbMetricBlock {
+                received: false,
+                ecn: Ecn::Ce,
+                arrival_time_offset: 100,
+            }],
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let raw = lost.marshal()?;
+    assert_eq!(&raw[16..18], &[0, 0]);
+
+    let too_many = CongestionControlFeedback {
+        report_blocks: vec![CcfbReportBlock {
+            metric_blocks: vec![CcfbMetricBlock::default(); CCFB_MAX_METRIC_BLOCKS + 1],
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    assert_eq!(
+        Error::TooManyReports,
+        too_many.marshal().unwrap_err(),
+        "report blocks are limited to 16384 packets"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_ccfb_unmarshal() -> Result<()> {
+    let mut raw = Bytes::from_static(EXAMPLE);
+    assert_eq!(CongestionControlFeedback::unmarshal(&mut raw)?, example());
+
+    // Through the packet factory.
+    let mut raw = Bytes::from_static(EXAMPLE);
+    let packets = crate::packet::unmarshal(&mut raw)?;
+    assert_eq!(
+        packets[0]
+            .as_any()
+            .downcast_ref::<CongestionControlFeedback>(),
+        Some(&example())
+    );
+
+    // An empty report.
+    let empty = CongestionControlFeedback {
+        sender_ssrc: 1,
+        report_blocks: vec![],
+        report_timestamp: 2,
+    };
+    let mut raw = empty.marshal()?;
+    assert_eq!(raw.len(), 12);
+    assert_eq!(CongestionControlFeedback::unmarshal(&mut raw)?, empty);
+
+    // Padding added by the sender is ignored.
+    let mut padded = EXAMPLE.to_vec();
+    padded[0] |= 0x20;
+    padded[3] += 1;
+    padded.extend_from_slice(&[0, 0, 0, 4]);
+    let mut raw = Bytes::from(padded);
+    assert_eq!(CongestionControlFeedback::unmarshal(&mut raw)?, example());
+
+    Ok(())
+}
+
+#[test]
+fn test_ccfb_unmarshal_errors() {
+    let tests: Vec<(&str, Vec<u8>, Error)> = vec![
+        (
+            "too short",
+            vec![0x8b, 0xcd, 0x00, 0x01, 0x90, 0x2f, 0x9e, 0x2e],
+            Error::PacketTooShort,
+        ),
+        (
+            "wrong type",
+            vec![
+                0x8f, 0xcd, 0x00, 0x02, 0x90, 0x2f, 0x9e, 0x2e, 0xde, 0xad, 0xbe, 0xef,
+            ],
+            Error::WrongType,
+        ),
+        (
+            "truncated report block",
+            vec![
+                0x8b, 0xcd, 0x00, 0x03, 0x90, 0x2f, 0x9e, 0x2e, 0x4b, 0xc4, 0xfc, 0xb4, 0xde, 0xad,
+                0xbe, 0xef,
+            ],
+            Error::PacketTooShort,
+        ),
+        (
+            "more reports than metric blocks",
+            vec![
+                0x8b, 0xcd, 0x00, 0x04, 0x90, 0x2f, 0x9e, 0x2e, 0x4b, 0xc4, 0xfc, 0xb4, 0x00, 0x01,
+                0x00, 0x02, 0xde, 0xad, 0xbe, 0xef,
+            ],
+            Error::PacketTooShort,
+        ),
+        (
+            "too many reports",
+            vec![
+                0x8b, 0xcd, 0x00, 0x04, 0x90, 0x2f, 0x9e, 0x2e, 0x4b, 0xc4, 0xfc, 0xb4, 0x00, 0x01,
+                0x40, 0x01, 0xde, 0xad, 0xbe, 0xef,
+            ],
+            Error::TooManyReports,
+        ),
+        (
+            "invalid padding",
+            vec![
+                0xab, 0xcd, 0x00, 0x02, 0x90, 0x2f, 0x9e, 0x2e, 0xde, 0xad, 0xbe, 0x00,
+            ],
+            Error::WrongPadding,
+        ),
+    ];
+
+    for (name, data, want) in tests {
+        let mut raw = Bytes::from(data);
+        let got = CongestionControlFeedback::unmarshal(&mut raw);
+        assert_eq!(want, got.unwrap_err(), "{name}");
+    }
+}
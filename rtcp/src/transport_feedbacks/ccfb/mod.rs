@@ -0,0 +1,333 @@
+#[cfg(test)]
+mod ccfb_test;
+
+use std::any::Any;
+use std::fmt;
+
+use bytes::{Buf, BufMut};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+use crate::header::*;
+use crate::packet::*;
+use crate::util::*;
+
+type Result<T> = std::result::Result<T, util::Error>;
+
+const CCFB_REPORT_BLOCK_HEADER_LENGTH: usize = SSRC_LENGTH + 4;
+const CCFB_METRIC_BLOCK_LENGTH: usize = 2;
+const CCFB_REPORT_TIMESTAMP_LENGTH: usize = 4;
+
+/// The largest number of metric blocks in a report block, RFC 8888 Sec 3.1.
+pub const CCFB_MAX_METRIC_BLOCKS: usize = 16384;
+/// The largest arrival time offset, in 1/1024 seconds. Larger offsets are reported
+/// as [`CCFB_ARRIVAL_TIME_OFFSET_OVERRANGE`].
+pub const CCFB_MAX_ARRIVAL_TIME_OFFSET: u16 = 0x1FFD;
+/// Arrival time offset of packets received more than
+/// [`CCFB_MAX_ARRIVAL_TIME_OFFSET`] before the report timestamp.
+pub const CCFB_ARRIVAL_TIME_OFFSET_OVERRANGE: u16 = 0x1FFE;
+/// Arrival time offset of packets whose arrival time is unknown.
+pub const CCFB_ARRIVAL_TIME_OFFSET_UNAVAILABLE: u16 = 0x1FFF;
+
+/// Ecn is the ECN codepoint (RFC 3168) of the IP header of a received packet.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[repr(u8)]
+pub enum Ecn {
+    #[default]
+    NotEct = 0,
+    Ect1 = 1,
+    Ect0 = 2,
+    Ce = 3,
+}
+
+impl From<u8> for Ecn {
+    fn from(v: u8) -> Self {
+        match v & 0x3 {
+            1 => Ecn::Ect1,
+            2 => Ecn::Ect0,
+            3 => Ecn::Ce,
+            _ => Ecn::NotEct,
+        }
+    }
+}
+
+/// CcfbMetricBlock reports on one RTP packet: whether it was received and, if so,
+/// its ECN codepoint and how long before the report timestamp it arrived.
+///
+/// ```text
+///  0                   1
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |R|ECN|  Arrival time offset    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct CcfbMetricBlock {
+    pub received: bool,
+    pub ecn: Ecn,
+    /// Arrival time offset to the report timestamp in 1/1024 seconds, 13 bits.
+    pub arrival_time_offset: u16,
+}
+
+impl CcfbMetricBlock {
+    fn marshal(&self) -> u16 {
+        if !self.received {
+            // ECN and arrival time offset must be 0 for lost packets.
+            return 0;
+        }
+        (1 << 15) | ((self.ecn as u16) << 13) | (self.arrival_time_offset & 0x1FFF)
+    }
+
+    fn unmarshal(v: u16) -> Self {
+        CcfbMetricBlock {
+            received: v >> 15 == 1,
+            ecn: Ecn::from((v >> 13) as u8),
+            arrival_time_offset: v & 0x1FFF,
+        }
+    }
+}
+
+/// CcfbReportBlock reports on consecutive RTP packets of a stream, starting at
+/// begin_sequence.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct CcfbReportBlock {
+    pub media_ssrc: u32,
+    pub begin_sequence: u16,
+    pub metric_blocks: Vec<CcfbMetricBlock>,
+}
+
+impl CcfbReportBlock {
+    fn raw_size(&self) -> usize {
+        // Metric blocks are padded to a 32-bit boundary.
+        let n = self.metric_blocks.len() + self.metric_blocks.len() % 2;
+        CCFB_REPORT_BLOCK_HEADER_LENGTH + n * CCFB_METRIC_BLOCK_LENGTH
+    }
+}
+
+/// CongestionControlFeedback is the RTP Control Protocol feedback for congestion
+/// control of RFC 8888, reporting the arrival and ECN marking of each RTP packet.
+/// Unlike [`TransportLayerCc`](crate::transport_feedbacks::transport_layer_cc::TransportLayerCc)
+/// it relies on plain RTP sequence numbers and needs no header extension.
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |V=2|P| FMT=11  |   PT = 205    |          length               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                 SSRC of RTCP packet sender                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                   SSRC of 1st RTP Stream                      |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |          begin_seq            |          num_reports          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |R|ECN|  Arrival time offset    | ...                           .
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// .                                                               .
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                   SSRC of nth RTP Stream                      |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// .                              ...                              .
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                 Report Timestamp (32 bits)                    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct CongestionControlFeedback {
+    /// SSRC of sender
+    pub sender_ssrc: u32,
+    pub report_blocks: Vec<CcfbReportBlock>,
+    /// The middle 32 bits of the NTP time the report was sent at.
+    pub report_timestamp: u32,
+}
+
+impl fmt::Display for CongestionControlFeedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = format!(
+            "CongestionControlFeedback from {:x} at {:x}\n",
+            self.sender_ssrc, self.report_timestamp
+        );
+        for block in &self.report_blocks {
+            let received = block.metric_blocks.iter().filter(|m| m.received).count();
+            out += format!(
+                "\t{:x} {} +{}: {} received\n",
+                block.media_ssrc,
+                block.begin_sequence,
+                block.metric_blocks.len(),
+                received
+            )
+            .as_str();
+        }
+        write!(f, "{out}")
+    }
+}
+
+impl Packet for CongestionControlFeedback {
+    /// Header returns the Header associated with this packet.
+    fn header(&self) -> Header {
+        Header {
+            padding: get_padding_size(self.raw_size()) != 0,
+            count: FORMAT_CCFB,
+            packet_type: PacketType::TransportSpecificFeedback,
+            length: ((self.marshal_size() / 4) - 1) as u16,
+        }
+    }
+
+    /// Destination SSRC returns an array of SSRC values that this packet refers to.
+    fn destination_ssrc(&self) -> Vec<u32> {
+        self.report_blocks.iter().map(|b| b.media_ssrc).collect()
+    }
+
+    fn raw_size(&self) -> usize {
+        HEADER_LENGTH
+            + SSRC_LENGTH
+            + self
+                .report_blocks
+                .iter()
+                .map(CcfbReportBlock::raw_size)
+                .sum::<usize>()
+            + CCFB_REPORT_TIMESTAMP_LENGTH
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+
+    fn equal(&self, other: &(dyn Packet + Send + Sync)) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<CongestionControlFeedback>()
+            .map_or(false, |a| self == a)
+    }
+
+    fn cloned(&self) -> Box<dyn Packet + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+impl MarshalSize for CongestionControlFeedback {
+    fn marshal_size(&self) -> usize {
+        let l = self.raw_size();
+        // align to 32-bit boundary
+        l + get_padding_size(l)
+    }
+}
+
+impl Marshal for CongestionControlFeedback {
+    /// Marshal encodes the CongestionControlFeedback in binary
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize> {
+        if self
+            .report_blocks
+            .iter()
+            .any(|b| b.metric_blocks.len() > CCFB_MAX_METRIC_BLOCKS)
+        {
+            return Err(Error::TooManyReports.into());
+        }
+        if buf.remaining_mut() < self.marshal_size() {
+            return Err(Error::BufferTooShort.into());
+        }
+
+        let h = self.header();
+        let n = h.marshal_to(buf)?;
+        buf = &mut buf[n..];
+
+        buf.put_u32(self.sender_ssrc);
+        for block in &self.report_blocks {
+            buf.put_u32(block.media_ssrc);
+            buf.put_u16(block.begin_sequence);
+            buf.put_u16(block.metric_blocks.len() as u16);
+            for metric_block in &block.metric_blocks {
+                buf.put_u16(metric_block.marshal());
+            }
+            if block.metric_blocks.len() % 2 == 1 {
+                buf.put_u16(0);
+            }
+        }
+        buf.put_u32(self.report_timestamp);
+
+        if h.padding {
+            put_padding(buf, self.raw_size());
+        }
+
+        Ok(self.marshal_size())
+    }
+}
+
+impl Unmarshal for CongestionControlFeedback {
+    /// Unmarshal decodes the CongestionControlFeedback from binary
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        if raw_packet.remaining() < HEADER_LENGTH + SSRC_LENGTH + CCFB_REPORT_TIMESTAMP_LENGTH {
+            return Err(Error::PacketTooShort.into());
+        }
+
+        let h = Header::unmarshal(raw_packet)?;
+        if h.packet_type != PacketType::TransportSpecificFeedback || h.count != FORMAT_CCFB {
+            return Err(Error::WrongType.into());
+        }
+
+        let length = h.length as usize * 4;
+        if raw_packet.remaining() < length {
+            return Err(Error::PacketTooShort.into());
+        }
+        let mut payload = raw_packet.copy_to_bytes(length);
+        if h.padding {
+            let padding = payload.last().copied().unwrap_or_default() as usize;
+            if padding == 0 || padding > payload.len() {
+                return Err(Error::WrongPadding.into());
+            }
+            payload.truncate(payload.len() - padding);
+        }
+        if payload.len() < SSRC_LENGTH + CCFB_REPORT_TIMESTAMP_LENGTH {
+            return Err(Error::PacketTooShort.into());
+        }
+
+        let sender_ssrc = payload.get_u32();
+        // Report blocks take everything up to the report timestamp.
+        let mut report_blocks = vec![];
+        while payload.remaining() > CCFB_REPORT_TIMESTAMP_LENGTH {
+            if payload.remaining() < CCFB_REPORT_BLOCK_HEADER_LENGTH + CCFB_REPORT_TIMESTAMP_LENGTH
+            {
+                return Err(Error::PacketTooShort.into());
+            }
+            let media_ssrc = payload.get_u32();
+            let begin_sequence = payload.get_u16();
+            let num_reports = payload.get_u16() as usize;
+            if num_reports > CCFB_MAX_METRIC_BLOCKS {
+                return Err(Error::TooManyReports.into());
+            }
+
+            let padded = num_reports + num_reports % 2;
+            if payload.remaining()
+                < padded * CCFB_METRIC_BLOCK_LENGTH + CCFB_REPORT_TIMESTAMP_LENGTH
+            {
+                return Err(Error::PacketTooShort.into());
+            }
+            let metric_blocks = (0..num_reports)
+                .map(|_| CcfbMetricBlock::unmarshal(payload.get_u16()))
+                .collect();
+            if padded != num_reports {
+                payload.advance(CCFB_METRIC_BLOCK_LENGTH);
+            }
+
+            report_blocks.push(CcfbReportBlock {
+                media_ssrc,
+                begin_sequence,
+                metric_blocks,
+            });
+        }
+        if payload.remaining() != CCFB_REPORT_TIMESTAMP_LENGTH {
+            return Err(Error::PacketTooShort.into());
+        }
+        let report_timestamp = payload.get_u32();
+
+        Ok(CongestionControlFeedback {
+            sender_ssrc,
+            report_blocks,
+            report_timestamp,
+        })
+    }
+}
@@ -1,3 +1,6 @@
+pub mod ccfb;
 pub mod rapid_resynchronization_request;
+pub mod temporary_maximum_media_stream_bitrate_notification;
+pub mod temporary_maximum_media_stream_bitrate_request;
 pub mod transport_layer_cc;
 pub mod transport_layer_nack;
@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod temporary_maximum_media_stream_bitrate_notification_test;
+
+use std::any::Any;
+use std::fmt;
+
+use bytes::{Buf, BufMut};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+use crate::header::*;
+use crate::packet::*;
+use crate::transport_feedbacks::temporary_maximum_media_stream_bitrate_request::{
+    marshal_tmmb, unmarshal_tmmb, TmmbItem, TMMB_HEADER_LENGTH, TMMB_ITEM_LENGTH,
+};
+use crate::util::*;
+
+type Result<T> = std::result::Result<T, util::Error>;
+
+/// The TemporaryMaximumMediaStreamBitrateNotification (TMMBN) packet is the answer
+/// of a media sender to TMMBR, listing the limits it currently applies, as
+/// specified in https://www.rfc-editor.org/rfc/rfc5104#section-4.2.2
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct TemporaryMaximumMediaStreamBitrateNotification {
+    /// SSRC of sender
+    pub sender_ssrc: u32,
+    /// The limits in effect, the bounding set of RFC 5104. Empty if there is none.
+    /// The SSRC of each item is the one of the TMMBR sender which owns the limit.
+    pub items: Vec<TmmbItem>,
+}
+
+impl fmt::Display for TemporaryMaximumMediaStreamBitrateNotification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = format!(
+            "TemporaryMaximumMediaStreamBitrateNotification from {:x}\n",
+            self.sender_ssrc
+        );
+        for item in &self.items {
+            out += format!(
+                "\t{:x} {} bps, overhead {}\n",
+                item.ssrc, item.bitrate, item.overhead
+            )
+            .as_str();
+        }
+        write!(f, "{out}")
+    }
+}
+
+impl Packet for TemporaryMaximumMediaStreamBitrateNotification {
+    /// Header returns the Header associated with this packet.
+    fn header(&self) -> Header {
+        Header {
+            padding: get_padding_size(self.raw_size()) != 0,
+            count: FORMAT_TMMBN,
+            packet_type: PacketType::TransportSpecificFeedback,
+            length: ((self.marshal_size() / 4) - 1) as u16,
+        }
+    }
+
+    /// Destination SSRC returns an array of SSRC values that this packet refers to.
+    /// The items are owned by the senders of TMMBR, so the packet refers to the
+    /// media sender notifying its bounding set.
+    fn destination_ssrc(&self) -> Vec<u32> {
+        vec![self.sender_ssrc]
+    }
+
+    fn raw_size(&self) -> usize {
+        HEADER_LENGTH + TMMB_HEADER_LENGTH + self.items.len() * TMMB_ITEM_LENGTH
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+
+    fn equal(&self, other: &(dyn Packet + Send + Sync)) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<TemporaryMaximumMediaStreamBitrateNotification>()
+            .map_or(false, |a| self == a)
+    }
+
+    fn cloned(&self) -> Box<dyn Packet + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+impl MarshalSize for TemporaryMaximumMediaStreamBitrateNotification {
+    fn marshal_size(&self) -> usize {
+        let l = self.raw_size();
+        // align to 32-bit boundary
+        l + get_padding_size(l)
+    }
+}
+
+impl Marshal for TemporaryMaximumMediaStreamBitrateNotification {
+    /// Marshal encodes the TemporaryMaximumMediaStreamBitrateNotification in binary
+    fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        if buf.remaining_mut() < self.marshal_size() {
+            return Err(Error::BufferTooShort.into());
+        }
+
+        marshal_tmmb(buf, &self.header(), self.sender_ssrc, &self.items)?;
+
+        Ok(self.marshal_size())
+    }
+}
+
+impl Unmarshal for TemporaryMaximumMediaStreamBitrateNotification {
+    /// Unmarshal decodes the TemporaryMaximumMediaStreamBitrateNotification from binary
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        let (sender_ssrc, items) = unmarshal_tmmb(raw_packet, FORMAT_TMMBN)?;
+
+        Ok(TemporaryMaximumMediaStreamBitrateNotification { sender_ssrc, items })
+    }
+}
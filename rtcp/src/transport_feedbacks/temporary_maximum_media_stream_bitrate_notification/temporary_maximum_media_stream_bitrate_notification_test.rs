@@ -0,0 +1,78 @@
+use bytes::Bytes;
+
+use super::*;
+
+#[test]
+fn test_tmmbn_round_trip() -> Result<()> {
+    let tests = vec![
+        TemporaryMaximumMediaStreamBitrateNotification {
+            sender_ssrc: 0x902f9e2e,
+            items: vec![
+                TmmbItem {
+                    ssrc: 0x4bc4fcb4,
+                    bitrate: 64_000,
+                    overhead: 0,
+                },
+                TmmbItem {
+                    ssrc: 0x12345678,
+                    bitrate: 2_500_000 >> 5 << 5,
+                    overhead: 511,
+                },
+            ],
+        },
+        // An empty bounding set.
+        TemporaryMaximumMediaStreamBitrateNotification {
+            sender_ssrc: 1,
+            items: vec![],
+        },
+    ];
+
+    for want in tests {
+        let mut raw = want.marshal()?;
+        assert_eq!(raw.len(), want.marshal_size());
+        let packets = crate::packet::unmarshal(&mut raw)?;
+        assert_eq!(
+            packets[0]
+                .as_any()
+                .downcast_ref::<TemporaryMaximumMediaStreamBitrateNotification>(),
+            Some(&want)
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_tmmbn_unmarshal() -> Result<()> {
+    let mut raw = Bytes::from_static(&[
+        0x84, 0xcd, 0x00, 0x04, // v=2, p=0, FMT=4, PT=205, len=4
+        0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+        0x00, 0x00, 0x00, 0x00, // media=0
+        0x4b, 0xc4, 0xfc, 0xb4, // ssrc=0x4bc4fcb4
+        0x0f, 0xd0, 0x90, 0x28, // exp=3, mantissa=125000, overhead=40
+    ]);
+    let tmmbn = TemporaryMaximumMediaStreamBitrateNotification::unmarshal(&mut raw)?;
+    // The packet refers to the media sender, not to the owners of the items.
+    assert_eq!(tmmbn.destination_ssrc(), vec![0x902f9e2e]);
+    assert_eq!(
+        tmmbn,
+        TemporaryMaximumMediaStreamBitrateNotification {
+            sender_ssrc: 0x902f9e2e,
+            items: vec![TmmbItem {
+                ssrc: 0x4bc4fcb4,
+                bitrate: 1_000_000,
+                overhead: 40,
+            }],
+        }
+    );
+
+    let mut raw = Bytes::from_static(&[
+        0x83, 0xcd, 0x00, 0x02, 0x90, 0x2f, 0x9e, 0x2e, 0x00, 0x00, 0x00, 0x00,
+    ]);
+    assert_eq!(
+        Error::WrongType,
+        TemporaryMaximumMediaStreamBitrateNotification::unmarshal(&mut raw).unwrap_err()
+    );
+
+    Ok(())
+}
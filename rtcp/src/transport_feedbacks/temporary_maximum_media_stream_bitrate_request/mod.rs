@@ -0,0 +1,248 @@
+#[cfg(test)]
+mod temporary_maximum_media_stream_bitrate_request_test;
+
+use std::any::Any;
+use std::fmt;
+
+use bytes::{Buf, BufMut};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+use crate::header::*;
+use crate::packet::*;
+use crate::util::*;
+
+type Result<T> = std::result::Result<T, util::Error>;
+
+pub(crate) const TMMB_HEADER_LENGTH: usize = SSRC_LENGTH * 2;
+pub(crate) const TMMB_ITEM_LENGTH: usize = 8;
+
+/// The largest measured overhead of a TmmbItem, 9 bits.
+pub const TMMB_MAX_OVERHEAD: u16 = 0x1FF;
+
+const TMMB_MANTISSA_BITS: u32 = 17;
+const TMMB_MAX_MANTISSA: u64 = (1 << TMMB_MANTISSA_BITS) - 1;
+const TMMB_MAX_EXP: u32 = 63;
+
+/// TmmbItem is the Feedback Control Information of TMMBR and TMMBN, a maximum
+/// total media bitrate limit of a media sender.
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                              SSRC                             |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// | MxTBR Exp |  MxTBR Mantissa                 |Measured Overhead|
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
+pub struct TmmbItem {
+    /// SSRC of the media sender the limit applies to
+    pub ssrc: u32,
+    /// Maximum total media bitrate in bits per second. It is sent as a 17 bit
+    /// mantissa and a 6 bit exponent, so it is rounded down to 17 significant bits.
+    pub bitrate: u64,
+    /// Per packet overhead in bytes, 9 bits
+    pub overhead: u16,
+}
+
+impl TmmbItem {
+    pub(crate) fn marshal_to(&self, buf: &mut &mut [u8]) -> Result<()> {
+        if self.overhead > TMMB_MAX_OVERHEAD {
+            return Err(Error::InvalidOverhead.into());
+        }
+
+        let mut exp = 0;
+        while (self.bitrate >> exp) > TMMB_MAX_MANTISSA {
+            exp += 1;
+        }
+        let mantissa = (self.bitrate >> exp) as u32;
+
+        buf.put_u32(self.ssrc);
+        buf.put_u32((exp << 26) | (mantissa << 9) | self.overhead as u32);
+        Ok(())
+    }
+
+    pub(crate) fn unmarshal<B: Buf>(raw_packet: &mut B) -> Result<Self> {
+        let ssrc = raw_packet.get_u32();
+        let v = raw_packet.get_u32();
+        let exp = v >> 26;
+        let mantissa = ((v >> 9) as u64) & TMMB_MAX_MANTISSA;
+        let overhead = (v & TMMB_MAX_OVERHEAD as u32) as u16;
+
+        // The mantissa must not be shifted out of 64 bits.
+        if exp > TMMB_MAX_EXP - TMMB_MANTISSA_BITS + 1 && mantissa >> (64 - exp) != 0 {
+            return Err(Error::InvalidBitrate.into());
+        }
+
+        Ok(TmmbItem {
+            ssrc,
+            bitrate: mantissa << exp,
+            overhead,
+        })
+    }
+}
+
+/// unmarshal_tmmb decodes the header shared by TMMBR and TMMBN, returning the
+/// sender SSRC and the items.
+pub(crate) fn unmarshal_tmmb<B: Buf>(
+    raw_packet: &mut B,
+    format: u8,
+) -> Result<(u32, Vec<TmmbItem>)> {
+    if raw_packet.remaining() < HEADER_LENGTH + TMMB_HEADER_LENGTH {
+        return Err(Error::PacketTooShort.into());
+    }
+
+    let h = Header::unmarshal(raw_packet)?;
+    if h.packet_type != PacketType::TransportSpecificFeedback || h.count != format {
+        return Err(Error::WrongType.into());
+    }
+
+    let length = h.length as usize * 4;
+    if raw_packet.remaining() < length {
+        return Err(Error::PacketTooShort.into());
+    }
+    let mut payload = raw_packet.copy_to_bytes(length);
+    if h.padding {
+        let padding = payload.last().copied().unwrap_or_default() as usize;
+        if padding == 0 || padding > payload.len() {
+            return Err(Error::WrongPadding.into());
+        }
+        payload.truncate(payload.len() - padding);
+    }
+    if payload.len() < TMMB_HEADER_LENGTH
+        || (payload.len() - TMMB_HEADER_LENGTH) % TMMB_ITEM_LENGTH != 0
+    {
+        return Err(Error::PacketTooShort.into());
+    }
+
+    let sender_ssrc = payload.get_u32();
+    // RFC 5104 Sec 4.2.1.2, the media source SSRC is not used. It SHOULD be 0, but
+    // some senders put the media SSRC there, so any value is accepted.
+    payload.advance(4);
+
+    let mut items = Vec::with_capacity(payload.remaining() / TMMB_ITEM_LENGTH);
+    while payload.has_remaining() {
+        items.push(TmmbItem::unmarshal(&mut payload)?);
+    }
+
+    Ok((sender_ssrc, items))
+}
+
+/// marshal_tmmb encodes the header shared by TMMBR and TMMBN followed by the items.
+pub(crate) fn marshal_tmmb(
+    mut buf: &mut [u8],
+    h: &Header,
+    sender_ssrc: u32,
+    items: &[TmmbItem],
+) -> Result<()> {
+    let n = h.marshal_to(buf)?;
+    buf = &mut buf[n..];
+
+    buf.put_u32(sender_ssrc);
+    // The media source SSRC is not used and always written as 0.
+    buf.put_u32(0);
+    for item in items {
+        item.marshal_to(&mut buf)?;
+    }
+    Ok(())
+}
+
+/// The TemporaryMaximumMediaStreamBitrateRequest (TMMBR) packet asks the media
+/// senders of its items to limit their bitrate, as specified in
+/// https://www.rfc-editor.org/rfc/rfc5104#section-4.2.1
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct TemporaryMaximumMediaStreamBitrateRequest {
+    /// SSRC of sender
+    pub sender_ssrc: u32,
+    /// The requested limits, one per media sender
+    pub items: Vec<TmmbItem>,
+}
+
+impl fmt::Display for TemporaryMaximumMediaStreamBitrateRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = format!(
+            "TemporaryMaximumMediaStreamBitrateRequest from {:x}\n",
+            self.sender_ssrc
+        );
+        for item in &self.items {
+            out += format!(
+                "\t{:x} {} bps, overhead {}\n",
+                item.ssrc, item.bitrate, item.overhead
+            )
+            .as_str();
+        }
+        write!(f, "{out}")
+    }
+}
+
+impl Packet for TemporaryMaximumMediaStreamBitrateRequest {
+    /// Header returns the Header associated with this packet.
+    fn header(&self) -> Header {
+        Header {
+            padding: get_padding_size(self.raw_size()) != 0,
+            count: FORMAT_TMMBR,
+            packet_type: PacketType::TransportSpecificFeedback,
+            length: ((self.marshal_size() / 4) - 1) as u16,
+        }
+    }
+
+    /// Destination SSRC returns an array of SSRC values that this packet refers to.
+    fn destination_ssrc(&self) -> Vec<u32> {
+        self.items.iter().map(|item| item.ssrc).collect()
+    }
+
+    fn raw_size(&self) -> usize {
+        HEADER_LENGTH + TMMB_HEADER_LENGTH + self.items.len() * TMMB_ITEM_LENGTH
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+
+    fn equal(&self, other: &(dyn Packet + Send + Sync)) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<TemporaryMaximumMediaStreamBitrateRequest>()
+            .map_or(false, |a| self == a)
+    }
+
+    fn cloned(&self) -> Box<dyn Packet + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+impl MarshalSize for TemporaryMaximumMediaStreamBitrateRequest {
+    fn marshal_size(&self) -> usize {
+        let l = self.raw_size();
+        // align to 32-bit boundary
+        l + get_padding_size(l)
+    }
+}
+
+impl Marshal for TemporaryMaximumMediaStreamBitrateRequest {
+    /// Marshal encodes the TemporaryMaximumMediaStreamBitrateRequest in binary
+    fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        if buf.remaining_mut() < self.marshal_size() {
+            return Err(Error::BufferTooShort.into());
+        }
+
+        marshal_tmmb(buf, &self.header(), self.sender_ssrc, &self.items)?;
+
+        Ok(self.marshal_size())
+    }
+}
+
+impl Unmarshal for TemporaryMaximumMediaStreamBitrateRequest {
+    /// Unmarshal decodes the TemporaryMaximumMediaStreamBitrateRequest from binary
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        let (sender_ssrc, items) = unmarshal_tmmb(raw_packet, FORMAT_TMMBR)?;
+
+        Ok(TemporaryMaximumMediaStreamBitrateRequest { sender_ssrc, items })
+    }
+}
@@ -0,0 +1,126 @@
+use bytes::Bytes;
+
+use super::*;
+
+const EXAMPLE: &[u8] = &[
+    0x83, 0xcd, 0x00, 0x04, // v=2, p=0, FMT=3, PT=205, len=4
+    0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+    0x00, 0x00, 0x00, 0x00, // media=0
+    0x4b, 0xc4, 0xfc, 0xb4, // ssrc=0x4bc4fcb4
+    0x0f, 0xd0, 0x90, 0x28, // exp=3, mantissa=125000, overhead=40
+];
+
+fn example() -> TemporaryMaximumMediaStreamBitrateRequest {
+    TemporaryMaximumMediaStreamBitrateRequest {
+        sender_ssrc: 0x902f9e2e,
+        items: vec![TmmbItem {
+            ssrc: 0x4bc4fcb4,
+            bitrate: 1_000_000,
+            overhead: 40,
+        }],
+    }
+}
+
+#[test]
+fn test_tmmbr_marshal() -> Result<()> {
+    assert_eq!(example().marshal()?, Bytes::from_static(EXAMPLE));
+    assert_eq!(example().destination_ssrc(), vec![0x4bc4fcb4]);
+
+    // The bitrate is rounded down to 17 significant bits.
+    let mut raw = TemporaryMaximumMediaStreamBitrateRequest {
+        items: vec![TmmbItem {
+            bitrate: 1_000_001,
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+    .marshal()?;
+    let got = TemporaryMaximumMediaStreamBitrateRequest::unmarshal(&mut raw)?;
+    assert_eq!(got.items[0].bitrate, 1_000_000);
+
+    let mut raw = TemporaryMaximumMediaStreamBitrateRequest {
+        items: vec![TmmbItem {
+            bitrate: u64::MAX,
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+    .marshal()?;
+    let got = TemporaryMaximumMediaStreamBitrateRequest::unmarshal(&mut raw)?;
+    assert_eq!(got.items[0].bitrate, TMMB_MAX_MANTISSA << 47);
+
+    let invalid = TemporaryMaximumMediaStreamBitrateRequest {
+        items: vec![TmmbItem {
+            overhead: TMMB_MAX_OVERHEAD + 1,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    assert_eq!(Error::InvalidOverhead, invalid.marshal().unwrap_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_tmmbr_unmarshal() -> Result<()> {
+    let mut raw = Bytes::from_static(EXAMPLE);
+    assert_eq!(
+        TemporaryMaximumMediaStreamBitrateRequest::unmarshal(&mut raw)?,
+        example()
+    );
+
+    let mut raw = Bytes::from_static(EXAMPLE);
+    let packets = crate::packet::unmarshal(&mut raw)?;
+    assert_eq!(
+        packets[0]
+            .as_any()
+            .downcast_ref::<TemporaryMaximumMediaStreamBitrateRequest>(),
+        Some(&example())
+    );
+
+    // A non zero media source SSRC is accepted, and written back as 0.
+    let mut data = EXAMPLE.to_vec();
+    data[8..12].copy_from_slice(&[0x4b, 0xc4, 0xfc, 0xb4]);
+    let mut raw = Bytes::from(data);
+    let tmmbr = TemporaryMaximumMediaStreamBitrateRequest::unmarshal(&mut raw)?;
+    assert_eq!(tmmbr, example());
+    assert_eq!(tmmbr.marshal()?, Bytes::from_static(EXAMPLE));
+
+    Ok(())
+}
+
+#[test]
+fn test_tmmbr_unmarshal_errors() {
+    let tests: Vec<(&str, Vec<u8>, Error)> = vec![
+        ("nil", vec![], Error::PacketTooShort),
+        (
+            "wrong type",
+            vec![
+                0x84, 0xcd, 0x00, 0x02, 0x90, 0x2f, 0x9e, 0x2e, 0x00, 0x00, 0x00, 0x00,
+            ],
+            Error::WrongType,
+        ),
+        (
+            "truncated item",
+            vec![
+                0x83, 0xcd, 0x00, 0x03, 0x90, 0x2f, 0x9e, 0x2e, 0x00, 0x00, 0x00, 0x00, 0x4b, 0xc4,
+                0xfc, 0xb4,
+            ],
+            Error::PacketTooShort,
+        ),
+        (
+            "bitrate overflow",
+            vec![
+                0x83, 0xcd, 0x00, 0x04, 0x90, 0x2f, 0x9e, 0x2e, 0x00, 0x00, 0x00, 0x00, 0x4b, 0xc4,
+                0xfc, 0xb4, 0xff, 0xff, 0xfe, 0x00,
+            ],
+            Error::InvalidBitrate,
+        ),
+    ];
+
+    for (name, data, want) in tests {
+        let mut raw = Bytes::from(data);
+        let got = TemporaryMaximumMediaStreamBitrateRequest::unmarshal(&mut raw);
+        assert_eq!(want, got.unwrap_err(), "{name}");
+    }
+}
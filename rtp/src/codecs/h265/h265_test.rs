@@ -887,3 +887,171 @@ fn test_h265_packet_real() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_h265_payloader() -> Result<()> {
+    let mut pck = H265Payloader::default();
+
+    // Empty payload or MTU
+    assert!(pck.payload(1500, &Bytes::new())?.is_empty());
+    assert!(pck
+        .payload(0, &Bytes::from_static(&[0x26, 0x01, 0xaa]))?
+        .is_empty());
+
+    // Single NAL Unit packet
+    let nalu = Bytes::from_static(&[0x26, 0x01, 0xaa, 0xbb, 0xcc]);
+    assert_eq!(pck.payload(1500, &nalu)?, vec![nalu.clone()]);
+
+    // VPS, SPS and PPS are aggregated
+    let parameter_sets = Bytes::from_static(&[
+        0x00, 0x00, 0x00, 0x01, 0x40, 0x01, 0x0c, 0x00, 0x00, 0x00, 0x01, 0x42, 0x01, 0x0d, 0x00,
+        0x00, 0x01, 0x44, 0x01, 0x0e,
+    ]);
+    let result = pck.payload(1500, &parameter_sets)?;
+    assert_eq!(
+        result,
+        vec![Bytes::from_static(&[
+            0x60, 0x01, 0x00, 0x03, 0x40, 0x01, 0x0c, 0x00, 0x03, 0x42, 0x01, 0x0d, 0x00, 0x03,
+            0x44, 0x01, 0x0e,
+        ])]
+    );
+
+    let mut packet = H265Packet::default();
+    packet.depacketize(&result[0])?;
+    if let H265Payload::H265AggregationPacket(aggregation) = packet.payload() {
+        let first_unit = aggregation.first_unit().expect("first unit");
+        assert_eq!(
+            first_unit.nal_unit(),
+            Bytes::from_static(&[0x40, 0x01, 0x0c])
+        );
+        assert_eq!(aggregation.other_units().len(), 2);
+    } else {
+        panic!("expected an aggregation packet");
+    }
+
+    // Aggregation Packets don't exceed the MTU
+    let result = pck.payload(12, &parameter_sets)?;
+    assert_eq!(
+        result,
+        vec![
+            Bytes::from_static(&[
+                0x60, 0x01, 0x00, 0x03, 0x40, 0x01, 0x0c, 0x00, 0x03, 0x42, 0x01, 0x0d,
+            ]),
+            Bytes::from_static(&[0x44, 0x01, 0x0e]),
+        ]
+    );
+
+    // Fragmentation Units
+    let large_nalu = Bytes::from_static(&[
+        0x26, 0x01, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a,
+    ]);
+    let result = pck.payload(6, &large_nalu)?;
+    assert_eq!(
+        result,
+        vec![
+            Bytes::from_static(&[0x62, 0x01, 0x93, 0x01, 0x02, 0x03]),
+            Bytes::from_static(&[0x62, 0x01, 0x13, 0x04, 0x05, 0x06]),
+            Bytes::from_static(&[0x62, 0x01, 0x13, 0x07, 0x08, 0x09]),
+            Bytes::from_static(&[0x62, 0x01, 0x53, 0x0a]),
+        ]
+    );
+
+    let mut reassembled = vec![];
+    for payload in &result {
+        let mut packet = H265Packet::default();
+        packet.depacketize(payload)?;
+        if let H265Payload::H265FragmentationUnitPacket(fu) = packet.payload() {
+            assert_eq!(fu.fu_header().fu_type(), 19);
+            reassembled.extend_from_slice(&fu.payload());
+        } else {
+            panic!("expected a fragmentation unit");
+        }
+    }
+    assert_eq!(reassembled, large_nalu[2..]);
+
+    // MTU too small for a Fragmentation Unit
+    assert!(pck.payload(3, &large_nalu)?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_h265_payloader_donl() -> Result<()> {
+    let mut pck = H265Payloader::default();
+    pck.with_donl(true);
+
+    let result = pck.payload(1500, &Bytes::from_static(&[0x26, 0x01, 0xaa]))?;
+    assert_eq!(
+        result,
+        vec![Bytes::from_static(&[0x26, 0x01, 0x00, 0x00, 0xaa])]
+    );
+
+    // The first unit carries the DONL, the others a DOND
+    let parameter_sets = Bytes::from_static(&[
+        0x00, 0x00, 0x01, 0x40, 0x01, 0x0c, 0x00, 0x00, 0x01, 0x42, 0x01, 0x0d,
+    ]);
+    let result = pck.payload(1500, &parameter_sets)?;
+    assert_eq!(
+        result,
+        vec![Bytes::from_static(&[
+            0x60, 0x01, 0x00, 0x01, 0x00, 0x03, 0x40, 0x01, 0x0c, 0x00, 0x00, 0x03, 0x42, 0x01,
+            0x0d,
+        ])]
+    );
+
+    let mut packet = H265Packet::default();
+    packet.with_donl(true);
+    packet.depacketize(&result[0])?;
+    if let H265Payload::H265AggregationPacket(aggregation) = packet.payload() {
+        assert_eq!(aggregation.first_unit().and_then(|u| u.donl()), Some(1));
+        assert_eq!(aggregation.other_units()[0].dond(), Some(0));
+    } else {
+        panic!("expected an aggregation packet");
+    }
+
+    // Only the first Fragmentation Unit carries the DONL
+    let large_nalu = Bytes::from_static(&[0x26, 0x01, 0x01, 0x02, 0x03, 0x04, 0x05]);
+    let result = pck.payload(6, &large_nalu)?;
+    assert_eq!(
+        result,
+        vec![
+            Bytes::from_static(&[0x62, 0x01, 0x93, 0x00, 0x03, 0x01]),
+            Bytes::from_static(&[0x62, 0x01, 0x13, 0x02, 0x03, 0x04]),
+            Bytes::from_static(&[0x62, 0x01, 0x53, 0x05]),
+        ]
+    );
+
+    let mut packet = H265Packet::default();
+    packet.with_donl(true);
+    packet.depacketize(&result[0])?;
+    if let H265Payload::H265FragmentationUnitPacket(fu) = packet.payload() {
+        assert_eq!(fu.donl(), Some(3));
+        assert_eq!(fu.payload(), Bytes::from_static(&[0x01]));
+    } else {
+        panic!("expected a fragmentation unit");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_h265_payloader_skip_aggregation() -> Result<()> {
+    let mut pck = H265Payloader::default();
+    pck.with_skip_aggregation(true);
+
+    let result = pck.payload(
+        1500,
+        &Bytes::from_static(&[
+            0x00, 0x00, 0x01, 0x40, 0x01, 0x0c, 0x00, 0x00, 0x01, 0x42, 0x01, 0x0d,
+        ]),
+    )?;
+    assert_eq!(
+        result,
+        vec![
+            Bytes::from_static(&[0x40, 0x01, 0x0c]),
+            Bytes::from_static(&[0x42, 0x01, 0x0d]),
+        ]
+    );
+
+    Ok(())
+}
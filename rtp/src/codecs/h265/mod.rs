@@ -1,7 +1,8 @@
-use bytes::Bytes;
+use bytes::{BufMut, Bytes, BytesMut};
 
+use crate::codecs::h264::H264Payloader;
 use crate::error::{Error, Result};
-use crate::packetizer::Depacketizer;
+use crate::packetizer::{Depacketizer, Payloader};
 
 #[cfg(test)]
 mod h265_test;
@@ -713,6 +714,251 @@ impl Default for H265Payload {
     }
 }
 
+//
+// Payloader implementation
+//
+
+/// H265Payloader payloads H265 packets as described in RFC7798.
+/// NAL units that fit the MTU are sent as Single NAL Unit packets or grouped into Aggregation
+/// Packets, larger ones are split into Fragmentation Units.
+#[derive(Default, Debug, Clone)]
+pub struct H265Payloader {
+    add_donl: bool,
+    skip_aggregation: bool,
+    donl: u16,
+}
+
+impl H265Payloader {
+    /// with_donl can be called to specify whether or not DONL/DOND are written.
+    /// DONL must be written if `sprop-max-don-diff` is greater than 0 on the RTP stream.
+    pub fn with_donl(&mut self, value: bool) {
+        self.add_donl = value;
+    }
+
+    /// with_skip_aggregation can be called to send every NAL unit that fits the MTU as a Single
+    /// NAL Unit packet instead of grouping them into Aggregation Packets.
+    pub fn with_skip_aggregation(&mut self, value: bool) {
+        self.skip_aggregation = value;
+    }
+
+    fn next_donl(&mut self) -> u16 {
+        let donl = self.donl;
+        self.donl = self.donl.wrapping_add(1);
+        donl
+    }
+
+    fn emit(
+        &mut self,
+        nalu: &Bytes,
+        mtu: usize,
+        aggregation: &mut Vec<Bytes>,
+        payloads: &mut Vec<Bytes>,
+    ) {
+        if nalu.len() <= H265NALU_HEADER_SIZE {
+            return;
+        }
+
+        let donl_size = if self.add_donl { 2 } else { 0 };
+        if nalu.len() + donl_size > mtu {
+            self.flush_aggregation(aggregation, payloads);
+            self.emit_fragmentation_units(nalu, mtu, payloads);
+            return;
+        }
+
+        if self.skip_aggregation {
+            payloads.push(self.single_nal_unit_packet(nalu));
+            return;
+        }
+
+        let aggregated_size = if aggregation.is_empty() {
+            H265NALU_HEADER_SIZE + donl_size
+        } else {
+            Self::aggregation_packet_size(aggregation, donl_size)
+        };
+        // DOND of the unit when it isn't the first one, then its size.
+        let unit_size = if aggregation.is_empty() {
+            0
+        } else {
+            donl_size / 2
+        } + 2
+            + nalu.len();
+        if aggregated_size + unit_size > mtu {
+            self.flush_aggregation(aggregation, payloads);
+        }
+        aggregation.push(nalu.clone());
+    }
+
+    fn aggregation_packet_size(aggregation: &[Bytes], donl_size: usize) -> usize {
+        let units_size: usize = aggregation.iter().map(|nalu| 2 + nalu.len()).sum();
+        H265NALU_HEADER_SIZE + donl_size + (aggregation.len() - 1) * (donl_size / 2) + units_size
+    }
+
+    fn flush_aggregation(&mut self, aggregation: &mut Vec<Bytes>, payloads: &mut Vec<Bytes>) {
+        match aggregation.len() {
+            0 => {}
+            1 => payloads.push(self.single_nal_unit_packet(&aggregation[0])),
+            _ => payloads.push(self.aggregation_packet(aggregation)),
+        }
+        aggregation.clear();
+    }
+
+    fn single_nal_unit_packet(&mut self, nalu: &Bytes) -> Bytes {
+        if !self.add_donl {
+            return nalu.clone();
+        }
+
+        let mut out = BytesMut::with_capacity(nalu.len() + 2);
+        out.put(&nalu[..H265NALU_HEADER_SIZE]);
+        out.put_u16(self.next_donl());
+        out.put(&nalu[H265NALU_HEADER_SIZE..]);
+        out.freeze()
+    }
+
+    /// https://datatracker.ietf.org/doc/html/rfc7798#section-4.4.2
+    fn aggregation_packet(&mut self, aggregation: &[Bytes]) -> Bytes {
+        let donl_size = if self.add_donl { 2 } else { 0 };
+        let mut out =
+            BytesMut::with_capacity(Self::aggregation_packet_size(aggregation, donl_size));
+
+        // The F bit MUST be equal to 0 if the F bit of each aggregated NAL unit is equal to
+        // zero, LayerId and TID MUST be equal to the lowest value of the aggregated NAL units.
+        let headers: Vec<H265NALUHeader> = aggregation
+            .iter()
+            .map(|nalu| H265NALUHeader::new(nalu[0], nalu[1]))
+            .collect();
+        let f = headers.iter().any(|h| h.f());
+        let layer_id = headers.iter().map(|h| h.layer_id()).min().unwrap_or(0);
+        let tid = headers.iter().map(|h| h.tid()).min().unwrap_or(0);
+        out.put_u16(
+            ((f as u16) << 15)
+                | ((H265NALU_AGGREGATION_PACKET_TYPE as u16) << 9)
+                | ((layer_id as u16) << 3)
+                | tid as u16,
+        );
+
+        for (i, nalu) in aggregation.iter().enumerate() {
+            if self.add_donl {
+                if i == 0 {
+                    out.put_u16(self.next_donl());
+                } else {
+                    // The NAL units are aggregated in decoding order, so DOND is always 0.
+                    self.next_donl();
+                    out.put_u8(0);
+                }
+            }
+            out.put_u16(nalu.len() as u16);
+            out.put(&nalu[..]);
+        }
+
+        out.freeze()
+    }
+
+    /// https://datatracker.ietf.org/doc/html/rfc7798#section-4.4.3
+    fn emit_fragmentation_units(&mut self, nalu: &Bytes, mtu: usize, payloads: &mut Vec<Bytes>) {
+        const TOTAL_HEADER_SIZE: usize = H265NALU_HEADER_SIZE + H265FRAGMENTATION_UNIT_HEADER_SIZE;
+
+        let donl_size = if self.add_donl { 2 } else { 0 };
+        if mtu <= TOTAL_HEADER_SIZE + donl_size {
+            return;
+        }
+
+        let header = H265NALUHeader::new(nalu[0], nalu[1]);
+        // The payload header is a copy of the NAL unit header with the type set to FU.
+        const TYPE_MASK: u16 = 0b01111110 << 8;
+        let payload_header =
+            (header.0 & !TYPE_MASK) | ((H265NALU_FRAGMENTATION_UNIT_TYPE as u16) << 9);
+        let donl = if self.add_donl {
+            Some(self.next_donl())
+        } else {
+            None
+        };
+
+        // The NAL unit header is not included in the fragments, it is conveyed by the payload
+        // header and the FU header.
+        let mut nalu_data = nalu.slice(H265NALU_HEADER_SIZE..);
+        let mut first = true;
+        while !nalu_data.is_empty() {
+            let header_size = TOTAL_HEADER_SIZE + if first { donl_size } else { 0 };
+            let fragment_size = std::cmp::min(mtu - header_size, nalu_data.len());
+            let fragment = nalu_data.split_to(fragment_size);
+
+            let mut out = BytesMut::with_capacity(header_size + fragment_size);
+            out.put_u16(payload_header);
+
+            // +---------------+
+            // |0|1|2|3|4|5|6|7|
+            // +-+-+-+-+-+-+-+-+
+            // |S|E|  FuType   |
+            // +---------------+
+            let mut fu_header = header.nalu_type();
+            if first {
+                fu_header |= 1 << 7;
+            }
+            if nalu_data.is_empty() {
+                fu_header |= 1 << 6;
+            }
+            out.put_u8(fu_header);
+
+            if first {
+                if let Some(donl) = donl {
+                    out.put_u16(donl);
+                }
+            }
+            out.put(fragment);
+            payloads.push(out.freeze());
+
+            first = false;
+        }
+    }
+}
+
+impl Payloader for H265Payloader {
+    /// Payload fragments a H265 packet across one or more byte arrays
+    fn payload(&mut self, mtu: usize, payload: &Bytes) -> Result<Vec<Bytes>> {
+        if payload.is_empty() || mtu == 0 {
+            return Ok(vec![]);
+        }
+
+        let mut payloads = vec![];
+        let mut aggregation = vec![];
+
+        let (mut next_ind_start, mut next_ind_len) = H264Payloader::next_ind(payload, 0);
+        if next_ind_start == -1 {
+            self.emit(payload, mtu, &mut aggregation, &mut payloads);
+        } else {
+            while next_ind_start != -1 {
+                let prev_start = (next_ind_start + next_ind_len) as usize;
+                let (next_ind_start2, next_ind_len2) = H264Payloader::next_ind(payload, prev_start);
+                next_ind_start = next_ind_start2;
+                next_ind_len = next_ind_len2;
+                if next_ind_start != -1 {
+                    self.emit(
+                        &payload.slice(prev_start..next_ind_start as usize),
+                        mtu,
+                        &mut aggregation,
+                        &mut payloads,
+                    );
+                } else {
+                    // Emit until end of stream, no end indicator found
+                    self.emit(
+                        &payload.slice(prev_start..),
+                        mtu,
+                        &mut aggregation,
+                        &mut payloads,
+                    );
+                }
+            }
+        }
+        self.flush_aggregation(&mut aggregation, &mut payloads);
+
+        Ok(payloads)
+    }
+
+    fn clone_to(&self) -> Box<dyn Payloader + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
 ///
 /// Packet implementation
 ///
@@ -0,0 +1,269 @@
+#[cfg(test)]
+mod red_test;
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+use crate::error::{Error, Result};
+use crate::header::Header;
+use crate::packet::Packet;
+use crate::packetizer::{Depacketizer, Payloader};
+
+/// Size of the header of a redundant block.
+const RED_BLOCK_HEADER_SIZE: usize = 4;
+/// Size of the header of the primary block.
+const RED_PRIMARY_BLOCK_HEADER_SIZE: usize = 1;
+/// The timestamp offset of a redundant block is 14 bits.
+const RED_MAX_TIMESTAMP_OFFSET: u32 = (1 << 14) - 1;
+/// The length of a redundant block is 10 bits.
+const RED_MAX_BLOCK_LENGTH: usize = (1 << 10) - 1;
+
+/// RedBlock is an encoding of a frame carried in a RED packet.
+#[derive(PartialEq, Eq, Debug, Default, Clone)]
+pub struct RedBlock {
+    /// payload_type of the encoding of the block
+    pub payload_type: u8,
+    /// timestamp_offset is the offset of the timestamp of the block to the one of
+    /// the packet, 0 for the primary block
+    pub timestamp_offset: u32,
+    pub payload: Bytes,
+}
+
+/// RedPayloader wraps the payloader of an audio codec like Opus to send RED (RFC 2198)
+/// payloads, which repeat the previous frames after the current one so frames of lost
+/// packets can be recovered from the following packets.
+/// <https://www.rfc-editor.org/rfc/rfc2198>
+pub struct RedPayloader {
+    payloader: Box<dyn Payloader + Send + Sync>,
+    payload_type: u8,
+    distance: usize,
+    history: VecDeque<(u32, Bytes)>,
+}
+
+impl fmt::Debug for RedPayloader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedPayloader")
+            .field("payloader", &self.payloader)
+            .field("payload_type", &self.payload_type)
+            .field("distance", &self.distance)
+            .finish()
+    }
+}
+
+impl Clone for RedPayloader {
+    fn clone(&self) -> Self {
+        RedPayloader {
+            payloader: self.payloader.clone(),
+            payload_type: self.payload_type,
+            distance: self.distance,
+            history: self.history.clone(),
+        }
+    }
+}
+
+impl RedPayloader {
+    /// new creates a RedPayloader for the frames of the payloader, which are sent with
+    /// payload_type. Each payload repeats up to distance previous frames.
+    pub fn new(
+        payloader: Box<dyn Payloader + Send + Sync>,
+        payload_type: u8,
+        distance: usize,
+    ) -> Self {
+        RedPayloader {
+            payloader,
+            payload_type,
+            distance,
+            history: VecDeque::with_capacity(distance),
+        }
+    }
+
+    fn red_payload(&mut self, mtu: usize, primary: Bytes, timestamp: u32) -> Bytes {
+        // The most recent frames are the most useful, they are kept if the mtu is exceeded
+        let mut size = RED_PRIMARY_BLOCK_HEADER_SIZE + primary.len();
+        let mut redundant = vec![];
+        for (frame_timestamp, frame) in self.history.iter().rev() {
+            let timestamp_offset = timestamp.wrapping_sub(*frame_timestamp);
+            if timestamp_offset == 0
+                || timestamp_offset > RED_MAX_TIMESTAMP_OFFSET
+                || frame.len() > RED_MAX_BLOCK_LENGTH
+                || size + RED_BLOCK_HEADER_SIZE + frame.len() > mtu
+            {
+                break;
+            }
+            size += RED_BLOCK_HEADER_SIZE + frame.len();
+            redundant.push((timestamp_offset, frame));
+        }
+
+        let mut out = BytesMut::with_capacity(size);
+        for (timestamp_offset, frame) in redundant.iter().rev() {
+            out.put_u8(0x80 | self.payload_type);
+            out.put_u16(((timestamp_offset << 2) | (frame.len() as u32 >> 8)) as u16);
+            out.put_u8(frame.len() as u8);
+        }
+        out.put_u8(self.payload_type);
+        for (_, frame) in redundant.iter().rev() {
+            out.put_slice(frame);
+        }
+        out.put_slice(&primary);
+
+        if self.distance > 0 {
+            if self.history.len() == self.distance {
+                self.history.pop_front();
+            }
+            self.history.push_back((timestamp, primary));
+        }
+
+        out.freeze()
+    }
+}
+
+impl Payloader for RedPayloader {
+    /// payload sends the frame as the primary block only, as the timestamps of the
+    /// previous frames are unknown.
+    fn payload(&mut self, mtu: usize, payload: &Bytes) -> Result<Vec<Bytes>> {
+        if mtu <= RED_PRIMARY_BLOCK_HEADER_SIZE {
+            return Ok(vec![]);
+        }
+        self.history.clear();
+
+        let payloads = self
+            .payloader
+            .payload(mtu - RED_PRIMARY_BLOCK_HEADER_SIZE, payload)?;
+        Ok(payloads
+            .into_iter()
+            .map(|p| self.red_payload(mtu, p, 0))
+            .collect())
+    }
+
+    fn payload_with_timestamp(
+        &mut self,
+        mtu: usize,
+        payload: &Bytes,
+        timestamp: u32,
+    ) -> Result<Vec<Bytes>> {
+        if mtu <= RED_PRIMARY_BLOCK_HEADER_SIZE {
+            return Ok(vec![]);
+        }
+
+        let payloads = self.payloader.payload_with_timestamp(
+            mtu - RED_PRIMARY_BLOCK_HEADER_SIZE,
+            payload,
+            timestamp,
+        )?;
+        if payloads.len() > 1 {
+            // Redundancy is only supported for frames fitting in a single packet
+            self.history.clear();
+        }
+        Ok(payloads
+            .into_iter()
+            .map(|p| self.red_payload(mtu, p, timestamp))
+            .collect())
+    }
+
+    fn clone_to(&self) -> Box<dyn Payloader + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+/// RedPacket represents the blocks of a RED payload
+#[derive(PartialEq, Eq, Debug, Default, Clone)]
+pub struct RedPacket {
+    /// blocks of the last depacketized payload, the redundant blocks from the oldest
+    /// to the most recent followed by the primary block
+    pub blocks: Vec<RedBlock>,
+}
+
+impl RedPacket {
+    /// parse returns the blocks of a RED payload.
+    pub fn parse(packet: &Bytes) -> Result<Vec<RedBlock>> {
+        let mut headers = vec![];
+        let mut offset = 0;
+        loop {
+            if offset >= packet.len() {
+                return Err(Error::ErrShortPacket);
+            }
+            let b0 = packet[offset];
+            if b0 & 0x80 == 0 {
+                headers.push((b0 & 0x7F, 0, None));
+                offset += RED_PRIMARY_BLOCK_HEADER_SIZE;
+                break;
+            }
+
+            if offset + RED_BLOCK_HEADER_SIZE > packet.len() {
+                return Err(Error::ErrShortPacket);
+            }
+            let timestamp_offset =
+                (u32::from(packet[offset + 1]) << 6) | (u32::from(packet[offset + 2]) >> 2);
+            let length =
+                ((usize::from(packet[offset + 2]) & 0x03) << 8) | usize::from(packet[offset + 3]);
+            headers.push((b0 & 0x7F, timestamp_offset, Some(length)));
+            offset += RED_BLOCK_HEADER_SIZE;
+        }
+
+        let mut blocks = Vec::with_capacity(headers.len());
+        for (payload_type, timestamp_offset, length) in headers {
+            // The primary block takes the rest of the payload
+            let end = length.map_or(packet.len(), |length| offset + length);
+            if end > packet.len() {
+                return Err(Error::ErrShortPacket);
+            }
+            blocks.push(RedBlock {
+                payload_type,
+                timestamp_offset,
+                payload: packet.slice(offset..end),
+            });
+            offset = end;
+        }
+
+        Ok(blocks)
+    }
+
+    /// split_packet returns the packets of the encodings carried by a RED packet,
+    /// the redundant ones before the primary one. Each block is assumed to have been
+    /// sent in its own packet, the redundant packets get the sequence numbers preceding
+    /// the one of the RED packet, so the ones that were already received can be dropped.
+    pub fn split_packet(packet: &Packet) -> Result<Vec<Packet>> {
+        let blocks = RedPacket::parse(&packet.payload)?;
+        let count = blocks.len() as u16;
+        Ok(blocks
+            .into_iter()
+            .enumerate()
+            .map(|(i, block)| Packet {
+                header: Header {
+                    payload_type: block.payload_type,
+                    sequence_number: packet
+                        .header
+                        .sequence_number
+                        .wrapping_sub(count - 1 - i as u16),
+                    timestamp: packet.header.timestamp.wrapping_sub(block.timestamp_offset),
+                    padding: false,
+                    ..packet.header.clone()
+                },
+                payload: block.payload,
+            })
+            .collect())
+    }
+}
+
+impl Depacketizer for RedPacket {
+    /// depacketize returns the primary block of a RED payload, all of its blocks are kept
+    /// in blocks.
+    fn depacketize(&mut self, packet: &Bytes) -> Result<Bytes> {
+        self.blocks = RedPacket::parse(packet)?;
+        Ok(self
+            .blocks
+            .last()
+            .map(|b| b.payload.clone())
+            .unwrap_or_default())
+    }
+
+    fn is_partition_head(&self, _payload: &Bytes) -> bool {
+        true
+    }
+
+    fn is_partition_tail(&self, _marker: bool, _payload: &Bytes) -> bool {
+        true
+    }
+}
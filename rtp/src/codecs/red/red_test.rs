@@ -0,0 +1,164 @@
+use super::*;
+use crate::codecs::opus::OpusPayloader;
+use crate::packetizer::Packetizer;
+
+#[test]
+fn test_red_payload() -> Result<()> {
+    let mut pck = RedPayloader::new(Box::<OpusPayloader>::default(), 111, 2);
+
+    // Empty payload
+    let result = pck.payload_with_timestamp(100, &Bytes::from_static(&[]), 0)?;
+    assert!(result.is_empty(), "Generated payload should be empty");
+
+    // The first frame is sent alone
+    let result = pck.payload_with_timestamp(100, &Bytes::from_static(&[0x01, 0x02]), 1000)?;
+    assert_eq!(result, vec![Bytes::from_static(&[111, 0x01, 0x02])]);
+
+    // followed by the previous frames with their timestamp offsets
+    let result = pck.payload_with_timestamp(100, &Bytes::from_static(&[0x03]), 1960)?;
+    assert_eq!(
+        result,
+        vec![Bytes::from_static(&[
+            0x80 | 111,
+            0x0F, // timestamp offset 960 = 0b00001111000000
+            0x00, // block length 2
+            0x02,
+            111,
+            0x01,
+            0x02,
+            0x03,
+        ])]
+    );
+
+    // up to distance previous frames
+    let result = pck.payload_with_timestamp(100, &Bytes::from_static(&[0x04]), 2920)?;
+    let blocks = RedPacket::parse(&result[0])?;
+    assert_eq!(
+        blocks,
+        vec![
+            RedBlock {
+                payload_type: 111,
+                timestamp_offset: 1920,
+                payload: Bytes::from_static(&[0x01, 0x02]),
+            },
+            RedBlock {
+                payload_type: 111,
+                timestamp_offset: 960,
+                payload: Bytes::from_static(&[0x03]),
+            },
+            RedBlock {
+                payload_type: 111,
+                timestamp_offset: 0,
+                payload: Bytes::from_static(&[0x04]),
+            },
+        ]
+    );
+    let result = pck.payload_with_timestamp(100, &Bytes::from_static(&[0x05]), 3880)?;
+    let blocks = RedPacket::parse(&result[0])?;
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[0].payload, Bytes::from_static(&[0x03]));
+
+    // the oldest frames are dropped to fit the mtu
+    let result = pck.payload_with_timestamp(8, &Bytes::from_static(&[0x06]), 4840)?;
+    let blocks = RedPacket::parse(&result[0])?;
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].payload, Bytes::from_static(&[0x05]));
+    assert_eq!(blocks[0].timestamp_offset, 960);
+
+    // frames older than the timestamp offset allows aren't repeated
+    let result = pck.payload_with_timestamp(100, &Bytes::from_static(&[0x07]), 30000)?;
+    assert_eq!(result, vec![Bytes::from_static(&[111, 0x07])]);
+
+    // without timestamps only the primary block is sent
+    let result = pck.payload(100, &Bytes::from_static(&[0x08]))?;
+    assert_eq!(result, vec![Bytes::from_static(&[111, 0x08])]);
+
+    Ok(())
+}
+
+#[test]
+fn test_red_packetizer() -> Result<()> {
+    let mut packetizer = crate::packetizer::new_packetizer(
+        100,
+        63,
+        0x1234ABCD,
+        Box::new(RedPayloader::new(Box::<OpusPayloader>::default(), 111, 1)),
+        Box::new(crate::sequence::new_random_sequencer()),
+        48000,
+    );
+
+    let first = packetizer.packetize(&Bytes::from_static(&[0x01]), 960)?;
+    let second = packetizer.packetize(&Bytes::from_static(&[0x02]), 960)?;
+    assert_eq!(second[0].header.payload_type, 63);
+
+    let packets = RedPacket::split_packet(&second[0])?;
+    assert_eq!(packets.len(), 2);
+    assert_eq!(packets[0].header.payload_type, 111);
+    assert_eq!(packets[0].header.timestamp, first[0].header.timestamp);
+    assert_eq!(
+        packets[0].header.sequence_number,
+        first[0].header.sequence_number
+    );
+    assert_eq!(packets[0].payload, Bytes::from_static(&[0x01]));
+    assert_eq!(packets[1].header.payload_type, 111);
+    assert_eq!(packets[1].header.timestamp, second[0].header.timestamp);
+    assert_eq!(
+        packets[1].header.sequence_number,
+        second[0].header.sequence_number
+    );
+    assert_eq!(packets[1].payload, Bytes::from_static(&[0x02]));
+
+    Ok(())
+}
+
+#[test]
+fn test_red_depacketize() -> Result<()> {
+    let mut pck = RedPacket::default();
+
+    // Empty packet
+    let result = pck.depacketize(&Bytes::from_static(&[]));
+    assert!(result.is_err(), "Result should be err in case of error");
+
+    // Redundant block header without primary block header
+    let result = pck.depacketize(&Bytes::from_static(&[0x80 | 111, 0x0F, 0x00, 0x01]));
+    assert!(result.is_err(), "Result should be err in case of error");
+
+    // Redundant block longer than the packet
+    let result = pck.depacketize(&Bytes::from_static(&[
+        0x80 | 111,
+        0x0F,
+        0x00,
+        0x05,
+        111,
+        0xAA,
+    ]));
+    assert!(result.is_err(), "Result should be err in case of error");
+
+    // Primary block only
+    let payload = pck.depacketize(&Bytes::from_static(&[111, 0xAA, 0xBB]))?;
+    assert_eq!(payload, Bytes::from_static(&[0xAA, 0xBB]));
+    assert_eq!(pck.blocks.len(), 1);
+
+    // Redundant and primary blocks
+    let payload = pck.depacketize(&Bytes::from_static(&[
+        0x80 | 111,
+        0x0F,
+        0x00,
+        0x01,
+        111,
+        0xAA,
+        0xBB,
+        0xCC,
+    ]))?;
+    assert_eq!(payload, Bytes::from_static(&[0xBB, 0xCC]));
+    assert_eq!(
+        pck.blocks[0],
+        RedBlock {
+            payload_type: 111,
+            timestamp_offset: 960,
+            payload: Bytes::from_static(&[0xAA]),
+        }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use bytes::BytesMut;
+
+use super::Vp8Packet;
+use crate::error::Result;
+use crate::packet::Packet;
+use crate::packetizer::Depacketizer;
+use crate::sequence::SequenceMapper;
+
+/// Vp8LayerFilter forwards the temporal layers of a VP8 stream up to a target layer,
+/// for selective forwarding units. It drops the packets of the higher layers and
+/// rewrites the sequence numbers, picture ids and TL0PICIDX of the forwarded packets
+/// to stay continuous, so receivers see neither the dropped packets as lost nor the
+/// switches between encodings.
+///
+/// The filter follows the SSRC of the packets it gets: packets of another SSRC are
+/// dropped until one of them starts a key frame, from which on that SSRC is forwarded.
+/// To switch simulcast encodings, pass the packets of both until `ssrc` returns the new
+/// one.
+#[derive(Debug, Clone)]
+pub struct Vp8LayerFilter {
+    target_temporal_id: u8,
+    temporal_id: Option<u8>,
+    ssrc: Option<u32>,
+    sequence_numbers: SequenceMapper,
+    picture_ids: SequenceMapper,
+    tl0_pic_idxs: SequenceMapper,
+}
+
+impl Vp8LayerFilter {
+    /// new creates a Vp8LayerFilter forwarding up to the given temporal layer.
+    pub fn new(target_temporal_id: u8) -> Self {
+        Vp8LayerFilter {
+            target_temporal_id,
+            temporal_id: None,
+            ssrc: None,
+            sequence_numbers: SequenceMapper::new(16),
+            picture_ids: SequenceMapper::new(15),
+            tl0_pic_idxs: SequenceMapper::new(8),
+        }
+    }
+
+    /// set_target_temporal_id changes the target layer. A lower layer is forwarded from
+    /// the next frame on, a higher one from its next layer sync frame.
+    pub fn set_target_temporal_id(&mut self, target_temporal_id: u8) {
+        self.target_temporal_id = target_temporal_id;
+    }
+
+    /// temporal_id returns the highest forwarded temporal layer, or None before the
+    /// first key frame.
+    pub fn temporal_id(&self) -> Option<u8> {
+        self.temporal_id
+    }
+
+    /// ssrc returns the SSRC of the forwarded packets, or None before the first key frame.
+    pub fn ssrc(&self) -> Option<u32> {
+        self.ssrc
+    }
+
+    /// filter returns whether to forward the packet, and rewrites it if so.
+    pub fn filter(&mut self, packet: &mut Packet) -> Result<bool> {
+        let mut vp8 = Vp8Packet::default();
+        let frame = vp8.depacketize(&packet.payload)?;
+        let start_of_frame = vp8.s == 1 && vp8.pid == 0;
+        let key_frame = start_of_frame && frame.first().map_or(false, |b| b & 0x01 == 0);
+        let tid = if vp8.t == 1 { vp8.tid } else { 0 };
+        let picture_id_index = 1 + vp8.x as usize;
+        let long_picture_id = vp8.i == 1 && packet.payload[picture_id_index] & 0x80 != 0;
+
+        if self.ssrc != Some(packet.header.ssrc) {
+            if !key_frame {
+                return Ok(false);
+            }
+            self.ssrc = Some(packet.header.ssrc);
+            self.sequence_numbers.sync(16);
+            self.picture_ids.sync(if long_picture_id { 15 } else { 7 });
+            self.tl0_pic_idxs.sync(8);
+        }
+
+        if key_frame {
+            self.temporal_id = Some(self.target_temporal_id);
+        } else if start_of_frame {
+            if let Some(temporal_id) = self.temporal_id {
+                if self.target_temporal_id < temporal_id {
+                    self.temporal_id = Some(self.target_temporal_id);
+                } else if tid > temporal_id && tid <= self.target_temporal_id && vp8.y == 1 {
+                    self.temporal_id = Some(tid);
+                }
+            }
+        }
+
+        let temporal_id = match self.temporal_id {
+            Some(temporal_id) => temporal_id,
+            None => return Ok(false),
+        };
+        if tid > temporal_id {
+            self.sequence_numbers
+                .skip(packet.header.sequence_number as u32);
+            if vp8.i == 1 {
+                self.picture_ids.skip(vp8.picture_id as u32);
+            }
+            return Ok(false);
+        }
+
+        let sequence_number = match self
+            .sequence_numbers
+            .map(packet.header.sequence_number as u32)
+        {
+            Some(sequence_number) => sequence_number as u16,
+            None => return Ok(false),
+        };
+
+        let mut payload = BytesMut::from(&packet.payload[..]);
+        let mut index = picture_id_index;
+        if vp8.i == 1 {
+            if let Some(picture_id) = self.picture_ids.map(vp8.picture_id as u32) {
+                if long_picture_id {
+                    payload[index] = 0x80 | (picture_id >> 8) as u8 & 0x7F;
+                    payload[index + 1] = picture_id as u8;
+                } else {
+                    payload[index] = picture_id as u8 & 0x7F;
+                }
+            }
+            index += if long_picture_id { 2 } else { 1 };
+        }
+        if vp8.l == 1 {
+            if let Some(tl0_pic_idx) = self.tl0_pic_idxs.map(vp8.tl0_pic_idx as u32) {
+                payload[index] = tl0_pic_idx as u8;
+            }
+        }
+
+        packet.header.sequence_number = sequence_number;
+        packet.payload = payload.freeze();
+
+        Ok(true)
+    }
+}
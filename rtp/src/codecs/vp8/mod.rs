@@ -1,3 +1,4 @@
+mod layer_filter;
 #[cfg(test)]
 mod vp8_test;
 
@@ -6,6 +7,8 @@ use bytes::{Buf, BufMut, Bytes, BytesMut};
 use crate::error::{Error, Result};
 use crate::packetizer::{Depacketizer, Payloader};
 
+pub use layer_filter::Vp8LayerFilter;
+
 pub const VP8_HEADER_SIZE: usize = 1;
 
 /// Vp8Payloader payloads VP8 packets
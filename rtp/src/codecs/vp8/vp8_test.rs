@@ -1,4 +1,6 @@
 use super::*;
+use crate::header::Header;
+use crate::packet::Packet;
 
 #[test]
 fn test_vp8_unmarshal() -> Result<()> {
@@ -223,3 +225,112 @@ fn test_vp8_partition_head_checker_is_partition_head() -> Result<()> {
 
     Ok(())
 }
+
+fn vp8_rtp_packet(
+    ssrc: u32,
+    sequence_number: u16,
+    picture_id: u16,
+    tl0_pic_idx: u8,
+    tid: u8,
+    layer_sync: bool,
+    key_frame: bool,
+) -> Packet {
+    Packet {
+        header: Header {
+            ssrc,
+            sequence_number,
+            ..Default::default()
+        },
+        payload: Bytes::from(vec![
+            0x90,
+            0xe0,
+            0x80 | (picture_id >> 8) as u8,
+            picture_id as u8,
+            tl0_pic_idx,
+            tid << 6 | (layer_sync as u8) << 5,
+            if key_frame { 0x00 } else { 0x01 },
+            0xaa,
+        ]),
+    }
+}
+
+/// Filters the packets and returns the sequence number, picture id and TL0PICIDX of
+/// the forwarded ones.
+fn filter_vp8(filter: &mut Vp8LayerFilter, packets: Vec<Packet>) -> Result<Vec<(u16, u16, u8)>> {
+    let mut forwarded = vec![];
+    for mut packet in packets {
+        if filter.filter(&mut packet)? {
+            let mut vp8 = Vp8Packet::default();
+            vp8.depacketize(&packet.payload)?;
+            forwarded.push((
+                packet.header.sequence_number,
+                vp8.picture_id,
+                vp8.tl0_pic_idx,
+            ));
+        }
+    }
+    Ok(forwarded)
+}
+
+#[test]
+fn test_vp8_layer_filter() -> Result<()> {
+    let mut filter = Vp8LayerFilter::new(1);
+
+    // Temporal layers 0 2 1 2 0 2 1 2, dropping layer 2.
+    let tids = [0, 2, 1, 2, 0, 2, 1, 2];
+    let packets = tids
+        .iter()
+        .enumerate()
+        .map(|(i, tid)| {
+            vp8_rtp_packet(
+                1,
+                0xfffe_u16.wrapping_add(i as u16),
+                (0x7ffe + i as u16) & 0x7fff,
+                (i / 4) as u8,
+                *tid,
+                false,
+                i == 0,
+            )
+        })
+        .collect();
+    assert_eq!(
+        filter_vp8(&mut filter, packets)?,
+        vec![
+            (0xfffe, 0x7ffe, 0),
+            (0xffff, 0x7fff, 0),
+            (0, 0, 1),
+            (1, 1, 1),
+        ]
+    );
+    assert_eq!(filter.temporal_id(), Some(1));
+
+    // Switching up waits for a layer sync frame.
+    filter.set_target_temporal_id(2);
+    let packets = vec![
+        vp8_rtp_packet(1, 6, 6, 2, 0, false, false),
+        vp8_rtp_packet(1, 7, 7, 2, 2, false, false),
+        vp8_rtp_packet(1, 8, 8, 2, 1, false, false),
+        vp8_rtp_packet(1, 9, 9, 2, 2, true, false),
+    ];
+    assert_eq!(
+        filter_vp8(&mut filter, packets)?,
+        vec![(2, 2, 2), (3, 3, 2), (4, 4, 2)]
+    );
+    assert_eq!(filter.temporal_id(), Some(2));
+
+    // Another encoding is forwarded from its first key frame on, continuing the numbers.
+    let packets = vec![
+        vp8_rtp_packet(2, 500, 300, 40, 0, false, false),
+        vp8_rtp_packet(1, 10, 10, 3, 0, false, false),
+        vp8_rtp_packet(2, 501, 301, 41, 0, false, true),
+        vp8_rtp_packet(1, 11, 11, 3, 2, false, false),
+        vp8_rtp_packet(2, 502, 302, 41, 2, false, false),
+    ];
+    assert_eq!(
+        filter_vp8(&mut filter, packets)?,
+        vec![(5, 5, 3), (6, 6, 4), (7, 7, 4)]
+    );
+    assert_eq!(filter.ssrc(), Some(2));
+
+    Ok(())
+}
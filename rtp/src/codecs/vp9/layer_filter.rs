@@ -0,0 +1,163 @@
+use bytes::BytesMut;
+
+use super::Vp9Packet;
+use crate::error::Result;
+use crate::packet::Packet;
+use crate::packetizer::Depacketizer;
+use crate::sequence::SequenceMapper;
+
+/// Vp9LayerFilter forwards the spatial and temporal layers of a VP9 SVC stream up to a
+/// target layer, for selective forwarding units. It drops the packets of the higher
+/// layers and rewrites the sequence numbers, picture ids, TL0PICIDX and reference
+/// indices of the forwarded packets to stay continuous, and sets the marker bit on the
+/// last packet of the highest forwarded spatial layer.
+///
+/// The filter follows the SSRC of the packets it gets: packets of another SSRC are
+/// dropped until one of them starts a key frame, from which on that SSRC is forwarded.
+/// To switch simulcast encodings, pass the packets of both until `ssrc` returns the new
+/// one.
+#[derive(Debug, Clone)]
+pub struct Vp9LayerFilter {
+    target_layer: (u8, u8),
+    layer: Option<(u8, u8)>,
+    ssrc: Option<u32>,
+    sequence_numbers: SequenceMapper,
+    picture_ids: SequenceMapper,
+    tl0_pic_idxs: SequenceMapper,
+}
+
+impl Vp9LayerFilter {
+    /// new creates a Vp9LayerFilter forwarding up to the given spatial and temporal layer.
+    pub fn new(spatial_id: u8, temporal_id: u8) -> Self {
+        Vp9LayerFilter {
+            target_layer: (spatial_id, temporal_id),
+            layer: None,
+            ssrc: None,
+            sequence_numbers: SequenceMapper::new(16),
+            picture_ids: SequenceMapper::new(15),
+            tl0_pic_idxs: SequenceMapper::new(8),
+        }
+    }
+
+    /// set_target_layer changes the target layer. Lower layers are forwarded from the
+    /// next picture on. A higher spatial layer is forwarded from its next frame that is
+    /// not inter-picture predicted, a higher temporal layer from its next switching up
+    /// point.
+    pub fn set_target_layer(&mut self, spatial_id: u8, temporal_id: u8) {
+        self.target_layer = (spatial_id, temporal_id);
+    }
+
+    /// layer returns the highest forwarded spatial and temporal layer, or None before
+    /// the first key frame.
+    pub fn layer(&self) -> Option<(u8, u8)> {
+        self.layer
+    }
+
+    /// ssrc returns the SSRC of the forwarded packets, or None before the first key frame.
+    pub fn ssrc(&self) -> Option<u32> {
+        self.ssrc
+    }
+
+    /// filter returns whether to forward the packet, and rewrites it if so.
+    pub fn filter(&mut self, packet: &mut Packet) -> Result<bool> {
+        let mut vp9 = Vp9Packet::default();
+        vp9.depacketize(&packet.payload)?;
+        let (sid, tid) = if vp9.l { (vp9.sid, vp9.tid) } else { (0, 0) };
+        let start_of_picture = vp9.b && sid == 0;
+        let key_frame = start_of_picture && !vp9.p;
+        let long_picture_id = vp9.i && packet.payload[1] & 0x80 != 0;
+
+        if self.ssrc != Some(packet.header.ssrc) {
+            if !key_frame {
+                return Ok(false);
+            }
+            self.ssrc = Some(packet.header.ssrc);
+            self.sequence_numbers.sync(16);
+            self.picture_ids.sync(if long_picture_id { 15 } else { 7 });
+            self.tl0_pic_idxs.sync(8);
+        }
+
+        let (target_spatial_id, target_temporal_id) = self.target_layer;
+        if key_frame {
+            self.layer = Some(self.target_layer);
+        } else if let Some((mut spatial_id, mut temporal_id)) = self.layer {
+            if start_of_picture {
+                spatial_id = spatial_id.min(target_spatial_id);
+                if target_temporal_id < temporal_id {
+                    temporal_id = target_temporal_id;
+                } else if tid > temporal_id && tid <= target_temporal_id && vp9.u {
+                    temporal_id = tid;
+                }
+            }
+            if vp9.b && !vp9.p && sid == spatial_id + 1 && sid <= target_spatial_id {
+                spatial_id = sid;
+            }
+            self.layer = Some((spatial_id, temporal_id));
+        }
+
+        let (spatial_id, temporal_id) = match self.layer {
+            Some(layer) => layer,
+            None => return Ok(false),
+        };
+        if sid > spatial_id || tid > temporal_id {
+            self.sequence_numbers
+                .skip(packet.header.sequence_number as u32);
+            // Only dropping a temporal layer drops whole pictures.
+            if vp9.i && tid > temporal_id {
+                self.picture_ids.skip(vp9.picture_id as u32);
+            }
+            return Ok(false);
+        }
+
+        let sequence_number = match self
+            .sequence_numbers
+            .map(packet.header.sequence_number as u32)
+        {
+            Some(sequence_number) => sequence_number as u16,
+            None => return Ok(false),
+        };
+
+        let mut payload = BytesMut::from(&packet.payload[..]);
+        let mut index = 1;
+        if vp9.i {
+            let modulus = 1u32 << if long_picture_id { 15 } else { 7 };
+            if let Some(picture_id) = self.picture_ids.map(vp9.picture_id as u32) {
+                if long_picture_id {
+                    payload[index] = 0x80 | (picture_id >> 8) as u8 & 0x7F;
+                    payload[index + 1] = picture_id as u8;
+                } else {
+                    payload[index] = picture_id as u8 & 0x7F;
+                }
+
+                if vp9.f && vp9.p {
+                    // The reference indices follow the layer indices, if any.
+                    let first_pdiff_index =
+                        index + if long_picture_id { 2 } else { 1 } + vp9.l as usize;
+                    for (i, pdiff) in vp9.pdiff.iter().enumerate() {
+                        let reference = (vp9.picture_id as u32 + modulus - *pdiff as u32) % modulus;
+                        if let Some(reference) = self.picture_ids.map(reference) {
+                            let pdiff = (picture_id + modulus - reference) % modulus;
+                            if (1..0x80).contains(&pdiff) {
+                                let pdiff_index = first_pdiff_index + i;
+                                payload[pdiff_index] =
+                                    (pdiff as u8) << 1 | payload[pdiff_index] & 0x01;
+                            }
+                        }
+                    }
+                }
+            }
+            index += if long_picture_id { 2 } else { 1 };
+        }
+        if vp9.l && !vp9.f {
+            if let Some(tl0_pic_idx) = self.tl0_pic_idxs.map(vp9.tl0picidx as u32) {
+                payload[index + 1] = tl0_pic_idx as u8;
+            }
+        }
+
+        packet.header.sequence_number = sequence_number;
+        packet.header.marker |= vp9.e && sid == spatial_id;
+        packet.payload = payload.freeze();
+
+        Ok(true)
+    }
+}
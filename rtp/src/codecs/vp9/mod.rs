@@ -1,3 +1,4 @@
+mod layer_filter;
 #[cfg(test)]
 mod vp9_test;
 
@@ -9,6 +10,8 @@ use bytes::{Buf, BufMut, Bytes, BytesMut};
 use crate::error::{Error, Result};
 use crate::packetizer::{Depacketizer, Payloader};
 
+pub use layer_filter::Vp9LayerFilter;
+
 /// Flexible mode 15 bit picture ID
 const VP9HEADER_SIZE: usize = 3;
 const MAX_SPATIAL_LAYERS: u8 = 5;
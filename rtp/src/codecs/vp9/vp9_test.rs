@@ -1,4 +1,6 @@
 use super::*;
+use crate::header::Header;
+use crate::packet::Packet;
 
 #[test]
 fn test_vp9_packet_unmarshal() -> Result<()> {
@@ -362,3 +364,89 @@ fn test_vp9_partition_head_checker_is_partition_head() -> Result<()> {
 
     Ok(())
 }
+
+#[allow(clippy::too_many_arguments)]
+fn vp9_rtp_packet(
+    sequence_number: u16,
+    picture_id: u16,
+    tl0_pic_idx: u8,
+    sid: u8,
+    tid: u8,
+    switching_up_point: bool,
+    inter_picture_predicted: bool,
+    end_of_frame: bool,
+) -> Packet {
+    Packet {
+        header: Header {
+            ssrc: 1,
+            sequence_number,
+            ..Default::default()
+        },
+        payload: Bytes::from(vec![
+            0xa8 | (inter_picture_predicted as u8) << 6 | (end_of_frame as u8) << 2,
+            0x80 | (picture_id >> 8) as u8,
+            picture_id as u8,
+            tid << 5 | (switching_up_point as u8) << 4 | sid << 1,
+            tl0_pic_idx,
+            0xaa,
+        ]),
+    }
+}
+
+/// Filters the packets and returns the sequence number, picture id, TL0PICIDX and
+/// marker of the forwarded ones.
+fn filter_vp9(
+    filter: &mut Vp9LayerFilter,
+    packets: Vec<Packet>,
+) -> Result<Vec<(u16, u16, u8, bool)>> {
+    let mut forwarded = vec![];
+    for mut packet in packets {
+        if filter.filter(&mut packet)? {
+            let mut vp9 = Vp9Packet::default();
+            vp9.depacketize(&packet.payload)?;
+            forwarded.push((
+                packet.header.sequence_number,
+                vp9.picture_id,
+                vp9.tl0picidx,
+                packet.header.marker,
+            ));
+        }
+    }
+    Ok(forwarded)
+}
+
+#[test]
+fn test_vp9_layer_filter() -> Result<()> {
+    let mut filter = Vp9LayerFilter::new(0, 0);
+
+    // Pictures of two spatial and two temporal layers, one packet per layer frame.
+    let packets = vec![
+        vp9_rtp_packet(10, 100, 5, 0, 0, false, false, true),
+        vp9_rtp_packet(11, 100, 5, 1, 0, false, false, true),
+        vp9_rtp_packet(12, 101, 5, 0, 1, true, true, true),
+        vp9_rtp_packet(13, 101, 5, 1, 1, true, true, true),
+        vp9_rtp_packet(14, 102, 6, 0, 0, false, true, true),
+        vp9_rtp_packet(15, 102, 6, 1, 0, false, true, true),
+    ];
+    assert_eq!(
+        filter_vp9(&mut filter, packets)?,
+        vec![(10, 100, 5, true), (11, 101, 6, true)]
+    );
+
+    // A higher spatial layer needs a frame which is not inter-picture predicted, a
+    // higher temporal layer a switching up point.
+    filter.set_target_layer(1, 1);
+    let packets = vec![
+        vp9_rtp_packet(16, 103, 6, 0, 1, true, true, true),
+        vp9_rtp_packet(17, 103, 6, 1, 1, true, true, true),
+        vp9_rtp_packet(18, 104, 7, 0, 0, false, true, true),
+        vp9_rtp_packet(19, 104, 7, 1, 0, false, false, true),
+    ];
+    assert_eq!(
+        filter_vp9(&mut filter, packets)?,
+        vec![(12, 102, 6, true), (13, 103, 7, true), (14, 103, 7, true)]
+    );
+    assert_eq!(filter.layer(), Some((1, 1)));
+
+    Ok(())
+}
@@ -54,6 +54,18 @@ pub enum Error {
     HeaderExtensionPayloadNot32BitWords,
     #[error("audio level overflow")]
     AudioLevelOverflow,
+    #[error("playout delay overflow")]
+    PlayoutDelayOverflow,
+    #[error("SDES value is not valid UTF-8")]
+    ErrSdesValueNotUtf8,
+    #[error("invalid frame dependency template id")]
+    ErrInvalidTemplateId,
+    #[error("dependency descriptor without a template structure")]
+    ErrDependencyDescriptorNoStructure,
+    #[error("invalid frame dependency structure")]
+    ErrInvalidDependencyStructure,
+    #[error("frame dependencies do not match the frame dependency structure")]
+    ErrInvalidFrameDependencies,
     #[error("payload is not large enough")]
     PayloadIsNotLargeEnough,
     #[error("STAP-A declared size({0}) is larger than buffer({1})")]
@@ -0,0 +1,55 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use bytes::{Bytes, BytesMut};
+
+use super::*;
+use crate::error::Result;
+
+#[test]
+fn test_abs_capture_time_extension_round_trip() -> Result<()> {
+    let tests = vec![
+        (
+            AbsCaptureTimeExtension {
+                timestamp: 0x0123456789abcdef,
+                estimated_capture_clock_offset: None,
+            },
+            Bytes::from_static(&[0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef]),
+        ),
+        (
+            AbsCaptureTimeExtension {
+                timestamp: 0x0123456789abcdef,
+                estimated_capture_clock_offset: Some(-2),
+            },
+            Bytes::from_static(&[
+                0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                0xff, 0xfe,
+            ]),
+        ),
+    ];
+
+    for (ext, raw) in tests {
+        let mut dst = BytesMut::with_capacity(ext.marshal_size());
+        dst.resize(ext.marshal_size(), 0);
+        ext.marshal_to(&mut dst)?;
+        assert_eq!(raw, dst.freeze());
+
+        let buf = &mut raw.clone();
+        assert_eq!(AbsCaptureTimeExtension::unmarshal(buf)?, ext);
+    }
+
+    let mut buf = &vec![0u8; 7][..];
+    assert!(AbsCaptureTimeExtension::unmarshal(&mut buf).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_abs_capture_time_extension_capture_time() {
+    let capture_time = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+    let ext = AbsCaptureTimeExtension::new(capture_time);
+    let diff = ext
+        .capture_time()
+        .duration_since(capture_time)
+        .unwrap_or_else(|e| e.duration());
+    assert!(diff < Duration::from_micros(1), "{diff:?}");
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod abs_capture_time_extension_test;
+
+use std::time::SystemTime;
+
+use bytes::{Buf, BufMut};
+use serde::{Deserialize, Serialize};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+use crate::extension::abs_send_time_extension::{ntp2unix, unix2ntp};
+
+pub const ABS_CAPTURE_TIME_EXTENSION_SIZE: usize = 8;
+pub const ABS_CAPTURE_TIME_EXTENDED_EXTENSION_SIZE: usize = 16;
+
+/// AbsCaptureTimeExtension is a extension payload format in
+/// http://www.webrtc.org/experiments/rtp-hdrext/abs-capture-time
+///
+/// It carries the NTP time at which the first sample of a frame was captured, so
+/// receivers can synchronize streams relayed by different mixers or SFUs.
+///
+/// Data layout of the shortened version:
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  ID   | len=7 |     absolute capture timestamp (bit 0-23)     |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |             absolute capture timestamp (bit 24-55)            |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  ... (56-63)  |
+/// +-+-+-+-+-+-+-+-+
+///
+/// The extended version has len=15 and appends the 64 bit estimated capture clock
+/// offset.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone, Serialize, Deserialize)]
+pub struct AbsCaptureTimeExtension {
+    /// NTP timestamp (UQ32.32) of the capture on the clock of the capturing system
+    pub timestamp: u64,
+    /// Estimated offset (Q32.32) from the clock of the capturing system to the clock
+    /// of the sender, if known
+    pub estimated_capture_clock_offset: Option<i64>,
+}
+
+impl AbsCaptureTimeExtension {
+    /// new makes a new AbsCaptureTimeExtension from the time of the capture.
+    pub fn new(capture_time: SystemTime) -> Self {
+        AbsCaptureTimeExtension {
+            timestamp: unix2ntp(capture_time),
+            estimated_capture_clock_offset: None,
+        }
+    }
+
+    /// capture_time returns the time of the capture on the clock of the capturing system.
+    pub fn capture_time(&self) -> SystemTime {
+        ntp2unix(self.timestamp)
+    }
+}
+
+impl Unmarshal for AbsCaptureTimeExtension {
+    /// Unmarshal parses the passed byte slice and stores the result in the members.
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self, util::Error>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        if raw_packet.remaining() < ABS_CAPTURE_TIME_EXTENSION_SIZE {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        let timestamp = raw_packet.get_u64();
+        let estimated_capture_clock_offset = if raw_packet.remaining()
+            >= ABS_CAPTURE_TIME_EXTENDED_EXTENSION_SIZE - ABS_CAPTURE_TIME_EXTENSION_SIZE
+        {
+            Some(raw_packet.get_i64())
+        } else {
+            None
+        };
+
+        Ok(AbsCaptureTimeExtension {
+            timestamp,
+            estimated_capture_clock_offset,
+        })
+    }
+}
+
+impl MarshalSize for AbsCaptureTimeExtension {
+    /// MarshalSize returns the size of the AbsCaptureTimeExtension once marshaled.
+    fn marshal_size(&self) -> usize {
+        if self.estimated_capture_clock_offset.is_some() {
+            ABS_CAPTURE_TIME_EXTENDED_EXTENSION_SIZE
+        } else {
+            ABS_CAPTURE_TIME_EXTENSION_SIZE
+        }
+    }
+}
+
+impl Marshal for AbsCaptureTimeExtension {
+    /// MarshalTo serializes the members to buffer.
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize, util::Error> {
+        if buf.remaining_mut() < self.marshal_size() {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        buf.put_u64(self.timestamp);
+        if let Some(offset) = self.estimated_capture_clock_offset {
+            buf.put_i64(offset);
+        }
+
+        Ok(self.marshal_size())
+    }
+}
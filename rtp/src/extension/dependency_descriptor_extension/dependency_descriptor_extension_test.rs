@@ -0,0 +1,388 @@
+use std::sync::Arc;
+
+use bytes::BytesMut;
+
+use super::*;
+use crate::error::Result;
+
+#[test]
+fn test_dependency_descriptor_extension_round_trip() -> Result<()> {
+    let tests = vec![
+        (
+            DependencyDescriptorExtension {
+                start_of_frame: true,
+                end_of_frame: false,
+                frame_dependency_template_id: 0x12,
+                frame_number: 0x3456,
+                extended: Bytes::new(),
+            },
+            Bytes::from_static(&[0x92, 0x34, 0x56]),
+        ),
+        (
+            DependencyDescriptorExtension {
+                start_of_frame: false,
+                end_of_frame: true,
+                frame_dependency_template_id: DEPENDENCY_DESCRIPTOR_MAX_TEMPLATE_ID,
+                frame_number: 0xffff,
+                extended: Bytes::from_static(&[0x80, 0x01]),
+            },
+            Bytes::from_static(&[0x7f, 0xff, 0xff, 0x80, 0x01]),
+        ),
+    ];
+
+    for (ext, raw) in tests {
+        let mut dst = BytesMut::with_capacity(ext.marshal_size());
+        dst.resize(ext.marshal_size(), 0);
+        ext.marshal_to(&mut dst)?;
+        assert_eq!(raw, dst.freeze());
+
+        let buf = &mut raw.clone();
+        assert_eq!(DependencyDescriptorExtension::unmarshal(buf)?, ext);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_dependency_descriptor_extension_errors() {
+    let mut buf = &[0u8; 2][..];
+    assert!(DependencyDescriptorExtension::unmarshal(&mut buf).is_err());
+
+    let ext = DependencyDescriptorExtension {
+        frame_dependency_template_id: DEPENDENCY_DESCRIPTOR_MAX_TEMPLATE_ID + 1,
+        ..Default::default()
+    };
+    let mut dst = BytesMut::with_capacity(ext.marshal_size());
+    dst.resize(ext.marshal_size(), 0);
+    assert_eq!(
+        Error::ErrInvalidTemplateId,
+        ext.marshal_to(&mut dst).unwrap_err()
+    );
+}
+
+fn dtis(s: &str) -> Vec<DecodeTargetIndication> {
+    s.chars()
+        .map(|c| match c {
+            'D' => DecodeTargetIndication::Discardable,
+            'S' => DecodeTargetIndication::Switch,
+            'R' => DecodeTargetIndication::Required,
+            _ => DecodeTargetIndication::NotPresent,
+        })
+        .collect()
+}
+
+fn template(
+    spatial_id: u8,
+    temporal_id: u8,
+    indications: &str,
+    frame_diffs: &[u16],
+    chain_diffs: &[u16],
+) -> FrameDependencyTemplate {
+    FrameDependencyTemplate {
+        spatial_id,
+        temporal_id,
+        decode_target_indications: dtis(indications),
+        frame_diffs: frame_diffs.to_vec(),
+        chain_diffs: chain_diffs.to_vec(),
+    }
+}
+
+/// One spatial and three temporal layers, decode targets T0, T0T1 and T0T1T2.
+fn l1t3() -> FrameDependencyStructure {
+    FrameDependencyStructure {
+        structure_id: 62,
+        num_decode_targets: 3,
+        num_chains: 1,
+        decode_target_protected_by_chain: vec![0, 0, 0],
+        resolutions: vec![],
+        templates: vec![
+            template(0, 0, "SSS", &[], &[0]),
+            template(0, 0, "SSS", &[4], &[4]),
+            template(0, 1, "-DS", &[2], &[2]),
+            template(0, 2, "--D", &[1], &[1]),
+            template(0, 2, "--D", &[1], &[3]),
+        ],
+    }
+}
+
+/// Two spatial layers, decode targets S0 and S0S1.
+fn l2t1() -> FrameDependencyStructure {
+    FrameDependencyStructure {
+        structure_id: 0,
+        num_decode_targets: 2,
+        num_chains: 2,
+        decode_target_protected_by_chain: vec![0, 1],
+        resolutions: vec![
+            RenderResolution {
+                width: 320,
+                height: 180,
+            },
+            RenderResolution {
+                width: 640,
+                height: 360,
+            },
+        ],
+        templates: vec![
+            template(0, 0, "SS", &[], &[0, 0]),
+            template(0, 0, "SR", &[2], &[2, 1]),
+            template(1, 0, "-S", &[1], &[1, 1]),
+            template(1, 0, "-R", &[2, 1], &[1, 1]),
+        ],
+    }
+}
+
+fn descriptor(
+    frame_number: u16,
+    frame_dependencies: FrameDependencyTemplate,
+    attached_structure: Option<&FrameDependencyStructure>,
+) -> DependencyDescriptor {
+    DependencyDescriptor {
+        start_of_frame: true,
+        end_of_frame: true,
+        frame_number,
+        frame_dependencies,
+        attached_structure: attached_structure.map(|s| Arc::new(s.clone())),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_dependency_descriptor_write_structure() -> Result<()> {
+    let structure = FrameDependencyStructure {
+        num_decode_targets: 1,
+        templates: vec![template(0, 0, "S", &[], &[])],
+        ..Default::default()
+    };
+    let mut writer = DependencyDescriptorWriter::new();
+    let ext = writer.write(&descriptor(
+        1,
+        structure.templates[0].clone(),
+        Some(&structure),
+    ))?;
+
+    // Flags 10000, structure id 0, one decode target, one template, S, no frame
+    // diffs, no chains and no resolutions.
+    assert_eq!(ext.frame_dependency_template_id, 0);
+    assert_eq!(ext.extended, Bytes::from_static(&[0x80, 0x00, 0xe0]));
+
+    let mut reader = DependencyDescriptorReader::new();
+    let read = reader.read(&ext)?;
+    assert_eq!(read.attached_structure.as_deref(), Some(&structure));
+    assert_eq!(read.frame_dependencies, structure.templates[0]);
+    assert_eq!(read.active_decode_targets_bitmask, Some(1));
+
+    Ok(())
+}
+
+#[test]
+fn test_dependency_descriptor_round_trip() -> Result<()> {
+    for structure in [l1t3(), l2t1()] {
+        let mut writer = DependencyDescriptorWriter::new();
+        let mut reader = DependencyDescriptorReader::new();
+
+        let mut descriptors = vec![descriptor(
+            0xfffe,
+            structure.templates[0].clone(),
+            Some(&structure),
+        )];
+        for (i, template) in structure.templates.iter().enumerate().skip(1) {
+            descriptors.push(descriptor(i as u16, template.clone(), None));
+        }
+
+        // Custom decode target indications, frame diffs and chain diffs.
+        let mut custom = structure.templates[1].clone();
+        custom.decode_target_indications[0] = DecodeTargetIndication::Required;
+        custom.frame_diffs = vec![1, 17, 300, 4096];
+        custom.chain_diffs = vec![255; structure.num_chains];
+        descriptors.push(descriptor(100, custom, None));
+
+        let mut active = descriptor(101, structure.templates[1].clone(), None);
+        active.active_decode_targets_bitmask = Some(0b1);
+        descriptors.push(active);
+
+        for mut descriptor in descriptors {
+            let ext = writer.write(&descriptor)?;
+
+            let mut raw = BytesMut::with_capacity(ext.marshal_size());
+            raw.resize(ext.marshal_size(), 0);
+            ext.marshal_to(&mut raw)?;
+            let ext = DependencyDescriptorExtension::unmarshal(&mut raw.freeze())?;
+
+            if descriptor.attached_structure.is_some() {
+                descriptor.active_decode_targets_bitmask =
+                    Some((1 << structure.num_decode_targets) - 1);
+            }
+            if !structure.resolutions.is_empty() {
+                descriptor.resolution =
+                    Some(structure.resolutions[descriptor.frame_dependencies.spatial_id as usize]);
+            }
+            assert_eq!(reader.read(&ext)?, descriptor);
+        }
+        assert_eq!(reader.active_decode_targets_bitmask(), 0b1);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_dependency_descriptor_errors() {
+    let mut reader = DependencyDescriptorReader::new();
+    let ext = DependencyDescriptorExtension::default();
+    assert_eq!(
+        Error::ErrDependencyDescriptorNoStructure,
+        reader.read(&ext).unwrap_err()
+    );
+
+    let mut writer = DependencyDescriptorWriter::new();
+    assert_eq!(
+        Error::ErrDependencyDescriptorNoStructure,
+        writer
+            .write(&descriptor(0, l1t3().templates[0].clone(), None))
+            .unwrap_err()
+    );
+
+    // Templates have to follow the layer order.
+    let mut structure = l1t3();
+    structure.templates.swap(2, 3);
+    assert_eq!(
+        Error::ErrInvalidDependencyStructure,
+        writer
+            .write(&descriptor(
+                0,
+                l1t3().templates[0].clone(),
+                Some(&structure)
+            ))
+            .unwrap_err()
+    );
+
+    let structure = l1t3();
+    writer
+        .write(&descriptor(
+            0,
+            structure.templates[0].clone(),
+            Some(&structure),
+        ))
+        .unwrap();
+    // No template of spatial layer 1.
+    assert_eq!(
+        Error::ErrInvalidFrameDependencies,
+        writer
+            .write(&descriptor(1, template(1, 0, "SSS", &[], &[0]), None))
+            .unwrap_err()
+    );
+
+    // Template id outside of the structure.
+    reader
+        .read(
+            &writer
+                .write(&descriptor(
+                    0,
+                    structure.templates[0].clone(),
+                    Some(&structure),
+                ))
+                .unwrap(),
+        )
+        .unwrap();
+    let ext = DependencyDescriptorExtension {
+        frame_dependency_template_id: 10,
+        ..Default::default()
+    };
+    assert_eq!(Error::ErrInvalidTemplateId, reader.read(&ext).unwrap_err());
+
+    // Truncated extended fields.
+    let ext = DependencyDescriptorExtension {
+        extended: Bytes::from_static(&[0x80]),
+        ..Default::default()
+    };
+    assert_eq!(Error::ErrBufferTooSmall, reader.read(&ext).unwrap_err());
+}
+
+/// Writes the frames of the templates of structure, the first one with the structure,
+/// and returns whether the filter drops them.
+fn filter_frames(
+    filter: &mut LayerFilter,
+    writer: &mut DependencyDescriptorWriter,
+    structure: &FrameDependencyStructure,
+    frames: &[(usize, bool)],
+) -> Result<Vec<bool>> {
+    let mut dropped = vec![];
+    for (frame_number, (template_index, attach)) in frames.iter().enumerate() {
+        let ext = writer.write(&descriptor(
+            frame_number as u16,
+            structure.templates[*template_index].clone(),
+            attach.then_some(structure),
+        ))?;
+        dropped.push(filter.should_drop(&ext)?);
+    }
+    Ok(dropped)
+}
+
+#[test]
+fn test_layer_filter_temporal() -> Result<()> {
+    let structure = l1t3();
+    let mut writer = DependencyDescriptorWriter::new();
+    let mut filter = LayerFilter::new(0, 1);
+
+    // T0 key frame, T2, T1, T2, T0
+    let frames = [(0, true), (3, false), (2, false), (4, false), (1, false)];
+    assert_eq!(
+        filter_frames(&mut filter, &mut writer, &structure, &frames)?,
+        vec![false, true, false, true, false]
+    );
+    assert_eq!(filter.current_layer(), Some((0, 1)));
+
+    // Switching up waits for a switch point of T0T1T2, the T1 frame.
+    filter.set_target_layer(0, 2);
+    let frames = [(3, false), (2, false), (4, false), (1, false)];
+    assert_eq!(
+        filter_frames(&mut filter, &mut writer, &structure, &frames)?,
+        vec![true, false, false, false]
+    );
+    assert_eq!(filter.current_layer(), Some((0, 2)));
+
+    // Switching down happens at the next frame.
+    filter.set_target_layer(0, 0);
+    let frames = [(3, false), (2, false), (1, false)];
+    assert_eq!(
+        filter_frames(&mut filter, &mut writer, &structure, &frames)?,
+        vec![true, true, false]
+    );
+    assert_eq!(filter.current_layer(), Some((0, 0)));
+
+    Ok(())
+}
+
+#[test]
+fn test_layer_filter_spatial() -> Result<()> {
+    let structure = l2t1();
+    let mut writer = DependencyDescriptorWriter::new();
+    let mut filter = LayerFilter::new(0, 0);
+
+    // S0 key frame, S1, S0, S1
+    let frames = [(0, true), (2, false), (1, false), (3, false)];
+    assert_eq!(
+        filter_frames(&mut filter, &mut writer, &structure, &frames)?,
+        vec![false, true, false, true]
+    );
+
+    // Targets above the highest layer get the highest one.
+    let mut filter = LayerFilter::new(3, 3);
+    assert_eq!(
+        filter_frames(&mut filter, &mut writer, &structure, &frames)?,
+        vec![false, false, false, false]
+    );
+    assert_eq!(filter.current_layer(), Some((1, 0)));
+
+    // Only active decode targets are forwarded.
+    let mut filter = LayerFilter::new(1, 0);
+    let mut key = descriptor(0, structure.templates[0].clone(), Some(&structure));
+    key.active_decode_targets_bitmask = Some(0b01);
+    assert!(!filter.should_drop(&writer.write(&key)?)?);
+    let frames = [(2, false), (1, false)];
+    assert_eq!(
+        filter_frames(&mut filter, &mut writer, &structure, &frames)?,
+        vec![true, false]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use super::reader::DependencyDescriptorReader;
+use super::structure::*;
+use super::DependencyDescriptorExtension;
+use crate::error::Result;
+
+/// LayerFilter decides which packets of an AV1 SVC stream to forward so the receiver
+/// gets a target spatial and temporal layer, from their dependency descriptors.
+///
+/// The filter forwards the frames of the active decode target with the highest layer
+/// not above the target layer. It moves to a lower decode target at the start of any
+/// frame, and to a higher one only at a frame that is a switch point for it.
+///
+/// A selective forwarding unit calls it for every packet read from the remote track,
+/// with the extension id negotiated for the dependency descriptor:
+///
+/// ```
+/// use rtp::extension::dependency_descriptor_extension::{
+///     DependencyDescriptorExtension, LayerFilter,
+/// };
+/// use rtp::packet::Packet;
+/// use util::marshal::Unmarshal;
+///
+/// fn forward(filter: &mut LayerFilter, packet: &Packet, id: u8) -> bool {
+///     let Some(mut raw) = packet.header.get_extension(id) else {
+///         return true;
+///     };
+///     DependencyDescriptorExtension::unmarshal(&mut raw)
+///         .ok()
+///         .and_then(|ext| filter.should_drop(&ext).ok())
+///         == Some(false)
+/// }
+/// ```
+///
+/// The sequence numbers of the forwarded packets have gaps where packets were dropped,
+/// a [`SequenceMapper`](crate::sequence::SequenceMapper) closes them.
+#[derive(Debug, Default, Clone)]
+pub struct LayerFilter {
+    reader: DependencyDescriptorReader,
+    target_layer: (u8, u8),
+    decode_target: Option<usize>,
+}
+
+impl LayerFilter {
+    /// new creates a LayerFilter forwarding up to the given spatial and temporal layer.
+    pub fn new(spatial_id: u8, temporal_id: u8) -> Self {
+        LayerFilter {
+            target_layer: (spatial_id, temporal_id),
+            ..Default::default()
+        }
+    }
+
+    /// set_target_layer changes the target layer, the forwarded layer changes at the
+    /// next frame that allows it.
+    pub fn set_target_layer(&mut self, spatial_id: u8, temporal_id: u8) {
+        self.target_layer = (spatial_id, temporal_id);
+    }
+
+    /// current_layer returns the spatial id and highest temporal id of the forwarded
+    /// decode target, or None before the first switch point.
+    pub fn current_layer(&self) -> Option<(u8, u8)> {
+        let structure = self.reader.structure()?;
+        self.decode_target
+            .map(|decode_target| structure.decode_target_layer(decode_target))
+    }
+
+    /// should_drop reads the dependency descriptor of a packet and returns whether the
+    /// packet can be dropped. Descriptors have to be passed in order for every packet
+    /// of the stream, including the dropped ones, to track the template structure.
+    pub fn should_drop(&mut self, ext: &DependencyDescriptorExtension) -> Result<bool> {
+        let descriptor = self.reader.read(ext)?;
+        let structure = match self.reader.structure() {
+            Some(structure) => structure,
+            None => return Ok(true),
+        };
+
+        if descriptor.attached_structure.is_some() {
+            // Decode target indices only hold within a structure.
+            self.decode_target = None;
+        }
+
+        let dtis = &descriptor.frame_dependencies.decode_target_indications;
+        if descriptor.start_of_frame {
+            if let Some(wanted) = self.select_decode_target(structure) {
+                let can_switch = match self.decode_target {
+                    Some(current) if current == wanted => false,
+                    Some(current) => {
+                        let (current_spatial_id, current_temporal_id) =
+                            structure.decode_target_layer(current);
+                        let (wanted_spatial_id, wanted_temporal_id) =
+                            structure.decode_target_layer(wanted);
+                        (wanted_spatial_id <= current_spatial_id
+                            && wanted_temporal_id <= current_temporal_id)
+                            || dtis.get(wanted) == Some(&DecodeTargetIndication::Switch)
+                    }
+                    None => dtis.get(wanted) == Some(&DecodeTargetIndication::Switch),
+                };
+                if can_switch {
+                    self.decode_target = Some(wanted);
+                }
+            }
+        }
+
+        Ok(match self.decode_target {
+            Some(decode_target) => !dtis
+                .get(decode_target)
+                .map_or(false, |dti| *dti != DecodeTargetIndication::NotPresent),
+            None => true,
+        })
+    }
+
+    /// select_decode_target returns the active decode target with the highest layer
+    /// not above the target layer, or the lowest active one if all are above it.
+    fn select_decode_target(&self, structure: &FrameDependencyStructure) -> Option<usize> {
+        let active = self.reader.active_decode_targets_bitmask();
+        let (target_spatial_id, target_temporal_id) = self.target_layer;
+        let layers: Vec<(usize, (u8, u8))> = (0..structure.num_decode_targets)
+            .filter(|decode_target| active & (1 << decode_target) != 0)
+            .map(|decode_target| (decode_target, structure.decode_target_layer(decode_target)))
+            .collect();
+
+        layers
+            .iter()
+            .filter(|(_, (spatial_id, temporal_id))| {
+                *spatial_id <= target_spatial_id && *temporal_id <= target_temporal_id
+            })
+            .max_by_key(|(_, layer)| *layer)
+            .or_else(|| layers.iter().min_by_key(|(_, layer)| *layer))
+            .map(|(decode_target, _)| *decode_target)
+    }
+}
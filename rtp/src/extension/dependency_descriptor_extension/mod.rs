@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod dependency_descriptor_extension_test;
+mod layer_filter;
+mod reader;
+mod structure;
+mod writer;
+
+use bytes::{Buf, BufMut, Bytes};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+pub use layer_filter::LayerFilter;
+pub use reader::DependencyDescriptorReader;
+pub use structure::{
+    DecodeTargetIndication, DependencyDescriptor, FrameDependencyStructure,
+    FrameDependencyTemplate, RenderResolution, MAX_DECODE_TARGETS, MAX_TEMPLATES,
+};
+pub use writer::DependencyDescriptorWriter;
+
+use crate::error::Error;
+
+// DEPENDENCY_DESCRIPTOR_MANDATORY_SIZE is the size of the mandatory descriptor fields
+pub const DEPENDENCY_DESCRIPTOR_MANDATORY_SIZE: usize = 3;
+/// The largest frame dependency template id, 6 bits.
+pub const DEPENDENCY_DESCRIPTOR_MAX_TEMPLATE_ID: u8 = 0x3F;
+
+/// DependencyDescriptorExtension is a extension payload format in
+/// https://aomediacodec.github.io/av1-rtp-spec/#dependency-descriptor-rtp-header-extension
+///
+/// The mandatory fields are always present:
+///  0                   1                   2
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |S|E|  template id  |         frame number          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+///
+/// The extended fields that follow them can only be interpreted with the template
+/// dependency structure of the stream, they are kept as is. DependencyDescriptorReader
+/// and DependencyDescriptorWriter convert them from and to a DependencyDescriptor.
+#[derive(PartialEq, Eq, Debug, Default, Clone)]
+pub struct DependencyDescriptorExtension {
+    /// Set on the first packet of a frame
+    pub start_of_frame: bool,
+    /// Set on the last packet of a frame
+    pub end_of_frame: bool,
+    /// Id of the frame dependency template of the frame, 6 bits
+    pub frame_dependency_template_id: u8,
+    /// Number of the frame, wrapping at 2^16
+    pub frame_number: u16,
+    /// The extended descriptor fields, empty if there are none
+    pub extended: Bytes,
+}
+
+impl Unmarshal for DependencyDescriptorExtension {
+    /// Unmarshal parses the passed byte slice and stores the result in the members.
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self, util::Error>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        if raw_packet.remaining() < DEPENDENCY_DESCRIPTOR_MANDATORY_SIZE {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        let b0 = raw_packet.get_u8();
+        let frame_number = raw_packet.get_u16();
+        let extended = raw_packet.copy_to_bytes(raw_packet.remaining());
+
+        Ok(DependencyDescriptorExtension {
+            start_of_frame: b0 & 0x80 != 0,
+            end_of_frame: b0 & 0x40 != 0,
+            frame_dependency_template_id: b0 & DEPENDENCY_DESCRIPTOR_MAX_TEMPLATE_ID,
+            frame_number,
+            extended,
+        })
+    }
+}
+
+impl MarshalSize for DependencyDescriptorExtension {
+    /// MarshalSize returns the size of the DependencyDescriptorExtension once marshaled.
+    fn marshal_size(&self) -> usize {
+        DEPENDENCY_DESCRIPTOR_MANDATORY_SIZE + self.extended.len()
+    }
+}
+
+impl Marshal for DependencyDescriptorExtension {
+    /// MarshalTo serializes the members to buffer.
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize, util::Error> {
+        if buf.remaining_mut() < self.marshal_size() {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+        if self.frame_dependency_template_id > DEPENDENCY_DESCRIPTOR_MAX_TEMPLATE_ID {
+            return Err(Error::ErrInvalidTemplateId.into());
+        }
+
+        let mut b0 = self.frame_dependency_template_id;
+        if self.start_of_frame {
+            b0 |= 0x80;
+        }
+        if self.end_of_frame {
+            b0 |= 0x40;
+        }
+        buf.put_u8(b0);
+        buf.put_u16(self.frame_number);
+        buf.put_slice(&self.extended);
+
+        Ok(self.marshal_size())
+    }
+}
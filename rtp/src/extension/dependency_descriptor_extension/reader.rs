@@ -0,0 +1,245 @@
+use std::sync::Arc;
+
+use super::structure::*;
+use super::DependencyDescriptorExtension;
+use crate::error::{Error, Result};
+
+/// BitReader reads MSB first bit fields of a dependency descriptor.
+struct BitReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        BitReader { buf, pos: 0 }
+    }
+
+    /// read_bits reads the value of the next n bits, f(n), n <= 32.
+    fn read_bits(&mut self, n: usize) -> Result<u32> {
+        if self.pos + n > self.buf.len() * 8 {
+            return Err(Error::ErrBufferTooSmall);
+        }
+
+        let mut value = 0u32;
+        for _ in 0..n {
+            let bit = (self.buf[self.pos / 8] >> (7 - self.pos % 8)) & 1;
+            value = value << 1 | bit as u32;
+            self.pos += 1;
+        }
+
+        Ok(value)
+    }
+
+    fn read_bool(&mut self) -> Result<bool> {
+        Ok(self.read_bits(1)? == 1)
+    }
+
+    /// read_non_symmetric reads a value below num_values, ns(n).
+    fn read_non_symmetric(&mut self, num_values: u32) -> Result<u32> {
+        let w = (32 - num_values.leading_zeros()) as usize;
+        let m = (1 << w) - num_values;
+        let v = self.read_bits(w - 1)?;
+        if v < m {
+            return Ok(v);
+        }
+        let extra_bit = self.read_bits(1)?;
+        Ok((v << 1) - m + extra_bit)
+    }
+}
+
+/// DependencyDescriptorReader reads the dependency descriptors of a stream. It keeps
+/// the latest template structure and active decode targets across packets, as the
+/// descriptors of most frames can only be read with them.
+#[derive(Debug, Default, Clone)]
+pub struct DependencyDescriptorReader {
+    structure: Option<Arc<FrameDependencyStructure>>,
+    active_decode_targets_bitmask: u32,
+}
+
+impl DependencyDescriptorReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// structure returns the latest template structure of the stream.
+    pub fn structure(&self) -> Option<&Arc<FrameDependencyStructure>> {
+        self.structure.as_ref()
+    }
+
+    /// active_decode_targets_bitmask returns the active decode targets of the stream,
+    /// bit i for decode target i.
+    pub fn active_decode_targets_bitmask(&self) -> u32 {
+        self.active_decode_targets_bitmask
+    }
+
+    /// read reads a dependency descriptor, with the template structure attached to it
+    /// or the latest one read before.
+    pub fn read(&mut self, ext: &DependencyDescriptorExtension) -> Result<DependencyDescriptor> {
+        let mut reader = BitReader::new(&ext.extended);
+        let mut descriptor = DependencyDescriptor {
+            start_of_frame: ext.start_of_frame,
+            end_of_frame: ext.end_of_frame,
+            frame_number: ext.frame_number,
+            ..Default::default()
+        };
+
+        let mut structure = self.structure.clone();
+        let (mut custom_dtis, mut custom_fdiffs, mut custom_chains) = (false, false, false);
+        if !ext.extended.is_empty() {
+            let structure_present = reader.read_bool()?;
+            let active_decode_targets_present = reader.read_bool()?;
+            custom_dtis = reader.read_bool()?;
+            custom_fdiffs = reader.read_bool()?;
+            custom_chains = reader.read_bool()?;
+
+            if structure_present {
+                let attached = Arc::new(read_structure(&mut reader)?);
+                descriptor.active_decode_targets_bitmask =
+                    Some(all_decode_targets(attached.num_decode_targets));
+                descriptor.attached_structure = Some(Arc::clone(&attached));
+                structure = Some(attached);
+            }
+
+            if active_decode_targets_present {
+                let num_decode_targets = structure
+                    .as_ref()
+                    .ok_or(Error::ErrDependencyDescriptorNoStructure)?
+                    .num_decode_targets;
+                descriptor.active_decode_targets_bitmask =
+                    Some(reader.read_bits(num_decode_targets)?);
+            }
+        }
+
+        let structure = structure.ok_or(Error::ErrDependencyDescriptorNoStructure)?;
+        let template_index = (ext.frame_dependency_template_id as usize + MAX_TEMPLATES
+            - structure.structure_id as usize)
+            % MAX_TEMPLATES;
+        let mut frame_dependencies = structure
+            .templates
+            .get(template_index)
+            .ok_or(Error::ErrInvalidTemplateId)?
+            .clone();
+
+        if custom_dtis {
+            for dti in &mut frame_dependencies.decode_target_indications {
+                *dti = reader.read_bits(2)?.into();
+            }
+        }
+        if custom_fdiffs {
+            frame_dependencies.frame_diffs.clear();
+            loop {
+                let next_fdiff_size = reader.read_bits(2)? as usize;
+                if next_fdiff_size == 0 {
+                    break;
+                }
+                let fdiff_minus_one = reader.read_bits(4 * next_fdiff_size)?;
+                frame_dependencies
+                    .frame_diffs
+                    .push(fdiff_minus_one as u16 + 1);
+            }
+        }
+        if custom_chains {
+            for chain_diff in &mut frame_dependencies.chain_diffs {
+                *chain_diff = reader.read_bits(8)? as u16;
+            }
+        }
+
+        if !structure.resolutions.is_empty() {
+            descriptor.resolution = Some(
+                *structure
+                    .resolutions
+                    .get(frame_dependencies.spatial_id as usize)
+                    .ok_or(Error::ErrInvalidDependencyStructure)?,
+            );
+        }
+        descriptor.frame_dependencies = frame_dependencies;
+
+        if descriptor.attached_structure.is_some() {
+            self.structure = Some(structure);
+        }
+        if let Some(bitmask) = descriptor.active_decode_targets_bitmask {
+            self.active_decode_targets_bitmask = bitmask;
+        }
+
+        Ok(descriptor)
+    }
+}
+
+fn read_structure(reader: &mut BitReader<'_>) -> Result<FrameDependencyStructure> {
+    let structure_id = reader.read_bits(6)? as u8;
+    let num_decode_targets = reader.read_bits(5)? as usize + 1;
+
+    // template_layers
+    let mut templates = vec![];
+    let (mut spatial_id, mut temporal_id) = (0u8, 0u8);
+    loop {
+        if templates.len() == MAX_TEMPLATES {
+            return Err(Error::ErrInvalidDependencyStructure);
+        }
+        templates.push(FrameDependencyTemplate {
+            spatial_id,
+            temporal_id,
+            ..Default::default()
+        });
+
+        match reader.read_bits(2)? {
+            0 => {}
+            1 => temporal_id += 1,
+            2 => {
+                temporal_id = 0;
+                spatial_id += 1;
+            }
+            _ => break,
+        }
+    }
+
+    // template_dtis
+    for template in &mut templates {
+        template.decode_target_indications = (0..num_decode_targets)
+            .map(|_| reader.read_bits(2).map(DecodeTargetIndication::from))
+            .collect::<Result<_>>()?;
+    }
+
+    // template_fdiffs
+    for template in &mut templates {
+        while reader.read_bool()? {
+            let fdiff_minus_one = reader.read_bits(4)?;
+            template.frame_diffs.push(fdiff_minus_one as u16 + 1);
+        }
+    }
+
+    // template_chains
+    let num_chains = reader.read_non_symmetric(num_decode_targets as u32 + 1)? as usize;
+    let mut decode_target_protected_by_chain = vec![];
+    if num_chains > 0 {
+        for _ in 0..num_decode_targets {
+            let chain = reader.read_non_symmetric(num_chains as u32)?;
+            decode_target_protected_by_chain.push(chain as usize);
+        }
+        for template in &mut templates {
+            template.chain_diffs = (0..num_chains)
+                .map(|_| reader.read_bits(4).map(|diff| diff as u16))
+                .collect::<Result<_>>()?;
+        }
+    }
+
+    // render_resolutions
+    let mut resolutions = vec![];
+    if reader.read_bool()? {
+        for _ in 0..=spatial_id {
+            let width = reader.read_bits(16)? + 1;
+            let height = reader.read_bits(16)? + 1;
+            resolutions.push(RenderResolution { width, height });
+        }
+    }
+
+    Ok(FrameDependencyStructure {
+        structure_id,
+        num_decode_targets,
+        num_chains,
+        decode_target_protected_by_chain,
+        resolutions,
+        templates,
+    })
+}
@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+/// The largest number of templates in a FrameDependencyStructure.
+pub const MAX_TEMPLATES: usize = 64;
+/// The largest number of decode targets in a FrameDependencyStructure.
+pub const MAX_DECODE_TARGETS: usize = 32;
+
+/// DecodeTargetIndication tells how a frame relates to a decode target.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeTargetIndication {
+    /// The frame is not part of the decode target
+    #[default]
+    NotPresent = 0,
+    /// No later frame of the decode target depends on the frame
+    Discardable = 1,
+    /// Decoding of the decode target can start at the frame
+    Switch = 2,
+    /// The frame is needed to decode later frames of the decode target
+    Required = 3,
+}
+
+impl From<u32> for DecodeTargetIndication {
+    fn from(v: u32) -> Self {
+        match v & 0x3 {
+            0 => DecodeTargetIndication::NotPresent,
+            1 => DecodeTargetIndication::Discardable,
+            2 => DecodeTargetIndication::Switch,
+            _ => DecodeTargetIndication::Required,
+        }
+    }
+}
+
+/// RenderResolution is the resolution frames of a spatial layer are rendered at.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RenderResolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// FrameDependencyTemplate describes the layer of a frame and the frames it depends on.
+/// A FrameDependencyStructure holds the common ones so descriptors only refer to them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FrameDependencyTemplate {
+    pub spatial_id: u8,
+    pub temporal_id: u8,
+    /// The indication of the frame for each decode target
+    pub decode_target_indications: Vec<DecodeTargetIndication>,
+    /// The differences between the frame number and the numbers of the frames it references
+    pub frame_diffs: Vec<u16>,
+    /// For each chain, the difference between the frame number and the number of the
+    /// previous frame in the chain, 0 if there is none
+    pub chain_diffs: Vec<u16>,
+}
+
+/// FrameDependencyStructure is the template dependency structure of a stream. It is
+/// attached to the descriptor of key frames and used to read the descriptors of the
+/// frames that follow.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FrameDependencyStructure {
+    /// The template id offset, the template id of the first template
+    pub structure_id: u8,
+    pub num_decode_targets: usize,
+    pub num_chains: usize,
+    /// The chain protecting each decode target, empty if there are no chains
+    pub decode_target_protected_by_chain: Vec<usize>,
+    /// The render resolution of each spatial layer, empty if not sent
+    pub resolutions: Vec<RenderResolution>,
+    pub templates: Vec<FrameDependencyTemplate>,
+}
+
+impl FrameDependencyStructure {
+    /// decode_target_layer returns the spatial id and the highest temporal id of the
+    /// frames which are part of the decode target.
+    pub fn decode_target_layer(&self, decode_target: usize) -> (u8, u8) {
+        self.templates
+            .iter()
+            .filter(|template| {
+                template
+                    .decode_target_indications
+                    .get(decode_target)
+                    .map_or(false, |dti| *dti != DecodeTargetIndication::NotPresent)
+            })
+            .fold((0, 0), |(spatial_id, temporal_id), template| {
+                (
+                    spatial_id.max(template.spatial_id),
+                    temporal_id.max(template.temporal_id),
+                )
+            })
+    }
+}
+
+/// DependencyDescriptor is the content of a dependency descriptor read with the
+/// template structure of its stream.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DependencyDescriptor {
+    pub start_of_frame: bool,
+    pub end_of_frame: bool,
+    pub frame_number: u16,
+    /// The layer and dependencies of the frame
+    pub frame_dependencies: FrameDependencyTemplate,
+    /// The render resolution of the frame, if the structure has resolutions
+    pub resolution: Option<RenderResolution>,
+    /// The active decode targets, bit i for decode target i, if sent with the packet
+    pub active_decode_targets_bitmask: Option<u32>,
+    /// The template structure, if attached to the packet
+    pub attached_structure: Option<Arc<FrameDependencyStructure>>,
+}
+
+/// all_decode_targets returns the bitmask of num_decode_targets active decode targets.
+pub(crate) fn all_decode_targets(num_decode_targets: usize) -> u32 {
+    if num_decode_targets == 0 {
+        0
+    } else {
+        u32::MAX >> (MAX_DECODE_TARGETS - num_decode_targets.min(MAX_DECODE_TARGETS))
+    }
+}
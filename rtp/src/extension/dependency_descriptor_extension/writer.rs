@@ -0,0 +1,279 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+
+use super::structure::*;
+use super::DependencyDescriptorExtension;
+use crate::error::{Error, Result};
+
+/// BitWriter writes MSB first bit fields of a dependency descriptor.
+#[derive(Default)]
+struct BitWriter {
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl BitWriter {
+    /// write_bits writes the low n bits of value, f(n), n <= 32.
+    fn write_bits(&mut self, value: u32, n: usize) {
+        for i in (0..n).rev() {
+            if self.pos == self.buf.len() * 8 {
+                self.buf.push(0);
+            }
+            if (value >> i) & 1 == 1 {
+                let last = self.buf.len() - 1;
+                self.buf[last] |= 0x80 >> (self.pos % 8);
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn write_bool(&mut self, value: bool) {
+        self.write_bits(value as u32, 1);
+    }
+
+    /// write_non_symmetric writes a value below num_values, ns(n).
+    fn write_non_symmetric(&mut self, value: u32, num_values: u32) {
+        let w = (32 - num_values.leading_zeros()) as usize;
+        let m = (1 << w) - num_values;
+        if value < m {
+            self.write_bits(value, w - 1);
+        } else {
+            self.write_bits(value + m, w);
+        }
+    }
+
+    fn finish(self) -> Bytes {
+        Bytes::from(self.buf)
+    }
+}
+
+/// DependencyDescriptorWriter writes the dependency descriptors of a stream. It keeps
+/// the latest attached template structure to write the descriptors that follow it.
+#[derive(Debug, Default, Clone)]
+pub struct DependencyDescriptorWriter {
+    structure: Option<Arc<FrameDependencyStructure>>,
+}
+
+impl DependencyDescriptorWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// write writes a dependency descriptor, using the template of the structure that
+    /// needs the fewest custom fields to describe the frame. The frame must have the
+    /// layer of a template of the structure.
+    pub fn write(
+        &mut self,
+        descriptor: &DependencyDescriptor,
+    ) -> Result<DependencyDescriptorExtension> {
+        let structure = match &descriptor.attached_structure {
+            Some(structure) => {
+                validate_structure(structure)?;
+                Arc::clone(structure)
+            }
+            None => self
+                .structure
+                .clone()
+                .ok_or(Error::ErrDependencyDescriptorNoStructure)?,
+        };
+
+        let frame = &descriptor.frame_dependencies;
+        if frame.decode_target_indications.len() != structure.num_decode_targets
+            || frame.chain_diffs.len() != structure.num_chains
+            || frame.chain_diffs.iter().any(|diff| *diff > 0xFF)
+            || frame
+                .frame_diffs
+                .iter()
+                .any(|diff| *diff == 0 || *diff > 1 << 12)
+        {
+            return Err(Error::ErrInvalidFrameDependencies);
+        }
+
+        let (template_index, template) = structure
+            .templates
+            .iter()
+            .enumerate()
+            .filter(|(_, template)| {
+                template.spatial_id == frame.spatial_id && template.temporal_id == frame.temporal_id
+            })
+            .min_by_key(|(_, template)| custom_fields(template, frame))
+            .ok_or(Error::ErrInvalidFrameDependencies)?;
+
+        let custom_dtis = template.decode_target_indications != frame.decode_target_indications;
+        let custom_fdiffs = template.frame_diffs != frame.frame_diffs;
+        let custom_chains = template.chain_diffs != frame.chain_diffs;
+        let all = all_decode_targets(structure.num_decode_targets);
+        let active_decode_targets_bitmask = descriptor
+            .active_decode_targets_bitmask
+            .map(|bitmask| bitmask & all)
+            .filter(|bitmask| descriptor.attached_structure.is_none() || *bitmask != all);
+
+        let mut writer = BitWriter::default();
+        if descriptor.attached_structure.is_some()
+            || active_decode_targets_bitmask.is_some()
+            || custom_dtis
+            || custom_fdiffs
+            || custom_chains
+        {
+            writer.write_bool(descriptor.attached_structure.is_some());
+            writer.write_bool(active_decode_targets_bitmask.is_some());
+            writer.write_bool(custom_dtis);
+            writer.write_bool(custom_fdiffs);
+            writer.write_bool(custom_chains);
+
+            if descriptor.attached_structure.is_some() {
+                write_structure(&mut writer, &structure);
+            }
+            if let Some(bitmask) = active_decode_targets_bitmask {
+                writer.write_bits(bitmask, structure.num_decode_targets);
+            }
+            if custom_dtis {
+                for dti in &frame.decode_target_indications {
+                    writer.write_bits(*dti as u32, 2);
+                }
+            }
+            if custom_fdiffs {
+                for fdiff in &frame.frame_diffs {
+                    let fdiff_minus_one = *fdiff as u32 - 1;
+                    let next_fdiff_size = match fdiff_minus_one {
+                        0..=0xF => 1,
+                        0x10..=0xFF => 2,
+                        _ => 3,
+                    };
+                    writer.write_bits(next_fdiff_size as u32, 2);
+                    writer.write_bits(fdiff_minus_one, 4 * next_fdiff_size);
+                }
+                writer.write_bits(0, 2);
+            }
+            if custom_chains {
+                for chain_diff in &frame.chain_diffs {
+                    writer.write_bits(*chain_diff as u32, 8);
+                }
+            }
+        }
+
+        let frame_dependency_template_id =
+            ((template_index + structure.structure_id as usize) % MAX_TEMPLATES) as u8;
+        if descriptor.attached_structure.is_some() {
+            self.structure = Some(structure);
+        }
+
+        Ok(DependencyDescriptorExtension {
+            start_of_frame: descriptor.start_of_frame,
+            end_of_frame: descriptor.end_of_frame,
+            frame_dependency_template_id,
+            frame_number: descriptor.frame_number,
+            extended: writer.finish(),
+        })
+    }
+}
+
+/// custom_fields returns the number of fields of the frame that differ from the template.
+fn custom_fields(template: &FrameDependencyTemplate, frame: &FrameDependencyTemplate) -> usize {
+    (template.decode_target_indications != frame.decode_target_indications) as usize
+        + (template.frame_diffs != frame.frame_diffs) as usize
+        + (template.chain_diffs != frame.chain_diffs) as usize
+}
+
+fn validate_structure(structure: &FrameDependencyStructure) -> Result<()> {
+    let valid_layers = structure.templates.first().map_or(false, |first| {
+        first.spatial_id == 0 && first.temporal_id == 0
+    }) && structure.templates.windows(2).all(|pair| {
+        let (prev, next) = (&pair[0], &pair[1]);
+        (next.spatial_id == prev.spatial_id
+            && (next.temporal_id == prev.temporal_id || next.temporal_id == prev.temporal_id + 1))
+            || (next.spatial_id == prev.spatial_id + 1 && next.temporal_id == 0)
+    });
+    let max_spatial_id = structure
+        .templates
+        .last()
+        .map_or(0, |template| template.spatial_id as usize);
+
+    if !valid_layers
+        || structure.templates.len() > MAX_TEMPLATES
+        || structure.structure_id as usize >= MAX_TEMPLATES
+        || structure.num_decode_targets == 0
+        || structure.num_decode_targets > MAX_DECODE_TARGETS
+        || structure.num_chains > structure.num_decode_targets
+        || (structure.num_chains > 0
+            && (structure.decode_target_protected_by_chain.len() != structure.num_decode_targets
+                || structure
+                    .decode_target_protected_by_chain
+                    .iter()
+                    .any(|chain| *chain >= structure.num_chains)))
+        || structure.templates.iter().any(|template| {
+            template.decode_target_indications.len() != structure.num_decode_targets
+                || template.chain_diffs.len() != structure.num_chains
+                || template.chain_diffs.iter().any(|diff| *diff > 0xF)
+                || template
+                    .frame_diffs
+                    .iter()
+                    .any(|diff| *diff == 0 || *diff > 0x10)
+        })
+        || !(structure.resolutions.is_empty() || structure.resolutions.len() == max_spatial_id + 1)
+        || structure.resolutions.iter().any(|resolution| {
+            !(1..=1 << 16).contains(&resolution.width)
+                || !(1..=1 << 16).contains(&resolution.height)
+        })
+    {
+        return Err(Error::ErrInvalidDependencyStructure);
+    }
+
+    Ok(())
+}
+
+fn write_structure(writer: &mut BitWriter, structure: &FrameDependencyStructure) {
+    writer.write_bits(structure.structure_id as u32, 6);
+    writer.write_bits(structure.num_decode_targets as u32 - 1, 5);
+
+    // template_layers
+    for (i, template) in structure.templates.iter().enumerate() {
+        let next_layer_idc = match structure.templates.get(i + 1) {
+            None => 3,
+            Some(next) if next.spatial_id != template.spatial_id => 2,
+            Some(next) if next.temporal_id != template.temporal_id => 1,
+            Some(_) => 0,
+        };
+        writer.write_bits(next_layer_idc, 2);
+    }
+
+    // template_dtis
+    for template in &structure.templates {
+        for dti in &template.decode_target_indications {
+            writer.write_bits(*dti as u32, 2);
+        }
+    }
+
+    // template_fdiffs
+    for template in &structure.templates {
+        for fdiff in &template.frame_diffs {
+            writer.write_bool(true);
+            writer.write_bits(*fdiff as u32 - 1, 4);
+        }
+        writer.write_bool(false);
+    }
+
+    // template_chains
+    writer.write_non_symmetric(
+        structure.num_chains as u32,
+        structure.num_decode_targets as u32 + 1,
+    );
+    if structure.num_chains > 0 {
+        for chain in &structure.decode_target_protected_by_chain {
+            writer.write_non_symmetric(*chain as u32, structure.num_chains as u32);
+        }
+        for template in &structure.templates {
+            for chain_diff in &template.chain_diffs {
+                writer.write_bits(*chain_diff as u32, 4);
+            }
+        }
+    }
+
+    // render_resolutions
+    writer.write_bool(!structure.resolutions.is_empty());
+    for resolution in &structure.resolutions {
+        writer.write_bits(resolution.width - 1, 16);
+        writer.write_bits(resolution.height - 1, 16);
+    }
+}
@@ -3,8 +3,12 @@ use std::fmt;
 
 use util::{Marshal, MarshalSize};
 
+pub mod abs_capture_time_extension;
 pub mod abs_send_time_extension;
 pub mod audio_level_extension;
+pub mod dependency_descriptor_extension;
+pub mod playout_delay_extension;
+pub mod sdes_extension;
 pub mod transport_cc_extension;
 pub mod video_orientation_extension;
 
@@ -14,6 +18,12 @@ pub enum HeaderExtension {
     AudioLevel(audio_level_extension::AudioLevelExtension),
     TransportCc(transport_cc_extension::TransportCcExtension),
     VideoOrientation(video_orientation_extension::VideoOrientationExtension),
+    AbsCaptureTime(abs_capture_time_extension::AbsCaptureTimeExtension),
+    PlayoutDelay(playout_delay_extension::PlayoutDelayExtension),
+    Mid(sdes_extension::SdesExtension),
+    RtpStreamId(sdes_extension::SdesExtension),
+    RepairedRtpStreamId(sdes_extension::SdesExtension),
+    DependencyDescriptor(dependency_descriptor_extension::DependencyDescriptorExtension),
 
     /// A custom extension
     Custom {
@@ -33,6 +43,14 @@ impl HeaderExtension {
                 "http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01".into()
             }
             VideoOrientation(_) => "urn:3gpp:video-orientation".into(),
+            AbsCaptureTime(_) => {
+                "http://www.webrtc.org/experiments/rtp-hdrext/abs-capture-time".into()
+            }
+            PlayoutDelay(_) => "http://www.webrtc.org/experiments/rtp-hdrext/playout-delay".into(),
+            Mid(_) => "urn:ietf:params:rtp-hdrext:sdes:mid".into(),
+            RtpStreamId(_) => "urn:ietf:params:rtp-hdrext:sdes:rtp-stream-id".into(),
+            RepairedRtpStreamId(_) => "urn:ietf:params:rtp-hdrext:sdes:repaired-rtp-stream-id".into(),
+            DependencyDescriptor(_) => "https://aomediacodec.github.io/av1-rtp-spec/#dependency-descriptor-rtp-header-extension".into(),
             Custom { uri, .. } => uri.clone(),
         }
     }
@@ -44,6 +62,12 @@ impl HeaderExtension {
             (AudioLevel(_), AudioLevel(_)) => true,
             (TransportCc(_), TransportCc(_)) => true,
             (VideoOrientation(_), VideoOrientation(_)) => true,
+            (AbsCaptureTime(_), AbsCaptureTime(_)) => true,
+            (PlayoutDelay(_), PlayoutDelay(_)) => true,
+            (Mid(_), Mid(_)) => true,
+            (RtpStreamId(_), RtpStreamId(_)) => true,
+            (RepairedRtpStreamId(_), RepairedRtpStreamId(_)) => true,
+            (DependencyDescriptor(_), DependencyDescriptor(_)) => true,
             (Custom { uri, .. }, Custom { uri: other_uri, .. }) => uri == other_uri,
             _ => false,
         }
@@ -58,6 +82,12 @@ impl MarshalSize for HeaderExtension {
             AudioLevel(ext) => ext.marshal_size(),
             TransportCc(ext) => ext.marshal_size(),
             VideoOrientation(ext) => ext.marshal_size(),
+            AbsCaptureTime(ext) => ext.marshal_size(),
+            PlayoutDelay(ext) => ext.marshal_size(),
+            Mid(ext) => ext.marshal_size(),
+            RtpStreamId(ext) => ext.marshal_size(),
+            RepairedRtpStreamId(ext) => ext.marshal_size(),
+            DependencyDescriptor(ext) => ext.marshal_size(),
             Custom { extension: ext, .. } => ext.marshal_size(),
         }
     }
@@ -71,6 +101,12 @@ impl Marshal for HeaderExtension {
             AudioLevel(ext) => ext.marshal_to(buf),
             TransportCc(ext) => ext.marshal_to(buf),
             VideoOrientation(ext) => ext.marshal_to(buf),
+            AbsCaptureTime(ext) => ext.marshal_to(buf),
+            PlayoutDelay(ext) => ext.marshal_to(buf),
+            Mid(ext) => ext.marshal_to(buf),
+            RtpStreamId(ext) => ext.marshal_to(buf),
+            RepairedRtpStreamId(ext) => ext.marshal_to(buf),
+            DependencyDescriptor(ext) => ext.marshal_to(buf),
             Custom { extension: ext, .. } => ext.marshal_to(buf),
         }
     }
@@ -85,6 +121,12 @@ impl fmt::Debug for HeaderExtension {
             AudioLevel(ext) => f.debug_tuple("AudioLevel").field(ext).finish(),
             TransportCc(ext) => f.debug_tuple("TransportCc").field(ext).finish(),
             VideoOrientation(ext) => f.debug_tuple("VideoOrientation").field(ext).finish(),
+            AbsCaptureTime(ext) => f.debug_tuple("AbsCaptureTime").field(ext).finish(),
+            PlayoutDelay(ext) => f.debug_tuple("PlayoutDelay").field(ext).finish(),
+            Mid(ext) => f.debug_tuple("Mid").field(ext).finish(),
+            RtpStreamId(ext) => f.debug_tuple("RtpStreamId").field(ext).finish(),
+            RepairedRtpStreamId(ext) => f.debug_tuple("RepairedRtpStreamId").field(ext).finish(),
+            DependencyDescriptor(ext) => f.debug_tuple("DependencyDescriptor").field(ext).finish(),
             Custom { uri, extension: _ } => f.debug_struct("Custom").field("uri", uri).finish(),
         }
     }
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod playout_delay_extension_test;
+
+use bytes::{Buf, BufMut};
+use serde::{Deserialize, Serialize};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+
+pub const PLAYOUT_DELAY_EXTENSION_SIZE: usize = 3;
+/// The largest delay of a PlayoutDelayExtension, 12 bits of 10 ms.
+pub const PLAYOUT_DELAY_MAX_VALUE: u16 = (1 << 12) - 1;
+
+/// PlayoutDelayExtension is a extension payload format in
+/// http://www.webrtc.org/experiments/rtp-hdrext/playout-delay
+///
+/// It lets the sender bound the delay the receiver adds before rendering, a min and
+/// max delay of 0 asks for frames to be rendered as soon as possible.
+///
+/// 0                   1                   2                   3
+/// 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  ID   | len=2 |       MIN delay       |       MAX delay       |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone, Serialize, Deserialize)]
+pub struct PlayoutDelayExtension {
+    /// Minimum playout delay in 10 ms units
+    pub min_delay: u16,
+    /// Maximum playout delay in 10 ms units
+    pub max_delay: u16,
+}
+
+impl Unmarshal for PlayoutDelayExtension {
+    /// Unmarshal parses the passed byte slice and stores the result in the members.
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self, util::Error>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        if raw_packet.remaining() < PLAYOUT_DELAY_EXTENSION_SIZE {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        let b0 = raw_packet.get_u8() as u16;
+        let b1 = raw_packet.get_u8() as u16;
+        let b2 = raw_packet.get_u8() as u16;
+
+        Ok(PlayoutDelayExtension {
+            min_delay: b0 << 4 | b1 >> 4,
+            max_delay: (b1 & 0x0F) << 8 | b2,
+        })
+    }
+}
+
+impl MarshalSize for PlayoutDelayExtension {
+    /// MarshalSize returns the size of the PlayoutDelayExtension once marshaled.
+    fn marshal_size(&self) -> usize {
+        PLAYOUT_DELAY_EXTENSION_SIZE
+    }
+}
+
+impl Marshal for PlayoutDelayExtension {
+    /// MarshalTo serializes the members to buffer.
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize, util::Error> {
+        if buf.remaining_mut() < PLAYOUT_DELAY_EXTENSION_SIZE {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+        if self.min_delay > PLAYOUT_DELAY_MAX_VALUE || self.max_delay > PLAYOUT_DELAY_MAX_VALUE {
+            return Err(Error::PlayoutDelayOverflow.into());
+        }
+
+        buf.put_u8((self.min_delay >> 4) as u8);
+        buf.put_u8(((self.min_delay & 0x0F) << 4 | self.max_delay >> 8) as u8);
+        buf.put_u8((self.max_delay & 0xFF) as u8);
+
+        Ok(PLAYOUT_DELAY_EXTENSION_SIZE)
+    }
+}
@@ -0,0 +1,60 @@
+use bytes::{Bytes, BytesMut};
+
+use super::*;
+use crate::error::Result;
+
+#[test]
+fn test_playout_delay_extension_round_trip() -> Result<()> {
+    let tests = vec![
+        (
+            PlayoutDelayExtension {
+                min_delay: 0,
+                max_delay: 0,
+            },
+            Bytes::from_static(&[0x00, 0x00, 0x00]),
+        ),
+        (
+            PlayoutDelayExtension {
+                min_delay: 0x123,
+                max_delay: 0x456,
+            },
+            Bytes::from_static(&[0x12, 0x34, 0x56]),
+        ),
+        (
+            PlayoutDelayExtension {
+                min_delay: PLAYOUT_DELAY_MAX_VALUE,
+                max_delay: PLAYOUT_DELAY_MAX_VALUE,
+            },
+            Bytes::from_static(&[0xff, 0xff, 0xff]),
+        ),
+    ];
+
+    for (ext, raw) in tests {
+        let mut dst = BytesMut::with_capacity(ext.marshal_size());
+        dst.resize(ext.marshal_size(), 0);
+        ext.marshal_to(&mut dst)?;
+        assert_eq!(raw, dst.freeze());
+
+        let buf = &mut raw.clone();
+        assert_eq!(PlayoutDelayExtension::unmarshal(buf)?, ext);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_playout_delay_extension_errors() {
+    let mut buf = &vec![0u8; 2][..];
+    assert!(PlayoutDelayExtension::unmarshal(&mut buf).is_err());
+
+    let ext = PlayoutDelayExtension {
+        min_delay: 0,
+        max_delay: PLAYOUT_DELAY_MAX_VALUE + 1,
+    };
+    let mut dst = BytesMut::with_capacity(ext.marshal_size());
+    dst.resize(ext.marshal_size(), 0);
+    assert_eq!(
+        Error::PlayoutDelayOverflow,
+        ext.marshal_to(&mut dst).unwrap_err()
+    );
+}
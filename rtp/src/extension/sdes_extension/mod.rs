@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod sdes_extension_test;
+
+use bytes::{Buf, BufMut};
+use serde::{Deserialize, Serialize};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+
+/// SdesExtension carries the value of an SDES item in a header extension. It is the
+/// payload format of the MID (RFC 8843),
+/// urn:ietf:params:rtp-hdrext:sdes:mid
+/// and of the RtpStreamId and RepairedRtpStreamId (RFC 8852) extensions,
+/// urn:ietf:params:rtp-hdrext:sdes:rtp-stream-id
+/// urn:ietf:params:rtp-hdrext:sdes:repaired-rtp-stream-id
+///
+/// The value takes the whole extension, it is not null terminated.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SdesExtension {
+    pub value: String,
+}
+
+impl SdesExtension {
+    pub fn new(value: impl Into<String>) -> Self {
+        SdesExtension {
+            value: value.into(),
+        }
+    }
+}
+
+impl Unmarshal for SdesExtension {
+    /// Unmarshal parses the passed byte slice and stores the result in the members.
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self, util::Error>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        let raw = raw_packet.copy_to_bytes(raw_packet.remaining());
+        let value = String::from_utf8(raw.to_vec()).map_err(|_| Error::ErrSdesValueNotUtf8)?;
+
+        Ok(SdesExtension { value })
+    }
+}
+
+impl MarshalSize for SdesExtension {
+    /// MarshalSize returns the size of the SdesExtension once marshaled.
+    fn marshal_size(&self) -> usize {
+        self.value.len()
+    }
+}
+
+impl Marshal for SdesExtension {
+    /// MarshalTo serializes the members to buffer.
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize, util::Error> {
+        if buf.remaining_mut() < self.marshal_size() {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        buf.put_slice(self.value.as_bytes());
+
+        Ok(self.marshal_size())
+    }
+}
@@ -0,0 +1,25 @@
+use bytes::{Bytes, BytesMut};
+
+use super::*;
+use crate::error::Result;
+
+#[test]
+fn test_sdes_extension_round_trip() -> Result<()> {
+    let raw = Bytes::from_static(b"hi");
+    let buf = &mut raw.clone();
+    let ext = SdesExtension::unmarshal(buf)?;
+    assert_eq!(ext, SdesExtension::new("hi"));
+
+    let mut dst = BytesMut::with_capacity(ext.marshal_size());
+    dst.resize(ext.marshal_size(), 0);
+    ext.marshal_to(&mut dst)?;
+    assert_eq!(raw, dst.freeze());
+
+    let mut buf = &[0xffu8, 0xfe][..];
+    assert_eq!(
+        Error::ErrSdesValueNotUtf8,
+        SdesExtension::unmarshal(&mut buf).unwrap_err()
+    );
+
+    Ok(())
+}
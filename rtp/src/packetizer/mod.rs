@@ -17,6 +17,18 @@ use crate::sequence::*;
 /// Payloader payloads a byte array for use as rtp.Packet payloads
 pub trait Payloader: fmt::Debug {
     fn payload(&mut self, mtu: usize, b: &Bytes) -> Result<Vec<Bytes>>;
+
+    /// payload_with_timestamp payloads a frame sampled at the given RTP timestamp.
+    /// Payloaders that bundle several frames in a payload need it to describe them.
+    fn payload_with_timestamp(
+        &mut self,
+        mtu: usize,
+        b: &Bytes,
+        _timestamp: u32,
+    ) -> Result<Vec<Bytes>> {
+        self.payload(mtu, b)
+    }
+
     fn clone_to(&self) -> Box<dyn Payloader + Send + Sync>;
 }
 
@@ -112,7 +124,9 @@ impl Packetizer for PacketizerImpl {
     }
 
     fn packetize(&mut self, payload: &Bytes, samples: u32) -> Result<Vec<Packet>> {
-        let payloads = self.payloader.payload(self.mtu - 12, payload)?;
+        let payloads =
+            self.payloader
+                .payload_with_timestamp(self.mtu - 12, payload, self.timestamp)?;
         let payloads_len = payloads.len();
         let mut packets = Vec::with_capacity(payloads_len);
         for (i, payload) in payloads.into_iter().enumerate() {
@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::fmt;
 use std::sync::atomic::{AtomicU16, AtomicU64, Ordering};
 use std::sync::Arc;
@@ -70,3 +71,112 @@ impl Sequencer for SequencerImpl {
         Box::new(self.clone())
     }
 }
+
+/// SequenceMapper maps the sequence numbers of a stream some packets of which are
+/// dropped to continuous sequence numbers, for forwarders which drop packets. It works
+/// on numbers of any width, RTP sequence numbers as well as codec picture ids.
+///
+/// Only drops of packets newer than all the packets seen before close the gap, a
+/// packet dropped after a newer one was mapped leaves a gap.
+#[derive(Debug, Clone)]
+pub struct SequenceMapper {
+    bits: u32,
+    offset: i64,
+    highest_input: Option<i64>,
+    highest_output: Option<i64>,
+    dropped: BTreeSet<i64>,
+}
+
+impl SequenceMapper {
+    /// new creates a SequenceMapper of bits wide numbers, which maps the numbers to
+    /// themselves until packets are dropped.
+    pub fn new(bits: u32) -> Self {
+        SequenceMapper {
+            bits,
+            offset: 0,
+            highest_input: None,
+            highest_output: None,
+            dropped: BTreeSet::new(),
+        }
+    }
+
+    /// bits returns the width of the numbers.
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    fn modulus(&self) -> i64 {
+        1 << self.bits
+    }
+
+    /// sync makes the next input map to the number following the highest output, for
+    /// when the input switches to another stream. The width of the numbers can change.
+    pub fn sync(&mut self, bits: u32) {
+        self.bits = bits;
+        self.highest_input = None;
+        self.dropped.clear();
+    }
+
+    /// extend returns the input extended past its wraps.
+    fn extend(&mut self, input: u32) -> i64 {
+        let input = input as i64 & (self.modulus() - 1);
+        match self.highest_input {
+            Some(highest) => {
+                let delta = (input - highest).rem_euclid(self.modulus());
+                if delta >= self.modulus() / 2 {
+                    highest + delta - self.modulus()
+                } else {
+                    highest + delta
+                }
+            }
+            None => {
+                self.offset = self.highest_output.map_or(input, |output| output + 1) - input;
+                self.highest_input = Some(input);
+                input
+            }
+        }
+    }
+
+    /// update_highest_input records input as the highest one and forgets the drops
+    /// too old to matter, counting them in the offset.
+    fn update_highest_input(&mut self, input: i64) {
+        if self.highest_input.map_or(false, |highest| input <= highest) {
+            return;
+        }
+        self.highest_input = Some(input);
+
+        let kept = self.dropped.split_off(&(input - self.modulus() / 4));
+        self.offset -= self.dropped.len() as i64;
+        self.dropped = kept;
+    }
+
+    /// skip records the input as dropped, the following inputs map to the numbers it
+    /// would have had.
+    pub fn skip(&mut self, input: u32) {
+        let is_new = self.highest_input.is_none();
+        let input = self.extend(input);
+        if is_new || self.highest_input.map_or(false, |highest| input > highest) {
+            self.update_highest_input(input);
+            self.dropped.insert(input);
+        }
+    }
+
+    /// map returns the output number of input, or None if the input was dropped or is
+    /// too old to be mapped.
+    pub fn map(&mut self, input: u32) -> Option<u32> {
+        let input = self.extend(input);
+        if self.dropped.contains(&input)
+            || self
+                .highest_input
+                .map_or(false, |highest| input < highest - self.modulus() / 4)
+        {
+            return None;
+        }
+        self.update_highest_input(input);
+
+        let output = input + self.offset - self.dropped.range(..input).count() as i64;
+        self.highest_output = Some(self.highest_output.map_or(output, |o| o.max(output)));
+
+        Some(output.rem_euclid(self.modulus()) as u32)
+    }
+}
@@ -67,6 +67,7 @@ async fn main() -> Result<(), Error> {
         max_receive_buffer_size: 0,
         max_message_size: 0,
         name: "server".to_owned(),
+        stream_scheduler: StreamSchedulerType::default(),
     };
     let a = Association::server(config).await?;
     println!("created a server");
@@ -68,6 +68,7 @@ fn main() -> Result<(), Error> {
                     max_receive_buffer_size: 0,
                     max_message_size: 0,
                     name: "recver".to_owned(),
+                    stream_scheduler: StreamSchedulerType::default(),
                 };
                 let a = Association::server(config).await?;
                 println!("created a server");
@@ -114,6 +115,7 @@ fn main() -> Result<(), Error> {
                     max_receive_buffer_size: 0,
                     max_message_size: 0,
                     name: "sender".to_owned(),
+                    stream_scheduler: StreamSchedulerType::default(),
                 };
                 let a = Association::client(config).await.unwrap();
                 println!("created a client");
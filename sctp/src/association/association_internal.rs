@@ -48,10 +48,14 @@ pub struct AssociationInternal {
     pending_queue: Arc<PendingQueue>,
     control_queue: ControlQueue,
     pub(crate) mtu: u32,
-    max_payload_size: u32, // max DATA chunk payload size
+    max_payload_size: Arc<AtomicU32>, // max DATA chunk payload size
+    pub(crate) pmtud: PathMtuDiscovery,
     cumulative_tsn_ack_point: u32,
     advanced_peer_tsn_ack_point: u32,
     use_forward_tsn: bool,
+    /// Set if I-DATA chunks are negotiated, so the fragments of messages of different
+    /// streams can be interleaved (RFC 8260)
+    pub(crate) use_interleaving: bool,
 
     // Congestion control parameters
     pub(crate) max_receive_buffer_size: u32,
@@ -69,6 +73,7 @@ pub struct AssociationInternal {
     pub(crate) t2shutdown: Option<RtxTimer<AssociationInternal>>,
     pub(crate) t3rtx: Option<RtxTimer<AssociationInternal>>,
     pub(crate) treconfig: Option<RtxTimer<AssociationInternal>>,
+    pub(crate) pmtu_probe_timer: Option<RtxTimer<AssociationInternal>>,
     pub(crate) ack_timer: Option<AckTimer<AssociationInternal>>,
 
     // Chunks stored for retransmission
@@ -93,6 +98,15 @@ pub struct AssociationInternal {
     pub(crate) ack_mode: AckMode, // for testing
 }
 
+/// data_chunk_header_size returns the size of the chunk header of a DATA or I-DATA chunk.
+fn data_chunk_header_size(c: &ChunkPayloadData) -> u32 {
+    if c.interleaved {
+        I_DATA_CHUNK_HEADER_SIZE
+    } else {
+        DATA_CHUNK_HEADER_SIZE
+    }
+}
+
 impl AssociationInternal {
     pub(crate) fn new(
         config: Config,
@@ -129,10 +143,12 @@ impl AssociationInternal {
             payload_queue: PayloadQueue::new(Arc::new(AtomicUsize::new(0))),
             inflight_queue: PayloadQueue::new(Arc::clone(&inflight_queue_length)),
             inflight_queue_length,
-            pending_queue: Arc::new(PendingQueue::new()),
+            pending_queue: Arc::new(PendingQueue::with_stream_scheduler(config.stream_scheduler)),
             control_queue: ControlQueue::new(),
             mtu: INITIAL_MTU,
-            max_payload_size: INITIAL_MTU - (COMMON_HEADER_SIZE + DATA_CHUNK_HEADER_SIZE),
+            max_payload_size: Arc::new(AtomicU32::new(
+                INITIAL_MTU - (COMMON_HEADER_SIZE + DATA_CHUNK_HEADER_SIZE),
+            )),
             my_verification_tag: random::<u32>(),
             my_next_tsn: tsn,
             my_next_rsn: tsn,
@@ -152,6 +168,13 @@ impl AssociationInternal {
             awake_write_loop_ch: Some(awake_write_loop_ch),
             ..Default::default()
         };
+        a.stats.set_mtu(a.mtu);
+
+        // The association tests count and drop packets, which the probes would disturb.
+        // The tests of the path MTU discovery turn it back on.
+        if cfg!(test) {
+            a.pmtud.set_enabled(false);
+        }
 
         // RFC 4690 Sec 7.2.1
         //  o  The initial cwnd before DATA transmission or after a sufficiently
@@ -285,6 +308,9 @@ impl AssociationInternal {
         if let Some(treconfig) = &self.treconfig {
             treconfig.stop().await;
         }
+        if let Some(pmtu_probe_timer) = &self.pmtu_probe_timer {
+            pmtu_probe_timer.stop().await;
+        }
         if let Some(ack_timer) = &mut self.ack_timer {
             ack_timer.stop();
         }
@@ -307,6 +333,8 @@ impl AssociationInternal {
                 s.read_notifier.notify_waiters();
             }
             s.write_shutdown.store(true, Ordering::SeqCst);
+            self.pending_queue
+                .set_stream_priority(stream_identifier, DEFAULT_STREAM_PRIORITY);
         }
     }
 
@@ -440,7 +468,7 @@ impl AssociationInternal {
                     //      of cwnd and SHOULD NOT delay retransmission for this single
                     //		packet.
 
-                    let data_chunk_size = DATA_CHUNK_HEADER_SIZE + c.user_data.len() as u32;
+                    let data_chunk_size = data_chunk_header_size(c) + c.user_data.len() as u32;
                     if self.mtu < fast_retrans_size + data_chunk_size {
                         break;
                     }
@@ -499,8 +527,16 @@ impl AssociationInternal {
                 self.advanced_peer_tsn_ack_point,
                 self.cumulative_tsn_ack_point,
             ) {
-                let fwd_tsn = self.create_forward_tsn();
-                let p = self.create_packet(vec![Box::new(fwd_tsn)]);
+                // From RFC 8260 Sec 2.3:
+                //   The I-FORWARD-TSN chunk replaces the FORWARD-TSN chunk when
+                //   I-DATA chunks are used.
+                let p = if self.use_interleaving {
+                    let fwd_tsn = self.create_i_forward_tsn();
+                    self.create_packet(vec![Box::new(fwd_tsn)])
+                } else {
+                    let fwd_tsn = self.create_forward_tsn();
+                    self.create_packet(vec![Box::new(fwd_tsn)])
+                };
                 raw_packets.push(p);
             }
         }
@@ -508,6 +544,33 @@ impl AssociationInternal {
         raw_packets
     }
 
+    /// gather_outbound_pmtu_probe_packets adds the next path MTU probe, if any: a
+    /// HEARTBEAT chunk padded with a PAD chunk to the probed size (RFC 8899 Sec 6.2).
+    async fn gather_outbound_pmtu_probe_packets(
+        &mut self,
+        mut raw_packets: Vec<Packet>,
+    ) -> Vec<Packet> {
+        if let Some(size) = self.pmtud.next_probe(SystemTime::now()) {
+            log::debug!("[{}] sending PMTU probe of {} bytes", self.name, size);
+            raw_packets.push(self.create_packet(vec![
+                Box::new(ChunkHeartbeat {
+                    params: vec![Box::new(ParamHeartbeatInfo {
+                        heartbeat_information: marshal_pmtu_probe_info(size),
+                    })],
+                }),
+                Box::new(ChunkPadding {
+                    padding_length: size.saturating_sub(PMTU_PROBE_OVERHEAD) as usize,
+                }),
+            ]));
+
+            if let Some(pmtu_probe_timer) = &self.pmtu_probe_timer {
+                pmtu_probe_timer.start(self.rto_mgr.get_rto()).await;
+            }
+        }
+
+        raw_packets
+    }
+
     async fn gather_outbound_shutdown_packets(
         &mut self,
         mut raw_packets: Vec<Packet>,
@@ -570,6 +633,7 @@ impl AssociationInternal {
                 raw_packets = self.gather_outbound_fast_retransmission_packets(raw_packets);
                 raw_packets = self.gather_outbound_sack_packets(raw_packets).await;
                 raw_packets = self.gather_outbound_forward_tsn_packets(raw_packets);
+                raw_packets = self.gather_outbound_pmtu_probe_packets(raw_packets).await;
                 (raw_packets, true)
             }
             AssociationState::ShutdownPending
@@ -605,6 +669,13 @@ impl AssociationInternal {
         self.state.load(Ordering::SeqCst).into()
     }
 
+    /// set_use_interleaving switches to I-DATA chunks once both endpoints listed them in
+    /// their supported extensions.
+    fn set_use_interleaving(&mut self) {
+        self.use_interleaving = true;
+        self.pending_queue.set_interleaved(true);
+    }
+
     async fn handle_init(&mut self, p: &Packet, i: &ChunkInit) -> Result<Vec<Packet>> {
         let state = self.get_state();
         log::debug!("[{}] chunkInit received in state '{}'", self.name, state);
@@ -650,6 +721,9 @@ impl AssociationInternal {
                     if *t == CT_FORWARD_TSN {
                         log::debug!("[{}] use ForwardTSN (on init)", self.name);
                         self.use_forward_tsn = true;
+                    } else if *t == CT_I_DATA {
+                        log::debug!("[{}] use I-DATA (on init)", self.name);
+                        self.set_use_interleaving();
                     }
                 }
             }
@@ -769,6 +843,9 @@ impl AssociationInternal {
                     if *t == CT_FORWARD_TSN {
                         log::debug!("[{}] use ForwardTSN (on initAck)", self.name);
                         self.use_forward_tsn = true;
+                    } else if *t == CT_I_DATA {
+                        log::debug!("[{}] use I-DATA (on initAck)", self.name);
+                        self.set_use_interleaving();
                     }
                 }
             } else if param
@@ -827,6 +904,44 @@ impl AssociationInternal {
         Ok(vec![])
     }
 
+    async fn handle_heartbeat_ack(&mut self, c: &ChunkHeartbeatAck) -> Result<Vec<Packet>> {
+        log::trace!("[{}] chunkHeartbeatAck", self.name);
+        let probe_size = c
+            .params
+            .first()
+            .and_then(|p| p.as_any().downcast_ref::<ParamHeartbeatInfo>())
+            .and_then(|hbi| unmarshal_pmtu_probe_info(&hbi.heartbeat_information));
+
+        if let Some(size) = probe_size {
+            if self.pmtud.on_probe_acked(size, SystemTime::now()) {
+                if let Some(pmtu_probe_timer) = &self.pmtu_probe_timer {
+                    pmtu_probe_timer.stop().await;
+                }
+                self.set_mtu(self.pmtud.plpmtu());
+                // send the probe of the next size
+                self.awake_write_loop();
+            }
+        }
+
+        Ok(vec![])
+    }
+
+    /// set_mtu sets the MTU packets are sized to. Messages written from now on are
+    /// fragmented to fit it, while the chunks already queued keep their size.
+    fn set_mtu(&mut self, mtu: u32) {
+        if self.mtu == mtu {
+            return;
+        }
+        log::debug!("[{}] updated mtu: {} => {}", self.name, self.mtu, mtu);
+
+        self.mtu = mtu;
+        self.max_payload_size.store(
+            mtu - (COMMON_HEADER_SIZE + DATA_CHUNK_HEADER_SIZE),
+            Ordering::SeqCst,
+        );
+        self.stats.set_mtu(mtu);
+    }
+
     async fn handle_cookie_echo(&mut self, c: &ChunkCookieEcho) -> Result<Vec<Packet>> {
         let state = self.get_state();
         log::debug!("[{}] COOKIE-ECHO received in state '{}'", self.name, state);
@@ -1042,7 +1157,7 @@ impl AssociationInternal {
         let s = Arc::new(Stream::new(
             format!("{}:{}", stream_identifier, self.name),
             stream_identifier,
-            self.max_payload_size,
+            Arc::clone(&self.max_payload_size),
             Arc::clone(&self.max_message_size),
             Arc::clone(&self.state),
             self.awake_write_loop_ch.clone(),
@@ -1611,6 +1726,51 @@ impl AssociationInternal {
         fwd_tsn
     }
 
+    /// create_i_forward_tsn generates an I-FORWARD-TSN chunk, which reports the largest
+    /// skipped MID of the ordered and unordered messages of each stream.
+    fn create_i_forward_tsn(&self) -> ChunkIForwardTsn {
+        // to report only once per SI and ordering
+        let mut stream_map: HashMap<(u16, bool), u32> = HashMap::new();
+        let mut i = self.cumulative_tsn_ack_point + 1;
+        while sna32lte(i, self.advanced_peer_tsn_ack_point) {
+            if let Some(c) = self.inflight_queue.get(i) {
+                let key = (c.stream_identifier, c.unordered);
+                match stream_map.get(&key) {
+                    Some(mid) if !sna32lt(*mid, c.message_identifier) => {}
+                    _ => {
+                        // to report only once with greatest MID
+                        stream_map.insert(key, c.message_identifier);
+                    }
+                }
+            } else {
+                break;
+            }
+
+            i += 1;
+        }
+
+        let mut fwd_tsn = ChunkIForwardTsn {
+            new_cumulative_tsn: self.advanced_peer_tsn_ack_point,
+            streams: vec![],
+        };
+
+        for ((si, unordered), mid) in &stream_map {
+            fwd_tsn.streams.push(ChunkIForwardTsnStream {
+                identifier: *si,
+                unordered: *unordered,
+                message_identifier: *mid,
+            });
+        }
+        log::trace!(
+            "[{}] building i_fwd_tsn: newCumulativeTSN={} cumTSN={}",
+            self.name,
+            fwd_tsn.new_cumulative_tsn,
+            self.cumulative_tsn_ack_point,
+        );
+
+        fwd_tsn
+    }
+
     /// create_packet wraps chunks in a packet.
     /// The caller should hold the read lock.
     pub(crate) fn create_packet(&self, chunks: Vec<Box<dyn Chunk + Send + Sync>>) -> Packet {
@@ -1641,6 +1801,55 @@ impl AssociationInternal {
     async fn handle_forward_tsn(&mut self, c: &ChunkForwardTsn) -> Result<Vec<Packet>> {
         log::trace!("[{}] FwdTSN: {}", self.name, c.to_string());
 
+        if let Some(reply) = self.forward_peer_last_tsn(c.new_cumulative_tsn) {
+            return Ok(reply);
+        }
+
+        // Report new peer_last_tsn value and abandoned largest SSN value to
+        // corresponding streams so that the abandoned chunks can be removed
+        // from the reassemblyQueue.
+        for forwarded in &c.streams {
+            if let Some(s) = self.streams.get_mut(&forwarded.identifier) {
+                s.handle_forward_tsn_for_ordered(forwarded.sequence).await;
+            }
+        }
+
+        // TSN may be forewared for unordered chunks. ForwardTSN chunk does not
+        // report which stream identifier it skipped for unordered chunks.
+        // Therefore, we need to broadcast this event to all existing streams for
+        // unordered chunks.
+        // See https://github.com/pion/sctp/issues/106
+        for s in self.streams.values_mut() {
+            s.handle_forward_tsn_for_unordered(c.new_cumulative_tsn)
+                .await;
+        }
+
+        self.handle_peer_last_tsn_and_acknowledgement(false)
+    }
+
+    async fn handle_i_forward_tsn(&mut self, c: &ChunkIForwardTsn) -> Result<Vec<Packet>> {
+        log::trace!("[{}] I-FwdTSN: {}", self.name, c.to_string());
+
+        if let Some(reply) = self.forward_peer_last_tsn(c.new_cumulative_tsn) {
+            return Ok(reply);
+        }
+
+        // Unlike the ForwardTSN chunk, the I-FORWARD-TSN chunk reports the skipped
+        // messages by MID for both ordered and unordered messages.
+        for forwarded in &c.streams {
+            if let Some(s) = self.streams.get_mut(&forwarded.identifier) {
+                s.handle_forward_tsn_for_mid(forwarded.unordered, forwarded.message_identifier)
+                    .await;
+            }
+        }
+
+        self.handle_peer_last_tsn_and_acknowledgement(false)
+    }
+
+    /// forward_peer_last_tsn advances peer_last_tsn to the new cumulative TSN of a
+    /// ForwardTSN or I-FORWARD-TSN chunk. It returns the packets to reply with if the
+    /// chunk must not be processed further.
+    fn forward_peer_last_tsn(&mut self, new_cumulative_tsn: u32) -> Option<Vec<Packet>> {
         if !self.use_forward_tsn {
             log::warn!("[{}] received FwdTSN but not enabled", self.name);
             // Return an error chunk
@@ -1654,7 +1863,7 @@ impl AssociationInternal {
                 destination_port: self.destination_port,
                 chunks: vec![Box::new(cerr)],
             };
-            return Ok(vec![outbound]);
+            return Some(vec![outbound]);
         }
 
         // From RFC 3758 Sec 3.6:
@@ -1668,17 +1877,17 @@ impl AssociationInternal {
         log::trace!(
             "[{}] should send ack? newCumTSN={} peer_last_tsn={}",
             self.name,
-            c.new_cumulative_tsn,
+            new_cumulative_tsn,
             self.peer_last_tsn
         );
-        if sna32lte(c.new_cumulative_tsn, self.peer_last_tsn) {
+        if sna32lte(new_cumulative_tsn, self.peer_last_tsn) {
             log::trace!("[{}] sending ack on Forward TSN", self.name);
             self.ack_state = AckState::Immediate;
             if let Some(ack_timer) = &mut self.ack_timer {
                 ack_timer.stop();
             }
             self.awake_write_loop();
-            return Ok(vec![]);
+            return Some(vec![]);
         }
 
         // From RFC 3758 Sec 3.6:
@@ -1692,31 +1901,12 @@ impl AssociationInternal {
         //   chunk,
 
         // Advance peer_last_tsn
-        while sna32lt(self.peer_last_tsn, c.new_cumulative_tsn) {
+        while sna32lt(self.peer_last_tsn, new_cumulative_tsn) {
             self.payload_queue.pop(self.peer_last_tsn + 1); // may not exist
             self.peer_last_tsn += 1;
         }
 
-        // Report new peer_last_tsn value and abandoned largest SSN value to
-        // corresponding streams so that the abandoned chunks can be removed
-        // from the reassemblyQueue.
-        for forwarded in &c.streams {
-            if let Some(s) = self.streams.get_mut(&forwarded.identifier) {
-                s.handle_forward_tsn_for_ordered(forwarded.sequence).await;
-            }
-        }
-
-        // TSN may be forewared for unordered chunks. ForwardTSN chunk does not
-        // report which stream identifier it skipped for unordered chunks.
-        // Therefore, we need to broadcast this event to all existing streams for
-        // unordered chunks.
-        // See https://github.com/pion/sctp/issues/106
-        for s in self.streams.values_mut() {
-            s.handle_forward_tsn_for_unordered(c.new_cumulative_tsn)
-                .await;
-        }
-
-        self.handle_peer_last_tsn_and_acknowledgement(false)
+        None
     }
 
     async fn send_reset_request(&mut self, stream_identifier: u16) -> Result<()> {
@@ -1973,7 +2163,7 @@ impl AssociationInternal {
                 bytes_in_packet = COMMON_HEADER_SIZE;
             }
 
-            bytes_in_packet += DATA_CHUNK_HEADER_SIZE + c.user_data.len() as u32;
+            bytes_in_packet += data_chunk_header_size(&c) + c.user_data.len() as u32;
             chunks_to_send.push(Box::new(c));
         }
 
@@ -2154,6 +2344,8 @@ impl AssociationInternal {
             return Err(Error::ErrChunk);
         } else if let Some(c) = chunk_any.downcast_ref::<ChunkHeartbeat>() {
             self.handle_heartbeat(c).await?
+        } else if let Some(c) = chunk_any.downcast_ref::<ChunkHeartbeatAck>() {
+            self.handle_heartbeat_ack(c).await?
         } else if let Some(c) = chunk_any.downcast_ref::<ChunkCookieEcho>() {
             self.handle_cookie_echo(c).await?
         } else if chunk_any.downcast_ref::<ChunkCookieAck>().is_some() {
@@ -2166,6 +2358,8 @@ impl AssociationInternal {
             self.handle_reconfig(c).await?
         } else if let Some(c) = chunk_any.downcast_ref::<ChunkForwardTsn>() {
             self.handle_forward_tsn(c).await?
+        } else if let Some(c) = chunk_any.downcast_ref::<ChunkIForwardTsn>() {
+            self.handle_i_forward_tsn(c).await?
         } else if let Some(c) = chunk_any.downcast_ref::<ChunkShutdown>() {
             self.handle_shutdown(c).await?
         } else if let Some(c) = chunk_any.downcast_ref::<ChunkShutdownAck>() {
@@ -2375,6 +2569,18 @@ impl RtxTimerObserver for AssociationInternal {
                     self.ssthresh
                 );
 
+                // RFC 8899 Sec 4.3
+                //  A PL that sent a packet of the PLPMTU size that was lost repeatedly
+                //  may be facing a black hole, and falls back to the base PLPMTU.
+                if n_rtos >= 2 && self.pmtud.on_black_hole(SystemTime::now()) {
+                    log::warn!(
+                        "[{}] T3-rtx timed out {} times, assuming a PMTU black hole",
+                        self.name,
+                        n_rtos
+                    );
+                    self.set_mtu(self.pmtud.plpmtu());
+                }
+
                 self.inflight_queue.mark_all_to_retrasmit();
                 self.awake_write_loop();
             }
@@ -2383,6 +2589,11 @@ impl RtxTimerObserver for AssociationInternal {
                 self.will_retransmit_reconfig = true;
                 self.awake_write_loop();
             }
+
+            RtxTimerId::PmtuProbe => {
+                self.pmtud.on_probe_timeout();
+                self.awake_write_loop();
+            }
         }
     }
 
@@ -2416,6 +2627,16 @@ impl RtxTimerObserver for AssociationInternal {
                 //  * WebRTC spec is not clear how this incident should be reported to ULP
                 log::error!("[{}] retransmission failure: T3-rtx (DATA)", self.name);
             }
+
+            RtxTimerId::PmtuProbe => {
+                log::debug!(
+                    "[{}] PMTU probe lost {} times, search completed with mtu={}",
+                    self.name,
+                    MAX_PROBES,
+                    self.pmtud.plpmtu()
+                );
+                self.pmtud.on_probe_failed(SystemTime::now());
+            }
             _ => {}
         }
     }
@@ -365,6 +365,7 @@ async fn handle_init_test(name: &str, initial_state: AssociationState, expect_er
         max_receive_buffer_size: 0,
         max_message_size: 0,
         name: "client".to_owned(),
+        stream_scheduler: StreamSchedulerType::default(),
     });
     a.set_state(initial_state);
     let pkt = Packet {
@@ -455,6 +456,7 @@ async fn test_assoc_max_message_size_default() -> Result<()> {
         max_receive_buffer_size: 0,
         max_message_size: 0,
         name: "client".to_owned(),
+        stream_scheduler: StreamSchedulerType::default(),
     });
     assert_eq!(
         a.max_message_size.load(Ordering::SeqCst),
@@ -500,6 +502,7 @@ async fn test_assoc_max_message_size_explicit() -> Result<()> {
         max_receive_buffer_size: 0,
         max_message_size: 30000,
         name: "client".to_owned(),
+        stream_scheduler: StreamSchedulerType::default(),
     });
 
     assert_eq!(
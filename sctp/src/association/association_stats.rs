@@ -1,4 +1,4 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 
 #[derive(Default, Debug)]
 pub(crate) struct AssociationStats {
@@ -7,6 +7,7 @@ pub(crate) struct AssociationStats {
     n_t3timeouts: AtomicU64,
     n_ack_timeouts: AtomicU64,
     n_fast_retrans: AtomicU64,
+    mtu: AtomicU32,
 }
 
 impl AssociationStats {
@@ -50,6 +51,16 @@ impl AssociationStats {
         self.n_fast_retrans.load(Ordering::SeqCst)
     }
 
+    pub(crate) fn set_mtu(&self, mtu: u32) {
+        self.mtu.store(mtu, Ordering::SeqCst);
+    }
+
+    pub(crate) fn get_mtu(&self) -> u32 {
+        self.mtu.load(Ordering::SeqCst)
+    }
+
+    /// reset clears the counters. The MTU is the current state of the path, not a
+    /// counter, so it is kept.
     pub(crate) fn reset(&self) {
         self.n_datas.store(0, Ordering::SeqCst);
         self.n_sacks.store(0, Ordering::SeqCst);
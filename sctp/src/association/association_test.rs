@@ -35,6 +35,7 @@ async fn create_new_association_pair(
             max_receive_buffer_size: recv_buf_size,
             max_message_size: 0,
             name: "client".to_owned(),
+            stream_scheduler: StreamSchedulerType::default(),
         })
         .await;
 
@@ -51,6 +52,7 @@ async fn create_new_association_pair(
             max_receive_buffer_size: recv_buf_size,
             max_message_size: 0,
             name: "server".to_owned(),
+            stream_scheduler: StreamSchedulerType::default(),
         })
         .await;
 
@@ -452,6 +454,59 @@ async fn test_assoc_reliable_ordered_fragmented_then_defragmented() -> Result<()
     Ok(())
 }
 
+#[tokio::test]
+async fn test_assoc_reliable_interleaved_streams() -> Result<()> {
+    const SI0: u16 = 1;
+    const SI1: u16 = 2;
+    let mut sbufl = vec![0u8; 4000];
+    for i in 0..sbufl.len() {
+        sbufl[i] = (i & 0xff) as u8;
+    }
+    let sbufs = Bytes::from_static(b"small message");
+
+    let (br, ca, cb) = Bridge::new(0, None, None);
+
+    let (a0, mut a1) =
+        create_new_association_pair(&br, Arc::new(ca), Arc::new(cb), AckMode::NoDelay, 0).await?;
+
+    {
+        let ai0 = a0.association_internal.lock().await;
+        let ai1 = a1.association_internal.lock().await;
+        assert!(ai0.use_interleaving, "a0 should have negotiated I-DATA");
+        assert!(ai1.use_interleaving, "a1 should have negotiated I-DATA");
+    }
+
+    let (s00, s10) = establish_session_pair(&br, &a0, &mut a1, SI0).await?;
+    let (s01, s11) = establish_session_pair(&br, &a0, &mut a1, SI1).await?;
+
+    let n = s00
+        .write_sctp(
+            &Bytes::from(sbufl.clone()),
+            PayloadProtocolIdentifier::Binary,
+        )
+        .await?;
+    assert_eq!(n, sbufl.len(), "unexpected length of sent data");
+    let n = s01
+        .write_sctp(&sbufs, PayloadProtocolIdentifier::String)
+        .await?;
+    assert_eq!(n, sbufs.len(), "unexpected length of sent data");
+
+    flush_buffers(&br, &a0, &a1).await;
+
+    let mut rbuf = vec![0u8; 4000];
+    let (n, ppi) = s11.read_sctp(&mut rbuf).await?;
+    assert_eq!(&rbuf[..n], &sbufs[..], "unexpected received data");
+    assert_eq!(ppi, PayloadProtocolIdentifier::String, "unexpected ppi");
+
+    let (n, ppi) = s10.read_sctp(&mut rbuf).await?;
+    assert_eq!(&rbuf[..n], &sbufl, "unexpected received data");
+    assert_eq!(ppi, PayloadProtocolIdentifier::Binary, "unexpected ppi");
+
+    close_association_pair(&br, a0, a1).await;
+
+    Ok(())
+}
+
 //use std::io::Write;
 
 #[tokio::test]
@@ -2209,6 +2264,7 @@ async fn test_stats() -> Result<()> {
         max_receive_buffer_size: 0,
         max_message_size: 0,
         name: "client".to_owned(),
+        stream_scheduler: StreamSchedulerType::default(),
     })
     .await?;
 
@@ -2244,6 +2300,7 @@ async fn create_assocs() -> Result<(Association, Association)> {
             max_receive_buffer_size: 0,
             max_message_size: 0,
             name: "client".to_owned(),
+            stream_scheduler: StreamSchedulerType::default(),
         })
         .await?;
 
@@ -2258,6 +2315,7 @@ async fn create_assocs() -> Result<(Association, Association)> {
             max_receive_buffer_size: 0,
             max_message_size: 0,
             name: "server".to_owned(),
+            stream_scheduler: StreamSchedulerType::default(),
         })
         .await?;
 
@@ -2440,6 +2498,55 @@ async fn test_association_shutdown_during_write() -> Result<()> {
     Ok(())
 }
 
+//TODO: remove this conditional test
+#[cfg(not(target_os = "windows"))]
+#[tokio::test]
+async fn test_association_path_mtu_discovery() -> Result<()> {
+    let (a1, a2) = create_assocs().await?;
+    assert_eq!(a1.mtu(), INITIAL_MTU);
+
+    {
+        let mut ai = a1.association_internal.lock().await;
+        ai.pmtud.set_enabled(true);
+    }
+    let _ = a1.awake_write_loop_ch.try_send(());
+
+    // the loopback path passes every probe, so the search ends at the largest size
+    let result = tokio::time::timeout(Duration::from_secs(5), async {
+        while a1.mtu() != MAX_PLPMTU {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    assert!(result.is_ok(), "timed out waiting for the PMTU search");
+    assert_eq!(
+        a1.association_internal.lock().await.pmtud.state(),
+        PmtudState::SearchComplete
+    );
+    assert_eq!(a2.mtu(), INITIAL_MTU, "the peer does not probe");
+
+    // messages are fragmented to the new MTU
+    let s11 = a1.open_stream(1, PayloadProtocolIdentifier::Binary).await?;
+    let s21 = a2.open_stream(1, PayloadProtocolIdentifier::Binary).await?;
+    assert_eq!(
+        s11.max_payload_size.load(Ordering::SeqCst),
+        MAX_PLPMTU - (COMMON_HEADER_SIZE + DATA_CHUNK_HEADER_SIZE)
+    );
+
+    let test_data = Bytes::from(vec![7u8; 4000]);
+    let n = s11.write(&test_data).await?;
+    assert_eq!(n, test_data.len());
+
+    let mut buf = vec![0u8; test_data.len()];
+    let n = s21.read(&mut buf).await?;
+    assert_eq!(&buf[..n], &test_data);
+
+    a1.close().await?;
+    a2.close().await?;
+
+    Ok(())
+}
+
 //use std::io::Write;
 
 #[tokio::test]
@@ -2602,6 +2709,7 @@ async fn test_association_handle_packet_before_init() -> Result<()> {
                 max_message_size: 0,
                 max_receive_buffer_size: 0,
                 name: "client".to_owned(),
+                stream_scheduler: StreamSchedulerType::default(),
             },
             true,
         )
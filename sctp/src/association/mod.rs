@@ -3,6 +3,7 @@ mod association_test;
 
 mod association_internal;
 mod association_stats;
+mod path_mtu_discovery;
 
 use std::collections::{HashMap, VecDeque};
 use std::fmt;
@@ -13,6 +14,7 @@ use std::time::SystemTime;
 use association_internal::*;
 use association_stats::*;
 use bytes::{Bytes, BytesMut};
+use path_mtu_discovery::*;
 use rand::random;
 use tokio::sync::{broadcast, mpsc, Mutex};
 use util::Conn;
@@ -24,7 +26,9 @@ use crate::chunk::chunk_error::ChunkError;
 use crate::chunk::chunk_forward_tsn::{ChunkForwardTsn, ChunkForwardTsnStream};
 use crate::chunk::chunk_heartbeat::ChunkHeartbeat;
 use crate::chunk::chunk_heartbeat_ack::ChunkHeartbeatAck;
+use crate::chunk::chunk_i_forward_tsn::{ChunkIForwardTsn, ChunkIForwardTsnStream};
 use crate::chunk::chunk_init::ChunkInit;
+use crate::chunk::chunk_padding::ChunkPadding;
 use crate::chunk::chunk_payload_data::{ChunkPayloadData, PayloadProtocolIdentifier};
 use crate::chunk::chunk_reconfig::ChunkReconfig;
 use crate::chunk::chunk_selective_ack::ChunkSelectiveAck;
@@ -45,6 +49,7 @@ use crate::param::Param;
 use crate::queue::control_queue::ControlQueue;
 use crate::queue::payload_queue::PayloadQueue;
 use crate::queue::pending_queue::PendingQueue;
+pub use crate::queue::stream_scheduler::{StreamSchedulerType, DEFAULT_STREAM_PRIORITY};
 use crate::stream::*;
 use crate::timer::ack_timer::*;
 use crate::timer::rtx_timer::*;
@@ -57,6 +62,7 @@ pub(crate) const INITIAL_MTU: u32 = 1228;
 pub(crate) const INITIAL_RECV_BUF_SIZE: u32 = 1024 * 1024;
 pub(crate) const COMMON_HEADER_SIZE: u32 = 12;
 pub(crate) const DATA_CHUNK_HEADER_SIZE: u32 = 16;
+pub(crate) const I_DATA_CHUNK_HEADER_SIZE: u32 = 20;
 pub(crate) const DEFAULT_MAX_MESSAGE_SIZE: u32 = 65536;
 
 /// other constants
@@ -115,6 +121,7 @@ pub(crate) enum RtxTimerId {
     T2Shutdown,
     T3RTX,
     Reconfig,
+    PmtuProbe,
 }
 
 impl fmt::Display for RtxTimerId {
@@ -125,6 +132,7 @@ impl fmt::Display for RtxTimerId {
             RtxTimerId::T2Shutdown => "T2Shutdown",
             RtxTimerId::T3RTX => "T3RTX",
             RtxTimerId::Reconfig => "Reconfig",
+            RtxTimerId::PmtuProbe => "PmtuProbe",
         };
         write!(f, "{s}")
     }
@@ -177,6 +185,8 @@ pub struct Config {
     pub max_receive_buffer_size: u32,
     pub max_message_size: u32,
     pub name: String,
+    /// stream_scheduler selects how the outgoing streams share the sending capacity.
+    pub stream_scheduler: StreamSchedulerType,
 }
 
 ///Association represents an SCTP association
@@ -208,6 +218,7 @@ pub struct Association {
     net_conn: Arc<dyn Conn + Send + Sync>,
     bytes_received: Arc<AtomicUsize>,
     bytes_sent: Arc<AtomicUsize>,
+    stats: Arc<AssociationStats>,
 
     pub(crate) association_internal: Arc<Mutex<AssociationInternal>>,
 }
@@ -308,6 +319,7 @@ impl Association {
         let max_message_size = Arc::clone(&ai.max_message_size);
         let inflight_queue_length = Arc::clone(&ai.inflight_queue_length);
         let will_send_shutdown = Arc::clone(&ai.will_send_shutdown);
+        let stats = Arc::clone(&ai.stats);
 
         let mut init = ChunkInit {
             initial_tsn: ai.my_next_tsn,
@@ -361,6 +373,11 @@ impl Association {
                 RtxTimerId::Reconfig,
                 NO_MAX_RETRANS,
             )); // retransmit forever
+            ai.pmtu_probe_timer = Some(RtxTimer::new(
+                Arc::downgrade(&association_internal3),
+                RtxTimerId::PmtuProbe,
+                MAX_PROBES - 1,
+            ));
             ai.ack_timer = Some(AckTimer::new(
                 Arc::downgrade(&association_internal3),
                 ACK_INTERVAL,
@@ -414,6 +431,7 @@ impl Association {
                 net_conn,
                 bytes_received,
                 bytes_sent,
+                stats,
                 association_internal,
             },
             handshake_completed_ch_rx,
@@ -504,6 +522,7 @@ impl Association {
                 let mut buf = buffer
                     .take()
                     .unwrap_or_else(|| BytesMut::with_capacity(16 * 1024));
+                let is_pmtu_probe = raw.is_pmtu_probe();
 
                 // We do the marshalling work in a blocking task here for a reason:
                 // If we don't tokio tends to run the write_loop and read_loop of one connection on the same OS thread
@@ -516,8 +535,18 @@ impl Association {
                     Ok(mut buf) => {
                         let raw = buf.as_ref();
                         if let Err(err) = net_conn.send(raw.as_ref()).await {
-                            log::warn!("[{}] failed to write packets on net_conn: {}", name2, err);
-                            done2.store(true, Ordering::Relaxed)
+                            if is_pmtu_probe {
+                                // A probe larger than the local MTU may be refused, which
+                                // is handled like a lost probe
+                                log::debug!("[{}] failed to write PMTU probe: {}", name2, err);
+                            } else {
+                                log::warn!(
+                                    "[{}] failed to write packets on net_conn: {}",
+                                    name2,
+                                    err
+                                );
+                                done2.store(true, Ordering::Relaxed)
+                            }
                         } else {
                             bytes_sent.fetch_add(raw.len(), Ordering::SeqCst);
                         }
@@ -567,6 +596,12 @@ impl Association {
         self.bytes_received.load(Ordering::SeqCst)
     }
 
+    /// mtu returns the MTU outgoing packets are currently sized to, as found by the
+    /// path MTU discovery.
+    pub fn mtu(&self) -> u32 {
+        self.stats.get_mtu()
+    }
+
     /// open_stream opens a stream
     pub async fn open_stream(
         &self,
@@ -0,0 +1,287 @@
+use std::time::{Duration, SystemTime};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use super::{COMMON_HEADER_SIZE, INITIAL_MTU};
+use crate::chunk::chunk_header::CHUNK_HEADER_SIZE;
+use crate::param::param_header::PARAM_HEADER_LENGTH;
+
+/// BASE_PLPMTU is the MTU assumed to work on every path, and the one used until a
+/// probe of a larger size is acknowledged (RFC 8899 Sec 5.1.4).
+pub(crate) const BASE_PLPMTU: u32 = INITIAL_MTU;
+/// MAX_PLPMTU is the largest MTU probed for. It leaves room for the IPv6, UDP and
+/// DTLS headers within the 1500 bytes of an Ethernet link.
+pub(crate) const MAX_PLPMTU: u32 = 1400;
+/// PMTU_PROBE_STEP is the size increase between two probes.
+pub(crate) const PMTU_PROBE_STEP: u32 = 32;
+/// MAX_PROBES is the number of probes of a size sent before the size is considered
+/// not to pass the path (RFC 8899 Sec 5.1.2).
+pub(crate) const MAX_PROBES: usize = 3;
+/// PMTU_RAISE_INTERVAL is how long a completed search is kept before larger sizes are
+/// probed again (RFC 8899 Sec 5.1.1).
+pub(crate) const PMTU_RAISE_INTERVAL: Duration = Duration::from_secs(600);
+
+/// PMTU_PROBE_MAGIC marks the heartbeat information of a probe ("PMTU").
+const PMTU_PROBE_MAGIC: u32 = 0x504d_5455;
+const PMTU_PROBE_INFO_LENGTH: usize = 8;
+/// PMTU_PROBE_OVERHEAD is the size of a probe packet without the padding data: the
+/// common header, the HEARTBEAT chunk with its information and the PAD chunk header.
+pub(crate) const PMTU_PROBE_OVERHEAD: u32 = COMMON_HEADER_SIZE
+    + (2 * CHUNK_HEADER_SIZE + PARAM_HEADER_LENGTH + PMTU_PROBE_INFO_LENGTH) as u32;
+
+/// marshal_pmtu_probe_info returns the heartbeat information of a probe of the given size.
+pub(crate) fn marshal_pmtu_probe_info(size: u32) -> Bytes {
+    let mut info = BytesMut::with_capacity(PMTU_PROBE_INFO_LENGTH);
+    info.put_u32(PMTU_PROBE_MAGIC);
+    info.put_u32(size);
+    info.freeze()
+}
+
+/// unmarshal_pmtu_probe_info returns the probe size carried by a heartbeat information,
+/// or None if the heartbeat was not a probe.
+pub(crate) fn unmarshal_pmtu_probe_info(info: &Bytes) -> Option<u32> {
+    if info.len() != PMTU_PROBE_INFO_LENGTH {
+        return None;
+    }
+    let reader = &mut info.clone();
+    if reader.get_u32() != PMTU_PROBE_MAGIC {
+        return None;
+    }
+    Some(reader.get_u32())
+}
+
+/// PmtudState is the state of the packetization layer path MTU discovery
+/// (RFC 8899 Sec 5.2).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum PmtudState {
+    Disabled,
+    Base,
+    Searching,
+    SearchComplete,
+}
+
+/// PathMtuDiscovery implements the packetization layer path MTU discovery of
+/// RFC 8899. Probes are HEARTBEAT chunks padded with a PAD chunk to the probed
+/// size; an acknowledged probe raises the PLPMTU, MAX_PROBES lost probes complete the
+/// search, and a black hole, detected by the association, falls back to BASE_PLPMTU.
+#[derive(Debug)]
+pub(crate) struct PathMtuDiscovery {
+    state: PmtudState,
+    plpmtu: u32,
+    probe_size: u32,
+    probe_in_flight: bool,
+    search_completed_at: Option<SystemTime>,
+}
+
+impl Default for PathMtuDiscovery {
+    fn default() -> Self {
+        PathMtuDiscovery::new()
+    }
+}
+
+impl PathMtuDiscovery {
+    pub(crate) fn new() -> Self {
+        PathMtuDiscovery {
+            state: PmtudState::Base,
+            plpmtu: BASE_PLPMTU,
+            probe_size: BASE_PLPMTU,
+            probe_in_flight: false,
+            search_completed_at: None,
+        }
+    }
+
+    /// set_enabled turns the discovery on or off. The PLPMTU is kept when it is
+    /// turned off.
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.state = PmtudState::Disabled;
+            self.probe_in_flight = false;
+        } else if self.state == PmtudState::Disabled {
+            self.state = PmtudState::Base;
+        }
+    }
+
+    pub(crate) fn state(&self) -> PmtudState {
+        self.state
+    }
+
+    /// plpmtu returns the MTU packets should be sized to.
+    pub(crate) fn plpmtu(&self) -> u32 {
+        self.plpmtu
+    }
+
+    /// next_probe returns the size of the probe to send now, if any. It starts the
+    /// search from the base state, and again once PMTU_RAISE_INTERVAL has passed
+    /// since the last search completed.
+    pub(crate) fn next_probe(&mut self, now: SystemTime) -> Option<u32> {
+        match self.state {
+            PmtudState::Disabled => return None,
+            PmtudState::Base => self.start_search(),
+            PmtudState::Searching => {}
+            PmtudState::SearchComplete => {
+                let raise = self.plpmtu < MAX_PLPMTU
+                    && self.search_completed_at.map_or(true, |t| {
+                        now.duration_since(t).unwrap_or_default() >= PMTU_RAISE_INTERVAL
+                    });
+                if !raise {
+                    return None;
+                }
+                self.start_search();
+            }
+        }
+
+        if self.probe_in_flight {
+            None
+        } else {
+            self.probe_in_flight = true;
+            Some(self.probe_size)
+        }
+    }
+
+    fn start_search(&mut self) {
+        self.state = PmtudState::Searching;
+        self.probe_size = (self.plpmtu + PMTU_PROBE_STEP).min(MAX_PLPMTU);
+        self.probe_in_flight = false;
+    }
+
+    fn complete_search(&mut self, now: SystemTime) {
+        self.state = PmtudState::SearchComplete;
+        self.probe_in_flight = false;
+        self.search_completed_at = Some(now);
+    }
+
+    /// on_probe_acked handles the acknowledgement of a probe of the given size, and
+    /// returns true if the PLPMTU was raised to it.
+    pub(crate) fn on_probe_acked(&mut self, size: u32, now: SystemTime) -> bool {
+        if self.state != PmtudState::Searching || size != self.probe_size {
+            return false;
+        }
+
+        self.plpmtu = size;
+        if size >= MAX_PLPMTU {
+            self.complete_search(now);
+        } else {
+            self.probe_size = (size + PMTU_PROBE_STEP).min(MAX_PLPMTU);
+            self.probe_in_flight = false;
+        }
+        true
+    }
+
+    /// on_probe_timeout lets the probe in flight be sent again.
+    pub(crate) fn on_probe_timeout(&mut self) {
+        self.probe_in_flight = false;
+    }
+
+    /// on_probe_failed handles the loss of MAX_PROBES probes of the same size, which
+    /// completes the search at the current PLPMTU.
+    pub(crate) fn on_probe_failed(&mut self, now: SystemTime) {
+        if self.state == PmtudState::Searching {
+            self.complete_search(now);
+        }
+    }
+
+    /// on_black_hole handles packets of the PLPMTU no longer passing the path. It falls
+    /// back to BASE_PLPMTU, and returns true if the PLPMTU was lowered.
+    pub(crate) fn on_black_hole(&mut self, now: SystemTime) -> bool {
+        if self.state == PmtudState::Disabled || self.plpmtu <= BASE_PLPMTU {
+            return false;
+        }
+
+        self.plpmtu = BASE_PLPMTU;
+        self.complete_search(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pmtu_probe_info() {
+        let info = marshal_pmtu_probe_info(1260);
+        assert_eq!(unmarshal_pmtu_probe_info(&info), Some(1260));
+        assert_eq!(
+            unmarshal_pmtu_probe_info(&Bytes::from_static(&[0; 8])),
+            None
+        );
+        assert_eq!(
+            unmarshal_pmtu_probe_info(&Bytes::from_static(&[0; 4])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_path_mtu_discovery_search() {
+        let now = SystemTime::now();
+        let mut pmtud = PathMtuDiscovery::new();
+        assert_eq!(pmtud.plpmtu(), BASE_PLPMTU);
+
+        let mut size = pmtud.next_probe(now).unwrap();
+        assert_eq!(size, BASE_PLPMTU + PMTU_PROBE_STEP);
+        assert_eq!(pmtud.next_probe(now), None, "one probe in flight at a time");
+
+        pmtud.on_probe_timeout();
+        assert_eq!(pmtud.next_probe(now), Some(size), "lost probe is resent");
+
+        assert!(!pmtud.on_probe_acked(size + 1, now), "stale ack is ignored");
+        while pmtud.state() == PmtudState::Searching {
+            assert!(pmtud.on_probe_acked(size, now));
+            assert_eq!(pmtud.plpmtu(), size);
+            if let Some(next) = pmtud.next_probe(now) {
+                assert!(next > size);
+                size = next;
+            }
+        }
+
+        assert_eq!(pmtud.state(), PmtudState::SearchComplete);
+        assert_eq!(pmtud.plpmtu(), MAX_PLPMTU);
+        assert_eq!(pmtud.next_probe(now + PMTU_RAISE_INTERVAL), None);
+    }
+
+    #[test]
+    fn test_path_mtu_discovery_probe_failed() {
+        let now = SystemTime::now();
+        let mut pmtud = PathMtuDiscovery::new();
+
+        let size = pmtud.next_probe(now).unwrap();
+        assert!(pmtud.on_probe_acked(size, now));
+        pmtud.next_probe(now).unwrap();
+        pmtud.on_probe_failed(now);
+        assert_eq!(pmtud.state(), PmtudState::SearchComplete);
+        assert_eq!(pmtud.plpmtu(), size);
+
+        assert_eq!(pmtud.next_probe(now), None);
+        assert_eq!(
+            pmtud.next_probe(now + PMTU_RAISE_INTERVAL),
+            Some(size + PMTU_PROBE_STEP),
+            "larger sizes are probed again after the raise interval"
+        );
+    }
+
+    #[test]
+    fn test_path_mtu_discovery_black_hole() {
+        let now = SystemTime::now();
+        let mut pmtud = PathMtuDiscovery::new();
+        assert!(!pmtud.on_black_hole(now), "already at the base PLPMTU");
+
+        let size = pmtud.next_probe(now).unwrap();
+        assert!(pmtud.on_probe_acked(size, now));
+        assert!(pmtud.on_black_hole(now));
+        assert_eq!(pmtud.plpmtu(), BASE_PLPMTU);
+        assert_eq!(pmtud.state(), PmtudState::SearchComplete);
+        assert!(!pmtud.on_probe_acked(size + PMTU_PROBE_STEP, now));
+    }
+
+    #[test]
+    fn test_path_mtu_discovery_disabled() {
+        let now = SystemTime::now();
+        let mut pmtud = PathMtuDiscovery::new();
+        pmtud.set_enabled(false);
+        assert_eq!(pmtud.next_probe(now), None);
+        assert!(!pmtud.on_black_hole(now));
+
+        pmtud.set_enabled(true);
+        assert_eq!(pmtud.next_probe(now), Some(BASE_PLPMTU + PMTU_PROBE_STEP));
+    }
+}
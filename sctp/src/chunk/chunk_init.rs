@@ -298,7 +298,7 @@ impl ChunkInit {
         // extension MUST list the ASCONF, the ASCONF-ACK, and the AUTH chunks
         // in its INIT and INIT-ACK parameters.
         self.params.push(Box::new(ParamSupportedExtensions {
-            chunk_types: vec![CT_RECONFIG, CT_FORWARD_TSN],
+            chunk_types: vec![CT_RECONFIG, CT_FORWARD_TSN, CT_I_DATA, CT_I_FORWARD_TSN],
         }));
     }
 }
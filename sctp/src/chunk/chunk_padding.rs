@@ -0,0 +1,72 @@
+use std::fmt;
+
+use bytes::{Bytes, BytesMut};
+
+use super::chunk_header::*;
+use super::chunk_type::*;
+use super::*;
+
+///chunkPadding represents an SCTP Chunk of type PAD, defined in RFC 4820.
+///It only pads a packet to a given size, as used by the path MTU probes, and
+///is skipped by its receiver.
+///
+/// 0                   1                   2                   3
+/// 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+///+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+///| Type = 0x84   |   Flags=0     |             Length            |
+///+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+///|                                                               |
+///|                          Padding Data                         |
+///|                                                               |
+///+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+#[derive(Default, Debug, Clone)]
+pub(crate) struct ChunkPadding {
+    pub(crate) padding_length: usize,
+}
+
+/// makes ChunkPadding printable
+impl fmt::Display for ChunkPadding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.header(), self.padding_length)
+    }
+}
+
+impl Chunk for ChunkPadding {
+    fn header(&self) -> ChunkHeader {
+        ChunkHeader {
+            typ: CT_PAD,
+            flags: 0,
+            value_length: self.value_length() as u16,
+        }
+    }
+
+    fn unmarshal(raw: &Bytes) -> Result<Self> {
+        let header = ChunkHeader::unmarshal(raw)?;
+
+        if header.typ != CT_PAD {
+            return Err(Error::ErrChunkTypeNotPadding);
+        }
+
+        Ok(ChunkPadding {
+            padding_length: header.value_length(),
+        })
+    }
+
+    fn marshal_to(&self, writer: &mut BytesMut) -> Result<usize> {
+        self.header().marshal_to(writer)?;
+        writer.resize(writer.len() + self.padding_length, 0);
+        Ok(writer.len())
+    }
+
+    fn check(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn value_length(&self) -> usize {
+        self.padding_length
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+}
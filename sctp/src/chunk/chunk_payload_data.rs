@@ -14,6 +14,7 @@ pub(crate) const PAYLOAD_DATA_BEGINNING_FRAGMENT_BITMASK: u8 = 2;
 pub(crate) const PAYLOAD_DATA_UNORDERED_BITMASK: u8 = 4;
 pub(crate) const PAYLOAD_DATA_IMMEDIATE_SACK: u8 = 8;
 pub(crate) const PAYLOAD_DATA_HEADER_SIZE: usize = 12;
+pub(crate) const PAYLOAD_I_DATA_HEADER_SIZE: usize = 16;
 
 /// PayloadProtocolIdentifier is an enum for DataChannel payload types
 /// PayloadProtocolIdentifier enums
@@ -91,6 +92,32 @@ impl From<u32> for PayloadProtocolIdentifier {
 ///============================================================
 ///|             Table 1: Fragment Description Flags          |
 ///============================================================
+///
+///The same struct represents an SCTP Chunk of type I-DATA if `interleaved` is set. The
+///fragments of a message are then identified by their Message Identifier and Fragment
+///Sequence Number rather than by contiguous TSNs, allowing the fragments of messages of
+///different streams to be interleaved (RFC 8260):
+///
+/// 0                   1                   2                   3
+/// 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+///+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+///|   Type = 64   |  Res  |I|U|B|E|       Length = Variable       |
+///+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+///|                              TSN                              |
+///+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+///|        Stream Identifier      |           Reserved            |
+///+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+///|                      Message Identifier                       |
+///+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+///|    Payload Protocol Identifier / Fragment Sequence Number     |
+///+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+///|                                                               |
+///|                           User Data                           |
+///|                                                               |
+///+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+///
+///The first fragment (B bit set) carries the Payload Protocol Identifier and has an
+///implicit Fragment Sequence Number of 0, the others carry their Fragment Sequence Number.
 #[derive(Debug, Clone)]
 pub struct ChunkPayloadData {
     pub(crate) unordered: bool,
@@ -104,6 +131,13 @@ pub struct ChunkPayloadData {
     pub(crate) payload_type: PayloadProtocolIdentifier,
     pub(crate) user_data: Bytes,
 
+    /// Set if the chunk is an I-DATA chunk
+    pub(crate) interleaved: bool,
+    /// Message Identifier of an I-DATA chunk
+    pub(crate) message_identifier: u32,
+    /// Fragment Sequence Number of an I-DATA chunk
+    pub(crate) fragment_sequence_number: u32,
+
     /// Whether this data chunk was acknowledged (received by peer)
     pub(crate) acked: bool,
     pub(crate) miss_indicator: u32,
@@ -135,6 +169,9 @@ impl Default for ChunkPayloadData {
             stream_sequence_number: 0,
             payload_type: PayloadProtocolIdentifier::default(),
             user_data: Bytes::new(),
+            interleaved: false,
+            message_identifier: 0,
+            fragment_sequence_number: 0,
             acked: false,
             miss_indicator: 0,
             since: SystemTime::now(),
@@ -170,7 +207,11 @@ impl Chunk for ChunkPayloadData {
         }
 
         ChunkHeader {
-            typ: CT_PAYLOAD_DATA,
+            typ: if self.interleaved {
+                CT_I_DATA
+            } else {
+                CT_PAYLOAD_DATA
+            },
             flags,
             value_length: self.value_length() as u16,
         }
@@ -179,9 +220,15 @@ impl Chunk for ChunkPayloadData {
     fn unmarshal(raw: &Bytes) -> Result<Self> {
         let header = ChunkHeader::unmarshal(raw)?;
 
-        if header.typ != CT_PAYLOAD_DATA {
+        if header.typ != CT_PAYLOAD_DATA && header.typ != CT_I_DATA {
             return Err(Error::ErrChunkTypeNotPayloadData);
         }
+        let interleaved = header.typ == CT_I_DATA;
+        let header_size = if interleaved {
+            PAYLOAD_I_DATA_HEADER_SIZE
+        } else {
+            PAYLOAD_DATA_HEADER_SIZE
+        };
 
         let immediate_sack = (header.flags & PAYLOAD_DATA_IMMEDIATE_SACK) != 0;
         let unordered = (header.flags & PAYLOAD_DATA_UNORDERED_BITMASK) != 0;
@@ -189,7 +236,7 @@ impl Chunk for ChunkPayloadData {
         let ending_fragment = (header.flags & PAYLOAD_DATA_ENDING_FRAGMENT_BITMASK) != 0;
 
         // validity of value_length is checked in ChunkHeader::unmarshal
-        if header.value_length() < PAYLOAD_DATA_HEADER_SIZE {
+        if header.value_length() < header_size {
             return Err(Error::ErrChunkPayloadSmall);
         }
 
@@ -197,11 +244,28 @@ impl Chunk for ChunkPayloadData {
 
         let tsn = reader.get_u32();
         let stream_identifier = reader.get_u16();
-        let stream_sequence_number = reader.get_u16();
-        let payload_type: PayloadProtocolIdentifier = reader.get_u32().into();
-        let user_data = raw.slice(
-            CHUNK_HEADER_SIZE + PAYLOAD_DATA_HEADER_SIZE..CHUNK_HEADER_SIZE + header.value_length(),
-        );
+        let (stream_sequence_number, message_identifier, payload_type, fragment_sequence_number) =
+            if interleaved {
+                reader.get_u16(); // reserved
+                let message_identifier = reader.get_u32();
+                let ppi_or_fsn = reader.get_u32();
+                if beginning_fragment {
+                    (0, message_identifier, ppi_or_fsn.into(), 0)
+                } else {
+                    (
+                        0,
+                        message_identifier,
+                        PayloadProtocolIdentifier::Unknown,
+                        ppi_or_fsn,
+                    )
+                }
+            } else {
+                let stream_sequence_number = reader.get_u16();
+                let payload_type = reader.get_u32().into();
+                (stream_sequence_number, 0, payload_type, 0)
+            };
+        let user_data =
+            raw.slice(CHUNK_HEADER_SIZE + header_size..CHUNK_HEADER_SIZE + header.value_length());
 
         Ok(ChunkPayloadData {
             unordered,
@@ -214,6 +278,9 @@ impl Chunk for ChunkPayloadData {
             stream_sequence_number,
             payload_type,
             user_data,
+            interleaved,
+            message_identifier,
+            fragment_sequence_number,
             acked: false,
             miss_indicator: 0,
             since: SystemTime::now(),
@@ -229,8 +296,18 @@ impl Chunk for ChunkPayloadData {
 
         writer.put_u32(self.tsn);
         writer.put_u16(self.stream_identifier);
-        writer.put_u16(self.stream_sequence_number);
-        writer.put_u32(self.payload_type as u32);
+        if self.interleaved {
+            writer.put_u16(0); // reserved
+            writer.put_u32(self.message_identifier);
+            if self.beginning_fragment {
+                writer.put_u32(self.payload_type as u32);
+            } else {
+                writer.put_u32(self.fragment_sequence_number);
+            }
+        } else {
+            writer.put_u16(self.stream_sequence_number);
+            writer.put_u32(self.payload_type as u32);
+        }
         writer.extend_from_slice(&self.user_data);
 
         Ok(writer.len())
@@ -241,7 +318,7 @@ impl Chunk for ChunkPayloadData {
     }
 
     fn value_length(&self) -> usize {
-        PAYLOAD_DATA_HEADER_SIZE + self.user_data.len()
+        self.header_size() + self.user_data.len()
     }
 
     fn as_any(&self) -> &(dyn Any + Send + Sync) {
@@ -250,6 +327,15 @@ impl Chunk for ChunkPayloadData {
 }
 
 impl ChunkPayloadData {
+    /// header_size returns the size of the DATA or I-DATA header, without the chunk header.
+    pub(crate) fn header_size(&self) -> usize {
+        if self.interleaved {
+            PAYLOAD_I_DATA_HEADER_SIZE
+        } else {
+            PAYLOAD_DATA_HEADER_SIZE
+        }
+    }
+
     pub(crate) fn abandoned(&self) -> bool {
         let (abandoned, all_inflight) = (
             self.abandoned.load(Ordering::SeqCst),
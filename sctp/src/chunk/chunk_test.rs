@@ -24,6 +24,7 @@ fn test_chunk_type_string() -> Result<()> {
         (CT_SHUTDOWN_COMPLETE, "SHUTDOWN-COMPLETE"),
         (CT_RECONFIG, "RECONFIG"),
         (CT_FORWARD_TSN, "FORWARD-TSN"),
+        (CT_PAD, "PAD"),
         (ChunkType(255), "Unknown ChunkType: 255"),
     ];
 
@@ -229,6 +230,68 @@ fn test_chunk_forward_tsn_unmarshal_failure() -> Result<()> {
     Ok(())
 }
 
+///////////////////////////////////////////////////////////////////
+//chunk_i_forward_tsn_test
+///////////////////////////////////////////////////////////////////
+use super::chunk_i_forward_tsn::*;
+
+#[test]
+fn test_chunk_i_forward_tsn_success() -> Result<()> {
+    let tests = vec![
+        Bytes::from_static(&[0xc2, 0x0, 0x0, 0x8, 0x0, 0x0, 0x0, 0x3]),
+        Bytes::from_static(&[
+            0xc2, 0x0, 0x0, 0x10, 0x0, 0x0, 0x0, 0x3, 0x0, 0x4, 0x0, 0x1, 0x0, 0x0, 0x0, 0x5,
+        ]),
+        Bytes::from_static(&[
+            0xc2, 0x0, 0x0, 0x18, 0x0, 0x0, 0x0, 0x3, 0x0, 0x4, 0x0, 0x1, 0x0, 0x0, 0x0, 0x5, 0x0,
+            0x6, 0x0, 0x0, 0x0, 0x0, 0x1, 0x7,
+        ]),
+    ];
+
+    for binary in tests {
+        let actual = ChunkIForwardTsn::unmarshal(&binary)?;
+        let b = actual.marshal()?;
+        assert_eq!(b, binary, "test not equal");
+    }
+
+    let c = ChunkIForwardTsn::unmarshal(&Bytes::from_static(&[
+        0xc2, 0x0, 0x0, 0x10, 0x0, 0x0, 0x0, 0x3, 0x0, 0x4, 0x0, 0x1, 0x0, 0x0, 0x0, 0x5,
+    ]))?;
+    assert_eq!(c.new_cumulative_tsn, 3);
+    assert_eq!(c.streams.len(), 1);
+    assert_eq!(c.streams[0].identifier, 4);
+    assert!(c.streams[0].unordered);
+    assert_eq!(c.streams[0].message_identifier, 5);
+
+    Ok(())
+}
+
+#[test]
+fn test_chunk_i_forward_tsn_unmarshal_failure() -> Result<()> {
+    let tests = vec![
+        ("chunk header to short", Bytes::from_static(&[0xc2])),
+        (
+            "missing New Cumulative TSN",
+            Bytes::from_static(&[0xc2, 0x0, 0x0, 0x4]),
+        ),
+        (
+            "missing message identifier",
+            Bytes::from_static(&[0xc2, 0x0, 0x0, 0xc, 0x0, 0x0, 0x0, 0x3, 0x0, 0x4, 0x0, 0x1]),
+        ),
+        (
+            "not an I-FORWARD-TSN chunk",
+            Bytes::from_static(&[0xc0, 0x0, 0x0, 0x8, 0x0, 0x0, 0x0, 0x3]),
+        ),
+    ];
+
+    for (name, binary) in tests {
+        let result = ChunkIForwardTsn::unmarshal(&binary);
+        assert!(result.is_err(), "expected unmarshal: {name} to fail.");
+    }
+
+    Ok(())
+}
+
 ///////////////////////////////////////////////////////////////////
 //chunk_reconfig_test
 ///////////////////////////////////////////////////////////////////
@@ -458,6 +521,29 @@ fn test_chunk_shutdown_complete_failure() -> Result<()> {
     Ok(())
 }
 
+///////////////////////////////////////////////////////////////////
+//chunk_padding_test
+///////////////////////////////////////////////////////////////////
+use super::chunk_padding::*;
+
+#[test]
+fn test_chunk_padding_success() -> Result<()> {
+    let binary = Bytes::from_static(&[0x84, 0x00, 0x00, 0x0c, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+    let actual = ChunkPadding::unmarshal(&binary)?;
+    assert_eq!(actual.padding_length, 8);
+    let b = actual.marshal()?;
+    assert_eq!(b, binary, "test not equal");
+
+    let result = ChunkPadding::unmarshal(&Bytes::from_static(&[0x04, 0x00, 0x00, 0x04]));
+    assert!(
+        result.is_err(),
+        "expected unmarshal of another type to fail."
+    );
+
+    Ok(())
+}
+
 ///////////////////////////////////////////////////////////////////
 //chunk_test
 ///////////////////////////////////////////////////////////////////
@@ -750,3 +836,43 @@ fn test_select_ack_chunk_followed_by_a_payload_data_chunk() -> Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn test_chunk_i_data_success() -> Result<()> {
+    // First fragment, carrying the payload protocol identifier
+    let first = Bytes::from_static(&[
+        0x40, 0x02, 0x00, 0x15, 0x00, 0x00, 0x00, 0x07, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x09, 0x00, 0x00, 0x00, 0x35, 0xaa,
+    ]);
+    let c = ChunkPayloadData::unmarshal(&first)?;
+    assert!(c.interleaved);
+    assert!(c.beginning_fragment && !c.ending_fragment);
+    assert_eq!(c.tsn, 7);
+    assert_eq!(c.stream_identifier, 3);
+    assert_eq!(c.message_identifier, 9);
+    assert_eq!(c.fragment_sequence_number, 0);
+    assert_eq!(c.payload_type, PayloadProtocolIdentifier::Binary);
+    assert_eq!(&c.user_data[..], &[0xaa]);
+    assert_eq!(c.marshal()?.slice(..first.len()), first);
+
+    // Last fragment, carrying its fragment sequence number
+    let last = Bytes::from_static(&[
+        0x40, 0x01, 0x00, 0x15, 0x00, 0x00, 0x00, 0x08, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x09, 0x00, 0x00, 0x00, 0x01, 0xbb,
+    ]);
+    let c = ChunkPayloadData::unmarshal(&last)?;
+    assert!(c.interleaved);
+    assert!(!c.beginning_fragment && c.ending_fragment);
+    assert_eq!(c.message_identifier, 9);
+    assert_eq!(c.fragment_sequence_number, 1);
+    assert_eq!(c.marshal()?.slice(..last.len()), last);
+
+    // Too short for the I-DATA header
+    let short = Bytes::from_static(&[
+        0x40, 0x03, 0x00, 0x10, 0x00, 0x00, 0x00, 0x07, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x09,
+    ]);
+    assert!(ChunkPayloadData::unmarshal(&short).is_err());
+
+    Ok(())
+}
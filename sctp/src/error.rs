@@ -34,6 +34,10 @@ pub enum Error {
     ErrChunkTooShort,
     #[error("ChunkType is not of type ForwardTsn")]
     ErrChunkTypeNotForwardTsn,
+    #[error("ChunkType is not of type IForwardTsn")]
+    ErrChunkTypeNotIForwardTsn,
+    #[error("ChunkType is not of type PAD")]
+    ErrChunkTypeNotPadding,
     #[error("ChunkType is not of type HEARTBEAT")]
     ErrChunkTypeNotHeartbeat,
     #[error("ChunkType is not of type HEARTBEATACK")]
@@ -5,3 +5,4 @@ pub(crate) mod control_queue;
 pub(crate) mod payload_queue;
 pub(crate) mod pending_queue;
 pub(crate) mod reassembly_queue;
+pub(crate) mod stream_scheduler;
@@ -1,9 +1,10 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 use tokio::sync::{Mutex, Semaphore};
-use util::sync::RwLock;
+use util::sync::{Mutex as SyncMutex, RwLock};
 
+use super::stream_scheduler::*;
 use crate::chunk::chunk_payload_data::ChunkPayloadData;
 
 // TODO: benchmark performance between multiple Atomic+Mutex vs one Mutex<PendingQueueInternal>
@@ -21,6 +22,42 @@ const QUEUE_APPEND_LARGE: usize = (QUEUE_BYTES_LIMIT * 2) / 3;
 /// Basic queue for either ordered or unordered chunks.
 pub(crate) type PendingBaseQueue = VecDeque<ChunkPayloadData>;
 
+/// State of the selection of the chunks to send by the stream scheduler.
+#[derive(Debug)]
+struct StreamSelection {
+    scheduler: Box<dyn StreamScheduler>,
+    /// Priority of the streams, for the streams not using the default one
+    priorities: HashMap<u16, u16>,
+    /// Streams in the middle of sending a fragmented message, with whether the message
+    /// is unordered. The fragments of a message are sent before the next message of the
+    /// same stream.
+    in_progress: HashMap<u16, bool>,
+    /// Stream and ordering of the chunk returned by the last peek
+    peeked: Option<(u16, bool)>,
+}
+
+impl StreamSelection {
+    fn new(scheduler_type: StreamSchedulerType) -> Self {
+        StreamSelection {
+            scheduler: new_stream_scheduler(scheduler_type),
+            priorities: HashMap::new(),
+            in_progress: HashMap::new(),
+            peeked: None,
+        }
+    }
+
+    fn scheduled_stream(&self, stream_identifier: u16) -> ScheduledStream {
+        ScheduledStream {
+            stream_identifier,
+            priority: self
+                .priorities
+                .get(&stream_identifier)
+                .copied()
+                .unwrap_or(DEFAULT_STREAM_PRIORITY),
+        }
+    }
+}
+
 /// A queue for both ordered and unordered chunks.
 #[derive(Debug)]
 pub(crate) struct PendingQueue {
@@ -39,6 +76,14 @@ pub(crate) struct PendingQueue {
     n_bytes: AtomicUsize,
     selected: AtomicBool,
     unordered_is_selected: AtomicBool,
+
+    /// Set once I-DATA chunks are negotiated, to interleave the fragments of messages of
+    /// different streams
+    interleaved: AtomicBool,
+    /// Set unless the streams are served first-come, first-served, which doesn't need
+    /// the stream scheduler
+    scheduled: bool,
+    stream_selection: SyncMutex<StreamSelection>,
 }
 
 impl Default for PendingQueue {
@@ -49,6 +94,12 @@ impl Default for PendingQueue {
 
 impl PendingQueue {
     pub(crate) fn new() -> Self {
+        PendingQueue::with_stream_scheduler(StreamSchedulerType::default())
+    }
+
+    /// with_stream_scheduler creates a queue whose streams share the sending capacity as
+    /// decided by the given scheduler.
+    pub(crate) fn with_stream_scheduler(scheduler_type: StreamSchedulerType) -> Self {
         Self {
             semaphore_lock: Mutex::default(),
             semaphore: Semaphore::new(QUEUE_BYTES_LIMIT),
@@ -58,9 +109,46 @@ impl PendingQueue {
             n_bytes: Default::default(),
             selected: Default::default(),
             unordered_is_selected: Default::default(),
+            interleaved: Default::default(),
+            scheduled: scheduler_type != StreamSchedulerType::Fcfs,
+            stream_selection: SyncMutex::new(StreamSelection::new(scheduler_type)),
         }
     }
 
+    /// set_interleaved makes the queue interleave the fragments of messages of different
+    /// streams, which is only allowed with I-DATA chunks (RFC 8260).
+    pub(crate) fn set_interleaved(&self, interleaved: bool) {
+        self.interleaved.store(interleaved, Ordering::SeqCst);
+    }
+
+    /// is_interleaved tells if I-DATA chunks are used.
+    pub(crate) fn is_interleaved(&self) -> bool {
+        self.interleaved.load(Ordering::SeqCst)
+    }
+
+    /// set_stream_priority sets the priority of a stream, as used by the priority and
+    /// weighted fair queue schedulers.
+    pub(crate) fn set_stream_priority(&self, stream_identifier: u16, priority: u16) {
+        let mut selection = self.stream_selection.lock();
+        if priority == DEFAULT_STREAM_PRIORITY {
+            selection.priorities.remove(&stream_identifier);
+        } else {
+            selection.priorities.insert(stream_identifier, priority);
+        }
+    }
+
+    /// stream_priority returns the priority of a stream.
+    pub(crate) fn stream_priority(&self, stream_identifier: u16) -> u16 {
+        let selection = self.stream_selection.lock();
+        selection.scheduled_stream(stream_identifier).priority
+    }
+
+    /// uses_stream_scheduler tells if the chunks are selected by the stream scheduler
+    /// rather than first-come, first-served.
+    fn uses_stream_scheduler(&self) -> bool {
+        self.scheduled || self.is_interleaved()
+    }
+
     /// Appends a chunk to the back of the pending queue.
     pub(crate) async fn push(&self, c: ChunkPayloadData) {
         let user_data_len = c.user_data.len();
@@ -162,6 +250,10 @@ impl PendingQueue {
     }
 
     pub(crate) fn peek(&self) -> Option<ChunkPayloadData> {
+        if self.uses_stream_scheduler() {
+            return self.peek_scheduled();
+        }
+
         if self.selected.load(Ordering::SeqCst) {
             if self.unordered_is_selected.load(Ordering::SeqCst) {
                 let unordered_queue = self.unordered_queue.read();
@@ -190,7 +282,9 @@ impl PendingQueue {
         beginning_fragment: bool,
         unordered: bool,
     ) -> Option<ChunkPayloadData> {
-        let popped = if self.selected.load(Ordering::SeqCst) {
+        let popped = if self.uses_stream_scheduler() {
+            self.pop_scheduled()
+        } else if self.selected.load(Ordering::SeqCst) {
             let popped = if self.unordered_is_selected.load(Ordering::SeqCst) {
                 let mut unordered_queue = self.unordered_queue.write();
                 unordered_queue.pop_front()
@@ -245,6 +339,78 @@ impl PendingQueue {
         popped
     }
 
+    /// peek_scheduled returns the first chunk of the stream selected by the stream
+    /// scheduler. Without I-DATA chunks, the fragments of a message are sent back to back,
+    /// so the stream with a message in progress is the only one to select from.
+    fn peek_scheduled(&self) -> Option<ChunkPayloadData> {
+        let interleaved = self.is_interleaved();
+        let unordered_queue = self.unordered_queue.read();
+        let ordered_queue = self.ordered_queue.read();
+        let mut selection = self.stream_selection.lock();
+
+        // The candidates are the first chunk of each stream, or the next fragment of the
+        // message in progress
+        let mut candidates: Vec<(bool, &ChunkPayloadData)> = vec![];
+        for (unordered, queue) in [(true, &*unordered_queue), (false, &*ordered_queue)] {
+            for c in queue.iter() {
+                let si = c.stream_identifier;
+                if candidates.iter().any(|(_, c)| c.stream_identifier == si) {
+                    continue;
+                }
+                match selection.in_progress.get(&si) {
+                    Some(u) if *u != unordered => continue,
+                    None if !interleaved && !selection.in_progress.is_empty() => continue,
+                    _ => candidates.push((unordered, c)),
+                }
+            }
+        }
+
+        if candidates.is_empty() {
+            selection.peeked = None;
+            return None;
+        }
+
+        let streams: Vec<ScheduledStream> = candidates
+            .iter()
+            .map(|(_, c)| selection.scheduled_stream(c.stream_identifier))
+            .collect();
+        let (unordered, c) = candidates[selection.scheduler.select(&streams)];
+
+        selection.peeked = Some((c.stream_identifier, unordered));
+        Some(c.clone())
+    }
+
+    /// pop_scheduled removes the chunk returned by the last peek.
+    fn pop_scheduled(&self) -> Option<ChunkPayloadData> {
+        let (si, unordered) = {
+            let mut selection = self.stream_selection.lock();
+            selection.peeked.take()?
+        };
+
+        let popped = {
+            let mut queue = if unordered {
+                self.unordered_queue.write()
+            } else {
+                self.ordered_queue.write()
+            };
+            let i = queue.iter().position(|c| c.stream_identifier == si)?;
+            queue.remove(i)
+        };
+
+        if let Some(p) = &popped {
+            let mut selection = self.stream_selection.lock();
+            let stream = selection.scheduled_stream(si);
+            selection.scheduler.sent(&stream, p.user_data.len());
+            if p.ending_fragment {
+                selection.in_progress.remove(&si);
+            } else {
+                selection.in_progress.insert(si, unordered);
+            }
+        }
+
+        popped
+    }
+
     pub(crate) fn get_num_bytes(&self) -> usize {
         self.n_bytes.load(Ordering::SeqCst)
     }
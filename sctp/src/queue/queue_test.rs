@@ -191,6 +191,7 @@ fn test_payload_queue_reset_retransmit_flag_on_ack() -> Result<()> {
 //pending_queue_test
 ///////////////////////////////////////////////////////////////////
 use super::pending_queue::*;
+use super::stream_scheduler::*;
 
 const NO_FRAGMENT: usize = 0;
 const FRAG_BEGIN: usize = 1;
@@ -439,6 +440,132 @@ async fn test_pending_queue_append() -> Result<()> {
     Ok(())
 }
 
+// With I-DATA chunks, the fragments of a large message don't block the messages of
+// the other streams, while the fragments of each stream stay in order.
+#[tokio::test]
+async fn test_pending_queue_interleaved() -> Result<()> {
+    let pq = PendingQueue::with_stream_scheduler(StreamSchedulerType::RoundRobin);
+    pq.set_interleaved(true);
+
+    let chunk = |tsn: u32, si: u16, frag: usize| ChunkPayloadData {
+        stream_identifier: si,
+        interleaved: true,
+        ..make_data_chunk(tsn, false, frag)
+    };
+    pq.append(vec![
+        chunk(0, 1, FRAG_BEGIN),
+        chunk(1, 1, FRAG_MIDDLE),
+        chunk(2, 1, FRAG_END),
+    ])
+    .await;
+    pq.push(chunk(3, 2, NO_FRAGMENT)).await;
+    pq.push(chunk(4, 1, NO_FRAGMENT)).await;
+    pq.push(chunk(5, 2, NO_FRAGMENT)).await;
+
+    let expects = vec![0, 3, 1, 5, 2, 4];
+
+    for exp in expects {
+        let c = pq.peek();
+        assert!(c.is_some(), "peek error");
+        let c = c.unwrap();
+        assert_eq!(c.tsn, exp, "TSN should match");
+        let (beginning_fragment, unordered) = (c.beginning_fragment, c.unordered);
+        let result = pq.pop(beginning_fragment, unordered);
+        assert_eq!(
+            result.map(|c| c.tsn),
+            Some(exp),
+            "should pop the peeked chunk"
+        );
+    }
+    assert!(pq.is_empty(), "queue should be empty");
+
+    Ok(())
+}
+
+fn make_stream_chunk(tsn: u32, si: u16, frag: usize) -> ChunkPayloadData {
+    ChunkPayloadData {
+        stream_identifier: si,
+        ..make_data_chunk(tsn, false, frag)
+    }
+}
+
+fn pop_all_tsns(pq: &PendingQueue) -> Vec<u32> {
+    let mut tsns = vec![];
+    while let Some(c) = pq.peek() {
+        let (beginning_fragment, unordered) = (c.beginning_fragment, c.unordered);
+        let popped = pq.pop(beginning_fragment, unordered);
+        assert_eq!(
+            popped.map(|p| p.tsn),
+            Some(c.tsn),
+            "should pop the peeked chunk"
+        );
+        tsns.push(c.tsn);
+    }
+    tsns
+}
+
+#[tokio::test]
+async fn test_pending_queue_round_robin() -> Result<()> {
+    let pq = PendingQueue::with_stream_scheduler(StreamSchedulerType::RoundRobin);
+
+    // Without I-DATA chunks, the fragments of a message are still sent back to back
+    pq.append(vec![
+        make_stream_chunk(0, 1, FRAG_BEGIN),
+        make_stream_chunk(1, 1, FRAG_END),
+    ])
+    .await;
+    pq.push(make_stream_chunk(2, 1, NO_FRAGMENT)).await;
+    pq.push(make_stream_chunk(3, 2, NO_FRAGMENT)).await;
+    pq.push(make_stream_chunk(4, 2, NO_FRAGMENT)).await;
+    pq.push(make_stream_chunk(5, 3, NO_FRAGMENT)).await;
+
+    assert_eq!(pop_all_tsns(&pq), vec![0, 1, 3, 5, 2, 4]);
+    assert!(pq.is_empty(), "queue should be empty");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pending_queue_priority() -> Result<()> {
+    let pq = PendingQueue::with_stream_scheduler(StreamSchedulerType::Priority);
+    pq.set_stream_priority(2, 512);
+    pq.set_stream_priority(3, 512);
+    assert_eq!(pq.stream_priority(1), DEFAULT_STREAM_PRIORITY);
+    assert_eq!(pq.stream_priority(2), 512);
+
+    pq.push(make_stream_chunk(0, 1, NO_FRAGMENT)).await;
+    pq.push(make_stream_chunk(1, 1, NO_FRAGMENT)).await;
+    pq.push(make_stream_chunk(2, 2, NO_FRAGMENT)).await;
+    pq.push(make_stream_chunk(3, 3, NO_FRAGMENT)).await;
+    pq.push(make_stream_chunk(4, 2, NO_FRAGMENT)).await;
+
+    // The streams of the highest priority are served in turn before the others
+    assert_eq!(pop_all_tsns(&pq), vec![2, 3, 4, 0, 1]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pending_queue_weighted_fair_queue() -> Result<()> {
+    let pq = PendingQueue::with_stream_scheduler(StreamSchedulerType::WeightedFairQueue);
+    pq.set_stream_priority(1, 256);
+    pq.set_stream_priority(2, 768);
+
+    for i in 0..8 {
+        pq.push(make_stream_chunk(i, 1 + (i % 2) as u16, NO_FRAGMENT))
+            .await;
+    }
+
+    // Stream 2 weighs three times as much as stream 1, so it gets three chunks for
+    // each chunk of stream 1 as long as both have data pending
+    let tsns = pop_all_tsns(&pq);
+    assert_eq!(tsns.len(), 8);
+    let n_stream2 = tsns[..4].iter().filter(|tsn| *tsn % 2 == 1).count();
+    assert_eq!(n_stream2, 3, "unexpected share of stream 2: {tsns:?}");
+
+    Ok(())
+}
+
 ///////////////////////////////////////////////////////////////////
 //reassembly_queue_test
 ///////////////////////////////////////////////////////////////////
@@ -927,6 +1054,116 @@ fn test_reassembly_queue_forward_tsn_for_unordered_fragments() -> Result<()> {
     Ok(())
 }
 
+fn make_i_data_chunk(
+    tsn: u32,
+    unordered: bool,
+    mid: u32,
+    fsn: u32,
+    frag: usize,
+    data: &'static [u8],
+) -> ChunkPayloadData {
+    ChunkPayloadData {
+        interleaved: true,
+        message_identifier: mid,
+        fragment_sequence_number: fsn,
+        payload_type: if fsn == 0 {
+            PayloadProtocolIdentifier::Binary
+        } else {
+            PayloadProtocolIdentifier::Unknown
+        },
+        user_data: Bytes::from_static(data),
+        ..make_data_chunk(tsn, unordered, frag)
+    }
+}
+
+#[test]
+fn test_reassembly_queue_interleaved_ordered_fragments() -> Result<()> {
+    let mut rq = ReassemblyQueue::new(0);
+
+    // The fragments of MID 0 and 1 are interleaved with the ones of another stream,
+    // so their TSNs are not contiguous
+    let complete = rq.push(make_i_data_chunk(1, false, 1, 0, FRAG_BEGIN, b"DEF"));
+    assert!(!complete, "chunk set should not be complete yet");
+    let complete = rq.push(make_i_data_chunk(4, false, 1, 1, FRAG_END, b"GHI"));
+    assert!(complete, "chunk set should be complete");
+    assert!(!rq.is_readable(), "MID 0 should be read first");
+
+    let complete = rq.push(make_i_data_chunk(3, false, 0, 1, FRAG_END, b"456"));
+    assert!(!complete, "chunk set should not be complete yet");
+    let complete = rq.push(make_i_data_chunk(0, false, 0, 0, FRAG_BEGIN, b"123"));
+    assert!(complete, "chunk set should be complete");
+    assert_eq!(rq.get_num_bytes(), 12, "num bytes mismatch");
+
+    let mut buf = vec![0u8; 16];
+    let (n, ppi) = rq.read(&mut buf)?;
+    assert_eq!(&buf[..n], b"123456", "data should match");
+    assert_eq!(
+        ppi,
+        PayloadProtocolIdentifier::Binary,
+        "should have valid ppi"
+    );
+    let (n, _) = rq.read(&mut buf)?;
+    assert_eq!(&buf[..n], b"DEFGHI", "data should match");
+    assert_eq!(rq.next_mid, 2, "next MID mismatch");
+    assert_eq!(rq.get_num_bytes(), 0, "num bytes mismatch");
+
+    // Stale MID
+    let complete = rq.push(make_i_data_chunk(5, false, 1, 0, NO_FRAGMENT, b"X"));
+    assert!(!complete, "chunk with stale MID should be ignored");
+
+    Ok(())
+}
+
+#[test]
+fn test_reassembly_queue_interleaved_unordered_fragments() -> Result<()> {
+    let mut rq = ReassemblyQueue::new(0);
+
+    let complete = rq.push(make_i_data_chunk(0, true, 7, 0, FRAG_BEGIN, b"ABC"));
+    assert!(!complete, "chunk set should not be complete yet");
+    let complete = rq.push(make_i_data_chunk(2, true, 8, 0, NO_FRAGMENT, b"XYZ"));
+    assert!(complete, "chunk set should be complete");
+    assert!(rq.is_readable(), "unordered MID 8 should be readable");
+
+    let mut buf = vec![0u8; 16];
+    let (n, _) = rq.read(&mut buf)?;
+    assert_eq!(&buf[..n], b"XYZ", "data should match");
+
+    let complete = rq.push(make_i_data_chunk(3, true, 7, 1, FRAG_END, b"DEF"));
+    assert!(complete, "chunk set should be complete");
+    let (n, _) = rq.read(&mut buf)?;
+    assert_eq!(&buf[..n], b"ABCDEF", "data should match");
+    assert_eq!(rq.get_num_bytes(), 0, "num bytes mismatch");
+
+    Ok(())
+}
+
+#[test]
+fn test_reassembly_queue_forward_tsn_for_mid() -> Result<()> {
+    let mut rq = ReassemblyQueue::new(0);
+
+    let complete = rq.push(make_i_data_chunk(10, false, 5, 0, NO_FRAGMENT, b"123"));
+    assert!(complete, "chunk set should be complete");
+    let complete = rq.push(make_i_data_chunk(11, false, 6, 0, FRAG_BEGIN, b"ABC"));
+    assert!(!complete, "chunk set should not be complete yet");
+    let complete = rq.push(make_i_data_chunk(12, true, 2, 0, FRAG_BEGIN, b"DEF"));
+    assert!(!complete, "chunk set should not be complete yet");
+    assert_eq!(rq.get_num_bytes(), 9, "num bytes mismatch");
+
+    rq.forward_tsn_for_ordered_mid(6);
+    assert_eq!(rq.ordered.len(), 1, "there should be one chunk set left");
+    assert_eq!(rq.next_mid, 7, "next MID mismatch");
+    assert_eq!(rq.get_num_bytes(), 6, "num bytes mismatch");
+
+    rq.forward_tsn_for_unordered_mid(2);
+    assert!(
+        rq.unordered_interleaved.is_empty(),
+        "unordered chunk set should be removed"
+    );
+    assert_eq!(rq.get_num_bytes(), 3, "num bytes mismatch");
+
+    Ok(())
+}
+
 #[test]
 fn test_chunk_set_empty_chunk_set() -> Result<()> {
     let cset = ChunkSet::new(0, PayloadProtocolIdentifier::default());
@@ -958,6 +1195,8 @@ fn test_chunk_set_incomplete_chunk_set_no_beginning() -> Result<()> {
         ssn: 0,
         ppi: PayloadProtocolIdentifier::default(),
         chunks: vec![],
+        mid: 0,
+        interleaved: false,
     };
     assert!(
         !cset.is_complete(),
@@ -987,6 +1226,8 @@ fn test_chunk_set_incomplete_chunk_set_no_contiguous_tsn() -> Result<()> {
                 ..Default::default()
             },
         ],
+        mid: 0,
+        interleaved: false,
     };
     assert!(
         !cset.is_complete(),
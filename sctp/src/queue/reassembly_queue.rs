@@ -14,6 +14,16 @@ fn sort_chunks_by_tsn(c: &mut [ChunkPayloadData]) {
     });
 }
 
+fn sort_chunks_by_fsn(c: &mut [ChunkPayloadData]) {
+    c.sort_by(|a, b| {
+        if sna32lt(a.fragment_sequence_number, b.fragment_sequence_number) {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        }
+    });
+}
+
 fn sort_chunks_by_ssn(c: &mut [ChunkSet]) {
     c.sort_by(|a, b| {
         if sna16lt(a.ssn, b.ssn) {
@@ -24,13 +34,27 @@ fn sort_chunks_by_ssn(c: &mut [ChunkSet]) {
     });
 }
 
-/// chunkSet is a set of chunks that share the same SSN
+fn sort_chunks_by_mid(c: &mut [ChunkSet]) {
+    c.sort_by(|a, b| {
+        if sna32lt(a.mid, b.mid) {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        }
+    });
+}
+
+/// chunkSet is a set of chunks that share the same SSN, or the same MID for I-DATA chunks
 #[derive(Debug, Clone)]
 pub(crate) struct ChunkSet {
     /// used only with the ordered chunks
     pub(crate) ssn: u16,
     pub(crate) ppi: PayloadProtocolIdentifier,
     pub(crate) chunks: Vec<ChunkPayloadData>,
+    /// Message Identifier shared by the I-DATA chunks of the set
+    pub(crate) mid: u32,
+    /// Set if the set is made of I-DATA chunks, whose fragments are ordered by FSN
+    pub(crate) interleaved: bool,
 }
 
 impl ChunkSet {
@@ -39,6 +63,20 @@ impl ChunkSet {
             ssn,
             ppi,
             chunks: vec![],
+            mid: 0,
+            interleaved: false,
+        }
+    }
+
+    /// new_interleaved creates a set of I-DATA chunks with the given MID. Its payload
+    /// protocol identifier is only known once the first fragment is received.
+    pub(crate) fn new_interleaved(mid: u32) -> Self {
+        ChunkSet {
+            ssn: 0,
+            ppi: PayloadProtocolIdentifier::Unknown,
+            chunks: vec![],
+            mid,
+            interleaved: true,
         }
     }
 
@@ -51,8 +89,15 @@ impl ChunkSet {
         }
 
         // append and sort
+        if chunk.interleaved && chunk.beginning_fragment {
+            self.ppi = chunk.payload_type;
+        }
         self.chunks.push(chunk);
-        sort_chunks_by_tsn(&mut self.chunks);
+        if self.interleaved {
+            sort_chunks_by_fsn(&mut self.chunks);
+        } else {
+            sort_chunks_by_tsn(&mut self.chunks);
+        }
 
         // Check if we now have a complete set
         self.is_complete()
@@ -63,7 +108,7 @@ impl ChunkSet {
         //   0. Has at least one chunk.
         //   1. Begins with beginningFragment set to true
         //   2. Ends with endingFragment set to true
-        //   3. TSN monotinically increase by 1 from beginning to end
+        //   3. TSN monotinically increase by 1 from beginning to end, or FSN for I-DATA
 
         // 0.
         let n_chunks = self.chunks.len();
@@ -82,6 +127,16 @@ impl ChunkSet {
         }
 
         // 3.
+        if self.interleaved {
+            // From RFC 8260 Sec 2.1:
+            //   The FSN is used to enumerate all fragments of a single user message,
+            //   starting from 0 and incremented by 1.
+            return self
+                .chunks
+                .iter()
+                .enumerate()
+                .all(|(i, c)| c.fragment_sequence_number == i as u32);
+        }
         let mut last_tsn = 0u32;
         for (i, c) in self.chunks.iter().enumerate() {
             if i > 0 {
@@ -113,6 +168,10 @@ pub(crate) struct ReassemblyQueue {
     pub(crate) unordered: Vec<ChunkSet>,
     pub(crate) unordered_chunks: Vec<ChunkPayloadData>,
     pub(crate) n_bytes: usize,
+    /// expected MID for next ordered I-DATA chunk
+    pub(crate) next_mid: u32,
+    /// incomplete sets of unordered I-DATA chunks
+    pub(crate) unordered_interleaved: Vec<ChunkSet>,
 }
 
 impl ReassemblyQueue {
@@ -129,6 +188,8 @@ impl ReassemblyQueue {
             unordered: vec![],
             unordered_chunks: vec![],
             n_bytes: 0,
+            next_mid: 0,
+            unordered_interleaved: vec![],
         }
     }
 
@@ -137,6 +198,10 @@ impl ReassemblyQueue {
             return false;
         }
 
+        if chunk.interleaved {
+            return self.push_interleaved(chunk);
+        }
+
         if chunk.unordered {
             // First, insert into unordered_chunks array
             //atomic.AddUint64(&r.n_bytes, uint64(len(chunk.userData)))
@@ -180,6 +245,63 @@ impl ReassemblyQueue {
         }
     }
 
+    /// push_interleaved adds an I-DATA chunk to the set of its MID. The fragments of the
+    /// messages of a stream are reassembled by MID and FSN rather than by TSN.
+    fn push_interleaved(&mut self, chunk: ChunkPayloadData) -> bool {
+        let mid = chunk.message_identifier;
+        if chunk.unordered {
+            self.n_bytes += chunk.user_data.len();
+
+            let complete = match self.unordered_interleaved.iter_mut().find(|s| s.mid == mid) {
+                Some(cset) => cset.push(chunk),
+                None => {
+                    let mut cset = ChunkSet::new_interleaved(mid);
+                    let complete = cset.push(chunk);
+                    self.unordered_interleaved.push(cset);
+                    complete
+                }
+            };
+            if complete {
+                if let Some(i) = self.unordered_interleaved.iter().position(|s| s.mid == mid) {
+                    let cset = self.unordered_interleaved.remove(i);
+                    self.unordered.push(cset);
+                }
+            }
+
+            complete
+        } else {
+            if sna32lt(mid, self.next_mid) {
+                return false;
+            }
+
+            self.n_bytes += chunk.user_data.len();
+
+            if let Some(cset) = self
+                .ordered
+                .iter_mut()
+                .find(|s| s.interleaved && s.mid == mid)
+            {
+                return cset.push(chunk);
+            }
+
+            let mut cset = ChunkSet::new_interleaved(mid);
+            let ok = cset.push(chunk);
+            self.ordered.push(cset);
+            sort_chunks_by_mid(&mut self.ordered);
+
+            ok
+        }
+    }
+
+    /// is_next tells if the ordered set is the next one to be read
+    fn is_next(&self, cset: &ChunkSet) -> bool {
+        if cset.interleaved {
+            sna32lte(cset.mid, self.next_mid)
+        } else {
+            sna16lte(cset.ssn, self.next_ssn)
+        }
+    }
+
     pub(crate) fn find_complete_unordered_chunk_set(&mut self) -> Option<ChunkSet> {
         let mut start_idx = -1isize;
         let mut n_chunks = 0usize;
@@ -245,7 +367,7 @@ impl ReassemblyQueue {
         // Check ordered sets
         if !self.ordered.is_empty() {
             let cset = &self.ordered[0];
-            if cset.is_complete() && sna16lte(cset.ssn, self.next_ssn) {
+            if cset.is_complete() && self.is_next(cset) {
                 return true;
             }
         }
@@ -262,10 +384,14 @@ impl ReassemblyQueue {
             if !cset.is_complete() {
                 return Err(Error::ErrTryAgain);
             }
-            if sna16gt(cset.ssn, self.next_ssn) {
+            if !self.is_next(cset) {
                 return Err(Error::ErrTryAgain);
             }
-            if cset.ssn == self.next_ssn {
+            if cset.interleaved {
+                if cset.mid == self.next_mid {
+                    self.next_mid = self.next_mid.wrapping_add(1);
+                }
+            } else if cset.ssn == self.next_ssn {
                 // From RFC 4960 Sec 6.5:
                 self.next_ssn = self.next_ssn.wrapping_add(1);
             }
@@ -339,6 +465,42 @@ impl ReassemblyQueue {
         }
     }
 
+    /// Use last_mid to remove the incomplete sets of ordered I-DATA chunks skipped by an
+    /// I-FORWARD-TSN chunk
+    pub(crate) fn forward_tsn_for_ordered_mid(&mut self, last_mid: u32) {
+        let num_bytes = self
+            .ordered
+            .iter()
+            .filter(|s| s.interleaved && sna32lte(s.mid, last_mid) && !s.is_complete())
+            .fold(0, |n, s| {
+                n + s.chunks.iter().fold(0, |acc, c| acc + c.user_data.len())
+            });
+        self.subtract_num_bytes(num_bytes);
+
+        self.ordered
+            .retain(|s| !s.interleaved || !sna32lte(s.mid, last_mid) || s.is_complete());
+
+        // Finally, forward next_mid
+        if sna32lte(self.next_mid, last_mid) {
+            self.next_mid = last_mid.wrapping_add(1);
+        }
+    }
+
+    /// Remove the incomplete set of unordered I-DATA chunks with the MID skipped by an
+    /// I-FORWARD-TSN chunk
+    pub(crate) fn forward_tsn_for_unordered_mid(&mut self, mid: u32) {
+        let num_bytes = self
+            .unordered_interleaved
+            .iter()
+            .filter(|s| s.mid == mid)
+            .fold(0, |n, s| {
+                n + s.chunks.iter().fold(0, |acc, c| acc + c.user_data.len())
+            });
+        self.subtract_num_bytes(num_bytes);
+
+        self.unordered_interleaved.retain(|s| s.mid != mid);
+    }
+
     pub(crate) fn subtract_num_bytes(&mut self, n_bytes: usize) {
         if self.n_bytes >= n_bytes {
             self.n_bytes -= n_bytes;
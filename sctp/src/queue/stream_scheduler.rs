@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// Default priority of a stream, which is the priority of a "normal" data channel
+/// (RFC 8831 Sec 6.4).
+pub const DEFAULT_STREAM_PRIORITY: u16 = 256;
+
+/// Scale of the virtual time of the weighted fair queue, so that the bytes sent divided
+/// by the weight keep enough precision.
+const WFQ_VIRTUAL_TIME_SCALE: u64 = 1 << 16;
+
+/// StreamSchedulerType selects how the outgoing streams of an association share the
+/// sending capacity (RFC 8260 Sec 3).
+///
+/// Unless I-DATA chunks are used, the fragments of a message are always sent back to
+/// back, so the scheduler only decides between the messages of the streams.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StreamSchedulerType {
+    /// First-come, first-served: the messages are sent in the order they were written,
+    /// unordered messages first.
+    #[default]
+    Fcfs,
+    /// Round-robin: the streams with pending data are served in turn.
+    RoundRobin,
+    /// Priority: the streams with the highest priority are served first, and the
+    /// streams of the same priority in turn.
+    Priority,
+    /// Weighted fair queueing: each stream gets a share of the sending capacity
+    /// proportional to its priority.
+    WeightedFairQueue,
+}
+
+impl fmt::Display for StreamSchedulerType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match *self {
+            StreamSchedulerType::Fcfs => "FCFS",
+            StreamSchedulerType::RoundRobin => "RoundRobin",
+            StreamSchedulerType::Priority => "Priority",
+            StreamSchedulerType::WeightedFairQueue => "WeightedFairQueue",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// A stream with pending data, as seen by a StreamScheduler.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct ScheduledStream {
+    pub(crate) stream_identifier: u16,
+    pub(crate) priority: u16,
+}
+
+/// StreamScheduler selects the stream to send the next chunk from.
+pub(crate) trait StreamScheduler: fmt::Debug + Send + Sync {
+    /// select returns the index of the stream to send from. The streams are never empty
+    /// and come in queue order, the streams with unordered data first.
+    fn select(&self, streams: &[ScheduledStream]) -> usize;
+
+    /// sent is called once a chunk of n_bytes of user data of the stream was sent.
+    fn sent(&mut self, _stream: &ScheduledStream, _n_bytes: usize) {}
+}
+
+pub(crate) fn new_stream_scheduler(typ: StreamSchedulerType) -> Box<dyn StreamScheduler> {
+    match typ {
+        StreamSchedulerType::Fcfs => Box::<FcfsScheduler>::default(),
+        StreamSchedulerType::RoundRobin => Box::<RoundRobinScheduler>::default(),
+        StreamSchedulerType::Priority => Box::<PriorityScheduler>::default(),
+        StreamSchedulerType::WeightedFairQueue => Box::<WeightedFairQueueScheduler>::default(),
+    }
+}
+
+/// next_in_turn returns the index of the stream following the last one served among the
+/// given streams, wrapping around.
+fn next_in_turn<'a>(
+    streams: impl Iterator<Item = (usize, &'a ScheduledStream)> + Clone,
+    last: Option<u16>,
+) -> Option<usize> {
+    streams
+        .clone()
+        .filter(|(_, s)| last.map_or(true, |l| s.stream_identifier > l))
+        .min_by_key(|(_, s)| s.stream_identifier)
+        .or_else(|| streams.min_by_key(|(_, s)| s.stream_identifier))
+        .map(|(i, _)| i)
+}
+
+#[derive(Default, Debug)]
+pub(crate) struct FcfsScheduler;
+
+impl StreamScheduler for FcfsScheduler {
+    fn select(&self, _streams: &[ScheduledStream]) -> usize {
+        0
+    }
+}
+
+#[derive(Default, Debug)]
+pub(crate) struct RoundRobinScheduler {
+    last_stream_identifier: Option<u16>,
+}
+
+impl StreamScheduler for RoundRobinScheduler {
+    fn select(&self, streams: &[ScheduledStream]) -> usize {
+        next_in_turn(streams.iter().enumerate(), self.last_stream_identifier).unwrap_or(0)
+    }
+
+    fn sent(&mut self, stream: &ScheduledStream, _n_bytes: usize) {
+        self.last_stream_identifier = Some(stream.stream_identifier);
+    }
+}
+
+#[derive(Default, Debug)]
+pub(crate) struct PriorityScheduler {
+    last_stream_identifier: Option<u16>,
+}
+
+impl StreamScheduler for PriorityScheduler {
+    fn select(&self, streams: &[ScheduledStream]) -> usize {
+        let highest = streams.iter().map(|s| s.priority).max().unwrap_or_default();
+        next_in_turn(
+            streams
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| s.priority == highest),
+            self.last_stream_identifier,
+        )
+        .unwrap_or(0)
+    }
+
+    fn sent(&mut self, stream: &ScheduledStream, _n_bytes: usize) {
+        self.last_stream_identifier = Some(stream.stream_identifier);
+    }
+}
+
+/// WeightedFairQueueScheduler is a start-time fair queue: the stream with the smallest
+/// virtual start time is served, and its virtual finish time moves forward by the bytes
+/// sent divided by its weight.
+#[derive(Default, Debug)]
+pub(crate) struct WeightedFairQueueScheduler {
+    virtual_time: u64,
+    finish_times: HashMap<u16, u64>,
+}
+
+impl WeightedFairQueueScheduler {
+    fn start_time(&self, stream_identifier: u16) -> u64 {
+        self.finish_times
+            .get(&stream_identifier)
+            .map_or(self.virtual_time, |f| (*f).max(self.virtual_time))
+    }
+}
+
+impl StreamScheduler for WeightedFairQueueScheduler {
+    fn select(&self, streams: &[ScheduledStream]) -> usize {
+        streams
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, s)| self.start_time(s.stream_identifier))
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn sent(&mut self, stream: &ScheduledStream, n_bytes: usize) {
+        let start = self.start_time(stream.stream_identifier);
+        let weight = u64::from(stream.priority.max(1));
+        self.virtual_time = start;
+        self.finish_times.insert(
+            stream.stream_identifier,
+            start + (n_bytes as u64 * WFQ_VIRTUAL_TIME_SCALE) / weight,
+        );
+    }
+}
@@ -15,7 +15,10 @@ use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use tokio::sync::{mpsc, Mutex, Notify};
 
 use crate::association::AssociationState;
-use crate::chunk::chunk_payload_data::{ChunkPayloadData, PayloadProtocolIdentifier};
+use crate::chunk::chunk_payload_data::{
+    ChunkPayloadData, PayloadProtocolIdentifier, PAYLOAD_DATA_HEADER_SIZE,
+    PAYLOAD_I_DATA_HEADER_SIZE,
+};
 use crate::error::{Error, Result};
 use crate::queue::pending_queue::PendingQueue;
 use crate::queue::reassembly_queue::ReassemblyQueue;
@@ -61,7 +64,7 @@ pub type OnBufferedAmountLowFn =
 /// Stream represents an SCTP stream
 #[derive(Default)]
 pub struct Stream {
-    pub(crate) max_payload_size: u32,
+    pub(crate) max_payload_size: Arc<AtomicU32>, // clone from association
     pub(crate) max_message_size: Arc<AtomicU32>, // clone from association
     pub(crate) state: Arc<AtomicU8>,             // clone from association
     pub(crate) awake_write_loop_ch: Option<Arc<mpsc::Sender<()>>>,
@@ -71,6 +74,10 @@ pub struct Stream {
     pub(crate) default_payload_type: AtomicU32, //PayloadProtocolIdentifier,
     pub(crate) reassembly_queue: Mutex<ReassemblyQueue>,
     pub(crate) sequence_number: AtomicU16,
+    /// MID of the next ordered message sent with I-DATA chunks
+    pub(crate) message_identifier: AtomicU32,
+    /// MID of the next unordered message sent with I-DATA chunks
+    pub(crate) unordered_message_identifier: AtomicU32,
     pub(crate) read_notifier: Notify,
     pub(crate) read_shutdown: AtomicBool,
     pub(crate) write_shutdown: AtomicBool,
@@ -110,7 +117,7 @@ impl Stream {
     pub(crate) fn new(
         name: String,
         stream_identifier: u16,
-        max_payload_size: u32,
+        max_payload_size: Arc<AtomicU32>,
         max_message_size: Arc<AtomicU32>,
         state: Arc<AtomicU8>,
         awake_write_loop_ch: Option<Arc<mpsc::Sender<()>>>,
@@ -127,6 +134,8 @@ impl Stream {
             default_payload_type: AtomicU32::new(0), //PayloadProtocolIdentifier::Unknown,
             reassembly_queue: Mutex::new(ReassemblyQueue::new(stream_identifier)),
             sequence_number: AtomicU16::new(0),
+            message_identifier: AtomicU32::new(0),
+            unordered_message_identifier: AtomicU32::new(0),
             read_notifier: Notify::new(),
             read_shutdown: AtomicBool::new(false),
             write_shutdown: AtomicBool::new(false),
@@ -151,6 +160,19 @@ impl Stream {
             .store(default_payload_type as u32, Ordering::SeqCst);
     }
 
+    /// set_priority sets the priority of the stream, as used by the priority and weighted
+    /// fair queue stream schedulers of the association. Higher values get served first or
+    /// get a larger share of the sending capacity.
+    pub fn set_priority(&self, priority: u16) {
+        self.pending_queue
+            .set_stream_priority(self.stream_identifier, priority);
+    }
+
+    /// priority returns the priority of the stream.
+    pub fn priority(&self) -> u16 {
+        self.pending_queue.stream_priority(self.stream_identifier)
+    }
+
     /// set_reliability_params sets reliability parameters for this stream.
     pub fn set_reliability_params(&self, unordered: bool, rel_type: ReliabilityType, rel_val: u32) {
         log::debug!(
@@ -257,6 +279,26 @@ impl Stream {
         }
     }
 
+    /// handle_forward_tsn_for_mid removes the fragments of the messages skipped by an
+    /// I-FORWARD-TSN chunk, which identifies them by MID for both ordered and unordered
+    /// messages.
+    pub(crate) async fn handle_forward_tsn_for_mid(&self, unordered: bool, mid: u32) {
+        let readable = {
+            let mut reassembly_queue = self.reassembly_queue.lock().await;
+            if unordered {
+                reassembly_queue.forward_tsn_for_unordered_mid(mid);
+            } else {
+                reassembly_queue.forward_tsn_for_ordered_mid(mid);
+            }
+            reassembly_queue.is_readable()
+        };
+
+        // Notify the reader asynchronously if there's a data chunk to read.
+        if readable {
+            self.read_notifier.notify_one();
+        }
+    }
+
     /// Writes `p` to the DTLS connection with the default Payload Protocol Identifier.
     ///
     /// Returns an error if the write half of this stream is shutdown or `p` is too large.
@@ -311,12 +353,31 @@ impl Stream {
         let unordered =
             ppi != PayloadProtocolIdentifier::Dcep && self.unordered.load(Ordering::SeqCst);
 
+        // I-DATA chunks identify the fragments of a message by MID and FSN, which are
+        // counted separately for ordered and unordered messages (RFC 8260 Sec 2.1)
+        let interleaved = self.pending_queue.is_interleaved();
+        let max_payload_size = self.max_payload_size.load(Ordering::SeqCst) as usize;
+        let (max_payload_size, message_identifier) = if interleaved {
+            let message_identifier = if unordered {
+                self.unordered_message_identifier
+                    .fetch_add(1, Ordering::SeqCst)
+            } else {
+                self.message_identifier.fetch_add(1, Ordering::SeqCst)
+            };
+            (
+                max_payload_size - (PAYLOAD_I_DATA_HEADER_SIZE - PAYLOAD_DATA_HEADER_SIZE),
+                message_identifier,
+            )
+        } else {
+            (max_payload_size, 0)
+        };
+
         let mut chunks = vec![];
 
         let head_abandoned = Arc::new(AtomicBool::new(false));
         let head_all_inflight = Arc::new(AtomicBool::new(false));
         while remaining != 0 {
-            let fragment_size = std::cmp::min(self.max_payload_size as usize, remaining); //self.association.max_payload_size
+            let fragment_size = std::cmp::min(max_payload_size, remaining); //self.association.max_payload_size
 
             // Copy the userdata since we'll have to store it until acked
             // and the caller may re-use the buffer in the mean time
@@ -331,6 +392,9 @@ impl Stream {
                 immediate_sack: false,
                 payload_type: ppi,
                 stream_sequence_number: self.sequence_number.load(Ordering::SeqCst),
+                interleaved,
+                message_identifier,
+                fragment_sequence_number: chunks.len() as u32,
                 abandoned: head_abandoned.clone(), // all fragmented chunks use the same abandoned
                 all_inflight: head_all_inflight.clone(), // all fragmented chunks use the same all_inflight
                 ..Default::default()
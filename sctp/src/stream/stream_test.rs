@@ -77,7 +77,7 @@ async fn test_stream() -> std::result::Result<(), io::Error> {
     let s = Stream::new(
         "test_poll_stream".to_owned(),
         0,
-        4096,
+        Arc::new(AtomicU32::new(4096)),
         Arc::new(AtomicU32::new(4096)),
         Arc::new(AtomicU8::new(AssociationState::Established as u8)),
         None,
@@ -151,7 +151,7 @@ async fn test_poll_stream() -> std::result::Result<(), io::Error> {
     let s = Arc::new(Stream::new(
         "test_poll_stream".to_owned(),
         0,
-        4096,
+        Arc::new(AtomicU32::new(4096)),
         Arc::new(AtomicU32::new(4096)),
         Arc::new(AtomicU8::new(AssociationState::Established as u8)),
         None,
@@ -0,0 +1,336 @@
+use super::*;
+use crate::description::media::MediaDescription;
+
+fn round_trip<T: TypedAttribute + fmt::Debug + PartialEq>(value: &str, expected: T) -> Result<()> {
+    let actual = T::unmarshal(value)?;
+    assert_eq!(actual, expected, "{value}");
+    assert_eq!(actual.to_string(), value);
+    assert_eq!(T::from_attribute(&actual.convert())?, expected);
+    Ok(())
+}
+
+fn assert_malformed<T: TypedAttribute + fmt::Debug>(value: &str, reason: &'static str) {
+    assert_eq!(
+        T::unmarshal(value).err(),
+        Some(Error::ParseAttribute {
+            key: T::KEY,
+            value: value.to_owned(),
+            reason,
+        }),
+        "{value}"
+    );
+}
+
+#[test]
+fn test_rtpmap() -> Result<()> {
+    round_trip(
+        "96 VP8/90000",
+        RtpMap {
+            payload_type: 96,
+            encoding_name: "VP8".to_owned(),
+            clock_rate: 90000,
+            channels: None,
+        },
+    )?;
+    round_trip(
+        "111 opus/48000/2",
+        RtpMap {
+            payload_type: 111,
+            encoding_name: "opus".to_owned(),
+            clock_rate: 48000,
+            channels: Some(2),
+        },
+    )?;
+
+    assert_malformed::<RtpMap>("96", "missing encoding");
+    assert_malformed::<RtpMap>("x VP8/90000", "invalid payload type");
+    assert_malformed::<RtpMap>("256 VP8/90000", "invalid payload type");
+    assert_malformed::<RtpMap>("96 VP8", "missing clock rate");
+    assert_malformed::<RtpMap>("96 VP8/fast", "invalid clock rate");
+    assert_malformed::<RtpMap>("111 opus/48000/two", "invalid encoding parameters");
+    assert_malformed::<RtpMap>("111 opus/48000/2/1", "trailing data");
+
+    Ok(())
+}
+
+#[test]
+fn test_fmtp() -> Result<()> {
+    let fmtp = Fmtp {
+        payload_type: 102,
+        parameters: "level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42001f"
+            .to_owned(),
+    };
+    round_trip(
+        "102 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42001f",
+        fmtp.clone(),
+    )?;
+    assert_eq!(fmtp.parameter("packetization-mode"), Some("1"));
+    assert_eq!(fmtp.parameter("profile-level-id"), Some("42001f"));
+    assert_eq!(fmtp.parameter("level"), None);
+
+    // Parameters without keys, like the ones of RED, are kept as written.
+    round_trip(
+        "63 111/111",
+        Fmtp {
+            payload_type: 63,
+            parameters: "111/111".to_owned(),
+        },
+    )?;
+    let fmtp = Fmtp::unmarshal("111 minptime=10; useinbandfec=1")?;
+    assert_eq!(fmtp.parameter("useinbandfec"), Some("1"));
+
+    assert_malformed::<Fmtp>("96", "missing parameters");
+    assert_malformed::<Fmtp>("96 ", "missing parameters");
+    assert_malformed::<Fmtp>("VP8 apt=96", "invalid payload type");
+
+    Ok(())
+}
+
+#[test]
+fn test_rtcp_fb() -> Result<()> {
+    round_trip(
+        "96 nack",
+        RtcpFb {
+            payload_type: Some(96),
+            typ: "nack".to_owned(),
+            parameter: None,
+        },
+    )?;
+    round_trip(
+        "96 nack pli",
+        RtcpFb {
+            payload_type: Some(96),
+            typ: "nack".to_owned(),
+            parameter: Some("pli".to_owned()),
+        },
+    )?;
+    round_trip(
+        "* transport-cc",
+        RtcpFb {
+            payload_type: None,
+            typ: "transport-cc".to_owned(),
+            parameter: None,
+        },
+    )?;
+    round_trip(
+        "96 ccm tmmbr smaxpr=120",
+        RtcpFb {
+            payload_type: Some(96),
+            typ: "ccm".to_owned(),
+            parameter: Some("tmmbr smaxpr=120".to_owned()),
+        },
+    )?;
+
+    assert_malformed::<RtcpFb>("96", "missing feedback type");
+    assert_malformed::<RtcpFb>("96 ", "missing feedback type");
+    assert_malformed::<RtcpFb>("all nack", "invalid payload type");
+
+    Ok(())
+}
+
+#[test]
+fn test_ssrc() -> Result<()> {
+    round_trip(
+        "2231627014 cname:4TOk42mSjXCkVIa6",
+        Ssrc {
+            ssrc: 2231627014,
+            attribute: "cname".to_owned(),
+            value: Some("4TOk42mSjXCkVIa6".to_owned()),
+        },
+    )?;
+    round_trip(
+        "2231627014 msid:stream track",
+        Ssrc {
+            ssrc: 2231627014,
+            attribute: "msid".to_owned(),
+            value: Some("stream track".to_owned()),
+        },
+    )?;
+    round_trip(
+        "1 previous-ssrc",
+        Ssrc {
+            ssrc: 1,
+            attribute: "previous-ssrc".to_owned(),
+            value: None,
+        },
+    )?;
+
+    round_trip(
+        "5000",
+        Ssrc {
+            ssrc: 5000,
+            ..Default::default()
+        },
+    )?;
+
+    assert_malformed::<Ssrc>("2231627014 ", "missing source attribute");
+    assert_malformed::<Ssrc>("2231627014 :x", "missing source attribute");
+    assert_malformed::<Ssrc>("-1 cname:x", "invalid ssrc");
+    assert_malformed::<Ssrc>("4294967296 cname:x", "invalid ssrc");
+
+    Ok(())
+}
+
+#[test]
+fn test_ssrc_group() -> Result<()> {
+    round_trip(
+        "FID 2231627014 632943048",
+        SsrcGroup {
+            semantics: "FID".to_owned(),
+            ssrcs: vec![2231627014, 632943048],
+        },
+    )?;
+    round_trip(
+        "SIM 1 2 3",
+        SsrcGroup {
+            semantics: "SIM".to_owned(),
+            ssrcs: vec![1, 2, 3],
+        },
+    )?;
+
+    assert_malformed::<SsrcGroup>("", "missing semantics");
+    assert_malformed::<SsrcGroup>("FID", "missing ssrc");
+    assert_malformed::<SsrcGroup>("FID 1 x", "invalid ssrc");
+
+    Ok(())
+}
+
+#[test]
+fn test_rid() -> Result<()> {
+    round_trip(
+        "hi send",
+        Rid {
+            id: "hi".to_owned(),
+            direction: RidDirection::Send,
+            ..Default::default()
+        },
+    )?;
+    round_trip(
+        "1 recv pt=96,97;max-width=1280;max-height=720",
+        Rid {
+            id: "1".to_owned(),
+            direction: RidDirection::Recv,
+            payload_types: vec![96, 97],
+            restrictions: vec![
+                ("max-width".to_owned(), "1280".to_owned()),
+                ("max-height".to_owned(), "720".to_owned()),
+            ],
+        },
+    )?;
+    round_trip(
+        "lo_1 send max-br=100000",
+        Rid {
+            id: "lo_1".to_owned(),
+            direction: RidDirection::Send,
+            payload_types: vec![],
+            restrictions: vec![("max-br".to_owned(), "100000".to_owned())],
+        },
+    )?;
+
+    assert_malformed::<Rid>("", "invalid rid id");
+    assert_malformed::<Rid>("h.i send", "invalid rid id");
+    assert_malformed::<Rid>("hi", "missing direction");
+    assert_malformed::<Rid>("hi sendrecv", "unknown direction");
+    assert_malformed::<Rid>("hi send pt=x", "invalid payload type");
+    assert_malformed::<Rid>("hi send max-width", "parameter without value");
+
+    Ok(())
+}
+
+#[test]
+fn test_simulcast() -> Result<()> {
+    let rid = |id: &str, paused: bool| SimulcastRid {
+        id: id.to_owned(),
+        paused,
+    };
+
+    round_trip(
+        "send hi;mid;lo",
+        Simulcast {
+            send: vec![
+                vec![rid("hi", false)],
+                vec![rid("mid", false)],
+                vec![rid("lo", false)],
+            ],
+            recv: vec![],
+        },
+    )?;
+    round_trip(
+        "send 1,~4;2;3 recv c",
+        Simulcast {
+            send: vec![
+                vec![rid("1", false), rid("4", true)],
+                vec![rid("2", false)],
+                vec![rid("3", false)],
+            ],
+            recv: vec![vec![rid("c", false)]],
+        },
+    )?;
+    round_trip(
+        "recv ~a;b",
+        Simulcast {
+            send: vec![],
+            recv: vec![vec![rid("a", true)], vec![rid("b", false)]],
+        },
+    )?;
+
+    // The send streams are always written first.
+    let simulcast = Simulcast::unmarshal("recv c send a")?;
+    assert_eq!(simulcast.to_string(), "send a recv c");
+
+    assert_malformed::<Simulcast>("", "missing direction");
+    assert_malformed::<Simulcast>("sendrecv a", "unknown direction");
+    assert_malformed::<Simulcast>("send", "missing rids");
+    assert_malformed::<Simulcast>("send a send b", "repeated direction");
+    assert_malformed::<Simulcast>("send a;;b", "invalid rid id");
+    assert_malformed::<Simulcast>("send ~", "invalid rid id");
+
+    Ok(())
+}
+
+#[test]
+fn test_media_description_typed_attributes() -> Result<()> {
+    let rtpmap = RtpMap {
+        payload_type: 96,
+        encoding_name: "VP8".to_owned(),
+        clock_rate: 90000,
+        channels: None,
+    };
+    let rtcp_fb = RtcpFb {
+        payload_type: Some(96),
+        typ: "nack".to_owned(),
+        parameter: None,
+    };
+    let media = MediaDescription::default()
+        .with_typed_attribute(&rtpmap)
+        .with_typed_attribute(&rtcp_fb)
+        .with_value_attribute("rtcp-fb".to_owned(), "96 nack pli".to_owned());
+
+    assert_eq!(media.attribute(ATTR_KEY_RTPMAP), Some(Some("96 VP8/90000")));
+    assert_eq!(media.typed_attributes::<RtpMap>()?, vec![rtpmap]);
+    assert_eq!(media.typed_attributes::<RtcpFb>()?.len(), 2);
+    assert!(media.typed_attributes::<Rid>()?.is_empty());
+
+    let media = media.with_value_attribute("ssrc".to_owned(), "x cname:y".to_owned());
+    assert_eq!(
+        media.typed_attributes::<Ssrc>().err(),
+        Some(Error::ParseAttribute {
+            key: ATTR_KEY_SSRC,
+            value: "x cname:y".to_owned(),
+            reason: "invalid ssrc",
+        })
+    );
+
+    assert!(RtpMap::from_attribute(&rtcp_fb.convert()).is_err());
+    assert!(RtpMap::from_attribute(&Attribute::new(ATTR_KEY_RTPMAP.to_owned(), None)).is_err());
+
+    let extmap =
+        <ExtMap as TypedAttribute>::unmarshal("2/recvonly urn:ietf:params:rtp-hdrext:sdes:mid")?;
+    assert_eq!(extmap.value, 2);
+    assert_eq!(
+        extmap.convert().to_string(),
+        "extmap:2/recvonly urn:ietf:params:rtp-hdrext:sdes:mid"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,530 @@
+#[cfg(test)]
+mod attribute_test;
+
+use std::fmt;
+
+use super::error::{Error, Result};
+use crate::description::common::Attribute;
+use crate::description::session::{
+    ATTR_KEY_EXT_MAP, ATTR_KEY_FMTP, ATTR_KEY_RID, ATTR_KEY_RTCPFB, ATTR_KEY_RTPMAP,
+    ATTR_KEY_SIMULCAST, ATTR_KEY_SSRC, ATTR_KEY_SSRCGROUP,
+};
+use crate::extmap::ExtMap;
+
+/// TypedAttribute is an attribute whose value has a known syntax, parsed into a
+/// struct of its own. Display renders the value of the attribute, without its key.
+pub trait TypedAttribute: Sized + fmt::Display {
+    /// The key of the attribute, as in `a=<key>:<value>`.
+    const KEY: &'static str;
+
+    /// unmarshal parses the value of the attribute.
+    fn unmarshal(value: &str) -> Result<Self>;
+
+    /// from_attribute parses an attribute, which must have the key of this type.
+    fn from_attribute(attribute: &Attribute) -> Result<Self> {
+        if attribute.key != Self::KEY {
+            return Err(malformed::<Self>(&attribute.to_string(), "unexpected key"));
+        }
+        match &attribute.value {
+            Some(value) => Self::unmarshal(value),
+            None => Err(malformed::<Self>("", "missing value")),
+        }
+    }
+
+    /// convert returns the attribute with this value.
+    fn convert(&self) -> Attribute {
+        Attribute::new(Self::KEY.to_owned(), Some(self.to_string()))
+    }
+}
+
+fn malformed<T: TypedAttribute>(value: &str, reason: &'static str) -> Error {
+    Error::ParseAttribute {
+        key: T::KEY,
+        value: value.to_owned(),
+        reason,
+    }
+}
+
+fn parse<T: TypedAttribute, N: std::str::FromStr>(
+    value: &str,
+    field: &str,
+    reason: &'static str,
+) -> Result<N> {
+    field.parse().map_err(|_| malformed::<T>(value, reason))
+}
+
+impl TypedAttribute for ExtMap {
+    const KEY: &'static str = ATTR_KEY_EXT_MAP;
+
+    fn unmarshal(value: &str) -> Result<Self> {
+        let line = format!("{}:{value}", Self::KEY);
+        ExtMap::unmarshal(&mut line.as_bytes())
+    }
+}
+
+/// RtpMap is an `a=rtpmap:<payload type> <encoding name>/<clock rate>[/<encoding parameters>]`
+/// attribute, mapping a payload type to a codec.
+/// <https://tools.ietf.org/html/rfc4566#section-6>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RtpMap {
+    pub payload_type: u8,
+    pub encoding_name: String,
+    pub clock_rate: u32,
+    /// The number of channels of audio codecs.
+    pub channels: Option<u16>,
+}
+
+impl fmt::Display for RtpMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}/{}",
+            self.payload_type, self.encoding_name, self.clock_rate
+        )?;
+        if let Some(channels) = self.channels {
+            write!(f, "/{channels}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedAttribute for RtpMap {
+    const KEY: &'static str = ATTR_KEY_RTPMAP;
+
+    fn unmarshal(value: &str) -> Result<Self> {
+        let (payload_type, encoding) = value
+            .split_once(' ')
+            .ok_or_else(|| malformed::<Self>(value, "missing encoding"))?;
+        let payload_type = parse::<Self, _>(value, payload_type, "invalid payload type")?;
+
+        let mut split = encoding.trim().split('/');
+        let encoding_name = split.next().unwrap_or_default();
+        if encoding_name.is_empty() {
+            return Err(malformed::<Self>(value, "missing encoding name"));
+        }
+        let clock_rate = split
+            .next()
+            .ok_or_else(|| malformed::<Self>(value, "missing clock rate"))?;
+        let clock_rate = parse::<Self, _>(value, clock_rate, "invalid clock rate")?;
+        let channels = split
+            .next()
+            .map(|channels| parse::<Self, _>(value, channels, "invalid encoding parameters"))
+            .transpose()?;
+        if split.next().is_some() {
+            return Err(malformed::<Self>(value, "trailing data"));
+        }
+
+        Ok(RtpMap {
+            payload_type,
+            encoding_name: encoding_name.to_owned(),
+            clock_rate,
+            channels,
+        })
+    }
+}
+
+/// Fmtp is an `a=fmtp:<format> <format specific parameters>` attribute.
+/// <https://tools.ietf.org/html/rfc4566#section-6>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Fmtp {
+    pub payload_type: u8,
+    /// The parameters as written, usually `key=value` pairs separated by ";".
+    pub parameters: String,
+}
+
+impl Fmtp {
+    /// parameter returns the value of a `key=value` parameter.
+    pub fn parameter(&self, key: &str) -> Option<&str> {
+        self.parameters.split(';').find_map(|parameter| {
+            let (k, v) = parameter.split_once('=')?;
+            (k.trim() == key).then(|| v.trim())
+        })
+    }
+}
+
+impl fmt::Display for Fmtp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.payload_type, self.parameters)
+    }
+}
+
+impl TypedAttribute for Fmtp {
+    const KEY: &'static str = ATTR_KEY_FMTP;
+
+    fn unmarshal(value: &str) -> Result<Self> {
+        let (payload_type, parameters) = value
+            .split_once(' ')
+            .ok_or_else(|| malformed::<Self>(value, "missing parameters"))?;
+        let parameters = parameters.trim();
+        if parameters.is_empty() {
+            return Err(malformed::<Self>(value, "missing parameters"));
+        }
+
+        Ok(Fmtp {
+            payload_type: parse::<Self, _>(value, payload_type, "invalid payload type")?,
+            parameters: parameters.to_owned(),
+        })
+    }
+}
+
+/// RtcpFb is an `a=rtcp-fb:<payload type> <type> [<parameter>]` attribute, enabling
+/// an RTCP feedback message for a payload type or, with "*", for all of them.
+/// <https://tools.ietf.org/html/rfc4585#section-4.2>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RtcpFb {
+    /// The payload type, or None for all of them.
+    pub payload_type: Option<u8>,
+    pub typ: String,
+    pub parameter: Option<String>,
+}
+
+impl fmt::Display for RtcpFb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.payload_type {
+            Some(payload_type) => write!(f, "{payload_type} {}", self.typ)?,
+            None => write!(f, "* {}", self.typ)?,
+        }
+        if let Some(parameter) = &self.parameter {
+            write!(f, " {parameter}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedAttribute for RtcpFb {
+    const KEY: &'static str = ATTR_KEY_RTCPFB;
+
+    fn unmarshal(value: &str) -> Result<Self> {
+        let (payload_type, feedback) = value
+            .split_once(' ')
+            .ok_or_else(|| malformed::<Self>(value, "missing feedback type"))?;
+        let payload_type = match payload_type {
+            "*" => None,
+            payload_type => Some(parse::<Self, _>(
+                value,
+                payload_type,
+                "invalid payload type",
+            )?),
+        };
+
+        let feedback = feedback.trim();
+        let (typ, parameter) = match feedback.split_once(' ') {
+            Some((typ, parameter)) => (typ, Some(parameter.trim().to_owned())),
+            None => (feedback, None),
+        };
+        if typ.is_empty() {
+            return Err(malformed::<Self>(value, "missing feedback type"));
+        }
+
+        Ok(RtcpFb {
+            payload_type,
+            typ: typ.to_owned(),
+            parameter,
+        })
+    }
+}
+
+/// Ssrc is an `a=ssrc:<ssrc-id> <attribute>[:<value>]` attribute, describing a
+/// source of the media section.
+/// <https://tools.ietf.org/html/rfc5576#section-4.1>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Ssrc {
+    pub ssrc: u32,
+    /// The source attribute, empty for a bare `a=ssrc:<ssrc-id>` as some
+    /// implementations send.
+    pub attribute: String,
+    pub value: Option<String>,
+}
+
+impl fmt::Display for Ssrc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.ssrc)?;
+        if self.attribute.is_empty() {
+            return Ok(());
+        }
+        write!(f, " {}", self.attribute)?;
+        if let Some(value) = &self.value {
+            write!(f, ":{value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedAttribute for Ssrc {
+    const KEY: &'static str = ATTR_KEY_SSRC;
+
+    fn unmarshal(value: &str) -> Result<Self> {
+        let (ssrc, source_attribute) = match value.split_once(' ') {
+            Some((ssrc, source_attribute)) => (ssrc, Some(source_attribute)),
+            None => (value, None),
+        };
+        let ssrc = parse::<Self, _>(value, ssrc, "invalid ssrc")?;
+        let Some(source_attribute) = source_attribute else {
+            return Ok(Ssrc {
+                ssrc,
+                ..Default::default()
+            });
+        };
+
+        let (attribute, source_value) = match source_attribute.split_once(':') {
+            Some((attribute, source_value)) => (attribute, Some(source_value.to_owned())),
+            None => (source_attribute, None),
+        };
+        if attribute.is_empty() {
+            return Err(malformed::<Self>(value, "missing source attribute"));
+        }
+
+        Ok(Ssrc {
+            ssrc,
+            attribute: attribute.to_owned(),
+            value: source_value,
+        })
+    }
+}
+
+/// SsrcGroup is an `a=ssrc-group:<semantics> <ssrc-id> ...` attribute, like
+/// `FID` pairing a source with its RTX repair flow.
+/// <https://tools.ietf.org/html/rfc5576#section-4.2>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SsrcGroup {
+    pub semantics: String,
+    pub ssrcs: Vec<u32>,
+}
+
+impl fmt::Display for SsrcGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.semantics)?;
+        for ssrc in &self.ssrcs {
+            write!(f, " {ssrc}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedAttribute for SsrcGroup {
+    const KEY: &'static str = ATTR_KEY_SSRCGROUP;
+
+    fn unmarshal(value: &str) -> Result<Self> {
+        let mut split = value.split_whitespace();
+        let semantics = split
+            .next()
+            .ok_or_else(|| malformed::<Self>(value, "missing semantics"))?;
+        let ssrcs = split
+            .map(|ssrc| parse::<Self, _>(value, ssrc, "invalid ssrc"))
+            .collect::<Result<Vec<u32>>>()?;
+        if ssrcs.is_empty() {
+            return Err(malformed::<Self>(value, "missing ssrc"));
+        }
+
+        Ok(SsrcGroup {
+            semantics: semantics.to_owned(),
+            ssrcs,
+        })
+    }
+}
+
+/// RidDirection is the direction of the RTP streams a rid applies to.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum RidDirection {
+    #[default]
+    Send,
+    Recv,
+}
+
+impl fmt::Display for RidDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RidDirection::Send => write!(f, "send"),
+            RidDirection::Recv => write!(f, "recv"),
+        }
+    }
+}
+
+/// Rid is an `a=rid:<rid-id> <direction> [pt=<fmt-list>;]<restriction>=<value>...`
+/// attribute, restricting the RTP stream of a rid.
+/// <https://tools.ietf.org/html/rfc8851#section-4>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Rid {
+    pub id: String,
+    pub direction: RidDirection,
+    /// The payload types of the `pt=` parameter, empty if it is absent.
+    pub payload_types: Vec<u8>,
+    /// The other parameters, like `max-width=1280`, in order.
+    pub restrictions: Vec<(String, String)>,
+}
+
+impl fmt::Display for Rid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.id, self.direction)?;
+
+        let mut params = vec![];
+        if !self.payload_types.is_empty() {
+            let payload_types: Vec<String> = self.payload_types.iter().map(u8::to_string).collect();
+            params.push(format!("pt={}", payload_types.join(",")));
+        }
+        params.extend(self.restrictions.iter().map(|(k, v)| format!("{k}={v}")));
+        if !params.is_empty() {
+            write!(f, " {}", params.join(";"))?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedAttribute for Rid {
+    const KEY: &'static str = ATTR_KEY_RID;
+
+    fn unmarshal(value: &str) -> Result<Self> {
+        let mut split = value.splitn(3, ' ');
+        let id = split.next().unwrap_or_default();
+        if !is_rid_id(id) {
+            return Err(malformed::<Self>(value, "invalid rid id"));
+        }
+        let direction = match split.next() {
+            Some("send") => RidDirection::Send,
+            Some("recv") => RidDirection::Recv,
+            Some(_) => return Err(malformed::<Self>(value, "unknown direction")),
+            None => return Err(malformed::<Self>(value, "missing direction")),
+        };
+
+        let mut payload_types = vec![];
+        let mut restrictions = vec![];
+        for param in split.next().unwrap_or_default().split(';') {
+            let param = param.trim();
+            if param.is_empty() {
+                continue;
+            }
+            let (key, param_value) = param
+                .split_once('=')
+                .ok_or_else(|| malformed::<Self>(value, "parameter without value"))?;
+            if key == "pt" {
+                for payload_type in param_value.split(',') {
+                    payload_types.push(parse::<Self, _>(
+                        value,
+                        payload_type,
+                        "invalid payload type",
+                    )?);
+                }
+            } else {
+                restrictions.push((key.to_owned(), param_value.to_owned()));
+            }
+        }
+
+        Ok(Rid {
+            id: id.to_owned(),
+            direction,
+            payload_types,
+            restrictions,
+        })
+    }
+}
+
+fn is_rid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// SimulcastRid is a rid of an `a=simulcast` attribute, paused when written with
+/// a leading "~".
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SimulcastRid {
+    pub id: String,
+    pub paused: bool,
+}
+
+impl fmt::Display for SimulcastRid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.paused {
+            write!(f, "~")?;
+        }
+        write!(f, "{}", self.id)
+    }
+}
+
+/// Simulcast is an `a=simulcast:send <streams> recv <streams>` attribute. Streams
+/// are separated by ";", and each is a list of alternative rids separated by ",".
+/// The send streams are always written first.
+/// <https://tools.ietf.org/html/rfc8853#section-5.1>
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Simulcast {
+    pub send: Vec<Vec<SimulcastRid>>,
+    pub recv: Vec<Vec<SimulcastRid>>,
+}
+
+impl Simulcast {
+    fn fmt_streams(f: &mut fmt::Formatter<'_>, streams: &[Vec<SimulcastRid>]) -> fmt::Result {
+        for (i, alternatives) in streams.iter().enumerate() {
+            if i > 0 {
+                write!(f, ";")?;
+            }
+            for (j, rid) in alternatives.iter().enumerate() {
+                if j > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{rid}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Simulcast {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.send.is_empty() {
+            write!(f, "send ")?;
+            Simulcast::fmt_streams(f, &self.send)?;
+        }
+        if !self.recv.is_empty() {
+            if !self.send.is_empty() {
+                write!(f, " ")?;
+            }
+            write!(f, "recv ")?;
+            Simulcast::fmt_streams(f, &self.recv)?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedAttribute for Simulcast {
+    const KEY: &'static str = ATTR_KEY_SIMULCAST;
+
+    fn unmarshal(value: &str) -> Result<Self> {
+        let mut simulcast = Simulcast::default();
+        let mut split = value.split_whitespace();
+        while let Some(direction) = split.next() {
+            let streams = match direction {
+                "send" if simulcast.send.is_empty() => &mut simulcast.send,
+                "recv" if simulcast.recv.is_empty() => &mut simulcast.recv,
+                "send" | "recv" => return Err(malformed::<Self>(value, "repeated direction")),
+                _ => return Err(malformed::<Self>(value, "unknown direction")),
+            };
+            let list = split
+                .next()
+                .ok_or_else(|| malformed::<Self>(value, "missing rids"))?;
+
+            for alternatives in list.split(';') {
+                let mut rids = vec![];
+                for rid in alternatives.split(',') {
+                    let (id, paused) = match rid.strip_prefix('~') {
+                        Some(id) => (id, true),
+                        None => (rid, false),
+                    };
+                    if !is_rid_id(id) {
+                        return Err(malformed::<Self>(value, "invalid rid id"));
+                    }
+                    rids.push(SimulcastRid {
+                        id: id.to_owned(),
+                        paused,
+                    });
+                }
+                streams.push(rids);
+            }
+        }
+
+        if simulcast.send.is_empty() && simulcast.recv.is_empty() {
+            return Err(malformed::<Self>(value, "missing direction"));
+        }
+        Ok(simulcast)
+    }
+}
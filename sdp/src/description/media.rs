@@ -3,7 +3,9 @@ use std::fmt;
 
 use url::Url;
 
+use crate::attribute::TypedAttribute;
 use crate::description::common::*;
+use crate::error::Result;
 use crate::extmap::*;
 
 /// Constants for extmap key
@@ -70,6 +72,16 @@ impl MediaDescription {
         None
     }
 
+    /// typed_attributes parses every attribute of type T, failing on the first
+    /// malformed one.
+    pub fn typed_attributes<T: TypedAttribute>(&self) -> Result<Vec<T>> {
+        self.attributes
+            .iter()
+            .filter(|a| a.key == T::KEY)
+            .map(T::from_attribute)
+            .collect()
+    }
+
     /// new_jsep_media_description creates a new MediaName with
     /// some settings that are required by the JSEP spec.
     pub fn new_jsep_media_description(codec_type: String, _codec_prefs: Vec<&str>) -> Self {
@@ -116,6 +128,12 @@ impl MediaDescription {
         self
     }
 
+    /// with_typed_attribute adds a typed attribute 'a=key:value' to the media description
+    pub fn with_typed_attribute<T: TypedAttribute>(mut self, attribute: &T) -> Self {
+        self.attributes.push(attribute.convert());
+        self
+    }
+
     /// with_fingerprint adds a fingerprint to the media description
     pub fn with_fingerprint(self, algorithm: String, value: String) -> Self {
         self.with_value_attribute("fingerprint".to_owned(), algorithm + " " + &value)
@@ -204,7 +222,7 @@ impl MediaDescription {
 /// it may be necessary to specify multiple transport ports, the protocol allows
 /// to write it as: <port>/<number of ports> where number of ports is a an
 /// offsetting range.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct RangedPort {
     pub value: isize,
     pub range: Option<isize>,
@@ -221,7 +239,7 @@ impl fmt::Display for RangedPort {
 }
 
 /// MediaName describes the "m=" field storage structure.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct MediaName {
     pub media: String,
     pub port: RangedPort,
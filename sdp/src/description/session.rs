@@ -30,11 +30,17 @@ pub const ATTR_KEY_RECV_ONLY: &str = "recvonly";
 pub const ATTR_KEY_SEND_ONLY: &str = "sendonly";
 pub const ATTR_KEY_SEND_RECV: &str = "sendrecv";
 pub const ATTR_KEY_EXT_MAP: &str = "extmap";
+pub const ATTR_KEY_RTPMAP: &str = "rtpmap";
+pub const ATTR_KEY_FMTP: &str = "fmtp";
+pub const ATTR_KEY_RTCPFB: &str = "rtcp-fb";
+pub const ATTR_KEY_RID: &str = "rid";
+pub const ATTR_KEY_SIMULCAST: &str = "simulcast";
 
 /// Constants for semantic tokens used in JSEP
 pub const SEMANTIC_TOKEN_LIP_SYNCHRONIZATION: &str = "LS";
 pub const SEMANTIC_TOKEN_FLOW_IDENTIFICATION: &str = "FID";
 pub const SEMANTIC_TOKEN_FORWARD_ERROR_CORRECTION: &str = "FEC";
+pub const SEMANTIC_TOKEN_FORWARD_ERROR_CORRECTION_FRAMEWORK: &str = "FEC-FR";
 pub const SEMANTIC_TOKEN_WEBRTC_MEDIA_STREAMS: &str = "WMS";
 
 /// Version describes the value provided by the "v=" field which gives
@@ -1193,11 +1199,8 @@ fn unmarshal_session_attribute<'a, R: io::BufRead + io::Seek>(
     Ok(Some(StateFn { f: s11 }))
 }
 
-fn unmarshal_media_description<'a, R: io::BufRead + io::Seek>(
-    lexer: &mut Lexer<'a, R>,
-) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
-
+/// unmarshal_media_name parses the value of a "m=" field.
+pub(crate) fn unmarshal_media_name(value: &str) -> Result<MediaName> {
     let fields: Vec<&str> = value.split_whitespace().collect();
     if fields.len() < 4 {
         return Err(Error::SdpInvalidSyntax(format!("`m={value}`")));
@@ -1248,16 +1251,24 @@ fn unmarshal_media_description<'a, R: io::BufRead + io::Seek>(
         formats.push(field.to_string());
     }
 
-    lexer.desc.media_descriptions.push(MediaDescription {
-        media_name: MediaName {
-            media: fields[0].to_owned(),
-            port: RangedPort {
-                value: port_value,
-                range: port_range,
-            },
-            protos,
-            formats,
+    Ok(MediaName {
+        media: fields[0].to_owned(),
+        port: RangedPort {
+            value: port_value,
+            range: port_range,
         },
+        protos,
+        formats,
+    })
+}
+
+fn unmarshal_media_description<'a, R: io::BufRead + io::Seek>(
+    lexer: &mut Lexer<'a, R>,
+) -> Result<Option<StateFn<'a, R>>> {
+    let (value, _) = read_value(lexer.reader)?;
+
+    lexer.desc.media_descriptions.push(MediaDescription {
+        media_name: unmarshal_media_name(&value)?,
         media_title: None,
         connection_information: None,
         bandwidth: vec![],
@@ -34,6 +34,12 @@ pub enum Error {
     ParseUrl(#[from] url::ParseError),
     #[error("parse extmap: {0}")]
     ParseExtMap(String),
+    #[error("parse {key} attribute {value:?}: {reason}")]
+    ParseAttribute {
+        key: &'static str,
+        value: String,
+        reason: &'static str,
+    },
     #[error("{} --> {} <-- {}", .s.substring(0,*.p), .s.substring(*.p, *.p+1), .s.substring(*.p+1, .s.len()))]
     SyntaxError { s: String, p: usize },
 }
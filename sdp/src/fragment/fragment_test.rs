@@ -0,0 +1,150 @@
+use super::*;
+use crate::description::media::RangedPort;
+
+const CANDIDATE: &str =
+    "1387637174 1 udp 2122260223 192.0.2.1 61764 typ host generation 0 ufrag EsAw network-id 1";
+
+// https://www.rfc-editor.org/rfc/rfc8840#section-9
+const FRAGMENT: &str = "a=ice-options:trickle ice2\r\n\
+a=group:BUNDLE 0 1\r\n\
+m=audio 9 RTP/AVP 0\r\n\
+a=mid:0\r\n\
+a=ice-ufrag:EsAw\r\n\
+a=ice-pwd:P2uYro0UCOQ4zxjKXaWCBui1\r\n\
+a=candidate:1387637174 1 udp 2122260223 192.0.2.1 61764 typ host generation 0 ufrag EsAw network-id 1\r\n\
+a=candidate:3471623853 1 udp 2122194687 198.51.100.2 61765 typ host generation 0 ufrag EsAw network-id 2\r\n\
+a=candidate:473322822 1 tcp 1518280447 192.0.2.1 9 typ host tcptype active generation 0 ufrag EsAw network-id 1\r\n\
+a=candidate:2154773085 1 tcp 1518214911 198.51.100.2 9 typ host tcptype active generation 0 ufrag EsAw network-id 2\r\n\
+a=end-of-candidates\r\n";
+
+#[test]
+fn test_sdp_fragment_unmarshal() -> Result<()> {
+    let fragment = SdpFragment::try_from(FRAGMENT.to_owned())?;
+
+    assert_eq!(fragment.ice_ufrag, None);
+    assert_eq!(
+        fragment.attributes,
+        vec![
+            Attribute {
+                key: "ice-options".to_owned(),
+                value: Some("trickle ice2".to_owned()),
+            },
+            Attribute {
+                key: "group".to_owned(),
+                value: Some("BUNDLE 0 1".to_owned()),
+            },
+        ]
+    );
+    assert_eq!(fragment.media_fragments.len(), 1);
+
+    let media_fragment = &fragment.media_fragments[0];
+    assert_eq!(
+        media_fragment.media_name,
+        MediaName {
+            media: "audio".to_owned(),
+            port: RangedPort {
+                value: 9,
+                range: None,
+            },
+            protos: vec!["RTP".to_owned(), "AVP".to_owned()],
+            formats: vec!["0".to_owned()],
+        }
+    );
+    assert_eq!(media_fragment.mid.as_deref(), Some("0"));
+    assert_eq!(media_fragment.ice_ufrag(&fragment), Some("EsAw"));
+    assert_eq!(
+        media_fragment.ice_pwd(&fragment),
+        Some("P2uYro0UCOQ4zxjKXaWCBui1")
+    );
+    assert_eq!(media_fragment.candidates.len(), 4);
+    assert_eq!(media_fragment.candidates[0], CANDIDATE);
+    assert!(media_fragment.end_of_candidates);
+    assert!(media_fragment.attributes.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_sdp_fragment_round_trip() -> Result<()> {
+    let fragment = SdpFragment::try_from(FRAGMENT.to_owned())?;
+    assert_eq!(fragment.marshal(), FRAGMENT);
+
+    let fragment = SdpFragment {
+        ice_ufrag: Some("ufrag".to_owned()),
+        ice_pwd: Some("pwd".to_owned()),
+        media_fragments: vec![
+            MediaFragment {
+                media_name: MediaName {
+                    media: "video".to_owned(),
+                    port: RangedPort {
+                        value: 9,
+                        range: None,
+                    },
+                    protos: vec!["UDP".to_owned(), "TLS".to_owned(), "RTP".to_owned()],
+                    formats: vec!["96".to_owned()],
+                },
+                mid: Some("1".to_owned()),
+                candidates: vec![CANDIDATE.to_owned()],
+                ..Default::default()
+            },
+            MediaFragment {
+                media_name: MediaName {
+                    media: "application".to_owned(),
+                    port: RangedPort {
+                        value: 9,
+                        range: None,
+                    },
+                    protos: vec!["UDP".to_owned(), "DTLS".to_owned(), "SCTP".to_owned()],
+                    formats: vec!["webrtc-datachannel".to_owned()],
+                },
+                mid: Some("2".to_owned()),
+                end_of_candidates: true,
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    let marshaled = fragment.marshal();
+    assert!(
+        marshaled.starts_with("a=ice-ufrag:ufrag\r\na=ice-pwd:pwd\r\nm=video 9 UDP/TLS/RTP 96\r\n")
+    );
+    assert_eq!(SdpFragment::try_from(marshaled)?, fragment);
+
+    // LF line endings are accepted too.
+    let lf = FRAGMENT.replace("\r\n", "\n");
+    assert_eq!(
+        SdpFragment::try_from(lf)?,
+        SdpFragment::try_from(FRAGMENT.to_owned())?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_sdp_fragment_unmarshal_errors() {
+    let tests = [
+        ("v=0\r\n", "not an attribute"),
+        (
+            "a=candidate:1 1 udp 1 192.0.2.1 1 typ host\r\n",
+            "session level candidate",
+        ),
+        ("a=mid:0\r\n", "session level mid"),
+        ("a=ice-ufrag\r\n", "ufrag without value"),
+        (
+            "m=audio 9 RTP/AVP 0\r\na=candidate\r\n",
+            "candidate without value",
+        ),
+        (
+            "m=audio 9 RTP/AVP 0\r\na=end-of-candidates:1\r\n",
+            "end-of-candidates with value",
+        ),
+        ("m=audio 9\r\n", "short m= line"),
+    ];
+
+    for (fragment, name) in tests {
+        assert!(
+            SdpFragment::try_from(fragment.to_owned()).is_err(),
+            "{name} should fail"
+        );
+    }
+}
@@ -0,0 +1,214 @@
+#[cfg(test)]
+mod fragment_test;
+
+use std::{fmt, io};
+
+use super::error::{Error, Result};
+use crate::description::common::Attribute;
+use crate::description::media::MediaName;
+use crate::description::session::{
+    unmarshal_media_name, ATTR_KEY_CANDIDATE, ATTR_KEY_END_OF_CANDIDATES, ATTR_KEY_MID,
+};
+use crate::lexer::{key_value_build, END_LINE};
+use crate::util::ATTRIBUTE_KEY;
+
+pub const ATTR_KEY_ICE_UFRAG: &str = "ice-ufrag";
+pub const ATTR_KEY_ICE_PWD: &str = "ice-pwd";
+
+/// MIME type of SDP fragments, as sent in the trickle ICE PATCH requests of WHIP
+/// and WHEP.
+pub const SDP_FRAGMENT_MIME_TYPE: &str = "application/trickle-ice-sdpfrag";
+
+/// SdpFragment is an SDP fragment as defined in RFC 8840 Sec 9: the ICE credentials
+/// and candidates of a session, without the rest of a session description. It is the
+/// body of the `application/trickle-ice-sdpfrag` messages used to trickle candidates.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SdpFragment {
+    /// Session level "a=ice-ufrag", applying to every media section without one.
+    pub ice_ufrag: Option<String>,
+    /// Session level "a=ice-pwd", applying to every media section without one.
+    pub ice_pwd: Option<String>,
+    /// Other session level attributes, like "a=ice-options" or "a=group".
+    pub attributes: Vec<Attribute>,
+    pub media_fragments: Vec<MediaFragment>,
+}
+
+/// MediaFragment is the part of an SdpFragment about one media section, identified
+/// by its "a=mid".
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MediaFragment {
+    /// The "m=" line of the media section. The port is ignored by receivers and
+    /// usually 9.
+    pub media_name: MediaName,
+    pub mid: Option<String>,
+    pub ice_ufrag: Option<String>,
+    pub ice_pwd: Option<String>,
+    /// The values of the "a=candidate" attributes, without the "candidate:" prefix.
+    pub candidates: Vec<String>,
+    /// Whether "a=end-of-candidates" is present: no more candidates follow for the
+    /// media section.
+    pub end_of_candidates: bool,
+    /// Other attributes of the media section.
+    pub attributes: Vec<Attribute>,
+}
+
+impl MediaFragment {
+    /// ice_ufrag returns the ICE username fragment of the media section, falling
+    /// back to the session level one.
+    pub fn ice_ufrag<'a>(&'a self, fragment: &'a SdpFragment) -> Option<&'a str> {
+        self.ice_ufrag.as_deref().or(fragment.ice_ufrag.as_deref())
+    }
+
+    /// ice_pwd returns the ICE password of the media section, falling back to the
+    /// session level one.
+    pub fn ice_pwd<'a>(&'a self, fragment: &'a SdpFragment) -> Option<&'a str> {
+        self.ice_pwd.as_deref().or(fragment.ice_pwd.as_deref())
+    }
+}
+
+impl From<SdpFragment> for String {
+    fn from(fragment: SdpFragment) -> String {
+        fragment.marshal()
+    }
+}
+
+impl fmt::Display for SdpFragment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.marshal())
+    }
+}
+
+impl SdpFragment {
+    /// marshal returns the fragment in its text form, one line per attribute.
+    pub fn marshal(&self) -> String {
+        let mut result = String::new();
+
+        for attribute in &self.attributes {
+            result += key_value_build(ATTRIBUTE_KEY, Some(&attribute.to_string())).as_str();
+        }
+        result += marshal_credentials(self.ice_ufrag.as_ref(), self.ice_pwd.as_ref()).as_str();
+
+        for media_fragment in &self.media_fragments {
+            result += key_value_build("m=", Some(&media_fragment.media_name.to_string())).as_str();
+            if let Some(mid) = &media_fragment.mid {
+                result += format!("{ATTRIBUTE_KEY}{ATTR_KEY_MID}:{mid}{END_LINE}").as_str();
+            }
+            result += marshal_credentials(
+                media_fragment.ice_ufrag.as_ref(),
+                media_fragment.ice_pwd.as_ref(),
+            )
+            .as_str();
+            for attribute in &media_fragment.attributes {
+                result += key_value_build(ATTRIBUTE_KEY, Some(&attribute.to_string())).as_str();
+            }
+            for candidate in &media_fragment.candidates {
+                result +=
+                    format!("{ATTRIBUTE_KEY}{ATTR_KEY_CANDIDATE}:{candidate}{END_LINE}").as_str();
+            }
+            if media_fragment.end_of_candidates {
+                result += format!("{ATTRIBUTE_KEY}{ATTR_KEY_END_OF_CANDIDATES}{END_LINE}").as_str();
+            }
+        }
+
+        result
+    }
+
+    /// unmarshal parses a fragment. Lines may end with CRLF or LF, and only "a="
+    /// and "m=" lines are allowed.
+    pub fn unmarshal<R: io::BufRead>(reader: &mut R) -> Result<Self> {
+        let mut fragment = SdpFragment::default();
+
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            let line = line.trim_end_matches(['\r', '\n']);
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(value) = line.strip_prefix("m=") {
+                fragment.media_fragments.push(MediaFragment {
+                    media_name: unmarshal_media_name(value)?,
+                    ..Default::default()
+                });
+                continue;
+            }
+
+            let Some(value) = line.strip_prefix(ATTRIBUTE_KEY) else {
+                return Err(Error::SdpInvalidSyntax(line.to_owned()));
+            };
+            let attribute = match value.split_once(':') {
+                Some((key, value)) => Attribute {
+                    key: key.to_owned(),
+                    value: Some(value.to_owned()),
+                },
+                None => Attribute {
+                    key: value.to_owned(),
+                    value: None,
+                },
+            };
+
+            match fragment.media_fragments.last_mut() {
+                Some(media_fragment) => match (attribute.key.as_str(), attribute.value) {
+                    (ATTR_KEY_MID, Some(mid)) => media_fragment.mid = Some(mid),
+                    (ATTR_KEY_ICE_UFRAG, Some(ufrag)) => media_fragment.ice_ufrag = Some(ufrag),
+                    (ATTR_KEY_ICE_PWD, Some(pwd)) => media_fragment.ice_pwd = Some(pwd),
+                    (ATTR_KEY_CANDIDATE, Some(candidate)) => {
+                        media_fragment.candidates.push(candidate)
+                    }
+                    (ATTR_KEY_END_OF_CANDIDATES, None) => media_fragment.end_of_candidates = true,
+                    (
+                        ATTR_KEY_MID | ATTR_KEY_ICE_UFRAG | ATTR_KEY_ICE_PWD | ATTR_KEY_CANDIDATE,
+                        None,
+                    )
+                    | (ATTR_KEY_END_OF_CANDIDATES, Some(_)) => {
+                        return Err(Error::SdpInvalidValue(line.to_owned()))
+                    }
+                    (_, value) => media_fragment.attributes.push(Attribute {
+                        key: attribute.key,
+                        value,
+                    }),
+                },
+                None => match (attribute.key.as_str(), attribute.value) {
+                    (ATTR_KEY_ICE_UFRAG, Some(ufrag)) => fragment.ice_ufrag = Some(ufrag),
+                    (ATTR_KEY_ICE_PWD, Some(pwd)) => fragment.ice_pwd = Some(pwd),
+                    (ATTR_KEY_ICE_UFRAG | ATTR_KEY_ICE_PWD, None) => {
+                        return Err(Error::SdpInvalidValue(line.to_owned()))
+                    }
+                    // Candidates belong to a media section (RFC 8840 Sec 9).
+                    (ATTR_KEY_MID | ATTR_KEY_CANDIDATE, _) => {
+                        return Err(Error::SdpInvalidSyntax(line.to_owned()))
+                    }
+                    (_, value) => fragment.attributes.push(Attribute {
+                        key: attribute.key,
+                        value,
+                    }),
+                },
+            }
+        }
+
+        Ok(fragment)
+    }
+}
+
+impl TryFrom<String> for SdpFragment {
+    type Error = Error;
+    fn try_from(fragment_string: String) -> Result<Self> {
+        let mut reader = io::Cursor::new(fragment_string.as_bytes());
+        SdpFragment::unmarshal(&mut reader)
+    }
+}
+
+fn marshal_credentials(ufrag: Option<&String>, pwd: Option<&String>) -> String {
+    let mut result = String::new();
+    if let Some(ufrag) = ufrag {
+        result += format!("{ATTRIBUTE_KEY}{ATTR_KEY_ICE_UFRAG}:{ufrag}{END_LINE}").as_str();
+    }
+    if let Some(pwd) = pwd {
+        result += format!("{ATTRIBUTE_KEY}{ATTR_KEY_ICE_PWD}:{pwd}{END_LINE}").as_str();
+    }
+    result
+}
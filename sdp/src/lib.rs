@@ -1,9 +1,11 @@
 #![warn(rust_2018_idioms)]
 #![allow(dead_code)]
 
+pub mod attribute;
 pub mod description;
 pub mod direction;
 pub mod extmap;
+pub mod fragment;
 pub mod util;
 
 mod error;
@@ -12,3 +14,4 @@ pub(crate) mod lexer;
 pub use description::media::MediaDescription;
 pub use description::session::SessionDescription;
 pub use error::Error;
+pub use fragment::SdpFragment;
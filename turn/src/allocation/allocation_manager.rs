@@ -21,6 +21,7 @@ pub struct ManagerConfig {
 /// `Manager` is used to hold active allocations.
 pub struct Manager {
     allocations: AllocationMap,
+    connections: ConnectionMap,
     reservations: Arc<Mutex<HashMap<String, u16>>>,
     relay_addr_generator: Box<dyn RelayAddressGenerator + Send + Sync>,
     alloc_close_notify: Option<mpsc::Sender<AllocationInfo>>,
@@ -31,6 +32,7 @@ impl Manager {
     pub fn new(config: ManagerConfig) -> Self {
         Manager {
             allocations: Arc::new(Mutex::new(HashMap::new())),
+            connections: Arc::new(SyncMutex::new(HashMap::new())),
             reservations: Arc::new(Mutex::new(HashMap::new())),
             relay_addr_generator: config.relay_addr_generator,
             alloc_close_notify: config.alloc_close_notify,
@@ -124,6 +126,61 @@ impl Manager {
         Ok(a)
     }
 
+    /// Creates a new TCP [`Allocation`] and starts accepting peer connections.
+    pub async fn create_tcp_allocation(
+        &self,
+        five_tuple: FiveTuple,
+        turn_socket: Arc<dyn Conn + Send + Sync>,
+        lifetime: Duration,
+        username: Username,
+        use_ipv4: bool,
+    ) -> Result<Arc<Allocation>> {
+        if lifetime == Duration::from_secs(0) {
+            return Err(Error::ErrLifetimeZero);
+        }
+
+        if self.get_allocation(&five_tuple).await.is_some() {
+            return Err(Error::ErrDupeFiveTuple);
+        }
+
+        let (relay_listener, relay_addr) = self
+            .relay_addr_generator
+            .allocate_listener(use_ipv4, 0)
+            .await?;
+        let mut a = Allocation::new_tcp(
+            turn_socket,
+            relay_addr,
+            relay_listener.local_addr()?,
+            five_tuple,
+            username,
+            self.alloc_close_notify.clone(),
+            Arc::clone(&self.connections),
+        );
+        a.allocations = Some(Arc::clone(&self.allocations));
+
+        log::debug!("listening on TCP relay addr: {:?}", a.relay_addr);
+        a.start(lifetime).await;
+        a.tcp_connection_handler(relay_listener);
+
+        let a = Arc::new(a);
+        {
+            let mut allocations = self.allocations.lock().await;
+            allocations.insert(five_tuple, Arc::clone(&a));
+        }
+
+        Ok(a)
+    }
+
+    /// Removes the peer data connection with the CONNECTION-ID `id` of an allocation of
+    /// `username` to bind it to a client data connection.
+    pub(crate) fn take_connection(&self, id: u32, username: &str) -> Option<PeerConnection> {
+        let mut connections = self.connections.lock();
+        match connections.get(&id) {
+            Some(connection) if connection.username() == username => connections.remove(&id),
+            _ => None,
+        }
+    }
+
     /// Removes an [`Allocation`].
     pub async fn delete_allocation(&self, five_tuple: &FiveTuple) {
         let allocation = self.allocations.lock().await.remove(five_tuple);
@@ -93,7 +93,7 @@ async fn test_packet_handler() -> Result<()> {
         a.add_channel_bind(channel_bind.clone(), DEFAULT_LIFETIME)
             .await?;
 
-        a.relay_socket.local_addr()?.port()
+        a.relay_socket.as_ref().unwrap().local_addr()?.port()
     };
 
     let relay_addr_with_host_str = format!("127.0.0.1:{port}");
@@ -418,6 +418,7 @@ async fn create_server(
                 net: Arc::new(Net::new(None)),
             }),
         }],
+        listener_configs: vec![],
         realm: "webrtc.rs".to_owned(),
         auth_handler: Arc::new(TestAuthHandler {}),
         channel_bind_timeout: Duration::from_secs(0),
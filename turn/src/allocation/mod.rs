@@ -5,6 +5,7 @@ pub mod allocation_manager;
 pub mod channel_bind;
 pub mod five_tuple;
 pub mod permission;
+mod tcp_relay;
 
 use std::collections::HashMap;
 use std::marker::{Send, Sync};
@@ -18,6 +19,8 @@ use permission::*;
 use stun::agent::*;
 use stun::message::*;
 use stun::textattrs::Username;
+use tcp_relay::*;
+use tokio::net::TcpListener;
 use tokio::sync::oneshot::{self, Sender};
 use tokio::sync::{mpsc, Mutex};
 use tokio::time::{Duration, Instant};
@@ -27,6 +30,7 @@ use util::Conn;
 use crate::error::*;
 use crate::proto::chandata::*;
 use crate::proto::channum::*;
+use crate::proto::connid::*;
 use crate::proto::data::*;
 use crate::proto::peeraddr::*;
 use crate::proto::*;
@@ -68,10 +72,13 @@ impl AllocationInfo {
 /// `Allocation` is tied to a FiveTuple and relays traffic
 /// use create_allocation and get_allocation to operate.
 pub struct Allocation {
-    protocol: Protocol,
+    pub(crate) protocol: Protocol,
     turn_socket: Arc<dyn Conn + Send + Sync>,
     pub(crate) relay_addr: SocketAddr,
-    pub(crate) relay_socket: Arc<dyn Conn + Send + Sync>,
+    /// Relayed transport of UDP allocations.
+    pub(crate) relay_socket: Option<Arc<dyn Conn + Send + Sync>>,
+    /// Peer data connections of TCP allocations.
+    pub(crate) tcp_relay: Option<TcpRelay>,
     five_tuple: FiveTuple,
     username: Username,
     permissions: Arc<Mutex<HashMap<String, Permission>>>,
@@ -103,7 +110,40 @@ impl Allocation {
             protocol: PROTO_UDP,
             turn_socket,
             relay_addr,
-            relay_socket,
+            relay_socket: Some(relay_socket),
+            tcp_relay: None,
+            five_tuple,
+            username,
+            permissions: Arc::new(Mutex::new(HashMap::new())),
+            channel_bindings: Arc::new(Mutex::new(HashMap::new())),
+            allocations: None,
+            reset_tx: SyncMutex::new(None),
+            timer_expired: Arc::new(AtomicBool::new(false)),
+            closed: AtomicBool::new(false),
+            relayed_bytes: Default::default(),
+            drop_tx: None,
+            alloc_close_notify,
+        }
+    }
+
+    /// Creates a new TCP [`Allocation`] listening on `relay_local_addr`, registering its peer
+    /// data connections in `connections` until they are bound by the client.
+    pub(crate) fn new_tcp(
+        turn_socket: Arc<dyn Conn + Send + Sync>,
+        relay_addr: SocketAddr,
+        relay_local_addr: SocketAddr,
+        five_tuple: FiveTuple,
+        username: Username,
+        alloc_close_notify: Option<mpsc::Sender<AllocationInfo>>,
+        connections: ConnectionMap,
+    ) -> Self {
+        let tcp_relay = TcpRelay::new(connections, relay_local_addr, username.text.clone());
+        Allocation {
+            protocol: PROTO_TCP,
+            turn_socket,
+            relay_addr,
+            relay_socket: None,
+            tcp_relay: Some(tcp_relay),
             five_tuple,
             username,
             permissions: Arc::new(Mutex::new(HashMap::new())),
@@ -246,7 +286,12 @@ impl Allocation {
         log::trace!("allocation with {} closed!", self.five_tuple);
 
         let _ = self.turn_socket.close().await;
-        let _ = self.relay_socket.close().await;
+        if let Some(relay_socket) = &self.relay_socket {
+            let _ = relay_socket.close().await;
+        }
+        if let Some(tcp_relay) = &self.tcp_relay {
+            tcp_relay.close();
+        }
 
         if let Some(notify_tx) = &self.alloc_close_notify {
             let _ = notify_tx
@@ -335,7 +380,10 @@ impl Allocation {
     async fn packet_handler(&mut self) {
         let five_tuple = self.five_tuple;
         let relay_addr = self.relay_addr;
-        let relay_socket = Arc::clone(&self.relay_socket);
+        let relay_socket = match &self.relay_socket {
+            Some(relay_socket) => Arc::clone(relay_socket),
+            None => return,
+        };
         let turn_socket = Arc::clone(&self.turn_socket);
         let allocations = self.allocations.clone();
         let channel_bindings = Arc::clone(&self.channel_bindings);
@@ -464,4 +512,95 @@ impl Allocation {
             }
         });
     }
+
+    //  https://www.rfc-editor.org/rfc/rfc6062#section-5.3
+    //  When a server receives a TCP connection at a relayed transport
+    //  address, it does the following:
+    //
+    //  1. If the connection is not permitted by the permissions of the
+    //     allocation, the server MUST reject the connection.
+    //
+    //  2. The server assigns a CONNECTION-ID to the connection and sends a
+    //     ConnectionAttempt indication to the client over the control
+    //     connection.  The indication contains the XOR-PEER-ADDRESS of the
+    //     peer and the CONNECTION-ID.
+    //
+    //  3. If no ConnectionBind request associated with this peer data
+    //     connection is received after 30 seconds, the peer data connection
+    //     MUST be closed.
+    fn tcp_connection_handler(&self, listener: TcpListener) {
+        let tcp_relay = match &self.tcp_relay {
+            Some(tcp_relay) => tcp_relay.clone(),
+            None => return,
+        };
+        let five_tuple = self.five_tuple;
+        let relay_addr = self.relay_addr;
+        let turn_socket = Arc::clone(&self.turn_socket);
+        let permissions = Arc::clone(&self.permissions);
+        let close_notify = tcp_relay.close_notify();
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, peer_addr) = tokio::select! {
+                    result = listener.accept() => match result {
+                        Ok(v) => v,
+                        Err(err) => {
+                            log::debug!("relay listener {} failed: {}", relay_addr, err);
+                            break;
+                        }
+                    },
+                    _ = close_notify.cancelled() => break,
+                };
+
+                let exist = {
+                    let ps = permissions.lock().await;
+                    ps.get(&addr2ipfingerprint(&peer_addr)).is_some()
+                };
+                if !exist {
+                    log::info!(
+                        "No Permission exists for {} on allocation {}",
+                        peer_addr,
+                        relay_addr
+                    );
+                    continue;
+                }
+
+                let id = tcp_relay.add_connection(stream, peer_addr, false);
+
+                let mut msg = Message::new();
+                if let Err(err) = msg.build(&[
+                    Box::new(TransactionId::new()),
+                    Box::new(MessageType::new(
+                        METHOD_CONNECTION_ATTEMPT,
+                        CLASS_INDICATION,
+                    )),
+                    Box::new(PeerAddress {
+                        ip: peer_addr.ip(),
+                        port: peer_addr.port(),
+                    }),
+                    Box::new(ConnectionId(id)),
+                ]) {
+                    log::error!(
+                        "Failed to build ConnectionAttempt for allocation {} {}",
+                        relay_addr,
+                        err
+                    );
+                    continue;
+                }
+
+                log::debug!(
+                    "connection attempt from {} to client at {}",
+                    peer_addr,
+                    five_tuple.src_addr
+                );
+                if let Err(err) = turn_socket.send_to(&msg.raw, five_tuple.src_addr).await {
+                    log::error!(
+                        "Failed to send ConnectionAttempt from allocation {} {}",
+                        relay_addr,
+                        err
+                    );
+                }
+            }
+        });
+    }
 }
@@ -0,0 +1,198 @@
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use stun::agent::TransactionId;
+use tokio::net::TcpStream;
+use tokio::time::Duration;
+use tokio_util::sync::CancellationToken;
+use util::sync::Mutex as SyncMutex;
+
+use crate::error::*;
+use crate::relay::reusable_tcp_socket;
+use crate::stream::AsyncStream;
+
+/// The time a peer data connection waits for the client's ConnectionBind request.
+///
+/// [RFC 6062 Section 5.3](https://www.rfc-editor.org/rfc/rfc6062#section-5.3).
+pub(crate) const CONNECTION_BIND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The time the server tries to connect to a peer on behalf of a Connect request.
+///
+/// [RFC 6062 Section 5.2](https://www.rfc-editor.org/rfc/rfc6062#section-5.2).
+pub(crate) const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Peer data connections waiting for a ConnectionBind request, keyed by their CONNECTION-ID.
+pub(crate) type ConnectionMap = Arc<SyncMutex<HashMap<u32, PeerConnection>>>;
+
+/// `TcpRelay` manages the peer data connections of a TCP allocation.
+#[derive(Clone)]
+pub(crate) struct TcpRelay {
+    connections: ConnectionMap,
+    /// Peers with a pending or active connection initiated by a Connect request.
+    connected_peers: Arc<SyncMutex<HashSet<SocketAddr>>>,
+    /// Connect requests which are still being processed.
+    pending_connects: Arc<SyncMutex<HashSet<TransactionId>>>,
+    /// The local address of the listener, which the connections to the peers originate from.
+    local_addr: SocketAddr,
+    username: String,
+    close_notify: CancellationToken,
+}
+
+impl TcpRelay {
+    /// Creates a new [`TcpRelay`] for the listener on `local_addr`, registering its
+    /// connections in `connections`.
+    pub(crate) fn new(
+        connections: ConnectionMap,
+        local_addr: SocketAddr,
+        username: String,
+    ) -> Self {
+        TcpRelay {
+            connections,
+            connected_peers: Arc::new(SyncMutex::new(HashSet::new())),
+            pending_connects: Arc::new(SyncMutex::new(HashSet::new())),
+            local_addr,
+            username,
+            close_notify: CancellationToken::new(),
+        }
+    }
+
+    /// Connects to `peer_addr` on behalf of the Connect request `transaction_id` and returns
+    /// the CONNECTION-ID of the new peer data connection.
+    ///
+    /// Returns `None` for retransmissions of a request that is still being processed.
+    pub(crate) async fn connect(
+        &self,
+        peer_addr: SocketAddr,
+        transaction_id: TransactionId,
+    ) -> Option<Result<u32>> {
+        if !self.pending_connects.lock().insert(transaction_id) {
+            return None;
+        }
+
+        let result = self.connect_peer(peer_addr).await;
+        self.pending_connects.lock().remove(&transaction_id);
+        Some(result)
+    }
+
+    async fn connect_peer(&self, peer_addr: SocketAddr) -> Result<u32> {
+        if !self.connected_peers.lock().insert(peer_addr) {
+            return Err(Error::ErrConnectionAlreadyExists);
+        }
+
+        match tokio::time::timeout(CONNECT_TIMEOUT, self.connect_from_relay_addr(peer_addr)).await {
+            Ok(Ok(stream)) => Ok(self.add_connection(stream, peer_addr, true)),
+            Ok(Err(err)) => {
+                log::debug!("failed to connect to {}: {}", peer_addr, err);
+                self.connected_peers.lock().remove(&peer_addr);
+                Err(Error::ErrConnectionTimeoutOrFailure)
+            }
+            Err(_) => {
+                self.connected_peers.lock().remove(&peer_addr);
+                Err(Error::ErrConnectionTimeoutOrFailure)
+            }
+        }
+    }
+
+    async fn connect_from_relay_addr(&self, peer_addr: SocketAddr) -> std::io::Result<TcpStream> {
+        let socket = reusable_tcp_socket(self.local_addr)?;
+        socket.bind(self.local_addr)?;
+        socket.connect(peer_addr).await
+    }
+
+    /// Registers a peer data connection until it is bound by a ConnectionBind request or
+    /// times out, and returns its CONNECTION-ID.
+    pub(crate) fn add_connection(
+        &self,
+        stream: TcpStream,
+        peer_addr: SocketAddr,
+        connected: bool,
+    ) -> u32 {
+        let connection = PeerConnection {
+            stream,
+            peer_addr,
+            username: self.username.clone(),
+            connected_peers: connected.then(|| Arc::clone(&self.connected_peers)),
+            close_notify: self.close_notify.clone(),
+        };
+
+        let id = {
+            let mut connections = self.connections.lock();
+            let id = loop {
+                let id = rand::random::<u32>();
+                if !connections.contains_key(&id) {
+                    break id;
+                }
+            };
+            connections.insert(id, connection);
+            id
+        };
+
+        let connections = Arc::clone(&self.connections);
+        let close_notify = self.close_notify.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = tokio::time::sleep(CONNECTION_BIND_TIMEOUT) => {},
+                _ = close_notify.cancelled() => {},
+            }
+
+            if connections.lock().remove(&id).is_some() {
+                log::debug!("peer data connection {} closed without ConnectionBind", id);
+            }
+        });
+
+        id
+    }
+
+    /// Returns a token that is cancelled once the allocation is closed.
+    pub(crate) fn close_notify(&self) -> CancellationToken {
+        self.close_notify.clone()
+    }
+
+    /// Closes all peer data connections.
+    pub(crate) fn close(&self) {
+        self.close_notify.cancel();
+    }
+}
+
+/// `PeerConnection` is a TCP connection between the relayed transport address of a TCP
+/// allocation and a peer.
+pub(crate) struct PeerConnection {
+    stream: TcpStream,
+    peer_addr: SocketAddr,
+    username: String,
+    connected_peers: Option<Arc<SyncMutex<HashSet<SocketAddr>>>>,
+    close_notify: CancellationToken,
+}
+
+impl PeerConnection {
+    /// Returns the username of the allocation this connection belongs to.
+    pub(crate) fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Relays between the client data connection `client` and the peer until either side
+    /// closes its connection, or the allocation is closed.
+    pub(crate) async fn relay(mut self, mut client: Box<dyn AsyncStream>) {
+        tokio::select! {
+            result = tokio::io::copy_bidirectional(&mut client, &mut self.stream) => match result {
+                Ok((to_peer, to_client)) => log::debug!(
+                    "connection with {} closed after relaying {} bytes to and {} bytes from the peer",
+                    self.peer_addr,
+                    to_peer,
+                    to_client
+                ),
+                Err(err) => log::debug!("connection with {} failed: {}", self.peer_addr, err),
+            },
+            _ = self.close_notify.cancelled() => {},
+        }
+    }
+}
+
+impl Drop for PeerConnection {
+    fn drop(&mut self) {
+        if let Some(connected_peers) = &self.connected_peers {
+            connected_peers.lock().remove(&self.peer_addr);
+        }
+    }
+}
@@ -5,6 +5,7 @@ pub mod binding;
 pub mod periodic_timer;
 pub mod permission;
 pub mod relay_conn;
+pub mod tcp_alloc;
 pub mod transaction;
 
 use std::net::SocketAddr;
@@ -24,6 +25,7 @@ use stun::integrity::*;
 use stun::message::*;
 use stun::textattrs::*;
 use stun::xoraddr::*;
+use tcp_alloc::*;
 use tokio::pin;
 use tokio::select;
 use tokio::sync::{mpsc, Mutex};
@@ -34,12 +36,13 @@ use util::vnet::net::*;
 
 use crate::error::*;
 use crate::proto::chandata::*;
+use crate::proto::connid::*;
 use crate::proto::data::*;
 use crate::proto::lifetime::*;
 use crate::proto::peeraddr::*;
 use crate::proto::relayaddr::*;
 use crate::proto::reqtrans::*;
-use crate::proto::PROTO_UDP;
+use crate::proto::{Protocol, PROTO_TCP, PROTO_UDP};
 
 const DEFAULT_RTO_IN_MS: u16 = 200;
 const MAX_DATA_BUFFER_SIZE: usize = u16::MAX as usize; // message size limit for Chromium
@@ -81,6 +84,7 @@ struct ClientInternal {
     binding_mgr: Arc<Mutex<BindingManager>>,
     rto_in_ms: u16,
     read_ch_tx: Arc<Mutex<Option<mpsc::Sender<InboundData>>>>,
+    conn_attempt_tx: Arc<Mutex<Option<mpsc::Sender<ConnectionAttempt>>>>,
     close_notify: CancellationToken,
 }
 
@@ -214,6 +218,7 @@ impl ClientInternal {
             },
             integrity: MessageIntegrity::new_short_term_integrity(String::new()),
             read_ch_tx: Arc::new(Mutex::new(None)),
+            conn_attempt_tx: Arc::new(Mutex::new(None)),
             close_notify: CancellationToken::new(),
         })
     }
@@ -231,6 +236,7 @@ impl ClientInternal {
         let stun_serv_str = self.stun_serv_addr.clone();
         let tr_map = Arc::clone(&self.tr_map);
         let read_ch_tx = Arc::clone(&self.read_ch_tx);
+        let conn_attempt_tx = Arc::clone(&self.conn_attempt_tx);
         let binding_mgr = Arc::clone(&self.binding_mgr);
         let close_notify = self.close_notify.clone();
 
@@ -266,6 +272,7 @@ impl ClientInternal {
                     },
                     result = ClientInternal::handle_inbound(
                         &read_ch_tx,
+                        &conn_attempt_tx,
                         &buf[..n],
                         from,
                         &stun_serv_str,
@@ -293,6 +300,7 @@ impl ClientInternal {
     /// If an error is returned, the caller should discard the packet regardless.
     async fn handle_inbound(
         read_ch_tx: &Arc<Mutex<Option<mpsc::Sender<InboundData>>>>,
+        conn_attempt_tx: &Arc<Mutex<Option<mpsc::Sender<ConnectionAttempt>>>>,
         data: &[u8],
         from: SocketAddr,
         stun_serv_str: &str,
@@ -318,7 +326,8 @@ impl ClientInternal {
         //  - Non-STUN message from the STUN server
 
         if is_message(data) {
-            ClientInternal::handle_stun_message(tr_map, read_ch_tx, data, from).await
+            ClientInternal::handle_stun_message(tr_map, read_ch_tx, conn_attempt_tx, data, from)
+                .await
         } else if ChannelData::is_channel_data(data) {
             ClientInternal::handle_channel_data(binding_mgr, read_ch_tx, data).await
         } else if !stun_serv_str.is_empty() && from.to_string() == *stun_serv_str {
@@ -334,6 +343,7 @@ impl ClientInternal {
     async fn handle_stun_message(
         tr_map: &Arc<Mutex<TransactionMap>>,
         read_ch_tx: &Arc<Mutex<Option<mpsc::Sender<InboundData>>>>,
+        conn_attempt_tx: &Arc<Mutex<Option<mpsc::Sender<ConnectionAttempt>>>>,
         data: &[u8],
         mut from: SocketAddr,
     ) -> Result<()> {
@@ -361,6 +371,27 @@ impl ClientInternal {
                 log::debug!("data indication received from {}", from);
 
                 let _ = ClientInternal::handle_inbound_relay_conn(read_ch_tx, &data.0, from).await;
+            } else if msg.typ.method == METHOD_CONNECTION_ATTEMPT {
+                let mut peer_addr = PeerAddress::default();
+                peer_addr.get_from(&msg)?;
+                from = SocketAddr::new(peer_addr.ip, peer_addr.port);
+
+                let mut connection_id = ConnectionId::default();
+                connection_id.get_from(&msg)?;
+
+                log::debug!("connection attempt received from {}", from);
+
+                if let Some(tx) = &*conn_attempt_tx.lock().await {
+                    if tx
+                        .try_send(ConnectionAttempt {
+                            connection_id: connection_id.0,
+                            from,
+                        })
+                        .is_err()
+                    {
+                        log::warn!("connection attempt queue full");
+                    }
+                }
             }
 
             return Ok(());
@@ -458,6 +489,10 @@ impl ClientInternal {
             let mut read_ch_tx = self.read_ch_tx.lock().await;
             read_ch_tx.take();
         }
+        {
+            let mut conn_attempt_tx = self.conn_attempt_tx.lock().await;
+            conn_attempt_tx.take();
+        }
         {
             let mut tm = self.tr_map.lock().await;
             tm.close_and_delete_all();
@@ -511,8 +546,9 @@ impl ClientInternal {
         bm.find_by_number(ch_num).map(|b| b.addr)
     }
 
-    /// Sends a TURN allocation request to the given transport address.
-    async fn allocate(&mut self) -> Result<RelayConnConfig> {
+    /// Sends a TURN allocation request for a relayed transport address of `protocol` to the
+    /// given transport address.
+    async fn allocate(&mut self, protocol: Protocol) -> Result<RelayConnConfig> {
         {
             let read_ch_tx = self.read_ch_tx.lock().await;
             log::debug!("allocate check: read_ch_tx_opt = {}", read_ch_tx.is_some());
@@ -525,9 +561,7 @@ impl ClientInternal {
         msg.build(&[
             Box::new(TransactionId::new()),
             Box::new(MessageType::new(METHOD_ALLOCATE, CLASS_REQUEST)),
-            Box::new(RequestedTransport {
-                protocol: PROTO_UDP,
-            }),
+            Box::new(RequestedTransport { protocol }),
             Box::new(FINGERPRINT),
         ])?;
 
@@ -551,9 +585,7 @@ impl ClientInternal {
         msg.build(&[
             Box::new(TransactionId::new()),
             Box::new(MessageType::new(METHOD_ALLOCATE, CLASS_REQUEST)),
-            Box::new(RequestedTransport {
-                protocol: PROTO_UDP,
-            }),
+            Box::new(RequestedTransport { protocol }),
             Box::new(self.username.clone()),
             Box::new(self.realm.clone()),
             Box::new(nonce.clone()),
@@ -626,12 +658,33 @@ impl Client {
     pub async fn allocate(&self) -> Result<impl Conn> {
         let config = {
             let mut ci = self.client_internal.lock().await;
-            ci.allocate().await?
+            ci.allocate(PROTO_UDP).await?
         };
 
         Ok(RelayConn::new(Arc::clone(&self.client_internal), config).await)
     }
 
+    /// Allocates a TCP relayed transport address, see [RFC 6062].
+    ///
+    /// The client must be connected to the server over TCP or TLS, e.g. by using a
+    /// [`StreamConn`](crate::stream::StreamConn) as the `conn` of the [`ClientConfig`].
+    ///
+    /// [RFC 6062]: https://www.rfc-editor.org/rfc/rfc6062
+    pub async fn allocate_tcp(&self) -> Result<TcpAllocation> {
+        let (config, conn_attempt_rx) = {
+            let mut ci = self.client_internal.lock().await;
+            let config = ci.allocate(PROTO_TCP).await?;
+
+            let (conn_attempt_tx, conn_attempt_rx) = mpsc::channel(MAX_READ_QUEUE_SIZE);
+            let mut conn_attempt_tx_opt = ci.conn_attempt_tx.lock().await;
+            *conn_attempt_tx_opt = Some(conn_attempt_tx);
+
+            (config, conn_attempt_rx)
+        };
+
+        Ok(TcpAllocation::new(Arc::clone(&self.client_internal), config, conn_attempt_rx).await)
+    }
+
     pub async fn close(&self) -> Result<()> {
         let mut ci = self.client_internal.lock().await;
         ci.close().await;
@@ -24,8 +24,8 @@ use super::permission::*;
 use super::transaction::*;
 use crate::{proto, Error};
 
-const PERM_REFRESH_INTERVAL: Duration = Duration::from_secs(120);
-const MAX_RETRY_ATTEMPTS: u16 = 3;
+pub(crate) const PERM_REFRESH_INTERVAL: Duration = Duration::from_secs(120);
+pub(crate) const MAX_RETRY_ATTEMPTS: u16 = 3;
 
 pub(crate) struct InboundData {
     pub(crate) data: Vec<u8>,
@@ -190,7 +190,7 @@ impl<T: RelayConnObserver + Send + Sync> Conn for RelayConn<T> {
 
 impl<T: RelayConnObserver + Send + Sync> RelayConnInternal<T> {
     /// Creates a new [`RelayConnInternal`].
-    fn new(obs: Arc<Mutex<T>>, config: RelayConnConfig) -> Self {
+    pub(crate) fn new(obs: Arc<Mutex<T>>, config: RelayConnConfig) -> Self {
         RelayConnInternal {
             obs,
             relayed_addr: config.relayed_addr,
@@ -208,25 +208,7 @@ impl<T: RelayConnObserver + Send + Sync> RelayConnInternal<T> {
     /// see SetDeadline and SetWriteDeadline.
     /// On packet-oriented connections, write timeouts are rare.
     async fn send_to(&mut self, p: &[u8], addr: SocketAddr) -> Result<usize, Error> {
-        // check if we have a permission for the destination IP addr
-        let perm = if let Some(perm) = self.perm_map.find(&addr) {
-            Arc::clone(perm)
-        } else {
-            let perm = Arc::new(Permission::default());
-            self.perm_map.insert(&addr, Arc::clone(&perm));
-            perm
-        };
-
-        let mut result = Ok(());
-        for _ in 0..MAX_RETRY_ATTEMPTS {
-            result = self.create_perm(&perm, addr).await;
-            if let Err(err) = &result {
-                if Error::ErrTryAgain != *err {
-                    break;
-                }
-            }
-        }
-        result?;
+        self.ensure_permission(addr).await?;
 
         let number = {
             let (bind_st, bind_at, bind_number, bind_addr) = {
@@ -354,6 +336,109 @@ impl<T: RelayConnObserver + Send + Sync> RelayConnInternal<T> {
         self.send_channel_data(p, number).await
     }
 
+    /// Creates a permission for `addr` unless one already exists.
+    pub(crate) async fn ensure_permission(&mut self, addr: SocketAddr) -> Result<(), Error> {
+        // check if we have a permission for the destination IP addr
+        let perm = if let Some(perm) = self.perm_map.find(&addr) {
+            Arc::clone(perm)
+        } else {
+            let perm = Arc::new(Permission::default());
+            self.perm_map.insert(&addr, Arc::clone(&perm));
+            perm
+        };
+
+        let mut result = Ok(());
+        for _ in 0..MAX_RETRY_ATTEMPTS {
+            result = self.create_perm(&perm, addr).await;
+            if let Err(err) = &result {
+                if Error::ErrTryAgain != *err {
+                    break;
+                }
+            }
+        }
+        result
+    }
+
+    /// Asks the server to open a TCP connection to `peer_addr` and returns the
+    /// CONNECTION-ID of the new connection. [RFC 6062 Section 4.3]
+    pub(crate) async fn connect(&mut self, peer_addr: SocketAddr) -> Result<u32, Error> {
+        self.ensure_permission(peer_addr).await?;
+
+        let mut result = Err(Error::ErrTryAgain);
+        for _ in 0..MAX_RETRY_ATTEMPTS {
+            result = self.send_connect_request(peer_addr).await;
+            if !matches!(result, Err(Error::ErrTryAgain)) {
+                break;
+            }
+        }
+        result
+    }
+
+    async fn send_connect_request(&mut self, peer_addr: SocketAddr) -> Result<u32, Error> {
+        let res = {
+            let mut obs = self.obs.lock().await;
+
+            let mut msg = Message::new();
+            msg.build(&[
+                Box::new(TransactionId::new()),
+                Box::new(MessageType::new(METHOD_CONNECT, CLASS_REQUEST)),
+                Box::new(socket_addr2peer_address(&peer_addr)),
+                Box::new(obs.username()),
+                Box::new(obs.realm()),
+                Box::new(self.nonce.clone()),
+                Box::new(self.integrity.clone()),
+                Box::new(FINGERPRINT),
+            ])?;
+
+            log::debug!("TcpAllocation.connect call PerformTransaction 1");
+            let turn_server_addr = obs.turn_server_addr();
+            let tr_res = obs
+                .perform_transaction(&msg, &turn_server_addr, false)
+                .await?;
+
+            tr_res.msg
+        };
+
+        if res.typ.class == CLASS_ERROR_RESPONSE {
+            let mut code = ErrorCodeAttribute::default();
+            let result = code.get_from(&res);
+            if result.is_err() {
+                return Err(Error::Other(format!("{}", res.typ)));
+            } else if code.code == CODE_STALE_NONCE {
+                self.set_nonce_from_msg(&res);
+                return Err(Error::ErrTryAgain);
+            } else {
+                return Err(Error::Other(format!("{} (error {})", res.typ, code)));
+            }
+        }
+
+        let mut connection_id = proto::connid::ConnectionId::default();
+        connection_id.get_from(&res)?;
+        Ok(connection_id.0)
+    }
+
+    /// Builds a ConnectionBind request for the connection `connection_id`, which is sent on
+    /// the client data connection. [RFC 6062 Section 4.3]
+    pub(crate) async fn connection_bind_request(
+        &self,
+        connection_id: u32,
+    ) -> Result<Message, Error> {
+        let obs = self.obs.lock().await;
+
+        let mut msg = Message::new();
+        msg.build(&[
+            Box::new(TransactionId::new()),
+            Box::new(MessageType::new(METHOD_CONNECTION_BIND, CLASS_REQUEST)),
+            Box::new(proto::connid::ConnectionId(connection_id)),
+            Box::new(obs.username()),
+            Box::new(obs.realm()),
+            Box::new(self.nonce.clone()),
+            Box::new(self.integrity.clone()),
+            Box::new(FINGERPRINT),
+        ])?;
+        Ok(msg)
+    }
+
     /// This func-block would block, per destination IP (, or perm), until
     /// the perm state becomes "requested". Purpose of this is to guarantee
     /// the order of packets (within the same perm).
@@ -0,0 +1,151 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use stun::error_code::*;
+use stun::message::*;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio::sync::{mpsc, Mutex};
+
+use super::periodic_timer::*;
+use super::relay_conn::*;
+use super::ClientInternal;
+use crate::error::*;
+use crate::stream::{read_frame, MAX_FRAME_SIZE};
+
+/// A connection attempt of a peer to the relayed transport address of a [`TcpAllocation`].
+pub(crate) struct ConnectionAttempt {
+    pub(crate) connection_id: u32,
+    pub(crate) from: SocketAddr,
+}
+
+/// `TcpAllocation` is a TCP relayed transport address allocated on the TURN server,
+/// see [RFC 6062].
+///
+/// Every connection with a peer is identified by a CONNECTION-ID, either returned by
+/// [`TcpAllocation::connect`] or [`TcpAllocation::accept`]. The data of a connection is
+/// exchanged over a separate TCP or TLS connection to the server, the client data
+/// connection, after binding it to the CONNECTION-ID with [`TcpAllocation::connection_bind`].
+///
+/// [RFC 6062]: https://www.rfc-editor.org/rfc/rfc6062
+pub struct TcpAllocation {
+    relayed_addr: SocketAddr,
+    conn_attempt_rx: Mutex<mpsc::Receiver<ConnectionAttempt>>,
+    relay_conn: Arc<Mutex<RelayConnInternal<ClientInternal>>>,
+    refresh_alloc_timer: PeriodicTimer,
+    refresh_perms_timer: PeriodicTimer,
+}
+
+impl TcpAllocation {
+    /// Creates a new [`TcpAllocation`].
+    pub(super) async fn new(
+        obs: Arc<Mutex<ClientInternal>>,
+        config: RelayConnConfig,
+        conn_attempt_rx: mpsc::Receiver<ConnectionAttempt>,
+    ) -> Self {
+        log::debug!("initial lifetime: {} seconds", config.lifetime.as_secs());
+
+        let a = TcpAllocation {
+            refresh_alloc_timer: PeriodicTimer::new(TimerIdRefresh::Alloc, config.lifetime / 2),
+            refresh_perms_timer: PeriodicTimer::new(TimerIdRefresh::Perms, PERM_REFRESH_INTERVAL),
+            relayed_addr: config.relayed_addr,
+            conn_attempt_rx: Mutex::new(conn_attempt_rx),
+            relay_conn: Arc::new(Mutex::new(RelayConnInternal::new(obs, config))),
+        };
+
+        if a.refresh_alloc_timer.start(Arc::clone(&a.relay_conn)).await {
+            log::debug!("refresh_alloc_timer started");
+        }
+        if a.refresh_perms_timer.start(Arc::clone(&a.relay_conn)).await {
+            log::debug!("refresh_perms_timer started");
+        }
+
+        a
+    }
+
+    /// Returns the relayed transport address.
+    pub fn relayed_addr(&self) -> SocketAddr {
+        self.relayed_addr
+    }
+
+    /// Installs permissions for `addrs`, which allows the peers to connect to the
+    /// relayed transport address.
+    pub async fn create_permissions(&self, addrs: &[SocketAddr]) -> Result<()> {
+        let mut relay_conn = self.relay_conn.lock().await;
+        for addr in addrs {
+            relay_conn.ensure_permission(*addr).await?;
+        }
+        Ok(())
+    }
+
+    /// Asks the server to connect to `peer_addr` and returns the CONNECTION-ID of the
+    /// new connection.
+    pub async fn connect(&self, peer_addr: SocketAddr) -> Result<u32> {
+        let mut relay_conn = self.relay_conn.lock().await;
+        relay_conn.connect(peer_addr).await
+    }
+
+    /// Waits for a peer to connect to the relayed transport address and returns the
+    /// CONNECTION-ID of the connection together with the address of the peer.
+    pub async fn accept(&self) -> Result<(u32, SocketAddr)> {
+        let mut conn_attempt_rx = self.conn_attempt_rx.lock().await;
+        match conn_attempt_rx.recv().await {
+            Some(attempt) => Ok((attempt.connection_id, attempt.from)),
+            None => Err(Error::ErrAlreadyClosed),
+        }
+    }
+
+    /// Binds the new client data connection `stream` to the peer connection with the
+    /// CONNECTION-ID `connection_id`.
+    ///
+    /// Once bound, `stream` carries the data exchanged with the peer.
+    pub async fn connection_bind<S>(&self, stream: &mut S, connection_id: u32) -> Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let mut buf = vec![0u8; MAX_FRAME_SIZE];
+        for _ in 0..MAX_RETRY_ATTEMPTS {
+            let msg = {
+                let relay_conn = self.relay_conn.lock().await;
+                relay_conn.connection_bind_request(connection_id).await?
+            };
+            stream.write_all(&msg.raw).await?;
+
+            // Only the response is read, anything following it belongs to the peer.
+            let n = read_frame(stream, &mut buf).await?;
+            let mut res = Message::new();
+            res.raw = buf[..n].to_vec();
+            res.decode()?;
+
+            if res.transaction_id != msg.transaction_id {
+                return Err(Error::ErrUnexpectedResponse);
+            }
+
+            if res.typ.class == CLASS_ERROR_RESPONSE {
+                let mut code = ErrorCodeAttribute::default();
+                let result = code.get_from(&res);
+                if result.is_err() {
+                    return Err(Error::Other(format!("{}", res.typ)));
+                } else if code.code == CODE_STALE_NONCE {
+                    let mut relay_conn = self.relay_conn.lock().await;
+                    relay_conn.set_nonce_from_msg(&res);
+                    continue;
+                } else {
+                    return Err(Error::Other(format!("{} (error {})", res.typ, code)));
+                }
+            }
+
+            return Ok(());
+        }
+
+        Err(Error::ErrTryAgain)
+    }
+
+    /// Closes the allocation together with all its connections.
+    pub async fn close(&self) -> Result<()> {
+        self.refresh_alloc_timer.stop().await;
+        self.refresh_perms_timer.stop().await;
+
+        let mut relay_conn = self.relay_conn.lock().await;
+        relay_conn.close().await
+    }
+}
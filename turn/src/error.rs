@@ -137,8 +137,20 @@ pub enum Error {
     ErrFailedToCreateChannelData,
     #[error("relay already allocated for 5-TUPLE")]
     ErrRelayAlreadyAllocatedForFiveTuple,
-    #[error("RequestedTransport must be UDP")]
+    #[error("RequestedTransport must be UDP or TCP")]
     ErrRequestedTransportMustBeUdp,
+    #[error("TCP allocations must be requested over TCP or TLS")]
+    ErrTcpTransportRequired,
+    #[error("request does not apply to the relayed transport protocol of the allocation")]
+    ErrAllocationProtocolMismatch,
+    #[error("RelayAddressGenerator does not support TCP relays")]
+    ErrTcpRelayUnsupported,
+    #[error("error code 446: connection already exists")]
+    ErrConnectionAlreadyExists,
+    #[error("error code 447: connection timeout or failure")]
+    ErrConnectionTimeoutOrFailure,
+    #[error("no pending connection with the CONNECTION-ID")]
+    ErrNoSuchConnection,
     #[error("no support for DONT-FRAGMENT")]
     ErrNoDontFragmentSupport,
     #[error("Request must not contain RESERVATION-TOKEN and EVEN-PORT")]
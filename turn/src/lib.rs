@@ -9,5 +9,6 @@ mod error;
 pub mod proto;
 pub mod relay;
 pub mod server;
+pub mod stream;
 
 pub use error::Error;
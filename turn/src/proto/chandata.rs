@@ -6,7 +6,7 @@ use crate::error::*;
 
 const PADDING: usize = 4;
 
-fn nearest_padded_value_length(l: usize) -> usize {
+pub(crate) fn nearest_padded_value_length(l: usize) -> usize {
     let mut n = PADDING * (l / PADDING);
     if n < l {
         n += PADDING;
@@ -0,0 +1,46 @@
+use super::*;
+
+#[test]
+fn test_connection_id_add_to() -> Result<(), stun::Error> {
+    let mut m = Message::new();
+    let c = ConnectionId(0x1234_5678);
+    c.add_to(&mut m)?;
+    m.write_header();
+
+    //"GetFrom"
+    {
+        let mut decoded = Message::new();
+        decoded.write(&m.raw)?;
+
+        let mut id = ConnectionId::default();
+        id.get_from(&decoded)?;
+        assert_eq!(id, c, "Decoded {id}, expected {c}");
+
+        //"HandleErr"
+        {
+            let mut m = Message::new();
+            let mut n_handle = ConnectionId::default();
+            if let Err(err) = n_handle.get_from(&m) {
+                assert_eq!(
+                    stun::Error::ErrAttributeNotFound,
+                    err,
+                    "{err} should be not found"
+                );
+            } else {
+                panic!("expected error, but got ok");
+            }
+            m.add(ATTR_CONNECTION_ID, &[1, 2, 3]);
+
+            if let Err(err) = n_handle.get_from(&m) {
+                assert!(
+                    is_attr_size_invalid(&err),
+                    "IsAttrSizeInvalid should be true"
+                );
+            } else {
+                panic!("expected error, but got ok");
+            }
+        }
+    }
+
+    Ok(())
+}
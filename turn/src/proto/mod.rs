@@ -4,6 +4,7 @@ mod proto_test;
 pub mod addr;
 pub mod chandata;
 pub mod channum;
+pub mod connid;
 pub mod data;
 pub mod dontfrag;
 pub mod evenport;
@@ -18,7 +19,8 @@ use std::fmt;
 
 use stun::message::*;
 
-// proto implements RFC 5766 Traversal Using Relays around NAT.
+// proto implements RFC 5766 Traversal Using Relays around NAT, and the TCP
+// allocations of RFC 6062.
 
 /// `Protocol` is IANA assigned protocol number.
 #[derive(PartialEq, Eq, Default, Debug, Clone, Copy, Hash)]
@@ -68,3 +70,18 @@ pub fn send_indication() -> MessageType {
 pub fn refresh_request() -> MessageType {
     MessageType::new(METHOD_REFRESH, CLASS_REQUEST)
 }
+
+/// Shorthand for connect request message type.
+pub fn connect_request() -> MessageType {
+    MessageType::new(METHOD_CONNECT, CLASS_REQUEST)
+}
+
+/// Shorthand for connection bind request message type.
+pub fn connection_bind_request() -> MessageType {
+    MessageType::new(METHOD_CONNECTION_BIND, CLASS_REQUEST)
+}
+
+/// Shorthand for connection attempt indication message type.
+pub fn connection_attempt_indication() -> MessageType {
+    MessageType::new(METHOD_CONNECTION_ATTEMPT, CLASS_INDICATION)
+}
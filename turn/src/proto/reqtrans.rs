@@ -13,7 +13,8 @@ use super::*;
 ///
 /// This attribute is used by the client to request a specific transport
 /// protocol for the allocated transport address. RFC 5766 only allows the use of
-/// codepoint 17 (User Datagram protocol).
+/// codepoint 17 (User Datagram protocol), RFC 6062 adds codepoint 6 (Transmission
+/// Control Protocol).
 ///
 /// [RFC 5766 Section 14.7](https://www.rfc-editor.org/rfc/rfc5766#section-14.7).
 #[derive(Default, Debug, PartialEq, Eq)]
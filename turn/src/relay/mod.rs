@@ -2,13 +2,15 @@ pub mod relay_none;
 pub mod relay_range;
 pub mod relay_static;
 
+use std::io;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use tokio::net::{TcpListener, TcpSocket};
 use util::Conn;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 
 /// `RelayAddressGenerator` is used to generate a Relay Address when creating an allocation.
 /// You can use one of the provided ones or provide your own.
@@ -23,4 +25,36 @@ pub trait RelayAddressGenerator {
         use_ipv4: bool,
         requested_port: u16,
     ) -> Result<(Arc<dyn Conn + Send + Sync>, SocketAddr)>;
+
+    /// Allocates a Relay Address for a TCP allocation (RFC 6062)
+    async fn allocate_listener(
+        &self,
+        _use_ipv4: bool,
+        _requested_port: u16,
+    ) -> Result<(TcpListener, SocketAddr)> {
+        Err(Error::ErrTcpRelayUnsupported)
+    }
+}
+
+/// Binds the listener of a TCP allocation to `addr`.
+///
+/// The address stays available for the connections to the peers, which originate from the
+/// relayed transport address. [RFC 6062 Section 5.2]
+pub fn bind_tcp_listener(addr: SocketAddr) -> Result<TcpListener> {
+    let socket = reusable_tcp_socket(addr)?;
+    socket.bind(addr)?;
+    Ok(socket.listen(1024)?)
+}
+
+/// Creates a TCP socket which can be bound to the address of a TCP allocation's listener.
+pub(crate) fn reusable_tcp_socket(addr: SocketAddr) -> io::Result<TcpSocket> {
+    let socket = if addr.is_ipv4() {
+        TcpSocket::new_v4()?
+    } else {
+        TcpSocket::new_v6()?
+    };
+    socket.set_reuseaddr(true)?;
+    #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+    socket.set_reuseport(true)?;
+    Ok(socket)
 }
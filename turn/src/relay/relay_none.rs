@@ -34,4 +34,23 @@ impl RelayAddressGenerator for RelayAddressGeneratorNone {
         let relay_addr = conn.local_addr()?;
         Ok((conn, relay_addr))
     }
+
+    async fn allocate_listener(
+        &self,
+        use_ipv4: bool,
+        requested_port: u16,
+    ) -> Result<(TcpListener, SocketAddr)> {
+        // Net doesn't emulate TCP
+        if self.net.is_virtual() {
+            return Err(Error::ErrTcpRelayUnsupported);
+        }
+
+        let addr = self
+            .net
+            .resolve_addr(use_ipv4, &format!("{}:{}", self.address, requested_port))
+            .await?;
+        let listener = bind_tcp_listener(addr)?;
+        let relay_addr = listener.local_addr()?;
+        Ok((listener, relay_addr))
+    }
 }
@@ -82,4 +82,50 @@ impl RelayAddressGenerator for RelayAddressGeneratorRanges {
 
         Err(Error::ErrMaxRetriesExceeded)
     }
+
+    async fn allocate_listener(
+        &self,
+        use_ipv4: bool,
+        requested_port: u16,
+    ) -> Result<(TcpListener, SocketAddr)> {
+        // Net doesn't emulate TCP
+        if self.net.is_virtual() {
+            return Err(Error::ErrTcpRelayUnsupported);
+        }
+
+        let max_retries = if self.max_retries == 0 {
+            10
+        } else {
+            self.max_retries
+        };
+
+        if requested_port != 0 {
+            let addr = self
+                .net
+                .resolve_addr(use_ipv4, &format!("{}:{}", self.address, requested_port))
+                .await?;
+            let listener = bind_tcp_listener(addr)?;
+            let mut relay_addr = listener.local_addr()?;
+            relay_addr.set_ip(self.relay_address);
+            return Ok((listener, relay_addr));
+        }
+
+        for _ in 0..max_retries {
+            let port = self.min_port + rand::random::<u16>() % (self.max_port - self.min_port + 1);
+            let addr = self
+                .net
+                .resolve_addr(use_ipv4, &format!("{}:{}", self.address, port))
+                .await?;
+            let listener = match bind_tcp_listener(addr) {
+                Ok(listener) => listener,
+                Err(_) => continue,
+            };
+
+            let mut relay_addr = listener.local_addr()?;
+            relay_addr.set_ip(self.relay_address);
+            return Ok((listener, relay_addr));
+        }
+
+        Err(Error::ErrMaxRetriesExceeded)
+    }
 }
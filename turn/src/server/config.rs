@@ -8,6 +8,7 @@ use crate::allocation::*;
 use crate::auth::*;
 use crate::error::*;
 use crate::relay::*;
+use crate::stream::StreamListener;
 
 /// ConnConfig is used for UDP listeners
 pub struct ConnConfig {
@@ -24,12 +25,30 @@ impl ConnConfig {
     }
 }
 
+/// ListenerConfig is used for TCP and TLS listeners
+pub struct ListenerConfig {
+    pub listener: Arc<dyn StreamListener + Send + Sync>,
+
+    // When an allocation is generated the RelayAddressGenerator
+    // creates the relay and returns the IP/Port it is available at
+    pub relay_addr_generator: Box<dyn RelayAddressGenerator + Send + Sync>,
+}
+
+impl ListenerConfig {
+    pub fn validate(&self) -> Result<()> {
+        self.relay_addr_generator.validate()
+    }
+}
+
 /// ServerConfig configures the TURN Server
 pub struct ServerConfig {
     /// `conn_configs` are a list of all the turn listeners.
     /// Each listener can have custom behavior around the creation of Relays.
     pub conn_configs: Vec<ConnConfig>,
 
+    /// `listener_configs` are a list of all the TCP and TLS turn listeners.
+    pub listener_configs: Vec<ListenerConfig>,
+
     /// `realm` sets the realm for this server
     pub realm: String,
 
@@ -46,13 +65,16 @@ pub struct ServerConfig {
 
 impl ServerConfig {
     pub fn validate(&self) -> Result<()> {
-        if self.conn_configs.is_empty() {
+        if self.conn_configs.is_empty() && self.listener_configs.is_empty() {
             return Err(Error::ErrNoAvailableConns);
         }
 
         for cc in &self.conn_configs {
             cc.validate()?;
         }
+        for lc in &self.listener_configs {
+            lc.validate()?;
+        }
         Ok(())
     }
 }
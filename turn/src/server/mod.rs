@@ -13,6 +13,7 @@ use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::broadcast::{self};
 use tokio::sync::{mpsc, oneshot, Mutex};
 use tokio::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 use util::Conn;
 
 use crate::allocation::allocation_manager::*;
@@ -21,9 +22,16 @@ use crate::allocation::AllocationInfo;
 use crate::auth::AuthHandler;
 use crate::error::*;
 use crate::proto::lifetime::DEFAULT_LIFETIME;
+use crate::proto::PROTO_TCP;
+use crate::stream::{StreamConn, StreamListener, MAX_FRAME_SIZE};
 
 const INBOUND_MTU: usize = 1500;
 
+/// Bounds of the delay before accepting again after a failed accept, e.g. when the
+/// process ran out of file descriptors. It doubles on each consecutive failure.
+const MIN_ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(5);
+const MAX_ACCEPT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
 /// Server is an instance of the TURN Server
 pub struct Server {
     auth_handler: Arc<dyn AuthHandler + Send + Sync>,
@@ -74,6 +82,29 @@ impl Server {
             ));
         }
 
+        for p in config.listener_configs.into_iter() {
+            let nonces = Arc::clone(&s.nonces);
+            let auth_handler = Arc::clone(&s.auth_handler);
+            let realm = s.realm.clone();
+            let channel_bind_timeout = s.channel_bind_timeout;
+            let handle_rx = command_tx.subscribe();
+            let listener = p.listener;
+            let allocation_manager = Arc::new(Manager::new(ManagerConfig {
+                relay_addr_generator: p.relay_addr_generator,
+                alloc_close_notify: config.alloc_close_notify.clone(),
+            }));
+
+            tokio::spawn(Server::accept_loop(
+                listener,
+                allocation_manager,
+                nonces,
+                auth_handler,
+                realm,
+                channel_bind_timeout,
+                handle_rx,
+            ));
+        }
+
         Ok(s)
     }
 
@@ -147,42 +178,183 @@ impl Server {
         auth_handler: Arc<dyn AuthHandler + Send + Sync>,
         realm: String,
         channel_bind_timeout: Duration,
-        mut handle_rx: broadcast::Receiver<Command>,
+        handle_rx: broadcast::Receiver<Command>,
     ) {
         let mut buf = vec![0u8; INBOUND_MTU];
 
-        let (mut close_tx, mut close_rx) = oneshot::channel::<()>();
+        let (mut close_tx, close_rx) = oneshot::channel::<()>();
 
-        tokio::spawn({
-            let allocation_manager = Arc::clone(&allocation_manager);
+        tokio::spawn(Server::command_loop(
+            Arc::clone(&allocation_manager),
+            handle_rx,
+            close_rx,
+        ));
 
-            async move {
-                loop {
-                    match handle_rx.recv().await {
-                        Ok(Command::DeleteAllocations(name, _)) => {
-                            allocation_manager
-                                .delete_allocations_by_username(name.as_str())
-                                .await;
-                            continue;
+        loop {
+            let (n, addr) = tokio::select! {
+                v = conn.recv_from(&mut buf) => {
+                    match v {
+                        Ok(v) => v,
+                        Err(err) => {
+                            log::debug!("exit read loop on error: {}", err);
+                            break;
                         }
-                        Ok(Command::GetAllocationsInfo(five_tuples, tx)) => {
-                            let infos = allocation_manager.get_allocations_info(five_tuples).await;
-                            let _ = tx.send(infos).await;
+                    }
+                },
+                _ = close_tx.closed() => break
+            };
 
-                            continue;
-                        }
-                        Err(RecvError::Closed) | Ok(Command::Close(_)) => {
-                            close_rx.close();
-                            break;
+            let mut r = Request {
+                conn: Arc::clone(&conn),
+                src_addr: addr,
+                buff: buf[..n].to_vec(),
+                allocation_manager: Arc::clone(&allocation_manager),
+                nonces: Arc::clone(&nonces),
+                auth_handler: Arc::clone(&auth_handler),
+                realm: realm.clone(),
+                channel_bind_timeout,
+                stream_conn: None,
+            };
+
+            if let Err(err) = r.handle_request().await {
+                log::error!("error when handling datagram: {}", err);
+            }
+        }
+
+        let _ = allocation_manager.close().await;
+        let _ = conn.close().await;
+    }
+
+    /// Handles the [`Command`]s for the listener of `allocation_manager` until the [`Server`]
+    /// is closed, which closes `close_rx`.
+    async fn command_loop(
+        allocation_manager: Arc<Manager>,
+        mut handle_rx: broadcast::Receiver<Command>,
+        mut close_rx: oneshot::Receiver<()>,
+    ) {
+        loop {
+            match handle_rx.recv().await {
+                Ok(Command::DeleteAllocations(name, _)) => {
+                    allocation_manager
+                        .delete_allocations_by_username(name.as_str())
+                        .await;
+                    continue;
+                }
+                Ok(Command::GetAllocationsInfo(five_tuples, tx)) => {
+                    let infos = allocation_manager.get_allocations_info(five_tuples).await;
+                    let _ = tx.send(infos).await;
+
+                    continue;
+                }
+                Err(RecvError::Closed) | Ok(Command::Close(_)) => {
+                    close_rx.close();
+                    break;
+                }
+                Err(RecvError::Lagged(n)) => {
+                    log::warn!("Turn server has lagged by {} messages", n);
+                    continue;
+                }
+            }
+        }
+    }
+
+    async fn accept_loop(
+        listener: Arc<dyn StreamListener + Send + Sync>,
+        allocation_manager: Arc<Manager>,
+        nonces: Arc<Mutex<HashMap<String, Instant>>>,
+        auth_handler: Arc<dyn AuthHandler + Send + Sync>,
+        realm: String,
+        channel_bind_timeout: Duration,
+        handle_rx: broadcast::Receiver<Command>,
+    ) {
+        let (mut close_tx, close_rx) = oneshot::channel::<()>();
+
+        tokio::spawn(Server::command_loop(
+            Arc::clone(&allocation_manager),
+            handle_rx,
+            close_rx,
+        ));
+
+        let close_notify = CancellationToken::new();
+        let mut retry_delay = MIN_ACCEPT_RETRY_DELAY;
+        loop {
+            let (stream, remote_addr) = tokio::select! {
+                v = listener.accept() => {
+                    match v {
+                        Ok(v) => {
+                            retry_delay = MIN_ACCEPT_RETRY_DELAY;
+                            v
                         }
-                        Err(RecvError::Lagged(n)) => {
-                            log::warn!("Turn server has lagged by {} messages", n);
-                            continue;
+                        Err(err) => {
+                            log::warn!("failed to accept connection, retrying in {:?}: {}", retry_delay, err);
+                            let delay = retry_delay;
+                            retry_delay = (retry_delay * 2).min(MAX_ACCEPT_RETRY_DELAY);
+                            tokio::select! {
+                                _ = tokio::time::sleep(delay) => continue,
+                                _ = close_tx.closed() => break
+                            }
                         }
                     }
+                },
+                _ = close_tx.closed() => break
+            };
+
+            let local_addr = match listener.local_addr() {
+                Ok(local_addr) => local_addr,
+                Err(err) => {
+                    log::debug!("exit accept loop on error: {}", err);
+                    break;
                 }
-            }
-        });
+            };
+
+            let listener = Arc::clone(&listener);
+            let allocation_manager = Arc::clone(&allocation_manager);
+            let nonces = Arc::clone(&nonces);
+            let auth_handler = Arc::clone(&auth_handler);
+            let realm = realm.clone();
+            let close_notify = close_notify.child_token();
+            tokio::spawn(async move {
+                // A slow or failed handshake, e.g. of TLS, doesn't affect the other connections.
+                let stream = tokio::select! {
+                    v = listener.handshake(stream) => {
+                        match v {
+                            Ok(stream) => stream,
+                            Err(err) => {
+                                log::debug!("failed handshake with {}: {}", remote_addr, err);
+                                return;
+                            }
+                        }
+                    },
+                    _ = close_notify.cancelled() => return
+                };
+
+                Server::stream_read_loop(
+                    Arc::new(StreamConn::new(stream, local_addr, remote_addr)),
+                    allocation_manager,
+                    nonces,
+                    auth_handler,
+                    realm,
+                    channel_bind_timeout,
+                    close_notify,
+                )
+                .await;
+            });
+        }
+
+        close_notify.cancel();
+        let _ = allocation_manager.close().await;
+    }
+
+    async fn stream_read_loop(
+        conn: Arc<StreamConn>,
+        allocation_manager: Arc<Manager>,
+        nonces: Arc<Mutex<HashMap<String, Instant>>>,
+        auth_handler: Arc<dyn AuthHandler + Send + Sync>,
+        realm: String,
+        channel_bind_timeout: Duration,
+        close_notify: CancellationToken,
+    ) {
+        let mut buf = vec![0u8; MAX_FRAME_SIZE];
 
         loop {
             let (n, addr) = tokio::select! {
@@ -190,16 +362,16 @@ impl Server {
                     match v {
                         Ok(v) => v,
                         Err(err) => {
-                            log::debug!("exit read loop on error: {}", err);
+                            log::debug!("exit stream read loop on error: {}", err);
                             break;
                         }
                     }
                 },
-                _ = close_tx.closed() => break
+                _ = close_notify.cancelled() => break
             };
 
             let mut r = Request {
-                conn: Arc::clone(&conn),
+                conn: Arc::clone(&conn) as Arc<dyn Conn + Send + Sync>,
                 src_addr: addr,
                 buff: buf[..n].to_vec(),
                 allocation_manager: Arc::clone(&allocation_manager),
@@ -207,14 +379,25 @@ impl Server {
                 auth_handler: Arc::clone(&auth_handler),
                 realm: realm.clone(),
                 channel_bind_timeout,
+                stream_conn: Some(Arc::clone(&conn)),
             };
 
             if let Err(err) = r.handle_request().await {
-                log::error!("error when handling datagram: {}", err);
+                log::error!("error when handling message: {}", err);
             }
         }
 
-        let _ = allocation_manager.close().await;
+        // The allocation of a control connection is deleted once the connection
+        // is closed. [RFC 6062 Section 5.1]
+        if let (Ok(local_addr), Some(remote_addr)) = (conn.local_addr(), conn.remote_addr()) {
+            allocation_manager
+                .delete_allocation(&FiveTuple {
+                    protocol: PROTO_TCP,
+                    src_addr: remote_addr,
+                    dst_addr: local_addr,
+                })
+                .await;
+        }
         let _ = conn.close().await;
     }
 
@@ -240,7 +423,7 @@ impl Server {
 }
 
 /// The protocol to communicate between the [`Server`]'s public methods
-/// and the tasks spawned in the [`Server::read_loop`] method.
+/// and the tasks spawned in the [`Server::read_loop`] and [`Server::accept_loop`] methods.
 #[derive(Clone)]
 enum Command {
     /// Command to delete [`Allocation`][`Allocation`] by provided `username`.
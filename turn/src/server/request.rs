@@ -31,6 +31,7 @@ use crate::auth::*;
 use crate::error::*;
 use crate::proto::chandata::ChannelData;
 use crate::proto::channum::ChannelNumber;
+use crate::proto::connid::ConnectionId;
 use crate::proto::data::Data;
 use crate::proto::evenport::EvenPort;
 use crate::proto::lifetime::*;
@@ -42,6 +43,7 @@ use crate::proto::reqfamily::{
 use crate::proto::reqtrans::RequestedTransport;
 use crate::proto::rsrvtoken::ReservationToken;
 use crate::proto::*;
+use crate::stream::StreamConn;
 
 pub(crate) const MAXIMUM_ALLOCATION_LIFETIME: Duration = Duration::from_secs(3600); // https://tools.ietf.org/html/rfc5766#section-6.2 defines 3600 seconds recommendation
 pub(crate) const NONCE_LIFETIME: Duration = Duration::from_secs(3600); // https://tools.ietf.org/html/rfc5766#section-4
@@ -52,6 +54,8 @@ pub struct Request {
     pub conn: Arc<dyn Conn + Send + Sync>,
     pub src_addr: SocketAddr,
     pub buff: Vec<u8>,
+    /// The TCP or TLS connection `conn` refers to, `None` for UDP.
+    pub stream_conn: Option<Arc<StreamConn>>,
 
     // Server State
     pub allocation_manager: Arc<Manager>,
@@ -74,6 +78,7 @@ impl Request {
             conn,
             src_addr,
             buff: vec![],
+            stream_conn: None,
             allocation_manager,
             nonces: Arc::new(Mutex::new(HashMap::new())),
             auth_handler,
@@ -82,6 +87,19 @@ impl Request {
         }
     }
 
+    /// Returns the [`FiveTuple`] the request was received on.
+    fn five_tuple(&self) -> Result<FiveTuple> {
+        Ok(FiveTuple {
+            src_addr: self.src_addr,
+            dst_addr: self.conn.local_addr()?,
+            protocol: if self.stream_conn.is_some() {
+                PROTO_TCP
+            } else {
+                PROTO_UDP
+            },
+        })
+    }
+
     /// Processes the give [`Request`]
     pub async fn handle_request(&mut self) -> Result<()> {
         /*log::debug!(
@@ -132,6 +150,8 @@ impl Request {
                 METHOD_CREATE_PERMISSION => self.handle_create_permission_request(m).await,
                 METHOD_CHANNEL_BIND => self.handle_channel_bind_request(m).await,
                 METHOD_BINDING => self.handle_binding_request(m).await,
+                METHOD_CONNECT => self.handle_connect_request(m).await,
+                METHOD_CONNECTION_BIND => self.handle_connection_bind_request(m).await,
                 _ => Err(Error::ErrUnexpectedClass),
             }
         } else {
@@ -295,11 +315,7 @@ impl Request {
                 return Ok(());
             };
 
-        let five_tuple = FiveTuple {
-            src_addr: self.src_addr,
-            dst_addr: self.conn.local_addr()?,
-            protocol: PROTO_UDP,
-        };
+        let five_tuple = self.five_tuple()?;
         let mut requested_port = 0;
         let mut reservation_token = "".to_owned();
         let mut use_ipv4 = true;
@@ -334,7 +350,7 @@ impl Request {
         //    attribute.  If the REQUESTED-TRANSPORT attribute is not included
         //    or is malformed, the server rejects the request with a 400 (Bad
         //    Request) error.  Otherwise, if the attribute is included but
-        //    specifies a protocol other that UDP, the server rejects the
+        //    specifies a protocol other that UDP or TCP, the server rejects the
         //    request with a 442 (Unsupported Transport Protocol) error.
         let mut requested_transport = RequestedTransport::default();
         if let Err(err) = requested_transport.get_from(m) {
@@ -348,7 +364,9 @@ impl Request {
             )?;
             return build_and_send_err(&self.conn, self.src_addr, bad_request_msg, err.into())
                 .await;
-        } else if requested_transport.protocol != PROTO_UDP {
+        } else if requested_transport.protocol != PROTO_UDP
+            && requested_transport.protocol != PROTO_TCP
+        {
             let msg = build_msg(
                 m.transaction_id,
                 MessageType::new(METHOD_ALLOCATE, CLASS_ERROR_RESPONSE),
@@ -366,6 +384,39 @@ impl Request {
             .await;
         }
 
+        // RFC 6062, Section 5.1:
+        //
+        // If the REQUESTED-TRANSPORT attribute specifies TCP and the request
+        // was not received over TCP or TLS, the server rejects the request with
+        // a 400 (Bad Request) error.  The same applies if a TCP allocation is
+        // requested with the DONT-FRAGMENT, EVEN-PORT or RESERVATION-TOKEN
+        // attribute.
+        let tcp_allocation = requested_transport.protocol == PROTO_TCP;
+        if tcp_allocation {
+            let err = if self.stream_conn.is_none() {
+                Some(Error::ErrTcpTransportRequired)
+            } else if m.contains(ATTR_DONT_FRAGMENT)
+                || m.contains(ATTR_EVEN_PORT)
+                || m.contains(ATTR_RESERVATION_TOKEN)
+            {
+                Some(Error::ErrAllocationProtocolMismatch)
+            } else {
+                None
+            };
+
+            if let Some(err) = err {
+                let bad_request_msg = build_msg(
+                    m.transaction_id,
+                    MessageType::new(METHOD_ALLOCATE, CLASS_ERROR_RESPONSE),
+                    vec![Box::new(ErrorCodeAttribute {
+                        code: CODE_BAD_REQUEST,
+                        reason: vec![],
+                    })],
+                )?;
+                return build_and_send_err(&self.conn, self.src_addr, bad_request_msg, err).await;
+            }
+        }
+
         // 4. The request may contain a DONT-FRAGMENT attribute.  If it does,
         //    but the server does not support sending UDP datagrams with the DF
         //    bit set to 1 (see Section 12), then the server treats the DONT-
@@ -531,18 +582,29 @@ impl Request {
         //    client to a different server.  The use of this error code and
         //    attribute follow the specification in [RFC5389].
         let lifetime_duration = allocation_lifetime(m);
-        let a = match self
-            .allocation_manager
-            .create_allocation(
-                five_tuple,
-                Arc::clone(&self.conn),
-                requested_port,
-                lifetime_duration,
-                username,
-                use_ipv4,
-            )
-            .await
-        {
+        let result = if tcp_allocation {
+            self.allocation_manager
+                .create_tcp_allocation(
+                    five_tuple,
+                    Arc::clone(&self.conn),
+                    lifetime_duration,
+                    username,
+                    use_ipv4,
+                )
+                .await
+        } else {
+            self.allocation_manager
+                .create_allocation(
+                    five_tuple,
+                    Arc::clone(&self.conn),
+                    requested_port,
+                    lifetime_duration,
+                    username,
+                    use_ipv4,
+                )
+                .await
+        };
+        let a = match result {
             Ok(a) => a,
             Err(err) => {
                 let insufficient_capacity_msg = build_msg(
@@ -626,11 +688,7 @@ impl Request {
             };
 
         let lifetime_duration = allocation_lifetime(m);
-        let five_tuple = FiveTuple {
-            src_addr: self.src_addr,
-            dst_addr: self.conn.local_addr()?,
-            protocol: PROTO_UDP,
-        };
+        let five_tuple = self.five_tuple()?;
 
         if lifetime_duration != Duration::from_secs(0) {
             let a = self.allocation_manager.get_allocation(&five_tuple).await;
@@ -685,11 +743,7 @@ impl Request {
 
         let a = self
             .allocation_manager
-            .get_allocation(&FiveTuple {
-                src_addr: self.src_addr,
-                dst_addr: self.conn.local_addr()?,
-                protocol: PROTO_UDP,
-            })
+            .get_allocation(&self.five_tuple()?)
             .await;
 
         if let Some(a) = a {
@@ -776,11 +830,7 @@ impl Request {
 
         let a = self
             .allocation_manager
-            .get_allocation(&FiveTuple {
-                src_addr: self.src_addr,
-                dst_addr: self.conn.local_addr()?,
-                protocol: PROTO_UDP,
-            })
+            .get_allocation(&self.five_tuple()?)
             .await;
 
         if let Some(a) = a {
@@ -797,7 +847,11 @@ impl Request {
                 return Err(Error::ErrNoPermission);
             }
 
-            let l = a.relay_socket.send_to(&data_attr.0, msg_dst).await?;
+            let relay_socket = a
+                .relay_socket
+                .as_ref()
+                .ok_or(Error::ErrAllocationProtocolMismatch)?;
+            let l = relay_socket.send_to(&data_attr.0, msg_dst).await?;
             if l != data_attr.0.len() {
                 Err(Error::ErrShortWrite)
             } else {
@@ -817,11 +871,7 @@ impl Request {
 
         let a = self
             .allocation_manager
-            .get_allocation(&FiveTuple {
-                src_addr: self.src_addr,
-                dst_addr: self.conn.local_addr()?,
-                protocol: PROTO_UDP,
-            })
+            .get_allocation(&self.five_tuple()?)
             .await;
 
         if let Some(a) = a {
@@ -841,6 +891,18 @@ impl Request {
                     log::debug!("no MessageIntegrity");
                     return Ok(());
                 };
+
+            // Data of TCP allocations is relayed over data connections, not channels.
+            if a.protocol != PROTO_UDP {
+                return build_and_send_err(
+                    &self.conn,
+                    self.src_addr,
+                    bad_request_msg,
+                    Error::ErrAllocationProtocolMismatch,
+                )
+                .await;
+            }
+
             let mut channel = ChannelNumber::default();
             if let Err(err) = channel.get_from(m) {
                 return build_and_send_err(&self.conn, self.src_addr, bad_request_msg, err.into())
@@ -913,22 +975,232 @@ impl Request {
         }
     }
 
+    /// https://www.rfc-editor.org/rfc/rfc6062#section-5.2
+    pub(crate) async fn handle_connect_request(&mut self, m: &Message) -> Result<()> {
+        log::debug!("received ConnectRequest from {}", self.src_addr);
+
+        let a = self
+            .allocation_manager
+            .get_allocation(&self.five_tuple()?)
+            .await;
+
+        if let Some(a) = a {
+            let bad_request_msg = build_msg(
+                m.transaction_id,
+                MessageType::new(METHOD_CONNECT, CLASS_ERROR_RESPONSE),
+                vec![Box::new(ErrorCodeAttribute {
+                    code: CODE_BAD_REQUEST,
+                    reason: vec![],
+                })],
+            )?;
+
+            let (_, message_integrity) =
+                if let Some(mi) = self.authenticate_request(m, METHOD_CONNECT).await? {
+                    mi
+                } else {
+                    log::debug!("no MessageIntegrity");
+                    return Ok(());
+                };
+
+            let tcp_relay = if let Some(tcp_relay) = &a.tcp_relay {
+                tcp_relay.clone()
+            } else {
+                return build_and_send_err(
+                    &self.conn,
+                    self.src_addr,
+                    bad_request_msg,
+                    Error::ErrAllocationProtocolMismatch,
+                )
+                .await;
+            };
+
+            let mut peer_addr = PeerAddress::default();
+            if let Err(err) = peer_addr.get_from(m) {
+                return build_and_send_err(&self.conn, self.src_addr, bad_request_msg, err.into())
+                    .await;
+            }
+
+            // If the XOR-PEER-ADDRESS attribute contains an address of an address
+            // family different than that of the relayed transport address for the
+            // allocation, the server MUST generate an error response with the 443
+            // (Peer Address Family Mismatch) response code. [RFC 6156, Section 6.2]
+            if (peer_addr.ip.is_ipv4() && !a.relay_addr.is_ipv4())
+                || (peer_addr.ip.is_ipv6() && !a.relay_addr.is_ipv6())
+            {
+                let peer_address_family_mismatch_msg = build_msg(
+                    m.transaction_id,
+                    MessageType::new(METHOD_CONNECT, CLASS_ERROR_RESPONSE),
+                    vec![Box::new(ErrorCodeAttribute {
+                        code: CODE_PEER_ADDR_FAMILY_MISMATCH,
+                        reason: vec![],
+                    })],
+                )?;
+                return build_and_send_err(
+                    &self.conn,
+                    self.src_addr,
+                    peer_address_family_mismatch_msg,
+                    Error::ErrPeerAddressFamilyMismatch,
+                )
+                .await;
+            }
+
+            let peer = SocketAddr::new(peer_addr.ip, peer_addr.port);
+            if !a.has_permission(&peer).await {
+                let forbidden_msg = build_msg(
+                    m.transaction_id,
+                    MessageType::new(METHOD_CONNECT, CLASS_ERROR_RESPONSE),
+                    vec![Box::new(ErrorCodeAttribute {
+                        code: CODE_FORBIDDEN,
+                        reason: vec![],
+                    })],
+                )?;
+                return build_and_send_err(
+                    &self.conn,
+                    self.src_addr,
+                    forbidden_msg,
+                    Error::ErrNoPermission,
+                )
+                .await;
+            }
+
+            log::debug!("connecting to {}", peer);
+
+            // Connecting to the peer may take a while, so the response is sent once the
+            // connection is established without holding up the other requests.
+            let conn = Arc::clone(&self.conn);
+            let src_addr = self.src_addr;
+            let transaction_id = m.transaction_id;
+            tokio::spawn(async move {
+                let result = match tcp_relay.connect(peer, transaction_id).await {
+                    None => {
+                        log::debug!("ignoring retransmitted ConnectRequest");
+                        Ok(())
+                    }
+                    Some(Ok(id)) => match build_msg(
+                        transaction_id,
+                        MessageType::new(METHOD_CONNECT, CLASS_SUCCESS_RESPONSE),
+                        vec![Box::new(ConnectionId(id)), Box::new(message_integrity)],
+                    ) {
+                        Ok(msg) => build_and_send(&conn, src_addr, msg).await,
+                        Err(err) => Err(err),
+                    },
+                    Some(Err(err)) => {
+                        let code = if err == Error::ErrConnectionAlreadyExists {
+                            CODE_CONN_ALREADY_EXISTS
+                        } else {
+                            CODE_CONN_TIMEOUT_OR_FAILURE
+                        };
+                        match build_msg(
+                            transaction_id,
+                            MessageType::new(METHOD_CONNECT, CLASS_ERROR_RESPONSE),
+                            vec![Box::new(ErrorCodeAttribute {
+                                code,
+                                reason: vec![],
+                            })],
+                        ) {
+                            Ok(msg) => build_and_send_err(&conn, src_addr, msg, err).await,
+                            Err(err) => Err(err),
+                        }
+                    }
+                };
+
+                if let Err(err) = result {
+                    log::error!("error when connecting to {}: {}", peer, err);
+                }
+            });
+
+            Ok(())
+        } else {
+            Err(Error::ErrNoAllocationFound)
+        }
+    }
+
+    /// https://www.rfc-editor.org/rfc/rfc6062#section-5.4
+    pub(crate) async fn handle_connection_bind_request(&mut self, m: &Message) -> Result<()> {
+        log::debug!("received ConnectionBindRequest from {}", self.src_addr);
+
+        let bad_request_msg = build_msg(
+            m.transaction_id,
+            MessageType::new(METHOD_CONNECTION_BIND, CLASS_ERROR_RESPONSE),
+            vec![Box::new(ErrorCodeAttribute {
+                code: CODE_BAD_REQUEST,
+                reason: vec![],
+            })],
+        )?;
+
+        // The connection the request is received on becomes the client data connection.
+        let stream_conn = if let Some(stream_conn) = &self.stream_conn {
+            Arc::clone(stream_conn)
+        } else {
+            return build_and_send_err(
+                &self.conn,
+                self.src_addr,
+                bad_request_msg,
+                Error::ErrTcpTransportRequired,
+            )
+            .await;
+        };
+
+        let (username, message_integrity) =
+            if let Some(mi) = self.authenticate_request(m, METHOD_CONNECTION_BIND).await? {
+                mi
+            } else {
+                log::debug!("no MessageIntegrity");
+                return Ok(());
+            };
+
+        let mut connection_id = ConnectionId::default();
+        if let Err(err) = connection_id.get_from(m) {
+            return build_and_send_err(&self.conn, self.src_addr, bad_request_msg, err.into())
+                .await;
+        }
+
+        let connection = if let Some(connection) = self
+            .allocation_manager
+            .take_connection(connection_id.0, &username.text)
+        {
+            connection
+        } else {
+            return build_and_send_err(
+                &self.conn,
+                self.src_addr,
+                bad_request_msg,
+                Error::ErrNoSuchConnection,
+            )
+            .await;
+        };
+
+        let msg = build_msg(
+            m.transaction_id,
+            MessageType::new(METHOD_CONNECTION_BIND, CLASS_SUCCESS_RESPONSE),
+            vec![Box::new(message_integrity)],
+        )?;
+        build_and_send(&self.conn, self.src_addr, msg).await?;
+
+        // From now on the connection carries the data of the peer instead of TURN messages.
+        if let Some(stream) = stream_conn.take_stream().await {
+            tokio::spawn(connection.relay(stream));
+        }
+
+        Ok(())
+    }
+
     pub(crate) async fn handle_channel_data(&mut self, c: &ChannelData) -> Result<()> {
         log::debug!("received ChannelData from {}", self.src_addr);
 
         let a = self
             .allocation_manager
-            .get_allocation(&FiveTuple {
-                src_addr: self.src_addr,
-                dst_addr: self.conn.local_addr()?,
-                protocol: PROTO_UDP,
-            })
+            .get_allocation(&self.five_tuple()?)
             .await;
 
         if let Some(a) = a {
             let channel = a.get_channel_addr(&c.number).await;
             if let Some(peer) = channel {
-                let l = a.relay_socket.send_to(&c.data, peer).await?;
+                let relay_socket = a
+                    .relay_socket
+                    .as_ref()
+                    .ok_or(Error::ErrAllocationProtocolMismatch)?;
+                let l = relay_socket.send_to(&c.data, peer).await?;
                 if l != c.data.len() {
                     Err(Error::ErrShortWrite)
                 } else {
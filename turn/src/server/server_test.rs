@@ -1,8 +1,11 @@
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use tokio::net::UdpSocket;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
 use tokio::sync::mpsc;
+use tokio_rustls::{rustls, TlsConnector};
 use util::vnet::router::Nic;
 use util::vnet::*;
 
@@ -13,6 +16,7 @@ use crate::client::*;
 use crate::error::*;
 use crate::relay::relay_none::RelayAddressGeneratorNone;
 use crate::relay::relay_static::*;
+use crate::stream::{AsyncStream, StreamConn, TlsListener};
 
 struct TestAuthHandler {
     cred_map: HashMap<String, Vec<u8>>,
@@ -57,6 +61,7 @@ async fn test_server_simple() -> Result<()> {
                 net: Arc::new(net::Net::new(None)),
             }),
         }],
+        listener_configs: vec![],
         realm: "webrtc.rs".to_owned(),
         auth_handler: Arc::new(TestAuthHandler::new()),
         channel_bind_timeout: Duration::from_secs(0),
@@ -192,6 +197,7 @@ async fn build_vnet() -> Result<VNet> {
                 net: Arc::clone(&net0),
             }),
         }],
+        listener_configs: vec![],
         realm: "webrtc.rs".to_owned(),
         auth_handler: Arc::new(TestAuthHandler::new()),
         channel_bind_timeout: Duration::from_secs(0),
@@ -336,3 +342,236 @@ async fn test_server_vnet_echo_via_relay() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_server_tcp_allocation() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let server_addr = listener.local_addr()?;
+
+    let server = Server::new(ServerConfig {
+        conn_configs: vec![],
+        listener_configs: vec![ListenerConfig {
+            listener: Arc::new(listener),
+            relay_addr_generator: Box::new(RelayAddressGeneratorStatic {
+                relay_address: IpAddr::from_str("127.0.0.1")?,
+                address: "127.0.0.1".to_owned(),
+                net: Arc::new(net::Net::new(None)),
+            }),
+        }],
+        realm: "webrtc.rs".to_owned(),
+        auth_handler: Arc::new(TestAuthHandler::new()),
+        channel_bind_timeout: Duration::from_secs(0),
+        alloc_close_notify: None,
+    })
+    .await?;
+
+    let control = TcpStream::connect(server_addr).await?;
+    let local_addr = control.local_addr()?;
+    let client = Client::new(ClientConfig {
+        stun_serv_addr: server_addr.to_string(),
+        turn_serv_addr: server_addr.to_string(),
+        username: "user".to_owned(),
+        password: "pass".to_owned(),
+        realm: String::new(),
+        software: String::new(),
+        rto_in_ms: 0,
+        conn: Arc::new(StreamConn::new(control, local_addr, server_addr)),
+        vnet: None,
+    })
+    .await?;
+
+    client.listen().await?;
+
+    // STUN over TCP
+    let mapped_addr = client.send_binding_request().await?;
+    assert_eq!(mapped_addr, local_addr, "should match");
+
+    let allocation = client.allocate_tcp().await?;
+    let peer_listener = TcpListener::bind("127.0.0.1:0").await?;
+    let peer_addr = peer_listener.local_addr()?;
+
+    // Connection initiated by the client
+    let connection_id = allocation.connect(peer_addr).await?;
+    let (mut peer, from) = peer_listener.accept().await?;
+    assert_eq!(from, allocation.relayed_addr(), "should match");
+
+    let mut data = TcpStream::connect(server_addr).await?;
+    allocation.connection_bind(&mut data, connection_id).await?;
+    exchange(&mut data, &mut peer).await?;
+
+    // Connection initiated by the peer, permitted by the permission for its IP address
+    let mut peer = TcpStream::connect(allocation.relayed_addr()).await?;
+    let (connection_id, from) = allocation.accept().await?;
+    assert_eq!(from, peer.local_addr()?, "should match");
+
+    let mut data = TcpStream::connect(server_addr).await?;
+    allocation.connection_bind(&mut data, connection_id).await?;
+    exchange(&mut data, &mut peer).await?;
+
+    // A connection can only be bound once
+    let mut data = TcpStream::connect(server_addr).await?;
+    assert!(allocation
+        .connection_bind(&mut data, connection_id)
+        .await
+        .is_err());
+
+    allocation.close().await?;
+    client.close().await?;
+    server.close().await?;
+
+    Ok(())
+}
+
+// Assert that a connection stalling in its TLS handshake doesn't hold up the others
+#[tokio::test]
+async fn test_server_tls_stalled_handshake() -> Result<()> {
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_owned()])
+        .map_err(|err| Error::Other(err.to_string()))?;
+    let cert_der = rustls::Certificate(
+        cert.serialize_der()
+            .map_err(|err| Error::Other(err.to_string()))?,
+    );
+    let server_config = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(
+            vec![cert_der.clone()],
+            rustls::PrivateKey(cert.serialize_private_key_der()),
+        )
+        .map_err(|err| Error::Other(err.to_string()))?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let server_addr = listener.local_addr()?;
+
+    let server = Server::new(ServerConfig {
+        conn_configs: vec![],
+        listener_configs: vec![ListenerConfig {
+            listener: Arc::new(TlsListener::new(listener, Arc::new(server_config))),
+            relay_addr_generator: Box::new(RelayAddressGeneratorNone {
+                address: "127.0.0.1".to_owned(),
+                net: Arc::new(net::Net::new(None)),
+            }),
+        }],
+        realm: "webrtc.rs".to_owned(),
+        auth_handler: Arc::new(TestAuthHandler::new()),
+        channel_bind_timeout: Duration::from_secs(0),
+        alloc_close_notify: None,
+    })
+    .await?;
+
+    // Never starts the TLS handshake
+    let _stalled = TcpStream::connect(server_addr).await?;
+
+    let mut roots = rustls::RootCertStore::empty();
+    roots
+        .add(&cert_der)
+        .map_err(|err| Error::Other(err.to_string()))?;
+    let connector = TlsConnector::from(Arc::new(
+        rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth(),
+    ));
+    let control = TcpStream::connect(server_addr).await?;
+    let local_addr = control.local_addr()?;
+    let server_name =
+        rustls::ServerName::try_from("localhost").map_err(|err| Error::Other(err.to_string()))?;
+    let control = tokio::time::timeout(
+        Duration::from_secs(5),
+        connector.connect(server_name, control),
+    )
+    .await
+    .map_err(|err| Error::Other(err.to_string()))??;
+
+    let client = Client::new(ClientConfig {
+        stun_serv_addr: server_addr.to_string(),
+        turn_serv_addr: server_addr.to_string(),
+        username: "user".to_owned(),
+        password: "pass".to_owned(),
+        realm: String::new(),
+        software: String::new(),
+        rto_in_ms: 0,
+        conn: Arc::new(StreamConn::new(control, local_addr, server_addr)),
+        vnet: None,
+    })
+    .await?;
+
+    client.listen().await?;
+
+    // STUN over TLS
+    let mapped_addr = client.send_binding_request().await?;
+    assert_eq!(mapped_addr, local_addr, "should match");
+
+    client.close().await?;
+    server.close().await?;
+
+    Ok(())
+}
+
+/// FailingListener fails every accept, like a listener of a process which ran out of
+/// file descriptors.
+struct FailingListener {
+    accepts: Arc<AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl StreamListener for FailingListener {
+    async fn accept(&self) -> Result<(Box<dyn AsyncStream>, SocketAddr)> {
+        self.accepts.fetch_add(1, Ordering::SeqCst);
+        Err(Error::Other("too many open files".to_owned()))
+    }
+
+    fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(SocketAddr::from_str("127.0.0.1:3478")?)
+    }
+}
+
+// A busy accept loop would starve a single threaded runtime instead of failing.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_server_accept_error_backoff() -> Result<()> {
+    let accepts = Arc::new(AtomicUsize::new(0));
+    let server = Server::new(ServerConfig {
+        conn_configs: vec![],
+        listener_configs: vec![ListenerConfig {
+            listener: Arc::new(FailingListener {
+                accepts: Arc::clone(&accepts),
+            }),
+            relay_addr_generator: Box::new(RelayAddressGeneratorNone {
+                address: "127.0.0.1".to_owned(),
+                net: Arc::new(net::Net::new(None)),
+            }),
+        }],
+        realm: "webrtc.rs".to_owned(),
+        auth_handler: Arc::new(TestAuthHandler::new()),
+        channel_bind_timeout: Duration::from_secs(0),
+        alloc_close_notify: None,
+    })
+    .await?;
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // The retry delay doubles from 5ms, so only a handful of accepts are retried.
+    let n = accepts.load(Ordering::SeqCst);
+    assert!((1..10).contains(&n), "accepted {n} times");
+
+    // Closing doesn't wait for the retry delay to pass.
+    tokio::time::timeout(Duration::from_millis(100), server.close())
+        .await
+        .map_err(|err| Error::Other(err.to_string()))??;
+
+    Ok(())
+}
+
+async fn exchange(data: &mut TcpStream, peer: &mut TcpStream) -> Result<()> {
+    let mut buf = [0u8; 5];
+
+    data.write_all(b"Hello").await?;
+    peer.read_exact(&mut buf).await?;
+    assert_eq!(&buf, b"Hello", "should match");
+
+    peer.write_all(b"World").await?;
+    data.read_exact(&mut buf).await?;
+    assert_eq!(&buf, b"World", "should match");
+
+    Ok(())
+}
@@ -0,0 +1,250 @@
+#[cfg(test)]
+mod stream_test;
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use stun::message::MESSAGE_HEADER_SIZE;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadHalf, WriteHalf};
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+use tokio_rustls::{rustls, TlsAcceptor};
+use tokio_util::sync::CancellationToken;
+use util::Conn;
+
+use crate::error::Result;
+use crate::proto::chandata::nearest_padded_value_length;
+
+// Transporting TURN over TCP and TLS-over-TCP, see RFC 5766 Section 2.1.
+//
+// STUN messages are self delimiting through the length field in their header, and
+// ChannelData messages are padded to a multiple of four bytes so that the next message
+// starts on a four byte boundary.
+
+/// The size of the largest message that can be framed, a STUN message with the maximum length.
+pub(crate) const MAX_FRAME_SIZE: usize = MESSAGE_HEADER_SIZE + u16::MAX as usize;
+
+const FRAME_HEADER_SIZE: usize = 4;
+
+/// A byte stream TURN messages can be transported over, e.g. a TCP or a TLS stream.
+pub trait AsyncStream: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> AsyncStream for T {}
+
+/// `StreamListener` accepts the connections of a TCP or TLS based TURN listener.
+///
+/// It is implemented for [`TcpListener`], and by [`TlsListener`] for TURN over TLS.
+#[async_trait]
+pub trait StreamListener {
+    /// Waits for and returns the next connection together with its remote address.
+    ///
+    /// Handshakes on the connection are left to [`StreamListener::handshake`], so a
+    /// single peer can't hold up accepting the connections of the others.
+    async fn accept(&self) -> Result<(Box<dyn AsyncStream>, SocketAddr)>;
+
+    /// Performs the handshake on an accepted connection, e.g. the TLS handshake, and returns
+    /// the stream TURN messages are transported over. It's called in the task of the
+    /// connection. The default implementation returns the connection unchanged.
+    async fn handshake(&self, stream: Box<dyn AsyncStream>) -> Result<Box<dyn AsyncStream>> {
+        Ok(stream)
+    }
+
+    /// Returns the local address of the listener.
+    fn local_addr(&self) -> Result<SocketAddr>;
+}
+
+#[async_trait]
+impl StreamListener for TcpListener {
+    async fn accept(&self) -> Result<(Box<dyn AsyncStream>, SocketAddr)> {
+        let (stream, remote_addr) = TcpListener::accept(self).await?;
+        stream.set_nodelay(true)?;
+        Ok((Box::new(stream), remote_addr))
+    }
+
+    fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(TcpListener::local_addr(self)?)
+    }
+}
+
+/// `TlsListener` accepts TURN over TLS connections on a TCP listener.
+pub struct TlsListener {
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+}
+
+impl TlsListener {
+    /// Creates a new [`TlsListener`] accepting the connections of `listener` with the
+    /// TLS server `config`.
+    pub fn new(listener: TcpListener, config: Arc<rustls::ServerConfig>) -> Self {
+        TlsListener {
+            listener,
+            acceptor: TlsAcceptor::from(config),
+        }
+    }
+}
+
+#[async_trait]
+impl StreamListener for TlsListener {
+    async fn accept(&self) -> Result<(Box<dyn AsyncStream>, SocketAddr)> {
+        StreamListener::accept(&self.listener).await
+    }
+
+    async fn handshake(&self, stream: Box<dyn AsyncStream>) -> Result<Box<dyn AsyncStream>> {
+        Ok(Box::new(self.acceptor.accept(stream).await?))
+    }
+
+    fn local_addr(&self) -> Result<SocketAddr> {
+        StreamListener::local_addr(&self.listener)
+    }
+}
+
+/// Reads a single STUN or ChannelData message from `reader` into `buf`.
+///
+/// Returns the length of the message, including the padding of ChannelData messages.
+pub(crate) async fn read_frame<R>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut header = [0u8; FRAME_HEADER_SIZE];
+    reader.read_exact(&mut header).await?;
+
+    let length = u16::from_be_bytes([header[2], header[3]]) as usize;
+    // The first two bits are 0b00 for STUN messages and 0b01 for ChannelData messages.
+    let frame_size = match header[0] >> 6 {
+        0b00 => MESSAGE_HEADER_SIZE + length,
+        0b01 => nearest_padded_value_length(FRAME_HEADER_SIZE + length),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "neither a STUN nor a ChannelData message",
+            ))
+        }
+    };
+    if frame_size > buf.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "message of {} bytes exceeds buffer of {}",
+                frame_size,
+                buf.len()
+            ),
+        ));
+    }
+
+    buf[..FRAME_HEADER_SIZE].copy_from_slice(&header);
+    reader
+        .read_exact(&mut buf[FRAME_HEADER_SIZE..frame_size])
+        .await?;
+
+    Ok(frame_size)
+}
+
+/// `StreamConn` transports TURN messages over a TCP or TLS stream.
+///
+/// Every call to [`Conn::recv_from`] returns a single STUN or ChannelData message, and
+/// [`Conn::send_to`] writes a message to the stream regardless of the target address.
+pub struct StreamConn {
+    local_addr: SocketAddr,
+    remote_addr: SocketAddr,
+    reader: Mutex<Option<ReadHalf<Box<dyn AsyncStream>>>>,
+    writer: Mutex<Option<WriteHalf<Box<dyn AsyncStream>>>>,
+    close_notify: CancellationToken,
+}
+
+impl StreamConn {
+    /// Creates a new [`StreamConn`] on top of `stream`, which connects `local_addr`
+    /// with `remote_addr`.
+    pub fn new<S>(stream: S, local_addr: SocketAddr, remote_addr: SocketAddr) -> Self
+    where
+        S: AsyncStream + 'static,
+    {
+        let stream: Box<dyn AsyncStream> = Box::new(stream);
+        let (reader, writer) = tokio::io::split(stream);
+
+        StreamConn {
+            local_addr,
+            remote_addr,
+            reader: Mutex::new(Some(reader)),
+            writer: Mutex::new(Some(writer)),
+            close_notify: CancellationToken::new(),
+        }
+    }
+
+    /// Takes the underlying stream out of this connection, e.g. to use it as the data
+    /// connection of a TCP allocation. Subsequent reads and writes fail.
+    ///
+    /// Must not be called while a read is pending.
+    pub(crate) async fn take_stream(&self) -> Option<Box<dyn AsyncStream>> {
+        let reader = self.reader.lock().await.take()?;
+        let writer = self.writer.lock().await.take()?;
+        Some(reader.unsplit(writer))
+    }
+}
+
+#[async_trait]
+impl Conn for StreamConn {
+    async fn connect(&self, _addr: SocketAddr) -> std::result::Result<(), util::Error> {
+        Err(io::Error::new(io::ErrorKind::Other, "Not applicable").into())
+    }
+
+    async fn recv(&self, buf: &mut [u8]) -> std::result::Result<usize, util::Error> {
+        let mut reader = self.reader.lock().await;
+        let reader = reader
+            .as_mut()
+            .ok_or(util::Error::ErrUseClosedNetworkConn)?;
+
+        tokio::select! {
+            result = read_frame(reader, buf) => Ok(result?),
+            _ = self.close_notify.cancelled() => Err(util::Error::ErrUseClosedNetworkConn),
+        }
+    }
+
+    async fn recv_from(
+        &self,
+        buf: &mut [u8],
+    ) -> std::result::Result<(usize, SocketAddr), util::Error> {
+        let n = self.recv(buf).await?;
+        Ok((n, self.remote_addr))
+    }
+
+    async fn send(&self, buf: &[u8]) -> std::result::Result<usize, util::Error> {
+        if self.close_notify.is_cancelled() {
+            return Err(util::Error::ErrUseClosedNetworkConn);
+        }
+
+        let mut writer = self.writer.lock().await;
+        let writer = writer
+            .as_mut()
+            .ok_or(util::Error::ErrUseClosedNetworkConn)?;
+        writer.write_all(buf).await?;
+        Ok(buf.len())
+    }
+
+    async fn send_to(
+        &self,
+        buf: &[u8],
+        _target: SocketAddr,
+    ) -> std::result::Result<usize, util::Error> {
+        self.send(buf).await
+    }
+
+    fn local_addr(&self) -> std::result::Result<SocketAddr, util::Error> {
+        Ok(self.local_addr)
+    }
+
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        Some(self.remote_addr)
+    }
+
+    async fn close(&self) -> std::result::Result<(), util::Error> {
+        self.close_notify.cancel();
+
+        let mut writer = self.writer.lock().await;
+        if let Some(writer) = writer.as_mut() {
+            let _ = writer.shutdown().await;
+        }
+        Ok(())
+    }
+}
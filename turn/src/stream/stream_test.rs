@@ -0,0 +1,94 @@
+use stun::message::{Message, BINDING_REQUEST};
+use tokio::io::duplex;
+
+use super::*;
+use crate::proto::chandata::ChannelData;
+use crate::proto::channum::{ChannelNumber, MIN_CHANNEL_NUMBER};
+
+#[tokio::test]
+async fn test_read_frame() -> Result<()> {
+    let mut m = Message::new();
+    m.build(&[
+        Box::<stun::agent::TransactionId>::default(),
+        Box::new(BINDING_REQUEST),
+    ])?;
+
+    let mut d = ChannelData {
+        data: vec![1, 2, 3, 4, 5],
+        number: ChannelNumber(MIN_CHANNEL_NUMBER),
+        ..Default::default()
+    };
+    d.encode();
+    assert_eq!(d.raw.len(), 12, "ChannelData should be padded");
+
+    let (mut client, mut server) = duplex(1024);
+    client.write_all(&d.raw).await?;
+    client.write_all(&m.raw).await?;
+    client.write_all(&d.raw).await?;
+
+    let mut buf = vec![0u8; MAX_FRAME_SIZE];
+    for expected in [&d.raw, &m.raw, &d.raw] {
+        let n = read_frame(&mut server, &mut buf).await?;
+        assert_eq!(&buf[..n], &expected[..]);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_read_frame_invalid() -> Result<()> {
+    let (mut client, mut server) = duplex(1024);
+    let mut buf = vec![0u8; 64];
+
+    // Neither STUN nor ChannelData
+    client.write_all(&[0x80, 0x00, 0x00, 0x00]).await?;
+    let err = read_frame(&mut server, &mut buf).await.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    // Exceeds the buffer
+    client.write_all(&[0x40, 0x00, 0x01, 0x00]).await?;
+    let err = read_frame(&mut server, &mut buf).await.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_conn() -> Result<()> {
+    let local_addr: SocketAddr = "127.0.0.1:3478".parse()?;
+    let remote_addr: SocketAddr = "127.0.0.1:50000".parse()?;
+
+    let (client, server) = duplex(1024);
+    let client = StreamConn::new(client, remote_addr, local_addr);
+    let server = StreamConn::new(server, local_addr, remote_addr);
+
+    let mut m = Message::new();
+    m.build(&[
+        Box::<stun::agent::TransactionId>::default(),
+        Box::new(BINDING_REQUEST),
+    ])?;
+    client.send_to(&m.raw, local_addr).await?;
+
+    let mut buf = vec![0u8; MAX_FRAME_SIZE];
+    let (n, addr) = server.recv_from(&mut buf).await?;
+    assert_eq!(&buf[..n], &m.raw[..]);
+    assert_eq!(addr, remote_addr);
+
+    // The stream carries raw data once taken out of the connection.
+    let mut stream = server
+        .take_stream()
+        .await
+        .expect("stream should be available");
+    client.send(b"data").await?;
+    let mut data = [0u8; 4];
+    stream.read_exact(&mut data).await?;
+    assert_eq!(&data, b"data");
+
+    assert!(server.recv(&mut buf).await.is_err());
+    assert!(server.take_stream().await.is_none());
+
+    client.close().await?;
+    assert!(client.send(b"data").await.is_err());
+
+    Ok(())
+}
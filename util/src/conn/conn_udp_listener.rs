@@ -9,14 +9,20 @@ use tokio::sync::{mpsc, watch, Mutex};
 
 use super::*;
 use crate::error::Error;
-use crate::Buffer;
 
 const RECEIVE_MTU: usize = 8192;
 const DEFAULT_LISTEN_BACKLOG: usize = 128; // same as Linux default
+const RECEIVE_BACKLOG: usize = 1024;
 
 pub type AcceptFilterFn =
     Box<dyn (Fn(&[u8]) -> Pin<Box<dyn Future<Output = bool> + Send + 'static>>) + Send + Sync>;
 
+/// DatagramRouterFn extracts a connection identifier from an incoming packet.
+pub type DatagramRouterFn = Box<dyn (Fn(&[u8]) -> Option<String>) + Send + Sync>;
+
+/// ConnectionIdentifierFn extracts a connection identifier from an outgoing packet.
+pub type ConnectionIdentifierFn = Arc<dyn (Fn(&[u8]) -> Option<String>) + Send + Sync>;
+
 type AcceptDoneCh = (mpsc::Receiver<Arc<UdpConn>>, watch::Receiver<()>);
 
 /// listener is used in the [DTLS](https://github.com/webrtc-rs/dtls) and
@@ -40,7 +46,7 @@ impl Listener for ListenerImpl {
         tokio::select! {
             c = accept_ch_rx.recv() =>{
                 if let Some(c) = c{
-                    let raddr = c.raddr;
+                    let raddr = c.raddr();
                     Ok((c, raddr))
                 }else{
                     Err(Error::ErrClosedListenerAcceptCh)
@@ -88,6 +94,17 @@ pub struct ListenConfig {
     /// AcceptFilter determines whether the new conn should be made for
     /// the incoming packet. If not set, any packet creates new conn.
     pub accept_filter: Option<AcceptFilterFn>,
+
+    /// DatagramRouter routes an incoming packet to a connection by the
+    /// identifier it extracts from the packet, packets it returns None for
+    /// are routed by their remote address. This lets a connection survive
+    /// a change of the remote address, e.g. by a NAT rebinding.
+    pub datagram_router: Option<DatagramRouterFn>,
+
+    /// ConnectionIdentifier extracts an identifier from a packet sent by a
+    /// connection, the datagram router then routes packets carrying the
+    /// same identifier to the connection.
+    pub connection_identifier: Option<ConnectionIdentifierFn>,
 }
 
 pub async fn listen<A: ToSocketAddrs>(laddr: A) -> Result<impl Listener> {
@@ -117,6 +134,8 @@ impl ListenConfig {
         let pconn = Arc::clone(&l.pconn);
         let accepting = Arc::clone(&l.accepting);
         let accept_filter = self.accept_filter.take();
+        let datagram_router = self.datagram_router.take();
+        let connection_identifier = self.connection_identifier.take();
         let accept_ch_tx = Arc::clone(&l.accept_ch_tx);
         let conns = Arc::clone(&l.conns);
         tokio::spawn(async move {
@@ -125,6 +144,8 @@ impl ListenConfig {
                 pconn,
                 accepting,
                 accept_filter,
+                datagram_router,
+                connection_identifier,
                 accept_ch_tx,
                 conns,
             )
@@ -138,11 +159,14 @@ impl ListenConfig {
     /// 1. Dispatching incoming packets to the correct Conn.
     ///    It can therefore not be ended until all Conns are closed.
     /// 2. Creating a new Conn when receiving from a new remote.
+    #[allow(clippy::too_many_arguments)]
     async fn read_loop(
         mut done_ch_rx: watch::Receiver<()>,
         pconn: Arc<dyn Conn + Send + Sync>,
         accepting: Arc<AtomicBool>,
         accept_filter: Option<AcceptFilterFn>,
+        datagram_router: Option<DatagramRouterFn>,
+        connection_identifier: Option<ConnectionIdentifierFn>,
         accept_ch_tx: Arc<Mutex<Option<mpsc::Sender<Arc<UdpConn>>>>>,
         conns: Arc<Mutex<HashMap<String, Arc<UdpConn>>>>,
     ) {
@@ -160,6 +184,8 @@ impl ListenConfig {
                                 &pconn,
                                 &accepting,
                                 &accept_filter,
+                                &datagram_router,
+                                &connection_identifier,
                                 &accept_ch_tx,
                                 &conns,
                                 raddr,
@@ -172,7 +198,9 @@ impl ListenConfig {
                             };
 
                             if let Some(conn) = udp_conn {
-                                let _ = conn.buffer.write(&buf[..n]).await;
+                                // Packets are dropped like by any UDP socket if
+                                // they are not read fast enough.
+                                let _ = conn.buffer_tx.try_send((buf[..n].to_vec(), raddr));
                             }
                         }
                         Err(err) => {
@@ -185,10 +213,13 @@ impl ListenConfig {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn get_udp_conn(
         pconn: &Arc<dyn Conn + Send + Sync>,
         accepting: &Arc<AtomicBool>,
         accept_filter: &Option<AcceptFilterFn>,
+        datagram_router: &Option<DatagramRouterFn>,
+        connection_identifier: &Option<ConnectionIdentifierFn>,
         accept_ch_tx: &Arc<Mutex<Option<mpsc::Sender<Arc<UdpConn>>>>>,
         conns: &Arc<Mutex<HashMap<String, Arc<UdpConn>>>>,
         raddr: SocketAddr,
@@ -196,6 +227,11 @@ impl ListenConfig {
     ) -> Result<Option<Arc<UdpConn>>> {
         {
             let m = conns.lock().await;
+            if let Some(id) = datagram_router.as_ref().and_then(|f| f(buf)) {
+                if let Some(conn) = m.get(id.as_str()) {
+                    return Ok(Some(conn.clone()));
+                }
+            }
             if let Some(conn) = m.get(raddr.to_string().as_str()) {
                 return Ok(Some(conn.clone()));
             }
@@ -211,7 +247,12 @@ impl ListenConfig {
             }
         }
 
-        let udp_conn = Arc::new(UdpConn::new(Arc::clone(pconn), Arc::clone(conns), raddr));
+        let udp_conn = Arc::new(UdpConn::new(
+            Arc::clone(pconn),
+            Arc::clone(conns),
+            raddr,
+            connection_identifier.clone(),
+        ));
         {
             let accept_ch = accept_ch_tx.lock().await;
             if let Some(tx) = &*accept_ch {
@@ -236,8 +277,11 @@ impl ListenConfig {
 pub struct UdpConn {
     pconn: Arc<dyn Conn + Send + Sync>,
     conns: Arc<Mutex<HashMap<String, Arc<UdpConn>>>>,
-    raddr: SocketAddr,
-    buffer: Buffer,
+    raddr: std::sync::Mutex<SocketAddr>,
+    id: std::sync::Mutex<Option<String>>,
+    connection_identifier: Option<ConnectionIdentifierFn>,
+    buffer_tx: mpsc::Sender<(Vec<u8>, SocketAddr)>,
+    buffer_rx: Mutex<mpsc::Receiver<(Vec<u8>, SocketAddr)>>,
 }
 
 impl UdpConn {
@@ -245,36 +289,105 @@ impl UdpConn {
         pconn: Arc<dyn Conn + Send + Sync>,
         conns: Arc<Mutex<HashMap<String, Arc<UdpConn>>>>,
         raddr: SocketAddr,
+        connection_identifier: Option<ConnectionIdentifierFn>,
     ) -> Self {
+        let (buffer_tx, buffer_rx) = mpsc::channel(RECEIVE_BACKLOG);
         UdpConn {
             pconn,
             conns,
-            raddr,
-            buffer: Buffer::new(0, 0),
+            raddr: std::sync::Mutex::new(raddr),
+            id: std::sync::Mutex::new(None),
+            connection_identifier,
+            buffer_tx,
+            buffer_rx: Mutex::new(buffer_rx),
+        }
+    }
+
+    fn raddr(&self) -> SocketAddr {
+        *self.raddr.lock().unwrap()
+    }
+
+    fn is_self(&self, conn: &Arc<UdpConn>) -> bool {
+        std::ptr::eq(Arc::as_ptr(conn), self)
+    }
+
+    /// identify associates the identifier of an outgoing packet with the
+    /// connection, so that the datagram router can route packets to it.
+    async fn identify(&self, buf: &[u8]) {
+        let id = match self.connection_identifier.as_ref().and_then(|f| f(buf)) {
+            Some(id) => id,
+            None => return,
+        };
+        if self.id.lock().unwrap().as_ref() == Some(&id) {
+            return;
         }
+
+        let mut conns = self.conns.lock().await;
+        let conn = match conns.get(self.raddr().to_string().as_str()) {
+            Some(conn) if self.is_self(conn) => Arc::clone(conn),
+            _ => return,
+        };
+        let old_id = self.id.lock().unwrap().replace(id.clone());
+        if let Some(old_id) = old_id {
+            conns.remove(old_id.as_str());
+        }
+        conns.insert(id, conn);
     }
 }
 
 #[async_trait]
 impl Conn for UdpConn {
+    /// connect changes the remote address packets are sent to, it is used
+    /// when the remote address of an identified connection changes. Packets
+    /// from the new address are then routed to this connection.
+    /// Fails with `ErrAddressAlreadyInUse`, leaving the connection unchanged,
+    /// if another connection of the listener has the new remote address.
     async fn connect(&self, addr: SocketAddr) -> Result<()> {
-        self.pconn.connect(addr).await
+        let mut conns = self.conns.lock().await;
+        let old_key = self.raddr().to_string();
+        let conn = match conns.get(old_key.as_str()) {
+            Some(conn) if self.is_self(conn) => Arc::clone(conn),
+            _ => return Err(Error::ErrClosedListener),
+        };
+        if conns
+            .get(addr.to_string().as_str())
+            .map_or(false, |other| !self.is_self(other))
+        {
+            return Err(Error::ErrAddressAlreadyInUse);
+        }
+        conns.remove(old_key.as_str());
+        conns.insert(addr.to_string(), conn);
+        *self.raddr.lock().unwrap() = addr;
+
+        Ok(())
     }
 
     async fn recv(&self, buf: &mut [u8]) -> Result<usize> {
-        Ok(self.buffer.read(buf, None).await?)
+        let (n, _) = self.recv_from(buf).await?;
+        Ok(n)
     }
 
     async fn recv_from(&self, buf: &mut [u8]) -> Result<(usize, SocketAddr)> {
-        let n = self.buffer.read(buf, None).await?;
-        Ok((n, self.raddr))
+        let mut buffer_rx = self.buffer_rx.lock().await;
+        let (packet, raddr) = match buffer_rx.recv().await {
+            Some(v) => v,
+            None => return Err(Error::ErrBufferClosed),
+        };
+        if packet.len() > buf.len() {
+            return Err(Error::ErrBufferShort);
+        }
+        buf[..packet.len()].copy_from_slice(&packet);
+
+        Ok((packet.len(), raddr))
     }
 
     async fn send(&self, buf: &[u8]) -> Result<usize> {
-        self.pconn.send_to(buf, self.raddr).await
+        self.identify(buf).await;
+        self.pconn.send_to(buf, self.raddr()).await
     }
 
     async fn send_to(&self, buf: &[u8], target: SocketAddr) -> Result<usize> {
+        self.identify(buf).await;
         self.pconn.send_to(buf, target).await
     }
 
@@ -283,12 +396,21 @@ impl Conn for UdpConn {
     }
 
     fn remote_addr(&self) -> Option<SocketAddr> {
-        Some(self.raddr)
+        Some(self.raddr())
     }
 
     async fn close(&self) -> Result<()> {
         let mut conns = self.conns.lock().await;
-        conns.remove(self.raddr.to_string().as_str());
+        let mut keys = vec![self.raddr().to_string()];
+        keys.extend(self.id.lock().unwrap().take());
+        for key in keys {
+            if conns
+                .get(key.as_str())
+                .map_or(false, |conn| self.is_self(conn))
+            {
+                conns.remove(key.as_str());
+            }
+        }
         Ok(())
     }
 }
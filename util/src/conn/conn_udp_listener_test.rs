@@ -218,3 +218,77 @@ async fn test_listener_concurrent() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_listener_datagram_router() -> Result<()> {
+    // Packets starting with '#' carry the identifier of their connection
+    fn identifier(pkt: &[u8]) -> Option<String> {
+        if pkt.len() > 1 && pkt[0] == b'#' {
+            Some(String::from_utf8_lossy(&pkt[1..2]).to_string())
+        } else {
+            None
+        }
+    }
+
+    let listener = ListenConfig {
+        datagram_router: Some(Box::new(identifier)),
+        connection_identifier: Some(Arc::new(identifier)),
+        ..Default::default()
+    }
+    .listen("0.0.0.0:0")
+    .await?;
+    let laddr = listener.addr().await?;
+
+    let d_conn1 = UdpSocket::bind("0.0.0.0:0").await?;
+    d_conn1.connect(laddr).await?;
+    d_conn1.send(b"hello").await?;
+
+    let (l_conn, raddr) = listener.accept().await?;
+    assert_eq!(d_conn1.local_addr()?, raddr);
+    let mut buf = vec![0u8; 64];
+    let n = l_conn.recv(&mut buf).await?;
+    assert_eq!(&buf[..n], b"hello");
+
+    // Identify the connection by an outgoing packet
+    l_conn.send(b"#a").await?;
+    let n = d_conn1.recv(&mut buf).await?;
+    assert_eq!(&buf[..n], b"#a");
+
+    // Packets of the identified connection are routed to it from any address
+    let d_conn2 = UdpSocket::bind("0.0.0.0:0").await?;
+    d_conn2.connect(laddr).await?;
+    d_conn2.send(b"#a moved").await?;
+
+    let (n, raddr) = l_conn.recv_from(&mut buf).await?;
+    assert_eq!(&buf[..n], b"#a moved");
+    assert_eq!(d_conn2.local_addr()?, raddr);
+
+    l_conn.connect(raddr).await?;
+    assert_eq!(l_conn.remote_addr(), Some(raddr));
+    l_conn.send(b"welcome").await?;
+    let n = d_conn2.recv(&mut buf).await?;
+    assert_eq!(&buf[..n], b"welcome");
+
+    // No connection is accepted for the new address
+    let result = tokio::time::timeout(Duration::from_millis(10), listener.accept()).await;
+    assert!(result.is_err(), "unexpected connection accepted");
+
+    // The address of another connection can't be taken over
+    let d_conn3 = UdpSocket::bind("0.0.0.0:0").await?;
+    d_conn3.connect(laddr).await?;
+    d_conn3.send(b"hello").await?;
+    let (l_conn3, raddr3) = listener.accept().await?;
+    assert_eq!(
+        l_conn.connect(raddr3).await,
+        Err(Error::ErrAddressAlreadyInUse)
+    );
+    assert_eq!(l_conn.remote_addr(), Some(raddr));
+    let n = l_conn3.recv(&mut buf).await?;
+    assert_eq!(&buf[..n], b"hello");
+
+    l_conn3.close().await?;
+    l_conn.close().await?;
+    listener.close().await?;
+
+    Ok(())
+}
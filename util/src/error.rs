@@ -87,6 +87,8 @@ pub enum Error {
     ErrPortSpaceExhausted,
     #[error("vnet is not enabled")]
     ErrVnetDisabled,
+    #[error("tcp is not supported by vnet")]
+    ErrVnetTcpNotSupported,
     #[error("invalid local IP in static_ips")]
     ErrInvalidLocalIpInStaticIps,
     #[error("mapped in static_ips is beyond subnet")]
@@ -9,7 +9,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use ipnet::IpNet;
-use tokio::net::UdpSocket;
+use tokio::net::{TcpSocket, TcpStream, UdpSocket};
 use tokio::sync::Mutex;
 
 use super::conn_map::*;
@@ -556,6 +556,24 @@ impl Net {
         }
     }
 
+    // dial_tcp connects a TCP stream from local_ip to remote_addr.
+    // TCP is not supported by the virtual network.
+    pub async fn dial_tcp(&self, local_ip: IpAddr, remote_addr: SocketAddr) -> Result<TcpStream> {
+        match self {
+            Net::VNet(_) => Err(Error::ErrVnetTcpNotSupported),
+            Net::Ifs(_) => {
+                let socket = if local_ip.is_ipv4() {
+                    TcpSocket::new_v4()?
+                } else {
+                    TcpSocket::new_v6()?
+                };
+                socket.bind(SocketAddr::new(local_ip, 0))?;
+
+                Ok(socket.connect(remote_addr).await?)
+            }
+        }
+    }
+
     pub fn get_nic(&self) -> Result<Arc<Mutex<dyn Nic + Send + Sync>>> {
         match self {
             Net::VNet(vnet) => Ok(Arc::clone(vnet) as Arc<Mutex<dyn Nic + Send + Sync>>),
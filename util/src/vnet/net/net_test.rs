@@ -88,6 +88,37 @@ async fn test_net_native_dail() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_net_native_dial_tcp() -> Result<()> {
+    let nw = Net::new(None);
+    assert!(!nw.is_virtual(), "should be false");
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let stream = nw
+        .dial_tcp(IpAddr::from_str("127.0.0.1")?, listener.local_addr()?)
+        .await?;
+    let (_, raddr) = listener.accept().await?;
+    assert_eq!(stream.local_addr()?, raddr, "local_addr should match");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_net_virtual_dial_tcp() -> Result<()> {
+    let nw = Net::new(Some(NetConfig::default()));
+    assert!(nw.is_virtual(), "should be true");
+
+    let result = nw
+        .dial_tcp(
+            IpAddr::from_str("127.0.0.1")?,
+            SocketAddr::from_str("127.0.0.1:1234")?,
+        )
+        .await;
+    assert_eq!(result.err(), Some(Error::ErrVnetTcpNotSupported));
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_net_native_loopback() -> Result<()> {
     let nw = Net::new(None);
@@ -1,6 +1,9 @@
 #[cfg(test)]
 mod interceptor_registry_test;
 
+use interceptor::ccfb::RTCP_FB_ACK_CCFB;
+use interceptor::flexfec::{FlexFecDecoder, FlexFecEncoderBuilder};
+use interceptor::gcc::SendSideBweBuilder;
 use interceptor::nack::generator::Generator;
 use interceptor::nack::responder::Responder;
 use interceptor::registry::Registry;
@@ -9,10 +12,12 @@ use interceptor::report::sender::SenderReport;
 use interceptor::twcc::receiver::Receiver;
 use interceptor::twcc::sender::Sender;
 
-use crate::api::media_engine::MediaEngine;
+use crate::api::media_engine::{MediaEngine, MIME_TYPE_FLEXFEC03};
 use crate::error::Result;
-use crate::rtp_transceiver::rtp_codec::{RTCRtpHeaderExtensionCapability, RTPCodecType};
-use crate::rtp_transceiver::{RTCPFeedback, TYPE_RTCP_FB_TRANSPORT_CC};
+use crate::rtp_transceiver::rtp_codec::{
+    RTCRtpCodecCapability, RTCRtpCodecParameters, RTCRtpHeaderExtensionCapability, RTPCodecType,
+};
+use crate::rtp_transceiver::{RTCPFeedback, TYPE_RTCP_FB_ACK, TYPE_RTCP_FB_TRANSPORT_CC};
 
 /// register_default_interceptors will register some useful interceptors.
 /// If you want to customize which interceptors are loaded, you should copy the
@@ -169,3 +174,89 @@ pub fn configure_twcc_receiver_only(
     registry.add(receiver);
     Ok(registry)
 }
+
+/// configure_ccfb will setup everything necessary for generating RTP Control Protocol
+/// Congestion Control Feedback (RFC 8888) reports. It negotiates the `ack ccfb` RTCP feedback,
+/// and only the streams for which the remote peer supports it are reported on.
+pub fn configure_ccfb(mut registry: Registry, media_engine: &mut MediaEngine) -> Registry {
+    for codec_type in [RTPCodecType::Video, RTPCodecType::Audio] {
+        media_engine.register_feedback(
+            RTCPFeedback {
+                typ: TYPE_RTCP_FB_ACK.to_owned(),
+                parameter: RTCP_FB_ACK_CCFB.to_owned(),
+            },
+            codec_type,
+        );
+    }
+
+    registry.add(Box::new(interceptor::ccfb::receiver::Receiver::builder()));
+    registry
+}
+
+/// configure_gcc will setup everything necessary for estimating the available send bandwidth
+/// with Google Congestion Control. It adds a TWCC header extension to outgoing RTP packets and
+/// feeds the TWCC reports of the remote peer to the estimator, so it replaces
+/// configure_twcc_sender_only. The estimate of each PeerConnection can be obtained with
+/// SendSideBweBuilder::with_on_new_estimator.
+pub fn configure_gcc(
+    mut registry: Registry,
+    media_engine: &mut MediaEngine,
+    builder: SendSideBweBuilder,
+) -> Result<Registry> {
+    for codec_type in [RTPCodecType::Video, RTPCodecType::Audio] {
+        media_engine.register_feedback(
+            RTCPFeedback {
+                typ: TYPE_RTCP_FB_TRANSPORT_CC.to_owned(),
+                ..Default::default()
+            },
+            codec_type,
+        );
+        media_engine.register_header_extension(
+            RTCRtpHeaderExtensionCapability {
+                uri: sdp::extmap::TRANSPORT_CC_URI.to_owned(),
+            },
+            codec_type,
+            None,
+        )?;
+    }
+
+    // The estimator has to be added before the sender so it sees the transport wide sequence
+    // numbers of the outgoing packets.
+    registry.add(Box::new(builder));
+    registry.add(Box::new(Sender::builder()));
+    Ok(registry)
+}
+
+/// configure_flexfec will setup everything necessary for protecting video streams with FlexFEC.
+/// It registers the FlexFEC codec, so a FEC stream is added to the video senders if the remote
+/// peer supports it, and the interceptors sending the protection packets and recovering lost
+/// packets with the ones received. The protection ratio is configured with the builder.
+/// The interceptor added first to a registry is the closest to the transport and sees the
+/// outgoing packets last, after the interceptors added later have modified them. The encoder
+/// protects the packets as it sees them, so configure_flexfec has to be called before the
+/// interceptors modifying outgoing packets, like the TWCC sender of configure_twcc or
+/// configure_gcc, for the protection to cover the packets as they are sent.
+pub fn configure_flexfec(
+    mut registry: Registry,
+    media_engine: &mut MediaEngine,
+    builder: FlexFecEncoderBuilder,
+) -> Result<Registry> {
+    media_engine.register_codec(
+        RTCRtpCodecParameters {
+            capability: RTCRtpCodecCapability {
+                mime_type: MIME_TYPE_FLEXFEC03.to_owned(),
+                clock_rate: 90000,
+                channels: 0,
+                sdp_fmtp_line: "repair-window=10000000".to_owned(),
+                rtcp_feedback: vec![],
+            },
+            payload_type: 49,
+            ..Default::default()
+        },
+        RTPCodecType::Video,
+    )?;
+
+    registry.add(Box::new(builder));
+    registry.add(Box::new(FlexFecDecoder::builder()));
+    Ok(registry)
+}
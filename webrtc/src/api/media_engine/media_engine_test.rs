@@ -1,11 +1,14 @@
 use std::io::Cursor;
 
+use interceptor::registry::Registry;
 use regex::Regex;
 
 use super::*;
+use crate::api::interceptor_registry::configure_ccfb;
 use crate::api::media_engine::MIME_TYPE_OPUS;
 use crate::api::APIBuilder;
 use crate::peer_connection::configuration::RTCConfiguration;
+use crate::rtp_transceiver::rtp_codec::find_rtx_payload_type;
 
 #[tokio::test]
 async fn test_opus_case() -> Result<()> {
@@ -51,6 +54,61 @@ async fn test_video_case() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_ccfb_feedback_in_offer() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let registry = configure_ccfb(Registry::new(), &mut m);
+    let api = APIBuilder::new()
+        .with_media_engine(m)
+        .with_interceptor_registry(registry)
+        .build();
+
+    let pc = api.new_peer_connection(RTCConfiguration::default()).await?;
+    pc.add_transceiver_from_kind(RTPCodecType::Audio, None)
+        .await?;
+    pc.add_transceiver_from_kind(RTPCodecType::Video, None)
+        .await?;
+
+    let offer = pc.create_offer(None).await?;
+
+    let re = Regex::new(r"(?m)^a=rtcp-fb:111 ack ccfb").unwrap();
+    assert!(re.is_match(offer.sdp.as_str()));
+    let re = Regex::new(r"(?m)^a=rtcp-fb:96 ack ccfb").unwrap();
+    assert!(re.is_match(offer.sdp.as_str()));
+
+    pc.close().await?;
+
+    Ok(())
+}
+
+#[test]
+fn test_register_rtx_codecs() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    assert!(!m
+        .video_codecs
+        .iter()
+        .any(|c| c.capability.mime_type == MIME_TYPE_RTX));
+
+    m.register_rtx_codecs()?;
+
+    for codec in &m.video_codecs {
+        let mime_type = codec.capability.mime_type.to_lowercase();
+        if mime_type == MIME_TYPE_RTX || mime_type == "video/ulpfec" {
+            continue;
+        }
+        assert!(
+            find_rtx_payload_type(codec.payload_type, &m.video_codecs).is_some(),
+            "no RTX codec for {} ({})",
+            codec.capability.mime_type,
+            codec.payload_type
+        );
+    }
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_media_engine_remote_description() -> Result<()> {
     let must_parse = |raw: &str| -> Result<SessionDescription> {
@@ -27,6 +27,9 @@ use crate::stats::StatsReportType::Codec;
 /// MIME_TYPE_H264 H264 MIME type.
 /// Note: Matching should be case insensitive.
 pub const MIME_TYPE_H264: &str = "video/H264";
+/// MIME_TYPE_H265 H265 MIME type
+/// Note: Matching should be case insensitive.
+pub const MIME_TYPE_H265: &str = "video/H265";
 /// MIME_TYPE_OPUS Opus MIME type
 /// Note: Matching should be case insensitive.
 pub const MIME_TYPE_OPUS: &str = "audio/opus";
@@ -51,6 +54,15 @@ pub const MIME_TYPE_PCMA: &str = "audio/PCMA";
 /// MIME_TYPE_TELEPHONE_EVENT telephone-event MIME type
 /// Note: Matching should be case insensitive.
 pub const MIME_TYPE_TELEPHONE_EVENT: &str = "audio/telephone-event";
+/// MIME_TYPE_RTX RTX (RFC 4588) retransmission MIME type
+/// Note: Matching should be case insensitive.
+pub const MIME_TYPE_RTX: &str = "video/rtx";
+/// MIME_TYPE_FLEXFEC03 FlexFEC (draft-ietf-payload-flexible-fec-scheme-03) MIME type
+/// Note: Matching should be case insensitive.
+pub const MIME_TYPE_FLEXFEC03: &str = "video/flexfec-03";
+/// MIME_TYPE_RED RED (RFC 2198) redundant audio MIME type
+/// Note: Matching should be case insensitive.
+pub const MIME_TYPE_RED: &str = "audio/red";
 
 const VALID_EXT_IDS: Range<isize> = 1..15;
 
@@ -285,11 +297,22 @@ impl MediaEngine {
                     clock_rate: 90000,
                     channels: 0,
                     sdp_fmtp_line: "profile-id=0".to_owned(),
-                    rtcp_feedback: video_rtcp_feedback,
+                    rtcp_feedback: video_rtcp_feedback.clone(),
                 },
                 payload_type: 41,
                 ..Default::default()
             },
+            RTCRtpCodecParameters {
+                capability: RTCRtpCodecCapability {
+                    mime_type: MIME_TYPE_H265.to_owned(),
+                    clock_rate: 90000,
+                    channels: 0,
+                    sdp_fmtp_line: "level-id=93;profile-id=1;tier-flag=0;tx-mode=SRST".to_owned(),
+                    rtcp_feedback: video_rtcp_feedback,
+                },
+                payload_type: 126,
+                ..Default::default()
+            },
             RTCRtpCodecParameters {
                 capability: RTCRtpCodecCapability {
                     mime_type: "video/ulpfec".to_owned(),
@@ -308,6 +331,48 @@ impl MediaEngine {
         Ok(())
     }
 
+    /// register_rtx_codecs registers a RTX (RFC 4588) codec for each of the video codecs
+    /// registered by register_default_codecs, which have to be registered first.
+    /// NACKed packets are then retransmitted on a RTX stream of their own if the remote peer
+    /// supports it, instead of being resent in the original stream.
+    /// register_rtx_codecs is not safe for concurrent use.
+    pub fn register_rtx_codecs(&mut self) -> Result<()> {
+        // The apt parameter names the retransmitted codec
+        for (payload_type, apt) in [
+            (97, 96),
+            (99, 98),
+            (101, 100),
+            (103, 102),
+            (104, 127),
+            (105, 125),
+            (109, 108),
+            (124, 123),
+            (42, 41),
+            (107, 126),
+        ] {
+            if !self.video_codecs.iter().any(|c| c.payload_type == apt) {
+                continue;
+            }
+
+            self.register_codec(
+                RTCRtpCodecParameters {
+                    capability: RTCRtpCodecCapability {
+                        mime_type: MIME_TYPE_RTX.to_owned(),
+                        clock_rate: 90000,
+                        channels: 0,
+                        sdp_fmtp_line: format!("apt={apt}"),
+                        rtcp_feedback: vec![],
+                    },
+                    payload_type,
+                    ..Default::default()
+                },
+                RTPCodecType::Video,
+            )?;
+        }
+
+        Ok(())
+    }
+
     /// add_codec will append codec if it not exists
     fn add_codec(codecs: &mut Vec<RTCRtpCodecParameters>, codec: RTCRtpCodecParameters) {
         for c in codecs.iter() {
@@ -484,6 +549,21 @@ impl MediaEngine {
         collector.merge(reports);
     }
 
+    /// codec_stats_id returns the id of the codec stats of the registered codec a
+    /// negotiated codec was matched with.
+    pub(crate) fn codec_stats_id(&self, codec: &RTCRtpCodecParameters) -> Option<String> {
+        self.video_codecs
+            .iter()
+            .chain(self.audio_codecs.iter())
+            .find(|c| {
+                c.payload_type == codec.payload_type
+                    && c.capability
+                        .mime_type
+                        .eq_ignore_ascii_case(&codec.capability.mime_type)
+            })
+            .map(|c| c.stats_id.clone())
+    }
+
     /// Look up a codec and enable if it exists
     pub(crate) fn match_remote_codec(
         &self,
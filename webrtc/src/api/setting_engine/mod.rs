@@ -7,7 +7,9 @@ use dtls::extension::extension_use_srtp::SrtpProtectionProfile;
 use ice::agent::agent_config::{InterfaceFilterFn, IpFilterFn};
 use ice::mdns::MulticastDnsMode;
 use ice::network_type::NetworkType;
+use ice::tcp_mux::TCPMux;
 use ice::udp_network::UDPNetwork;
+use sctp::association::StreamSchedulerType;
 use tokio::time::Duration;
 use util::vnet::net::*;
 
@@ -70,13 +72,14 @@ pub struct SettingEngine {
     pub(crate) disable_srtcp_replay_protection: bool,
     pub(crate) vnet: Option<Arc<Net>>,
     //BufferFactory                             :func(packetType packetio.BufferPacketType, ssrc uint32) io.ReadWriteCloser,
-    //iceTCPMux                                 :ice.TCPMux,?
     //iceProxyDialer                            :proxy.Dialer,?
     pub(crate) udp_network: UDPNetwork,
+    pub(crate) ice_tcp_mux: Option<Arc<dyn TCPMux + Send + Sync>>,
     pub(crate) disable_media_engine_copy: bool,
     pub(crate) srtp_protection_profiles: Vec<SrtpProtectionProfile>,
     pub(crate) receive_mtu: usize,
     pub(crate) mid_generator: Option<Arc<dyn Fn(isize) -> String + Send + Sync>>,
+    pub(crate) sctp_stream_scheduler: Option<StreamSchedulerType>,
 }
 
 impl SettingEngine {
@@ -88,6 +91,14 @@ impl SettingEngine {
             RECEIVE_MTU
         }
     }
+
+    /// get_sctp_stream_scheduler returns the configured SCTP stream scheduler. It defaults to
+    /// the weighted fair queue, so that the priority of the data channels shares the bandwidth.
+    pub(crate) fn get_sctp_stream_scheduler(&self) -> StreamSchedulerType {
+        self.sctp_stream_scheduler
+            .unwrap_or(StreamSchedulerType::WeightedFairQueue)
+    }
+
     /// detach_data_channels enables detaching data channels. When enabled
     /// data channels have to be detached in the OnOpen callback using the
     /// DataChannel.Detach method.
@@ -287,11 +298,12 @@ impl SettingEngine {
         self.sdp_media_level_fingerprints = sdp_media_level_fingerprints;
     }
 
-    // SetICETCPMux enables ICE-TCP when set to a non-nil value. Make sure that
-    // NetworkTypeTCP4 or NetworkTypeTCP6 is enabled as well.
-    //pub fn SetICETCPMux(&mut self, tcpMux ice.TCPMux) {
-    //    self.iceTCPMux = tcpMux
-    //}
+    /// set_ice_tcp_mux enables passive ICE-TCP candidates accepted through the given mux. Make sure
+    /// that NetworkType::Tcp4 or NetworkType::Tcp6 is enabled as well.
+    /// The TCPMux should be started prior to creating PeerConnections.
+    pub fn set_ice_tcp_mux(&mut self, tcp_mux: Arc<dyn TCPMux + Send + Sync>) {
+        self.ice_tcp_mux = Some(tcp_mux);
+    }
 
     // SetICEProxyDialer sets the proxy dialer interface based on golang.org/x/net/proxy.
     //pub fn SetICEProxyDialer(&mut self, d proxy.Dialer) {
@@ -311,6 +323,13 @@ impl SettingEngine {
         self.receive_mtu = receive_mtu;
     }
 
+    /// set_sctp_stream_scheduler sets how the data channels share the sending capacity of the
+    /// SCTP association. This is optional. Leave this unset for the weighted fair queue, where
+    /// each data channel gets a share proportional to its priority.
+    pub fn set_sctp_stream_scheduler(&mut self, stream_scheduler: StreamSchedulerType) {
+        self.sctp_stream_scheduler = Some(stream_scheduler);
+    }
+
     /// Sets a callback used to generate mid for transceivers created by this side of the RTCPeerconnection.
     /// By having separate "naming schemes" for mids generated by either side of a connection, it's
     /// possible to reduce complexity when handling SDP offers/answers clashing.
@@ -1,5 +1,7 @@
 use std::sync::atomic::Ordering;
 
+use ice::tcp_mux::{TCPMuxDefault, TCPMuxParams};
+
 use super::*;
 use crate::api::media_engine::MediaEngine;
 use crate::api::APIBuilder;
@@ -125,31 +127,27 @@ fn test_set_replay_protection() -> Result<()> {
     Ok(())
 }
 
-/*TODO:#[test] fn test_setting_engine_set_ice_tcp_mux() ->Result<()> {
-
-    listener, err := net.ListenTCP("tcp", &net.TCPAddr{})
-    if err != nil {
-        panic(err)
-    }
-
-    defer func() {
-        _ = listener.Close()
-    }()
-
-    tcpMux := NewICETCPMux(nil, listener, 8)
+#[tokio::test]
+async fn test_setting_engine_set_ice_tcp_mux() -> Result<()> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(util::Error::from_std)?;
+    let tcp_mux: Arc<dyn TCPMux + Send + Sync> = TCPMuxDefault::new(TCPMuxParams::new(listener))?;
 
-    defer func() {
-        _ = tcpMux.Close()
-    }()
+    let mut s = SettingEngine::default();
+    s.set_ice_tcp_mux(tcp_mux.clone());
 
-     let mut s = SettingEngine::default();
-    settingEngine.SetICETCPMux(tcpMux)
+    assert!(
+        s.ice_tcp_mux
+            .as_ref()
+            .map_or(false, |ice_tcp_mux| Arc::ptr_eq(ice_tcp_mux, &tcp_mux)),
+        "SettingEngine should hold the given TCPMux"
+    );
 
-    assert.Equal(t, tcpMux, settingEngine.iceTCPMux)
+    tcp_mux.close().await?;
 
     Ok(())
 }
-*/
 
 #[tokio::test]
 async fn test_setting_engine_set_disable_media_engine_copy() -> Result<()> {
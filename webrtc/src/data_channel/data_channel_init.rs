@@ -1,3 +1,5 @@
+use crate::rtp_transceiver::priority_type::RTCPriorityType;
+
 /// DataChannelConfig can be used to configure properties of the underlying
 /// channel such as data reliability.
 #[derive(Default, Debug, Clone)]
@@ -26,4 +28,9 @@ pub struct RTCDataChannelInit {
     /// to negotiate the channel and create an DataChannel with the same id
     /// at the other peer.
     pub negotiated: Option<u16>,
+
+    /// priority of the data channel relative to the other ones of the peer connection. The
+    /// default value of None uses the low priority. It is announced to the remote peer and
+    /// weights the bandwidth shared by the data channels.
+    pub priority: Option<RTCPriorityType>,
 }
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::rtp_transceiver::priority_type::RTCPriorityType;
+
 /// DataChannelParameters describes the configuration of the DataChannel.
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct DataChannelParameters {
@@ -9,4 +11,5 @@ pub struct DataChannelParameters {
     pub max_packet_life_time: u16,
     pub max_retransmits: u16,
     pub negotiated: Option<u16>,
+    pub priority: RTCPriorityType,
 }
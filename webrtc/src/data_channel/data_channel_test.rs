@@ -501,6 +501,53 @@ async fn test_data_channel_parameters_protocol_exchange() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_data_channel_parameters_priority_exchange() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let api = APIBuilder::new().with_media_engine(m).build();
+
+    let options = RTCDataChannelInit {
+        priority: Some(RTCPriorityType::High),
+        ..Default::default()
+    };
+
+    let (mut offer_pc, mut answer_pc, dc, done_tx, done_rx) =
+        set_up_data_channel_parameters_test(&api, Some(options)).await?;
+
+    // Check if parameters are correctly set
+    assert_eq!(
+        dc.priority(),
+        RTCPriorityType::High,
+        "Priority should match DataChannelConfig"
+    );
+
+    let done_tx = Arc::new(Mutex::new(Some(done_tx)));
+    answer_pc.on_data_channel(Box::new(move |d: Arc<RTCDataChannel>| {
+        // Make sure this is the data channel we were looking for. (Not the one
+        // created in signalPair).
+        if d.label() != EXPECTED_LABEL {
+            return Box::pin(async {});
+        }
+        // Check if parameters are correctly set
+        assert_eq!(
+            d.priority(),
+            RTCPriorityType::High,
+            "Priority should match what channel creator declared"
+        );
+
+        let done_tx2 = Arc::clone(&done_tx);
+        Box::pin(async move {
+            let mut done = done_tx2.lock().await;
+            done.take();
+        })
+    }));
+
+    close_reliability_param_test(&mut offer_pc, &mut answer_pc, done_rx).await?;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_data_channel_parameters_negotiated_exchange() -> Result<()> {
     let mut m = MediaEngine::default();
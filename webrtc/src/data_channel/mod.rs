@@ -24,6 +24,7 @@ use util::sync::Mutex as SyncMutex;
 
 use crate::api::setting_engine::SettingEngine;
 use crate::error::{Error, OnErrorHdlrFn, Result};
+use crate::rtp_transceiver::priority_type::RTCPriorityType;
 use crate::sctp_transport::RTCSctpTransport;
 use crate::stats::stats_collector::StatsCollector;
 use crate::stats::{DataChannelStats, StatsReportType};
@@ -55,6 +56,7 @@ pub struct RTCDataChannel {
     pub(crate) max_retransmits: u16,
     pub(crate) protocol: String,
     pub(crate) negotiated: bool,
+    pub(crate) priority: RTCPriorityType,
     pub(crate) id: AtomicU16,
     pub(crate) ready_state: Arc<AtomicU8>, // DataChannelState
     pub(crate) buffered_amount_low_threshold: AtomicUsize,
@@ -103,6 +105,7 @@ impl RTCDataChannel {
             ordered: params.ordered,
             max_packet_lifetime: params.max_packet_life_time,
             max_retransmits: params.max_retransmits,
+            priority: params.priority,
             ready_state: Arc::new(AtomicU8::new(RTCDataChannelState::Connecting as u8)),
             detach_called: Arc::new(AtomicBool::new(false)),
 
@@ -153,7 +156,7 @@ impl RTCDataChannel {
 
             let cfg = data::data_channel::Config {
                 channel_type,
-                priority: data::message::message_channel_open::CHANNEL_PRIORITY_NORMAL,
+                priority: self.priority.data_channel_priority(),
                 reliability_parameter,
                 label: self.label.clone(),
                 protocol: self.protocol.clone(),
@@ -461,6 +464,12 @@ impl RTCDataChannel {
         self.protocol.as_str()
     }
 
+    /// priority represents the priority of this DataChannel relative to the other
+    /// ones, which weights the bandwidth they share.
+    pub fn priority(&self) -> RTCPriorityType {
+        self.priority
+    }
+
     /// negotiated represents whether this DataChannel was negotiated by the
     /// application (true), or not (false).
     pub fn negotiated(&self) -> bool {
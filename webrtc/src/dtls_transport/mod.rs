@@ -11,7 +11,7 @@ use dtls::conn::DTLSConn;
 use dtls::extension::extension_use_srtp::SrtpProtectionProfile;
 use dtls_role::*;
 use interceptor::stream_info::StreamInfo;
-use interceptor::{Interceptor, RTCPReader, RTPReader};
+use interceptor::{Attributes, Interceptor, RTCPReader, RTPReader};
 use sha2::{Digest, Sha256};
 use srtp::protection_profile::ProtectionProfile;
 use srtp::session::Session;
@@ -31,6 +31,8 @@ use crate::mux::mux_func::{match_dtls, match_srtcp, match_srtp, MatchFunc};
 use crate::peer_connection::certificate::RTCCertificate;
 use crate::rtp_transceiver::SSRC;
 use crate::stats::stats_collector::StatsCollector;
+use crate::stats::StatsReportType::Transport;
+use crate::track::RepairedStreamReader;
 
 #[cfg(test)]
 mod dtls_transport_test;
@@ -40,6 +42,18 @@ pub mod dtls_parameters;
 pub mod dtls_role;
 pub mod dtls_transport_state;
 
+/// srtp_cipher_name returns the IANA name of an SRTP protection profile, as reported
+/// by `srtpCipher` in the transport stats.
+fn srtp_cipher_name(profile: SrtpProtectionProfile) -> Option<&'static str> {
+    match profile {
+        SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_80 => Some("AES_CM_128_HMAC_SHA1_80"),
+        SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_32 => Some("AES_CM_128_HMAC_SHA1_32"),
+        SrtpProtectionProfile::Srtp_Aead_Aes_128_Gcm => Some("AEAD_AES_128_GCM"),
+        SrtpProtectionProfile::Srtp_Aead_Aes_256_Gcm => Some("AEAD_AES_256_GCM"),
+        SrtpProtectionProfile::Unsupported => None,
+    }
+}
+
 pub(crate) fn default_srtp_protection_profiles() -> Vec<SrtpProtectionProfile> {
     vec![
         SrtpProtectionProfile::Srtp_Aead_Aes_128_Gcm,
@@ -310,6 +324,24 @@ impl RTCDtlsTransport {
         for cert in &self.certificates {
             cert.collect_stats(collector).await;
         }
+
+        if let Some(mut stats) = self.ice_transport.transport_stats().await {
+            stats.dtls_state = self.state();
+            stats.local_certificate_id = self.certificates.first().map(|c| c.stats_id.clone());
+
+            if let Some(conn) = self.conn().await {
+                stats.dtls_role = Some(self.role().await);
+                stats.tls_version = conn
+                    .protocol_version()
+                    .await
+                    .map(|v| format!("{:02X}{:02X}", v.major, v.minor));
+                stats.dtls_cipher = conn.cipher_suite_id().await.map(|id| id.to_string());
+                stats.srtp_cipher =
+                    srtp_cipher_name(conn.selected_srtpprotection_profile()).map(str::to_owned);
+            }
+
+            collector.insert(stats.id.clone(), Transport(stats));
+        }
     }
 
     async fn prepare_transport(
@@ -585,6 +617,7 @@ impl RTCDtlsTransport {
         Arc<dyn RTPReader + Send + Sync>,
         Arc<srtp::stream::Stream>,
         Arc<dyn RTCPReader + Send + Sync>,
+        mpsc::Sender<(rtp::packet::Packet, Attributes)>,
     )> {
         let srtp_session = self
             .get_srtp_session()
@@ -592,9 +625,10 @@ impl RTCDtlsTransport {
             .ok_or(Error::ErrDtlsTransportNotStarted)?;
         //log::debug!("streams_for_ssrc: srtp_session.listen ssrc={}", ssrc);
         let rtp_read_stream = srtp_session.open(ssrc).await;
-        let rtp_stream_reader = Arc::clone(&rtp_read_stream) as Arc<dyn RTPReader + Send + Sync>;
+        let (rtp_stream_reader, repair_packets_tx) =
+            RepairedStreamReader::new(Arc::clone(&rtp_read_stream));
         let rtp_interceptor = interceptor
-            .bind_remote_stream(stream_info, rtp_stream_reader)
+            .bind_remote_stream(stream_info, Arc::new(rtp_stream_reader))
             .await;
 
         let srtcp_session = self
@@ -611,6 +645,7 @@ impl RTCDtlsTransport {
             rtp_interceptor,
             rtcp_read_stream,
             rtcp_interceptor,
+            repair_packets_tx,
         ))
     }
 }
@@ -114,6 +114,16 @@ pub enum Error {
     #[error("remote description is not set")]
     ErrNoRemoteDescription,
 
+    /// ErrNoLocalDescription indicates that an operation was rejected because
+    /// the local description is not set
+    #[error("local description is not set")]
+    ErrNoLocalDescription,
+
+    /// ErrIceCandidateUfragMismatch indicates that a remote candidate was rejected
+    /// because its username fragment is not the one of the remote description
+    #[error("candidate username fragment does not match the remote description")]
+    ErrIceCandidateUfragMismatch,
+
     /// ErrIncorrectSDPSemantics indicates that the PeerConnection was configured to
     /// generate SDP Answers with different SDP Semantics than the received Offer
     #[error("offer SDP semantics does not match configuration")]
@@ -335,6 +345,26 @@ pub enum Error {
     ErrRTPSenderDTLSTransportNil,
     #[error("Send has already been called")]
     ErrRTPSenderSendAlreadyCalled,
+    #[error("Sender cannot add encoding as rid is empty")]
+    ErrRTPSenderRidNil,
+    #[error("Sender cannot add encoding as there is no base track")]
+    ErrRTPSenderNoBaseEncoding,
+    #[error("Sender cannot add encoding as provided track does not match base track")]
+    ErrRTPSenderBaseEncodingMismatch,
+    #[error("Sender cannot add encoding due to RID collision")]
+    ErrRTPSenderRIDCollision,
+    #[error("Sender does not have track for RID")]
+    ErrRTPSenderNoTrackForRID,
+    #[error("set_parameters must be called with the transaction of the last get_parameters")]
+    ErrRTPSenderInvalidTransactionId,
+    #[error("set_parameters cannot modify read-only parameters")]
+    ErrRTPSenderInvalidModification,
+    #[error("scale_resolution_down_by must be greater than or equal to 1.0")]
+    ErrRTPSenderInvalidScaleResolutionDownBy,
+    #[error("max_framerate must be greater than or equal to 0")]
+    ErrRTPSenderInvalidMaxFramerate,
+    #[error("RTPSender has been stopped")]
+    ErrRTPSenderStopped,
     #[error("errRTPSenderTrackNil")]
     ErrRTPTransceiverCannotChangeMid,
     #[error("invalid state change in RTPTransceiver.setSending")]
@@ -385,6 +415,15 @@ pub enum Error {
     #[error("not long enough to be a RTP Packet")]
     ErrRTPTooShort,
 
+    /// ErrWhipUnexpectedStatus indicates a WHIP or WHEP server answered a request
+    /// with another status than the protocol expects.
+    #[error("WHIP: unexpected HTTP status {0}")]
+    ErrWhipUnexpectedStatus(u16),
+    #[error("WHIP: response to the offer has no Location header")]
+    ErrWhipNoLocation,
+    #[error("WHIP: the session has been deleted")]
+    ErrWhipSessionDeleted,
+
     #[error("{0}")]
     Util(#[from] util::Error),
     #[error("{0}")]
@@ -416,6 +455,8 @@ pub enum Error {
     ParseInt(#[from] ParseIntError),
     #[error("parse url: {0}")]
     ParseUrl(#[from] url::ParseError),
+    #[error("http: {0}")]
+    Http(String),
 
     /// Error parsing a given PEM string.
     #[error("invalid PEM: {0}")]
@@ -436,6 +477,20 @@ impl<T> From<MpscSendError<T>> for Error {
     }
 }
 
+#[cfg(feature = "whip")]
+impl From<hyper::Error> for Error {
+    fn from(e: hyper::Error) -> Self {
+        Error::Http(e.to_string())
+    }
+}
+
+#[cfg(feature = "whip")]
+impl From<hyper::http::Error> for Error {
+    fn from(e: hyper::http::Error) -> Self {
+        Error::Http(e.to_string())
+    }
+}
+
 impl From<Error> for interceptor::Error {
     fn from(e: Error) -> Self {
         // this is a bit lol, but we do preserve the stack trace
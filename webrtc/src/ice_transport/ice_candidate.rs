@@ -7,11 +7,16 @@ use ice::candidate::candidate_peer_reflexive::CandidatePeerReflexiveConfig;
 use ice::candidate::candidate_relay::CandidateRelayConfig;
 use ice::candidate::candidate_server_reflexive::CandidateServerReflexiveConfig;
 use ice::candidate::Candidate;
+use ice::tcp_type::TcpType;
+use sdp::description::session::ATTR_KEY_MID;
+use sdp::fragment::{MediaFragment, SdpFragment};
+use sdp::SessionDescription;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{Error, Result};
 use crate::ice_transport::ice_candidate_type::RTCIceCandidateType;
 use crate::ice_transport::ice_protocol::RTCIceProtocol;
+use crate::peer_connection::sdp::extract_ice_credentials;
 
 /// ICECandidate represents a ice candidate
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -74,12 +79,11 @@ impl RTCIceCandidate {
                         address: self.address.clone(),
                         port: self.port,
                         component: self.component,
-                        //tcp_type: ice.NewTCPType(c.TCPType),
                         foundation: self.foundation.clone(),
                         priority: self.priority,
                         ..Default::default()
                     },
-                    ..Default::default()
+                    tcp_type: TcpType::from(self.tcp_type.as_str()),
                 };
                 config.new_candidate_host()?
             }
@@ -176,9 +180,88 @@ pub struct RTCIceCandidateInit {
     pub username_fragment: Option<String>,
 }
 
+impl RTCIceCandidateInit {
+    /// from_sdp_fragment returns the candidates of an SDP fragment (RFC 8840), each
+    /// with the mid, index and username fragment of its media section. A media section
+    /// ended with "a=end-of-candidates" adds a candidate with an empty candidate string,
+    /// which [`RTCPeerConnection::add_ice_candidate`] takes as the end of candidates.
+    ///
+    /// [`RTCPeerConnection::add_ice_candidate`]: crate::peer_connection::RTCPeerConnection::add_ice_candidate
+    pub fn from_sdp_fragment(fragment: &SdpFragment) -> Vec<RTCIceCandidateInit> {
+        let mut candidates = vec![];
+        for (index, media_fragment) in fragment.media_fragments.iter().enumerate() {
+            let init = |candidate: String| RTCIceCandidateInit {
+                candidate,
+                sdp_mid: media_fragment.mid.clone(),
+                sdp_mline_index: Some(index as u16),
+                username_fragment: media_fragment.ice_ufrag(fragment).map(str::to_owned),
+            };
+            for candidate in &media_fragment.candidates {
+                candidates.push(init(format!("candidate:{candidate}")));
+            }
+            if media_fragment.end_of_candidates {
+                candidates.push(init(String::new()));
+            }
+        }
+        candidates
+    }
+
+    /// to_sdp_fragment returns the SDP fragment (RFC 8840) trickling candidates
+    /// gathered for the local description, with its ICE credentials. A candidate goes
+    /// to the media section of its sdp_mid, or of its sdp_mline_index without one.
+    /// With end_of_candidates, every media section is ended.
+    pub fn to_sdp_fragment(
+        candidates: &[RTCIceCandidateInit],
+        local_description: &SessionDescription,
+        end_of_candidates: bool,
+    ) -> SdpFragment {
+        let (ice_ufrag, ice_pwd) = extract_ice_credentials(local_description);
+
+        let mut media_fragments = vec![];
+        for (index, media) in local_description.media_descriptions.iter().enumerate() {
+            let mid = media.attribute(ATTR_KEY_MID).flatten();
+            let candidates: Vec<String> = candidates
+                .iter()
+                .filter(|c| match c.sdp_mid.as_deref() {
+                    Some(sdp_mid) if !sdp_mid.is_empty() => Some(sdp_mid) == mid,
+                    _ => c.sdp_mline_index.unwrap_or_default() as usize == index,
+                })
+                .map(|c| {
+                    c.candidate
+                        .strip_prefix("candidate:")
+                        .unwrap_or(&c.candidate)
+                        .to_owned()
+                })
+                .filter(|c| !c.is_empty())
+                .collect();
+            if candidates.is_empty() && !end_of_candidates {
+                continue;
+            }
+
+            let mut media_name = media.media_name.clone();
+            media_name.port.value = 9;
+            media_fragments.push(MediaFragment {
+                media_name,
+                mid: mid.map(str::to_owned),
+                candidates,
+                end_of_candidates,
+                ..Default::default()
+            });
+        }
+
+        SdpFragment {
+            ice_ufrag,
+            ice_pwd,
+            media_fragments,
+            ..Default::default()
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::error::Result;
 
     #[test]
     fn test_ice_candidate_serialization() {
@@ -216,4 +299,70 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_ice_candidate_sdp_fragment() -> Result<()> {
+        let desc = SessionDescription::try_from(
+            "v=0\r\n\
+o=- 0 0 IN IP4 127.0.0.1\r\n\
+s=-\r\n\
+t=0 0\r\n\
+a=group:BUNDLE 0 1\r\n\
+m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+c=IN IP4 0.0.0.0\r\n\
+a=mid:0\r\n\
+a=ice-ufrag:ufrag\r\n\
+a=ice-pwd:pwd\r\n\
+m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+c=IN IP4 0.0.0.0\r\n\
+a=mid:1\r\n\
+a=ice-ufrag:ufrag\r\n\
+a=ice-pwd:pwd\r\n"
+                .to_owned(),
+        )?;
+
+        let candidates = [
+            RTCIceCandidateInit {
+                candidate: "candidate:1 1 udp 1 192.0.2.1 1000 typ host".to_owned(),
+                sdp_mid: Some(String::new()),
+                sdp_mline_index: Some(0),
+                username_fragment: None,
+            },
+            RTCIceCandidateInit {
+                candidate: "candidate:2 1 udp 1 192.0.2.1 2000 typ host".to_owned(),
+                sdp_mid: Some("1".to_owned()),
+                sdp_mline_index: None,
+                username_fragment: None,
+            },
+        ];
+        let fragment = RTCIceCandidateInit::to_sdp_fragment(&candidates, &desc, false);
+        assert_eq!(fragment.ice_ufrag.as_deref(), Some("ufrag"));
+        assert_eq!(fragment.ice_pwd.as_deref(), Some("pwd"));
+        assert_eq!(fragment.media_fragments.len(), 2);
+        assert_eq!(fragment.media_fragments[0].mid.as_deref(), Some("0"));
+        assert_eq!(
+            fragment.media_fragments[0].candidates,
+            vec!["1 1 udp 1 192.0.2.1 1000 typ host".to_owned()]
+        );
+        assert_eq!(fragment.media_fragments[1].mid.as_deref(), Some("1"));
+        assert_eq!(fragment.media_fragments[1].media_name.port.value, 9);
+
+        let remote = RTCIceCandidateInit::from_sdp_fragment(&fragment);
+        assert_eq!(remote.len(), 2);
+        assert_eq!(remote[0].candidate, candidates[0].candidate);
+        assert_eq!(remote[0].sdp_mline_index, Some(0));
+        assert_eq!(remote[1].sdp_mid.as_deref(), Some("1"));
+        assert_eq!(remote[1].username_fragment.as_deref(), Some("ufrag"));
+
+        // Only the media sections with candidates are sent, unless they are ended.
+        let fragment = RTCIceCandidateInit::to_sdp_fragment(&candidates[1..], &desc, false);
+        assert_eq!(fragment.media_fragments.len(), 1);
+        let fragment = RTCIceCandidateInit::to_sdp_fragment(&[], &desc, true);
+        assert_eq!(fragment.media_fragments.len(), 2);
+        let remote = RTCIceCandidateInit::from_sdp_fragment(&fragment);
+        assert_eq!(remote.len(), 2);
+        assert!(remote.iter().all(|c| c.candidate.is_empty()));
+
+        Ok(())
+    }
 }
@@ -1,9 +1,12 @@
 use std::fmt;
 
+use serde::Serialize;
+
 /// ICERole describes the role ice.Agent is playing in selecting the
 /// preferred the candidate pair.
-#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Serialize)]
 pub enum RTCIceRole {
+    #[serde(rename = "unspecified")]
     #[default]
     Unspecified,
 
@@ -11,10 +14,12 @@ pub enum RTCIceRole {
     /// for selecting the final choice of candidate pairs and signaling them
     /// through STUN and an updated offer, if needed. In any session, one agent
     /// is always controlling. The other is the controlled agent.
+    #[serde(rename = "controlling")]
     Controlling,
 
     /// ICERoleControlled indicates that an ICE agent that waits for the
     /// controlling agent to select the final choice of candidate pairs.
+    #[serde(rename = "controlled")]
     Controlled,
 }
 
@@ -19,9 +19,7 @@ use crate::ice_transport::ice_transport_state::RTCIceTransportState;
 use crate::mux::endpoint::Endpoint;
 use crate::mux::mux_func::MatchFunc;
 use crate::mux::{Config, Mux};
-use crate::stats::stats_collector::StatsCollector;
 use crate::stats::ICETransportStats;
-use crate::stats::StatsReportType::Transport;
 
 #[cfg(test)]
 mod ice_transport_test;
@@ -320,12 +318,20 @@ impl RTCIceTransport {
         }
     }
 
-    pub(crate) async fn collect_stats(&self, collector: &StatsCollector) {
-        if let Some(agent) = self.gatherer.get_agent().await {
-            let stats = ICETransportStats::new("ice_transport".to_string(), agent);
+    /// transport_stats returns the stats of the ICE side of the transport, the DTLS
+    /// transport running over it fills in the rest.
+    pub(crate) async fn transport_stats(&self) -> Option<ICETransportStats> {
+        let agent = self.gatherer.get_agent().await?;
+        let selected_candidate_pair_id = agent
+            .get_selected_candidate_pair()
+            .map(|p| format!("{}-{}", p.local.id(), p.remote.id()));
 
-            collector.insert("ice_transport".to_string(), Transport(stats));
-        }
+        let mut stats = ICETransportStats::new("ice_transport".to_string(), agent);
+        stats.ice_role = self.role().await;
+        stats.ice_state = self.state();
+        stats.selected_candidate_pair_id = selected_candidate_pair_id;
+
+        Some(stats)
     }
 
     pub(crate) async fn have_remote_credentials_change(
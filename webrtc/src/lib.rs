@@ -15,6 +15,8 @@ pub mod rtp_transceiver;
 pub mod sctp_transport;
 pub mod stats;
 pub mod track;
+#[cfg(feature = "whip")]
+pub mod whip;
 
 pub use error::Error;
 
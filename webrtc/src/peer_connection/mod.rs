@@ -20,6 +20,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use ::ice::candidate::candidate_base::unmarshal_candidate;
 use ::ice::candidate::Candidate;
 use ::sdp::description::session::*;
+use ::sdp::fragment::SdpFragment;
 use ::sdp::util::ConnectionRole;
 use arc_swap::ArcSwapOption;
 use async_trait::async_trait;
@@ -1606,7 +1607,7 @@ impl RTCPeerConnection {
         for transceiver in &*current_transceivers {
             let sender = transceiver.sender().await;
             if sender.is_negotiated() && !sender.has_sent() {
-                sender.send(&sender.get_parameters().await).await?;
+                sender.send(&sender.send_parameters().await).await?;
             }
         }
 
@@ -1624,8 +1625,20 @@ impl RTCPeerConnection {
     /// add_ice_candidate accepts an ICE candidate string and adds it
     /// to the existing set of candidates.
     pub async fn add_ice_candidate(&self, candidate: RTCIceCandidateInit) -> Result<()> {
-        if self.remote_description().await.is_none() {
-            return Err(Error::ErrNoRemoteDescription);
+        let remote_description = match self.remote_description().await {
+            Some(remote_description) => remote_description,
+            None => return Err(Error::ErrNoRemoteDescription),
+        };
+
+        // Candidates of other credentials belong to another ICE generation.
+        if let Some(ufrag) = candidate
+            .username_fragment
+            .as_deref()
+            .filter(|ufrag| !ufrag.is_empty())
+        {
+            if remote_ice_ufrag(&remote_description)?.map_or(false, |remote| remote != ufrag) {
+                return Err(Error::ErrIceCandidateUfragMismatch);
+            }
         }
 
         let candidate_value = match candidate.candidate.strip_prefix("candidate:") {
@@ -1648,6 +1661,56 @@ impl RTCPeerConnection {
             .await
     }
 
+    /// add_ice_fragment accepts the candidates trickled in an SDP fragment (RFC 8840)
+    /// from the remote peer, as [`RTCPeerConnection::add_ice_candidate`] does for each.
+    /// Media sections ended with "a=end-of-candidates" end the remote candidates.
+    /// The fragment is rejected as a whole if it carries other ICE credentials than
+    /// the remote description, as it does for an ICE restart.
+    pub async fn add_ice_fragment(&self, fragment: &SdpFragment) -> Result<()> {
+        let remote_description = self
+            .remote_description()
+            .await
+            .ok_or(Error::ErrNoRemoteDescription)?;
+        if let Some(remote_ufrag) = remote_ice_ufrag(&remote_description)? {
+            let mismatch = fragment.media_fragments.iter().any(|media_fragment| {
+                media_fragment
+                    .ice_ufrag(fragment)
+                    .map_or(false, |ufrag| ufrag != remote_ufrag)
+            });
+            if mismatch {
+                return Err(Error::ErrIceCandidateUfragMismatch);
+            }
+        }
+
+        for candidate in RTCIceCandidateInit::from_sdp_fragment(fragment) {
+            self.add_ice_candidate(candidate).await?;
+        }
+        Ok(())
+    }
+
+    /// create_ice_fragment returns the SDP fragment (RFC 8840) trickling local
+    /// candidates to the remote peer, with the ICE credentials of the current local
+    /// description, so that fragments sent after an ICE restart carry the new ones.
+    pub async fn create_ice_fragment(
+        &self,
+        candidates: &[RTCIceCandidateInit],
+        end_of_candidates: bool,
+    ) -> Result<SdpFragment> {
+        let local_description = self
+            .local_description()
+            .await
+            .ok_or(Error::ErrNoLocalDescription)?;
+        let parsed = match local_description.parsed {
+            Some(parsed) => parsed,
+            None => local_description.unmarshal()?,
+        };
+        Ok(RTCIceCandidateInit::to_sdp_fragment(
+            candidates,
+            &parsed,
+            end_of_candidates,
+        ))
+    }
+
     /// ice_connection_state returns the ICE connection state of the
     /// PeerConnection instance.
     pub fn ice_connection_state(&self) -> RTCIceConnectionState {
@@ -1789,15 +1852,19 @@ impl RTCPeerConnection {
             return Err(Error::ErrConnectionClosed);
         }
 
-        let direction = init
-            .map(|init| init.direction)
-            .unwrap_or(RTCRtpTransceiverDirection::Sendrecv);
+        let (direction, send_encodings) = init
+            .map(|init| (init.direction, init.send_encodings))
+            .unwrap_or((RTCRtpTransceiverDirection::Sendrecv, vec![]));
 
         let t = self
             .internal
             .new_transceiver_from_track(direction, track)
             .await?;
 
+        // Send encodings with rids configure the simulcast layers of the sender, each
+        // fed by a track added with RTCRtpSender::add_encoding
+        t.sender().await.set_send_encodings(&send_encodings).await?;
+
         self.internal.add_rtp_transceiver(Arc::clone(&t)).await;
 
         Ok(t)
@@ -1853,6 +1920,11 @@ impl RTCPeerConnection {
 
             // https://w3c.github.io/webrtc-pc/#peer-to-peer-data-api (Step #12)
             params.negotiated = options.negotiated;
+
+            // https://w3c.github.io/webrtc-priority/#rtcdatachannel-extensions
+            if let Some(priority) = options.priority {
+                params.priority = priority;
+            }
         }
 
         let d = Arc::new(RTCDataChannel::new(
@@ -1,8 +1,10 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::AtomicIsize;
 use std::sync::Weak;
+use std::time::Duration;
 
 use arc_swap::ArcSwapOption;
+use media::io::jitter_buffer::JitterBufferStats;
 use smol_str::SmolStr;
 use tokio::time::Instant;
 use util::Unmarshal;
@@ -11,9 +13,11 @@ use super::*;
 use crate::rtp_transceiver::create_stream_info;
 use crate::stats::stats_collector::StatsCollector;
 use crate::stats::{
-    InboundRTPStats, OutboundRTPStats, RTCStatsType, RemoteInboundRTPStats, RemoteOutboundRTPStats,
+    InboundRTPStats, MediaPlayoutStats, MediaSourceStats, OutboundRTPStats,
+    RTCQualityLimitationReason, RTCStatsType, RemoteInboundRTPStats, RemoteOutboundRTPStats,
     StatsReportType,
 };
+use crate::track::track_local::track_local_static_sample::TrackLocalStaticSample;
 use crate::track::TrackStream;
 use crate::{SDES_REPAIR_RTP_STREAM_ID_URI, SDP_ATTRIBUTE_RID};
 
@@ -980,7 +984,13 @@ impl PeerConnectionInternal {
             params.codecs[0].capability.clone(),
             &params.header_extensions,
         );
-        let (rtp_read_stream, rtp_interceptor, rtcp_read_stream, rtcp_interceptor) = self
+        let (
+            rtp_read_stream,
+            rtp_interceptor,
+            rtcp_read_stream,
+            rtcp_interceptor,
+            repair_packets_tx,
+        ) = self
             .dtls_transport
             .streams_for_ssrc(ssrc, &stream_info, &icpr)
             .await?;
@@ -1022,6 +1032,7 @@ impl PeerConnectionInternal {
                                 rtp_interceptor: Some(rtp_interceptor),
                                 rtcp_read_stream: Some(rtcp_read_stream),
                                 rtcp_interceptor: Some(rtcp_interceptor),
+                                repair_packets_tx: None,
                             },
                         )
                         .await;
@@ -1037,6 +1048,7 @@ impl PeerConnectionInternal {
                             rtp_interceptor: Some(rtp_interceptor),
                             rtcp_read_stream: Some(rtcp_read_stream),
                             rtcp_interceptor: Some(rtcp_interceptor),
+                            repair_packets_tx: Some(repair_packets_tx),
                         },
                     )
                     .await?;
@@ -1068,8 +1080,8 @@ impl PeerConnectionInternal {
         on_track_handler: Arc<ArcSwapOption<Mutex<OnTrackHdlrFn>>>,
     ) {
         receiver.start(incoming).await;
-        for t in receiver.tracks().await {
-            if t.ssrc() == 0 {
+        for track in receiver.tracks().await {
+            if track.ssrc() == 0 {
                 return;
             }
 
@@ -1077,31 +1089,29 @@ impl PeerConnectionInternal {
             let transceiver = Arc::clone(&transceiver);
             let on_track_handler = Arc::clone(&on_track_handler);
             tokio::spawn(async move {
-                if let Some(track) = receiver.track().await {
-                    let mut b = vec![0u8; receive_mtu];
-                    let pkt = match track.peek(&mut b).await {
-                        Ok((pkt, _)) => pkt,
-                        Err(err) => {
-                            log::warn!(
-                                "Could not determine PayloadType for SSRC {} ({})",
-                                track.ssrc(),
-                                err
-                            );
-                            return;
-                        }
-                    };
-
-                    if let Err(err) = track.check_and_update_track(&pkt).await {
+                let mut b = vec![0u8; receive_mtu];
+                let pkt = match track.peek(&mut b).await {
+                    Ok((pkt, _)) => pkt,
+                    Err(err) => {
                         log::warn!(
-                            "Failed to set codec settings for track SSRC {} ({})",
+                            "Could not determine PayloadType for SSRC {} ({})",
                             track.ssrc(),
                             err
                         );
                         return;
                     }
+                };
 
-                    RTCPeerConnection::do_track(on_track_handler, track, receiver, transceiver);
+                if let Err(err) = track.check_and_update_track(&pkt).await {
+                    log::warn!(
+                        "Failed to set codec settings for track SSRC {} ({})",
+                        track.ssrc(),
+                        err
+                    );
+                    return;
                 }
+
+                RTCPeerConnection::do_track(on_track_handler, track, receiver, transceiver);
             });
         }
     }
@@ -1186,7 +1196,6 @@ impl PeerConnectionInternal {
 
         tokio::join!(
             self.ice_gatherer.collect_stats(&collector),
-            self.ice_transport.collect_stats(&collector),
             self.sctp_transport.collect_stats(&collector, stats_id),
             self.dtls_transport.collect_stats(&collector),
             self.media_engine.collect_stats(&collector),
@@ -1208,6 +1217,10 @@ impl PeerConnectionInternal {
             mid: SmolStr,
             track_id: String,
             kind: &'static str,
+            codec_id: Option<String>,
+            clock_rate: u32,
+            jitter_buffer_stats: Option<JitterBufferStats>,
+            decoded_frames: Option<(u64, Duration)>,
         }
         let mut track_infos = vec![];
         for transeiver in transceivers {
@@ -1224,11 +1237,16 @@ impl PeerConnectionInternal {
                         RTPCodecType::Video => "video",
                     };
 
+                    let codec = track.codec();
                     track_infos.push(TrackInfo {
                         ssrc: track.ssrc(),
                         mid: mid.clone(),
                         track_id,
                         kind,
+                        codec_id: self.media_engine.codec_stats_id(&codec),
+                        clock_rate: codec.capability.clock_rate,
+                        jitter_buffer_stats: track.jitter_buffer_stats(),
+                        decoded_frames: track.decoded_frames(),
                     });
                 }
             }
@@ -1246,6 +1264,37 @@ impl PeerConnectionInternal {
             let kind = info.kind;
 
             let id = format!("RTCInboundRTP{}Stream_{}", capitalize(kind), ssrc);
+            let jitter_buffer_stats = info.jitter_buffer_stats;
+            let is_video = kind == "video";
+
+            // The jitter buffer of an audio track plays it out.
+            let playout_id = match jitter_buffer_stats {
+                Some(jitter_buffer_stats) if !is_video => {
+                    let playout_id = format!("RTCMediaPlayout_{ssrc}");
+                    let synthesized_samples_duration = if info.clock_rate != 0 {
+                        jitter_buffer_stats.concealed_samples as f64 / info.clock_rate as f64
+                    } else {
+                        0.0
+                    };
+                    collector.insert(
+                        playout_id.clone(),
+                        StatsReportType::MediaPlayout(MediaPlayoutStats {
+                            timestamp: Instant::now(),
+                            stats_type: RTCStatsType::MediaPlayout,
+                            id: playout_id.clone(),
+                            kind,
+                            synthesized_samples_duration,
+                            synthesized_samples_events: jitter_buffer_stats.concealment_events,
+                            total_playout_delay: jitter_buffer_stats
+                                .jitter_buffer_delay
+                                .as_secs_f64(),
+                        }),
+                    );
+                    Some(playout_id)
+                }
+                _ => None,
+            };
+
             let (
                 packets_received,
                 header_bytes_received,
@@ -1280,9 +1329,23 @@ impl PeerConnectionInternal {
                     id: id.clone(),
                     ssrc,
                     kind,
+                    transport_id: "ice_transport".to_owned(),
+                    codec_id: info.codec_id,
                     packets_received,
+                    packets_lost: stats.packets_lost(),
+                    jitter: jitter_buffer_stats.map(|s| s.jitter.as_secs_f64()),
                     track_identifier: info.track_id,
                     mid: info.mid,
+                    playout_id,
+                    frames_received: is_video.then(|| stats.frames_received()),
+                    frames_decoded: info
+                        .decoded_frames
+                        .filter(|_| is_video)
+                        .map(|(frames, _)| frames),
+                    total_decode_time: info
+                        .decoded_frames
+                        .filter(|_| is_video)
+                        .map(|(_, decode_time)| decode_time.as_secs_f64()),
                     last_packet_received_timestamp,
                     header_bytes_received,
                     bytes_received,
@@ -1290,6 +1353,21 @@ impl PeerConnectionInternal {
 
                     fir_count: (info.kind == "video").then(|| stats.firs_sent()),
                     pli_count: (info.kind == "video").then(|| stats.plis_sent()),
+                    packets_discarded: jitter_buffer_stats.map(|s| s.packets_discarded),
+                    jitter_buffer_delay: jitter_buffer_stats
+                        .map(|s| s.jitter_buffer_delay.as_secs_f64()),
+                    jitter_buffer_target_delay: jitter_buffer_stats
+                        .map(|s| s.jitter_buffer_target_delay.as_secs_f64()),
+                    jitter_buffer_emitted_count: jitter_buffer_stats
+                        .map(|s| s.jitter_buffer_emitted_count),
+                    jitter_buffer_minimum_delay: jitter_buffer_stats
+                        .map(|s| s.jitter_buffer_minimum_delay.as_secs_f64()),
+                    concealed_samples: jitter_buffer_stats
+                        .filter(|_| !is_video)
+                        .map(|s| s.concealed_samples),
+                    concealment_events: jitter_buffer_stats
+                        .filter(|_| !is_video)
+                        .map(|s| s.concealment_events),
                 }),
             );
 
@@ -1328,11 +1406,17 @@ impl PeerConnectionInternal {
     ) {
         // TODO: There's a lot of await points here that could run concurrently with `futures::join_all`.
         struct TrackInfo {
+            track: Arc<dyn TrackLocal + Send + Sync>,
             track_id: String,
             ssrc: SSRC,
             mid: SmolStr,
             rid: Option<SmolStr>,
             kind: &'static str,
+            codec_id: Option<String>,
+            quality_limitation: Option<(
+                RTCQualityLimitationReason,
+                HashMap<RTCQualityLimitationReason, f64>,
+            )>,
         }
         let mut track_infos = vec![];
         for transceiver in transceivers {
@@ -1343,25 +1427,44 @@ impl PeerConnectionInternal {
                 None => continue,
             };
 
-            let track = match sender.track().await {
-                Some(track) => track,
-                None => continue,
+            let quality_limitation = {
+                let quality_limitation = sender.quality_limitation.lock();
+                (
+                    quality_limitation.reason(),
+                    quality_limitation.durations(Instant::now()),
+                )
             };
 
-            let track_id = track.id().to_string();
-            let kind = match track.kind() {
-                RTPCodecType::Unspecified => continue,
-                RTPCodecType::Audio => "audio",
-                RTPCodecType::Video => "video",
-            };
+            let track_encodings = sender.track_encodings.lock().await;
+            for encoding in track_encodings.iter() {
+                let track = match &encoding.track {
+                    Some(track) => track,
+                    None => continue,
+                };
 
-            track_infos.push(TrackInfo {
-                track_id,
-                ssrc: sender.ssrc,
-                mid,
-                rid: None,
-                kind,
-            });
+                let track_id = track.id().to_string();
+                let kind = match track.kind() {
+                    RTPCodecType::Unspecified => continue,
+                    RTPCodecType::Audio => "audio",
+                    RTPCodecType::Video => "video",
+                };
+
+                track_infos.push(TrackInfo {
+                    track: Arc::clone(track),
+                    track_id,
+                    ssrc: encoding.ssrc,
+                    mid: mid.clone(),
+                    rid: (!encoding.rid.is_empty()).then(|| encoding.rid.clone()),
+                    kind,
+                    codec_id: encoding
+                        .context
+                        .params
+                        .codecs
+                        .first()
+                        .and_then(|codec| self.media_engine.codec_stats_id(codec)),
+                    quality_limitation: (kind == "video").then(|| quality_limitation.clone()),
+                });
+            }
         }
 
         let stream_stats = self
@@ -1405,13 +1508,53 @@ impl PeerConnectionInternal {
             );
 
             let TrackInfo {
+                track,
                 mid,
                 ssrc,
                 rid,
                 kind,
                 track_id: track_identifier,
+                codec_id,
+                quality_limitation,
             } = info;
 
+            // RTCMediaSourceStats, the samples written are only known for tracks written
+            // with samples.
+            let media_source_id = match &rid {
+                Some(rid) => format!(
+                    "RTCMediaSource{}_{}_{}",
+                    capitalize(kind),
+                    track_identifier,
+                    rid
+                ),
+                None => format!("RTCMediaSource{}_{}", capitalize(kind), track_identifier),
+            };
+            let samples_written = match track.as_any().downcast_ref::<TrackLocalStaticSample>() {
+                Some(track) => Some(track.samples_written().await),
+                None => None,
+            };
+            collector.insert(
+                media_source_id.clone(),
+                StatsReportType::MediaSource(MediaSourceStats {
+                    timestamp: Instant::now(),
+                    stats_type: RTCStatsType::MediaSource,
+                    id: media_source_id.clone(),
+                    track_identifier: track_identifier.clone(),
+                    kind,
+                    total_samples_duration: samples_written
+                        .filter(|_| kind == "audio")
+                        .map(|(_, duration)| duration.as_secs_f64()),
+                    frames: samples_written
+                        .filter(|_| kind == "video")
+                        .map(|(frames, _)| frames),
+                }),
+            );
+            let (quality_limitation_reason, quality_limitation_durations) = match quality_limitation
+            {
+                Some((reason, durations)) => (Some(reason), Some(durations)),
+                None => (None, None),
+            };
+
             collector.insert(
                 id.clone(),
                 crate::stats::StatsReportType::OutboundRTP(OutboundRTPStats {
@@ -1421,11 +1564,17 @@ impl PeerConnectionInternal {
                     id: id.clone(),
                     ssrc,
                     kind,
+                    transport_id: "ice_transport".to_owned(),
+                    codec_id,
                     packets_sent,
                     mid,
+                    media_source_id,
                     rid,
                     header_bytes_sent,
+                    frames_sent: (kind == "video").then(|| stats.frames_sent()),
                     bytes_sent,
+                    quality_limitation_reason,
+                    quality_limitation_durations,
                     nack_count,
 
                     fir_count: (info.kind == "video").then(|| stats.firs_received()),
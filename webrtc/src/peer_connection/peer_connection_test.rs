@@ -17,7 +17,7 @@ use crate::ice_transport::ice_candidate_pair::RTCIceCandidatePair;
 use crate::ice_transport::ice_server::RTCIceServer;
 use crate::peer_connection::configuration::RTCConfiguration;
 use crate::rtp_transceiver::rtp_codec::RTCRtpCodecCapability;
-use crate::stats::StatsReportType;
+use crate::stats::{RTCQualityLimitationReason, StatsReportType};
 use crate::track::track_local::track_local_static_sample::TrackLocalStaticSample;
 use crate::Error;
 
@@ -339,6 +339,9 @@ async fn test_get_stats() -> Result<()> {
     )
     .await;
 
+    pc_offer.get_senders().await[0]
+        .set_quality_limitation_reason(RTCQualityLimitationReason::Bandwidth);
+
     let offer_stats = pc_offer.get_stats().await;
     assert!(!offer_stats.reports.is_empty());
 
@@ -346,6 +349,20 @@ async fn test_get_stats() -> Result<()> {
         Some(StatsReportType::Transport(ice_transport_stats)) => {
             assert!(ice_transport_stats.bytes_received > 0);
             assert!(ice_transport_stats.bytes_sent > 0);
+            assert_eq!(ice_transport_stats.ice_role, RTCIceRole::Controlling);
+            assert!(ice_transport_stats.selected_candidate_pair_id.is_some());
+            assert_eq!(
+                ice_transport_stats.dtls_state,
+                RTCDtlsTransportState::Connected
+            );
+            assert!(ice_transport_stats.dtls_role.is_some());
+            assert!(ice_transport_stats.local_certificate_id.is_some());
+            assert!(ice_transport_stats.tls_version.is_some());
+            assert!(ice_transport_stats.dtls_cipher.is_some());
+            assert_eq!(
+                ice_transport_stats.srtp_cipher.as_deref(),
+                Some("AEAD_AES_128_GCM")
+            );
         }
         Some(_other) => panic!("found the wrong type"),
         None => panic!("missed it"),
@@ -362,6 +379,20 @@ async fn test_get_stats() -> Result<()> {
     assert_eq!(outbound_stats.kind, "video");
     assert_eq!(outbound_stats.bytes_sent, 8);
     assert_eq!(outbound_stats.header_bytes_sent, 12);
+    assert_eq!(outbound_stats.frames_sent, Some(1));
+    assert!(outbound_stats.codec_id.is_some());
+    assert_eq!(
+        outbound_stats.quality_limitation_reason,
+        Some(RTCQualityLimitationReason::Bandwidth)
+    );
+    match offer_stats.reports.get(&outbound_stats.media_source_id) {
+        Some(StatsReportType::MediaSource(media_source_stats)) => {
+            assert_eq!(media_source_stats.track_identifier, "video");
+            assert!(media_source_stats.frames.unwrap_or_default() >= 1);
+            assert_eq!(media_source_stats.total_samples_duration, None);
+        }
+        _ => panic!("Should have produced a media source stat"),
+    }
 
     let answer_stats = pc_answer.get_stats().await;
     let inbound_stats = answer_stats
@@ -376,6 +407,11 @@ async fn test_get_stats() -> Result<()> {
     assert_eq!(inbound_stats.kind, "video");
     assert_eq!(inbound_stats.bytes_received, 8);
     assert_eq!(inbound_stats.header_bytes_received, 12);
+    assert_eq!(inbound_stats.packets_lost, 0);
+    assert_eq!(inbound_stats.frames_received, Some(1));
+    assert_eq!(inbound_stats.frames_decoded, None);
+    assert_eq!(inbound_stats.playout_id, None);
+    assert!(inbound_stats.codec_id.is_some());
 
     close_pair_now(&pc_offer, &pc_answer).await;
 
@@ -421,3 +457,51 @@ async fn peer() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_peer_connection_ice_fragment() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let api = APIBuilder::new().with_media_engine(m).build();
+
+    let (mut pc_offer, mut pc_answer) = new_pair(&api).await?;
+    assert_eq!(
+        pc_offer.create_ice_fragment(&[], true).await.err(),
+        Some(Error::ErrNoLocalDescription)
+    );
+    signal_pair(&mut pc_offer, &mut pc_answer).await?;
+
+    let candidate = RTCIceCandidateInit {
+        candidate: "candidate:1 1 udp 2130706431 192.0.2.1 5000 typ host".to_owned(),
+        sdp_mid: Some("0".to_owned()),
+        sdp_mline_index: Some(0),
+        username_fragment: None,
+    };
+    let fragment = pc_offer
+        .create_ice_fragment(std::slice::from_ref(&candidate), true)
+        .await?;
+    let local_ufrag = pc_offer
+        .local_description()
+        .await
+        .and_then(|desc| desc.parsed)
+        .and_then(|desc| extract_ice_credentials(&desc).0);
+    assert!(local_ufrag.is_some());
+    assert_eq!(fragment.ice_ufrag, local_ufrag);
+    assert_eq!(fragment.media_fragments.len(), 1);
+    assert!(fragment.media_fragments[0].end_of_candidates);
+
+    pc_answer.add_ice_fragment(&fragment).await?;
+
+    // Candidates of other credentials are rejected.
+    let other = RTCIceCandidateInit {
+        username_fragment: Some("other".to_owned()),
+        ..candidate
+    };
+    assert_eq!(
+        pc_answer.add_ice_candidate(other).await,
+        Err(Error::ErrIceCandidateUfragMismatch)
+    );
+
+    close_pair_now(&pc_offer, &pc_answer).await;
+    Ok(())
+}
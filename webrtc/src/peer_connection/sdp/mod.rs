@@ -9,7 +9,8 @@ use crate::ice_transport::ice_gatherer::RTCIceGatherer;
 use crate::ice_transport::ice_gathering_state::RTCIceGatheringState;
 use crate::ice_transport::ice_parameters::RTCIceParameters;
 use crate::rtp_transceiver::rtp_codec::{
-    RTCRtpCodecCapability, RTCRtpCodecParameters, RTPCodecType,
+    codec_rtx_apt, find_fec_payload_type, RTCRtpCodecCapability, RTCRtpCodecParameters,
+    RTPCodecType,
 };
 use crate::rtp_transceiver::rtp_transceiver_direction::RTCRtpTransceiverDirection;
 use crate::rtp_transceiver::{PayloadType, RTCPFeedback, RTCRtpTransceiver, SSRC};
@@ -24,6 +25,7 @@ use std::sync::Arc;
 
 use ice::candidate::candidate_base::unmarshal_candidate;
 use ice::candidate::Candidate;
+use sdp::attribute::{Simulcast, Ssrc, SsrcGroup, TypedAttribute};
 use sdp::description::common::{Address, ConnectionInformation};
 use sdp::description::media::{MediaDescription, MediaName, RangedPort};
 use sdp::description::session::*;
@@ -45,6 +47,7 @@ pub(crate) struct TrackDetails {
     pub(crate) id: String,
     pub(crate) ssrcs: Vec<SSRC>,
     pub(crate) repair_ssrc: SSRC,
+    pub(crate) fec_ssrc: SSRC,
     pub(crate) rids: Vec<SmolStr>,
 }
 
@@ -76,9 +79,10 @@ pub(crate) fn track_details_from_sdp(
     for media in &s.media_descriptions {
         let mut tracks_in_media_section = vec![];
         let mut rtx_repair_flows = HashMap::new();
+        let mut fec_repair_flows = HashMap::new();
 
-        let mut stream_id = "";
-        let mut track_id = "";
+        let mut stream_id = String::new();
+        let mut track_id = String::new();
 
         // If media section is recvonly or inactive skip
         if media.attribute(ATTR_KEY_RECV_ONLY).is_some()
@@ -100,36 +104,39 @@ pub(crate) fn track_details_from_sdp(
         for attr in &media.attributes {
             match attr.key.as_str() {
                 ATTR_KEY_SSRCGROUP => {
-                    if let Some(value) = &attr.value {
-                        let split: Vec<&str> = value.split(' ').collect();
-                        if split[0] == SEMANTIC_TOKEN_FLOW_IDENTIFICATION {
-                            // Add rtx ssrcs to blacklist, to avoid adding them as tracks
-                            // Essentially lines like `a=ssrc-group:FID 2231627014 632943048` are processed by this section
-                            // as this declares that the second SSRC (632943048) is a rtx repair flow (RFC4588) for the first
-                            // (2231627014) as specified in RFC5576
-                            if split.len() == 3 {
-                                let base_ssrc = match split[1].parse::<u32>() {
-                                    Ok(ssrc) => ssrc,
-                                    Err(err) => {
-                                        log::warn!("Failed to parse SSRC: {}", err);
-                                        continue;
-                                    }
-                                };
-                                let rtx_repair_flow = match split[2].parse::<u32>() {
-                                    Ok(n) => n,
-                                    Err(err) => {
-                                        log::warn!("Failed to parse SSRC: {}", err);
-                                        continue;
-                                    }
-                                };
-                                rtx_repair_flows.insert(rtx_repair_flow, base_ssrc);
-                                // Remove if rtx was added as track before
-                                filter_track_with_ssrc(
-                                    &mut tracks_in_media_section,
-                                    rtx_repair_flow as SSRC,
-                                );
-                            }
+                    let group = match SsrcGroup::from_attribute(attr) {
+                        Ok(group) => group,
+                        Err(err) => {
+                            log::warn!("Failed to parse SSRC group: {}", err);
+                            continue;
+                        }
+                    };
+                    match (group.semantics.as_str(), group.ssrcs.as_slice()) {
+                        // Lines like `a=ssrc-group:FEC-FR 2231627014 1730296438` declare that the second
+                        // SSRC is a FlexFEC repair flow for the first as specified in RFC5956
+                        (
+                            SEMANTIC_TOKEN_FORWARD_ERROR_CORRECTION_FRAMEWORK,
+                            &[base_ssrc, fec_repair_flow],
+                        ) => {
+                            fec_repair_flows.insert(fec_repair_flow, base_ssrc);
+                            filter_track_with_ssrc(
+                                &mut tracks_in_media_section,
+                                fec_repair_flow as SSRC,
+                            );
                         }
+                        // Add rtx ssrcs to blacklist, to avoid adding them as tracks
+                        // Essentially lines like `a=ssrc-group:FID 2231627014 632943048` are processed by this section
+                        // as this declares that the second SSRC (632943048) is a rtx repair flow (RFC4588) for the first
+                        // (2231627014) as specified in RFC5576
+                        (SEMANTIC_TOKEN_FLOW_IDENTIFICATION, &[base_ssrc, rtx_repair_flow]) => {
+                            rtx_repair_flows.insert(rtx_repair_flow, base_ssrc);
+                            // Remove if rtx was added as track before
+                            filter_track_with_ssrc(
+                                &mut tracks_in_media_section,
+                                rtx_repair_flow as SSRC,
+                            );
+                        }
+                        _ => {}
                     }
                 }
 
@@ -143,69 +150,81 @@ pub(crate) fn track_details_from_sdp(
                         if let (Some(sid), Some(tid), None) =
                             (split.next(), split.next(), split.next())
                         {
-                            stream_id = sid;
-                            track_id = tid;
+                            stream_id = sid.to_owned();
+                            track_id = tid.to_owned();
                         }
                     }
                 }
 
-                ATTR_KEY_SSRC => {
-                    if let Some(value) = &attr.value {
-                        let split: Vec<&str> = value.split(' ').collect();
-                        let ssrc = match split[0].parse::<u32>() {
-                            Ok(ssrc) => ssrc,
-                            Err(err) => {
-                                log::warn!("Failed to parse SSRC: {}", err);
-                                continue;
-                            }
-                        };
-
-                        if rtx_repair_flows.contains_key(&ssrc) {
-                            continue; // This ssrc is a RTX repair flow, ignore
-                        }
-
-                        if split.len() == 3 && split[1].starts_with("msid:") {
-                            stream_id = &split[1]["msid:".len()..];
-                            track_id = split[2];
+                ATTR_KEY_SSRC if attr.value.is_some() => {
+                    let source = match Ssrc::from_attribute(attr) {
+                        Ok(source) => source,
+                        Err(err) => {
+                            log::warn!("Failed to parse SSRC: {}", err);
+                            continue;
                         }
+                    };
+                    let ssrc = source.ssrc;
 
-                        let mut track_idx = tracks_in_media_section.len();
+                    if rtx_repair_flows.contains_key(&ssrc) {
+                        continue; // This ssrc is a RTX repair flow, ignore
+                    }
+                    if fec_repair_flows.contains_key(&ssrc) {
+                        continue; // This ssrc is a FEC repair flow, ignore
+                    }
 
-                        for (i, t) in tracks_in_media_section.iter().enumerate() {
-                            if t.ssrcs.contains(&ssrc) {
-                                track_idx = i;
-                                //TODO: no break?
-                            }
+                    if let (ATTR_KEY_MSID, Some(value)) = (source.attribute.as_str(), &source.value)
+                    {
+                        if let Some((sid, tid)) =
+                            value.split_once(' ').filter(|(_, tid)| !tid.contains(' '))
+                        {
+                            stream_id = sid.to_owned();
+                            track_id = tid.to_owned();
                         }
+                    }
+
+                    let mut track_idx = tracks_in_media_section.len();
 
-                        let mut repair_ssrc = 0;
-                        for (repair, base) in &rtx_repair_flows {
-                            if *base == ssrc {
-                                repair_ssrc = *repair;
-                                //TODO: no break?
-                            }
+                    for (i, t) in tracks_in_media_section.iter().enumerate() {
+                        if t.ssrcs.contains(&ssrc) {
+                            track_idx = i;
+                            //TODO: no break?
                         }
+                    }
 
-                        if track_idx < tracks_in_media_section.len() {
-                            tracks_in_media_section[track_idx].mid = SmolStr::from(mid_value);
-                            tracks_in_media_section[track_idx].kind = codec_type;
-                            tracks_in_media_section[track_idx].stream_id = stream_id.to_owned();
-                            tracks_in_media_section[track_idx].id = track_id.to_owned();
-                            tracks_in_media_section[track_idx].ssrcs = vec![ssrc];
-                            tracks_in_media_section[track_idx].repair_ssrc = repair_ssrc;
-                        } else {
-                            let track_details = TrackDetails {
-                                mid: SmolStr::from(mid_value),
-                                kind: codec_type,
-                                stream_id: stream_id.to_owned(),
-                                id: track_id.to_owned(),
-                                ssrcs: vec![ssrc],
-                                repair_ssrc,
-                                ..Default::default()
-                            };
-                            tracks_in_media_section.push(track_details);
+                    let mut repair_ssrc = 0;
+                    for (repair, base) in &rtx_repair_flows {
+                        if *base == ssrc {
+                            repair_ssrc = *repair;
+                            //TODO: no break?
                         }
                     }
+                    let fec_ssrc = fec_repair_flows
+                        .iter()
+                        .find(|(_, base)| **base == ssrc)
+                        .map_or(0, |(fec, _)| *fec);
+
+                    if track_idx < tracks_in_media_section.len() {
+                        tracks_in_media_section[track_idx].mid = SmolStr::from(mid_value);
+                        tracks_in_media_section[track_idx].kind = codec_type;
+                        tracks_in_media_section[track_idx].stream_id = stream_id.to_owned();
+                        tracks_in_media_section[track_idx].id = track_id.to_owned();
+                        tracks_in_media_section[track_idx].ssrcs = vec![ssrc];
+                        tracks_in_media_section[track_idx].repair_ssrc = repair_ssrc;
+                        tracks_in_media_section[track_idx].fec_ssrc = fec_ssrc;
+                    } else {
+                        let track_details = TrackDetails {
+                            mid: SmolStr::from(mid_value),
+                            kind: codec_type,
+                            stream_id: stream_id.to_owned(),
+                            id: track_id.to_owned(),
+                            ssrcs: vec![ssrc],
+                            repair_ssrc,
+                            fec_ssrc,
+                            ..Default::default()
+                        };
+                        tracks_in_media_section.push(track_details);
+                    }
                 }
                 _ => {}
             };
@@ -241,7 +260,7 @@ pub(crate) fn track_details_from_sdp(
 
 pub(crate) fn get_rids(media: &MediaDescription) -> Vec<SimulcastRid> {
     let mut rids = vec![];
-    let mut simulcast_attr: Option<String> = None;
+    let mut simulcast = None;
     for attr in &media.attributes {
         if attr.key.as_str() == SDP_ATTRIBUTE_RID {
             if let Err(err) = attr
@@ -254,30 +273,18 @@ pub(crate) fn get_rids(media: &MediaDescription) -> Vec<SimulcastRid> {
                 log::warn!("Failed to parse RID: {}", err);
             }
         } else if attr.key.as_str() == SDP_ATTRIBUTE_SIMULCAST {
-            simulcast_attr = attr.value.clone();
+            match Simulcast::from_attribute(attr) {
+                Ok(attr) => simulcast = Some(attr),
+                Err(err) => log::warn!("Failed to parse simulcast: {}", err),
+            }
         }
     }
 
-    if let Some(attr) = simulcast_attr {
-        let mut split = attr.split(' ');
-        loop {
-            let _dir = split.next();
-            let sc_str_list = split.next();
-            if let Some(list) = sc_str_list {
-                let sc_list: Vec<&str> = list.split(';').flat_map(|alt| alt.split(',')).collect();
-                for sc_id in sc_list {
-                    let (sc_id, paused) = if let Some(sc_id) = sc_id.strip_prefix('~') {
-                        (sc_id, true)
-                    } else {
-                        (sc_id, false)
-                    };
-
-                    if let Some(rid) = rids.iter_mut().find(|f| f.id == sc_id) {
-                        rid.paused = paused;
-                    }
-                }
-            } else {
-                break;
+    if let Some(simulcast) = simulcast {
+        let sc_ids = simulcast.send.iter().chain(&simulcast.recv).flatten();
+        for sc_id in sc_ids {
+            if let Some(rid) = rids.iter_mut().find(|f| f.id == sc_id.id) {
+                rid.paused = sc_id.paused;
             }
         }
     }
@@ -589,12 +596,71 @@ pub(crate) async fn add_transceiver_sdp(
     for mt in transceivers {
         let sender = mt.sender().await;
         if let Some(track) = sender.track().await {
-            media = media.with_media_source(
-                sender.ssrc,
-                track.stream_id().to_owned(), /* cname */
-                track.stream_id().to_owned(), /* streamLabel */
-                track.id().to_owned(),
-            );
+            let rtx_enabled = codecs.iter().any(|c| codec_rtx_apt(c).is_some());
+            let fec_enabled = find_fec_payload_type(&codecs).is_some();
+            let encodings: Vec<(SmolStr, SSRC, SSRC, SSRC)> = {
+                let track_encodings = sender.track_encodings.lock().await;
+                track_encodings
+                    .iter()
+                    .map(|e| (e.rid.clone(), e.ssrc, e.rtx_ssrc, e.fec_ssrc))
+                    .collect()
+            };
+
+            for (_, ssrc, rtx_ssrc, fec_ssrc) in &encodings {
+                // Announce the RTX stream of the sender as its repair flow (RFC 4588)
+                if rtx_enabled {
+                    media = media.with_value_attribute(
+                        ATTR_KEY_SSRCGROUP.to_owned(),
+                        format!("{SEMANTIC_TOKEN_FLOW_IDENTIFICATION} {ssrc} {rtx_ssrc}"),
+                    );
+                }
+                // Announce the FlexFEC stream of the sender as its FEC repair flow (RFC 5956)
+                if fec_enabled {
+                    media = media.with_value_attribute(
+                        ATTR_KEY_SSRCGROUP.to_owned(),
+                        format!(
+                            "{SEMANTIC_TOKEN_FORWARD_ERROR_CORRECTION_FRAMEWORK} {ssrc} {fec_ssrc}"
+                        ),
+                    );
+                }
+                media = media.with_media_source(
+                    *ssrc,
+                    track.stream_id().to_owned(), /* cname */
+                    track.stream_id().to_owned(), /* streamLabel */
+                    track.id().to_owned(),
+                );
+                if rtx_enabled {
+                    media = media.with_media_source(
+                        *rtx_ssrc,
+                        track.stream_id().to_owned(), /* cname */
+                        track.stream_id().to_owned(), /* streamLabel */
+                        track.id().to_owned(),
+                    );
+                }
+                if fec_enabled {
+                    media = media.with_media_source(
+                        *fec_ssrc,
+                        track.stream_id().to_owned(), /* cname */
+                        track.stream_id().to_owned(), /* streamLabel */
+                        track.id().to_owned(),
+                    );
+                }
+            }
+
+            // Announce the simulcast layers of the sender, unless they were already
+            // answered from the rids of the remote description
+            if encodings.len() > 1 && media_section.rid_map.is_empty() {
+                let mut send_sc_list = vec![];
+                for (rid, _, _, _) in &encodings {
+                    media = media
+                        .with_value_attribute(SDP_ATTRIBUTE_RID.to_owned(), format!("{rid} send"));
+                    send_sc_list.push(rid.to_string());
+                }
+                media = media.with_value_attribute(
+                    SDP_ATTRIBUTE_SIMULCAST.to_owned(),
+                    format!("send {}", send_sc_list.join(";")),
+                );
+            }
 
             // Send msid based on the configured track if we haven't already
             // sent on this sender. If we have sent we must keep the msid line consistent, this
@@ -950,6 +1016,31 @@ pub(crate) async fn extract_ice_details(
     Ok((remote_ufrags[0].clone(), remote_pwds[0].clone(), candidates))
 }
 
+/// extract_ice_credentials returns the ICE username fragment and password of a
+/// session description, looked up at the session level and then in the media sections.
+pub(crate) fn extract_ice_credentials(
+    desc: &SessionDescription,
+) -> (Option<String>, Option<String>) {
+    let find = |key: &str| {
+        desc.attribute(key).cloned().or_else(|| {
+            desc.media_descriptions
+                .iter()
+                .find_map(|m| m.attribute(key).flatten().map(str::to_owned))
+        })
+    };
+    (find("ice-ufrag"), find("ice-pwd"))
+}
+
+/// remote_ice_ufrag returns the ICE username fragment of a remote description.
+pub(crate) fn remote_ice_ufrag(
+    desc: &session_description::RTCSessionDescription,
+) -> Result<Option<String>> {
+    Ok(match &desc.parsed {
+        Some(parsed) => extract_ice_credentials(parsed).0,
+        None => extract_ice_credentials(&desc.unmarshal()?).0,
+    })
+}
+
 pub(crate) fn have_application_media_section(desc: &SessionDescription) -> bool {
     for m in &desc.media_descriptions {
         if m.media_name.media == MEDIA_SECTION_APPLICATION {
@@ -322,6 +322,10 @@ fn test_track_details_from_sdp() -> Result<()> {
                             key: "ssrc-group".to_owned(),
                             value: Some("FID 3000 4000".to_owned()),
                         },
+                        Attribute {
+                            key: "ssrc-group".to_owned(),
+                            value: Some("FEC-FR 3000 6000".to_owned()),
+                        },
                         Attribute {
                             key: "ssrc".to_owned(),
                             value: Some("3000 msid:video_trk_label video_trk_guid".to_owned()),
@@ -330,6 +334,10 @@ fn test_track_details_from_sdp() -> Result<()> {
                             key: "ssrc".to_owned(),
                             value: Some("4000 msid:rtx_trk_label rtx_trck_guid".to_owned()),
                         },
+                        Attribute {
+                            key: "ssrc".to_owned(),
+                            value: Some("6000 msid:video_trk_label video_trk_guid".to_owned()),
+                        },
                     ],
                     ..Default::default()
                 },
@@ -395,12 +403,17 @@ fn test_track_details_from_sdp() -> Result<()> {
             assert_eq!(track.kind, RTPCodecType::Video);
             assert_eq!(track.ssrcs[0], 3000);
             assert_eq!(track.stream_id, "video_trk_label");
+            assert_eq!(track.repair_ssrc, 4000);
+            assert_eq!(track.fec_ssrc, 6000);
         } else {
             panic!("missing video track with ssrc:3000");
         }
         if track_details_for_ssrc(&tracks, 4000).is_some() {
             panic!("got the rtx track ssrc:3000 which should have been skipped");
         }
+        if track_details_for_ssrc(&tracks, 6000).is_some() {
+            panic!("got the fec track ssrc:6000 which should have been skipped");
+        }
         if let Some(track) = track_details_for_ssrc(&tracks, 5000) {
             assert_eq!(track.kind, RTPCodecType::Video);
             assert_eq!(track.ssrcs[0], 5000);
@@ -0,0 +1,88 @@
+use super::*;
+
+#[test]
+fn test_h265_fmtp_parse() {
+    let f = parse(
+        "video/H265",
+        "level-id=93;profile-id=1;tier-flag=0;tx-mode=SRST",
+    );
+    assert_eq!(f.mime_type(), "video/h265");
+
+    let expected: Box<dyn Fmtp> = Box::new(H265Fmtp {
+        parameters: [
+            ("level-id".to_owned(), "93".to_owned()),
+            ("profile-id".to_owned(), "1".to_owned()),
+            ("tier-flag".to_owned(), "0".to_owned()),
+            ("tx-mode".to_owned(), "SRST".to_owned()),
+        ]
+        .iter()
+        .cloned()
+        .collect(),
+    });
+    assert_eq!(&f, &expected);
+    assert_eq!(f.parameter("level-id"), Some(&"93".to_owned()));
+}
+
+#[test]
+fn test_h265_fmtp_compare() {
+    let tests = vec![
+        (
+            "Equal",
+            "level-id=93;profile-id=1;tier-flag=0;tx-mode=SRST",
+            "level-id=93;profile-id=1;tier-flag=0;tx-mode=SRST",
+            true,
+        ),
+        (
+            "EqualWithDefaults",
+            "level-id=93;profile-id=1;tier-flag=0;tx-mode=SRST",
+            "",
+            true,
+        ),
+        (
+            "DifferentLevelIDs",
+            "level-id=93;profile-id=1",
+            "level-id=180;profile-id=1",
+            true,
+        ),
+        (
+            "EqualWithCase",
+            "profile-id=2;tx-mode=SRST",
+            "PROFILE-ID=2;tx-mode=srst",
+            true,
+        ),
+        (
+            "DifferentProfileIDs",
+            "level-id=93;profile-id=1",
+            "level-id=93;profile-id=2",
+            false,
+        ),
+        ("DefaultProfileID", "profile-id=2", "", false),
+        (
+            "DifferentTierFlags",
+            "profile-id=1;tier-flag=0",
+            "profile-id=1;tier-flag=1",
+            false,
+        ),
+        (
+            "DifferentProfileSpaces",
+            "profile-space=0;profile-id=1",
+            "profile-space=1;profile-id=1",
+            false,
+        ),
+        ("DifferentTxModes", "tx-mode=SRST", "tx-mode=MRST", false),
+        ("InvalidProfileID", "profile-id=1", "profile-id=main", false),
+        ("InvalidLevelID", "level-id=93", "level-id=", false),
+    ];
+
+    for (name, a, b, consist) in tests {
+        let aa = parse("video/h265", a);
+        let bb = parse("video/h265", b);
+
+        assert_eq!(aa.match_fmtp(&*bb), consist, "{name}: '{a}' and '{b}'");
+        assert_eq!(bb.match_fmtp(&*aa), consist, "{name}: '{b}' and '{a}'");
+    }
+
+    // Different codecs never match
+    let h264 = parse("video/h264", "packetization-mode=1;profile-level-id=42e01f");
+    assert!(!parse("video/h265", "").match_fmtp(&*h264));
+}
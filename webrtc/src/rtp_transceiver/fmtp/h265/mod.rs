@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod h265_test;
+
+use super::*;
+
+/// Default values of the media format parameters, RFC7798 Section 7.1
+const DEFAULT_PROFILE_SPACE: u8 = 0;
+const DEFAULT_PROFILE_ID: u8 = 1;
+const DEFAULT_TIER_FLAG: u8 = 0;
+const DEFAULT_LEVEL_ID: u8 = 93;
+const DEFAULT_TX_MODE: &str = "SRST";
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct H265Fmtp {
+    pub(crate) parameters: HashMap<String, String>,
+}
+
+impl H265Fmtp {
+    /// numeric_parameter returns the value of key, its default if it is absent or None if it
+    /// can't be parsed.
+    fn numeric_parameter(&self, key: &str, default: u8) -> Option<u8> {
+        match self.parameters.get(key) {
+            Some(value) => value.trim().parse().ok(),
+            None => Some(default),
+        }
+    }
+
+    fn tx_mode(&self) -> &str {
+        self.parameters
+            .get("tx-mode")
+            .map_or(DEFAULT_TX_MODE, |s| s.as_str())
+    }
+}
+
+impl Fmtp for H265Fmtp {
+    fn mime_type(&self) -> &str {
+        "video/h265"
+    }
+
+    /// Match returns true if h and b are compatible fmtp descriptions
+    /// Based on RFC7798 Section 7.2.2:
+    ///   The parameters identifying a media format configuration for H.265
+    ///   are profile-space, tier-flag, profile-id, profile-compatibility-
+    ///   indicator, interop-constraints, and level-id.  These media format
+    ///   configuration parameters (except level-id) MUST be used
+    ///   symmetrically; that is, the answerer MUST either maintain all
+    ///   configuration parameters or remove the media format (payload type)
+    ///   completely if one or more of the parameter values are not
+    ///   supported.
+    ///     Informative note: The requirement for symmetric use does not
+    ///     apply for level-id, and does not apply for the other bitstream
+    ///     or stream properties and capability parameters.
+    /// Absent parameters take their default values, so a level-id only has to be valid.
+    fn match_fmtp(&self, f: &dyn Fmtp) -> bool {
+        if let Some(c) = f.as_any().downcast_ref::<H265Fmtp>() {
+            for (key, default) in [
+                ("profile-space", DEFAULT_PROFILE_SPACE),
+                ("profile-id", DEFAULT_PROFILE_ID),
+                ("tier-flag", DEFAULT_TIER_FLAG),
+            ] {
+                let hvalue = match self.numeric_parameter(key, default) {
+                    Some(v) => v,
+                    None => return false,
+                };
+                let cvalue = match c.numeric_parameter(key, default) {
+                    Some(v) => v,
+                    None => return false,
+                };
+                if hvalue != cvalue {
+                    return false;
+                }
+            }
+
+            // test level-id
+            if self
+                .numeric_parameter("level-id", DEFAULT_LEVEL_ID)
+                .is_none()
+                || c.numeric_parameter("level-id", DEFAULT_LEVEL_ID).is_none()
+            {
+                return false;
+            }
+
+            // test tx-mode
+            self.tx_mode().eq_ignore_ascii_case(c.tx_mode())
+        } else {
+            false
+        }
+    }
+
+    fn parameter(&self, key: &str) -> Option<&String> {
+        self.parameters.get(key)
+    }
+
+    fn equal(&self, other: &dyn Fmtp) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<H265Fmtp>()
+            .map_or(false, |a| self == a)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -1,5 +1,6 @@
 pub(crate) mod generic;
 pub(crate) mod h264;
+pub(crate) mod h265;
 
 use std::any::Any;
 use std::collections::HashMap;
@@ -7,6 +8,7 @@ use std::fmt;
 
 use crate::rtp_transceiver::fmtp::generic::GenericFmtp;
 use crate::rtp_transceiver::fmtp::h264::H264Fmtp;
+use crate::rtp_transceiver::fmtp::h265::H265Fmtp;
 
 /// Fmtp interface for implementing custom
 /// Fmtp parsers based on mime_type
@@ -49,6 +51,8 @@ pub fn parse(mime_type: &str, line: &str) -> Box<dyn Fmtp> {
 
     if mime_type.to_uppercase() == "video/h264".to_uppercase() {
         Box::new(H264Fmtp { parameters })
+    } else if mime_type.to_uppercase() == "video/h265".to_uppercase() {
+        Box::new(H265Fmtp { parameters })
     } else {
         Box::new(GenericFmtp {
             mime_type: mime_type.to_owned(),
@@ -17,6 +17,7 @@ use util::Unmarshal;
 
 use crate::api::media_engine::MediaEngine;
 use crate::error::{Error, Result};
+use crate::rtp_transceiver::priority_type::RTCPriorityType;
 use crate::rtp_transceiver::rtp_codec::*;
 use crate::rtp_transceiver::rtp_receiver::{RTCRtpReceiver, RTPReceiverInternal};
 use crate::rtp_transceiver::rtp_sender::RTCRtpSender;
@@ -24,6 +25,7 @@ use crate::rtp_transceiver::rtp_transceiver_direction::RTCRtpTransceiverDirectio
 use crate::track::track_local::TrackLocal;
 
 pub(crate) mod fmtp;
+pub mod priority_type;
 pub mod rtp_codec;
 pub mod rtp_receiver;
 pub mod rtp_sender;
@@ -88,6 +90,13 @@ pub struct RTCRtpRtxParameters {
     pub ssrc: SSRC,
 }
 
+/// RTPFecParameters dictionary contains information relating to forward error correction (FEC) settings.
+/// <https://draft.ortc.org/#dom-rtcrtpfecparameters>
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct RTCRtpFecParameters {
+    pub ssrc: SSRC,
+}
+
 /// RTPCodingParameters provides information relating to both encoding and decoding.
 /// This is a subset of the RFC since Pion WebRTC doesn't implement encoding/decoding itself
 /// <http://draft.ortc.org/#dom-rtcrtpcodingparameters>
@@ -97,6 +106,7 @@ pub struct RTCRtpCodingParameters {
     pub ssrc: SSRC,
     pub payload_type: PayloadType,
     pub rtx: RTCRtpRtxParameters,
+    pub fec: RTCRtpFecParameters,
 }
 
 /// RTPDecodingParameters provides information relating to both encoding and decoding.
@@ -104,10 +114,51 @@ pub struct RTCRtpCodingParameters {
 /// <http://draft.ortc.org/#dom-rtcrtpdecodingparameters>
 pub type RTCRtpDecodingParameters = RTCRtpCodingParameters;
 
-/// RTPEncodingParameters provides information relating to both encoding and decoding.
-/// This is a subset of the RFC since Pion WebRTC doesn't implement encoding itself
-/// <http://draft.ortc.org/#dom-rtcrtpencodingparameters>
-pub type RTCRtpEncodingParameters = RTCRtpCodingParameters;
+/// RTPEncodingParameters provides information relating to the encoding of a sent stream,
+/// a simulcast layer if the sender sends multiple encodings. The fields from `active` on
+/// can be changed without renegotiation with [`rtp_sender::RTCRtpSender::set_parameters`].
+/// <https://www.w3.org/TR/webrtc/#dom-rtcrtpencodingparameters>
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RTCRtpEncodingParameters {
+    pub rid: SmolStr,
+    pub ssrc: SSRC,
+    pub payload_type: PayloadType,
+    pub rtx: RTCRtpRtxParameters,
+    pub fec: RTCRtpFecParameters,
+
+    /// active indicates that the encoding is sent. Inactive encodings are paused.
+    pub active: bool,
+    /// max_bitrate is the maximum bitrate of the encoding in bits per second.
+    pub max_bitrate: Option<u64>,
+    /// max_framerate is the maximum number of frames per second of the encoding.
+    pub max_framerate: Option<f64>,
+    /// scale_resolution_down_by is the factor by which the resolution of a video encoding
+    /// is scaled down in each dimension, at least 1.0.
+    pub scale_resolution_down_by: Option<f64>,
+    /// priority is the priority of the encoding relative to the other streams.
+    pub priority: RTCPriorityType,
+    /// network_priority is the priority of the packets of the encoding on the network.
+    pub network_priority: RTCPriorityType,
+}
+
+impl Default for RTCRtpEncodingParameters {
+    fn default() -> Self {
+        RTCRtpEncodingParameters {
+            rid: SmolStr::default(),
+            ssrc: 0,
+            payload_type: 0,
+            rtx: RTCRtpRtxParameters::default(),
+            fec: RTCRtpFecParameters::default(),
+
+            active: true,
+            max_bitrate: None,
+            max_framerate: None,
+            scale_resolution_down_by: None,
+            priority: RTCPriorityType::default(),
+            network_priority: RTCPriorityType::default(),
+        }
+    }
+}
 
 /// RTPReceiveParameters contains the RTP stack settings used by receivers
 #[derive(Debug)]
@@ -115,9 +166,13 @@ pub struct RTCRtpReceiveParameters {
     pub encodings: Vec<RTCRtpDecodingParameters>,
 }
 
-/// RTPSendParameters contains the RTP stack settings used by receivers
-#[derive(Debug)]
+/// RTPSendParameters contains the RTP stack settings used by senders
+#[derive(Debug, Clone)]
 pub struct RTCRtpSendParameters {
+    /// transaction_id identifies the parameters returned by
+    /// [`rtp_sender::RTCRtpSender::get_parameters`], which must be given back unchanged to
+    /// [`rtp_sender::RTCRtpSender::set_parameters`].
+    pub transaction_id: String,
     pub rtp_parameters: RTCRtpParameters,
     pub encodings: Vec<RTCRtpEncodingParameters>,
 }
@@ -158,6 +213,10 @@ pub(crate) fn create_stream_info(
         attributes: Attributes::new(),
         ssrc,
         payload_type,
+        ssrc_retransmission: 0,
+        payload_type_retransmission: 0,
+        ssrc_forward_error_correction: 0,
+        payload_type_forward_error_correction: 0,
         rtp_header_extensions: header_extensions,
         mime_type: codec.mime_type,
         clock_rate: codec.clock_rate,
@@ -0,0 +1,141 @@
+use std::fmt;
+
+use data::message::message_channel_open::{
+    CHANNEL_PRIORITY_BELOW_NORMAL, CHANNEL_PRIORITY_EXTRA_HIGH, CHANNEL_PRIORITY_HIGH,
+    CHANNEL_PRIORITY_NORMAL,
+};
+use serde::{Deserialize, Serialize};
+
+/// RTCPriorityType indicates the priority of a sent stream relative to the other ones, as
+/// defined by the WebRTC Priority Control API.
+/// <https://www.w3.org/TR/webrtc-priority/#rtc-priority-type>
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RTCPriorityType {
+    #[serde(rename = "very-low")]
+    VeryLow,
+
+    /// Low is the default priority.
+    #[serde(rename = "low")]
+    #[default]
+    Low,
+
+    #[serde(rename = "medium")]
+    Medium,
+
+    #[serde(rename = "high")]
+    High,
+}
+
+const PRIORITY_TYPE_VERY_LOW_STR: &str = "very-low";
+const PRIORITY_TYPE_LOW_STR: &str = "low";
+const PRIORITY_TYPE_MEDIUM_STR: &str = "medium";
+const PRIORITY_TYPE_HIGH_STR: &str = "high";
+
+impl From<&str> for RTCPriorityType {
+    fn from(raw: &str) -> Self {
+        match raw {
+            PRIORITY_TYPE_VERY_LOW_STR => RTCPriorityType::VeryLow,
+            PRIORITY_TYPE_MEDIUM_STR => RTCPriorityType::Medium,
+            PRIORITY_TYPE_HIGH_STR => RTCPriorityType::High,
+            _ => RTCPriorityType::Low,
+        }
+    }
+}
+
+impl fmt::Display for RTCPriorityType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match *self {
+            RTCPriorityType::VeryLow => PRIORITY_TYPE_VERY_LOW_STR,
+            RTCPriorityType::Low => PRIORITY_TYPE_LOW_STR,
+            RTCPriorityType::Medium => PRIORITY_TYPE_MEDIUM_STR,
+            RTCPriorityType::High => PRIORITY_TYPE_HIGH_STR,
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl RTCPriorityType {
+    /// weight returns the relative weight of the priority, as used to share the bandwidth
+    /// between streams: each priority gets twice the share of the one below.
+    pub fn weight(&self) -> u32 {
+        match *self {
+            RTCPriorityType::VeryLow => 1,
+            RTCPriorityType::Low => 2,
+            RTCPriorityType::Medium => 4,
+            RTCPriorityType::High => 8,
+        }
+    }
+
+    /// data_channel_priority returns the priority of a data channel as sent in the
+    /// DATA_CHANNEL_OPEN message (RFC 8831 Sec 6.4).
+    pub fn data_channel_priority(&self) -> u16 {
+        match *self {
+            RTCPriorityType::VeryLow => CHANNEL_PRIORITY_BELOW_NORMAL,
+            RTCPriorityType::Low => CHANNEL_PRIORITY_NORMAL,
+            RTCPriorityType::Medium => CHANNEL_PRIORITY_HIGH,
+            RTCPriorityType::High => CHANNEL_PRIORITY_EXTRA_HIGH,
+        }
+    }
+
+    /// from_data_channel_priority maps the priority received in a DATA_CHANNEL_OPEN
+    /// message to the closest priority type.
+    pub fn from_data_channel_priority(priority: u16) -> Self {
+        if priority <= CHANNEL_PRIORITY_BELOW_NORMAL {
+            RTCPriorityType::VeryLow
+        } else if priority <= CHANNEL_PRIORITY_NORMAL {
+            RTCPriorityType::Low
+        } else if priority <= CHANNEL_PRIORITY_HIGH {
+            RTCPriorityType::Medium
+        } else {
+            RTCPriorityType::High
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_priority_type_string() {
+        let tests = vec![
+            (RTCPriorityType::VeryLow, "very-low"),
+            (RTCPriorityType::Low, "low"),
+            (RTCPriorityType::Medium, "medium"),
+            (RTCPriorityType::High, "high"),
+        ];
+
+        for (priority, expected_string) in tests {
+            assert_eq!(priority.to_string(), expected_string);
+            assert_eq!(RTCPriorityType::from(expected_string), priority);
+        }
+        assert_eq!(RTCPriorityType::from("invalid"), RTCPriorityType::Low);
+    }
+
+    #[test]
+    fn test_priority_type_data_channel_priority() {
+        for priority in [
+            RTCPriorityType::VeryLow,
+            RTCPriorityType::Low,
+            RTCPriorityType::Medium,
+            RTCPriorityType::High,
+        ] {
+            assert_eq!(
+                RTCPriorityType::from_data_channel_priority(priority.data_channel_priority()),
+                priority
+            );
+        }
+        assert_eq!(
+            RTCPriorityType::from_data_channel_priority(0),
+            RTCPriorityType::VeryLow
+        );
+        assert_eq!(
+            RTCPriorityType::from_data_channel_priority(300),
+            RTCPriorityType::Medium
+        );
+        assert_eq!(
+            RTCPriorityType::from_data_channel_priority(u16::MAX),
+            RTCPriorityType::High
+        );
+    }
+}
@@ -66,6 +66,8 @@ impl RTCRtpCodecCapability {
         let mime_type = self.mime_type.to_lowercase();
         if mime_type == MIME_TYPE_H264.to_lowercase() {
             Ok(Box::<rtp::codecs::h264::H264Payloader>::default())
+        } else if mime_type == MIME_TYPE_H265.to_lowercase() {
+            Ok(Box::<rtp::codecs::h265::H265Payloader>::default())
         } else if mime_type == MIME_TYPE_VP8.to_lowercase() {
             let mut vp8_payloader = rtp::codecs::vp8::Vp8Payloader::default();
             vp8_payloader.enable_picture_id = true;
@@ -82,6 +84,19 @@ impl RTCRtpCodecCapability {
             Ok(Box::<rtp::codecs::g7xx::G7xxPayloader>::default())
         } else if mime_type == MIME_TYPE_AV1.to_lowercase() {
             Ok(Box::<rtp::codecs::av1::Av1Payloader>::default())
+        } else if mime_type == MIME_TYPE_RED.to_lowercase() {
+            // The fmtp line lists the payload types of the blocks, like 111/111 for
+            // Opus frames followed by one redundant frame.
+            let payload_types: Vec<&str> = self.sdp_fmtp_line.split('/').collect();
+            let payload_type = payload_types[0]
+                .trim()
+                .parse::<u8>()
+                .map_err(|_| Error::ErrNoPayloaderForCodec)?;
+            Ok(Box::new(rtp::codecs::red::RedPayloader::new(
+                Box::<rtp::codecs::opus::OpusPayloader>::default(),
+                payload_type,
+                payload_types.len().max(2) - 1,
+            )))
         } else {
             Err(Error::ErrNoPayloaderForCodec)
         }
@@ -161,3 +176,43 @@ pub(crate) fn codec_parameters_fuzzy_search(
 
     (RTCRtpCodecParameters::default(), CodecMatch::None)
 }
+
+/// Returns the payload type of the codec an RTX codec retransmits, taken from
+/// its apt format parameter.
+pub(crate) fn codec_rtx_apt(codec: &RTCRtpCodecParameters) -> Option<PayloadType> {
+    if !codec
+        .capability
+        .mime_type
+        .eq_ignore_ascii_case(MIME_TYPE_RTX)
+    {
+        return None;
+    }
+
+    fmtp::parse(&codec.capability.mime_type, &codec.capability.sdp_fmtp_line)
+        .parameter("apt")
+        .and_then(|apt| apt.parse().ok())
+}
+
+/// Returns the payload type of the RTX codec retransmitting the codec with the
+/// given payload type.
+pub(crate) fn find_rtx_payload_type(
+    payload_type: PayloadType,
+    codecs: &[RTCRtpCodecParameters],
+) -> Option<PayloadType> {
+    codecs
+        .iter()
+        .find(|c| codec_rtx_apt(c) == Some(payload_type))
+        .map(|c| c.payload_type)
+}
+
+/// Returns the payload type of the FlexFEC codec, if one was negotiated.
+pub(crate) fn find_fec_payload_type(codecs: &[RTCRtpCodecParameters]) -> Option<PayloadType> {
+    codecs
+        .iter()
+        .find(|c| {
+            c.capability
+                .mime_type
+                .eq_ignore_ascii_case(MIME_TYPE_FLEXFEC03)
+        })
+        .map(|c| c.payload_type)
+}
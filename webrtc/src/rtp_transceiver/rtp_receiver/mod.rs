@@ -11,17 +11,17 @@ use log::trace;
 use smol_str::SmolStr;
 use tokio::sync::{watch, Mutex, RwLock};
 
-use crate::api::media_engine::MediaEngine;
+use crate::api::media_engine::{MediaEngine, MIME_TYPE_FLEXFEC03};
 use crate::dtls_transport::RTCDtlsTransport;
 use crate::error::{flatten_errs, Error, Result};
 use crate::peer_connection::sdp::TrackDetails;
 use crate::rtp_transceiver::rtp_codec::{
-    codec_parameters_fuzzy_search, CodecMatch, RTCRtpCodecCapability, RTCRtpCodecParameters,
-    RTCRtpParameters, RTPCodecType,
+    codec_parameters_fuzzy_search, codec_rtx_apt, find_fec_payload_type, CodecMatch,
+    RTCRtpCodecCapability, RTCRtpCodecParameters, RTCRtpParameters, RTPCodecType,
 };
 use crate::rtp_transceiver::rtp_transceiver_direction::RTCRtpTransceiverDirection;
 use crate::rtp_transceiver::{
-    create_stream_info, RTCRtpDecodingParameters, RTCRtpReceiveParameters, SSRC,
+    create_stream_info, PayloadType, RTCRtpDecodingParameters, RTCRtpReceiveParameters, SSRC,
 };
 use crate::track::track_remote::TrackRemote;
 use crate::track::{TrackStream, TrackStreams};
@@ -529,31 +529,53 @@ impl RTCRtpReceiver {
         };
 
         for encoding in &parameters.encodings {
-            let (stream_info, rtp_read_stream, rtp_interceptor, rtcp_read_stream, rtcp_interceptor) =
-                if encoding.ssrc != 0 {
-                    let stream_info = create_stream_info(
-                        "".to_owned(),
-                        encoding.ssrc,
-                        0,
-                        codec.clone(),
-                        &global_params.header_extensions,
-                    );
-                    let (rtp_read_stream, rtp_interceptor, rtcp_read_stream, rtcp_interceptor) =
-                        self.transport
-                            .streams_for_ssrc(encoding.ssrc, &stream_info, &interceptor)
-                            .await?;
-
-                    (
-                        Some(stream_info),
-                        Some(rtp_read_stream),
-                        Some(rtp_interceptor),
-                        Some(rtcp_read_stream),
-                        Some(rtcp_interceptor),
-                    )
-                } else {
-                    (None, None, None, None, None)
-                };
+            let (
+                stream_info,
+                rtp_read_stream,
+                rtp_interceptor,
+                rtcp_read_stream,
+                rtcp_interceptor,
+                repair_packets_tx,
+            ) = if encoding.ssrc != 0 {
+                let mut stream_info = create_stream_info(
+                    "".to_owned(),
+                    encoding.ssrc,
+                    0,
+                    codec.clone(),
+                    &global_params.header_extensions,
+                );
+                if encoding.fec.ssrc != 0 {
+                    if let Some(fec_payload_type) = find_fec_payload_type(&global_params.codecs) {
+                        stream_info.ssrc_forward_error_correction = encoding.fec.ssrc;
+                        stream_info.payload_type_forward_error_correction = fec_payload_type;
+                    }
+                }
+                let (
+                    rtp_read_stream,
+                    rtp_interceptor,
+                    rtcp_read_stream,
+                    rtcp_interceptor,
+                    repair_packets_tx,
+                ) = self
+                    .transport
+                    .streams_for_ssrc(encoding.ssrc, &stream_info, &interceptor)
+                    .await?;
+
+                (
+                    Some(stream_info),
+                    Some(rtp_read_stream),
+                    Some(rtp_interceptor),
+                    Some(rtcp_read_stream),
+                    Some(rtcp_interceptor),
+                    Some(repair_packets_tx),
+                )
+            } else {
+                (None, None, None, None, None, None)
+            };
 
+            let fec_ssrc = stream_info
+                .as_ref()
+                .map_or(0, |s| s.ssrc_forward_error_correction);
             let t = TrackStreams {
                 track: Arc::new(TrackRemote::new(
                     self.receive_mtu,
@@ -570,6 +592,7 @@ impl RTCRtpReceiver {
                     rtp_interceptor,
                     rtcp_read_stream,
                     rtcp_interceptor,
+                    repair_packets_tx,
                 },
 
                 repair_stream: TrackStream {
@@ -578,6 +601,15 @@ impl RTCRtpReceiver {
                     rtp_interceptor: None,
                     rtcp_read_stream: None,
                     rtcp_interceptor: None,
+                    repair_packets_tx: None,
+                },
+                fec_stream: TrackStream {
+                    stream_info: None,
+                    rtp_read_stream: None,
+                    rtp_interceptor: None,
+                    rtcp_read_stream: None,
+                    rtcp_interceptor: None,
+                    repair_packets_tx: None,
                 },
             };
 
@@ -586,6 +618,43 @@ impl RTCRtpReceiver {
                 tracks.push(t);
             };
 
+            if fec_ssrc != 0 {
+                let fec_codec = global_params
+                    .codecs
+                    .iter()
+                    .find(|c| {
+                        c.capability
+                            .mime_type
+                            .eq_ignore_ascii_case(MIME_TYPE_FLEXFEC03)
+                    })
+                    .map(|c| c.capability.clone())
+                    .unwrap_or_default();
+                let stream_info = create_stream_info(
+                    "".to_owned(),
+                    fec_ssrc,
+                    0,
+                    fec_codec,
+                    &global_params.header_extensions,
+                );
+                let (rtp_read_stream, rtp_interceptor, rtcp_read_stream, rtcp_interceptor, _) =
+                    self.transport
+                        .streams_for_ssrc(fec_ssrc, &stream_info, &interceptor)
+                        .await?;
+
+                self.receive_for_fec(
+                    fec_ssrc,
+                    TrackStream {
+                        stream_info: Some(stream_info),
+                        rtp_read_stream: Some(rtp_read_stream),
+                        rtp_interceptor: Some(rtp_interceptor),
+                        rtcp_read_stream: Some(rtcp_read_stream),
+                        rtcp_interceptor: Some(rtcp_interceptor),
+                        repair_packets_tx: None,
+                    },
+                )
+                .await?;
+            }
+
             let rtx_ssrc = encoding.rtx.ssrc;
             if rtx_ssrc != 0 {
                 let stream_info = create_stream_info(
@@ -595,10 +664,10 @@ impl RTCRtpReceiver {
                     codec.clone(),
                     &global_params.header_extensions,
                 );
-                let (rtp_read_stream, rtp_interceptor, rtcp_read_stream, rtcp_interceptor) = self
-                    .transport
-                    .streams_for_ssrc(rtx_ssrc, &stream_info, &interceptor)
-                    .await?;
+                let (rtp_read_stream, rtp_interceptor, rtcp_read_stream, rtcp_interceptor, _) =
+                    self.transport
+                        .streams_for_ssrc(rtx_ssrc, &stream_info, &interceptor)
+                        .await?;
 
                 self.receive_for_rtx(
                     rtx_ssrc,
@@ -609,6 +678,7 @@ impl RTCRtpReceiver {
                         rtp_interceptor: Some(rtp_interceptor),
                         rtcp_read_stream: Some(rtcp_read_stream),
                         rtcp_interceptor: Some(rtcp_interceptor),
+                        repair_packets_tx: None,
                     },
                 )
                 .await?;
@@ -673,6 +743,7 @@ impl RTCRtpReceiver {
             }
 
             encoding.rtx.ssrc = incoming.repair_ssrc;
+            encoding.fec.ssrc = incoming.fec_ssrc;
         }
 
         if let Err(err) = self.receive(&RTCRtpReceiveParameters { encodings }).await {
@@ -727,6 +798,18 @@ impl RTCRtpReceiver {
                     }
                 }
 
+                for fec_read_stream in [
+                    &t.fec_stream.rtcp_read_stream,
+                    &t.fec_stream.rtp_read_stream,
+                ]
+                .into_iter()
+                .flatten()
+                {
+                    if let Err(err) = fec_read_stream.close().await {
+                        errs.push(err);
+                    }
+                }
+
                 if let Some(stream_info) = &t.stream.stream_info {
                     self.internal
                         .interceptor
@@ -740,6 +823,13 @@ impl RTCRtpReceiver {
                         .unbind_remote_stream(repair_stream_info)
                         .await;
                 }
+
+                if let Some(fec_stream_info) = &t.fec_stream.stream_info {
+                    self.internal
+                        .interceptor
+                        .unbind_remote_stream(fec_stream_info)
+                        .await;
+                }
             }
         }
 
@@ -781,9 +871,10 @@ impl RTCRtpReceiver {
         Err(Error::ErrRTPReceiverForRIDTrackStreamNotFound)
     }
 
-    /// receiveForRtx starts a routine that processes the repair stream
-    /// These packets aren't exposed to the user yet, but we need to process them for
-    /// TWCC
+    /// receiveForRtx starts a routine that processes the repair stream.
+    /// RTX packets are restored to packets of the primary stream and read through
+    /// the interceptors of the primary stream, so they are returned by the reads of
+    /// its track.
     pub(crate) async fn receive_for_rtx(
         &self,
         ssrc: SSRC,
@@ -797,18 +888,77 @@ impl RTCRtpReceiver {
                 t.repair_stream = repair_stream;
 
                 let receive_mtu = self.receive_mtu;
-                let track = t.clone();
-                tokio::spawn(async move {
-                    let a = Attributes::new();
-                    let mut b = vec![0u8; receive_mtu];
-                    while let Some(repair_rtp_interceptor) = &track.repair_stream.rtp_interceptor {
+                let kind = self.kind;
+                let media_engine = Arc::clone(&self.internal.media_engine);
+                let internal = Arc::downgrade(&self.internal);
+                let track = Arc::clone(&t.track);
+                if let Some(repair_rtp_interceptor) = t.repair_stream.rtp_interceptor.clone() {
+                    tokio::spawn(async move {
+                        let a = Attributes::new();
+                        let mut b = vec![0u8; receive_mtu];
                         //TODO: cancel repair_rtp_interceptor.read gracefully
-                        //println!("repair_rtp_interceptor read begin with ssrc={}", ssrc);
-                        if repair_rtp_interceptor.read(&mut b, &a).await.is_err() {
-                            break;
+                        while let Ok((pkt, attributes)) =
+                            repair_rtp_interceptor.read(&mut b, &a).await
+                        {
+                            let apt = media_engine
+                                .get_codecs_by_kind(kind)
+                                .iter()
+                                .find(|c| c.payload_type == pkt.header.payload_type)
+                                .and_then(codec_rtx_apt);
+                            let pkt = match apt.and_then(|apt| unwrap_rtx(pkt, track.ssrc(), apt)) {
+                                Some(pkt) => pkt,
+                                None => continue,
+                            };
+
+                            // The primary stream of a simulcast track may be bound after its repair stream
+                            let repair_packets_tx = match internal.upgrade() {
+                                Some(internal) => {
+                                    let tracks = internal.tracks.read().await;
+                                    tracks
+                                        .iter()
+                                        .find(|t| t.track.tid() == track.tid())
+                                        .and_then(|t| t.stream.repair_packets_tx.clone())
+                                }
+                                None => break,
+                            };
+                            if let Some(repair_packets_tx) = repair_packets_tx {
+                                // Retransmissions are dropped if the track isn't read
+                                let _ = repair_packets_tx.try_send((pkt, attributes));
+                            }
                         }
-                    }
-                });
+                    });
+                }
+
+                return Ok(());
+            }
+        }
+
+        Err(Error::ErrRTPReceiverForRIDTrackStreamNotFound)
+    }
+
+    /// receive_for_fec starts a routine that reads the FEC stream with the given SSRC. It
+    /// is attached to the track whose primary stream is protected by it. Its packets are
+    /// consumed by the interceptors, which return the recovered packets from the reads
+    /// of the protected stream.
+    pub(crate) async fn receive_for_fec(&self, ssrc: SSRC, fec_stream: TrackStream) -> Result<()> {
+        let mut tracks = self.internal.tracks.write().await;
+        for t in &mut *tracks {
+            let protected = t
+                .stream
+                .stream_info
+                .as_ref()
+                .map_or(false, |s| s.ssrc_forward_error_correction == ssrc);
+            if ssrc != 0 && protected {
+                t.fec_stream = fec_stream;
+
+                let receive_mtu = self.receive_mtu;
+                if let Some(fec_rtp_interceptor) = t.fec_stream.rtp_interceptor.clone() {
+                    tokio::spawn(async move {
+                        let a = Attributes::new();
+                        let mut b = vec![0u8; receive_mtu];
+                        while fec_rtp_interceptor.read(&mut b, &a).await.is_ok() {}
+                    });
+                }
 
                 return Ok(());
             }
@@ -859,3 +1009,23 @@ impl RTCRtpReceiver {
         Ok(())
     }
 }
+
+/// unwrap_rtx restores the original packet of the stream from an RTX packet by
+/// taking its original sequence number from the start of the payload.
+/// Returns None for RTX packets without payload, which are sent for bandwidth probing.
+/// <https://www.rfc-editor.org/rfc/rfc4588#section-4>
+pub(crate) fn unwrap_rtx(
+    mut pkt: rtp::packet::Packet,
+    ssrc: SSRC,
+    payload_type: PayloadType,
+) -> Option<rtp::packet::Packet> {
+    if pkt.payload.len() < 2 {
+        return None;
+    }
+
+    pkt.header.sequence_number = u16::from_be_bytes([pkt.payload[0], pkt.payload[1]]);
+    pkt.header.ssrc = ssrc;
+    pkt.header.payload_type = payload_type;
+    pkt.payload = pkt.payload.slice(2..);
+    Some(pkt)
+}
@@ -1,20 +1,35 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_trait::async_trait;
 use bytes::Bytes;
+use interceptor::flexfec::FlexFecEncoder;
+use interceptor::mock::mock_builder::MockBuilder;
+use interceptor::mock::mock_interceptor::MockInterceptor;
+use interceptor::registry::Registry;
+use interceptor::stream_info::StreamInfo;
+use interceptor::{RTPReader, RTPWriter};
+use media::io::jitter_buffer::JitterBuffer;
 use media::Sample;
 use tokio::sync::mpsc;
 use tokio::time::Duration;
 use waitgroup::WaitGroup;
 
 use super::*;
+use crate::api::interceptor_registry::{configure_flexfec, configure_nack};
 use crate::api::media_engine::{MIME_TYPE_OPUS, MIME_TYPE_VP8};
+use crate::api::APIBuilder;
 use crate::error::Result;
 use crate::peer_connection::peer_connection_state::RTCPeerConnectionState;
 use crate::peer_connection::peer_connection_test::{
-    close_pair_now, create_vnet_pair, signal_pair, until_connection_state,
+    close_pair_now, create_vnet_pair, new_pair, signal_pair, until_connection_state,
 };
 use crate::rtp_transceiver::rtp_codec::RTCRtpHeaderExtensionParameters;
-use crate::rtp_transceiver::RTCPFeedback;
+use crate::rtp_transceiver::{RTCPFeedback, RTCRtpFecParameters};
+use crate::stats::StatsReportType;
+use crate::track::track_local::track_local_static_rtp::TrackLocalStaticRTP;
 use crate::track::track_local::track_local_static_sample::TrackLocalStaticSample;
-use crate::track::track_local::TrackLocal;
+use crate::track::track_local::{TrackLocal, TrackLocalWriter};
+use crate::track::track_remote::jitter_buffer_reader::JitterBufferReader;
 
 lazy_static! {
     static ref P: RTCRtpParameters = RTCRtpParameters {
@@ -229,3 +244,420 @@ async fn test_rtp_receiver_set_read_deadline() -> Result<()> {
 
     Ok(())
 }
+
+/// LossyWriter drops every fourth packet of a stream, after the protection
+/// packets have been generated for it.
+struct LossyWriter {
+    ssrc: u32,
+    count: AtomicUsize,
+    next_rtp_writer: Arc<dyn RTPWriter + Send + Sync>,
+}
+
+#[async_trait]
+impl RTPWriter for LossyWriter {
+    async fn write(
+        &self,
+        pkt: &rtp::packet::Packet,
+        a: &Attributes,
+    ) -> std::result::Result<usize, interceptor::Error> {
+        if pkt.header.ssrc == self.ssrc && self.count.fetch_add(1, Ordering::SeqCst) % 4 == 2 {
+            return Ok(pkt.payload.len());
+        }
+        self.next_rtp_writer.write(pkt, a).await
+    }
+}
+
+// Assert that packets lost on the way are recovered from the FlexFEC stream
+#[tokio::test]
+async fn test_rtp_receiver_flexfec_recovery() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let mut registry = Registry::new();
+    registry.add(Box::new(MockBuilder::new(|_| {
+        Ok(Arc::new(MockInterceptor {
+            bind_local_stream_fn: Some(Box::new(
+                |info: &StreamInfo, writer: Arc<dyn RTPWriter + Send + Sync>| {
+                    let ssrc = info.ssrc;
+                    Box::pin(async move {
+                        Arc::new(LossyWriter {
+                            ssrc,
+                            count: AtomicUsize::new(0),
+                            next_rtp_writer: writer,
+                        }) as Arc<dyn RTPWriter + Send + Sync>
+                    })
+                },
+            )),
+            ..Default::default()
+        }))
+    })));
+    let registry = configure_flexfec(
+        registry,
+        &mut m,
+        FlexFecEncoder::builder()
+            .with_num_media_packets(4)
+            .with_num_fec_packets(1),
+    )?;
+    let api = APIBuilder::new()
+        .with_media_engine(m)
+        .with_interceptor_registry(registry)
+        .build();
+
+    let (mut sender, mut receiver) = new_pair(&api).await?;
+
+    let track = Arc::new(TrackLocalStaticRTP::new(
+        RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_VP8.to_owned(),
+            ..Default::default()
+        },
+        "video".to_owned(),
+        "webrtc-rs".to_owned(),
+    ));
+    sender
+        .add_track(Arc::clone(&track) as Arc<dyn TrackLocal + Send + Sync>)
+        .await?;
+
+    let (recovered_tx, mut recovered_rx) = mpsc::channel::<u16>(1);
+    receiver.on_track(Box::new(move |track, _, _| {
+        let recovered_tx = recovered_tx.clone();
+        Box::pin(async move {
+            while let Ok((pkt, _)) = track.read_rtp().await {
+                // only recovered packets have these sequence numbers
+                if pkt.header.sequence_number % 4 == 2 {
+                    let _ = recovered_tx.try_send(pkt.header.sequence_number);
+                }
+            }
+        })
+    }));
+
+    signal_pair(&mut sender, &mut receiver).await?;
+
+    let mut sequence_number = 0u16;
+    let recovered = loop {
+        tokio::select! {
+            seq = recovered_rx.recv() => break seq,
+            _ = tokio::time::sleep(Duration::from_millis(20)) => {
+                if sequence_number == 250 {
+                    break None;
+                }
+                track
+                    .write_rtp(&rtp::packet::Packet {
+                        header: rtp::header::Header {
+                            version: 2,
+                            sequence_number,
+                            timestamp: 3000 * u32::from(sequence_number),
+                            ..Default::default()
+                        },
+                        payload: Bytes::from(vec![0x10, sequence_number as u8, 0xAA]),
+                    })
+                    .await?;
+                sequence_number = sequence_number.wrapping_add(1);
+            }
+        }
+    };
+    assert!(recovered.map_or(false, |seq| seq % 4 == 2));
+
+    close_pair_now(&sender, &receiver).await;
+
+    Ok(())
+}
+
+// Assert that each FEC stream is read for the track it protects
+#[tokio::test]
+async fn test_rtp_receiver_fec_stream_per_track() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let registry = configure_flexfec(Registry::new(), &mut m, FlexFecEncoder::builder())?;
+    let api = APIBuilder::new()
+        .with_media_engine(m)
+        .with_interceptor_registry(registry)
+        .build();
+
+    let (mut offerer, mut answerer) = new_pair(&api).await?;
+    offerer
+        .add_transceiver_from_kind(RTPCodecType::Video, None)
+        .await?;
+
+    let wg = WaitGroup::new();
+    until_connection_state(&mut offerer, &wg, RTCPeerConnectionState::Connected).await;
+    until_connection_state(&mut answerer, &wg, RTCPeerConnectionState::Connected).await;
+    signal_pair(&mut offerer, &mut answerer).await?;
+    wg.wait().await;
+
+    let rtp_receiver = api.new_rtp_receiver(
+        RTPCodecType::Video,
+        offerer.sctp().transport(),
+        api.interceptor_registry.build("")?,
+    );
+    let encodings = [(1000, 2000), (1001, 2001)]
+        .into_iter()
+        .map(|(ssrc, fec_ssrc)| RTCRtpDecodingParameters {
+            ssrc,
+            fec: RTCRtpFecParameters { ssrc: fec_ssrc },
+            ..Default::default()
+        })
+        .collect();
+    rtp_receiver
+        .receive(&RTCRtpReceiveParameters { encodings })
+        .await?;
+
+    {
+        let tracks = rtp_receiver.internal.tracks.read().await;
+        let fec_ssrcs: Vec<_> = tracks
+            .iter()
+            .map(|t| t.fec_stream.stream_info.as_ref().map(|s| s.ssrc))
+            .collect();
+        assert_eq!(fec_ssrcs, vec![Some(2000), Some(2001)]);
+    }
+
+    rtp_receiver.stop().await?;
+    close_pair_now(&offerer, &answerer).await;
+
+    Ok(())
+}
+
+/// RetransmissionReader reports the packets of a stream that LossyWriter dropped
+/// on the way, which can only have been restored from the RTX stream.
+struct RetransmissionReader {
+    ssrc: u32,
+    retransmitted_tx: mpsc::Sender<u16>,
+    parent_rtp_reader: Arc<dyn RTPReader + Send + Sync>,
+}
+
+#[async_trait]
+impl RTPReader for RetransmissionReader {
+    async fn read(
+        &self,
+        buf: &mut [u8],
+        a: &Attributes,
+    ) -> std::result::Result<(rtp::packet::Packet, Attributes), interceptor::Error> {
+        let (pkt, attr) = self.parent_rtp_reader.read(buf, a).await?;
+        // RTX packets read from the repair stream are longer by the original sequence number
+        if pkt.header.ssrc == self.ssrc
+            && pkt.header.sequence_number % 4 == 2
+            && pkt.payload.len() == 3
+        {
+            let _ = self.retransmitted_tx.try_send(pkt.header.sequence_number);
+        }
+        Ok((pkt, attr))
+    }
+}
+
+// Assert that packets restored from the RTX stream are read through the
+// interceptors of the primary stream
+#[tokio::test]
+async fn test_rtp_receiver_rtx_interceptors() -> Result<()> {
+    let (retransmitted_tx, mut retransmitted_rx) = mpsc::channel::<u16>(1);
+
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    m.register_rtx_codecs()?;
+    let mut registry = Registry::new();
+    registry.add(Box::new(MockBuilder::new(move |_| {
+        let retransmitted_tx = retransmitted_tx.clone();
+        Ok(Arc::new(MockInterceptor {
+            bind_local_stream_fn: Some(Box::new(
+                |info: &StreamInfo, writer: Arc<dyn RTPWriter + Send + Sync>| {
+                    let ssrc = info.ssrc;
+                    Box::pin(async move {
+                        Arc::new(LossyWriter {
+                            ssrc,
+                            count: AtomicUsize::new(0),
+                            next_rtp_writer: writer,
+                        }) as Arc<dyn RTPWriter + Send + Sync>
+                    })
+                },
+            )),
+            bind_remote_stream_fn: Some(Box::new(
+                move |info: &StreamInfo, reader: Arc<dyn RTPReader + Send + Sync>| {
+                    let ssrc = info.ssrc;
+                    let retransmitted_tx = retransmitted_tx.clone();
+                    Box::pin(async move {
+                        Arc::new(RetransmissionReader {
+                            ssrc,
+                            retransmitted_tx,
+                            parent_rtp_reader: reader,
+                        }) as Arc<dyn RTPReader + Send + Sync>
+                    })
+                },
+            )),
+            ..Default::default()
+        }))
+    })));
+    let registry = configure_nack(registry, &mut m);
+    let api = APIBuilder::new()
+        .with_media_engine(m)
+        .with_interceptor_registry(registry)
+        .build();
+
+    let (mut sender, mut receiver) = new_pair(&api).await?;
+
+    let track = Arc::new(TrackLocalStaticRTP::new(
+        RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_VP8.to_owned(),
+            ..Default::default()
+        },
+        "video".to_owned(),
+        "webrtc-rs".to_owned(),
+    ));
+    let rtp_sender = sender
+        .add_track(Arc::clone(&track) as Arc<dyn TrackLocal + Send + Sync>)
+        .await?;
+    // NACKs are handled by the interceptors when RTCP is read
+    tokio::spawn(async move { while rtp_sender.read_rtcp().await.is_ok() {} });
+
+    receiver.on_track(Box::new(move |track, _, _| {
+        Box::pin(async move { while track.read_rtp().await.is_ok() {} })
+    }));
+
+    signal_pair(&mut sender, &mut receiver).await?;
+
+    let mut sequence_number = 0u16;
+    let retransmitted = loop {
+        tokio::select! {
+            seq = retransmitted_rx.recv() => break seq,
+            _ = tokio::time::sleep(Duration::from_millis(20)) => {
+                if sequence_number == 250 {
+                    break None;
+                }
+                track
+                    .write_rtp(&rtp::packet::Packet {
+                        header: rtp::header::Header {
+                            version: 2,
+                            sequence_number,
+                            timestamp: 3000 * u32::from(sequence_number),
+                            ..Default::default()
+                        },
+                        payload: Bytes::from(vec![0x10, sequence_number as u8, 0xAA]),
+                    })
+                    .await?;
+                sequence_number = sequence_number.wrapping_add(1);
+            }
+        }
+    };
+    assert!(retransmitted.map_or(false, |seq| seq % 4 == 2));
+
+    close_pair_now(&sender, &receiver).await;
+
+    Ok(())
+}
+
+// Assert that the frames of a track are read through a jitter buffer and that its
+// stats are reported
+#[tokio::test]
+async fn test_rtp_receiver_jitter_buffer_reader() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let api = APIBuilder::new().with_media_engine(m).build();
+
+    let (mut sender, mut receiver) = new_pair(&api).await?;
+
+    let track = Arc::new(TrackLocalStaticSample::new(
+        RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_OPUS.to_owned(),
+            ..Default::default()
+        },
+        "audio".to_owned(),
+        "webrtc-rs".to_owned(),
+    ));
+    sender
+        .add_track(Arc::clone(&track) as Arc<dyn TrackLocal + Send + Sync>)
+        .await?;
+
+    let (emitted_tx, mut emitted_rx) = mpsc::channel::<u8>(1);
+    receiver.on_track(Box::new(move |track, _, _| {
+        let emitted_tx = emitted_tx.clone();
+        Box::pin(async move {
+            let jitter_buffer = JitterBuffer::new(rtp::codecs::opus::OpusPacket, 48000)
+                .with_min_delay(Duration::from_millis(40));
+            let mut reader = JitterBufferReader::new(track, jitter_buffer);
+            let mut previous = None;
+            while let Ok(sample) = reader.read_sample().await {
+                // The frames are emitted in order
+                let n = sample.data[0];
+                assert!(previous.map_or(true, |previous: u8| n > previous));
+                previous = Some(n);
+                if reader.jitter_buffer().stats().jitter_buffer_emitted_count == 5 {
+                    let _ = emitted_tx.try_send(n);
+                }
+            }
+        })
+    }));
+
+    signal_pair(&mut sender, &mut receiver).await?;
+
+    let mut n = 0u8;
+    let emitted = loop {
+        tokio::select! {
+            n = emitted_rx.recv() => break n,
+            _ = tokio::time::sleep(Duration::from_millis(20)) => {
+                if n == 250 {
+                    break None;
+                }
+                track
+                    .write_sample(&Sample {
+                        data: Bytes::from(vec![n]),
+                        duration: Duration::from_millis(20),
+                        ..Default::default()
+                    })
+                    .await?;
+                n += 1;
+            }
+        }
+    };
+    assert!(emitted.is_some());
+
+    let stats = receiver.get_stats().await;
+    let inbound_stats = stats
+        .reports
+        .values()
+        .find_map(|v| match v {
+            StatsReportType::InboundRTP(d) => Some(d),
+            _ => None,
+        })
+        .expect("Should have produced an RTP inbound stat");
+    assert_eq!(inbound_stats.kind, "audio");
+    assert!(inbound_stats.jitter_buffer_emitted_count >= Some(5));
+    assert!(inbound_stats.jitter_buffer_delay > Some(0.0));
+    assert!(inbound_stats.jitter_buffer_target_delay >= Some(0.2));
+    assert!(inbound_stats.concealed_samples.is_some());
+    assert!(inbound_stats.jitter.is_some());
+
+    close_pair_now(&sender, &receiver).await;
+
+    Ok(())
+}
+
+#[test]
+fn test_unwrap_rtx() {
+    let rtx = rtp::packet::Packet {
+        header: rtp::header::Header {
+            ssrc: 5,
+            payload_type: 97,
+            sequence_number: 1000,
+            timestamp: 3000,
+            marker: true,
+            ..Default::default()
+        },
+        payload: Bytes::from_static(&[0x12, 0x34, 0xAA, 0xBB]),
+    };
+
+    let pkt = unwrap_rtx(rtx, 1, 96).expect("an unwrapped packet");
+    assert_eq!(pkt.header.ssrc, 1);
+    assert_eq!(pkt.header.payload_type, 96);
+    assert_eq!(pkt.header.sequence_number, 0x1234);
+    assert_eq!(pkt.header.timestamp, 3000);
+    assert!(pkt.header.marker);
+    assert_eq!(&pkt.payload[..], &[0xAA, 0xBB]);
+
+    // Padding-only packets used for probing carry no original packet
+    let probe = rtp::packet::Packet {
+        header: rtp::header::Header {
+            ssrc: 5,
+            payload_type: 97,
+            padding: true,
+            ..Default::default()
+        },
+        payload: Bytes::new(),
+    };
+    assert!(unwrap_rtx(probe, 1, 96).is_none());
+}
@@ -1,68 +1,151 @@
 #[cfg(test)]
 mod rtp_sender_test;
 
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Weak};
+use std::task::Poll;
 
+use arc_swap::ArcSwapOption;
+use bytes::Bytes;
 use ice::rand::generate_crypto_random_string;
 use interceptor::stream_info::StreamInfo;
 use interceptor::{Attributes, Interceptor, RTCPReader, RTPWriter};
+use rtcp::transport_feedbacks::temporary_maximum_media_stream_bitrate_notification::TemporaryMaximumMediaStreamBitrateNotification;
+use rtcp::transport_feedbacks::temporary_maximum_media_stream_bitrate_request::{
+    TemporaryMaximumMediaStreamBitrateRequest, TmmbItem,
+};
+use smol_str::SmolStr;
 use tokio::sync::{mpsc, Mutex, Notify};
+use tokio::time::{Duration, Instant};
 use util::sync::Mutex as SyncMutex;
 
 use super::srtp_writer_future::SequenceTransformer;
 use crate::api::media_engine::MediaEngine;
 use crate::dtls_transport::RTCDtlsTransport;
-use crate::error::{Error, Result};
-use crate::rtp_transceiver::rtp_codec::{RTCRtpCodecParameters, RTPCodecType};
+use crate::error::{flatten_errs, Error, Result};
+use crate::rtp_transceiver::priority_type::RTCPriorityType;
+use crate::rtp_transceiver::rtp_codec::{
+    codec_rtx_apt, find_fec_payload_type, find_rtx_payload_type,
+};
 use crate::rtp_transceiver::rtp_transceiver_direction::RTCRtpTransceiverDirection;
 use crate::rtp_transceiver::srtp_writer_future::SrtpWriterFuture;
 use crate::rtp_transceiver::{
-    create_stream_info, PayloadType, RTCRtpEncodingParameters, RTCRtpSendParameters,
-    RTCRtpTransceiver, SSRC,
+    create_stream_info, PayloadType, RTCRtpEncodingParameters, RTCRtpFecParameters,
+    RTCRtpRtxParameters, RTCRtpSendParameters, RTCRtpTransceiver, SSRC,
 };
+use crate::stats::RTCQualityLimitationReason;
 use crate::track::track_local::{
     InterceptorToTrackLocalWriter, TrackLocal, TrackLocalContext, TrackLocalWriter,
 };
 
+pub type OnMaxBitrateHdlrFn = Box<
+    dyn (FnMut(String, u64) -> Pin<Box<dyn Future<Output = ()> + Send + 'static>>) + Send + Sync,
+>;
+
+/// QualityLimitation tracks the time the sender spends in each quality limitation
+/// reason, for the outbound RTP stats.
+#[derive(Debug)]
+pub(crate) struct QualityLimitation {
+    reason: RTCQualityLimitationReason,
+    since: Instant,
+    durations: HashMap<RTCQualityLimitationReason, Duration>,
+}
+
+impl QualityLimitation {
+    fn new(now: Instant) -> Self {
+        QualityLimitation {
+            reason: RTCQualityLimitationReason::None,
+            since: now,
+            durations: HashMap::new(),
+        }
+    }
+
+    fn set_reason(&mut self, reason: RTCQualityLimitationReason, now: Instant) {
+        *self.durations.entry(self.reason).or_default() += now - self.since;
+        self.reason = reason;
+        self.since = now;
+    }
+
+    pub(crate) fn reason(&self) -> RTCQualityLimitationReason {
+        self.reason
+    }
+
+    /// durations returns the seconds spent in each reason up to now.
+    pub(crate) fn durations(&self, now: Instant) -> HashMap<RTCQualityLimitationReason, f64> {
+        let mut durations: HashMap<_, _> = [
+            RTCQualityLimitationReason::None,
+            RTCQualityLimitationReason::Cpu,
+            RTCQualityLimitationReason::Bandwidth,
+            RTCQualityLimitationReason::Other,
+        ]
+        .into_iter()
+        .map(|reason| {
+            let duration = self.durations.get(&reason).copied().unwrap_or_default();
+            (reason, duration)
+        })
+        .collect();
+        *durations.entry(self.reason).or_default() += now - self.since;
+
+        durations
+            .into_iter()
+            .map(|(reason, duration)| (reason, duration.as_secs_f64()))
+            .collect()
+    }
+}
+
 pub(crate) struct RTPSenderInternal {
     pub(crate) send_called_rx: Mutex<mpsc::Receiver<()>>,
     pub(crate) stop_called_rx: Arc<Notify>,
     pub(crate) stop_called_signal: Arc<AtomicBool>,
-    pub(crate) rtcp_interceptor: Mutex<Option<Arc<dyn RTCPReader + Send + Sync>>>,
 }
 
 impl RTPSenderInternal {
-    /// read reads incoming RTCP for this RTPReceiver
+    /// read reads incoming RTCP of the given encodings of this RTPSender, returning the
+    /// packets of whichever encoding has some first
     async fn read(
         &self,
         b: &mut [u8],
+        rtcp_interceptors: Vec<Arc<dyn RTCPReader + Send + Sync>>,
     ) -> Result<(Vec<Box<dyn rtcp::packet::Packet + Send + Sync>>, Attributes)> {
-        let mut send_called_rx = self.send_called_rx.lock().await;
+        {
+            let mut send_called_rx = self.send_called_rx.lock().await;
 
-        tokio::select! {
-            _ = send_called_rx.recv() =>{
-                let rtcp_interceptor = {
-                    let rtcp_interceptor = self.rtcp_interceptor.lock().await;
-                    rtcp_interceptor.clone()
-                };
-                if let Some(rtcp_interceptor) = rtcp_interceptor{
-                    let a = Attributes::new();
-                    tokio::select! {
-                        _ = self.stop_called_rx.notified() => {
-                            Err(Error::ErrClosedPipe)
-                        }
-                        result = rtcp_interceptor.read(b, &a) => {
-                            Ok(result?)
-                        }
-                    }
-                }else{
-                    Err(Error::ErrInterceptorNotBind)
+            tokio::select! {
+                _ = send_called_rx.recv() => {}
+                _ = self.stop_called_rx.notified() => {
+                    return Err(Error::ErrClosedPipe);
                 }
             }
-            _ = self.stop_called_rx.notified() =>{
+        }
+
+        // The first encoding reads into b, the others into buffers of their own.
+        let mut bufs = vec![vec![0u8; b.len()]; rtcp_interceptors.len().saturating_sub(1)];
+        let a = Attributes::new();
+        let mut reads: Vec<_> = std::iter::once(b)
+            .chain(bufs.iter_mut().map(|buf| buf.as_mut_slice()))
+            .zip(&rtcp_interceptors)
+            .map(|(buf, rtcp_interceptor)| rtcp_interceptor.read(buf, &a))
+            .collect();
+        let read_any = std::future::poll_fn(|cx| {
+            reads
+                .iter_mut()
+                .find_map(|read| match read.as_mut().poll(cx) {
+                    Poll::Ready(result) => Some(result),
+                    Poll::Pending => None,
+                })
+                .map_or(Poll::Pending, Poll::Ready)
+        });
+
+        tokio::select! {
+            _ = self.stop_called_rx.notified() => {
                 Err(Error::ErrClosedPipe)
             }
+            result = read_any => {
+                Ok(result?)
+            }
         }
     }
 
@@ -70,28 +153,63 @@ impl RTPSenderInternal {
     async fn read_rtcp(
         &self,
         receive_mtu: usize,
+        rtcp_interceptors: Vec<Arc<dyn RTCPReader + Send + Sync>>,
     ) -> Result<(Vec<Box<dyn rtcp::packet::Packet + Send + Sync>>, Attributes)> {
         let mut b = vec![0u8; receive_mtu];
-        let (pkts, attributes) = self.read(&mut b).await?;
+        let (pkts, attributes) = self.read(&mut b, rtcp_interceptors).await?;
 
         Ok((pkts, attributes))
     }
 }
 
+/// TrackEncoding is an encoding of a RTPSender, fed by a track of its own and sent
+/// with SSRCs of its own. A sender has multiple encodings when it sends simulcast,
+/// each identified by its rid.
+pub(crate) struct TrackEncoding {
+    pub(crate) track: Option<Arc<dyn TrackLocal + Send + Sync>>,
+    pub(crate) rid: SmolStr,
+
+    pub(crate) srtp_stream: Arc<SrtpWriterFuture>,
+    pub(crate) rtcp_interceptor: Arc<dyn RTCPReader + Send + Sync>,
+    pub(crate) stream_info: StreamInfo,
+    pub(crate) context: TrackLocalContext,
+
+    pub(crate) ssrc: SSRC,
+    /// SSRC of the RTX stream, only used if an RTX codec is negotiated
+    pub(crate) rtx_ssrc: SSRC,
+    /// SSRC of the FlexFEC stream, only used if FlexFEC is negotiated
+    pub(crate) fec_ssrc: SSRC,
+
+    /// Set while the encoding is not active
+    pub(crate) paused: Arc<AtomicBool>,
+    pub(crate) max_bitrate: Option<u64>,
+    pub(crate) max_framerate: Option<f64>,
+    pub(crate) scale_resolution_down_by: Option<f64>,
+    pub(crate) priority: RTCPriorityType,
+    pub(crate) network_priority: RTCPriorityType,
+}
+
+impl TrackEncoding {
+    /// Sets the parameters of the encoding which can be changed without renegotiation.
+    fn set_parameters(&mut self, parameters: &RTCRtpEncodingParameters) {
+        self.paused.store(!parameters.active, Ordering::SeqCst);
+        self.max_bitrate = parameters.max_bitrate;
+        self.max_framerate = parameters.max_framerate;
+        self.scale_resolution_down_by = parameters.scale_resolution_down_by;
+        self.priority = parameters.priority;
+        self.network_priority = parameters.network_priority;
+    }
+}
+
 /// RTPSender allows an application to control how a given Track is encoded and transmitted to a remote peer
 pub struct RTCRtpSender {
-    pub(crate) track: Mutex<Option<Arc<dyn TrackLocal + Send + Sync>>>,
+    pub(crate) track_encodings: Mutex<Vec<TrackEncoding>>,
 
-    pub(crate) srtp_stream: Arc<SrtpWriterFuture>,
-    pub(crate) stream_info: Mutex<StreamInfo>,
     seq_trans: Arc<SequenceTransformer>,
 
-    pub(crate) context: Mutex<TrackLocalContext>,
-
     pub(crate) transport: Arc<RTCDtlsTransport>,
 
     pub(crate) payload_type: PayloadType,
-    pub(crate) ssrc: SSRC,
     receive_mtu: usize,
 
     /// a transceiver sender since we can just check the
@@ -111,12 +229,20 @@ pub struct RTCRtpSender {
 
     rtp_transceiver: SyncMutex<Option<Weak<RTCRtpTransceiver>>>,
 
+    /// The parameters last returned by get_parameters, which set_parameters must be
+    /// called with
+    last_returned_parameters: SyncMutex<Option<RTCRtpSendParameters>>,
+
     send_called_tx: SyncMutex<Option<mpsc::Sender<()>>>,
     stop_called_tx: Arc<Notify>,
     stop_called_signal: Arc<AtomicBool>,
 
     pub(crate) paused: Arc<AtomicBool>,
 
+    pub(crate) quality_limitation: SyncMutex<QualityLimitation>,
+
+    on_max_bitrate_handler: ArcSwapOption<Mutex<OnMaxBitrateHdlrFn>>,
+
     internal: Arc<RTPSenderInternal>,
 }
 
@@ -144,50 +270,44 @@ impl RTCRtpSender {
         let (send_called_tx, send_called_rx) = mpsc::channel(1);
         let stop_called_tx = Arc::new(Notify::new());
         let stop_called_rx = stop_called_tx.clone();
-        let ssrc = rand::random::<u32>();
         let stop_called_signal = Arc::new(AtomicBool::new(false));
 
         let internal = Arc::new(RTPSenderInternal {
             send_called_rx: Mutex::new(send_called_rx),
             stop_called_rx,
             stop_called_signal: Arc::clone(&stop_called_signal),
-            rtcp_interceptor: Mutex::new(None),
         });
 
         let seq_trans = Arc::new(SequenceTransformer::new());
-        let srtp_stream = Arc::new(SrtpWriterFuture {
-            closed: AtomicBool::new(false),
-            ssrc,
-            rtp_sender: Arc::downgrade(&internal),
-            rtp_transport: Arc::clone(&transport),
-            rtcp_read_stream: Mutex::new(None),
-            rtp_write_session: Mutex::new(None),
-            seq_trans: Arc::clone(&seq_trans),
-        });
-
-        let srtp_rtcp_reader = Arc::clone(&srtp_stream) as Arc<dyn RTCPReader + Send + Sync>;
-        let rtcp_interceptor = interceptor.bind_rtcp_reader(srtp_rtcp_reader).await;
-        {
-            let mut internal_rtcp_interceptor = internal.rtcp_interceptor.lock().await;
-            *internal_rtcp_interceptor = Some(rtcp_interceptor);
-        }
 
         let stream_ids = track
             .as_ref()
             .map(|track| vec![track.stream_id().to_string()])
             .unwrap_or_default();
+        let rid = track
+            .as_ref()
+            .and_then(|track| track.rid())
+            .map(SmolStr::from)
+            .unwrap_or_default();
+        let encoding = Self::new_track_encoding(
+            track,
+            rid,
+            0,
+            &transport,
+            &interceptor,
+            &internal,
+            &seq_trans,
+        )
+        .await;
+
         Self {
-            track: Mutex::new(track),
+            track_encodings: Mutex::new(vec![encoding]),
 
-            srtp_stream,
-            stream_info: Mutex::new(StreamInfo::default()),
             seq_trans,
 
-            context: Mutex::new(TrackLocalContext::default()),
             transport,
 
             payload_type: 0,
-            ssrc,
             receive_mtu,
 
             negotiated: AtomicBool::new(false),
@@ -201,16 +321,75 @@ impl RTCRtpSender {
 
             rtp_transceiver: SyncMutex::new(None),
 
+            last_returned_parameters: SyncMutex::new(None),
+
             send_called_tx: SyncMutex::new(Some(send_called_tx)),
             stop_called_tx,
             stop_called_signal,
 
             paused: Arc::new(AtomicBool::new(start_paused)),
 
+            quality_limitation: SyncMutex::new(QualityLimitation::new(Instant::now())),
+
+            on_max_bitrate_handler: ArcSwapOption::empty(),
+
             internal,
         }
     }
 
+    /// Creates an encoding sending with the given SSRC, or a random one if it is 0.
+    async fn new_track_encoding(
+        track: Option<Arc<dyn TrackLocal + Send + Sync>>,
+        rid: SmolStr,
+        ssrc: SSRC,
+        transport: &Arc<RTCDtlsTransport>,
+        interceptor: &Arc<dyn Interceptor + Send + Sync>,
+        internal: &Arc<RTPSenderInternal>,
+        seq_trans: &Arc<SequenceTransformer>,
+    ) -> TrackEncoding {
+        let ssrc = if ssrc != 0 {
+            ssrc
+        } else {
+            rand::random::<u32>()
+        };
+        let rtx_ssrc = rand::random::<u32>();
+        let fec_ssrc = rand::random::<u32>();
+
+        let srtp_stream = Arc::new(SrtpWriterFuture {
+            closed: AtomicBool::new(false),
+            ssrc,
+            rtp_sender: Arc::downgrade(internal),
+            rtp_transport: Arc::clone(transport),
+            rtcp_read_stream: Mutex::new(None),
+            rtp_write_session: Mutex::new(None),
+            seq_trans: Arc::clone(seq_trans),
+        });
+
+        let srtp_rtcp_reader = Arc::clone(&srtp_stream) as Arc<dyn RTCPReader + Send + Sync>;
+        let rtcp_interceptor = interceptor.bind_rtcp_reader(srtp_rtcp_reader).await;
+
+        TrackEncoding {
+            track,
+            rid,
+
+            srtp_stream,
+            rtcp_interceptor,
+            stream_info: StreamInfo::default(),
+            context: TrackLocalContext::default(),
+
+            ssrc,
+            rtx_ssrc,
+            fec_ssrc,
+
+            paused: Arc::new(AtomicBool::new(false)),
+            max_bitrate: None,
+            max_framerate: None,
+            scale_resolution_down_by: None,
+            priority: RTCPriorityType::default(),
+            network_priority: RTCPriorityType::default(),
+        }
+    }
+
     pub(crate) fn is_negotiated(&self) -> bool {
         self.negotiated.load(Ordering::SeqCst)
     }
@@ -237,29 +416,41 @@ impl RTCRtpSender {
         Arc::clone(&self.transport)
     }
 
+    /// set_quality_limitation_reason sets the reason the quality of the video sent is
+    /// limited, reported with the time spent in each reason in the outbound RTP stats.
+    /// Since the sender doesn't encode, the application encoding the video calls it
+    /// whenever the reason changes.
+    pub fn set_quality_limitation_reason(&self, reason: RTCQualityLimitationReason) {
+        let mut quality_limitation = self.quality_limitation.lock();
+        quality_limitation.set_reason(reason, Instant::now());
+    }
+
     /// get_parameters describes the current configuration for the encoding and
     /// transmission of media on the sender's track.
     pub async fn get_parameters(&self) -> RTCRtpSendParameters {
-        let kind = {
-            let track = self.track.lock().await;
-            if let Some(t) = &*track {
-                t.kind()
-            } else {
-                RTPCodecType::default()
-            }
-        };
+        let mut send_parameters = self.send_parameters().await;
+        send_parameters.transaction_id = generate_crypto_random_string(
+            32,
+            b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ",
+        );
 
-        let mut send_parameters = {
-            RTCRtpSendParameters {
-                rtp_parameters: self
-                    .media_engine
-                    .get_rtp_parameters_by_kind(kind, RTCRtpTransceiverDirection::Sendonly),
-                encodings: vec![RTCRtpEncodingParameters {
-                    ssrc: self.ssrc,
-                    payload_type: self.payload_type,
-                    ..Default::default()
-                }],
-            }
+        {
+            let mut last_returned_parameters = self.last_returned_parameters.lock();
+            *last_returned_parameters = Some(send_parameters.clone());
+        }
+
+        send_parameters
+    }
+
+    /// send_parameters describes the current configuration of the sender like
+    /// get_parameters, without starting a set_parameters transaction.
+    pub(crate) async fn send_parameters(&self) -> RTCRtpSendParameters {
+        let kind = {
+            let track_encodings = self.track_encodings.lock().await;
+            track_encodings
+                .iter()
+                .find_map(|e| e.track.as_ref().map(|t| t.kind()))
+                .unwrap_or_default()
         };
 
         let codecs = {
@@ -274,20 +465,237 @@ impl RTCRtpSender {
                 self.media_engine.get_codecs_by_kind(kind)
             }
         };
+        let rtx_enabled = codecs.iter().any(|c| codec_rtx_apt(c).is_some());
+        let fec_enabled = find_fec_payload_type(&codecs).is_some();
+
+        let encodings = {
+            let track_encodings = self.track_encodings.lock().await;
+            track_encodings
+                .iter()
+                .map(|e| RTCRtpEncodingParameters {
+                    rid: e.rid.clone(),
+                    ssrc: e.ssrc,
+                    payload_type: self.payload_type,
+                    rtx: RTCRtpRtxParameters {
+                        ssrc: if rtx_enabled { e.rtx_ssrc } else { 0 },
+                    },
+                    fec: RTCRtpFecParameters {
+                        ssrc: if fec_enabled { e.fec_ssrc } else { 0 },
+                    },
+
+                    active: !e.paused.load(Ordering::SeqCst),
+                    max_bitrate: e.max_bitrate,
+                    max_framerate: e.max_framerate,
+                    scale_resolution_down_by: e.scale_resolution_down_by,
+                    priority: e.priority,
+                    network_priority: e.network_priority,
+                })
+                .collect()
+        };
+
+        let mut send_parameters = RTCRtpSendParameters {
+            transaction_id: String::new(),
+            rtp_parameters: self
+                .media_engine
+                .get_rtp_parameters_by_kind(kind, RTCRtpTransceiverDirection::Sendonly),
+            encodings,
+        };
         send_parameters.rtp_parameters.codecs = codecs;
 
         send_parameters
     }
 
-    /// track returns the RTCRtpTransceiver track, or nil
+    /// set_parameters changes the parameters of the encodings of the sender without
+    /// renegotiation, like pausing a simulcast layer by setting it inactive. It must be
+    /// called with the parameters returned by the last call to get_parameters, where only
+    /// the `active`, `max_bitrate`, `max_framerate`, `scale_resolution_down_by`, `priority`
+    /// and `network_priority` of the encodings may be modified.
+    ///
+    /// The tracks of the changed encodings are notified with
+    /// [`TrackLocal::encoding_parameters_changed`].
+    pub async fn set_parameters(&self, parameters: RTCRtpSendParameters) -> Result<()> {
+        if self.stop_called_signal.load(Ordering::SeqCst) {
+            return Err(Error::ErrRTPSenderStopped);
+        }
+
+        {
+            let last_returned_parameters = self.last_returned_parameters.lock();
+            let last = match &*last_returned_parameters {
+                Some(last) if last.transaction_id == parameters.transaction_id => last,
+                _ => return Err(Error::ErrRTPSenderInvalidTransactionId),
+            };
+            if !is_modification_allowed(last, &parameters) {
+                return Err(Error::ErrRTPSenderInvalidModification);
+            }
+        }
+        for encoding in &parameters.encodings {
+            validate_encoding_parameters(encoding)?;
+        }
+
+        let mut changed = vec![];
+        {
+            let mut track_encodings = self.track_encodings.lock().await;
+            for (encoding, parameters_encoding) in
+                track_encodings.iter_mut().zip(&parameters.encodings)
+            {
+                encoding.set_parameters(parameters_encoding);
+                encoding.context.encoding_parameters = parameters_encoding.clone();
+                if let (Some(track), true) = (&encoding.track, self.has_sent()) {
+                    changed.push((Arc::clone(track), encoding.context.clone()));
+                }
+            }
+        }
+
+        {
+            let mut last_returned_parameters = self.last_returned_parameters.lock();
+            *last_returned_parameters = None;
+        }
+
+        let mut errs = vec![];
+        for (track, context) in changed {
+            if let Err(err) = track.encoding_parameters_changed(&context).await {
+                errs.push(err);
+            }
+        }
+
+        flatten_errs(errs)
+    }
+
+    /// track returns the RTCRtpTransceiver track, or nil. A sender sending simulcast
+    /// returns the track of its first encoding.
     pub async fn track(&self) -> Option<Arc<dyn TrackLocal + Send + Sync>> {
-        let track = self.track.lock().await;
-        track.clone()
+        let track_encodings = self.track_encodings.lock().await;
+        track_encodings.first().and_then(|e| e.track.clone())
+    }
+
+    /// tracks returns the tracks of the encodings of the sender, one per simulcast
+    /// layer.
+    pub async fn tracks(&self) -> Vec<Arc<dyn TrackLocal + Send + Sync>> {
+        let track_encodings = self.track_encodings.lock().await;
+        track_encodings
+            .iter()
+            .filter_map(|e| e.track.clone())
+            .collect()
+    }
+
+    /// add_encoding adds a simulcast layer to the sender, fed by the given track. The
+    /// track must have a rid which is unique in the sender, and the same id, stream id
+    /// and kind as the track of the first encoding. If a layer with the rid of the track
+    /// was configured by the send encodings of the transceiver, the track is set on it.
+    ///
+    /// Encodings must be added before negotiation, as each one is announced in the SDP
+    /// with an `a=rid` line and SSRCs of its own.
+    pub async fn add_encoding(&self, track: Arc<dyn TrackLocal + Send + Sync>) -> Result<()> {
+        if self.has_sent() {
+            return Err(Error::ErrRTPSenderSendAlreadyCalled);
+        }
+
+        let rid = match track.rid() {
+            Some(rid) if !rid.is_empty() => SmolStr::from(rid),
+            _ => return Err(Error::ErrRTPSenderRidNil),
+        };
+
+        let mut track_encodings = self.track_encodings.lock().await;
+        let base_track = match track_encodings.iter().find_map(|e| e.track.as_ref()) {
+            Some(base_track) => base_track,
+            None => return Err(Error::ErrRTPSenderNoBaseEncoding),
+        };
+        if track_encodings[0].rid.is_empty() {
+            return Err(Error::ErrRTPSenderNoBaseEncoding);
+        }
+        if base_track.id() != track.id()
+            || base_track.stream_id() != track.stream_id()
+            || base_track.kind() != track.kind()
+        {
+            return Err(Error::ErrRTPSenderBaseEncodingMismatch);
+        }
+
+        if let Some(encoding) = track_encodings.iter_mut().find(|e| e.rid == rid) {
+            if encoding.track.is_some() {
+                return Err(Error::ErrRTPSenderRIDCollision);
+            }
+            encoding.track = Some(track);
+            return Ok(());
+        }
+
+        let encoding = Self::new_track_encoding(
+            Some(track),
+            rid,
+            0,
+            &self.transport,
+            &self.interceptor,
+            &self.internal,
+            &self.seq_trans,
+        )
+        .await;
+        track_encodings.push(encoding);
+
+        Ok(())
     }
 
+    /// set_send_encodings configures the simulcast layers of the sender from the send
+    /// encodings of the transceiver. The track the sender was created with feeds the
+    /// layer with its rid, or the first one if it has none.
+    pub(crate) async fn set_send_encodings(
+        &self,
+        send_encodings: &[RTCRtpEncodingParameters],
+    ) -> Result<()> {
+        if send_encodings.is_empty() {
+            return Ok(());
+        }
+        if self.has_sent() {
+            return Err(Error::ErrRTPSenderSendAlreadyCalled);
+        }
+
+        let mut track_encodings = self.track_encodings.lock().await;
+        let track = track_encodings.first().and_then(|e| e.track.clone());
+        let track_rid = track.as_ref().and_then(|t| t.rid()).map(SmolStr::from);
+
+        let mut encodings = Vec::with_capacity(send_encodings.len());
+        for (i, send_encoding) in send_encodings.iter().enumerate() {
+            if send_encodings.len() > 1 && send_encoding.rid.is_empty() {
+                return Err(Error::ErrRTPSenderRidNil);
+            }
+            if encodings
+                .iter()
+                .any(|e: &TrackEncoding| e.rid == send_encoding.rid)
+            {
+                return Err(Error::ErrRTPSenderRIDCollision);
+            }
+
+            let has_track = match &track_rid {
+                Some(rid) if !send_encoding.rid.is_empty() => *rid == send_encoding.rid,
+                _ => i == 0,
+            };
+            validate_encoding_parameters(send_encoding)?;
+            let mut encoding = Self::new_track_encoding(
+                if has_track { track.clone() } else { None },
+                send_encoding.rid.clone(),
+                send_encoding.ssrc,
+                &self.transport,
+                &self.interceptor,
+                &self.internal,
+                &self.seq_trans,
+            )
+            .await;
+            encoding.set_parameters(send_encoding);
+            encodings.push(encoding);
+        }
+        if track.is_some() && encodings.iter().all(|e| e.track.is_none()) {
+            return Err(Error::ErrRTPSenderNoTrackForRID);
+        }
+
+        *track_encodings = encodings;
+
+        Ok(())
+    }
     /// replace_track replaces the track currently being used as the sender's source with a new TrackLocal.
     /// The new track must be of the same media kind (audio, video, etc) and switching the track should not
     /// require negotiation.
+    ///
+    /// A sender sending simulcast replaces the track of the encoding with the rid of the new
+    /// track, or of its first encoding if the new track has no rid. Replacing with `None`
+    /// removes the tracks of all encodings.
     pub async fn replace_track(
         &self,
         track: Option<Arc<dyn TrackLocal + Send + Sync>>,
@@ -307,51 +715,59 @@ impl RTCRtpSender {
             }
         }
 
-        if self.has_sent() {
-            let t = {
-                let t = self.track.lock().await;
-                t.clone()
-            };
-            if let Some(t) = t {
-                let context = self.context.lock().await;
-                t.unbind(&context).await?;
+        let mut track_encodings = self.track_encodings.lock().await;
+        let track = match track {
+            Some(track) => track,
+            None => {
+                for encoding in track_encodings.iter_mut() {
+                    if let Some(t) = encoding.track.take() {
+                        if self.has_sent() {
+                            t.unbind(&encoding.context).await?;
+                        }
+                    }
+                }
+                return Ok(());
             }
-        }
-
-        if !self.has_sent() || track.is_none() {
-            let mut t = self.track.lock().await;
-            *t = track;
-            return Ok(());
-        }
+        };
 
-        let context = {
-            let context = self.context.lock().await;
-            context.clone()
+        let encoding = match track.rid() {
+            Some(rid) if track_encodings.len() > 1 => track_encodings
+                .iter_mut()
+                .find(|e| e.rid == rid)
+                .ok_or(Error::ErrRTPSenderNoTrackForRID)?,
+            _ => track_encodings
+                .first_mut()
+                .ok_or(Error::ErrRTPSenderNoTrackForRID)?,
         };
 
-        let result = if let Some(t) = &track {
-            self.seq_trans.reset_offset();
+        if !self.has_sent() {
+            encoding.track = Some(track);
+            return Ok(());
+        }
 
-            let new_context = TrackLocalContext {
-                id: context.id.clone(),
-                params: self
-                    .media_engine
-                    .get_rtp_parameters_by_kind(t.kind(), RTCRtpTransceiverDirection::Sendonly),
-                ssrc: context.ssrc,
-                write_stream: context.write_stream.clone(),
-                paused: self.paused.clone(),
-            };
+        if let Some(t) = &encoding.track {
+            t.unbind(&encoding.context).await?;
+        }
 
-            t.bind(&new_context).await
-        } else {
-            Err(Error::ErrRTPSenderTrackNil)
+        let context = encoding.context.clone();
+        self.seq_trans.reset_offset(encoding.ssrc);
+
+        let new_context = TrackLocalContext {
+            id: context.id.clone(),
+            params: self
+                .media_engine
+                .get_rtp_parameters_by_kind(track.kind(), RTCRtpTransceiverDirection::Sendonly),
+            ssrc: context.ssrc,
+            write_stream: context.write_stream.clone(),
+            paused: self.paused.clone(),
+            encoding_paused: encoding.paused.clone(),
+            encoding_parameters: context.encoding_parameters.clone(),
         };
 
-        match result {
+        match track.bind(&new_context).await {
             Err(err) => {
                 // Re-bind the original track
-                let track = self.track.lock().await;
-                if let Some(t) = &*track {
+                if let Some(t) = &encoding.track {
                     t.bind(&context).await?;
                 }
 
@@ -360,14 +776,10 @@ impl RTCRtpSender {
             Ok(codec) => {
                 // Codec has changed
                 if self.payload_type != codec.payload_type {
-                    let mut context = self.context.lock().await;
-                    context.params.codecs = vec![codec];
+                    encoding.context.params.codecs = vec![codec];
                 }
 
-                {
-                    let mut t = self.track.lock().await;
-                    *t = track;
-                }
+                encoding.track = Some(track);
 
                 Ok(())
             }
@@ -380,63 +792,105 @@ impl RTCRtpSender {
             return Err(Error::ErrRTPSenderSendAlreadyCalled);
         }
 
-        let write_stream = Arc::new(InterceptorToTrackLocalWriter::new(self.paused.clone()));
-        let (context, stream_info) = {
-            let track = self.track.lock().await;
+        let mid = {
+            let tr = self.rtp_transceiver.lock().clone();
+            tr.and_then(|t| t.upgrade()).and_then(|t| t.mid())
+        };
+        let header_extension_id = |uri: &str| {
+            parameters
+                .rtp_parameters
+                .header_extensions
+                .iter()
+                .find(|ext| ext.uri == uri)
+                .map(|ext| ext.id as u8)
+        };
+        let mid_id = header_extension_id(::sdp::extmap::SDES_MID_URI);
+        let rid_id = header_extension_id(::sdp::extmap::SDES_RTP_STREAM_ID_URI);
+
+        let mut track_encodings = self.track_encodings.lock().await;
+        for (encoding, parameters_encoding) in track_encodings.iter_mut().zip(&parameters.encodings)
+        {
+            let track = match &encoding.track {
+                Some(track) => Arc::clone(track),
+                None => continue,
+            };
+
+            // The layers of a simulcast sender are identified by the mid and rid header
+            // extensions, as their SSRCs may not be signaled
+            let mut header_extensions = vec![];
+            if !encoding.rid.is_empty() {
+                if let (Some(id), Some(mid)) = (mid_id, &mid) {
+                    header_extensions.push((id, Bytes::copy_from_slice(mid.as_bytes())));
+                }
+                if let Some(id) = rid_id {
+                    header_extensions.push((id, Bytes::copy_from_slice(encoding.rid.as_bytes())));
+                }
+            }
+            let write_stream = Arc::new(InterceptorToTrackLocalWriter::new(
+                self.paused.clone(),
+                encoding.paused.clone(),
+                header_extensions,
+            ));
+
             let mut context = TrackLocalContext {
                 id: self.id.clone(),
-                params: self.media_engine.get_rtp_parameters_by_kind(
-                    if let Some(t) = &*track {
-                        t.kind()
-                    } else {
-                        RTPCodecType::default()
-                    },
-                    RTCRtpTransceiverDirection::Sendonly,
-                ),
-                ssrc: parameters.encodings[0].ssrc,
+                params: self
+                    .media_engine
+                    .get_rtp_parameters_by_kind(track.kind(), RTCRtpTransceiverDirection::Sendonly),
+                ssrc: parameters_encoding.ssrc,
                 write_stream: Some(
                     Arc::clone(&write_stream) as Arc<dyn TrackLocalWriter + Send + Sync>
                 ),
                 paused: self.paused.clone(),
+                encoding_paused: encoding.paused.clone(),
+                encoding_parameters: parameters_encoding.clone(),
             };
 
-            let codec = if let Some(t) = &*track {
-                t.bind(&context).await?
-            } else {
-                RTCRtpCodecParameters::default()
-            };
+            let codec = track.bind(&context).await?;
             let payload_type = codec.payload_type;
             let capability = codec.capability.clone();
             context.params.codecs = vec![codec];
-            let stream_info = create_stream_info(
+            let mut stream_info = create_stream_info(
                 self.id.clone(),
-                parameters.encodings[0].ssrc,
+                parameters_encoding.ssrc,
                 payload_type,
                 capability,
                 &parameters.rtp_parameters.header_extensions,
             );
+            let rtx_ssrc = parameters_encoding.rtx.ssrc;
+            if rtx_ssrc != 0 {
+                if let Some(rtx_payload_type) =
+                    find_rtx_payload_type(payload_type, &parameters.rtp_parameters.codecs)
+                {
+                    stream_info.ssrc_retransmission = rtx_ssrc;
+                    stream_info.payload_type_retransmission = rtx_payload_type;
+                }
+            }
+            let fec_ssrc = parameters_encoding.fec.ssrc;
+            if fec_ssrc != 0 {
+                if let Some(fec_payload_type) =
+                    find_fec_payload_type(&parameters.rtp_parameters.codecs)
+                {
+                    stream_info.ssrc_forward_error_correction = fec_ssrc;
+                    stream_info.payload_type_forward_error_correction = fec_payload_type;
+                }
+            }
 
-            (context, stream_info)
-        };
-
-        let srtp_rtp_writer = Arc::clone(&self.srtp_stream) as Arc<dyn RTPWriter + Send + Sync>;
-        let rtp_interceptor = self
-            .interceptor
-            .bind_local_stream(&stream_info, srtp_rtp_writer)
-            .await;
-        {
-            let mut interceptor_rtp_writer = write_stream.interceptor_rtp_writer.lock().await;
-            *interceptor_rtp_writer = Some(rtp_interceptor);
-        }
+            let srtp_rtp_writer =
+                Arc::clone(&encoding.srtp_stream) as Arc<dyn RTPWriter + Send + Sync>;
+            let rtp_interceptor = self
+                .interceptor
+                .bind_local_stream(&stream_info, srtp_rtp_writer)
+                .await;
+            {
+                let mut interceptor_rtp_writer = write_stream.interceptor_rtp_writer.lock().await;
+                *interceptor_rtp_writer = Some(rtp_interceptor);
+            }
 
-        {
-            let mut ctx = self.context.lock().await;
-            *ctx = context;
-        }
-        {
-            let mut si = self.stream_info.lock().await;
-            *si = stream_info;
+            encoding.context = context;
+            encoding.stream_info = stream_info;
         }
+        drop(track_encodings);
 
         {
             let mut send_called_tx = self.send_called_tx.lock();
@@ -460,33 +914,177 @@ impl RTCRtpSender {
 
         self.replace_track(None).await?;
 
-        {
-            let stream_info = self.stream_info.lock().await;
-            self.interceptor.unbind_local_stream(&stream_info).await;
+        let mut errs = vec![];
+        let track_encodings = self.track_encodings.lock().await;
+        for encoding in track_encodings.iter() {
+            self.interceptor
+                .unbind_local_stream(&encoding.stream_info)
+                .await;
+
+            if let Err(err) = encoding.srtp_stream.close().await {
+                errs.push(err);
+            }
         }
 
-        self.srtp_stream.close().await
+        flatten_errs(errs)
     }
 
-    /// read reads incoming RTCP for this RTPReceiver
+    /// read reads incoming RTCP for this RTPSender. With simulcast, the RTCP of all
+    /// encodings is read, so one call returns the packets of any of them.
     pub async fn read(
         &self,
         b: &mut [u8],
     ) -> Result<(Vec<Box<dyn rtcp::packet::Packet + Send + Sync>>, Attributes)> {
-        self.internal.read(b).await
+        let rtcp_interceptors = self.rtcp_interceptors(None).await?;
+        let (pkts, attributes) = self.internal.read(b, rtcp_interceptors).await?;
+        self.handle_max_bitrate_requests(&pkts).await;
+        Ok((pkts, attributes))
     }
 
     /// read_rtcp is a convenience method that wraps Read and unmarshals for you.
     pub async fn read_rtcp(
         &self,
     ) -> Result<(Vec<Box<dyn rtcp::packet::Packet + Send + Sync>>, Attributes)> {
-        self.internal.read_rtcp(self.receive_mtu).await
+        let rtcp_interceptors = self.rtcp_interceptors(None).await?;
+        let (pkts, attributes) = self
+            .internal
+            .read_rtcp(self.receive_mtu, rtcp_interceptors)
+            .await?;
+        self.handle_max_bitrate_requests(&pkts).await;
+        Ok((pkts, attributes))
+    }
+
+    /// read_simulcast reads incoming RTCP for the encoding of this RTPSender with the
+    /// given rid
+    pub async fn read_simulcast(
+        &self,
+        b: &mut [u8],
+        rid: &str,
+    ) -> Result<(Vec<Box<dyn rtcp::packet::Packet + Send + Sync>>, Attributes)> {
+        let rtcp_interceptors = self.rtcp_interceptors(Some(rid)).await?;
+        let (pkts, attributes) = self.internal.read(b, rtcp_interceptors).await?;
+        self.handle_max_bitrate_requests(&pkts).await;
+        Ok((pkts, attributes))
+    }
+
+    /// read_rtcp_simulcast is a convenience method that wraps read_simulcast and
+    /// unmarshals for you.
+    pub async fn read_rtcp_simulcast(
+        &self,
+        rid: &str,
+    ) -> Result<(Vec<Box<dyn rtcp::packet::Packet + Send + Sync>>, Attributes)> {
+        let rtcp_interceptors = self.rtcp_interceptors(Some(rid)).await?;
+        let (pkts, attributes) = self
+            .internal
+            .read_rtcp(self.receive_mtu, rtcp_interceptors)
+            .await?;
+        self.handle_max_bitrate_requests(&pkts).await;
+        Ok((pkts, attributes))
+    }
+
+    /// on_max_bitrate sets an event handler which is invoked when the remote peer limits
+    /// the bitrate of an encoding of this RTPSender with a TMMBR (RFC 5104) packet. It is
+    /// called with the rid of the encoding, empty without simulcast, and the maximum total
+    /// bitrate in bits per second, packet overhead included. The request is acknowledged
+    /// with a TMMBN packet. TMMBR packets are only seen while RTCP is read from the
+    /// RTPSender.
+    pub fn on_max_bitrate(&self, f: OnMaxBitrateHdlrFn) {
+        self.on_max_bitrate_handler
+            .store(Some(Arc::new(Mutex::new(f))));
+    }
+
+    /// Answers the TMMBR packets among pkts for the encodings of this RTPSender and
+    /// fires on_max_bitrate for each of their limits.
+    async fn handle_max_bitrate_requests(
+        &self,
+        pkts: &[Box<dyn rtcp::packet::Packet + Send + Sync>],
+    ) {
+        for pkt in pkts {
+            let tmmbr = match pkt
+                .as_any()
+                .downcast_ref::<TemporaryMaximumMediaStreamBitrateRequest>()
+            {
+                Some(tmmbr) => tmmbr,
+                None => continue,
+            };
+
+            let limits: Vec<_> = {
+                let track_encodings = self.track_encodings.lock().await;
+                tmmbr
+                    .items
+                    .iter()
+                    .filter_map(|item| {
+                        track_encodings
+                            .iter()
+                            .find(|e| e.ssrc == item.ssrc)
+                            .map(|e| (e.rid.to_string(), *item))
+                    })
+                    .collect()
+            };
+            if limits.is_empty() {
+                continue;
+            }
+
+            // Each request replaces the previous limit of an encoding, so the limits
+            // in effect are the ones just received. Each encoding notifies its own
+            // bounding set, whose tuples are owned by the sender of the request.
+            let tmmbns: Vec<Box<dyn rtcp::packet::Packet + Send + Sync>> = limits
+                .iter()
+                .map(|(_, item)| {
+                    Box::new(TemporaryMaximumMediaStreamBitrateNotification {
+                        sender_ssrc: item.ssrc,
+                        items: vec![TmmbItem {
+                            ssrc: tmmbr.sender_ssrc,
+                            ..*item
+                        }],
+                    }) as Box<dyn rtcp::packet::Packet + Send + Sync>
+                })
+                .collect();
+            if let Err(err) = self.transport.write_rtcp(&tmmbns).await {
+                log::warn!("failed to send TMMBN: {}", err);
+            }
+
+            let handler = self.on_max_bitrate_handler.load();
+            if let Some(f) = handler.as_ref() {
+                let mut f = f.lock().await;
+                for (rid, item) in limits {
+                    f(rid, item.bitrate).await;
+                }
+            }
+        }
+    }
+
+    /// Returns the RTCP interceptor of the encoding with the given rid, or the ones of
+    /// all encodings.
+    async fn rtcp_interceptors(
+        &self,
+        rid: Option<&str>,
+    ) -> Result<Vec<Arc<dyn RTCPReader + Send + Sync>>> {
+        let track_encodings = self.track_encodings.lock().await;
+        let rtcp_interceptors: Vec<_> = track_encodings
+            .iter()
+            .filter(|e| match rid {
+                Some(rid) => e.rid == rid,
+                None => true,
+            })
+            .map(|e| Arc::clone(&e.rtcp_interceptor))
+            .collect();
+        if rtcp_interceptors.is_empty() {
+            return Err(Error::ErrRTPSenderNoTrackForRID);
+        }
+        Ok(rtcp_interceptors)
     }
 
     /// Enables overriding outgoing `RTP` packets' `sequence number`s.
     ///
     /// Must be called once before any data sent or never called at all.
     ///
+    /// The `sequence number`s then stay continuous when the track is replaced. Together
+    /// with a layer filter, such as [`rtp::codecs::vp8::Vp8LayerFilter`] or
+    /// [`rtp::codecs::vp9::Vp9LayerFilter`], which keeps them continuous as it drops
+    /// packets and switches encodings, a selective forwarding unit can switch layers and
+    /// tracks without gaps.
+    ///
     /// # Errors
     ///
     /// Errors if this [`RTCRtpSender`] has started to send data or sequence
@@ -542,3 +1140,40 @@ impl RTCRtpSender {
         lock.clone()
     }
 }
+
+/// is_modification_allowed tells if the parameters given to set_parameters only differ
+/// from the last ones returned by get_parameters in the fields that can be changed
+/// without renegotiation.
+fn is_modification_allowed(last: &RTCRtpSendParameters, parameters: &RTCRtpSendParameters) -> bool {
+    last.rtp_parameters.codecs == parameters.rtp_parameters.codecs
+        && last.rtp_parameters.header_extensions == parameters.rtp_parameters.header_extensions
+        && last.encodings.len() == parameters.encodings.len()
+        && last
+            .encodings
+            .iter()
+            .zip(&parameters.encodings)
+            .all(|(l, p)| {
+                l.rid == p.rid
+                    && l.ssrc == p.ssrc
+                    && l.payload_type == p.payload_type
+                    && l.rtx.ssrc == p.rtx.ssrc
+                    && l.fec.ssrc == p.fec.ssrc
+            })
+}
+
+/// validate_encoding_parameters checks the values of the parameters of an encoding which
+/// can be changed without renegotiation.
+fn validate_encoding_parameters(encoding: &RTCRtpEncodingParameters) -> Result<()> {
+    if let Some(scale_resolution_down_by) = encoding.scale_resolution_down_by {
+        if scale_resolution_down_by.is_nan() || scale_resolution_down_by < 1.0 {
+            return Err(Error::ErrRTPSenderInvalidScaleResolutionDownBy);
+        }
+    }
+    if let Some(max_framerate) = encoding.max_framerate {
+        if max_framerate.is_nan() || max_framerate < 0.0 {
+            return Err(Error::ErrRTPSenderInvalidMaxFramerate);
+        }
+    }
+
+    Ok(())
+}
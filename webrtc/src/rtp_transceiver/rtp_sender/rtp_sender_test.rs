@@ -1,10 +1,16 @@
+use std::collections::HashSet;
 use std::sync::atomic::AtomicU64;
 
 use bytes::Bytes;
+use interceptor::flexfec::FlexFecEncoder;
+use interceptor::registry::Registry;
+use rtcp::payload_feedbacks::picture_loss_indication::PictureLossIndication;
+use rtcp::transport_feedbacks::temporary_maximum_media_stream_bitrate_request::TmmbItem;
 use tokio::time::Duration;
 use waitgroup::WaitGroup;
 
 use super::*;
+use crate::api::interceptor_registry::configure_flexfec;
 use crate::api::media_engine::{MIME_TYPE_H264, MIME_TYPE_OPUS, MIME_TYPE_VP8, MIME_TYPE_VP9};
 use crate::api::setting_engine::SettingEngine;
 use crate::api::APIBuilder;
@@ -14,7 +20,12 @@ use crate::peer_connection::peer_connection_test::{
     close_pair_now, create_vnet_pair, new_pair, send_video_until_done, signal_pair,
     until_connection_state,
 };
-use crate::rtp_transceiver::rtp_codec::RTCRtpCodecCapability;
+use crate::rtp_transceiver::priority_type::RTCPriorityType;
+use crate::rtp_transceiver::rtp_codec::{
+    RTCRtpCodecCapability, RTCRtpCodecParameters, RTCRtpHeaderExtensionCapability, RTPCodecType,
+};
+use crate::rtp_transceiver::RTCRtpTransceiverInit;
+use crate::track::track_local::track_local_static_rtp::TrackLocalStaticRTP;
 use crate::track::track_local::track_local_static_sample::TrackLocalStaticSample;
 
 #[tokio::test]
@@ -125,6 +136,7 @@ async fn test_rtp_sender_replace_track() -> Result<()> {
 async fn test_rtp_sender_get_parameters() -> Result<()> {
     let mut m = MediaEngine::default();
     m.register_default_codecs()?;
+    m.register_rtx_codecs()?;
     let api = APIBuilder::new().with_media_engine(m).build();
 
     let (mut offerer, mut answerer) = new_pair(&api).await?;
@@ -139,7 +151,96 @@ async fn test_rtp_sender_get_parameters() -> Result<()> {
     let parameters = sender.get_parameters().await;
     assert_ne!(0, parameters.rtp_parameters.codecs.len());
     assert_eq!(1, parameters.encodings.len());
-    assert_eq!(sender.ssrc, parameters.encodings[0].ssrc);
+    {
+        let track_encodings = sender.track_encodings.lock().await;
+        assert_eq!(track_encodings[0].ssrc, parameters.encodings[0].ssrc);
+        assert_eq!(
+            track_encodings[0].rtx_ssrc,
+            parameters.encodings[0].rtx.ssrc
+        );
+    }
+
+    close_pair_now(&offerer, &answerer).await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rtp_sender_rtx_ssrc_group() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    m.register_rtx_codecs()?;
+    let api = APIBuilder::new().with_media_engine(m).build();
+
+    let (offerer, answerer) = new_pair(&api).await?;
+
+    let track = Arc::new(TrackLocalStaticSample::new(
+        RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_VP8.to_owned(),
+            ..Default::default()
+        },
+        "video".to_owned(),
+        "webrtc-rs".to_owned(),
+    ));
+    let sender = offerer.add_track(track).await?;
+
+    let (ssrc, rtx_ssrc) = {
+        let track_encodings = sender.track_encodings.lock().await;
+        (track_encodings[0].ssrc, track_encodings[0].rtx_ssrc)
+    };
+
+    let offer = offerer.create_offer(None).await?;
+    assert!(offer
+        .sdp
+        .contains(&format!("a=ssrc-group:FID {ssrc} {rtx_ssrc}")));
+    assert!(offer
+        .sdp
+        .contains(&format!("a=ssrc:{rtx_ssrc} cname:webrtc-rs")));
+    assert!(offer.sdp.contains("a=fmtp:97 apt=96"));
+
+    close_pair_now(&offerer, &answerer).await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rtp_sender_fec_ssrc_group() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let registry = configure_flexfec(Registry::new(), &mut m, FlexFecEncoder::builder())?;
+    let api = APIBuilder::new()
+        .with_media_engine(m)
+        .with_interceptor_registry(registry)
+        .build();
+
+    let (mut offerer, mut answerer) = new_pair(&api).await?;
+
+    let track = Arc::new(TrackLocalStaticSample::new(
+        RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_VP8.to_owned(),
+            ..Default::default()
+        },
+        "video".to_owned(),
+        "webrtc-rs".to_owned(),
+    ));
+    let sender = offerer.add_track(track).await?;
+
+    let (ssrc, fec_ssrc) = {
+        let track_encodings = sender.track_encodings.lock().await;
+        (track_encodings[0].ssrc, track_encodings[0].fec_ssrc)
+    };
+
+    let offer = offerer.create_offer(None).await?;
+    assert!(offer
+        .sdp
+        .contains(&format!("a=ssrc-group:FEC-FR {ssrc} {fec_ssrc}")));
+    assert!(offer
+        .sdp
+        .contains(&format!("a=ssrc:{fec_ssrc} cname:webrtc-rs")));
+    assert!(offer.sdp.contains("a=rtpmap:49 flexfec-03/90000"));
+
+    signal_pair(&mut offerer, &mut answerer).await?;
+
+    let parameters = sender.get_parameters().await;
+    assert_eq!(fec_ssrc, parameters.encodings[0].fec.ssrc);
 
     close_pair_now(&offerer, &answerer).await;
     Ok(())
@@ -192,6 +293,108 @@ async fn test_rtp_sender_set_read_deadline() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_rtp_sender_max_bitrate() -> Result<()> {
+    const REQUESTER_SSRC: u32 = 0x1234;
+
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let api = APIBuilder::new().with_media_engine(m).build();
+
+    let (mut sender, mut receiver) = new_pair(&api).await?;
+
+    let track = Arc::new(TrackLocalStaticSample::new(
+        RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_VP8.to_owned(),
+            ..Default::default()
+        },
+        "video".to_owned(),
+        "webrtc-rs".to_owned(),
+    ));
+
+    let rtp_sender = sender
+        .add_track(Arc::clone(&track) as Arc<dyn TrackLocal + Send + Sync>)
+        .await?;
+    let ssrc = rtp_sender.track_encodings.lock().await[0].ssrc;
+
+    let (max_bitrate_tx, mut max_bitrate_rx) = mpsc::channel(1);
+    rtp_sender.on_max_bitrate(Box::new(move |rid, bitrate| {
+        let max_bitrate_tx = max_bitrate_tx.clone();
+        Box::pin(async move {
+            let _ = max_bitrate_tx.send((rid, bitrate)).await;
+        })
+    }));
+
+    // The receiver asks for a limit and waits for the TMMBN acknowledging it.
+    let (tmmbn_tx, tmmbn_rx) = mpsc::channel::<()>(1);
+    let tmmbn_tx = Arc::new(tmmbn_tx);
+    receiver.on_track(Box::new(move |_, rtp_receiver, _| {
+        let tmmbn_tx = Arc::clone(&tmmbn_tx);
+        Box::pin(async move {
+            let tmmbr = TemporaryMaximumMediaStreamBitrateRequest {
+                sender_ssrc: REQUESTER_SSRC,
+                items: vec![TmmbItem {
+                    ssrc,
+                    bitrate: 500_000,
+                    overhead: 40,
+                }],
+            };
+            rtp_receiver
+                .transport()
+                .write_rtcp(&[Box::new(tmmbr)])
+                .await
+                .unwrap();
+
+            while let Ok((pkts, _)) = rtp_receiver.read_rtcp().await {
+                // The tuple of the notification is owned by the requester
+                let acknowledged = pkts.iter().any(|p| {
+                    p.as_any()
+                        .downcast_ref::<TemporaryMaximumMediaStreamBitrateNotification>()
+                        .map_or(false, |tmmbn| {
+                            *tmmbn
+                                == TemporaryMaximumMediaStreamBitrateNotification {
+                                    sender_ssrc: ssrc,
+                                    items: vec![TmmbItem {
+                                        ssrc: REQUESTER_SSRC,
+                                        bitrate: 500_000,
+                                        overhead: 40,
+                                    }],
+                                }
+                        })
+                });
+                if acknowledged {
+                    let _ = tmmbn_tx.send(()).await;
+                    return;
+                }
+            }
+        })
+    }));
+
+    signal_pair(&mut sender, &mut receiver).await?;
+
+    let rtcp_sender = Arc::clone(&rtp_sender);
+    tokio::spawn(async move { while rtcp_sender.read_rtcp().await.is_ok() {} });
+
+    let sending = tokio::spawn(send_video_until_done(
+        tmmbn_rx,
+        vec![track],
+        Bytes::from_static(&[0xAA]),
+        None,
+    ));
+
+    let max_bitrate = tokio::time::timeout(Duration::from_secs(10), max_bitrate_rx.recv())
+        .await
+        .expect("TMMBR should fire on_max_bitrate");
+    assert_eq!(max_bitrate, Some((String::new(), 500_000)));
+    tokio::time::timeout(Duration::from_secs(10), sending)
+        .await
+        .expect("TMMBR should be acknowledged with TMMBN")
+        .unwrap();
+
+    close_pair_now(&sender, &receiver).await;
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_rtp_sender_replace_track_invalid_track_kind_change() -> Result<()> {
     let mut m = MediaEngine::default();
@@ -334,3 +537,421 @@ async fn test_rtp_sender_replace_track_invalid_codec_change() -> Result<()> {
     close_pair_now(&sender, &receiver).await;
     Ok(())
 }
+
+#[tokio::test]
+async fn test_rtp_sender_add_encoding() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let api = APIBuilder::new().with_media_engine(m).build();
+
+    let (offerer, answerer) = new_pair(&api).await?;
+
+    let new_track = |id: &str, rid: Option<&str>, mime_type: &str| {
+        let codec = RTCRtpCodecCapability {
+            mime_type: mime_type.to_owned(),
+            ..Default::default()
+        };
+        Arc::new(match rid {
+            Some(rid) => TrackLocalStaticRTP::new_with_rid(
+                codec,
+                id.to_owned(),
+                rid.to_owned(),
+                "webrtc-rs".to_owned(),
+            ),
+            None => TrackLocalStaticRTP::new(codec, id.to_owned(), "webrtc-rs".to_owned()),
+        })
+    };
+
+    // A sender without rid has no base encoding for simulcast
+    let sender = offerer
+        .add_track(new_track("video", None, MIME_TYPE_VP8))
+        .await?;
+    assert_eq!(
+        sender
+            .add_encoding(new_track("video", Some("h"), MIME_TYPE_VP8))
+            .await,
+        Err(Error::ErrRTPSenderNoBaseEncoding)
+    );
+
+    let sender = offerer
+        .add_track(new_track("video2", Some("q"), MIME_TYPE_VP8))
+        .await?;
+    assert_eq!(
+        sender
+            .add_encoding(new_track("video2", None, MIME_TYPE_VP8))
+            .await,
+        Err(Error::ErrRTPSenderRidNil)
+    );
+    assert_eq!(
+        sender
+            .add_encoding(new_track("other", Some("h"), MIME_TYPE_VP8))
+            .await,
+        Err(Error::ErrRTPSenderBaseEncodingMismatch)
+    );
+    assert_eq!(
+        sender
+            .add_encoding(new_track("video2", Some("h"), MIME_TYPE_OPUS))
+            .await,
+        Err(Error::ErrRTPSenderBaseEncodingMismatch)
+    );
+    assert_eq!(
+        sender
+            .add_encoding(new_track("video2", Some("q"), MIME_TYPE_VP8))
+            .await,
+        Err(Error::ErrRTPSenderRIDCollision)
+    );
+    sender
+        .add_encoding(new_track("video2", Some("h"), MIME_TYPE_VP8))
+        .await?;
+    assert_eq!(sender.tracks().await.len(), 2);
+
+    let parameters = sender.get_parameters().await;
+    let rids: Vec<&str> = parameters
+        .encodings
+        .iter()
+        .map(|e| e.rid.as_str())
+        .collect();
+    assert_eq!(rids, vec!["q", "h"]);
+    assert_ne!(parameters.encodings[0].ssrc, parameters.encodings[1].ssrc);
+
+    close_pair_now(&offerer, &answerer).await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rtp_sender_simulcast() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    for extension in [
+        ::sdp::extmap::SDES_MID_URI,
+        ::sdp::extmap::SDES_RTP_STREAM_ID_URI,
+        crate::SDES_REPAIR_RTP_STREAM_ID_URI,
+    ] {
+        m.register_header_extension(
+            RTCRtpHeaderExtensionCapability {
+                uri: extension.to_owned(),
+            },
+            RTPCodecType::Video,
+            None,
+        )?;
+    }
+    let api = APIBuilder::new().with_media_engine(m).build();
+
+    let (mut offerer, mut answerer) = new_pair(&api).await?;
+
+    let rids = ["q", "h", "f"];
+    let tracks: Vec<Arc<TrackLocalStaticRTP>> = rids
+        .iter()
+        .map(|rid| {
+            Arc::new(TrackLocalStaticRTP::new_with_rid(
+                RTCRtpCodecCapability {
+                    mime_type: MIME_TYPE_VP8.to_owned(),
+                    ..Default::default()
+                },
+                "video".to_owned(),
+                rid.to_string(),
+                "webrtc-rs".to_owned(),
+            ))
+        })
+        .collect();
+
+    // The layers are configured by the send encodings of the transceiver
+    let transceiver = offerer
+        .add_transceiver_from_track(
+            Arc::clone(&tracks[0]) as Arc<dyn TrackLocal + Send + Sync>,
+            Some(RTCRtpTransceiverInit {
+                direction: RTCRtpTransceiverDirection::Sendonly,
+                send_encodings: rids
+                    .iter()
+                    .map(|rid| RTCRtpEncodingParameters {
+                        rid: SmolStr::from(*rid),
+                        ..Default::default()
+                    })
+                    .collect(),
+            }),
+        )
+        .await?;
+    let sender = transceiver.sender().await;
+    for track in &tracks[1..] {
+        sender
+            .add_encoding(Arc::clone(track) as Arc<dyn TrackLocal + Send + Sync>)
+            .await?;
+    }
+
+    let offer = offerer.create_offer(None).await?;
+    for rid in rids {
+        assert!(offer.sdp.contains(&format!("a=rid:{rid} send")));
+    }
+    assert!(offer.sdp.contains("a=simulcast:send q;h;f"));
+    let parameters = sender.get_parameters().await;
+    assert_eq!(parameters.encodings.len(), 3);
+    for encoding in &parameters.encodings {
+        assert!(offer
+            .sdp
+            .contains(&format!("a=ssrc:{} cname:webrtc-rs", encoding.ssrc)));
+    }
+
+    let (seen_rids_tx, mut seen_rids_rx) = mpsc::channel(3);
+    answerer.on_track(Box::new(move |track, rtp_receiver, _| {
+        let seen_rids_tx = seen_rids_tx.clone();
+        Box::pin(async move {
+            // The packets of each layer carry its rid
+            let rid_id = track
+                .params()
+                .header_extensions
+                .iter()
+                .find(|ext| ext.uri == ::sdp::extmap::SDES_RTP_STREAM_ID_URI)
+                .map(|ext| ext.id as u8);
+            if let (Ok((pkt, _)), Some(rid_id)) = (track.read_rtp().await, rid_id) {
+                let rid = pkt.header.get_extension(rid_id).unwrap_or_default();
+                assert_eq!(&rid[..], track.rid().as_bytes());
+                let _ = seen_rids_tx.send(track.rid().to_owned()).await;
+
+                // Each layer asks for a keyframe of its own
+                rtp_receiver
+                    .transport()
+                    .write_rtcp(&[Box::new(PictureLossIndication {
+                        sender_ssrc: 0,
+                        media_ssrc: track.ssrc(),
+                    })])
+                    .await
+                    .unwrap();
+            }
+        })
+    }));
+
+    signal_pair(&mut offerer, &mut answerer).await?;
+
+    let mut seen_rids = HashSet::new();
+    let mut sequence_number = 0u16;
+    for _ in 0..250 {
+        tokio::select! {
+            rid = seen_rids_rx.recv() => {
+                if let Some(rid) = rid {
+                    seen_rids.insert(rid);
+                }
+                if seen_rids.len() == rids.len() {
+                    break;
+                }
+            }
+            _ = tokio::time::sleep(Duration::from_millis(20)) => {
+                for track in &tracks {
+                    track
+                        .write_rtp(&rtp::packet::Packet {
+                            header: rtp::header::Header {
+                                version: 2,
+                                sequence_number,
+                                marker: true,
+                                ..Default::default()
+                            },
+                            payload: Bytes::from_static(&[0x00]),
+                        })
+                        .await?;
+                }
+                sequence_number = sequence_number.wrapping_add(1);
+            }
+        }
+    }
+    assert_eq!(
+        seen_rids,
+        rids.iter()
+            .map(|rid| rid.to_string())
+            .collect::<HashSet<_>>()
+    );
+
+    // read_rtcp reads the RTCP of all layers
+    let mut pli_ssrcs = HashSet::new();
+    while pli_ssrcs.len() < rids.len() {
+        let (pkts, _) = tokio::time::timeout(Duration::from_secs(5), sender.read_rtcp())
+            .await
+            .expect("the PLI of every layer should be read")?;
+        pli_ssrcs.extend(pkts.iter().filter_map(|p| {
+            p.as_any()
+                .downcast_ref::<PictureLossIndication>()
+                .map(|pli| pli.media_ssrc)
+        }));
+    }
+    assert_eq!(
+        pli_ssrcs,
+        parameters
+            .encodings
+            .iter()
+            .map(|encoding| encoding.ssrc)
+            .collect::<HashSet<_>>()
+    );
+
+    close_pair_now(&offerer, &answerer).await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rtp_sender_set_parameters() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let api = APIBuilder::new().with_media_engine(m).build();
+
+    let (offerer, answerer) = new_pair(&api).await?;
+
+    let track = Arc::new(TrackLocalStaticSample::new(
+        RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_VP8.to_owned(),
+            ..Default::default()
+        },
+        "video".to_owned(),
+        "webrtc-rs".to_owned(),
+    ));
+    let sender = offerer.add_track(track).await?;
+
+    // The parameters must come from get_parameters
+    let parameters = sender.send_parameters().await;
+    assert_eq!(
+        sender.set_parameters(parameters).await,
+        Err(Error::ErrRTPSenderInvalidTransactionId)
+    );
+
+    // Only the parameters which don't need renegotiation can be changed
+    let mut parameters = sender.get_parameters().await;
+    parameters.encodings[0].ssrc = parameters.encodings[0].ssrc.wrapping_add(1);
+    assert_eq!(
+        sender.set_parameters(parameters).await,
+        Err(Error::ErrRTPSenderInvalidModification)
+    );
+
+    let mut parameters = sender.get_parameters().await;
+    parameters.encodings[0].scale_resolution_down_by = Some(0.5);
+    assert_eq!(
+        sender.set_parameters(parameters).await,
+        Err(Error::ErrRTPSenderInvalidScaleResolutionDownBy)
+    );
+
+    let mut parameters = sender.get_parameters().await;
+    parameters.encodings[0].active = false;
+    parameters.encodings[0].max_bitrate = Some(500_000);
+    parameters.encodings[0].scale_resolution_down_by = Some(2.0);
+    parameters.encodings[0].priority = RTCPriorityType::High;
+    sender.set_parameters(parameters.clone()).await?;
+
+    // A transaction can only be used once
+    assert_eq!(
+        sender.set_parameters(parameters).await,
+        Err(Error::ErrRTPSenderInvalidTransactionId)
+    );
+
+    let parameters = sender.get_parameters().await;
+    assert!(!parameters.encodings[0].active);
+    assert_eq!(parameters.encodings[0].max_bitrate, Some(500_000));
+    assert_eq!(parameters.encodings[0].scale_resolution_down_by, Some(2.0));
+    assert_eq!(parameters.encodings[0].priority, RTCPriorityType::High);
+    assert_eq!(
+        parameters.encodings[0].network_priority,
+        RTCPriorityType::Low
+    );
+
+    close_pair_now(&offerer, &answerer).await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rtp_sender_set_parameters_pause_simulcast_layer() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    for extension in [
+        ::sdp::extmap::SDES_MID_URI,
+        ::sdp::extmap::SDES_RTP_STREAM_ID_URI,
+    ] {
+        m.register_header_extension(
+            RTCRtpHeaderExtensionCapability {
+                uri: extension.to_owned(),
+            },
+            RTPCodecType::Video,
+            None,
+        )?;
+    }
+    let api = APIBuilder::new().with_media_engine(m).build();
+
+    let (mut offerer, mut answerer) = new_pair(&api).await?;
+
+    let rids = ["h", "f"];
+    let tracks: Vec<Arc<TrackLocalStaticRTP>> = rids
+        .iter()
+        .map(|rid| {
+            Arc::new(TrackLocalStaticRTP::new_with_rid(
+                RTCRtpCodecCapability {
+                    mime_type: MIME_TYPE_VP8.to_owned(),
+                    ..Default::default()
+                },
+                "video".to_owned(),
+                rid.to_string(),
+                "webrtc-rs".to_owned(),
+            ))
+        })
+        .collect();
+
+    let transceiver = offerer
+        .add_transceiver_from_track(
+            Arc::clone(&tracks[0]) as Arc<dyn TrackLocal + Send + Sync>,
+            Some(RTCRtpTransceiverInit {
+                direction: RTCRtpTransceiverDirection::Sendonly,
+                send_encodings: rids
+                    .iter()
+                    .map(|rid| RTCRtpEncodingParameters {
+                        rid: SmolStr::from(*rid),
+                        ..Default::default()
+                    })
+                    .collect(),
+            }),
+        )
+        .await?;
+    let sender = transceiver.sender().await;
+    sender
+        .add_encoding(Arc::clone(&tracks[1]) as Arc<dyn TrackLocal + Send + Sync>)
+        .await?;
+
+    signal_pair(&mut offerer, &mut answerer).await?;
+
+    for _ in 0..250 {
+        if sender.has_sent() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(sender.has_sent());
+    assert!(!tracks[0].any_binding_paused().await);
+    assert!(!tracks[1].any_binding_paused().await);
+
+    // Pausing a layer doesn't pause the others
+    let mut parameters = sender.get_parameters().await;
+    parameters.encodings[1].active = false;
+    sender.set_parameters(parameters).await?;
+    assert!(!tracks[0].any_binding_paused().await);
+    assert!(tracks[1].all_binding_paused().await);
+
+    let mut parameters = sender.get_parameters().await;
+    parameters.encodings[1].active = true;
+    sender.set_parameters(parameters).await?;
+    assert!(!tracks[1].any_binding_paused().await);
+
+    close_pair_now(&offerer, &answerer).await;
+    Ok(())
+}
+
+#[test]
+fn test_quality_limitation_durations() {
+    let start = Instant::now();
+    let mut quality_limitation = QualityLimitation::new(start);
+    quality_limitation.set_reason(
+        RTCQualityLimitationReason::Bandwidth,
+        start + Duration::from_secs(2),
+    );
+    quality_limitation.set_reason(
+        RTCQualityLimitationReason::Cpu,
+        start + Duration::from_secs(5),
+    );
+    assert_eq!(quality_limitation.reason(), RTCQualityLimitationReason::Cpu);
+
+    let durations = quality_limitation.durations(start + Duration::from_secs(6));
+    assert_eq!(durations[&RTCQualityLimitationReason::None], 2.0);
+    assert_eq!(durations[&RTCQualityLimitationReason::Bandwidth], 3.0);
+    assert_eq!(durations[&RTCQualityLimitationReason::Cpu], 1.0);
+    assert_eq!(durations[&RTCQualityLimitationReason::Other], 0.0);
+}
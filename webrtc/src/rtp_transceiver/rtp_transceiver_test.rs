@@ -1,7 +1,9 @@
 use std::sync::atomic::AtomicUsize;
 
+use bytes::Bytes;
+
 use super::*;
-use crate::api::media_engine::{MIME_TYPE_OPUS, MIME_TYPE_VP8, MIME_TYPE_VP9};
+use crate::api::media_engine::{MIME_TYPE_OPUS, MIME_TYPE_RED, MIME_TYPE_VP8, MIME_TYPE_VP9};
 use crate::api::APIBuilder;
 use crate::dtls_transport::RTCDtlsTransport;
 use crate::peer_connection::configuration::RTCConfiguration;
@@ -212,6 +214,41 @@ async fn test_rtp_transceiver_set_codec_preferences_payload_type() -> Result<()>
     Ok(())
 }
 
+#[test]
+fn test_rtp_codec_red_payloader() -> Result<()> {
+    let codec = RTCRtpCodecCapability {
+        mime_type: MIME_TYPE_RED.to_owned(),
+        clock_rate: 48000,
+        channels: 2,
+        sdp_fmtp_line: "111/111".to_owned(),
+        rtcp_feedback: vec![],
+    };
+    let mut payloader = codec.payloader_for_codec()?;
+    let payloads = payloader.payload_with_timestamp(100, &Bytes::from_static(&[0x01]), 0)?;
+    assert_eq!(payloads, vec![Bytes::from_static(&[111, 0x01])]);
+    let payloads = payloader.payload_with_timestamp(100, &Bytes::from_static(&[0x02]), 960)?;
+    assert_eq!(
+        payloads,
+        vec![Bytes::from_static(&[
+            0x80 | 111,
+            0x0F,
+            0x00,
+            0x01,
+            111,
+            0x01,
+            0x02
+        ])]
+    );
+
+    let codec = RTCRtpCodecCapability {
+        sdp_fmtp_line: "".to_owned(),
+        ..codec
+    };
+    assert!(codec.payloader_for_codec().is_err());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_rtp_transceiver_direction_change() -> Result<()> {
     let (offer_pc, answer_pc, _) = create_vnet_pair().await?;
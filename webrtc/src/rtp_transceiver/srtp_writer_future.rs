@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Weak};
 
@@ -19,24 +20,30 @@ use crate::rtp_transceiver::SSRC;
 /// Used to override outgoing `RTP` packets' sequence numbers. On creating it is
 /// unabled and can be enabled before sending data beginning. Once data sending
 /// began it can not be enabled any more.
+///
+/// The sequence numbers of each `SSRC`, like the ones of the simulcast layers of
+/// a sender, are overridden separately.
 pub(crate) struct SequenceTransformer(util::sync::Mutex<SequenceTransformerInner>);
 
 /// [`SequenceTransformer`] inner.
 struct SequenceTransformerInner {
+    streams: HashMap<SSRC, SequenceState>,
+    enabled: bool,
+    data_sent: bool,
+}
+
+/// Sequence numbers state of a single `SSRC`.
+struct SequenceState {
     offset: u16,
     last_sq: u16,
     reset_needed: bool,
-    enabled: bool,
-    data_sent: bool,
 }
 
 impl SequenceTransformer {
     /// Creates a new [`SequenceTransformer`].
     pub(crate) fn new() -> Self {
         Self(util::sync::Mutex::new(SequenceTransformerInner {
-            offset: 0,
-            last_sq: rand::random(),
-            reset_needed: false,
+            streams: HashMap::new(),
             enabled: false,
             data_sent: false,
         }))
@@ -66,16 +73,18 @@ impl SequenceTransformer {
     }
 
     /// Indicates [`SequenceTransformer`] about necessity of recalculating
-    /// `offset`.
-    pub(crate) fn reset_offset(&self) {
-        self.0.lock().reset_needed = true;
+    /// `offset` of the given `SSRC`.
+    pub(crate) fn reset_offset(&self, ssrc: SSRC) {
+        if let Some(state) = self.0.lock().streams.get_mut(&ssrc) {
+            state.reset_needed = true;
+        }
     }
 
     /// Gets [`Some`] consistent `sequence number` if this [`SequenceTransformer`] is
     /// enabled or [`None`] if it is not.
     ///
     /// Once this method is called, considers data sending began.
-    fn seq_number(&self, raw_sn: u16) -> Option<u16> {
+    fn seq_number(&self, ssrc: SSRC, raw_sn: u16) -> Option<u16> {
         let mut guard = self.0.lock();
         guard.data_sent = true;
 
@@ -83,17 +92,18 @@ impl SequenceTransformer {
             return None;
         }
 
-        let offset = guard
-            .reset_needed
-            .then(|| {
-                guard.reset_needed = false;
-                let offset = guard.last_sq.overflowing_sub(raw_sn.overflowing_sub(1).0).0;
-                guard.offset = offset;
-                offset
-            })
-            .unwrap_or(guard.offset);
+        let state = guard.streams.entry(ssrc).or_insert_with(|| SequenceState {
+            offset: 0,
+            last_sq: rand::random(),
+            reset_needed: false,
+        });
+        if state.reset_needed {
+            state.reset_needed = false;
+            state.offset = state.last_sq.overflowing_sub(raw_sn.overflowing_sub(1).0).0;
+        }
+        let offset = state.offset;
         let next = raw_sn.overflowing_add(offset).0;
-        guard.last_sq = next;
+        state.last_sq = next;
 
         Some(next)
     }
@@ -275,8 +285,16 @@ impl RTCPReader for SrtpWriterFuture {
 #[async_trait]
 impl RTPWriter for SrtpWriterFuture {
     async fn write(&self, pkt: &rtp::packet::Packet, _a: &Attributes) -> IResult<usize> {
+        // Packets of the RTX stream have sequence numbers of their own
+        if pkt.header.ssrc != self.ssrc {
+            return Ok(self.write_rtp(pkt).await?);
+        }
+
         Ok(
-            match self.seq_trans.seq_number(pkt.header.sequence_number) {
+            match self
+                .seq_trans
+                .seq_number(self.ssrc, pkt.header.sequence_number)
+            {
                 Some(seq_num) => {
                     let mut new_pkt = pkt.clone();
                     new_pkt.header.sequence_number = seq_num;
@@ -25,10 +25,11 @@ use crate::data_channel::RTCDataChannel;
 use crate::dtls_transport::dtls_role::DTLSRole;
 use crate::dtls_transport::*;
 use crate::error::*;
+use crate::rtp_transceiver::priority_type::RTCPriorityType;
 use crate::sctp_transport::sctp_transport_capabilities::SCTPTransportCapabilities;
 use crate::stats::stats_collector::StatsCollector;
 use crate::stats::StatsReportType::{PeerConnection, SCTPTransport};
-use crate::stats::{ICETransportStats, PeerConnectionStats};
+use crate::stats::{PeerConnectionStats, SCTPTransportStats};
 
 const SCTP_MAX_CHANNELS: u16 = u16::MAX;
 
@@ -158,6 +159,7 @@ impl RTCSctpTransport {
                         max_receive_buffer_size: 0,
                         max_message_size: 0,
                         name: String::new(),
+                        stream_scheduler: self.setting_engine.get_sctp_stream_scheduler(),
                     }) => {
                         break Arc::new(association?);
                     }
@@ -286,6 +288,7 @@ impl RTCSctpTransport {
                     ordered,
                     max_packet_life_time: max_packet_lifetime,
                     max_retransmits,
+                    priority: RTCPriorityType::from_data_channel_priority(dc.config.priority),
                 },
                 Arc::clone(&param.setting_engine),
             ));
@@ -385,7 +388,9 @@ impl RTCSctpTransport {
 
         // conn
         if let Some(agent) = dtls_transport.ice_transport.gatherer.get_agent().await {
-            let stats = ICETransportStats::new("sctp_transport".to_owned(), agent);
+            let association = self.association().await;
+            let stats =
+                SCTPTransportStats::new("sctp_transport".to_owned(), agent, association.as_deref());
             reports.insert(stats.id.clone(), SCTPTransport(stats));
         }
 
@@ -6,6 +6,7 @@ use ice::agent::agent_stats::{CandidatePairStats, CandidateStats};
 use ice::agent::Agent;
 use ice::candidate::{CandidatePairState, CandidateType};
 use ice::network_type::NetworkType;
+use sctp::association::Association;
 use serde::{Serialize, Serializer};
 use smol_str::SmolStr;
 use stats_collector::StatsCollector;
@@ -14,11 +15,17 @@ use tokio::time::Instant;
 use crate::data_channel::data_channel_state::RTCDataChannelState;
 use crate::data_channel::RTCDataChannel;
 use crate::dtls_transport::dtls_fingerprint::RTCDtlsFingerprint;
+use crate::dtls_transport::dtls_role::DTLSRole;
+use crate::dtls_transport::dtls_transport_state::RTCDtlsTransportState;
+use crate::ice_transport::ice_role::RTCIceRole;
+use crate::ice_transport::ice_transport_state::RTCIceTransportState;
 use crate::peer_connection::certificate::RTCCertificate;
 use crate::rtp_transceiver::rtp_codec::RTCRtpCodecParameters;
 use crate::rtp_transceiver::{PayloadType, SSRC};
 use crate::sctp_transport::RTCSctpTransport;
 
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
 mod serialize;
 pub mod stats_collector;
 
@@ -38,6 +45,10 @@ pub enum RTCStatsType {
     InboundRTP,
     #[serde(rename = "local-candidate")]
     LocalCandidate,
+    #[serde(rename = "media-playout")]
+    MediaPlayout,
+    #[serde(rename = "media-source")]
+    MediaSource,
     #[serde(rename = "outbound-rtp")]
     OutboundRTP,
     #[serde(rename = "peer-connection")]
@@ -68,9 +79,11 @@ pub enum StatsReportType {
     Codec(CodecStats),
     DataChannel(DataChannelStats),
     LocalCandidate(ICECandidateStats),
+    MediaPlayout(MediaPlayoutStats),
+    MediaSource(MediaSourceStats),
     PeerConnection(PeerConnectionStats),
     RemoteCandidate(ICECandidateStats),
-    SCTPTransport(ICETransportStats),
+    SCTPTransport(SCTPTransportStats),
     Transport(ICETransportStats),
     InboundRTP(InboundRTPStats),
     OutboundRTP(OutboundRTPStats),
@@ -102,6 +115,8 @@ impl Serialize for StatsReportType {
             StatsReportType::Codec(stats) => stats.serialize(serializer),
             StatsReportType::DataChannel(stats) => stats.serialize(serializer),
             StatsReportType::LocalCandidate(stats) => stats.serialize(serializer),
+            StatsReportType::MediaPlayout(stats) => stats.serialize(serializer),
+            StatsReportType::MediaSource(stats) => stats.serialize(serializer),
             StatsReportType::PeerConnection(stats) => stats.serialize(serializer),
             StatsReportType::RemoteCandidate(stats) => stats.serialize(serializer),
             StatsReportType::SCTPTransport(stats) => stats.serialize(serializer),
@@ -234,7 +249,11 @@ pub struct ICECandidateStats {
     pub network_type: NetworkType,
     pub port: u16,
     pub priority: u32,
-    pub relay_protocol: String,
+    pub foundation: String,
+    pub related_address: Option<String>,
+    pub related_port: Option<u16>,
+    /// Only present for local relay candidates.
+    pub relay_protocol: Option<String>,
     pub url: String,
 }
 
@@ -248,7 +267,10 @@ impl ICECandidateStats {
             network_type: stats.network_type,
             port: stats.port,
             priority: stats.priority,
-            relay_protocol: stats.relay_protocol,
+            foundation: stats.foundation,
+            related_address: stats.related_address,
+            related_port: stats.related_port,
+            relay_protocol: (!stats.relay_protocol.is_empty()).then_some(stats.relay_protocol),
             stats_type,
             timestamp: stats.timestamp,
             url: stats.url,
@@ -266,6 +288,22 @@ pub struct ICETransportStats {
     pub stats_type: RTCStatsType,
     pub id: String,
 
+    // RTCTransportStats
+    pub ice_role: RTCIceRole,
+    pub ice_state: RTCIceTransportState,
+    pub selected_candidate_pair_id: Option<String>,
+    pub dtls_state: RTCDtlsTransportState,
+    pub dtls_role: Option<DTLSRole>,
+    pub local_certificate_id: Option<String>,
+    /// The DTLS version, "FEFD" for DTLS 1.2 and "FEFC" for DTLS 1.3. Only present
+    /// once the handshake has chosen a cipher suite.
+    pub tls_version: Option<String>,
+    /// The IANA name of the DTLS cipher suite, for example
+    /// "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256".
+    pub dtls_cipher: Option<String>,
+    /// The IANA name of the SRTP protection profile, for example "AES_CM_128_HMAC_SHA1_80".
+    pub srtp_cipher: Option<String>,
+
     // Non-canon
     pub bytes_received: usize,
     pub bytes_sent: usize,
@@ -275,6 +313,15 @@ impl ICETransportStats {
     pub(crate) fn new(id: String, agent: Arc<Agent>) -> Self {
         ICETransportStats {
             id,
+            ice_role: RTCIceRole::default(),
+            ice_state: RTCIceTransportState::default(),
+            selected_candidate_pair_id: None,
+            dtls_state: RTCDtlsTransportState::default(),
+            dtls_role: None,
+            local_certificate_id: None,
+            tls_version: None,
+            dtls_cipher: None,
+            srtp_cipher: None,
             bytes_received: agent.get_bytes_received(),
             bytes_sent: agent.get_bytes_sent(),
             stats_type: RTCStatsType::Transport,
@@ -283,6 +330,37 @@ impl ICETransportStats {
     }
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SCTPTransportStats {
+    // RTCStats
+    #[serde(with = "serialize::instant_to_epoch_seconds")]
+    pub timestamp: Instant,
+    #[serde(rename = "type")]
+    pub stats_type: RTCStatsType,
+    pub id: String,
+
+    // Non-canon
+    pub bytes_received: usize,
+    pub bytes_sent: usize,
+    /// MTU the SCTP association sizes its packets to, as found by the path MTU
+    /// discovery. None until the association is established.
+    pub mtu: Option<u32>,
+}
+
+impl SCTPTransportStats {
+    pub(crate) fn new(id: String, agent: Arc<Agent>, association: Option<&Association>) -> Self {
+        SCTPTransportStats {
+            id,
+            bytes_received: agent.get_bytes_received(),
+            bytes_sent: agent.get_bytes_sent(),
+            mtu: association.map(|a| a.mtu()),
+            stats_type: RTCStatsType::Transport,
+            timestamp: Instant::now(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CertificateStats {
@@ -366,12 +444,16 @@ pub struct DataChannelStats {
     pub messages_sent: usize,
     pub protocol: String,
     pub state: RTCDataChannelState,
+
+    // Non-canon
+    pub buffered_amount: usize,
 }
 
 impl DataChannelStats {
     pub(crate) async fn from(data_channel: &RTCDataChannel) -> Self {
         let state = data_channel.ready_state();
 
+        let mut buffered_amount = 0;
         let mut bytes_received = 0;
         let mut bytes_sent = 0;
         let mut messages_received = 0;
@@ -380,6 +462,7 @@ impl DataChannelStats {
         let lock = data_channel.data_channel.lock().await;
 
         if let Some(internal) = &*lock {
+            buffered_amount = internal.buffered_amount();
             bytes_received = internal.bytes_received();
             bytes_sent = internal.bytes_sent();
             messages_received = internal.messages_received();
@@ -387,6 +470,7 @@ impl DataChannelStats {
         }
 
         Self {
+            buffered_amount,
             bytes_received,
             bytes_sent,
             data_channel_identifier: data_channel.id(), // TODO: "The value is initially null"
@@ -448,13 +532,14 @@ pub struct InboundRTPStats {
     // RTCRtpStreamStats
     pub ssrc: SSRC,
     pub kind: &'static str, // Either "video" or "audio"
-    // TODO: Add transportId
-    // TODO: Add codecId
+    pub transport_id: String,
+    pub codec_id: Option<String>,
 
     // RTCReceivedRtpStreamStats
     pub packets_received: u64,
-    // TODO: packetsLost
-    // TODO: jitter(maybe, might be uattainable for the same reason as `framesDropped`)
+    pub packets_lost: i64,
+    /// Estimated by the jitter buffer of the track, if it is read with one.
+    pub jitter: Option<f64>,
     // NB: `framesDropped` can't be produced since we aren't decoding, might be worth introducing a
     // way for consumers to control this in the future.
 
@@ -462,23 +547,41 @@ pub struct InboundRTPStats {
     pub track_identifier: String,
     pub mid: SmolStr,
     // TODO: `remoteId`
-    // NB: `framesDecoded`, `frameWidth`, frameHeight`, `framesPerSecond`, `qpSum`,
-    // `totalDecodeTime`, `totalInterFrameDelay`, and `totalSquaredInterFrameDelay` are all decoder
-    // specific values and can't be produced since we aren't decoding.
+    pub playout_id: Option<String>,
+    /// Only present for video.
+    pub frames_received: Option<u64>,
+    /// Reported by the application through [`TrackRemote::record_frame_decoded`], since
+    /// we aren't decoding. Only present for video.
+    ///
+    /// [`TrackRemote::record_frame_decoded`]: crate::track::track_remote::TrackRemote::record_frame_decoded
+    pub frames_decoded: Option<u64>,
+    pub total_decode_time: Option<f64>,
+    // NB: `frameWidth`, frameHeight`, `framesPerSecond`, `qpSum`, `totalInterFrameDelay`, and
+    // `totalSquaredInterFrameDelay` are all decoder specific values and can't be produced since
+    // we aren't decoding.
     pub last_packet_received_timestamp: Option<SystemTime>,
     pub header_bytes_received: u64,
-    // TODO: `packetsDiscarded`. This value only makes sense if we have jitter buffer, which we
-    // cannot assume.
+    /// Discarded by the jitter buffer of the track, if it is read with one.
+    pub packets_discarded: Option<u64>,
     // TODO: `fecPacketsReceived`, `fecPacketsDiscarded`
     pub bytes_received: u64,
     pub nack_count: u64,
     pub fir_count: Option<u64>,
     pub pli_count: Option<u64>,
-    // NB: `totalProcessingDelay`, `estimatedPlayoutTimestamp`, `jitterBufferDelay`,
-    // `jitterBufferTargetDelay`, `jitterBufferEmittedCount`, `jitterBufferMinimumDelay`,
-    // `totalSamplesReceived`, `concealedSamples`, `silentConcealedSamples`, `concealmentEvents`,
-    // `insertedSamplesForDeceleration`, `removedSamplesForAcceleration`, `audioLevel`,
-    // `totalAudioEneregy`, `totalSampleDuration`, `framesReceived, and `decoderImplementation` are
+    // The jitter buffer stats are only known if the track is read with a
+    // `JitterBufferReader`.
+    pub jitter_buffer_delay: Option<f64>,
+    pub jitter_buffer_target_delay: Option<f64>,
+    pub jitter_buffer_emitted_count: Option<u64>,
+    pub jitter_buffer_minimum_delay: Option<f64>,
+    /// Only present for audio.
+    pub concealed_samples: Option<u64>,
+    /// Only present for audio.
+    pub concealment_events: Option<u64>,
+    // NB: `totalProcessingDelay`, `estimatedPlayoutTimestamp`, `totalSamplesReceived`,
+    // `silentConcealedSamples`, `insertedSamplesForDeceleration`,
+    // `removedSamplesForAcceleration`, `audioLevel`,
+    // `totalAudioEneregy`, `totalSampleDuration`, and `decoderImplementation` are
     // all decoder specific and can't be produced since we aren't decoding.
 }
 
@@ -495,29 +598,38 @@ pub struct OutboundRTPStats {
     // RTCRtpStreamStats
     pub ssrc: SSRC,
     pub kind: &'static str, // Either "video" or "audio"
-    // TODO: Add transportId
-    // TODO: Add codecId
+    pub transport_id: String,
+    pub codec_id: Option<String>,
 
     // RTCSentRtpStreamStats
     pub packets_sent: u64,
     pub bytes_sent: u64,
 
     // RTCOutboundRtpStreamStats
-    // NB: non-canon in browsers this is available via `RTCMediaSourceStats` which we are unlikely to implement
+    // NB: non-canon in browsers this is available via `RTCMediaSourceStats`
     pub track_identifier: String,
     pub mid: SmolStr,
-    // TODO: `mediaSourceId` and `remoteId`
+    pub media_source_id: String,
+    // TODO: `remoteId`
     pub rid: Option<SmolStr>,
     pub header_bytes_sent: u64,
     // TODO: `retransmittedPacketsSent` and `retransmittedPacketsSent`
-    // NB: `targetBitrate`, `totalEncodedBytesTarget`, `frameWidth` `frameHeight`, `framesPerSecond`, `framesSent`,
+    /// Only present for video.
+    pub frames_sent: Option<u64>,
+    // NB: `targetBitrate`, `totalEncodedBytesTarget`, `frameWidth` `frameHeight`, `framesPerSecond`,
     // `hugeFramesSent`, `framesEncoded`, `keyFramesEncoded`, `qpSum`, and `totalEncodeTime` are
     // all encoder specific and can't be produced snce we aren't encoding.
     // TODO: `totalPacketSendDelay` time from `TrackLocalWriter::write_rtp` to being written to
     // socket.
-
-    // NB: `qualityLimitationReason`, `qualityLimitationDurations`, and `qualityLimitationResolutionChanges` are all
-    // encoder specific and can't be produced since we aren't encoding.
+    /// Reported by the application through [`RTCRtpSender::set_quality_limitation_reason`],
+    /// since we aren't encoding. Only present for video.
+    ///
+    /// [`RTCRtpSender::set_quality_limitation_reason`]: crate::rtp_transceiver::rtp_sender::RTCRtpSender::set_quality_limitation_reason
+    pub quality_limitation_reason: Option<RTCQualityLimitationReason>,
+    /// The seconds spent in each quality limitation reason. Only present for video.
+    pub quality_limitation_durations: Option<HashMap<RTCQualityLimitationReason, f64>>,
+    // NB: `qualityLimitationResolutionChanges` is encoder specific and can't be produced since we
+    // aren't encoding.
     pub nack_count: u64,
     pub fir_count: Option<u64>,
     pub pli_count: Option<u64>,
@@ -584,3 +696,70 @@ pub struct RemoteOutboundRTPStats {
     pub total_round_trip_time: f64,
     pub round_trip_time_measurements: u64,
 }
+
+/// The reason the quality of an outbound video stream is limited, as set with
+/// [`RTCRtpSender::set_quality_limitation_reason`].
+///
+/// [`RTCRtpSender::set_quality_limitation_reason`]: crate::rtp_transceiver::rtp_sender::RTCRtpSender::set_quality_limitation_reason
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize)]
+pub enum RTCQualityLimitationReason {
+    #[serde(rename = "none")]
+    #[default]
+    None,
+    #[serde(rename = "cpu")]
+    Cpu,
+    #[serde(rename = "bandwidth")]
+    Bandwidth,
+    #[serde(rename = "other")]
+    Other,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaSourceStats {
+    // RTCStats
+    #[serde(with = "serialize::instant_to_epoch_seconds")]
+    pub timestamp: Instant,
+    #[serde(rename = "type")]
+    pub stats_type: RTCStatsType,
+    pub id: String,
+
+    // RTCMediaSourceStats
+    pub track_identifier: String,
+    pub kind: &'static str, // Either "video" or "audio"
+
+    // RTCAudioSourceStats
+    /// The duration of the samples written to the track, only known for audio tracks written
+    /// with samples.
+    pub total_samples_duration: Option<f64>,
+    // NB: `audioLevel`, `totalAudioEnergy`, `echoReturnLoss` and `echoReturnLossEnhancement`
+    // need the raw audio and can't be produced since we aren't encoding.
+
+    // RTCVideoSourceStats
+    /// The number of samples written to the track, only known for video tracks written with
+    /// samples.
+    pub frames: Option<u64>,
+    // NB: `width`, `height` and `framesPerSecond` need the raw video and can't be produced since
+    // we aren't encoding.
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaPlayoutStats {
+    // RTCStats
+    #[serde(with = "serialize::instant_to_epoch_seconds")]
+    pub timestamp: Instant,
+    #[serde(rename = "type")]
+    pub stats_type: RTCStatsType,
+    pub id: String,
+
+    // RTCAudioPlayoutStats
+    pub kind: &'static str, // Always "audio"
+    /// The duration of the gaps concealed by the jitter buffer of the track.
+    pub synthesized_samples_duration: f64,
+    pub synthesized_samples_events: u64,
+    /// The sum of the times the emitted frames spent in the jitter buffer of the track.
+    pub total_playout_delay: f64,
+    // NB: `totalSamplesDuration` and `totalSamplesCount` are decoder specific and can't be
+    // produced since we aren't decoding.
+}
@@ -0,0 +1,792 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::sync::{Arc, Weak};
+
+use ice::candidate::CandidatePairState;
+use util::sync::Mutex;
+
+use super::{RTCQualityLimitationReason, StatsReport, StatsReportType};
+use crate::peer_connection::peer_connection_state::RTCPeerConnectionState;
+use crate::peer_connection::RTCPeerConnection;
+
+/// CONTENT_TYPE is the content type of the Prometheus text exposition format, to be
+/// sent along with the output of [`encode`] and [`StatsRegistry::gather`].
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum MetricType {
+    Counter,
+    Gauge,
+}
+
+impl MetricType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MetricType::Counter => "counter",
+            MetricType::Gauge => "gauge",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct MetricFamily {
+    help: &'static str,
+    metric_type: MetricType,
+    samples: Vec<(String, f64)>,
+}
+
+/// MetricSet groups the samples of every metric by family, since the text format
+/// requires all samples of a family to follow its HELP and TYPE lines.
+#[derive(Debug, Default)]
+struct MetricSet {
+    families: BTreeMap<&'static str, MetricFamily>,
+}
+
+impl MetricSet {
+    fn counter(&mut self, name: &'static str, help: &'static str, labels: &Labels, value: f64) {
+        self.add(name, help, MetricType::Counter, labels, value);
+    }
+
+    fn gauge(&mut self, name: &'static str, help: &'static str, labels: &Labels, value: f64) {
+        self.add(name, help, MetricType::Gauge, labels, value);
+    }
+
+    fn add(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        metric_type: MetricType,
+        labels: &Labels,
+        value: f64,
+    ) {
+        self.families
+            .entry(name)
+            .or_insert_with(|| MetricFamily {
+                help,
+                metric_type,
+                samples: vec![],
+            })
+            .samples
+            .push((labels.encode(), value));
+    }
+
+    fn add_report(&mut self, report: &StatsReport, base: &Labels) {
+        let mut reports: Vec<_> = report.reports.iter().collect();
+        reports.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut candidate_pair_states = vec![];
+        let mut sctp_buffered_amount = 0;
+
+        for (id, stats) in &reports {
+            match stats {
+                StatsReportType::CandidatePair(stats) => {
+                    candidate_pair_states.push(stats.state);
+
+                    let labels = base.with("id", id);
+                    self.counter(
+                        "webrtc_ice_candidate_pair_packets_sent_total",
+                        "Packets sent on the candidate pair.",
+                        &labels,
+                        stats.packets_sent as f64,
+                    );
+                    self.counter(
+                        "webrtc_ice_candidate_pair_packets_received_total",
+                        "Packets received on the candidate pair.",
+                        &labels,
+                        stats.packets_received as f64,
+                    );
+                    self.counter(
+                        "webrtc_ice_candidate_pair_bytes_sent_total",
+                        "Bytes sent on the candidate pair.",
+                        &labels,
+                        stats.bytes_sent as f64,
+                    );
+                    self.counter(
+                        "webrtc_ice_candidate_pair_bytes_received_total",
+                        "Bytes received on the candidate pair.",
+                        &labels,
+                        stats.bytes_received as f64,
+                    );
+                    self.gauge(
+                        "webrtc_ice_candidate_pair_current_round_trip_time_seconds",
+                        "Latest round trip time measured with STUN requests on the candidate pair.",
+                        &labels,
+                        stats.current_round_trip_time,
+                    );
+                    self.counter(
+                        "webrtc_ice_candidate_pair_requests_sent_total",
+                        "STUN connectivity check requests sent on the candidate pair.",
+                        &labels,
+                        stats.requests_sent as f64,
+                    );
+                    self.counter(
+                        "webrtc_ice_candidate_pair_responses_received_total",
+                        "STUN connectivity check responses received on the candidate pair.",
+                        &labels,
+                        stats.responses_received as f64,
+                    );
+                }
+                StatsReportType::Transport(stats) => {
+                    let labels = base.with("id", id);
+                    self.counter(
+                        "webrtc_transport_bytes_sent_total",
+                        "Bytes sent on the transport.",
+                        &labels,
+                        stats.bytes_sent as f64,
+                    );
+                    self.counter(
+                        "webrtc_transport_bytes_received_total",
+                        "Bytes received on the transport.",
+                        &labels,
+                        stats.bytes_received as f64,
+                    );
+
+                    let labels = labels
+                        .with("ice_state", &stats.ice_state.to_string())
+                        .with("dtls_state", &stats.dtls_state.to_string())
+                        .with(
+                            "dtls_cipher",
+                            stats.dtls_cipher.as_deref().unwrap_or_default(),
+                        )
+                        .with(
+                            "srtp_cipher",
+                            stats.srtp_cipher.as_deref().unwrap_or_default(),
+                        );
+                    self.gauge(
+                        "webrtc_transport_info",
+                        "State and ciphers of the transport, always 1.",
+                        &labels,
+                        1.0,
+                    );
+                }
+                StatsReportType::SCTPTransport(stats) => {
+                    let labels = base.with("id", id);
+                    self.counter(
+                        "webrtc_sctp_transport_bytes_sent_total",
+                        "Bytes sent on the SCTP transport.",
+                        &labels,
+                        stats.bytes_sent as f64,
+                    );
+                    self.counter(
+                        "webrtc_sctp_transport_bytes_received_total",
+                        "Bytes received on the SCTP transport.",
+                        &labels,
+                        stats.bytes_received as f64,
+                    );
+                    if let Some(mtu) = stats.mtu {
+                        self.gauge(
+                            "webrtc_sctp_transport_mtu_bytes",
+                            "MTU the SCTP association sizes its packets to.",
+                            &labels,
+                            mtu as f64,
+                        );
+                    }
+                }
+                StatsReportType::DataChannel(stats) => {
+                    sctp_buffered_amount += stats.buffered_amount;
+
+                    let labels = base.with("id", id).with("label", &stats.label);
+                    self.counter(
+                        "webrtc_data_channel_messages_sent_total",
+                        "Messages sent on the data channel.",
+                        &labels,
+                        stats.messages_sent as f64,
+                    );
+                    self.counter(
+                        "webrtc_data_channel_messages_received_total",
+                        "Messages received on the data channel.",
+                        &labels,
+                        stats.messages_received as f64,
+                    );
+                    self.counter(
+                        "webrtc_data_channel_bytes_sent_total",
+                        "Bytes sent on the data channel.",
+                        &labels,
+                        stats.bytes_sent as f64,
+                    );
+                    self.counter(
+                        "webrtc_data_channel_bytes_received_total",
+                        "Bytes received on the data channel.",
+                        &labels,
+                        stats.bytes_received as f64,
+                    );
+                    self.gauge(
+                        "webrtc_data_channel_buffered_amount_bytes",
+                        "Bytes queued on the data channel and not sent yet.",
+                        &labels,
+                        stats.buffered_amount as f64,
+                    );
+                }
+                StatsReportType::PeerConnection(stats) => {
+                    let labels = base.with("id", id);
+                    self.counter(
+                        "webrtc_peer_connection_data_channels_opened_total",
+                        "Data channels that reached the open state.",
+                        &labels,
+                        stats.data_channels_opened as f64,
+                    );
+                    self.counter(
+                        "webrtc_peer_connection_data_channels_closed_total",
+                        "Data channels that left the open state.",
+                        &labels,
+                        stats.data_channels_closed as f64,
+                    );
+                }
+                StatsReportType::InboundRTP(stats) => {
+                    let labels = base
+                        .with("id", id)
+                        .with("ssrc", &stats.ssrc.to_string())
+                        .with("kind", stats.kind)
+                        .with("mid", &stats.mid);
+                    self.counter(
+                        "webrtc_inbound_rtp_packets_received_total",
+                        "RTP packets received on the stream.",
+                        &labels,
+                        stats.packets_received as f64,
+                    );
+                    self.counter(
+                        "webrtc_inbound_rtp_bytes_received_total",
+                        "RTP payload bytes received on the stream.",
+                        &labels,
+                        stats.bytes_received as f64,
+                    );
+                    self.counter(
+                        "webrtc_inbound_rtp_header_bytes_received_total",
+                        "RTP header and padding bytes received on the stream.",
+                        &labels,
+                        stats.header_bytes_received as f64,
+                    );
+                    // Duplicates make the loss negative, which a counter can't be.
+                    self.gauge(
+                        "webrtc_inbound_rtp_packets_lost",
+                        "RTP packets lost on the stream.",
+                        &labels,
+                        stats.packets_lost as f64,
+                    );
+                    if let Some(jitter) = stats.jitter {
+                        self.gauge(
+                            "webrtc_inbound_rtp_jitter_seconds",
+                            "Interarrival jitter of the stream.",
+                            &labels,
+                            jitter,
+                        );
+                    }
+                    if let Some(frames_received) = stats.frames_received {
+                        self.counter(
+                            "webrtc_inbound_rtp_frames_received_total",
+                            "Frames received on the stream.",
+                            &labels,
+                            frames_received as f64,
+                        );
+                    }
+                    self.counter(
+                        "webrtc_inbound_rtp_nack_count_total",
+                        "NACK packets sent for the stream.",
+                        &labels,
+                        stats.nack_count as f64,
+                    );
+                    if let Some(pli_count) = stats.pli_count {
+                        self.counter(
+                            "webrtc_inbound_rtp_pli_count_total",
+                            "PLI packets sent for the stream.",
+                            &labels,
+                            pli_count as f64,
+                        );
+                    }
+                    if let Some(fir_count) = stats.fir_count {
+                        self.counter(
+                            "webrtc_inbound_rtp_fir_count_total",
+                            "FIR packets sent for the stream.",
+                            &labels,
+                            fir_count as f64,
+                        );
+                    }
+                }
+                StatsReportType::OutboundRTP(stats) => {
+                    let mut labels = base
+                        .with("id", id)
+                        .with("ssrc", &stats.ssrc.to_string())
+                        .with("kind", stats.kind)
+                        .with("mid", &stats.mid);
+                    if let Some(rid) = &stats.rid {
+                        labels = labels.with("rid", rid);
+                    }
+                    self.counter(
+                        "webrtc_outbound_rtp_packets_sent_total",
+                        "RTP packets sent on the stream.",
+                        &labels,
+                        stats.packets_sent as f64,
+                    );
+                    self.counter(
+                        "webrtc_outbound_rtp_bytes_sent_total",
+                        "RTP payload bytes sent on the stream.",
+                        &labels,
+                        stats.bytes_sent as f64,
+                    );
+                    self.counter(
+                        "webrtc_outbound_rtp_header_bytes_sent_total",
+                        "RTP header and padding bytes sent on the stream.",
+                        &labels,
+                        stats.header_bytes_sent as f64,
+                    );
+                    if let Some(frames_sent) = stats.frames_sent {
+                        self.counter(
+                            "webrtc_outbound_rtp_frames_sent_total",
+                            "Frames sent on the stream.",
+                            &labels,
+                            frames_sent as f64,
+                        );
+                    }
+                    self.counter(
+                        "webrtc_outbound_rtp_nack_count_total",
+                        "NACK packets received for the stream.",
+                        &labels,
+                        stats.nack_count as f64,
+                    );
+                    if let Some(pli_count) = stats.pli_count {
+                        self.counter(
+                            "webrtc_outbound_rtp_pli_count_total",
+                            "PLI packets received for the stream.",
+                            &labels,
+                            pli_count as f64,
+                        );
+                    }
+                    if let Some(fir_count) = stats.fir_count {
+                        self.counter(
+                            "webrtc_outbound_rtp_fir_count_total",
+                            "FIR packets received for the stream.",
+                            &labels,
+                            fir_count as f64,
+                        );
+                    }
+                    if let Some(durations) = &stats.quality_limitation_durations {
+                        let mut durations: Vec<_> = durations.iter().collect();
+                        durations.sort_by_key(|(reason, _)| quality_limitation_reason(**reason));
+                        for (reason, duration) in durations {
+                            self.counter(
+                                "webrtc_outbound_rtp_quality_limitation_duration_seconds_total",
+                                "Time the quality of the stream was limited, by reason.",
+                                &labels.with("reason", quality_limitation_reason(*reason)),
+                                *duration,
+                            );
+                        }
+                    }
+                }
+                StatsReportType::RemoteInboundRTP(stats) => {
+                    let labels = base
+                        .with("id", id)
+                        .with("ssrc", &stats.ssrc.to_string())
+                        .with("kind", stats.kind);
+                    self.gauge(
+                        "webrtc_remote_inbound_rtp_packets_lost",
+                        "RTP packets of the stream reported lost by the remote.",
+                        &labels,
+                        stats.packets_lost as f64,
+                    );
+                    self.gauge(
+                        "webrtc_remote_inbound_rtp_fraction_lost",
+                        "Fraction of the stream lost since the previous report of the remote.",
+                        &labels,
+                        stats.fraction_lost,
+                    );
+                    if let Some(round_trip_time) = stats.round_trip_time {
+                        self.gauge(
+                            "webrtc_remote_inbound_rtp_round_trip_time_seconds",
+                            "Latest round trip time computed from the reports of the remote.",
+                            &labels,
+                            round_trip_time,
+                        );
+                    }
+                    self.counter(
+                        "webrtc_remote_inbound_rtp_round_trip_time_seconds_total",
+                        "Sum of the round trip times computed from the reports of the remote.",
+                        &labels,
+                        stats.total_round_trip_time,
+                    );
+                    self.counter(
+                        "webrtc_remote_inbound_rtp_round_trip_time_measurements_total",
+                        "Round trip times computed from the reports of the remote.",
+                        &labels,
+                        stats.round_trip_time_measurements as f64,
+                    );
+                }
+                StatsReportType::RemoteOutboundRTP(stats) => {
+                    let labels = base
+                        .with("id", id)
+                        .with("ssrc", &stats.ssrc.to_string())
+                        .with("kind", stats.kind);
+                    self.counter(
+                        "webrtc_remote_outbound_rtp_packets_sent_total",
+                        "RTP packets of the stream reported sent by the remote.",
+                        &labels,
+                        stats.packets_sent as f64,
+                    );
+                    self.counter(
+                        "webrtc_remote_outbound_rtp_bytes_sent_total",
+                        "RTP payload bytes of the stream reported sent by the remote.",
+                        &labels,
+                        stats.bytes_sent as f64,
+                    );
+                    self.counter(
+                        "webrtc_remote_outbound_rtp_reports_sent_total",
+                        "Sender reports sent by the remote for the stream.",
+                        &labels,
+                        stats.reports_sent as f64,
+                    );
+                }
+                StatsReportType::MediaSource(stats) => {
+                    let labels = base.with("id", id).with("kind", stats.kind);
+                    if let Some(frames) = stats.frames {
+                        self.counter(
+                            "webrtc_media_source_frames_total",
+                            "Frames written to the track of the media source.",
+                            &labels,
+                            frames as f64,
+                        );
+                    }
+                    if let Some(duration) = stats.total_samples_duration {
+                        self.counter(
+                            "webrtc_media_source_samples_duration_seconds_total",
+                            "Duration of the samples written to the track of the media source.",
+                            &labels,
+                            duration,
+                        );
+                    }
+                }
+                StatsReportType::MediaPlayout(stats) => {
+                    let labels = base.with("id", id).with("kind", stats.kind);
+                    self.counter(
+                        "webrtc_media_playout_synthesized_samples_duration_seconds_total",
+                        "Duration of the samples synthesized to conceal loss.",
+                        &labels,
+                        stats.synthesized_samples_duration,
+                    );
+                    self.counter(
+                        "webrtc_media_playout_synthesized_samples_events_total",
+                        "Times samples were synthesized to conceal loss.",
+                        &labels,
+                        stats.synthesized_samples_events as f64,
+                    );
+                }
+                StatsReportType::CertificateStats(_)
+                | StatsReportType::Codec(_)
+                | StatsReportType::LocalCandidate(_)
+                | StatsReportType::RemoteCandidate(_) => {}
+            }
+        }
+
+        for state in [
+            CandidatePairState::Waiting,
+            CandidatePairState::InProgress,
+            CandidatePairState::Succeeded,
+            CandidatePairState::Failed,
+        ] {
+            self.gauge(
+                "webrtc_ice_candidate_pairs",
+                "Candidate pairs, by state.",
+                &base.with("state", &state.to_string()),
+                candidate_pair_states
+                    .iter()
+                    .filter(|s| **s == state)
+                    .count() as f64,
+            );
+        }
+
+        if let Some((id, _)) = reports
+            .iter()
+            .find(|(_, stats)| matches!(stats, StatsReportType::SCTPTransport(_)))
+        {
+            self.gauge(
+                "webrtc_sctp_transport_buffered_amount_bytes",
+                "Bytes queued on the data channels of the SCTP transport and not sent yet.",
+                &base.with("id", id),
+                sctp_buffered_amount as f64,
+            );
+        }
+    }
+
+    fn encode(&self) -> String {
+        let mut out = String::new();
+        for (name, family) in &self.families {
+            let _ = writeln!(out, "# HELP {name} {}", family.help);
+            let _ = writeln!(out, "# TYPE {name} {}", family.metric_type.as_str());
+            for (labels, value) in &family.samples {
+                let _ = writeln!(out, "{name}{labels} {}", format_value(*value));
+            }
+        }
+        out
+    }
+}
+
+/// Labels is a label set, in the order the labels are rendered.
+#[derive(Debug, Default, Clone)]
+struct Labels(Vec<(&'static str, String)>);
+
+impl Labels {
+    fn with(&self, name: &'static str, value: &str) -> Labels {
+        let mut labels = self.clone();
+        labels.0.push((name, value.to_owned()));
+        labels
+    }
+
+    fn encode(&self) -> String {
+        if self.0.is_empty() {
+            return String::new();
+        }
+
+        let mut out = String::from("{");
+        for (i, (name, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, "{name}=\"");
+            for c in value.chars() {
+                match c {
+                    '\\' => out.push_str("\\\\"),
+                    '"' => out.push_str("\\\""),
+                    '\n' => out.push_str("\\n"),
+                    c => out.push(c),
+                }
+            }
+            out.push('"');
+        }
+        out.push('}');
+        out
+    }
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_owned()
+    } else if value == f64::INFINITY {
+        "+Inf".to_owned()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_owned()
+    } else {
+        value.to_string()
+    }
+}
+
+fn quality_limitation_reason(reason: RTCQualityLimitationReason) -> &'static str {
+    match reason {
+        RTCQualityLimitationReason::None => "none",
+        RTCQualityLimitationReason::Cpu => "cpu",
+        RTCQualityLimitationReason::Bandwidth => "bandwidth",
+        RTCQualityLimitationReason::Other => "other",
+    }
+}
+
+/// encode renders a [`StatsReport`] in the Prometheus text exposition format. Every
+/// sample is labelled with the `id` of the stats it comes from, along with the kind,
+/// SSRC and mid of RTP streams, the label of data channels and the state of candidate
+/// pairs. Stats without counters or gauges, like codecs and candidates, are left out.
+pub fn encode(report: &StatsReport) -> String {
+    let mut set = MetricSet::default();
+    set.add_report(report, &Labels::default());
+    set.encode()
+}
+
+/// StatsRegistry renders the stats of many peer connections in one Prometheus text
+/// exposition, with the samples of each labelled with its stats id as `peer_connection`.
+///
+/// The registry only keeps weak references, so dropped peer connections leave it on
+/// their own; [`StatsRegistry::unregister`] removes one that is still alive.
+#[derive(Debug, Default)]
+pub struct StatsRegistry {
+    peer_connections: Mutex<HashMap<String, Weak<RTCPeerConnection>>>,
+}
+
+impl StatsRegistry {
+    pub fn new() -> Self {
+        StatsRegistry::default()
+    }
+
+    /// register adds a peer connection to the registry.
+    pub fn register(&self, peer_connection: &Arc<RTCPeerConnection>) {
+        let mut peer_connections = self.peer_connections.lock();
+        peer_connections.insert(
+            peer_connection.get_stats_id().to_owned(),
+            Arc::downgrade(peer_connection),
+        );
+    }
+
+    /// unregister removes a peer connection from the registry, and returns false if it
+    /// wasn't registered.
+    pub fn unregister(&self, peer_connection: &RTCPeerConnection) -> bool {
+        let mut peer_connections = self.peer_connections.lock();
+        peer_connections
+            .remove(peer_connection.get_stats_id())
+            .is_some()
+    }
+
+    /// len returns the number of registered peer connections that are still alive.
+    pub fn len(&self) -> usize {
+        let peer_connections = self.peer_connections.lock();
+        peer_connections
+            .values()
+            .filter(|pc| pc.strong_count() > 0)
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// gather collects the stats of every registered peer connection, one after the
+    /// other, and renders them in the Prometheus text exposition format. Besides the
+    /// samples of [`encode`] for each peer connection, it reports the number of peer
+    /// connections by connection state.
+    pub async fn gather(&self) -> String {
+        let peer_connections: Vec<(String, Arc<RTCPeerConnection>)> = {
+            let mut peer_connections = self.peer_connections.lock();
+            peer_connections.retain(|_, pc| pc.strong_count() > 0);
+            let mut peer_connections: Vec<_> = peer_connections
+                .iter()
+                .filter_map(|(id, pc)| pc.upgrade().map(|pc| (id.clone(), pc)))
+                .collect();
+            peer_connections.sort_by(|a, b| a.0.cmp(&b.0));
+            peer_connections
+        };
+
+        let mut set = MetricSet::default();
+        let mut states = vec![];
+        for (id, peer_connection) in &peer_connections {
+            states.push(peer_connection.connection_state());
+
+            let report = peer_connection.get_stats().await;
+            set.add_report(&report, &Labels::default().with("peer_connection", id));
+        }
+
+        for state in [
+            RTCPeerConnectionState::New,
+            RTCPeerConnectionState::Connecting,
+            RTCPeerConnectionState::Connected,
+            RTCPeerConnectionState::Disconnected,
+            RTCPeerConnectionState::Failed,
+            RTCPeerConnectionState::Closed,
+        ] {
+            set.gauge(
+                "webrtc_peer_connections",
+                "Peer connections in the registry, by connection state.",
+                &Labels::default().with("state", &state.to_string()),
+                states.iter().filter(|s| **s == state).count() as f64,
+            );
+        }
+
+        set.encode()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::time::Instant;
+
+    use super::*;
+    use crate::api::APIBuilder;
+    use crate::data_channel::data_channel_state::RTCDataChannelState;
+    use crate::stats::{DataChannelStats, RTCStatsType, SCTPTransportStats};
+
+    #[test]
+    fn test_labels_encode() {
+        assert_eq!(Labels::default().encode(), "");
+        assert_eq!(
+            Labels::default()
+                .with("id", "a")
+                .with("label", "say \"hi\"\\\n")
+                .encode(),
+            r#"{id="a",label="say \"hi\"\\\n"}"#
+        );
+    }
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_value(1.0), "1");
+        assert_eq!(format_value(0.25), "0.25");
+        assert_eq!(format_value(f64::NAN), "NaN");
+        assert_eq!(format_value(f64::INFINITY), "+Inf");
+        assert_eq!(format_value(f64::NEG_INFINITY), "-Inf");
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut report = StatsReport {
+            reports: HashMap::new(),
+        };
+        report.reports.insert(
+            "sctp_transport".to_owned(),
+            StatsReportType::SCTPTransport(SCTPTransportStats {
+                timestamp: Instant::now(),
+                stats_type: RTCStatsType::Transport,
+                id: "sctp_transport".to_owned(),
+                bytes_received: 10,
+                bytes_sent: 20,
+                mtu: Some(1228),
+            }),
+        );
+        for (id, buffered_amount) in [("dc1", 100), ("dc2", 50)] {
+            report.reports.insert(
+                id.to_owned(),
+                StatsReportType::DataChannel(DataChannelStats {
+                    timestamp: Instant::now(),
+                    stats_type: RTCStatsType::DataChannel,
+                    id: id.to_owned(),
+                    bytes_received: 1,
+                    bytes_sent: 2,
+                    data_channel_identifier: 0,
+                    label: "chat".to_owned(),
+                    messages_received: 3,
+                    messages_sent: 4,
+                    protocol: String::new(),
+                    state: RTCDataChannelState::Open,
+                    buffered_amount,
+                }),
+            );
+        }
+
+        let out = encode(&report);
+        for line in [
+            "# TYPE webrtc_sctp_transport_bytes_sent_total counter",
+            "webrtc_sctp_transport_bytes_sent_total{id=\"sctp_transport\"} 20",
+            "# TYPE webrtc_sctp_transport_mtu_bytes gauge",
+            "webrtc_sctp_transport_mtu_bytes{id=\"sctp_transport\"} 1228",
+            "webrtc_sctp_transport_buffered_amount_bytes{id=\"sctp_transport\"} 150",
+            "webrtc_data_channel_buffered_amount_bytes{id=\"dc1\",label=\"chat\"} 100",
+            "webrtc_data_channel_messages_sent_total{id=\"dc2\",label=\"chat\"} 4",
+            "webrtc_ice_candidate_pairs{state=\"succeeded\"} 0",
+        ] {
+            assert!(out.lines().any(|l| l == line), "missing {line} in\n{out}");
+        }
+
+        let help = out
+            .lines()
+            .filter(|l| *l == "# TYPE webrtc_data_channel_bytes_sent_total counter")
+            .count();
+        assert_eq!(help, 1, "a family is described once");
+    }
+
+    #[tokio::test]
+    async fn test_stats_registry() {
+        let api = APIBuilder::new().build();
+        let pc = Arc::new(api.new_peer_connection(Default::default()).await.unwrap());
+
+        let registry = StatsRegistry::new();
+        registry.register(&pc);
+        assert_eq!(registry.len(), 1);
+
+        let out = registry.gather().await;
+        let label = format!("peer_connection=\"{}\"", pc.get_stats_id());
+        assert!(out.contains(&label), "{out}");
+        assert!(out.contains("webrtc_peer_connections{state=\"new\"} 1"));
+
+        assert!(registry.unregister(&pc));
+        assert!(!registry.unregister(&pc));
+
+        registry.register(&pc);
+        pc.close().await.unwrap();
+        drop(pc);
+        assert!(registry.is_empty());
+        let out = registry.gather().await;
+        assert!(!out.contains("peer_connection="), "{out}");
+        assert!(out.contains("webrtc_peer_connections{state=\"new\"} 0"));
+    }
+}
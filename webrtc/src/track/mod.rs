@@ -3,12 +3,16 @@ pub mod track_remote;
 
 use std::sync::Arc;
 
+use async_trait::async_trait;
 use interceptor::stream_info::StreamInfo;
-use interceptor::{RTCPReader, RTPReader};
+use interceptor::{Attributes, RTCPReader, RTPReader};
+use tokio::sync::{mpsc, Mutex};
 use track_remote::*;
 
 pub(crate) const RTP_OUTBOUND_MTU: usize = 1200;
 pub(crate) const RTP_PAYLOAD_TYPE_BITMASK: u8 = 0x7F;
+/// Number of retransmitted packets buffered until the track reads them
+pub(crate) const RTX_PACKETS_BUFFER_SIZE: usize = 64;
 
 #[derive(Clone)]
 pub(crate) struct TrackStream {
@@ -17,6 +21,8 @@ pub(crate) struct TrackStream {
     pub(crate) rtp_interceptor: Option<Arc<dyn RTPReader + Send + Sync>>,
     pub(crate) rtcp_read_stream: Option<Arc<srtp::stream::Stream>>,
     pub(crate) rtcp_interceptor: Option<Arc<dyn RTCPReader + Send + Sync>>,
+    /// Packets of the stream restored from RTX packets, read through the interceptors of the stream
+    pub(crate) repair_packets_tx: Option<mpsc::Sender<(rtp::packet::Packet, Attributes)>>,
 }
 
 /// TrackStreams maintains a mapping of RTP/RTCP streams to a specific track
@@ -26,4 +32,46 @@ pub(crate) struct TrackStreams {
     pub(crate) track: Arc<TrackRemote>,
     pub(crate) stream: TrackStream,
     pub(crate) repair_stream: TrackStream,
+    /// FlexFEC stream, its packets are used by the interceptors to recover packets of the stream
+    pub(crate) fec_stream: TrackStream,
+}
+
+/// RepairedStreamReader reads the packets of a SRTP stream together with the packets
+/// restored from its repair stream, so both are passed through the interceptors bound
+/// to the stream.
+pub(crate) struct RepairedStreamReader {
+    stream: Arc<srtp::stream::Stream>,
+    repair_packets_rx: Mutex<mpsc::Receiver<(rtp::packet::Packet, Attributes)>>,
+}
+
+impl RepairedStreamReader {
+    pub(crate) fn new(
+        stream: Arc<srtp::stream::Stream>,
+    ) -> (Self, mpsc::Sender<(rtp::packet::Packet, Attributes)>) {
+        let (repair_packets_tx, repair_packets_rx) = mpsc::channel(RTX_PACKETS_BUFFER_SIZE);
+        (
+            RepairedStreamReader {
+                stream,
+                repair_packets_rx: Mutex::new(repair_packets_rx),
+            },
+            repair_packets_tx,
+        )
+    }
+}
+
+type IResult<T> = std::result::Result<T, interceptor::Error>;
+
+#[async_trait]
+impl RTPReader for RepairedStreamReader {
+    async fn read(
+        &self,
+        buf: &mut [u8],
+        a: &Attributes,
+    ) -> IResult<(rtp::packet::Packet, Attributes)> {
+        let mut repair_packets_rx = self.repair_packets_rx.lock().await;
+        tokio::select! {
+            Some(result) = repair_packets_rx.recv() => Ok(result),
+            result = self.stream.read_rtp(buf) => Ok((result?, a.clone())),
+        }
+    }
 }
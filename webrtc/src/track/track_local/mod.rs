@@ -10,6 +10,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use interceptor::{Attributes, RTPWriter};
 use tokio::sync::Mutex;
 use util::Unmarshal;
@@ -37,6 +38,9 @@ pub struct TrackLocalContext {
     pub(crate) ssrc: SSRC,
     pub(crate) write_stream: Option<Arc<dyn TrackLocalWriter + Send + Sync>>,
     pub(crate) paused: Arc<AtomicBool>,
+    /// Set while the encoding the track is bound to is not active
+    pub(crate) encoding_paused: Arc<AtomicBool>,
+    pub(crate) encoding_parameters: RTCRtpEncodingParameters,
 }
 
 impl TrackLocalContext {
@@ -68,6 +72,13 @@ impl TrackLocalContext {
     pub fn id(&self) -> String {
         self.id.clone()
     }
+
+    /// encoding_parameters returns the parameters of the encoding the track is bound to,
+    /// like its rid, maximum bitrate or resolution scale, which the encoder of the track
+    /// should follow
+    pub fn encoding_parameters(&self) -> &RTCRtpEncodingParameters {
+        &self.encoding_parameters
+    }
 }
 /// TrackLocal is an interface that controls how the user can send media
 /// The user can provide their own TrackLocal implementations, or use
@@ -91,9 +102,21 @@ pub trait TrackLocal {
     /// stream_id is the group this track belongs too. This must be unique
     fn stream_id(&self) -> &str;
 
+    /// rid is the RTP stream identifier of the simulcast layer this track sends, if any
+    fn rid(&self) -> Option<&str> {
+        None
+    }
+
     /// kind controls if this TrackLocal is audio or video
     fn kind(&self) -> RTPCodecType;
 
+    /// encoding_parameters_changed is called when the parameters of the encoding the track
+    /// is bound to are changed with RTCRtpSender::set_parameters, so the encoder of the track
+    /// can react to the new parameters given by the context
+    async fn encoding_parameters_changed(&self, _t: &TrackLocalContext) -> Result<()> {
+        Ok(())
+    }
+
     fn as_any(&self) -> &dyn Any;
 }
 
@@ -108,29 +131,40 @@ pub(crate) struct TrackBinding {
     params: RTCRtpParameters,
     write_stream: Option<Arc<dyn TrackLocalWriter + Send + Sync>>,
     sender_paused: Arc<AtomicBool>,
+    encoding_paused: Arc<AtomicBool>,
 }
 
 impl TrackBinding {
+    /// is_sender_paused tells if the sender or the encoding the track is bound to is paused
     pub fn is_sender_paused(&self) -> bool {
-        self.sender_paused.load(Ordering::SeqCst)
+        self.sender_paused.load(Ordering::SeqCst) || self.encoding_paused.load(Ordering::SeqCst)
     }
 }
 
 pub(crate) struct InterceptorToTrackLocalWriter {
     pub(crate) interceptor_rtp_writer: Mutex<Option<Arc<dyn RTPWriter + Send + Sync>>>,
     sender_paused: Arc<AtomicBool>,
+    encoding_paused: Arc<AtomicBool>,
+    /// Header extensions set on every packet, like the mid and rid of simulcast layers
+    header_extensions: Vec<(u8, Bytes)>,
 }
 
 impl InterceptorToTrackLocalWriter {
-    pub(crate) fn new(paused: Arc<AtomicBool>) -> Self {
+    pub(crate) fn new(
+        paused: Arc<AtomicBool>,
+        encoding_paused: Arc<AtomicBool>,
+        header_extensions: Vec<(u8, Bytes)>,
+    ) -> Self {
         InterceptorToTrackLocalWriter {
             interceptor_rtp_writer: Mutex::new(None),
             sender_paused: paused,
+            encoding_paused,
+            header_extensions,
         }
     }
 
     fn is_sender_paused(&self) -> bool {
-        self.sender_paused.load(Ordering::SeqCst)
+        self.sender_paused.load(Ordering::SeqCst) || self.encoding_paused.load(Ordering::SeqCst)
     }
 }
 
@@ -150,7 +184,15 @@ impl TrackLocalWriter for InterceptorToTrackLocalWriter {
         let interceptor_rtp_writer = self.interceptor_rtp_writer.lock().await;
         if let Some(writer) = &*interceptor_rtp_writer {
             let a = Attributes::new();
-            Ok(writer.write(pkt, &a).await?)
+            if self.header_extensions.is_empty() {
+                return Ok(writer.write(pkt, &a).await?);
+            }
+
+            let mut pkt = pkt.clone();
+            for (id, payload) in &self.header_extensions {
+                pkt.header.set_extension(*id, payload.clone())?;
+            }
+            Ok(writer.write(&pkt, &a).await?)
         } else {
             Ok(0)
         }
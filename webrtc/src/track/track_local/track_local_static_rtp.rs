@@ -14,6 +14,7 @@ pub struct TrackLocalStaticRTP {
     pub(crate) bindings: Mutex<Vec<Arc<TrackBinding>>>,
     codec: RTCRtpCodecCapability,
     id: String,
+    rid: Option<String>,
     stream_id: String,
 }
 
@@ -24,6 +25,23 @@ impl TrackLocalStaticRTP {
             codec,
             bindings: Mutex::new(vec![]),
             id,
+            rid: None,
+            stream_id,
+        }
+    }
+
+    /// returns a TrackLocalStaticRTP sending the simulcast layer rid.
+    pub fn new_with_rid(
+        codec: RTCRtpCodecCapability,
+        id: String,
+        rid: String,
+        stream_id: String,
+    ) -> Self {
+        TrackLocalStaticRTP {
+            codec,
+            bindings: Mutex::new(vec![]),
+            id,
+            rid: Some(rid),
             stream_id,
         }
     }
@@ -35,16 +53,12 @@ impl TrackLocalStaticRTP {
 
     pub async fn any_binding_paused(&self) -> bool {
         let bindings = self.bindings.lock().await;
-        bindings
-            .iter()
-            .any(|b| b.sender_paused.load(Ordering::SeqCst))
+        bindings.iter().any(|b| b.is_sender_paused())
     }
 
     pub async fn all_binding_paused(&self) -> bool {
         let bindings = self.bindings.lock().await;
-        bindings
-            .iter()
-            .all(|b| b.sender_paused.load(Ordering::SeqCst))
+        bindings.iter().all(|b| b.is_sender_paused())
     }
 
     /// write_rtp_with_extensions writes a RTP Packet to the TrackLocalStaticRTP
@@ -155,6 +169,7 @@ impl TrackLocal for TrackLocalStaticRTP {
                     params: t.params.clone(),
                     id: t.id(),
                     sender_paused: t.paused.clone(),
+                    encoding_paused: t.encoding_paused.clone(),
                 }));
             }
 
@@ -195,6 +210,11 @@ impl TrackLocal for TrackLocalStaticRTP {
         self.stream_id.as_str()
     }
 
+    /// rid is the RTP stream identifier of the simulcast layer this track sends, if any
+    fn rid(&self) -> Option<&str> {
+        self.rid.as_deref()
+    }
+
     /// kind controls if this TrackLocal is audio or video
     fn kind(&self) -> RTPCodecType {
         if self.codec.mime_type.starts_with("audio/") {
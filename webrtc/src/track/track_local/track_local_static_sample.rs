@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use log::warn;
 use media::Sample;
 use tokio::sync::Mutex;
@@ -13,6 +15,9 @@ struct TrackLocalStaticSampleInternal {
     sequencer: Option<Box<dyn rtp::sequence::Sequencer + Send + Sync>>,
     clock_rate: f64,
     did_warn_about_wonky_pause: bool,
+    /// The number of samples written and their total duration, for the media source stats.
+    samples_written: u64,
+    samples_duration: Duration,
 }
 
 /// TrackLocalStaticSample is a TrackLocal that has a pre-set codec and accepts Samples.
@@ -28,6 +33,22 @@ impl TrackLocalStaticSample {
     pub fn new(codec: RTCRtpCodecCapability, id: String, stream_id: String) -> Self {
         let rtp_track = TrackLocalStaticRTP::new(codec, id, stream_id);
 
+        TrackLocalStaticSample::from_rtp_track(rtp_track)
+    }
+
+    /// returns a TrackLocalStaticSample sending the simulcast layer rid.
+    pub fn new_with_rid(
+        codec: RTCRtpCodecCapability,
+        id: String,
+        rid: String,
+        stream_id: String,
+    ) -> Self {
+        let rtp_track = TrackLocalStaticRTP::new_with_rid(codec, id, rid, stream_id);
+
+        TrackLocalStaticSample::from_rtp_track(rtp_track)
+    }
+
+    fn from_rtp_track(rtp_track: TrackLocalStaticRTP) -> Self {
         TrackLocalStaticSample {
             rtp_track,
             internal: Mutex::new(TrackLocalStaticSampleInternal {
@@ -35,6 +56,8 @@ impl TrackLocalStaticSample {
                 sequencer: None,
                 clock_rate: 0.0f64,
                 did_warn_about_wonky_pause: false,
+                samples_written: 0,
+                samples_duration: Duration::ZERO,
             }),
         }
     }
@@ -44,6 +67,13 @@ impl TrackLocalStaticSample {
         self.rtp_track.codec()
     }
 
+    /// samples_written returns the number of samples written to the track and their
+    /// total duration.
+    pub(crate) async fn samples_written(&self) -> (u64, Duration) {
+        let internal = self.internal.lock().await;
+        (internal.samples_written, internal.samples_duration)
+    }
+
     /// write_sample writes a Sample to the TrackLocalStaticSample
     /// If one PeerConnection fails the packets will still be sent to
     /// all PeerConnections. The error message will contain the ID of the failed
@@ -122,6 +152,8 @@ impl TrackLocalStaticSample {
         } else {
             vec![]
         };
+        internal.samples_written += 1;
+        internal.samples_duration += sample.duration;
 
         let mut write_errs = vec![];
         for p in packets {
@@ -226,6 +258,11 @@ impl TrackLocal for TrackLocalStaticSample {
         self.rtp_track.stream_id()
     }
 
+    /// rid is the RTP stream identifier of the simulcast layer this track sends, if any
+    fn rid(&self) -> Option<&str> {
+        self.rtp_track.rid()
+    }
+
     /// kind controls if this TrackLocal is audio or video
     fn kind(&self) -> RTPCodecType {
         self.rtp_track.kind()
@@ -238,7 +275,10 @@ impl TrackLocal for TrackLocalStaticSample {
 
 mod sample_writer {
     use media::Sample;
+    use rtp::extension::abs_capture_time_extension::AbsCaptureTimeExtension;
     use rtp::extension::audio_level_extension::AudioLevelExtension;
+    use rtp::extension::dependency_descriptor_extension::DependencyDescriptorExtension;
+    use rtp::extension::playout_delay_extension::PlayoutDelayExtension;
     use rtp::extension::video_orientation_extension::VideoOrientationExtension;
     use rtp::extension::HeaderExtension;
 
@@ -275,6 +315,27 @@ mod sample_writer {
             self.with_extension(HeaderExtension::VideoOrientation(ext))
         }
 
+        /// Add a RTP absolute capture time extension to all packets written for the sample.
+        ///
+        /// This overwrites any previously configured absolute capture time extension.
+        pub fn with_abs_capture_time(self, ext: AbsCaptureTimeExtension) -> Self {
+            self.with_extension(HeaderExtension::AbsCaptureTime(ext))
+        }
+
+        /// Add a RTP playout delay extension to all packets written for the sample.
+        ///
+        /// This overwrites any previously configured playout delay extension.
+        pub fn with_playout_delay(self, ext: PlayoutDelayExtension) -> Self {
+            self.with_extension(HeaderExtension::PlayoutDelay(ext))
+        }
+
+        /// Add a RTP dependency descriptor extension to all packets written for the sample.
+        ///
+        /// This overwrites any previously configured dependency descriptor extension.
+        pub fn with_dependency_descriptor(self, ext: DependencyDescriptorExtension) -> Self {
+            self.with_extension(HeaderExtension::DependencyDescriptor(ext))
+        }
+
         /// Add any RTP extension to all packets written for the sample.
         pub fn with_extension(mut self, ext: HeaderExtension) -> Self {
             self.extensions.retain(|e| !e.is_same(&ext));
@@ -0,0 +1,84 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use interceptor::Attributes;
+use media::io::jitter_buffer::JitterBuffer;
+use media::Sample;
+use rtp::packetizer::Depacketizer;
+use tokio::time::Instant;
+
+use super::TrackRemote;
+use crate::error::Result;
+
+type ReadRtpFut = Pin<Box<dyn Future<Output = Result<(rtp::packet::Packet, Attributes)>> + Send>>;
+
+/// JitterBufferReader reads the RTP packets of a [`TrackRemote`] into a [`JitterBuffer`]
+/// and returns its frames at their playout time. The statistics of the jitter buffer
+/// are reported in the inbound RTP stream stats of the track.
+pub struct JitterBufferReader<T: Depacketizer> {
+    track: Arc<TrackRemote>,
+    jitter_buffer: JitterBuffer<T>,
+    /// The pending read of the track, kept across calls so no packet is lost when a
+    /// frame is returned before the read completes.
+    read: Option<ReadRtpFut>,
+}
+
+impl<T: Depacketizer> JitterBufferReader<T> {
+    pub fn new(track: Arc<TrackRemote>, jitter_buffer: JitterBuffer<T>) -> Self {
+        JitterBufferReader {
+            track,
+            jitter_buffer,
+            read: None,
+        }
+    }
+
+    /// track returns the track the packets are read from.
+    pub fn track(&self) -> &Arc<TrackRemote> {
+        &self.track
+    }
+
+    /// jitter_buffer returns the jitter buffer the packets are read into.
+    pub fn jitter_buffer(&self) -> &JitterBuffer<T> {
+        &self.jitter_buffer
+    }
+
+    /// read_sample returns the next frame of the track once its playout time passed.
+    ///
+    /// **Cancel Safety:** This method is cancel safe.
+    pub async fn read_sample(&mut self) -> Result<Sample> {
+        loop {
+            let sample = self.jitter_buffer.pop(Instant::now().into_std());
+            self.track
+                .set_jitter_buffer_stats(Some(self.jitter_buffer.stats()));
+            if let Some(sample) = sample {
+                return Ok(sample);
+            }
+
+            let playout_time = self
+                .jitter_buffer
+                .next_playout_time()
+                .map(Instant::from_std);
+            let read = self.read.get_or_insert_with(|| {
+                let track = Arc::clone(&self.track);
+                Box::pin(async move { track.read_rtp().await })
+            });
+
+            tokio::select! {
+                result = read => {
+                    self.read = None;
+                    let (pkt, _) = result?;
+                    self.jitter_buffer.push(pkt, Instant::now().into_std());
+                }
+                _ = sleep_until(playout_time) => {}
+            }
+        }
+    }
+}
+
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
@@ -1,11 +1,15 @@
+pub mod jitter_buffer_reader;
+
 use std::collections::VecDeque;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicU32, AtomicU8, AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
+use std::time::Duration;
 
 use arc_swap::ArcSwapOption;
 use interceptor::{Attributes, Interceptor};
+use media::io::jitter_buffer::JitterBufferStats;
 use smol_str::SmolStr;
 use tokio::sync::Mutex;
 use util::sync::Mutex as SyncMutex;
@@ -54,6 +58,11 @@ pub struct TrackRemote {
 
     handlers: Arc<Handlers>,
 
+    jitter_buffer_stats: SyncMutex<Option<JitterBufferStats>>,
+    /// The number of frames decoded and the total time spent decoding them, as
+    /// reported by the application.
+    decoded_frames: SyncMutex<Option<(u64, Duration)>>,
+
     receiver: Option<Weak<RTPReceiverInternal>>,
     internal: Mutex<TrackRemoteInternal>,
 }
@@ -98,6 +107,8 @@ impl TrackRemote {
             media_engine,
             interceptor,
             handlers: Default::default(),
+            jitter_buffer_stats: Default::default(),
+            decoded_frames: Default::default(),
 
             internal: Default::default(),
         }
@@ -191,6 +202,33 @@ impl TrackRemote {
         *p = params;
     }
 
+    /// jitter_buffer_stats returns the statistics of the jitter buffer the track is read
+    /// into by a [`jitter_buffer_reader::JitterBufferReader`], if any.
+    pub fn jitter_buffer_stats(&self) -> Option<JitterBufferStats> {
+        *self.jitter_buffer_stats.lock()
+    }
+
+    pub(crate) fn set_jitter_buffer_stats(&self, stats: Option<JitterBufferStats>) {
+        let mut jitter_buffer_stats = self.jitter_buffer_stats.lock();
+        *jitter_buffer_stats = stats;
+    }
+
+    /// record_frame_decoded records a frame of the track decoded by the application in
+    /// decode_time. The frames are reported as `framesDecoded` and `totalDecodeTime` in
+    /// the inbound RTP stats, which can't be known otherwise since the track isn't decoded.
+    pub fn record_frame_decoded(&self, decode_time: Duration) {
+        let mut decoded_frames = self.decoded_frames.lock();
+        let (frames, total_decode_time) = decoded_frames.get_or_insert_with(Default::default);
+        *frames += 1;
+        *total_decode_time += decode_time;
+    }
+
+    /// decoded_frames returns the number of frames recorded with record_frame_decoded
+    /// and the total time spent decoding them, or None if none were recorded.
+    pub(crate) fn decoded_frames(&self) -> Option<(u64, Duration)> {
+        *self.decoded_frames.lock()
+    }
+
     pub fn onmute<F>(&self, handler: F)
     where
         F: FnMut() -> Pin<Box<dyn Future<Output = ()> + Send + 'static>> + Send + 'static + Sync,
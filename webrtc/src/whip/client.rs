@@ -0,0 +1,228 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use hyper::client::connect::Connect;
+use hyper::client::HttpConnector;
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE, ETAG, IF_MATCH, LOCATION};
+use hyper::http::request::Builder;
+use hyper::{Body, Client, Method, Request, Response, StatusCode, Uri};
+use sdp::fragment::{SdpFragment, SDP_FRAGMENT_MIME_TYPE};
+use sdp::SessionDescription;
+
+use super::SDP_MIME_TYPE;
+use crate::error::{Error, Result};
+use crate::ice_transport::ice_candidate::RTCIceCandidateInit;
+use crate::peer_connection::sdp::session_description::RTCSessionDescription;
+use crate::peer_connection::RTCPeerConnection;
+
+/// WhipClient starts sessions with a WHIP (RFC 9725) or WHEP endpoint. Both
+/// protocols share the same HTTP flows: the client POSTs its offer, and the server
+/// answers with the resource of the session. Whether media is published (WHIP) or
+/// played (WHEP) only depends on the transceivers of the peer connection.
+///
+/// The client speaks plain HTTP by default; [`WhipClient::with_client`] takes a
+/// hyper client with another connector, like one for HTTPS.
+#[derive(Debug, Clone)]
+pub struct WhipClient<C = HttpConnector> {
+    client: Client<C>,
+    endpoint: Uri,
+    token: Option<String>,
+    trickle_ice: bool,
+}
+
+impl WhipClient {
+    pub fn new(endpoint: &str) -> Result<Self> {
+        WhipClient::with_client(Client::new(), endpoint)
+    }
+}
+
+impl<C> WhipClient<C>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    pub fn with_client(client: Client<C>, endpoint: &str) -> Result<Self> {
+        Ok(WhipClient {
+            client,
+            endpoint: parse_uri(endpoint)?,
+            token: None,
+            trickle_ice: false,
+        })
+    }
+
+    /// with_bearer_token sets the token sent in the Authorization header of every
+    /// request.
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// with_trickle_ice makes connect send the offer right away, without waiting for
+    /// the local candidates to be gathered. They are then sent with
+    /// [`WhipSession::trickle`].
+    pub fn with_trickle_ice(mut self, trickle_ice: bool) -> Self {
+        self.trickle_ice = trickle_ice;
+        self
+    }
+
+    /// connect creates and sets the offer of the peer connection, POSTs it to the
+    /// endpoint and sets the answer of the server as the remote description.
+    pub async fn connect(&self, peer_connection: &RTCPeerConnection) -> Result<WhipSession<C>> {
+        let offer = peer_connection.create_offer(None).await?;
+        let mut gather_complete = peer_connection.gathering_complete_promise().await;
+        peer_connection.set_local_description(offer).await?;
+        if !self.trickle_ice {
+            let _ = gather_complete.recv().await;
+        }
+        let offer = peer_connection
+            .local_description()
+            .await
+            .ok_or(Error::ErrNoLocalDescription)?;
+
+        let request = new_request(Method::POST, self.endpoint.clone(), self.token.as_deref())
+            .header(CONTENT_TYPE, SDP_MIME_TYPE)
+            .body(Body::from(offer.sdp.clone()))?;
+        let response = self.client.request(request).await?;
+        let response = expect_status(response, &[StatusCode::CREATED])?;
+
+        let location = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .ok_or(Error::ErrWhipNoLocation)?;
+        let resource = resolve(&self.endpoint, location)?;
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(str::to_owned);
+
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        let answer = RTCSessionDescription::answer(String::from_utf8(body.to_vec())?)?;
+        peer_connection.set_remote_description(answer).await?;
+
+        Ok(WhipSession {
+            client: self.client.clone(),
+            resource,
+            token: self.token.clone(),
+            etag,
+            local_description: offer.unmarshal()?,
+            deleted: AtomicBool::new(false),
+        })
+    }
+}
+
+/// WhipSession is a session started by [`WhipClient::connect`], identified by the
+/// resource URL the server returned.
+#[derive(Debug)]
+pub struct WhipSession<C = HttpConnector> {
+    client: Client<C>,
+    resource: Uri,
+    token: Option<String>,
+    etag: Option<String>,
+    local_description: SessionDescription,
+    deleted: AtomicBool,
+}
+
+impl<C> WhipSession<C>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    /// resource_url returns the URL of the session on the server.
+    pub fn resource_url(&self) -> &Uri {
+        &self.resource
+    }
+
+    /// etag returns the entity tag the server gave the session, if any.
+    pub fn etag(&self) -> Option<&str> {
+        self.etag.as_deref()
+    }
+
+    /// trickle PATCHes local candidates to the session in an
+    /// `application/trickle-ice-sdpfrag` body. Candidates are typically the ones
+    /// passed to the on_ice_candidate handler of the peer connection;
+    /// end_of_candidates tells the server that gathering is complete.
+    pub async fn trickle(
+        &self,
+        candidates: &[RTCIceCandidateInit],
+        end_of_candidates: bool,
+    ) -> Result<()> {
+        let fragment = RTCIceCandidateInit::to_sdp_fragment(
+            candidates,
+            &self.local_description,
+            end_of_candidates,
+        );
+        self.trickle_fragment(&fragment).await
+    }
+
+    /// trickle_fragment PATCHes an SDP fragment to the session, like one returned by
+    /// [`RTCPeerConnection::create_ice_fragment`].
+    pub async fn trickle_fragment(&self, fragment: &SdpFragment) -> Result<()> {
+        if self.deleted.load(Ordering::SeqCst) {
+            return Err(Error::ErrWhipSessionDeleted);
+        }
+
+        let mut request = new_request(Method::PATCH, self.resource.clone(), self.token.as_deref())
+            .header(CONTENT_TYPE, SDP_FRAGMENT_MIME_TYPE);
+        if let Some(etag) = &self.etag {
+            request = request.header(IF_MATCH, etag.as_str());
+        }
+
+        let request = request.body(Body::from(fragment.marshal()))?;
+        let response = self.client.request(request).await?;
+        expect_status(response, &[StatusCode::NO_CONTENT, StatusCode::OK])?;
+        Ok(())
+    }
+
+    /// delete tears the session down on the server. The peer connection is left to
+    /// the caller to close.
+    pub async fn delete(&self) -> Result<()> {
+        if self.deleted.swap(true, Ordering::SeqCst) {
+            return Err(Error::ErrWhipSessionDeleted);
+        }
+
+        let request = new_request(Method::DELETE, self.resource.clone(), self.token.as_deref())
+            .body(Body::empty())?;
+        let response = self.client.request(request).await?;
+        expect_status(response, &[StatusCode::OK, StatusCode::NO_CONTENT])?;
+        Ok(())
+    }
+}
+
+fn new_request(method: Method, uri: Uri, token: Option<&str>) -> Builder {
+    let builder = Request::builder().method(method).uri(uri);
+    match token {
+        Some(token) => builder.header(AUTHORIZATION, format!("Bearer {token}")),
+        None => builder,
+    }
+}
+
+fn expect_status(response: Response<Body>, expected: &[StatusCode]) -> Result<Response<Body>> {
+    if expected.contains(&response.status()) {
+        Ok(response)
+    } else {
+        Err(Error::ErrWhipUnexpectedStatus(response.status().as_u16()))
+    }
+}
+
+fn parse_uri(uri: &str) -> Result<Uri> {
+    uri.parse::<Uri>().map_err(|e| Error::Http(e.to_string()))
+}
+
+/// resolve returns the URL of a Location header, which may be relative to the
+/// endpoint.
+pub(crate) fn resolve(endpoint: &Uri, location: &str) -> Result<Uri> {
+    if location.contains("://") {
+        return parse_uri(location);
+    }
+
+    let path = if location.starts_with('/') {
+        location.to_owned()
+    } else {
+        let base = endpoint.path();
+        let dir = &base[..base.rfind('/').map_or(0, |i| i + 1)];
+        format!("{dir}{location}")
+    };
+
+    let mut parts = endpoint.clone().into_parts();
+    parts.path_and_query = Some(path.parse().map_err(|e| Error::Http(format!("{e}")))?);
+    Uri::from_parts(parts).map_err(|e| Error::Http(e.to_string()))
+}
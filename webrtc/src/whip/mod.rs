@@ -0,0 +1,11 @@
+#[cfg(test)]
+mod whip_test;
+
+pub mod client;
+pub mod server;
+
+pub use client::{WhipClient, WhipSession};
+pub use server::{WhipHandler, WhipServer};
+
+/// MIME type of the SDP offers and answers of WHIP and WHEP.
+pub const SDP_MIME_TYPE: &str = "application/sdp";
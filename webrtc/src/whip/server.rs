@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use hyper::header::{HeaderValue, CONTENT_TYPE, ETAG, IF_MATCH, LOCATION};
+use hyper::{Body, HeaderMap, Method, Request, Response, StatusCode};
+use sdp::fragment::{SdpFragment, SDP_FRAGMENT_MIME_TYPE};
+use util::sync::Mutex;
+
+use super::SDP_MIME_TYPE;
+use crate::error::{Error, Result};
+use crate::peer_connection::sdp::session_description::RTCSessionDescription;
+use crate::peer_connection::RTCPeerConnection;
+
+/// WhipHandler decides who may start sessions on a [`WhipServer`], and creates
+/// their peer connections.
+#[async_trait]
+pub trait WhipHandler: Send + Sync {
+    /// authorize checks the headers of a request, typically its bearer token, for
+    /// the endpoint or resource at path. Every request is authorized by default.
+    async fn authorize(&self, _path: &str, _headers: &HeaderMap) -> bool {
+        true
+    }
+
+    /// new_peer_connection returns the peer connection answering an offer POSTed to
+    /// endpoint. A WHEP handler adds the tracks to play to it.
+    async fn new_peer_connection(&self, endpoint: &str) -> Result<Arc<RTCPeerConnection>>;
+}
+
+struct ServerSession {
+    peer_connection: Arc<RTCPeerConnection>,
+    etag: String,
+}
+
+/// WhipServer serves the HTTP side of WHIP (RFC 9725) and WHEP. It is not an HTTP
+/// server of its own: requests are passed to [`WhipServer::handle`] from a hyper
+/// service, which lets it share a server and its TLS with other routes.
+///
+/// A POST of an offer to any path creates a session whose resource is a path below
+/// it, which accepts PATCH requests trickling candidates and a DELETE ending the
+/// session. The answer is sent once the candidates of the server are gathered, so
+/// the server never trickles. ICE restarts are not supported.
+pub struct WhipServer {
+    handler: Arc<dyn WhipHandler>,
+    sessions: Mutex<HashMap<String, ServerSession>>,
+}
+
+impl WhipServer {
+    pub fn new(handler: Arc<dyn WhipHandler>) -> Self {
+        WhipServer {
+            handler,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// session returns the peer connection of the session at the resource path.
+    pub fn session(&self, resource: &str) -> Option<Arc<RTCPeerConnection>> {
+        let sessions = self.sessions.lock();
+        sessions
+            .get(resource)
+            .map(|session| Arc::clone(&session.peer_connection))
+    }
+
+    /// session_count returns the number of sessions that weren't deleted.
+    pub fn session_count(&self) -> usize {
+        let sessions = self.sessions.lock();
+        sessions.len()
+    }
+
+    /// remove_session forgets the session at the resource path, for when its peer
+    /// connection is closed by the application, and returns its peer connection.
+    pub fn remove_session(&self, resource: &str) -> Option<Arc<RTCPeerConnection>> {
+        let mut sessions = self.sessions.lock();
+        sessions
+            .remove(resource)
+            .map(|session| session.peer_connection)
+    }
+
+    /// handle answers a WHIP or WHEP request.
+    pub async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        let path = request.uri().path().to_owned();
+        if request.method() == Method::OPTIONS {
+            return Response::builder()
+                .status(StatusCode::NO_CONTENT)
+                .header("Accept-Post", SDP_MIME_TYPE)
+                .header("Accept-Patch", SDP_FRAGMENT_MIME_TYPE)
+                .body(Body::empty())
+                .unwrap_or_else(|_| status(StatusCode::INTERNAL_SERVER_ERROR));
+        }
+        if !self.handler.authorize(&path, request.headers()).await {
+            return status(StatusCode::UNAUTHORIZED);
+        }
+
+        match *request.method() {
+            Method::POST => self.handle_offer(path, request).await,
+            Method::PATCH => self.handle_trickle(path, request).await,
+            Method::DELETE => self.handle_delete(path).await,
+            _ => status(StatusCode::METHOD_NOT_ALLOWED),
+        }
+    }
+
+    async fn handle_offer(&self, endpoint: String, request: Request<Body>) -> Response<Body> {
+        if !has_content_type(request.headers(), SDP_MIME_TYPE) {
+            return status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        }
+        let offer = match read_body(request).await.map(RTCSessionDescription::offer) {
+            Some(Ok(offer)) => offer,
+            _ => return status(StatusCode::BAD_REQUEST),
+        };
+
+        let peer_connection = match self.handler.new_peer_connection(&endpoint).await {
+            Ok(peer_connection) => peer_connection,
+            Err(err) => {
+                log::warn!("WHIP: failed to create a peer connection: {err}");
+                return status(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+        if let Err(err) = peer_connection.set_remote_description(offer).await {
+            log::debug!("WHIP: rejected offer: {err}");
+            let _ = peer_connection.close().await;
+            return status(StatusCode::BAD_REQUEST);
+        }
+        let answer = match answer(&peer_connection).await {
+            Ok(answer) => answer,
+            Err(err) => {
+                log::warn!("WHIP: failed to answer: {err}");
+                let _ = peer_connection.close().await;
+                return status(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+
+        let id = format!("{:016x}", rand::random::<u64>());
+        let resource = format!("{}/{id}", endpoint.trim_end_matches('/'));
+        let etag = format!("\"{id}\"");
+        {
+            let mut sessions = self.sessions.lock();
+            sessions.insert(
+                resource.clone(),
+                ServerSession {
+                    peer_connection,
+                    etag: etag.clone(),
+                },
+            );
+        }
+
+        Response::builder()
+            .status(StatusCode::CREATED)
+            .header(CONTENT_TYPE, SDP_MIME_TYPE)
+            .header(LOCATION, resource)
+            .header(ETAG, etag)
+            .body(Body::from(answer.sdp))
+            .unwrap_or_else(|_| status(StatusCode::INTERNAL_SERVER_ERROR))
+    }
+
+    async fn handle_trickle(&self, resource: String, request: Request<Body>) -> Response<Body> {
+        let (peer_connection, etag) = {
+            let sessions = self.sessions.lock();
+            match sessions.get(&resource) {
+                Some(session) => (Arc::clone(&session.peer_connection), session.etag.clone()),
+                None => return status(StatusCode::NOT_FOUND),
+            }
+        };
+
+        if !has_content_type(request.headers(), SDP_FRAGMENT_MIME_TYPE) {
+            return status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        }
+        match request.headers().get(IF_MATCH).map(HeaderValue::as_bytes) {
+            // An ICE restart.
+            Some(b"*") => return status(StatusCode::NOT_IMPLEMENTED),
+            Some(if_match) if if_match != etag.as_bytes() => {
+                return status(StatusCode::PRECONDITION_FAILED)
+            }
+            _ => {}
+        }
+
+        let fragment = match read_body(request).await.map(SdpFragment::try_from) {
+            Some(Ok(fragment)) => fragment,
+            _ => return status(StatusCode::BAD_REQUEST),
+        };
+
+        match peer_connection.add_ice_fragment(&fragment).await {
+            Ok(()) => {}
+            // Candidates of other credentials than the offer are for an ICE restart.
+            Err(Error::ErrIceCandidateUfragMismatch) => {
+                return status(StatusCode::UNPROCESSABLE_ENTITY)
+            }
+            Err(err) => {
+                log::debug!("WHIP: rejected candidate: {err}");
+                return status(StatusCode::BAD_REQUEST);
+            }
+        }
+
+        status(StatusCode::NO_CONTENT)
+    }
+
+    async fn handle_delete(&self, resource: String) -> Response<Body> {
+        let Some(peer_connection) = self.remove_session(&resource) else {
+            return status(StatusCode::NOT_FOUND);
+        };
+        if let Err(err) = peer_connection.close().await {
+            log::warn!("WHIP: failed to close the peer connection: {err}");
+        }
+        status(StatusCode::OK)
+    }
+}
+
+async fn answer(peer_connection: &RTCPeerConnection) -> Result<RTCSessionDescription> {
+    let answer = peer_connection.create_answer(None).await?;
+    let mut gather_complete = peer_connection.gathering_complete_promise().await;
+    peer_connection.set_local_description(answer).await?;
+    let _ = gather_complete.recv().await;
+    peer_connection
+        .local_description()
+        .await
+        .ok_or(Error::ErrNoLocalDescription)
+}
+
+fn status(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+fn has_content_type(headers: &HeaderMap, mime_type: &str) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(|content_type| content_type.split(';').next())
+        .map_or(false, |content_type| {
+            content_type.trim().eq_ignore_ascii_case(mime_type)
+        })
+}
+
+async fn read_body(request: Request<Body>) -> Option<String> {
+    let body = hyper::body::to_bytes(request.into_body()).await.ok()?;
+    String::from_utf8(body.to_vec()).ok()
+}
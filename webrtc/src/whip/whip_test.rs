@@ -0,0 +1,226 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Client, HeaderMap, Method, Request, Server, StatusCode, Uri};
+use sdp::fragment::SDP_FRAGMENT_MIME_TYPE;
+use waitgroup::WaitGroup;
+
+use super::client::resolve;
+use super::*;
+use crate::api::media_engine::MediaEngine;
+use crate::api::{APIBuilder, API};
+use crate::error::{Error, Result};
+use crate::ice_transport::ice_candidate::RTCIceCandidateInit;
+use crate::peer_connection::configuration::RTCConfiguration;
+use crate::peer_connection::peer_connection_state::RTCPeerConnectionState;
+use crate::peer_connection::peer_connection_test::until_connection_state;
+use crate::peer_connection::RTCPeerConnection;
+use crate::rtp_transceiver::rtp_codec::RTPCodecType;
+use crate::rtp_transceiver::rtp_transceiver_direction::RTCRtpTransceiverDirection;
+use crate::rtp_transceiver::RTCRtpTransceiverInit;
+
+const TOKEN: &str = "secret";
+
+struct TestHandler {
+    api: API,
+}
+
+#[async_trait::async_trait]
+impl WhipHandler for TestHandler {
+    async fn authorize(&self, _path: &str, headers: &HeaderMap) -> bool {
+        headers
+            .get(hyper::header::AUTHORIZATION)
+            .map_or(false, |value| value == format!("Bearer {TOKEN}").as_str())
+    }
+
+    async fn new_peer_connection(&self, _endpoint: &str) -> Result<Arc<RTCPeerConnection>> {
+        Ok(Arc::new(
+            self.api
+                .new_peer_connection(RTCConfiguration::default())
+                .await?,
+        ))
+    }
+}
+
+fn new_api() -> Result<API> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    Ok(APIBuilder::new().with_media_engine(m).build())
+}
+
+async fn serve() -> Result<(Arc<WhipServer>, SocketAddr)> {
+    let server = Arc::new(WhipServer::new(Arc::new(TestHandler { api: new_api()? })));
+
+    let whip_server = Arc::clone(&server);
+    let make_service = make_service_fn(move |_| {
+        let whip_server = Arc::clone(&whip_server);
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let whip_server = Arc::clone(&whip_server);
+                async move { Ok::<_, Infallible>(whip_server.handle(request).await) }
+            }))
+        }
+    });
+    let http_server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+    let addr = http_server.local_addr();
+    tokio::spawn(http_server);
+
+    Ok((server, addr))
+}
+
+async fn new_publisher(api: &API) -> Result<RTCPeerConnection> {
+    let pc = api.new_peer_connection(RTCConfiguration::default()).await?;
+    pc.add_transceiver_from_kind(
+        RTPCodecType::Video,
+        Some(RTCRtpTransceiverInit {
+            direction: RTCRtpTransceiverDirection::Sendonly,
+            send_encodings: vec![],
+        }),
+    )
+    .await?;
+    Ok(pc)
+}
+
+async fn raw_request(uri: &Uri, method: Method, content_type: &str, body: String) -> StatusCode {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(hyper::header::AUTHORIZATION, format!("Bearer {TOKEN}"))
+        .header(hyper::header::CONTENT_TYPE, content_type)
+        .body(Body::from(body))
+        .unwrap();
+    Client::new().request(request).await.unwrap().status()
+}
+
+#[test]
+fn test_resolve_location() -> Result<()> {
+    let endpoint: Uri = "http://example.com/whip/endpoint".parse().unwrap();
+    for (location, expected) in [
+        ("/resource/1", "http://example.com/resource/1"),
+        ("resource/1", "http://example.com/whip/resource/1"),
+        ("https://other.com/1", "https://other.com/1"),
+    ] {
+        assert_eq!(resolve(&endpoint, location)?.to_string(), expected);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_whip_connect_and_delete() -> Result<()> {
+    let (server, addr) = serve().await?;
+    let api = new_api()?;
+
+    let pc = new_publisher(&api).await?;
+    let endpoint = format!("http://{addr}/whip/live");
+
+    let result = WhipClient::new(&endpoint)?.connect(&pc).await;
+    assert_eq!(
+        result.err(),
+        Some(Error::ErrWhipUnexpectedStatus(401)),
+        "requests without the token are rejected"
+    );
+    assert_eq!(server.session_count(), 0);
+
+    pc.close().await?;
+
+    let mut pc = new_publisher(&api).await?;
+    let wg = WaitGroup::new();
+    until_connection_state(&mut pc, &wg, RTCPeerConnectionState::Connected).await;
+
+    let client = WhipClient::new(&endpoint)?.with_bearer_token(TOKEN);
+    let session = client.connect(&pc).await?;
+    assert!(session.resource_url().path().starts_with("/whip/live/"));
+    assert!(session.etag().is_some());
+    assert!(pc.remote_description().await.is_some());
+    assert!(server.session(session.resource_url().path()).is_some());
+
+    tokio::time::timeout(Duration::from_secs(10), wg.wait())
+        .await
+        .expect("publisher should connect to the WHIP server");
+
+    session.delete().await?;
+    assert_eq!(server.session_count(), 0);
+    assert_eq!(session.delete().await, Err(Error::ErrWhipSessionDeleted));
+    assert_eq!(
+        raw_request(session.resource_url(), Method::DELETE, "", String::new()).await,
+        StatusCode::NOT_FOUND
+    );
+
+    pc.close().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_whip_trickle() -> Result<()> {
+    let (server, addr) = serve().await?;
+    let api = new_api()?;
+
+    let pc = new_publisher(&api).await?;
+    let client = WhipClient::new(&format!("http://{addr}/whip"))?
+        .with_bearer_token(TOKEN)
+        .with_trickle_ice(true);
+    let session = client.connect(&pc).await?;
+
+    let candidate = RTCIceCandidateInit {
+        candidate: "candidate:1 1 udp 2130706431 127.0.0.1 5000 typ host".to_owned(),
+        sdp_mid: Some(String::new()),
+        sdp_mline_index: Some(0),
+        username_fragment: None,
+    };
+    session.trickle(&[candidate], false).await?;
+    session.trickle(&[], true).await?;
+
+    let resource = session.resource_url();
+    assert_eq!(
+        raw_request(resource, Method::PATCH, SDP_MIME_TYPE, String::new()).await,
+        StatusCode::UNSUPPORTED_MEDIA_TYPE
+    );
+    assert_eq!(
+        raw_request(
+            resource,
+            Method::PATCH,
+            SDP_FRAGMENT_MIME_TYPE,
+            "a=ice-ufrag:other\r\na=ice-pwd:other\r\nm=video 9 UDP/TLS/RTP/SAVPF 96\r\na=mid:0\r\n"
+                .to_owned()
+        )
+        .await,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "other credentials are an ICE restart"
+    );
+    assert_eq!(
+        raw_request(
+            resource,
+            Method::PATCH,
+            SDP_FRAGMENT_MIME_TYPE,
+            "v=0\r\n".to_owned()
+        )
+        .await,
+        StatusCode::BAD_REQUEST
+    );
+
+    let request = Request::builder()
+        .method(Method::PATCH)
+        .uri(resource)
+        .header(hyper::header::AUTHORIZATION, format!("Bearer {TOKEN}"))
+        .header(hyper::header::CONTENT_TYPE, SDP_FRAGMENT_MIME_TYPE)
+        .header(hyper::header::IF_MATCH, "\"stale\"")
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(
+        Client::new().request(request).await.unwrap().status(),
+        StatusCode::PRECONDITION_FAILED
+    );
+
+    session.delete().await?;
+    assert_eq!(
+        session.trickle(&[], true).await,
+        Err(Error::ErrWhipSessionDeleted)
+    );
+    assert_eq!(server.session_count(), 0);
+
+    pc.close().await?;
+    Ok(())
+}